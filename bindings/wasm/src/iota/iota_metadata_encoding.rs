@@ -11,12 +11,16 @@ use wasm_bindgen::prelude::*;
 #[repr(u8)]
 pub enum WasmStateMetadataEncoding {
   Json = 0,
+  JsonBrotli = 1,
+  Cbor = 2,
 }
 
 impl From<WasmStateMetadataEncoding> for StateMetadataEncoding {
   fn from(encoding: WasmStateMetadataEncoding) -> Self {
     match encoding {
       WasmStateMetadataEncoding::Json => Self::Json,
+      WasmStateMetadataEncoding::JsonBrotli => Self::JsonBrotli,
+      WasmStateMetadataEncoding::Cbor => Self::Cbor,
     }
   }
 }
@@ -1,25 +1,20 @@
-use std::{collections::HashMap, fs::File, path::Path};
+use std::collections::HashMap;
 
 use examples::{create_did, random_stronghold_path, MemStorage, API_ENDPOINT};
 use identity_eddsa_verifier::EdDSAJwsVerifier;
 use identity_iota::{core::{Duration, FromJson, Object, Timestamp, Url}, credential::{Credential, CredentialBuilder, DecodedJwtCredential, DecodedJwtPresentation, FailFast, Jwt, JwtCredentialValidationOptions, JwtCredentialValidator, JwtCredentialValidatorUtils, JwtPresentationOptions, JwtPresentationValidationOptions, JwtPresentationValidator, JwtPresentationValidatorUtils, Presentation, PresentationBuilder, Subject, SubjectHolderRelationship}, did::{CoreDID, DID}, document::{verifiable::JwsVerificationOptions, CoreDocument}, iota::IotaDocument, resolver::Resolver, storage::{JwkMemStore, JwsSignatureOptions, KeyIdMemstore}, verification::{jws::JwsAlgorithm, MethodScope}};
 use identity_iota::storage::JwkDocumentExt;
+use identity_iota::storage::{DidWebFilePublisher, DidWebManager};
 use iota_sdk::{client::{secret::{stronghold::StrongholdSecretManager, SecretManager}, Client, Password}, types::block::address::Address};
 use reqwest::ClientBuilder;
 use serde_json::json;
 
-pub fn write_to_file(doc: &CoreDocument, path: Option<&str>) -> anyhow::Result<()> {
-  let path = Path::new(path.unwrap_or_else(|| "did.json"));
-  let file = File::create(path)?;
-  serde_json::to_writer_pretty(file, doc)?;
-  Ok(())
-}
-
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
 
   let did_url: &str = "https://localhost:4443/.well-known/did.json";
-  let path_did_file: &str = "C:/Projects/did-web-server/.well-known/did.json";
+  // The directory served by the web server hosting the DID Document.
+  let did_web_root: &str = "C:/Projects/did-web-server/";
 
   // Create a new client to make HTTPS requests.
   let client= ClientBuilder::new()
@@ -41,7 +36,8 @@ async fn main() -> anyhow::Result<()> {
     )
     .await?;
 
-  write_to_file(&issuer_document, Some(path_did_file))?;
+  // Publish the DID Document at the location derived from its DID, i.e. `.well-known/did.json`.
+  DidWebManager::new(DidWebFilePublisher::new(did_web_root)).publish(&issuer_document).await?;
   println!("Web DID Document: {:#}", issuer_document);
 
   // Create a new client to interact with the IOTA ledger.
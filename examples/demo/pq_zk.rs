@@ -0,0 +1,143 @@
+use examples::{MemStorage, DID_URL, DID_WEB_ROOT};
+use identity_iota::storage::{DidWebFilePublisher, DidWebManager};
+use identity_iota::{core::{FromJson, Object, Url}, credential::{Credential, CredentialBuilder, FailFast, Jpt, JptCredentialValidationOptions, JptCredentialValidator, JptPresentationValidationOptions, JptPresentationValidator, JptPresentationValidatorUtils, JwpCredentialOptions, JwpPresentationOptions, SelectiveDisclosurePresentation, Subject}, did::{CoreDID, DID}, document::CoreDocument, resolver::Resolver, storage::{DidJwkDocumentExt, JwkMemStore, JwpDocumentExt, JwsDocumentExtPQC, KeyIdMemstore}, verification::{jwk::Jwk, jws::JwsAlgorithm, MethodScope}};
+use identity_pqc_verifier::PQCJwsVerifier;
+use reqwest::ClientBuilder;
+use serde_json::json;
+use colored::Colorize;
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+  let binding = DID_URL.to_owned() + "pq_zk";
+  let did_url: &str = binding.as_str();
+
+  println!("{} {} {}", "[Issuer]".red(), ": Create DID (with did:web method) and publish the DID Document at", did_url);
+
+  let client= ClientBuilder::new()
+  .danger_accept_invalid_certs(true)
+  .build()?;
+
+  let mut issuer_document: CoreDocument = CoreDocument::new_from_url(did_url)?;
+
+  let storage_issuer: MemStorage = MemStorage::new(JwkMemStore::new(), KeyIdMemstore::new());
+  let fragment_issuer = issuer_document.generate_method_pqc(
+    &storage_issuer,
+    JwkMemStore::ML_DSA_KEY_TYPE,
+    JwsAlgorithm::ML_DSA_44,
+    None,
+    MethodScope::VerificationMethod,
+  ).await?;
+
+  DidWebManager::new(DidWebFilePublisher::new(DID_WEB_ROOT)).publish(&issuer_document).await?;
+
+  let storage_alice: MemStorage = MemStorage::new(JwkMemStore::new(), KeyIdMemstore::new());
+
+  let (alice_document, fragment_alice) = CoreDocument::new_did_jwk_pqc(
+    &storage_alice,
+    JwkMemStore::ML_DSA_KEY_TYPE,
+    JwsAlgorithm::ML_DSA_44
+  ).await?;
+
+  println!("{} {} {}", "[Holder]".blue(), ": Create DID Jwk:", alice_document.id().as_str());
+
+  let subject: Subject = Subject::from_json_value(json!({
+    "id": alice_document.id().as_str(),
+    "name": "Alice",
+    "degree": {
+      "type": "BachelorDegree",
+      "name": "Bachelor of Science and Arts",
+    },
+    "GPA": "4.0",
+  }))?;
+
+  println!("{} {} {}", "[Holder]".blue(), ": Inserted Credential subject information: ", serde_json::to_string_pretty(&subject)?);
+
+  println!("{} {} {}", "[Holder]".blue(), " <-> [Issuer]".red(), ": Challenge-response protocol to authenticate Holder's DID");
+
+  // The credential is bound to the holder's key, which signs every presentation of it.
+  let alice_key: Jwk = alice_document
+  .resolve_method(&fragment_alice, None)
+  .and_then(|method| method.data().public_key_jwk())
+  .cloned()
+  .unwrap();
+
+  println!("{} {} ","[Issuer]".red(), ": Construct VC");
+
+  let credential: Credential = CredentialBuilder::default()
+    .id(Url::parse("https://example.edu/credentials/3732")?)
+    .issuer(Url::parse(issuer_document.id().as_str())?)
+    .type_("UniversityDegreeCredential")
+    .subject(subject)
+    .build()?;
+
+  let credential_jpt: Jpt = issuer_document.create_credential_jpt_pqc(
+    &credential,
+    &storage_issuer,
+    &fragment_issuer,
+    &alice_key,
+    &JwpCredentialOptions::default(),
+    None,
+  ).await?;
+
+  println!("{} {} {} {}", "[Issuer]".red(), " -> [Holder]".blue(), ": Sending VC (as JPT):", credential_jpt.as_str());
+
+  println!("{} {} {}", "[Holder]".blue(), ": Resolve Issuer's DID:", issuer_document.id().as_str());
+
+  println!("{} {}", "[Holder]".blue(), ": Validate VC");
+
+  let decoded_jpt = JptCredentialValidator::validate_pqc::<_, Object, _>(
+      &credential_jpt,
+      &issuer_document,
+      &JptCredentialValidationOptions::default(),
+      FailFast::FirstError,
+      &PQCJwsVerifier::default(),
+    ).unwrap();
+
+  println!("{} {}", "[Verifier]".green(),  "-> [Holder]: Send challenge");
+
+  let challenge: &str = "475a7984-1bb5-4c4c-a56f-822bccd46440";
+
+  println!("{}: Engages in the Selective Disclosure of credential's attributes", "[Holder]".blue());
+
+  let mut selective_disclosure_presentation = SelectiveDisclosurePresentation::new(&decoded_jpt.decoded_jwp);
+  selective_disclosure_presentation
+  .conceal_in_subject("degree.name")
+  .unwrap();
+
+  println!("{} {}", "[Holder]".blue(), ": Sign the challenge and construct the Presentation JPT");
+
+  let presentation_jpt: Jpt = alice_document
+  .create_presentation_jpt_pqc(
+    &mut selective_disclosure_presentation,
+    &storage_alice,
+    &fragment_alice,
+    &JwpPresentationOptions::default().nonce(challenge),
+    )
+  .await?;
+
+  println!("{} {} {} {}", "[Holder]".blue(), " -> [Verifier]".green(),  ": Sending Presentation (as JPT):", presentation_jpt.as_str());
+
+  println!("{}: Resolve Issuer's DID and verifies the Presentation JPT","[Verifier]".green());
+
+  let mut resolver_web: Resolver<CoreDocument> = Resolver::new();
+  let _ = resolver_web.attach_web_handler(client)?;
+
+  let issuer: CoreDID = JptPresentationValidatorUtils::extract_issuer_from_presented_jpt(&presentation_jpt).unwrap();
+  let issuer_document: CoreDocument = resolver_web.resolve(&issuer).await?;
+
+  let presentation_validation_options = JptPresentationValidationOptions::default().nonce(challenge);
+
+  // Verifier validate the Presented Credential and retrieve the JwpPresented
+  let _decoded_presented_credential = JptPresentationValidator::validate_pqc::<_, Object, _, _>(
+    &presentation_jpt,
+    &issuer_document,
+    &presentation_validation_options,
+    FailFast::FirstError,
+    &PQCJwsVerifier::default(),
+    &PQCJwsVerifier::default(),
+  ).unwrap();
+
+  println!("{}: Presentation JPT successfully verified", "[Verifier]".green());
+
+  Ok(())
+}
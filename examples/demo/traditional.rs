@@ -1,5 +1,6 @@
-use std::{collections::HashMap, fs::File, path::Path};
-use examples::{MemStorage, DID_URL, PATH_DID_FILE};
+use std::collections::HashMap;
+use examples::{MemStorage, DID_URL, DID_WEB_ROOT};
+use identity_iota::storage::{DidWebFilePublisher, DidWebManager};
 use identity_eddsa_verifier::EdDSAJwsVerifier;
 use identity_iota::{core::{Duration, FromJson, Object, Timestamp, Url}, credential::{Credential, CredentialBuilder, DecodedJwtCredential, DecodedJwtPresentation, FailFast, Jwt, JwtCredentialValidationOptions, JwtCredentialValidator, JwtCredentialValidatorUtils, JwtPresentationOptions, JwtPresentationValidationOptions, JwtPresentationValidator, JwtPresentationValidatorUtils, Presentation, PresentationBuilder, Subject, SubjectHolderRelationship}, did::{CoreDID, DIDJwk, DID}, document::{verifiable::JwsVerificationOptions, CoreDocument}, resolver::Resolver, storage::{DidJwkDocumentExt, JwkMemStore, JwsSignatureOptions, KeyIdMemstore}, verification::{jws::JwsAlgorithm, MethodScope}};
 use identity_iota::storage::JwkDocumentExt;
@@ -7,19 +8,10 @@ use reqwest::ClientBuilder;
 use serde_json::json;
 use colored::Colorize;
 
-pub fn write_to_file(doc: &CoreDocument, path: Option<&str>) -> anyhow::Result<()> {
-    let path = Path::new(path.unwrap_or_else(|| "did.json"));
-    let file = File::create(path)?;
-    serde_json::to_writer_pretty(file, doc)?;
-    Ok(())
-}
-
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
   let binding = DID_URL.to_owned() + "did.json";
   let did_url: &str = binding.as_str();
-  let binding = PATH_DID_FILE.to_owned() + "did.json";
-  let path_did_file: &str = binding.as_str();
 
   println!("{} {} {}", "[Issuer]".red(), ": Create DID (with did:web method) and publish the DID Document at", did_url);
 
@@ -38,7 +30,7 @@ async fn main() -> anyhow::Result<()> {
     MethodScope::VerificationMethod,
   ).await?;
 
-  write_to_file(&issuer_document, Some(path_did_file))?;
+  DidWebManager::new(DidWebFilePublisher::new(DID_WEB_ROOT)).publish(&issuer_document).await?;
 
   let storage_alice: MemStorage = MemStorage::new(JwkMemStore::new(), KeyIdMemstore::new());
 
@@ -1,24 +1,15 @@
-use std::{fs::File, path::Path};
-use examples::{MemStorage, DID_URL, PATH_DID_FILE};
+use examples::{MemStorage, DID_URL, DID_WEB_ROOT};
+use identity_iota::storage::{DidWebFilePublisher, DidWebManager};
 use identity_iota::{core::{FromJson, Object, Url}, credential::{Credential, CredentialBuilder, FailFast, Jpt, JptCredentialValidationOptions, JptCredentialValidator, JptPresentationValidationOptions, JptPresentationValidator, JptPresentationValidatorUtils, JwpCredentialOptions, JwpPresentationOptions, SelectiveDisclosurePresentation, Subject}, did::{CoreDID, DID}, document::CoreDocument, resolver::Resolver, storage::{DidJwkDocumentExt, JwkMemStore, JwpDocumentExt, KeyIdMemstore}, verification::{jws::JwsAlgorithm, MethodScope}};
 use jsonprooftoken::jpa::algs::ProofAlgorithm;
 use reqwest::ClientBuilder;
 use serde_json::json;
 use colored::Colorize;
 
-pub fn write_to_file(doc: &CoreDocument, path: Option<&str>) -> anyhow::Result<()> {
-    let path = Path::new(path.unwrap_or_else(|| "did.json"));
-    let file = File::create(path)?;
-    serde_json::to_writer_pretty(file, doc)?;
-    Ok(())
-}
-
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
-  let binding = DID_URL.to_owned() + "did_zk.json";
+  let binding = DID_URL.to_owned() + "zk";
   let did_url: &str = binding.as_str();
-  let binding = PATH_DID_FILE.to_owned() + "did_zk.json";
-  let path_did_file: &str = binding.as_str();
 
   println!("{} {} {}", "[Issuer]".red(), ": Create DID (with did:web method) and publish the DID Document at", did_url);
 
@@ -37,7 +28,7 @@ async fn main() -> anyhow::Result<()> {
     MethodScope::VerificationMethod,
   ).await?;
 
-  write_to_file(&issuer_document, Some(path_did_file))?;
+  DidWebManager::new(DidWebFilePublisher::new(DID_WEB_ROOT)).publish(&issuer_document).await?;
 
   let storage_alice: MemStorage = MemStorage::new(JwkMemStore::new(), KeyIdMemstore::new());
 
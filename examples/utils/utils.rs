@@ -32,7 +32,8 @@ pub static API_ENDPOINT: &str = "http://localhost";
 pub static FAUCET_ENDPOINT: &str = "http://localhost/faucet/api/enqueue";
 
 pub static DID_URL: &str = "https://localhost:4443/.well-known/";
-pub static PATH_DID_FILE: &str = "./examples/demo/server/.well-known/";
+/// The directory served by the demo web server, see `examples/demo/server`.
+pub static DID_WEB_ROOT: &str = "./examples/demo/server/";
 
 pub type MemStorage = Storage<JwkMemStore, KeyIdMemstore>;
 
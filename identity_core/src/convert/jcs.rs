@@ -0,0 +1,202 @@
+// Copyright 2020-2024 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+//! JSON Canonicalization Scheme ([RFC 8785](https://www.rfc-editor.org/rfc/rfc8785)).
+
+use serde_json::Number;
+use serde_json::Value;
+
+use crate::error::Error;
+use crate::error::Result;
+
+/// The largest integer magnitude that is exactly representable as an IEEE 754 double.
+const MAX_SAFE_INTEGER: u64 = (1 << 53) - 1;
+
+/// Serializes `value` in its canonical form.
+pub(crate) fn canonicalize(value: &Value) -> Result<Vec<u8>> {
+  let mut output: String = String::new();
+  write_value(value, &mut output)?;
+  Ok(output.into_bytes())
+}
+
+fn write_value(value: &Value, output: &mut String) -> Result<()> {
+  match value {
+    Value::Null => output.push_str("null"),
+    Value::Bool(bool) => output.push_str(if *bool { "true" } else { "false" }),
+    Value::Number(number) => write_number(number, output),
+    Value::String(string) => write_string(string, output)?,
+    Value::Array(array) => {
+      output.push('[');
+      for (index, item) in array.iter().enumerate() {
+        if index > 0 {
+          output.push(',');
+        }
+        write_value(item, output)?;
+      }
+      output.push(']');
+    }
+    Value::Object(object) => {
+      // Members are sorted by the UTF-16 code units of their names.
+      let mut members: Vec<(Vec<u16>, &String, &Value)> = object
+        .iter()
+        .map(|(key, value)| (key.encode_utf16().collect(), key, value))
+        .collect();
+      members.sort_by(|(lhs, ..), (rhs, ..)| lhs.cmp(rhs));
+
+      output.push('{');
+      for (index, (_, key, value)) in members.into_iter().enumerate() {
+        if index > 0 {
+          output.push(',');
+        }
+        write_string(key, output)?;
+        output.push(':');
+        write_value(value, output)?;
+      }
+      output.push('}');
+    }
+  }
+  Ok(())
+}
+
+fn write_string(string: &str, output: &mut String) -> Result<()> {
+  // serde_json escapes exactly the characters required by RFC 8785, using lowercase hexadecimal digits.
+  output.push_str(&serde_json::to_string(string).map_err(Error::EncodeJSON)?);
+  Ok(())
+}
+
+fn write_number(number: &Number, output: &mut String) {
+  match (number.as_u64(), number.as_i64()) {
+    (Some(int), _) if int <= MAX_SAFE_INTEGER => output.push_str(&int.to_string()),
+    (_, Some(int)) if int.unsigned_abs() <= MAX_SAFE_INTEGER => output.push_str(&int.to_string()),
+    _ => write_f64(number.as_f64().unwrap_or_default(), output),
+  }
+}
+
+/// Formats `value` according to the ECMAScript `Number.prototype.toString` algorithm.
+///
+/// [More Info](https://www.rfc-editor.org/rfc/rfc8785#section-3.2.2.3)
+fn write_f64(value: f64, output: &mut String) {
+  if value == 0.0 {
+    output.push('0');
+    return;
+  }
+  if value < 0.0 {
+    output.push('-');
+  }
+
+  // Rust formats the shortest representation that round-trips, e.g. `1.2345e2`. If several representations of that
+  // length round-trip, ECMAScript requires the one closest to `value`, which is the correctly rounded one.
+  let shortest: String = format!("{:e}", value.abs());
+  let precision: usize = shortest
+    .split_once('e')
+    .map_or(0, |(mantissa, _)| mantissa.len().saturating_sub(2));
+  let scientific: String = format!("{:.precision$e}", value.abs());
+  let (mantissa, exponent) = scientific
+    .split_once('e')
+    .expect("scientific notation contains an exponent");
+  let digits: String = mantissa.replace('.', "").trim_end_matches('0').to_owned();
+  let digit_count: i32 = digits.len() as i32;
+  // The position of the decimal point relative to the start of `digits`.
+  let point: i32 = exponent.parse::<i32>().expect("valid exponent") + 1;
+
+  if digit_count <= point && point <= 21 {
+    output.push_str(&digits);
+    output.extend(std::iter::repeat('0').take((point - digit_count) as usize));
+  } else if 0 < point && point <= 21 {
+    let (integer, fraction) = digits.split_at(point as usize);
+    output.push_str(integer);
+    output.push('.');
+    output.push_str(fraction);
+  } else if -6 < point && point <= 0 {
+    output.push_str("0.");
+    output.extend(std::iter::repeat('0').take(point.unsigned_abs() as usize));
+    output.push_str(&digits);
+  } else {
+    let (first, rest) = digits.split_at(1);
+    output.push_str(first);
+    if !rest.is_empty() {
+      output.push('.');
+      output.push_str(rest);
+    }
+    output.push('e');
+    output.push(if point - 1 < 0 { '-' } else { '+' });
+    output.push_str(&(point - 1).unsigned_abs().to_string());
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use serde_json::json;
+
+  use super::*;
+
+  fn format_f64(value: f64) -> String {
+    let mut output: String = String::new();
+    write_f64(value, &mut output);
+    output
+  }
+
+  #[test]
+  fn test_numbers() {
+    // Examples from RFC 8785, Appendix B.
+    assert_eq!(format_f64(0.0), "0");
+    assert_eq!(format_f64(-0.0), "0");
+    assert_eq!(format_f64(f64::from_bits(0x0000000000000001)), "5e-324");
+    assert_eq!(format_f64(f64::from_bits(0x8000000000000001)), "-5e-324");
+    assert_eq!(
+      format_f64(f64::from_bits(0x7fefffffffffffff)),
+      "1.7976931348623157e+308"
+    );
+    assert_eq!(format_f64(f64::from_bits(0x4340000000000000)), "9007199254740992");
+    assert_eq!(format_f64(f64::from_bits(0xc340000000000000)), "-9007199254740992");
+    assert_eq!(format_f64(f64::from_bits(0x4430000000000000)), "295147905179352830000");
+    assert_eq!(format_f64(f64::from_bits(0x44b52d02c7e14af5)), "9.999999999999997e+22");
+    assert_eq!(format_f64(f64::from_bits(0x44b52d02c7e14af6)), "1e+23");
+    assert_eq!(format_f64(f64::from_bits(0x444b1ae4d6e2ef4e)), "999999999999999700000");
+    assert_eq!(format_f64(f64::from_bits(0x444b1ae4d6e2ef50)), "1e+21");
+    assert_eq!(format_f64(f64::from_bits(0x3eb0c6f7a0b5ed8c)), "9.999999999999997e-7");
+    assert_eq!(format_f64(f64::from_bits(0x3eb0c6f7a0b5ed8d)), "0.000001");
+    assert_eq!(format_f64(f64::from_bits(0x3e7ad7f29abcaf47)), "9.999999999999998e-8");
+    assert_eq!(format_f64(f64::from_bits(0x3e7ad7f29abcaf48)), "1e-7");
+    assert_eq!(format_f64(f64::from_bits(0x3e45798ee2308c39)), "9.999999999999999e-9");
+    assert_eq!(format_f64(f64::from_bits(0x4024000000000000)), "10");
+    assert_eq!(format_f64(f64::from_bits(0x4034000000000000)), "20");
+    assert_eq!(format_f64(1.5), "1.5");
+    assert_eq!(format_f64(0.0000010000000000000002), "0.0000010000000000000002");
+  }
+
+  #[test]
+  fn test_canonicalize() {
+    // Example from RFC 8785, Section 3.2.2.
+    let value: Value = json!({
+      "numbers": [333333333.33333329, 1E30, 4.50, 2e-3, 0.000000000000000000000000001],
+      "string": "\u{20ac}$\u{000F}\u{000a}A'\u{0042}\u{0022}\u{005c}\\\"/",
+      "literals": [null, true, false]
+    });
+    assert_eq!(
+      String::from_utf8(canonicalize(&value).unwrap()).unwrap(),
+      r#"{"literals":[null,true,false],"numbers":[333333333.3333333,1e+30,4.5,0.002,1e-27],"string":"€$\u000f\nA'B\"\\\\\"/"}"#
+    );
+  }
+
+  #[test]
+  fn test_member_order() {
+    // Example from RFC 8785, Section 3.2.3.
+    let value: Value = json!({
+      "\u{20ac}": "Euro Sign",
+      "\r": "Carriage Return",
+      "\u{fb33}": "Hebrew Letter Dalet With Dagesh",
+      "1": "One",
+      "\u{1f600}": "Emoji: Grinning Face",
+      "\u{0080}": "Control",
+      "\u{00f6}": "Latin Small Letter O With Diaeresis"
+    });
+    let canonical: String = String::from_utf8(canonicalize(&value).unwrap()).unwrap();
+    let keys: Vec<&str> = ["\\r", "1", "\u{0080}", "\u{00f6}", "\u{20ac}", "\u{1f600}", "\u{fb33}"].to_vec();
+    let mut position: usize = 0;
+    for key in keys {
+      let found: usize = canonical[position..].find(&format!("\"{key}\"")).unwrap() + position;
+      position = found + 1;
+    }
+  }
+}
@@ -30,6 +30,12 @@ pub trait ToJson: Serialize + Sized {
   fn to_json_pretty(&self) -> Result<String> {
     serde_json::to_string_pretty(self).map_err(Error::EncodeJSON)
   }
+
+  /// Serialize `self` as JSON bytes in the canonical form of the
+  /// [JSON Canonicalization Scheme](https://www.rfc-editor.org/rfc/rfc8785).
+  fn to_jcs(&self) -> Result<Vec<u8>> {
+    super::jcs::canonicalize(&self.to_json_value()?)
+  }
 }
 
 impl<T> ToJson for T where T: Serialize {}
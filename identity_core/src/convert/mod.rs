@@ -9,4 +9,5 @@ pub use self::json::ToJson;
 pub use base_encoding::*;
 
 mod base_encoding;
+mod jcs;
 mod json;
@@ -8,8 +8,10 @@ use identity_core::common::Url;
 use identity_core::common::Value;
 
 use crate::credential::Credential;
+use crate::credential::CredentialV2;
 use crate::credential::Evidence;
 use crate::credential::Issuer;
+use crate::credential::LocalizedString;
 use crate::credential::Policy;
 use crate::credential::RefreshService;
 use crate::credential::Schema;
@@ -29,6 +31,10 @@ pub struct CredentialBuilder<T = Object> {
   pub(crate) issuer: Option<Issuer>,
   pub(crate) issuance_date: Option<Timestamp>,
   pub(crate) expiration_date: Option<Timestamp>,
+  pub(crate) valid_from: Option<Timestamp>,
+  pub(crate) valid_until: Option<Timestamp>,
+  pub(crate) name: Option<LocalizedString>,
+  pub(crate) description: Option<LocalizedString>,
   pub(crate) status: Option<Status>,
   pub(crate) schema: Vec<Schema>,
   pub(crate) refresh_service: Vec<RefreshService>,
@@ -50,6 +56,10 @@ impl<T> CredentialBuilder<T> {
       issuer: None,
       issuance_date: None,
       expiration_date: None,
+      valid_from: None,
+      valid_until: None,
+      name: None,
+      description: None,
       status: None,
       schema: Vec::new(),
       refresh_service: Vec::new(),
@@ -119,6 +129,34 @@ impl<T> CredentialBuilder<T> {
     self
   }
 
+  /// Sets the value of the `validFrom` property of a [`CredentialV2`](crate::credential::CredentialV2).
+  #[must_use]
+  pub fn valid_from(mut self, value: Timestamp) -> Self {
+    self.valid_from = Some(value);
+    self
+  }
+
+  /// Sets the value of the `validUntil` property of a [`CredentialV2`](crate::credential::CredentialV2).
+  #[must_use]
+  pub fn valid_until(mut self, value: Timestamp) -> Self {
+    self.valid_until = Some(value);
+    self
+  }
+
+  /// Sets the value of the `name` property of a [`CredentialV2`](crate::credential::CredentialV2).
+  #[must_use]
+  pub fn name(mut self, value: impl Into<LocalizedString>) -> Self {
+    self.name = Some(value.into());
+    self
+  }
+
+  /// Sets the value of the `description` property of a [`CredentialV2`](crate::credential::CredentialV2).
+  #[must_use]
+  pub fn description(mut self, value: impl Into<LocalizedString>) -> Self {
+    self.description = Some(value.into());
+    self
+  }
+
   /// Adds a value to the `credentialStatus` set.
   #[must_use]
   pub fn status(mut self, value: impl Into<Status>) -> Self {
@@ -172,6 +210,13 @@ impl<T> CredentialBuilder<T> {
   pub fn build(self) -> Result<Credential<T>> {
     Credential::from_builder(self)
   }
+
+  /// Returns a new [`CredentialV2`] based on the `CredentialBuilder` configuration.
+  ///
+  /// See [`CredentialV2::from_builder`].
+  pub fn build_v2(self) -> Result<CredentialV2<T>> {
+    CredentialV2::from_builder(self)
+  }
 }
 
 impl CredentialBuilder {
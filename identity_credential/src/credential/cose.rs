@@ -0,0 +1,49 @@
+// Copyright 2020-2024 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+/// The media type of a credential secured as a COSE_Sign1 message.
+///
+/// [More Info](https://www.w3.org/TR/vc-jose-cose/#securing-with-cose)
+pub const VC_COSE_MEDIA_TYPE: &str = "application/vc+cose";
+/// The media type of a presentation secured as a COSE_Sign1 message.
+///
+/// [More Info](https://www.w3.org/TR/vc-jose-cose/#securing-with-cose)
+pub const VP_COSE_MEDIA_TYPE: &str = "application/vp+cose";
+/// The content type of the payload of a credential secured as a COSE_Sign1 message.
+pub const VC_CONTENT_TYPE: &str = "application/vc";
+/// The content type of the payload of a presentation secured as a COSE_Sign1 message.
+pub const VP_CONTENT_TYPE: &str = "application/vp";
+
+/// A wrapper around a tagged COSE_Sign1 message (`application/vc+cose` or `application/vp+cose`).
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Cose(Vec<u8>);
+
+impl Cose {
+  /// Creates a new `Cose` from the given CBOR bytes.
+  pub fn new(cose_bytes: Vec<u8>) -> Self {
+    Self(cose_bytes)
+  }
+
+  /// Returns a reference of the CBOR bytes.
+  pub fn as_bytes(&self) -> &[u8] {
+    &self.0
+  }
+}
+
+impl From<Vec<u8>> for Cose {
+  fn from(cose: Vec<u8>) -> Self {
+    Self::new(cose)
+  }
+}
+
+impl From<Cose> for Vec<u8> {
+  fn from(cose: Cose) -> Self {
+    cose.0
+  }
+}
+
+impl AsRef<[u8]> for Cose {
+  fn as_ref(&self) -> &[u8] {
+    self.as_bytes()
+  }
+}
@@ -0,0 +1,440 @@
+// Copyright 2020-2024 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+use core::fmt::Display;
+use core::fmt::Formatter;
+
+use serde::Deserialize;
+use serde::Serialize;
+
+use identity_core::common::Context;
+use identity_core::common::Object;
+use identity_core::common::OneOrMany;
+use identity_core::common::Timestamp;
+use identity_core::common::Url;
+use identity_core::common::Value;
+use identity_core::convert::FmtJson;
+use identity_core::convert::ToJson;
+
+use crate::credential::Credential;
+use crate::credential::CredentialBuilder;
+use crate::credential::Evidence;
+use crate::credential::Issuer;
+use crate::credential::LocalizedString;
+use crate::credential::Policy;
+use crate::credential::Proof;
+use crate::credential::RefreshService;
+use crate::credential::Schema;
+use crate::credential::Status;
+use crate::credential::Subject;
+use crate::credential::VcDataModelVersion;
+use crate::error::Error;
+use crate::error::Result;
+
+/// The media type of a [`CredentialV2`] secured as a JWT, as defined by
+/// [VC-JOSE-COSE](https://www.w3.org/TR/vc-jose-cose/#securing-vcs-with-jose).
+pub const VC_JWT_MEDIA_TYPE: &str = "vc+jwt";
+
+/// Represents a set of claims describing an entity, in accordance with
+/// [VC Data Model v2.0](https://www.w3.org/TR/vc-data-model-2.0/).
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub struct CredentialV2<T = Object> {
+  /// The JSON-LD context(s) applicable to the `Credential`.
+  #[serde(rename = "@context")]
+  pub context: OneOrMany<Context>,
+  /// A unique `URI` that may be used to identify the `Credential`.
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub id: Option<Url>,
+  /// One or more URIs defining the type of the `Credential`.
+  #[serde(rename = "type")]
+  pub types: OneOrMany<String>,
+  /// A human-readable name of the `Credential`.
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub name: Option<LocalizedString>,
+  /// A human-readable description of the `Credential`.
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub description: Option<LocalizedString>,
+  /// One or more `Object`s representing the `Credential` subject(s).
+  #[serde(rename = "credentialSubject")]
+  pub credential_subject: OneOrMany<Subject>,
+  /// A reference to the issuer of the `Credential`.
+  pub issuer: Issuer,
+  /// A timestamp of when the `Credential` becomes valid.
+  #[serde(default, rename = "validFrom", skip_serializing_if = "Option::is_none")]
+  pub valid_from: Option<Timestamp>,
+  /// A timestamp of when the `Credential` should no longer be considered valid.
+  #[serde(default, rename = "validUntil", skip_serializing_if = "Option::is_none")]
+  pub valid_until: Option<Timestamp>,
+  /// Information used to determine the current status of the `Credential`.
+  #[serde(default, rename = "credentialStatus", skip_serializing_if = "Option::is_none")]
+  pub credential_status: Option<Status>,
+  /// Information used to assist in the enforcement of a specific `Credential` structure.
+  #[serde(default, rename = "credentialSchema", skip_serializing_if = "OneOrMany::is_empty")]
+  pub credential_schema: OneOrMany<Schema>,
+  /// Service(s) used to refresh an expired `Credential`.
+  #[serde(default, rename = "refreshService", skip_serializing_if = "OneOrMany::is_empty")]
+  pub refresh_service: OneOrMany<RefreshService>,
+  /// Terms-of-use specified by the `Credential` issuer.
+  #[serde(default, rename = "termsOfUse", skip_serializing_if = "OneOrMany::is_empty")]
+  pub terms_of_use: OneOrMany<Policy>,
+  /// Human-readable evidence used to support the claims within the `Credential`.
+  #[serde(default, skip_serializing_if = "OneOrMany::is_empty")]
+  pub evidence: OneOrMany<Evidence>,
+  /// Indicates that the `Credential` must only be contained within a
+  /// [`Presentation`][crate::presentation::Presentation] with a proof issued from the `Credential` subject.
+  #[serde(rename = "nonTransferable", skip_serializing_if = "Option::is_none")]
+  pub non_transferable: Option<bool>,
+  /// Miscellaneous properties.
+  #[serde(flatten)]
+  pub properties: T,
+  /// Optional cryptographic proof, unrelated to JWT.
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub proof: Option<Proof>,
+}
+
+impl<T> CredentialV2<T> {
+  /// Returns the base JSON-LD context.
+  pub fn base_context() -> &'static Context {
+    VcDataModelVersion::V2_0.base_context()
+  }
+
+  /// Returns the base type.
+  pub const fn base_type() -> &'static str {
+    Credential::<T>::base_type()
+  }
+
+  /// Returns a new `CredentialV2` based on the `CredentialBuilder` configuration.
+  ///
+  /// If the first context of the builder is the VC Data Model v1.1 base context, it is replaced by
+  /// [`Self::base_context`]. The `issuanceDate` and `expirationDate` of the builder are ignored in favour of
+  /// `validFrom` and `validUntil`.
+  pub fn from_builder(builder: CredentialBuilder<T>) -> Result<Self> {
+    let mut context: Vec<Context> = builder.context;
+    match context.first_mut() {
+      Some(first) if first == Credential::<T>::base_context() => *first = Self::base_context().clone(),
+      _ => (),
+    }
+
+    let this: Self = Self {
+      context: context.into(),
+      id: builder.id,
+      types: builder.types.into(),
+      name: builder.name,
+      description: builder.description,
+      credential_subject: builder.subject.into(),
+      issuer: builder.issuer.ok_or(Error::MissingIssuer)?,
+      valid_from: builder.valid_from,
+      valid_until: builder.valid_until,
+      credential_status: builder.status,
+      credential_schema: builder.schema.into(),
+      refresh_service: builder.refresh_service.into(),
+      terms_of_use: builder.terms_of_use.into(),
+      evidence: builder.evidence.into(),
+      non_transferable: builder.non_transferable,
+      properties: builder.properties,
+      proof: builder.proof,
+    };
+
+    this.check_structure()?;
+
+    Ok(this)
+  }
+
+  /// Validates the semantic structure of the `CredentialV2`.
+  pub fn check_structure(&self) -> Result<()> {
+    // Ensure the base context is present and in the correct location
+    match self.context.get(0) {
+      Some(context) if context == Self::base_context() => {}
+      Some(_) | None => return Err(Error::MissingBaseContext),
+    }
+
+    // The set of types MUST contain the base type
+    if !self.types.iter().any(|type_| type_ == Self::base_type()) {
+      return Err(Error::MissingBaseType);
+    }
+
+    // Credentials MUST have at least one subject
+    if self.credential_subject.is_empty() {
+      return Err(Error::MissingSubject);
+    }
+
+    // Each subject is defined as one or more properties - no empty objects
+    for subject in self.credential_subject.iter() {
+      if subject.id.is_none() && subject.properties.is_empty() {
+        return Err(Error::InvalidSubject);
+      }
+    }
+
+    // The validity period must not end before it starts
+    if let (Some(valid_from), Some(valid_until)) = (self.valid_from, self.valid_until) {
+      if valid_until < valid_from {
+        return Err(Error::InvalidValidityPeriod);
+      }
+    }
+
+    Ok(())
+  }
+
+  /// Sets the proof property of the `CredentialV2`.
+  ///
+  /// Note that this proof is not related to JWT.
+  pub fn set_proof(&mut self, proof: Option<Proof>) {
+    self.proof = proof;
+  }
+
+  /// Serializes the [`CredentialV2`] as a JWT claims set
+  /// in accordance with [VC-JOSE-COSE](https://www.w3.org/TR/vc-jose-cose/#securing-vcs-with-jose).
+  ///
+  /// The credential itself is the claims set, the `custom_claims` are added next to its properties.
+  /// The resulting string can be used as the payload of a JWS with a `typ` of [`VC_JWT_MEDIA_TYPE`].
+  ///
+  /// # Errors
+  /// Fails if a custom claim conflicts with a property of the credential.
+  pub fn serialize_jwt(&self, custom_claims: Option<Object>) -> Result<String>
+  where
+    T: Serialize,
+  {
+    let mut claims: Object = match self.to_json_value() {
+      Ok(Value::Object(claims)) => claims.into_iter().collect(),
+      Ok(_) => return Err(Error::JwtClaimsSetSerializationError("expected a JSON object".into())),
+      Err(err) => return Err(Error::JwtClaimsSetSerializationError(err.into())),
+    };
+    merge_custom_claims(&mut claims, custom_claims)?;
+
+    claims
+      .to_json()
+      .map_err(|err| Error::JwtClaimsSetSerializationError(err.into()))
+  }
+
+  /// Parses a [`CredentialV2`] from the claims set of a JWT secured in accordance with
+  /// [VC-JOSE-COSE](https://www.w3.org/TR/vc-jose-cose/#securing-vcs-with-jose).
+  ///
+  /// Registered JWT claims are returned separately and are checked for consistency with the
+  /// corresponding credential properties.
+  #[cfg(feature = "validator")]
+  pub(crate) fn from_jwt_claims(claims: &[u8]) -> Result<(Self, Option<Object>)>
+  where
+    T: serde::de::DeserializeOwned,
+  {
+    use identity_core::convert::FromJson;
+
+    let mut claims: Object =
+      Object::from_json_slice(claims).map_err(|err| Error::JwtClaimsSetDeserializationError(err.into()))?;
+    let registered_claims: Option<Object> = split_registered_claims(&mut claims);
+    let credential: Self = Self::from_json_value(Value::Object(claims.into_iter().collect()))
+      .map_err(|err| Error::JwtClaimsSetDeserializationError(err.into()))?;
+
+    if let Some(registered_claims) = registered_claims.as_ref() {
+      credential.check_consistency(registered_claims)?;
+    }
+
+    Ok((credential, registered_claims))
+  }
+
+  /// Checks whether the registered claims of a JWT match the corresponding properties of the credential.
+  #[cfg(feature = "validator")]
+  fn check_consistency(&self, registered_claims: &Object) -> Result<()> {
+    let claim = |name: &str| registered_claims.get(name).and_then(Value::as_str);
+
+    // The validity period of the JWT is expressed as NumericDates.
+    for name in ["exp", "nbf", "iat"] {
+      if registered_claims.get(name).map_or(false, |value| value.as_i64().is_none()) {
+        return Err(Error::TimestampConversionError);
+      }
+    }
+
+    if let Some(iss) = claim("iss") {
+      if iss != self.issuer.url().as_str() {
+        return Err(Error::InconsistentCredentialJwtClaims("inconsistent issuer"));
+      }
+    }
+    if let Some(jti) = claim("jti") {
+      if Some(jti) != self.id.as_ref().map(|id| id.as_str()) {
+        return Err(Error::InconsistentCredentialJwtClaims("inconsistent credential id"));
+      }
+    }
+    if let Some(sub) = claim("sub") {
+      let matches_subject: bool = match self.credential_subject.as_slice() {
+        [subject] => subject.id.as_ref().map(|id| id.as_str()) == Some(sub),
+        _ => false,
+      };
+      if !matches_subject {
+        return Err(Error::InconsistentCredentialJwtClaims(
+          "inconsistent credentialSubject: identifiers do not match",
+        ));
+      }
+    }
+
+    Ok(())
+  }
+}
+
+impl<T> Display for CredentialV2<T>
+where
+  T: Serialize,
+{
+  fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+    self.fmt_json(f)
+  }
+}
+
+/// The registered claim names of [RFC 7519](https://www.rfc-editor.org/rfc/rfc7519#section-4.1).
+pub(crate) const REGISTERED_JWT_CLAIMS: [&str; 7] = ["iss", "sub", "aud", "exp", "nbf", "iat", "jti"];
+
+/// Adds `custom_claims` to a claims set produced from a credential or presentation.
+pub(crate) fn merge_custom_claims(claims: &mut Object, custom_claims: Option<Object>) -> Result<()> {
+  for (name, value) in custom_claims.into_iter().flatten() {
+    if claims.contains_key(&name) {
+      return Err(Error::JwtClaimsSetSerializationError(
+        format!("custom claim `{name}` conflicts with an existing property").into(),
+      ));
+    }
+    claims.insert(name, value);
+  }
+  Ok(())
+}
+
+/// Removes the registered JWT claims from `claims`, returning them if any were present.
+#[cfg(feature = "validator")]
+pub(crate) fn split_registered_claims(claims: &mut Object) -> Option<Object> {
+  let registered_claims: Object = REGISTERED_JWT_CLAIMS
+    .iter()
+    .filter_map(|name| claims.remove_entry(*name))
+    .collect();
+  (!registered_claims.is_empty()).then_some(registered_claims)
+}
+
+#[cfg(test)]
+mod tests {
+  use identity_core::common::Object;
+  use identity_core::common::Timestamp;
+  use identity_core::common::Url;
+  use identity_core::convert::FromJson;
+  use identity_core::convert::ToJson;
+  use serde_json::json;
+
+  use crate::credential::CredentialBuilder;
+  use crate::credential::CredentialV2;
+  use crate::credential::LanguageValue;
+  use crate::credential::Subject;
+  use crate::Error;
+
+  const CREDENTIAL_JSON: &str = r#"
+  {
+    "@context": [
+      "https://www.w3.org/ns/credentials/v2",
+      "https://www.w3.org/ns/credentials/examples/v2"
+    ],
+    "id": "http://university.example/credentials/3732",
+    "type": ["VerifiableCredential", "ExampleDegreeCredential"],
+    "name": [
+      { "@value": "Example Degree", "@language": "en" },
+      { "@value": "Exemple de diplôme", "@language": "fr" }
+    ],
+    "description": "A credential issued to a graduate of Example University.",
+    "issuer": "https://university.example/issuers/565049",
+    "validFrom": "2010-01-01T00:00:00Z",
+    "validUntil": "2030-01-01T00:00:00Z",
+    "credentialSubject": {
+      "id": "did:example:ebfeb1f712ebc6f1c276e12ec21",
+      "degree": {
+        "type": "ExampleBachelorDegree",
+        "name": "Bachelor of Science and Arts"
+      }
+    }
+  }"#;
+
+  #[test]
+  fn from_json() {
+    let credential: CredentialV2 = CredentialV2::from_json(CREDENTIAL_JSON).unwrap();
+    assert!(credential.check_structure().is_ok());
+    assert_eq!(credential.name.as_ref().unwrap().get("fr"), Some("Exemple de diplôme"));
+    assert_eq!(
+      credential.valid_until,
+      Some(Timestamp::parse("2030-01-01T00:00:00Z").unwrap())
+    );
+    assert_eq!(
+      CredentialV2::<Object>::from_json(&credential.to_json().unwrap()).unwrap(),
+      credential
+    );
+  }
+
+  #[test]
+  fn build_v2() {
+    let credential: CredentialV2 = CredentialBuilder::default()
+      .issuer(Url::parse("did:example:issuer").unwrap())
+      .type_("ExampleDegreeCredential")
+      .name(LanguageValue::new("Example Degree", "en"))
+      .description("A credential issued to a graduate of Example University.")
+      .subject(Subject::with_id(Url::parse("did:example:subject").unwrap()))
+      .valid_from(Timestamp::parse("2010-01-01T00:00:00Z").unwrap())
+      .build_v2()
+      .unwrap();
+    assert_eq!(credential.context.get(0), Some(CredentialV2::<Object>::base_context()));
+    assert!(credential.valid_until.is_none());
+
+    let result = CredentialBuilder::<Object>::default()
+      .issuer(Url::parse("did:example:issuer").unwrap())
+      .subject(Subject::with_id(Url::parse("did:example:subject").unwrap()))
+      .valid_from(Timestamp::parse("2030-01-01T00:00:00Z").unwrap())
+      .valid_until(Timestamp::parse("2010-01-01T00:00:00Z").unwrap())
+      .build_v2();
+    assert!(matches!(result.unwrap_err(), Error::InvalidValidityPeriod));
+  }
+
+  #[test]
+  fn v1_context_is_rejected() {
+    let mut credential: CredentialV2 = CredentialV2::from_json(CREDENTIAL_JSON).unwrap();
+    credential.context = crate::credential::Credential::<Object>::base_context().clone().into();
+    assert!(matches!(
+      credential.check_structure().unwrap_err(),
+      Error::MissingBaseContext
+    ));
+  }
+
+  #[cfg(feature = "validator")]
+  #[test]
+  fn jwt_claims_roundtrip() {
+    let credential: CredentialV2 = CredentialV2::from_json(CREDENTIAL_JSON).unwrap();
+    let custom_claims: Object = Object::from_json_value(json!({ "iat": 1262304000 })).unwrap();
+    let claims: String = credential.serialize_jwt(Some(custom_claims.clone())).unwrap();
+
+    let (decoded, registered_claims): (CredentialV2, _) = CredentialV2::from_jwt_claims(claims.as_bytes()).unwrap();
+    assert_eq!(decoded, credential);
+    assert_eq!(registered_claims, Some(custom_claims));
+  }
+
+  #[test]
+  fn jwt_custom_claims_conflict() {
+    let credential: CredentialV2 = CredentialV2::from_json(CREDENTIAL_JSON).unwrap();
+    let custom_claims: Object = Object::from_json_value(json!({ "issuer": "did:example:other" })).unwrap();
+    assert!(matches!(
+      credential.serialize_jwt(Some(custom_claims)).unwrap_err(),
+      Error::JwtClaimsSetSerializationError(_)
+    ));
+  }
+
+  #[cfg(feature = "validator")]
+  #[test]
+  fn jwt_inconsistent_claims() {
+    let credential: CredentialV2 = CredentialV2::from_json(CREDENTIAL_JSON).unwrap();
+
+    for (claims, expected) in [
+      (json!({ "iss": "did:example:other" }), "inconsistent issuer"),
+      (
+        json!({ "jti": "http://university.example/credentials/1" }),
+        "inconsistent credential id",
+      ),
+      (
+        json!({ "sub": "did:example:other" }),
+        "inconsistent credentialSubject: identifiers do not match",
+      ),
+    ] {
+      let claims: String = credential
+        .serialize_jwt(Some(Object::from_json_value(claims).unwrap()))
+        .unwrap();
+      let err = CredentialV2::<Object>::from_jwt_claims(claims.as_bytes()).unwrap_err();
+      assert!(matches!(err, Error::InconsistentCredentialJwtClaims(message) if message == expected));
+    }
+  }
+}
@@ -0,0 +1,77 @@
+// Copyright 2020-2024 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+use once_cell::sync::Lazy;
+
+use identity_core::common::Context;
+use identity_core::common::Url;
+
+use crate::credential::Credential;
+
+static BASE_CONTEXT_V2: Lazy<Context> =
+  Lazy::new(|| Context::Url(Url::parse("https://www.w3.org/ns/credentials/v2").unwrap()));
+
+/// The version of the Verifiable Credentials Data Model a credential or presentation conforms to.
+///
+/// The version is determined by the first entry of the `@context` property.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum VcDataModelVersion {
+  /// [VC Data Model v1.1](https://www.w3.org/TR/vc-data-model/).
+  V1_1,
+  /// [VC Data Model v2.0](https://www.w3.org/TR/vc-data-model-2.0/).
+  V2_0,
+}
+
+impl VcDataModelVersion {
+  /// Returns the base JSON-LD context of this version.
+  pub fn base_context(&self) -> &'static Context {
+    match self {
+      Self::V1_1 => Credential::<()>::base_context(),
+      Self::V2_0 => &BASE_CONTEXT_V2,
+    }
+  }
+
+  /// Determines the version from a set of JSON-LD contexts, returning `None` if the
+  /// first context is neither of the known base contexts.
+  pub fn from_context<'a>(context: impl IntoIterator<Item = &'a Context>) -> Option<Self> {
+    let first: &Context = context.into_iter().next()?;
+    [Self::V1_1, Self::V2_0]
+      .into_iter()
+      .find(|version| version.base_context() == first)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use identity_core::common::Context;
+  use identity_core::common::OneOrMany;
+  use identity_core::common::Url;
+
+  use super::VcDataModelVersion;
+
+  #[test]
+  fn from_context() {
+    let v1: OneOrMany<Context> = OneOrMany::One(VcDataModelVersion::V1_1.base_context().clone());
+    let v2: OneOrMany<Context> = vec![
+      VcDataModelVersion::V2_0.base_context().clone(),
+      Context::Url(Url::parse("https://www.w3.org/ns/credentials/examples/v2").unwrap()),
+    ]
+    .into();
+    let other: OneOrMany<Context> = vec![
+      Context::Url(Url::parse("https://www.w3.org/ns/credentials/examples/v2").unwrap()),
+      VcDataModelVersion::V2_0.base_context().clone(),
+    ]
+    .into();
+
+    assert_eq!(
+      VcDataModelVersion::from_context(v1.iter()),
+      Some(VcDataModelVersion::V1_1)
+    );
+    assert_eq!(
+      VcDataModelVersion::from_context(v2.iter()),
+      Some(VcDataModelVersion::V2_0)
+    );
+    assert_eq!(VcDataModelVersion::from_context(other.iter()), None);
+    assert_eq!(VcDataModelVersion::from_context(&[]), None);
+  }
+}
@@ -0,0 +1,501 @@
+// Copyright 2020-2024 IOTA Stiftung, Fondazione Links
+// SPDX-License-Identifier: Apache-2.0
+
+//! Selectively disclosable JPTs that do not rely on pairing-based cryptography.
+//!
+//! The construction commits to every payload of the JWP with a salted SHA-256 hash. The issuer signs the
+//! issuer protected header, the commitments and the public key of the holder with a JWS algorithm, e.g. ML-DSA, which
+//! makes the JWP quantum-safe when that algorithm is. Presenting the JWP discloses the salts of the disclosed payloads
+//! and the commitments of the concealed ones, and the holder signs the presentation protected header, binding the
+//! presentation to the nonce of the verifier. Unlike with BBS+, presentations of the same credential are linkable.
+//!
+//! JWP proof algorithms are a closed set without an identifier for this construction, so its JWPs are identified by
+//! the [`JPT_PQC_ALGORITHM`] together with the [`JPT_PQC_TYPE`] in the issuer protected header, which the issuer signs.
+//! JWPs of that algorithm with any other `typ` are rejected rather than verified as salted hashes.
+//!
+//! The proof of an Issued JWP is
+//!
+//! ```text
+//! u32 length, issuer signature, u32 length, holder JWK, count * salt
+//! ```
+//!
+//! and the proof of a Presented JWP is
+//!
+//! ```text
+//! u32 length, issuer signature, u32 length, holder JWK, count * (salt or commitment), holder signature
+//! ```
+
+use crypto::hashes::sha::SHA256;
+use crypto::hashes::sha::SHA256_LEN;
+use identity_verification::jwk::Jwk;
+use identity_verification::jws::JwsAlgorithm;
+use identity_verification::jws::JwsVerifier;
+use identity_verification::jws::VerificationInput;
+use jsonprooftoken::errors::CustomError;
+use jsonprooftoken::jpa::algs::PresentationProofAlgorithm;
+use jsonprooftoken::jpa::algs::ProofAlgorithm;
+use jsonprooftoken::jpt::payloads::PayloadType;
+use jsonprooftoken::jpt::payloads::Payloads;
+use jsonprooftoken::jwp::header::IssuerProtectedHeader;
+use jsonprooftoken::jwp::header::PresentationProtectedHeader;
+use jsonprooftoken::jwp::issued::JwpIssued;
+use jsonprooftoken::jwp::issued::JwpIssuedBuilder;
+use jsonprooftoken::jwp::issued::JwpIssuedDecoder;
+use jsonprooftoken::jwp::presented::JwpPresented;
+use jsonprooftoken::jwp::presented::JwpPresentedBuilder;
+use jsonprooftoken::jwp::presented::JwpPresentedDecoder;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::error::Error;
+use crate::error::Result;
+
+const SALT_LEN: usize = 32;
+
+fn error(message: impl Into<String>) -> Error {
+  Error::JwpPqcError(message.into())
+}
+
+/// The proof algorithm of JPTs secured with salted hashes.
+pub const JPT_PQC_ALGORITHM: ProofAlgorithm = ProofAlgorithm::MAC_H256;
+
+/// The `typ` of the issuer protected header of JPTs secured with salted hashes.
+pub const JPT_PQC_TYPE: &str = "JPT+PQC";
+
+/// Creates the issuer protected header of a JPT secured with salted hashes.
+pub fn pqc_issuer_protected_header() -> IssuerProtectedHeader {
+  let mut issuer_header: IssuerProtectedHeader = IssuerProtectedHeader::new(JPT_PQC_ALGORITHM);
+  issuer_header.set_typ(Some(JPT_PQC_TYPE.to_owned()));
+  issuer_header
+}
+
+/// Whether JWPs with the issuer protected header `issuer_header` are secured with salted hashes rather than BBS+.
+pub(crate) fn is_pqc(issuer_header: &IssuerProtectedHeader) -> bool {
+  issuer_header.alg() == JPT_PQC_ALGORITHM && issuer_header.typ().map(String::as_str) == Some(JPT_PQC_TYPE)
+}
+
+fn check_issuer_header(issuer_header: &IssuerProtectedHeader) -> Result<()> {
+  if !is_pqc(issuer_header) {
+    return Err(error(format!(
+      "unsupported proof algorithm {} with typ {:?}",
+      issuer_header.alg(),
+      issuer_header.typ()
+    )));
+  }
+  Ok(())
+}
+
+/// The input to the signature of the issuer of an Issued JWP secured with salted hashes.
+#[derive(Clone, Debug)]
+pub struct JptPqcSigningInput {
+  signing_input: Vec<u8>,
+  holder_key: Vec<u8>,
+  salts: Vec<[u8; SALT_LEN]>,
+}
+
+impl JptPqcSigningInput {
+  /// Commits to the payloads of `jwp_builder` with fresh salts, binding the JWP to the public key of the holder,
+  /// which must specify its `alg`.
+  pub fn new(jwp_builder: &JwpIssuedBuilder, holder_key: &Jwk) -> Result<Self> {
+    let issuer_header: &IssuerProtectedHeader = jwp_builder
+      .get_issuer_protected_header()
+      .ok_or_else(|| error("missing issuer protected header"))?;
+    check_issuer_header(issuer_header)?;
+    let payloads: &Payloads = jwp_builder.get_payloads().ok_or_else(|| error("missing payloads"))?;
+    let holder_key: Vec<u8> = encode_holder_key(holder_key)?;
+
+    let salts: Vec<[u8; SALT_LEN]> = (0..payloads.0.len())
+      .map(|_| {
+        let mut salt: [u8; SALT_LEN] = [0; SALT_LEN];
+        crypto::utils::rand::fill(&mut salt).map(|_| salt)
+      })
+      .collect::<std::result::Result<_, _>>()
+      .map_err(|err| error(err.to_string()))?;
+    let commitments: Vec<[u8; SHA256_LEN]> = payloads
+      .0
+      .iter()
+      .zip(&salts)
+      .map(|((value, _), salt)| commit(salt, value))
+      .collect::<Result<_>>()?;
+
+    Ok(Self {
+      signing_input: issuer_signing_input(issuer_header, &holder_key, &commitments)?,
+      holder_key,
+      salts,
+    })
+  }
+
+  /// The bytes to be signed by the issuer.
+  pub fn signing_input(&self) -> &[u8] {
+    &self.signing_input
+  }
+
+  /// Appends the public key of the holder and the salts to the `signature` of the issuer, giving the proof of the
+  /// Issued JWP.
+  pub fn into_proof(self, signature: Vec<u8>) -> Vec<u8> {
+    let mut proof: Vec<u8> = Vec::new();
+    write_prefixed(&mut proof, &signature);
+    write_prefixed(&mut proof, &self.holder_key);
+    self.salts.iter().for_each(|salt| proof.extend(salt));
+    proof
+  }
+}
+
+/// The bytes to be signed by the holder to present the Issued JWP in `jwp_builder`, secured with salted hashes.
+pub(crate) fn presentation_signing_input(jwp_builder: &JwpPresentedBuilder) -> Result<Vec<u8>> {
+  let presentation_header: &PresentationProtectedHeader = jwp_builder
+    .get_presentation_protected_header()
+    .ok_or_else(|| error("missing presentation protected header"))?;
+  let issued: IssuedProof = IssuedProof::decode(jwp_builder.issuer_proof(), jwp_builder.get_payloads().0.len())?;
+  holder_signing_input(presentation_header, &issued.signature)
+}
+
+/// Builds the Presented JWP of the Issued JWP in `jwp_builder`, secured with salted hashes, given the signature of the holder over its
+/// [`presentation_signing_input`].
+pub(crate) fn build_presented_jwp(jwp_builder: &JwpPresentedBuilder, holder_signature: &[u8]) -> Result<JwpPresented> {
+  let payloads: &Payloads = jwp_builder.get_payloads();
+  let issued: IssuedProof = IssuedProof::decode(jwp_builder.issuer_proof(), payloads.0.len())?;
+
+  let mut proof: Vec<u8> = Vec::new();
+  write_prefixed(&mut proof, &issued.signature);
+  write_prefixed(&mut proof, &issued.holder_key);
+  for ((value, payload_type), salt) in payloads.0.iter().zip(&issued.salts) {
+    match payload_type {
+      PayloadType::Disclosed => proof.extend(salt),
+      _ => proof.extend(commit(salt, value)?),
+    }
+  }
+  proof.extend(holder_signature);
+
+  jwp_builder
+    .build_with_proof(proof)
+    .map_err(|err| Error::JwpBuildingError(err.into()))
+}
+
+/// Verifies the proof of a decoded Issued JWP secured with salted hashes with the public key of the issuer.
+pub(crate) fn verify_issued_jwp<V>(decoded: &JwpIssuedDecoder, public_key: &Jwk, verifier: &V) -> Result<JwpIssued>
+where
+  V: JwsVerifier,
+{
+  let issued: JwpIssued = transcode(decoded)?;
+  let issuer_header: &IssuerProtectedHeader = issued.get_issuer_protected_header();
+  check_issuer_header(issuer_header)?;
+  let payloads: &Payloads = issued.get_payloads();
+  let proof: IssuedProof = IssuedProof::decode(issued.get_proof(), payloads.0.len())?;
+  let commitments: Vec<[u8; SHA256_LEN]> = payloads
+    .0
+    .iter()
+    .zip(&proof.salts)
+    .map(|((value, _), salt)| commit(salt, value))
+    .collect::<Result<_>>()?;
+
+  let signing_input: Vec<u8> = issuer_signing_input(issuer_header, &proof.holder_key, &commitments)?;
+  verify_signature(verifier, public_key, signing_input, proof.signature)?;
+
+  Ok(issued)
+}
+
+/// Verifies the proof of a decoded Presented JWP secured with salted hashes with the public key of the issuer and
+/// `issuer_verifier`, and the signature of the holder with the public key the issuer bound the JWP to and
+/// `holder_verifier`.
+pub(crate) fn verify_presented_jwp<IV, HV>(
+  decoded: &JwpPresentedDecoder,
+  public_key: &Jwk,
+  issuer_verifier: &IV,
+  holder_verifier: &HV,
+) -> Result<JwpPresented>
+where
+  IV: JwsVerifier,
+  HV: JwsVerifier,
+{
+  let presented: JwpPresented = transcode(decoded)?;
+  let issuer_header: &IssuerProtectedHeader = presented.get_issuer_protected_header();
+  let presentation_header: &PresentationProtectedHeader = presented.get_presentation_protected_header();
+  check_issuer_header(issuer_header)?;
+  let presentation_alg: PresentationProofAlgorithm = issuer_header.alg().into();
+  if presentation_header.alg() != presentation_alg {
+    return Err(error(format!(
+      "unsupported proof algorithm {}",
+      presentation_header.alg()
+    )));
+  }
+
+  let payloads: &Payloads = presented.get_payloads();
+  let mut reader: &[u8] = presented.get_proof();
+  let signature: Vec<u8> = read_prefixed(&mut reader)?;
+  let holder_key: Vec<u8> = read_prefixed(&mut reader)?;
+  let commitments: Vec<[u8; SHA256_LEN]> = payloads
+    .0
+    .iter()
+    .map(|(value, payload_type)| {
+      let bytes: [u8; SALT_LEN] = read_array(&mut reader)?;
+      match payload_type {
+        PayloadType::Disclosed => commit(&bytes, value),
+        _ => Ok(bytes),
+      }
+    })
+    .collect::<Result<_>>()?;
+  let holder_signature: Vec<u8> = reader.to_vec();
+
+  let signing_input: Vec<u8> = issuer_signing_input(issuer_header, &holder_key, &commitments)?;
+  verify_signature(issuer_verifier, public_key, signing_input, signature.clone())?;
+
+  let holder_key: Jwk = serde_json::from_slice(&holder_key).map_err(|_| error("invalid holder key"))?;
+  let signing_input: Vec<u8> = holder_signing_input(presentation_header, &signature)?;
+  verify_signature(holder_verifier, &holder_key, signing_input, holder_signature)
+    .map_err(|_| error("invalid holder signature"))?;
+
+  Ok(presented)
+}
+
+/// Converts errors of this module into those of the JWP library, as returned by the verification of JWPs.
+pub(crate) fn verification_error(err: Error) -> CustomError {
+  CustomError::ProofVerificationError(err.to_string())
+}
+
+/// The decoded proof of an Issued JWP.
+struct IssuedProof {
+  signature: Vec<u8>,
+  holder_key: Vec<u8>,
+  salts: Vec<[u8; SALT_LEN]>,
+}
+
+impl IssuedProof {
+  fn decode(mut proof: &[u8], payload_count: usize) -> Result<Self> {
+    let signature: Vec<u8> = read_prefixed(&mut proof)?;
+    let holder_key: Vec<u8> = read_prefixed(&mut proof)?;
+    let salts: Vec<[u8; SALT_LEN]> = (0..payload_count)
+      .map(|_| read_array(&mut proof))
+      .collect::<Result<_>>()?;
+    if !proof.is_empty() {
+      return Err(error("trailing bytes in proof"));
+    }
+    Ok(Self {
+      signature,
+      holder_key,
+      salts,
+    })
+  }
+}
+
+fn encode_holder_key(holder_key: &Jwk) -> Result<Vec<u8>> {
+  let alg: &str = holder_key
+    .alg()
+    .ok_or_else(|| error("the holder key must specify its alg"))?;
+  alg
+    .parse::<JwsAlgorithm>()
+    .map_err(|_| error(format!("unsupported holder key algorithm {alg}")))?;
+  let public_key: Jwk = holder_key
+    .to_public()
+    .ok_or_else(|| error("the holder key has no public key material"))?;
+  serde_json::to_vec(&public_key).map_err(|err| error(err.to_string()))
+}
+
+/// The salted commitment `SHA-256(salt || payload)` to a payload.
+fn commit(salt: &[u8; SALT_LEN], value: &Value) -> Result<[u8; SHA256_LEN]> {
+  let mut input: Vec<u8> = salt.to_vec();
+  input.extend(serde_json::to_vec(value).map_err(|err| error(err.to_string()))?);
+  let mut digest: [u8; SHA256_LEN] = [0; SHA256_LEN];
+  SHA256(&input, &mut digest);
+  Ok(digest)
+}
+
+fn issuer_signing_input(
+  issuer_header: &IssuerProtectedHeader,
+  holder_key: &[u8],
+  commitments: &[[u8; SHA256_LEN]],
+) -> Result<Vec<u8>> {
+  let mut input: Vec<u8> = Vec::new();
+  write_prefixed(
+    &mut input,
+    &serde_json::to_vec(issuer_header).map_err(|err| error(err.to_string()))?,
+  );
+  write_prefixed(&mut input, holder_key);
+  commitments.iter().for_each(|commitment| input.extend(commitment));
+  Ok(input)
+}
+
+fn holder_signing_input(presentation_header: &PresentationProtectedHeader, issuer_signature: &[u8]) -> Result<Vec<u8>> {
+  let mut input: Vec<u8> = Vec::new();
+  write_prefixed(
+    &mut input,
+    &serde_json::to_vec(presentation_header).map_err(|err| error(err.to_string()))?,
+  );
+  write_prefixed(&mut input, issuer_signature);
+  Ok(input)
+}
+
+fn verify_signature<V>(verifier: &V, public_key: &Jwk, signing_input: Vec<u8>, signature: Vec<u8>) -> Result<()>
+where
+  V: JwsVerifier,
+{
+  let alg: JwsAlgorithm = public_key
+    .alg()
+    .and_then(|alg| alg.parse().ok())
+    .ok_or_else(|| error("the public key does not specify a supported alg"))?;
+  verifier
+    .verify(
+      VerificationInput {
+        alg,
+        signing_input: signing_input.into_boxed_slice(),
+        decoded_signature: signature.into_boxed_slice(),
+      },
+      public_key,
+    )
+    .map_err(|err| error(err.to_string()))
+}
+
+fn write_prefixed(output: &mut Vec<u8>, bytes: &[u8]) {
+  output.extend((bytes.len() as u32).to_be_bytes());
+  output.extend(bytes);
+}
+
+fn read_prefixed(input: &mut &[u8]) -> Result<Vec<u8>> {
+  let length: usize = u32::from_be_bytes(read_array(input)?) as usize;
+  if input.len() < length {
+    return Err(error("unexpected end of proof"));
+  }
+  let (bytes, rest) = input.split_at(length);
+  *input = rest;
+  Ok(bytes.to_vec())
+}
+
+fn read_array<const N: usize>(input: &mut &[u8]) -> Result<[u8; N]> {
+  if input.len() < N {
+    return Err(error("unexpected end of proof"));
+  }
+  let (bytes, rest) = input.split_at(N);
+  *input = rest;
+  Ok(bytes.try_into().expect("length checked"))
+}
+
+/// Converts a decoded JWP into the JWP it represents, as both share their serialization.
+fn transcode<T, U>(decoded: &T) -> Result<U>
+where
+  T: Serialize,
+  U: DeserializeOwned,
+{
+  serde_json::to_value(decoded)
+    .and_then(serde_json::from_value)
+    .map_err(|err| error(err.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+  use crypto::signatures::ed25519::SecretKey;
+  use identity_eddsa_verifier::EdDSAJwsVerifier;
+  use identity_verification::jose::jwk::EdCurve;
+  use identity_verification::jose::jwk::JwkParamsOkp;
+  use identity_verification::jose::jwu;
+  use jsonprooftoken::encoding::SerializationType;
+  use jsonprooftoken::jpt::claims::JptClaims;
+  use serde_json::json;
+
+  use super::*;
+
+  struct Key {
+    secret: SecretKey,
+    jwk: Jwk,
+  }
+
+  impl Key {
+    fn new() -> Self {
+      let secret: SecretKey = SecretKey::generate().unwrap();
+      let mut params: JwkParamsOkp = JwkParamsOkp::new();
+      params.crv = EdCurve::Ed25519.name().to_owned();
+      params.x = jwu::encode_b64(secret.public_key().as_slice());
+      let mut jwk: Jwk = Jwk::from_params(params);
+      jwk.set_alg(JwsAlgorithm::EdDSA.name());
+      Self { secret, jwk }
+    }
+
+    fn sign(&self, input: &[u8]) -> Vec<u8> {
+      self.secret.sign(input).to_bytes().to_vec()
+    }
+  }
+
+  fn issue(issuer: &Key, holder: &Key) -> JwpIssued {
+    let mut claims: JptClaims = JptClaims::new();
+    claims.set_claim(
+      Some("vc"),
+      json!({"credentialSubject": {"name": "Alice", "age": 30}}),
+      true,
+    );
+    let jwp_builder: JwpIssuedBuilder = JwpIssuedBuilder::new(pqc_issuer_protected_header(), claims);
+    let input: JptPqcSigningInput = JptPqcSigningInput::new(&jwp_builder, &holder.jwk).unwrap();
+    let signature: Vec<u8> = issuer.sign(input.signing_input());
+    jwp_builder.build_with_proof(input.into_proof(signature)).unwrap()
+  }
+
+  fn present(issued: &JwpIssued, holder: &Key, nonce: &str) -> String {
+    let mut jwp_builder: JwpPresentedBuilder = JwpPresentedBuilder::new(issued);
+    jwp_builder.set_undisclosed("vc.credentialSubject.age").unwrap();
+    let mut presentation_header = PresentationProtectedHeader::new(JPT_PQC_ALGORITHM.into());
+    presentation_header.set_nonce(Some(nonce.to_owned()));
+    jwp_builder.set_presentation_protected_header(presentation_header);
+
+    let signature: Vec<u8> = holder.sign(&presentation_signing_input(&jwp_builder).unwrap());
+    build_presented_jwp(&jwp_builder, &signature)
+      .unwrap()
+      .encode(SerializationType::COMPACT)
+      .unwrap()
+  }
+
+  fn verify(presented: &str, issuer: &Key) -> Result<JwpPresented> {
+    let decoded: JwpPresentedDecoder = JwpPresentedDecoder::decode(presented, SerializationType::COMPACT).unwrap();
+    verify_presented_jwp(
+      &decoded,
+      &issuer.jwk,
+      &EdDSAJwsVerifier::default(),
+      &EdDSAJwsVerifier::default(),
+    )
+  }
+
+  #[test]
+  fn presentations_disclose_only_selected_payloads() {
+    let (issuer, holder) = (Key::new(), Key::new());
+    let issued: JwpIssued = issue(&issuer, &holder);
+    let encoded: String = issued.encode(SerializationType::COMPACT).unwrap();
+    let decoded: JwpIssuedDecoder = JwpIssuedDecoder::decode(&encoded, SerializationType::COMPACT).unwrap();
+    verify_issued_jwp(&decoded, &issuer.jwk, &EdDSAJwsVerifier::default()).unwrap();
+
+    let presented: JwpPresented = verify(&present(&issued, &holder, "nonce"), &issuer).unwrap();
+    let values: Vec<Value> = presented.get_payloads().get_values();
+    assert!(values.contains(&json!("Alice")));
+    assert!(!values.contains(&json!(30)));
+  }
+
+  #[test]
+  fn presentations_are_bound_to_the_issuer_and_holder() {
+    let (issuer, holder) = (Key::new(), Key::new());
+    let issued: JwpIssued = issue(&issuer, &holder);
+
+    // Another key of the holder cannot present the JWP.
+    assert!(verify(&present(&issued, &Key::new(), "nonce"), &issuer).is_err());
+    assert!(verify(&present(&issued, &holder, "nonce"), &Key::new()).is_err());
+
+    // The presentation header cannot be replaced, e.g. to replay the presentation with another nonce.
+    let presented: String = present(&issued, &holder, "nonce");
+    let other: String = present(&issued, &holder, "other");
+    let mut parts: Vec<&str> = presented.split('.').collect();
+    parts[1] = other.split('.').nth(1).unwrap();
+    assert!(verify(&parts.join("."), &issuer).is_err());
+  }
+
+  #[test]
+  fn mac_h256_jwps_without_the_pqc_type_are_rejected() {
+    let (issuer, holder) = (Key::new(), Key::new());
+    let mut claims: JptClaims = JptClaims::new();
+    claims.set_claim(Some("vc"), json!({"credentialSubject": {"name": "Alice"}}), true);
+    let jwp_builder: JwpIssuedBuilder =
+      JwpIssuedBuilder::new(IssuerProtectedHeader::new(ProofAlgorithm::MAC_H256), claims);
+    assert!(JptPqcSigningInput::new(&jwp_builder, &holder.jwk).is_err());
+
+    // The `typ` is part of what identifies the construction.
+    let issued: JwpIssued = issue(&issuer, &holder);
+    let mut issuer_header: IssuerProtectedHeader = issued.get_issuer_protected_header().clone();
+    issuer_header.set_typ(Some("JPT".to_owned()));
+    assert!(!is_pqc(&issuer_header));
+  }
+}
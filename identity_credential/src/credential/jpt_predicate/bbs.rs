@@ -0,0 +1,458 @@
+// Copyright 2020-2024 IOTA Stiftung, Fondazione Links
+// SPDX-License-Identifier: Apache-2.0
+
+//! BBS signatures and proofs over messages that are scalars rather than octet strings, following the core operations
+//! of [draft-irtf-cfrg-bbs-signatures-05](https://datatracker.ietf.org/doc/html/draft-irtf-cfrg-bbs-signatures-05).
+//!
+//! The proofs let the prover choose the blinding of undisclosed messages, which links them to other proofs about the
+//! same messages.
+//!
+//! Beyond the core operations, this is a construction of this crate rather than a standard one: scalar messages,
+//! chosen blindings and the signed holder secret of [`holder_binding`](super::holder_binding) change the challenge
+//! input of the draft's `ProofGen`, so these proofs only verify with this crate.
+//!
+//! # Warning
+//!
+//! This construction has not been reviewed by cryptographers and is only compiled with the
+//! `jpt-predicates-experimental` feature. It must not be used in production until it has been reviewed.
+
+use std::collections::BTreeMap;
+use std::collections::BTreeSet;
+
+use bls12_381_plus::group::Curve;
+use bls12_381_plus::multi_miller_loop;
+use bls12_381_plus::G1Projective;
+use bls12_381_plus::G2Prepared;
+use bls12_381_plus::G2Projective;
+use bls12_381_plus::Gt;
+use bls12_381_plus::Scalar;
+use zkryptium::bbsplus::ciphersuites::BbsCiphersuite;
+use zkryptium::bbsplus::generators::Generators;
+use zkryptium::bbsplus::keys::BBSplusPublicKey;
+use zkryptium::bbsplus::signature::BBSplusSignature;
+use zkryptium::utils::util::bbsplus_utils::calculate_random_scalars;
+use zkryptium::utils::util::bbsplus_utils::hash_to_scalar;
+use zkryptium::utils::util::bbsplus_utils::i2osp;
+
+use super::encoding::Reader;
+use super::error;
+use super::holder_binding::HolderResponse;
+use crate::error::Result;
+
+pub(crate) const G1_BYTES: usize = 48;
+pub(crate) const SCALAR_BYTES: usize = 32;
+
+/// The generators and domain shared by the signature and proofs over a number of messages.
+struct Context {
+  p1: G1Projective,
+  q1: G1Projective,
+  h: Vec<G1Projective>,
+  domain: Scalar,
+}
+
+impl Context {
+  fn new<CS>(pk: &BBSplusPublicKey, header: &[u8], message_count: usize) -> Result<Self>
+  where
+    CS: BbsCiphersuite,
+  {
+    let generators: Generators = Generators::create::<CS>(message_count + 1, Some(CS::API_ID));
+    let q1: G1Projective = generators.values[0];
+    let h: Vec<G1Projective> = generators.values[1..].to_vec();
+
+    let mut domain_input: Vec<u8> = pk.to_bytes().to_vec();
+    domain_input.extend(i2osp(message_count, 8));
+    domain_input.extend(q1.to_affine().to_compressed());
+    h.iter()
+      .for_each(|point| domain_input.extend(point.to_affine().to_compressed()));
+    domain_input.extend(CS::API_ID);
+    domain_input.extend(i2osp(header.len(), 8));
+    domain_input.extend(header);
+    let domain: Scalar = hash_to_scalar::<CS>(&domain_input, &[CS::API_ID, CS::H2S].concat()).map_err(bbs_error)?;
+
+    Ok(Self {
+      p1: generators.g1_base_point,
+      q1,
+      h,
+      domain,
+    })
+  }
+
+  /// Computes `P1 + Q1 * domain + H_i * msg_i` over the given indexed messages.
+  fn commitment<'a>(&self, messages: impl IntoIterator<Item = (usize, &'a Scalar)>) -> G1Projective {
+    messages
+      .into_iter()
+      .fold(self.p1 + self.q1 * self.domain, |b, (index, message)| {
+        b + self.h[index] * message
+      })
+  }
+}
+
+/// Verifies a BBS `signature` on scalar `messages`, and on the secret committed to by `holder_commitment` if set.
+pub(crate) fn verify_signature<CS>(
+  pk: &BBSplusPublicKey,
+  signature: &BBSplusSignature,
+  header: &[u8],
+  messages: &[Scalar],
+  holder_commitment: Option<&G1Projective>,
+) -> Result<()>
+where
+  CS: BbsCiphersuite,
+{
+  let context: Context = Context::new::<CS>(pk, header, messages.len())?;
+  let b: G1Projective =
+    context.commitment(messages.iter().enumerate()) + holder_commitment.copied().unwrap_or_default();
+  let a2: G2Projective = pk.0 + G2Projective::GENERATOR * signature.e;
+
+  if pairing_is_identity(&signature.A, &a2, &b) {
+    Ok(())
+  } else {
+    Err(error("invalid BBS signature"))
+  }
+}
+
+/// A proof of knowledge of a BBS signature, serialized as by `zkryptium`.
+#[derive(Clone, Debug)]
+pub(crate) struct BbsProof {
+  a_bar: G1Projective,
+  b_bar: G1Projective,
+  d: G1Projective,
+  e_cap: Scalar,
+  r1_cap: Scalar,
+  r3_cap: Scalar,
+  m_cap: Vec<Scalar>,
+  challenge: Scalar,
+}
+
+impl BbsProof {
+  /// The length of a serialized proof with `undisclosed` undisclosed messages.
+  pub(crate) fn byte_len(undisclosed: usize) -> usize {
+    3 * G1_BYTES + (4 + undisclosed) * SCALAR_BYTES
+  }
+
+  pub(crate) fn challenge(&self) -> Scalar {
+    self.challenge
+  }
+
+  /// The response for the undisclosed message at `position` among the undisclosed messages.
+  pub(crate) fn m_cap(&self, position: usize) -> Option<Scalar> {
+    self.m_cap.get(position).copied()
+  }
+
+  pub(crate) fn to_bytes(&self) -> Vec<u8> {
+    let mut bytes: Vec<u8> = Vec::with_capacity(Self::byte_len(self.m_cap.len()));
+    for point in [self.a_bar, self.b_bar, self.d] {
+      bytes.extend(point.to_affine().to_compressed());
+    }
+    for scalar in [self.e_cap, self.r1_cap, self.r3_cap]
+      .iter()
+      .chain(&self.m_cap)
+      .chain([&self.challenge])
+    {
+      bytes.extend(scalar.to_be_bytes());
+    }
+    bytes
+  }
+
+  pub(crate) fn from_reader(reader: &mut Reader<'_>, undisclosed: usize) -> Result<Self> {
+    Ok(Self {
+      a_bar: reader.point()?,
+      b_bar: reader.point()?,
+      d: reader.point()?,
+      e_cap: reader.scalar()?,
+      r1_cap: reader.scalar()?,
+      r3_cap: reader.scalar()?,
+      m_cap: (0..undisclosed).map(|_| reader.scalar()).collect::<Result<_>>()?,
+      challenge: reader.scalar()?,
+    })
+  }
+}
+
+/// The first phase of a proof of knowledge of a BBS signature, up to the computation of the challenge.
+#[derive(Clone, Debug)]
+pub(crate) struct ProofInit {
+  a_bar: G1Projective,
+  b_bar: G1Projective,
+  d: G1Projective,
+  e: Scalar,
+  r1: Scalar,
+  r3: Scalar,
+  e_tilde: Scalar,
+  r1_tilde: Scalar,
+  r3_tilde: Scalar,
+  m_tilde: Vec<Scalar>,
+  undisclosed_messages: Vec<Scalar>,
+  challenge_input: Vec<u8>,
+}
+
+impl ProofInit {
+  /// The input hashed to the challenge of the proof.
+  pub(crate) fn challenge_input(&self) -> &[u8] {
+    &self.challenge_input
+  }
+
+  /// Completes the proof with `challenge`.
+  pub(crate) fn finish(self, challenge: Scalar) -> BbsProof {
+    BbsProof {
+      a_bar: self.a_bar,
+      b_bar: self.b_bar,
+      d: self.d,
+      e_cap: self.e_tilde + self.e * challenge,
+      r1_cap: self.r1_tilde - self.r1 * challenge,
+      r3_cap: self.r3_tilde - self.r3 * challenge,
+      m_cap: self
+        .undisclosed_messages
+        .iter()
+        .zip(self.m_tilde)
+        .map(|(message, m_tilde)| m_tilde + message * challenge)
+        .collect(),
+      challenge,
+    }
+  }
+}
+
+/// Starts a proof of knowledge of a BBS `signature` on `messages`, disclosing the messages at `disclosed`.
+///
+/// The undisclosed messages listed in `blindings` are blinded with the given scalars, making their responses in the
+/// proof `blinding + message * challenge`. If the signature is also on a holder secret committed to by
+/// `holder_commitment`, the announcement for the secret is left out of the challenge input, as it is only known to
+/// the key storage of the holder.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn init_proof<CS>(
+  pk: &BBSplusPublicKey,
+  signature: &BBSplusSignature,
+  header: &[u8],
+  presentation_header: &[u8],
+  messages: &[Scalar],
+  disclosed: &BTreeSet<usize>,
+  blindings: &BTreeMap<usize, Scalar>,
+  holder_commitment: Option<&G1Projective>,
+) -> Result<ProofInit>
+where
+  CS: BbsCiphersuite,
+{
+  let context: Context = Context::new::<CS>(pk, header, messages.len())?;
+  let undisclosed: Vec<usize> = (0..messages.len()).filter(|index| !disclosed.contains(index)).collect();
+
+  let random: Vec<Scalar> = calculate_random_scalars(5 + undisclosed.len());
+  let (r1, r2, e_tilde, r1_tilde, r3_tilde) = (random[0], random[1], random[2], random[3], random[4]);
+  let m_tilde: Vec<Scalar> = undisclosed
+    .iter()
+    .zip(&random[5..])
+    .map(|(index, random)| blindings.get(index).copied().unwrap_or(*random))
+    .collect();
+
+  let b: G1Projective =
+    context.commitment(messages.iter().enumerate()) + holder_commitment.copied().unwrap_or_default();
+  let d: G1Projective = b * r2;
+  let a_bar: G1Projective = signature.A * (r1 * r2);
+  let b_bar: G1Projective = d * r1 - a_bar * signature.e;
+
+  let t1: G1Projective = a_bar * e_tilde + d * r1_tilde;
+  let t2: G1Projective = undisclosed
+    .iter()
+    .zip(&m_tilde)
+    .fold(d * r3_tilde, |t2, (&index, m_tilde)| t2 + context.h[index] * m_tilde);
+
+  let disclosed_messages: BTreeMap<usize, Scalar> = disclosed.iter().map(|&index| (index, messages[index])).collect();
+  let challenge_input: Vec<u8> = challenge_input(
+    &context,
+    [&a_bar, &b_bar, &d, &t1, &t2],
+    &disclosed_messages,
+    presentation_header,
+  );
+
+  Ok(ProofInit {
+    a_bar,
+    b_bar,
+    d,
+    e: signature.e,
+    r1,
+    r3: Option::from(r2.invert()).ok_or_else(|| error("invalid random scalar"))?,
+    e_tilde,
+    r1_tilde,
+    r3_tilde,
+    m_tilde,
+    undisclosed_messages: undisclosed.iter().map(|&index| messages[index]).collect(),
+    challenge_input,
+  })
+}
+
+/// Proves knowledge of a BBS `signature` on `messages`, disclosing the messages at `disclosed`.
+///
+/// See [`init_proof`] for the meaning of `blindings`.
+#[cfg(test)]
+pub(crate) fn prove<CS>(
+  pk: &BBSplusPublicKey,
+  signature: &BBSplusSignature,
+  header: &[u8],
+  presentation_header: &[u8],
+  messages: &[Scalar],
+  disclosed: &BTreeSet<usize>,
+  blindings: &BTreeMap<usize, Scalar>,
+) -> Result<BbsProof>
+where
+  CS: BbsCiphersuite,
+{
+  let init: ProofInit = init_proof::<CS>(
+    pk,
+    signature,
+    header,
+    presentation_header,
+    messages,
+    disclosed,
+    blindings,
+    None,
+  )?;
+  let challenge: Scalar = challenge::<CS>(init.challenge_input())?;
+  Ok(init.finish(challenge))
+}
+
+/// Verifies a BBS `proof` over `message_count` messages, of which `disclosed` are disclosed, and over a holder secret
+/// if `holder` is set.
+pub(crate) fn verify_proof<CS>(
+  pk: &BBSplusPublicKey,
+  proof: &BbsProof,
+  header: &[u8],
+  presentation_header: &[u8],
+  message_count: usize,
+  disclosed: &BTreeMap<usize, Scalar>,
+  holder: Option<&HolderResponse>,
+) -> Result<()>
+where
+  CS: BbsCiphersuite,
+{
+  let context: Context = Context::new::<CS>(pk, header, message_count)?;
+  let undisclosed: Vec<usize> = (0..message_count)
+    .filter(|index| !disclosed.contains_key(index))
+    .collect();
+  if proof.m_cap.len() != undisclosed.len() || disclosed.keys().any(|&index| index >= message_count) {
+    return Err(error("invalid number of undisclosed messages"));
+  }
+
+  let t1: G1Projective = proof.b_bar * proof.challenge + proof.a_bar * proof.e_cap + proof.d * proof.r1_cap;
+  let bv: G1Projective = context.commitment(disclosed.iter().map(|(&index, message)| (index, message)));
+  let t2: G1Projective = undisclosed
+    .iter()
+    .zip(&proof.m_cap)
+    .fold(bv * proof.challenge + proof.d * proof.r3_cap, |t2, (&index, m_cap)| {
+      t2 + context.h[index] * m_cap
+    })
+    + holder.map(HolderResponse::announcement_term::<CS>).unwrap_or_default();
+
+  let challenge_input: Vec<u8> = challenge_input(
+    &context,
+    [&proof.a_bar, &proof.b_bar, &proof.d, &t1, &t2],
+    disclosed,
+    presentation_header,
+  );
+  let challenge: Scalar = match holder {
+    Some(holder) => holder.challenge::<CS>(&challenge_input, proof.challenge)?,
+    None => challenge::<CS>(&challenge_input)?,
+  };
+
+  if challenge != proof.challenge
+    || bool::from(proof.a_bar.is_identity())
+    || !pairing_is_identity(&proof.a_bar, &pk.0, &proof.b_bar)
+  {
+    return Err(error("invalid BBS proof"));
+  }
+  Ok(())
+}
+
+/// The challenge of a proof without holder secret.
+pub(crate) fn challenge<CS>(challenge_input: &[u8]) -> Result<Scalar>
+where
+  CS: BbsCiphersuite,
+{
+  hash_to_scalar::<CS>(challenge_input, &[CS::API_ID, CS::H2S].concat()).map_err(bbs_error)
+}
+
+fn challenge_input(
+  context: &Context,
+  points: [&G1Projective; 5],
+  disclosed: &BTreeMap<usize, Scalar>,
+  presentation_header: &[u8],
+) -> Vec<u8> {
+  let mut input: Vec<u8> = Vec::new();
+  points
+    .iter()
+    .for_each(|point| input.extend(point.to_affine().to_compressed()));
+  input.extend(i2osp(disclosed.len(), 8));
+  disclosed.keys().for_each(|&index| input.extend(i2osp(index, 8)));
+  disclosed
+    .values()
+    .for_each(|message| input.extend(message.to_be_bytes()));
+  input.extend(context.domain.to_be_bytes());
+  input.extend(i2osp(presentation_header.len(), 8));
+  input.extend(presentation_header);
+  input
+}
+
+/// Checks `e(a, w) * e(b, -G2) == 1`.
+fn pairing_is_identity(a: &G1Projective, w: &G2Projective, b: &G1Projective) -> bool {
+  let w: G2Prepared = G2Prepared::from(w.to_affine());
+  let minus_g2: G2Prepared = G2Prepared::from(-G2Projective::GENERATOR.to_affine());
+  multi_miller_loop(&[(&a.to_affine(), &w), (&b.to_affine(), &minus_g2)]).final_exponentiation() == Gt::IDENTITY
+}
+
+fn bbs_error(err: zkryptium::errors::Error) -> crate::Error {
+  error(err.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+  use zkryptium::bbsplus::ciphersuites::Bls12381Sha256;
+  use zkryptium::bbsplus::keys::BBSplusSecretKey;
+  use zkryptium::keys::pair::KeyPair;
+  use zkryptium::schemes::algorithms::BBSplus;
+
+  use super::*;
+
+  /// Signs scalar messages as a key storage does.
+  pub(crate) fn sign(
+    sk: &BBSplusSecretKey,
+    pk: &BBSplusPublicKey,
+    header: &[u8],
+    messages: &[Scalar],
+  ) -> BBSplusSignature {
+    let context: Context = Context::new::<Bls12381Sha256>(pk, header, messages.len()).unwrap();
+    let mut input: Vec<u8> = sk.0.to_be_bytes().to_vec();
+    input.extend(context.domain.to_be_bytes());
+    messages.iter().for_each(|message| input.extend(message.to_be_bytes()));
+    let e: Scalar =
+      hash_to_scalar::<Bls12381Sha256>(&input, &[Bls12381Sha256::API_ID, Bls12381Sha256::H2S].concat()).unwrap();
+    let b: G1Projective = context.commitment(messages.iter().enumerate());
+    BBSplusSignature {
+      A: b * (sk.0 + e).invert().unwrap(),
+      e,
+    }
+  }
+
+  #[test]
+  fn scalar_messages_are_signed_and_proven() {
+    let (sk, pk) = KeyPair::<BBSplus<Bls12381Sha256>>::random().unwrap().into_parts();
+    let messages: Vec<Scalar> = (1..=4u64).map(Scalar::from).collect();
+    let signature: BBSplusSignature = sign(&sk, &pk, b"header", &messages);
+    verify_signature::<Bls12381Sha256>(&pk, &signature, b"header", &messages, None).unwrap();
+    assert!(verify_signature::<Bls12381Sha256>(&pk, &signature, b"other", &messages, None).is_err());
+
+    let disclosed: BTreeSet<usize> = [0, 2].into_iter().collect();
+    let blinding: Scalar = Scalar::from(99u64);
+    let blindings: BTreeMap<usize, Scalar> = [(3, blinding)].into_iter().collect();
+    let proof: BbsProof =
+      prove::<Bls12381Sha256>(&pk, &signature, b"header", b"ph", &messages, &disclosed, &blindings).unwrap();
+    assert_eq!(proof.m_cap(1).unwrap(), blinding + messages[3] * proof.challenge());
+
+    let bytes: Vec<u8> = proof.to_bytes();
+    assert_eq!(bytes.len(), BbsProof::byte_len(2));
+    let mut reader: Reader<'_> = Reader::new(&bytes);
+    let proof: BbsProof = BbsProof::from_reader(&mut reader, 2).unwrap();
+    reader.finish().unwrap();
+
+    let disclosed: BTreeMap<usize, Scalar> = [(0, messages[0]), (2, messages[2])].into_iter().collect();
+    verify_proof::<Bls12381Sha256>(&pk, &proof, b"header", b"ph", 4, &disclosed, None).unwrap();
+    assert!(verify_proof::<Bls12381Sha256>(&pk, &proof, b"header", b"other", 4, &disclosed, None).is_err());
+    let tampered: BTreeMap<usize, Scalar> = [(0, messages[1]), (2, messages[2])].into_iter().collect();
+    assert!(verify_proof::<Bls12381Sha256>(&pk, &proof, b"header", b"ph", 4, &tampered, None).is_err());
+  }
+}
@@ -0,0 +1,374 @@
+// Copyright 2020-2024 IOTA Stiftung, Fondazione Links
+// SPDX-License-Identifier: Apache-2.0
+
+use std::collections::BTreeMap;
+
+use bls12_381_plus::group::Curve;
+use bls12_381_plus::G1Affine;
+use bls12_381_plus::G1Projective;
+use bls12_381_plus::Scalar;
+use identity_core::common::Timestamp;
+use jsonprooftoken::jpa::algs::ProofAlgorithm;
+use jsonprooftoken::jpt::payloads::Payloads;
+use jsonprooftoken::jwp::header::IssuerProtectedHeader;
+use jsonprooftoken::jwp::issued::JwpIssuedBuilder;
+use serde_json::Value;
+use zkryptium::bbsplus::ciphersuites::BbsCiphersuite;
+use zkryptium::bbsplus::ciphersuites::Bls12381Sha256;
+use zkryptium::bbsplus::ciphersuites::Bls12381Shake256;
+use zkryptium::utils::message::bbsplus_message::BBSplusMessage;
+
+use super::error;
+use super::JptHolderCommitment;
+use super::JptPredicateValue;
+use crate::error::Result;
+
+/// Values of predicate claims must be smaller than this in magnitude, which keeps every difference proven by a range
+/// proof within 64 bits.
+const MAX_MAGNITUDE: i64 = 1 << 62;
+
+/// The kind of value held by a predicate claim.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum ClaimKind {
+  /// A JSON integer.
+  Integer,
+  /// A JSON string holding an RFC 3339 timestamp with a precision of one second, or a full date.
+  Date,
+}
+
+impl ClaimKind {
+  /// Determines the kind of `value`, failing if it cannot be encoded as an integer.
+  fn of(value: &Value) -> Result<Self> {
+    let kind: Self = if value.is_string() { Self::Date } else { Self::Integer };
+    kind.decode(value).map(|_| kind)
+  }
+
+  /// The kind of integers compared to by `value`.
+  pub(crate) fn of_predicate_value(value: &JptPredicateValue) -> Self {
+    match value {
+      JptPredicateValue::Integer(_) => Self::Integer,
+      JptPredicateValue::Date(_) => Self::Date,
+    }
+  }
+
+  /// Decodes the integer represented by `value`, which is the number of seconds since the Unix epoch for dates.
+  pub(crate) fn decode(self, value: &Value) -> Result<i64> {
+    let integer: i64 = match self {
+      Self::Integer => value
+        .as_i64()
+        .ok_or_else(|| error(format!("`{value}` is not an integer")))?,
+      Self::Date => {
+        let date: &str = value
+          .as_str()
+          .ok_or_else(|| error(format!("`{value}` is not a date")))?;
+        parse_date(date).ok_or_else(|| error(format!("`{date}` is not a canonical RFC 3339 date")))?
+      }
+    };
+    check_magnitude(integer)?;
+    Ok(integer)
+  }
+
+  pub(crate) fn to_byte(self) -> u8 {
+    match self {
+      Self::Integer => 0,
+      Self::Date => 1,
+    }
+  }
+
+  pub(crate) fn from_byte(byte: u8) -> Result<Self> {
+    match byte {
+      0 => Ok(Self::Integer),
+      1 => Ok(Self::Date),
+      _ => Err(error(format!("unknown claim kind {byte}"))),
+    }
+  }
+}
+
+/// Parses either a full date (`2000-01-31`) or a timestamp (`2000-01-31T12:00:00Z`) in the form produced by
+/// [`Timestamp::to_rfc3339`], so that every date has a single representation.
+fn parse_date(date: &str) -> Option<i64> {
+  if date.len() == 10 {
+    let timestamp: Timestamp = Timestamp::parse(&format!("{date}T00:00:00Z")).ok()?;
+    timestamp.to_rfc3339().starts_with(date).then(|| timestamp.to_unix())
+  } else {
+    let timestamp: Timestamp = Timestamp::parse(date).ok()?;
+    (timestamp.to_rfc3339() == date).then(|| timestamp.to_unix())
+  }
+}
+
+/// Fails if `value` cannot be used in a predicate.
+pub(crate) fn check_magnitude(value: i64) -> Result<()> {
+  if value.checked_abs().map_or(false, |magnitude| magnitude < MAX_MAGNITUDE) {
+    Ok(())
+  } else {
+    Err(error(format!("{value} exceeds the range of predicate values")))
+  }
+}
+
+/// Encodes a signed integer as a scalar.
+pub(crate) fn encode_integer(value: i64) -> Scalar {
+  let magnitude: Scalar = Scalar::from(value.unsigned_abs());
+  if value < 0 {
+    -magnitude
+  } else {
+    magnitude
+  }
+}
+
+/// The claims of a JPT whose BBS messages are their integer values instead of hashes of their JSON values, indexed by
+/// their position in the Issuer Protected Header.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub(crate) struct IntegerClaims(BTreeMap<usize, ClaimKind>);
+
+impl IntegerClaims {
+  pub(crate) fn get(&self, index: usize) -> Option<ClaimKind> {
+    self.0.get(&index).copied()
+  }
+
+  /// The header signed by the issuer, which binds the kinds of the integer claims to the signature.
+  pub(crate) fn bbs_header(&self, issuer_header: &IssuerProtectedHeader) -> Result<Vec<u8>> {
+    let mut header: Vec<u8> =
+      serde_json::to_vec(issuer_header).map_err(|err| error(format!("invalid issuer protected header: {err}")))?;
+    header.extend(self.to_bytes());
+    Ok(header)
+  }
+
+  /// Maps the payloads at `indexes` to BBS messages.
+  pub(crate) fn messages<CS>(
+    &self,
+    payloads: &Payloads,
+    indexes: impl IntoIterator<Item = usize>,
+  ) -> Result<Vec<Scalar>>
+  where
+    CS: BbsCiphersuite,
+  {
+    indexes
+      .into_iter()
+      .map(|index| {
+        let (value, _) = payloads
+          .0
+          .get(index)
+          .ok_or_else(|| error(format!("missing payload {index}")))?;
+        match self.get(index) {
+          Some(kind) => kind.decode(value).map(encode_integer),
+          None => {
+            let bytes: Vec<u8> = serde_json::to_vec(value).map_err(|err| error(err.to_string()))?;
+            BBSplusMessage::map_message_to_scalar_as_hash::<CS>(&bytes, CS::API_ID)
+              .map(|message| message.value)
+              .map_err(|err| error(err.to_string()))
+          }
+        }
+      })
+      .collect()
+  }
+
+  pub(crate) fn to_bytes(&self) -> Vec<u8> {
+    let mut bytes: Vec<u8> = Vec::with_capacity(2 + 3 * self.0.len());
+    bytes.extend((self.0.len() as u16).to_be_bytes());
+    for (index, kind) in &self.0 {
+      bytes.extend((*index as u16).to_be_bytes());
+      bytes.push(kind.to_byte());
+    }
+    bytes
+  }
+
+  pub(crate) fn from_reader(reader: &mut Reader<'_>) -> Result<Self> {
+    let count: u16 = reader.u16()?;
+    let mut claims: BTreeMap<usize, ClaimKind> = BTreeMap::new();
+    for _ in 0..count {
+      let index: usize = reader.u16()? as usize;
+      let kind: ClaimKind = ClaimKind::from_byte(reader.u8()?)?;
+      if claims.insert(index, kind).is_some() {
+        return Err(error(format!("duplicate integer claim {index}")));
+      }
+    }
+    Ok(Self(claims))
+  }
+}
+
+/// The input to the BBS signature of an Issued JWP that allows predicates over some of its claims.
+///
+/// The BBS messages of the predicate claims are their integer values rather than hashes of their JSON values, and
+/// the indexes and kinds of these claims are appended to the signature to form the proof of the Issued JWP. The
+/// commitment to the secret of the holder, if any, is signed as well and appended last.
+#[derive(Clone, Debug)]
+pub struct JptSigningInput {
+  alg: ProofAlgorithm,
+  header: Vec<u8>,
+  messages: Vec<[u8; 32]>,
+  integer_claims: IntegerClaims,
+  holder_commitment: Option<G1Projective>,
+}
+
+impl JptSigningInput {
+  /// Encodes the payloads of `jwp_builder`, using the integer values of the `predicate_claims`.
+  ///
+  /// Predicate claims must hold JSON integers or dates formatted as by [`Timestamp::to_rfc3339`] or as full dates
+  /// (`2000-01-31`), smaller than 2^62 in magnitude.
+  pub fn new(jwp_builder: &JwpIssuedBuilder, predicate_claims: &[String]) -> Result<Self> {
+    let issuer_header: &IssuerProtectedHeader = jwp_builder
+      .get_issuer_protected_header()
+      .ok_or_else(|| error("missing issuer protected header"))?;
+    let payloads: &Payloads = jwp_builder.get_payloads().ok_or_else(|| error("missing payloads"))?;
+    let claims = issuer_header.claims().ok_or_else(|| error("missing claims"))?;
+
+    let mut integer_claims: IntegerClaims = IntegerClaims::default();
+    for claim in predicate_claims {
+      let index: usize = claims
+        .get_claim_index(claim.clone())
+        .ok_or_else(|| error(format!("claim `{claim}` not found")))?;
+      let kind: ClaimKind = ClaimKind::of(&payloads.0[index].0)?;
+      integer_claims.0.insert(index, kind);
+    }
+
+    let indexes = 0..payloads.0.len();
+    let messages: Vec<Scalar> = match issuer_header.alg() {
+      ProofAlgorithm::BLS12381_SHA256 => integer_claims.messages::<Bls12381Sha256>(payloads, indexes)?,
+      ProofAlgorithm::BLS12381_SHAKE256 => integer_claims.messages::<Bls12381Shake256>(payloads, indexes)?,
+      alg => return Err(error(format!("unsupported proof algorithm {alg}"))),
+    };
+
+    Ok(Self {
+      alg: issuer_header.alg(),
+      header: integer_claims.bbs_header(issuer_header)?,
+      messages: messages.iter().map(Scalar::to_be_bytes).collect(),
+      integer_claims,
+      holder_commitment: None,
+    })
+  }
+
+  /// Binds the Issued JWP to the secret of the holder committed to by `holder_commitment`, after verifying the proof of
+  /// knowledge of the secret.
+  pub fn holder_commitment(mut self, holder_commitment: &JptHolderCommitment) -> Result<Self> {
+    if holder_commitment.alg() != self.alg {
+      return Err(error(format!(
+        "the holder commitment is for algorithm {}, not {}",
+        holder_commitment.alg(),
+        self.alg
+      )));
+    }
+    self.holder_commitment = Some(holder_commitment.commitment()?);
+    Ok(self)
+  }
+
+  /// The header to be signed.
+  pub fn bbs_header(&self) -> &[u8] {
+    &self.header
+  }
+
+  /// The big-endian scalars to be signed as BBS messages, without hashing them.
+  pub fn bbs_messages(&self) -> &[[u8; 32]] {
+    &self.messages
+  }
+
+  /// The compressed commitment to the secret of the holder, to be signed blindly, if the Issued JWP is bound to one.
+  pub fn bbs_holder_commitment(&self) -> Option<Vec<u8>> {
+    self
+      .holder_commitment
+      .map(|commitment| commitment.to_affine().to_compressed().to_vec())
+  }
+
+  /// Appends the description of the predicate claims, and the commitment to the secret of the holder, to the BBS
+  /// `signature`, giving the proof of the Issued JWP.
+  pub fn into_proof(self, mut signature: Vec<u8>) -> Vec<u8> {
+    signature.extend(self.integer_claims.to_bytes());
+    signature.extend(self.bbs_holder_commitment().unwrap_or_default());
+    signature
+  }
+}
+
+/// Reads the binary encodings making up the proofs of JWPs with predicate claims.
+pub(crate) struct Reader<'a>(&'a [u8]);
+
+impl<'a> Reader<'a> {
+  pub(crate) fn new(bytes: &'a [u8]) -> Self {
+    Self(bytes)
+  }
+
+  pub(crate) fn take(&mut self, len: usize) -> Result<&'a [u8]> {
+    if self.0.len() < len {
+      return Err(error("unexpected end of proof"));
+    }
+    let (head, tail) = self.0.split_at(len);
+    self.0 = tail;
+    Ok(head)
+  }
+
+  pub(crate) fn u8(&mut self) -> Result<u8> {
+    self.take(1).map(|bytes| bytes[0])
+  }
+
+  pub(crate) fn u16(&mut self) -> Result<u16> {
+    self.take(2).map(|bytes| u16::from_be_bytes([bytes[0], bytes[1]]))
+  }
+
+  pub(crate) fn i64(&mut self) -> Result<i64> {
+    let bytes: [u8; 8] = self.take(8)?.try_into().expect("8 bytes");
+    Ok(i64::from_be_bytes(bytes))
+  }
+
+  pub(crate) fn scalar(&mut self) -> Result<Scalar> {
+    let bytes: &[u8; 32] = self.take(32)?.try_into().expect("32 bytes");
+    Option::from(Scalar::from_be_bytes(bytes)).ok_or_else(|| error("invalid scalar"))
+  }
+
+  pub(crate) fn point(&mut self) -> Result<G1Projective> {
+    let bytes: &[u8; 48] = self.take(48)?.try_into().expect("48 bytes");
+    Option::<G1Affine>::from(G1Affine::from_compressed(bytes))
+      .map(G1Projective::from)
+      .ok_or_else(|| error("invalid G1 point"))
+  }
+
+  pub(crate) fn is_empty(&self) -> bool {
+    self.0.is_empty()
+  }
+
+  pub(crate) fn finish(self) -> Result<()> {
+    if self.0.is_empty() {
+      Ok(())
+    } else {
+      Err(error("trailing bytes in proof"))
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use serde_json::json;
+
+  use super::*;
+
+  #[test]
+  fn claim_values_are_decoded() {
+    assert_eq!(ClaimKind::of(&json!(42)).unwrap(), ClaimKind::Integer);
+    assert_eq!(ClaimKind::Integer.decode(&json!(-7)).unwrap(), -7);
+    assert_eq!(ClaimKind::of(&json!("1970-01-02")).unwrap(), ClaimKind::Date);
+    assert_eq!(ClaimKind::Date.decode(&json!("1970-01-02")).unwrap(), 86400);
+    assert_eq!(ClaimKind::Date.decode(&json!("1970-01-01T00:01:00Z")).unwrap(), 60);
+  }
+
+  #[test]
+  fn non_canonical_values_are_rejected() {
+    assert!(ClaimKind::of(&json!(4.5)).is_err());
+    assert!(ClaimKind::of(&json!("18")).is_err());
+    assert!(ClaimKind::of(&json!("1970-1-2")).is_err());
+    assert!(ClaimKind::of(&json!("1970-01-01T00:01:00+00:00")).is_err());
+    assert!(ClaimKind::of(&json!(i64::MAX)).is_err());
+    assert!(ClaimKind::of(&json!(u64::MAX)).is_err());
+  }
+
+  #[test]
+  fn integers_are_encoded_as_signed_scalars() {
+    assert_eq!(encode_integer(5) + encode_integer(-5), Scalar::ZERO);
+    assert_eq!(encode_integer(-1) + Scalar::ONE, Scalar::ZERO);
+  }
+
+  #[test]
+  fn integer_claims_roundtrip() {
+    let claims: IntegerClaims = IntegerClaims([(3, ClaimKind::Integer), (7, ClaimKind::Date)].into_iter().collect());
+    let bytes: Vec<u8> = claims.to_bytes();
+    let mut reader: Reader<'_> = Reader::new(&bytes);
+    assert_eq!(IntegerClaims::from_reader(&mut reader).unwrap(), claims);
+    reader.finish().unwrap();
+  }
+}
@@ -0,0 +1,501 @@
+// Copyright 2020-2024 IOTA Stiftung, Fondazione Links
+// SPDX-License-Identifier: Apache-2.0
+
+//! Binding of JPTs to a secret of the holder, and pseudonyms derived from that secret for each verifier.
+//!
+//! The holder commits to a secret scalar `s` as `C = J * s` and proves knowledge of `s` to the issuer, who adds `C` to
+//! the BBS signature as an additional message with generator `J`. Presenting the credential then requires proving
+//! knowledge of `s`, which never leaves the key storage of the holder: the storage answers a
+//! [`JptHolderProofRequest`] with the response for `s` under the challenge of the BBS proof. Holders may also disclose
+//! the pseudonym `P * s`, where `P` is derived from the identifier of the verifier, which is the same in every
+//! presentation to that verifier and unlinkable across verifiers.
+//!
+//! This construction is specific to this crate and does **not** follow
+//! [draft-irtf-cfrg-bbs-blind-signatures](https://datatracker.ietf.org/doc/draft-irtf-cfrg-bbs-blind-signatures/) or
+//! [draft-irtf-cfrg-bbs-per-verifier-linkability](https://datatracker.ietf.org/doc/draft-irtf-cfrg-bbs-per-verifier-linkability/):
+//! the drafts need the secret in the memory of the prover, whereas here it stays in the key storage. The commitment
+//! uses its own generator rather than the blind generators of the drafts, the issuer signs it as an ordinary message
+//! rather than with `BlindSign`, and the pseudonym base and challenges use their own domain separation tags. JWPs
+//! bound to holders are therefore only issued, presented and verified by this crate.
+
+use bls12_381_plus::group::Curve;
+use bls12_381_plus::G1Projective;
+use bls12_381_plus::Scalar;
+use identity_verification::jose::jwu;
+use jsonprooftoken::jpa::algs::ProofAlgorithm;
+use serde::Deserialize;
+use serde::Serialize;
+use zkryptium::bbsplus::ciphersuites::BbsCiphersuite;
+use zkryptium::bbsplus::ciphersuites::Bls12381Sha256;
+use zkryptium::bbsplus::ciphersuites::Bls12381Shake256;
+use zkryptium::utils::util::bbsplus_utils::calculate_random_scalars;
+use zkryptium::utils::util::bbsplus_utils::hash_to_scalar;
+
+use super::bbs::G1_BYTES;
+use super::bbs::SCALAR_BYTES;
+use super::encoding::Reader;
+use crate::error::Error;
+use crate::error::Result;
+
+const GENERATOR_DST: &[u8] = b"JPT_HOLDER_GENERATOR_";
+const PSEUDONYM_DST: &[u8] = b"JPT_PSEUDONYM_";
+const NONCE_DST: &[u8] = b"JPT_HOLDER_NONCE_";
+const CHALLENGE_DST: &[u8] = b"JPT_HOLDER_CHALLENGE_";
+const COMMITMENT_DST: &[u8] = b"JPT_HOLDER_COMMITMENT_";
+
+fn error(message: impl Into<String>) -> Error {
+  Error::JptHolderBindingError(message.into())
+}
+
+/// The generator `J` of the commitments to holder secrets.
+pub(crate) fn holder_generator<CS>() -> G1Projective
+where
+  CS: BbsCiphersuite,
+{
+  G1Projective::hash::<CS::Expander>(b"holder_secret", &[CS::API_ID, GENERATOR_DST].concat())
+}
+
+/// The base of the pseudonyms of holders for the verifier identified by `verifier_id`.
+fn pseudonym_base<CS>(verifier_id: &str) -> G1Projective
+where
+  CS: BbsCiphersuite,
+{
+  G1Projective::hash::<CS::Expander>(verifier_id.as_bytes(), &[CS::API_ID, PSEUDONYM_DST].concat())
+}
+
+/// The challenge of a proof of knowledge of a holder secret, over the `announcements` for every base and the
+/// disclosed `outputs`, i.e. the products of the secret with the disclosed bases.
+fn holder_challenge<CS>(
+  challenge_input: &[u8],
+  announcements: &[G1Projective],
+  outputs: &[G1Projective],
+) -> Result<Scalar>
+where
+  CS: BbsCiphersuite,
+{
+  let mut input: Vec<u8> = challenge_input.to_vec();
+  for point in announcements.iter().chain(outputs) {
+    input.extend(point.to_affine().to_compressed());
+  }
+  hash_to_scalar::<CS>(&input, &[CS::API_ID, CHALLENGE_DST].concat()).map_err(|err| error(err.to_string()))
+}
+
+/// A request to prove knowledge of a holder secret, answered by the key storage holding the secret.
+///
+/// The proof is a Schnorr proof over one or more bases, whose challenge extends the given challenge input, e.g. the
+/// challenge input of a BBS proof in which the secret is an undisclosed message.
+#[derive(Clone, Debug)]
+pub struct JptHolderProofRequest {
+  alg: ProofAlgorithm,
+  /// The bases of the proof, and whether the product of the secret with each base is disclosed.
+  bases: Vec<(G1Projective, bool)>,
+  challenge_input: Vec<u8>,
+}
+
+impl JptHolderProofRequest {
+  /// Requests the proof in the presentation of a holder-bound JWP, disclosing the pseudonym for `verifier_id` if set.
+  pub(crate) fn presentation<CS>(alg: ProofAlgorithm, challenge_input: Vec<u8>, verifier_id: Option<&str>) -> Self
+  where
+    CS: BbsCiphersuite,
+  {
+    let mut bases: Vec<(G1Projective, bool)> = vec![(holder_generator::<CS>(), false)];
+    bases.extend(verifier_id.map(|verifier_id| (pseudonym_base::<CS>(verifier_id), true)));
+    Self {
+      alg,
+      bases,
+      challenge_input,
+    }
+  }
+
+  /// The algorithm of the JWPs bound to the secret, which determines the ciphersuite of the proof.
+  pub fn alg(&self) -> ProofAlgorithm {
+    self.alg
+  }
+
+  /// Proves knowledge of the holder secret, given as the big-endian encoding of the scalar `secret_key`.
+  pub fn prove(&self, secret_key: &[u8]) -> Result<Vec<u8>> {
+    let secret_key: &[u8; SCALAR_BYTES] = secret_key
+      .try_into()
+      .map_err(|_| error("invalid holder secret length"))?;
+    let secret: Scalar =
+      Option::from(Scalar::from_be_bytes(secret_key)).ok_or_else(|| error("invalid holder secret"))?;
+    match self.alg {
+      ProofAlgorithm::BLS12381_SHA256 => self.prove_with::<Bls12381Sha256>(secret),
+      ProofAlgorithm::BLS12381_SHAKE256 => self.prove_with::<Bls12381Shake256>(secret),
+      alg => Err(error(format!("unsupported proof algorithm {alg}"))),
+    }
+  }
+
+  fn prove_with<CS>(&self, secret: Scalar) -> Result<Vec<u8>>
+  where
+    CS: BbsCiphersuite,
+  {
+    // The nonce is derived from the secret and the whole challenge input, hedged with fresh randomness, so that
+    // different challenges never share a nonce.
+    let mut nonce_input: Vec<u8> = secret.to_be_bytes().to_vec();
+    nonce_input.extend(calculate_random_scalars(1)[0].to_be_bytes());
+    nonce_input.extend(&self.challenge_input);
+    for (base, _) in &self.bases {
+      nonce_input.extend(base.to_affine().to_compressed());
+    }
+    let nonce: Scalar =
+      hash_to_scalar::<CS>(&nonce_input, &[CS::API_ID, NONCE_DST].concat()).map_err(|err| error(err.to_string()))?;
+
+    let announcements: Vec<G1Projective> = self.bases.iter().map(|(base, _)| base * nonce).collect();
+    let outputs: Vec<G1Projective> = self
+      .bases
+      .iter()
+      .filter(|(_, disclosed)| *disclosed)
+      .map(|(base, _)| base * secret)
+      .collect();
+    let challenge: Scalar = holder_challenge::<CS>(&self.challenge_input, &announcements, &outputs)?;
+
+    Ok(
+      HolderProof {
+        announcements,
+        outputs,
+        challenge,
+        response: nonce + challenge * secret,
+      }
+      .to_bytes(),
+    )
+  }
+
+  /// Decodes the answer of the key storage to this request.
+  pub(crate) fn decode_proof(&self, proof: &[u8]) -> Result<HolderProof> {
+    let disclosed: usize = self.bases.iter().filter(|(_, disclosed)| *disclosed).count();
+    let mut reader: Reader<'_> = Reader::new(proof);
+    let holder_proof: HolderProof = HolderProof {
+      announcements: (0..self.bases.len()).map(|_| reader.point()).collect::<Result<_>>()?,
+      outputs: (0..disclosed).map(|_| reader.point()).collect::<Result<_>>()?,
+      challenge: reader.scalar()?,
+      response: reader.scalar()?,
+    };
+    reader.finish()?;
+    Ok(holder_proof)
+  }
+}
+
+/// The answer to a [`JptHolderProofRequest`].
+#[derive(Clone, Debug)]
+pub(crate) struct HolderProof {
+  pub(crate) announcements: Vec<G1Projective>,
+  pub(crate) outputs: Vec<G1Projective>,
+  pub(crate) challenge: Scalar,
+  pub(crate) response: Scalar,
+}
+
+impl HolderProof {
+  fn to_bytes(&self) -> Vec<u8> {
+    let mut bytes: Vec<u8> = Vec::new();
+    for point in self.announcements.iter().chain(&self.outputs) {
+      bytes.extend(point.to_affine().to_compressed());
+    }
+    bytes.extend(self.challenge.to_be_bytes());
+    bytes.extend(self.response.to_be_bytes());
+    bytes
+  }
+}
+
+/// The commitment of a holder to its secret, with a proof of knowledge of the secret bound to a nonce chosen by the
+/// issuer.
+///
+/// Holders create it with `JwpDocumentExt::create_jpt_holder_commitment` and send it to the issuer, who passes it to
+/// [`JwpCredentialOptions::holder_commitment`](crate::credential::JwpCredentialOptions::holder_commitment) after
+/// checking its [`nonce`](JptHolderCommitment::nonce).
+///
+/// Holder binding is experimental and has not been reviewed, so it must not be used in production.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct JptHolderCommitment {
+  alg: ProofAlgorithm,
+  nonce: String,
+  /// The commitment, followed by the challenge and response of the proof of knowledge, encoded in base64url.
+  value: String,
+}
+
+impl JptHolderCommitment {
+  /// The request to the key storage of the holder for a commitment for JWPs of algorithm `alg`, bound to `nonce`.
+  pub fn request(alg: ProofAlgorithm, nonce: &str) -> Result<JptHolderProofRequest> {
+    let generator: G1Projective = match alg {
+      ProofAlgorithm::BLS12381_SHA256 => holder_generator::<Bls12381Sha256>(),
+      ProofAlgorithm::BLS12381_SHAKE256 => holder_generator::<Bls12381Shake256>(),
+      alg => return Err(error(format!("unsupported proof algorithm {alg}"))),
+    };
+    Ok(JptHolderProofRequest {
+      alg,
+      bases: vec![(generator, true)],
+      challenge_input: commitment_challenge_input(nonce),
+    })
+  }
+
+  /// Creates the commitment from the answer of the key storage to the [`request`](JptHolderCommitment::request) for
+  /// `alg` and `nonce`.
+  pub fn new(alg: ProofAlgorithm, nonce: impl Into<String>, proof: &[u8]) -> Result<Self> {
+    let nonce: String = nonce.into();
+    let holder_proof: HolderProof = Self::request(alg, &nonce)?.decode_proof(proof)?;
+    let commitment: &G1Projective = holder_proof
+      .outputs
+      .first()
+      .ok_or_else(|| error("missing holder commitment"))?;
+    let mut value: Vec<u8> = commitment.to_affine().to_compressed().to_vec();
+    value.extend(holder_proof.challenge.to_be_bytes());
+    value.extend(holder_proof.response.to_be_bytes());
+
+    let commitment: Self = Self {
+      alg,
+      nonce,
+      value: jwu::encode_b64(value),
+    };
+    commitment.verify()?;
+    Ok(commitment)
+  }
+
+  /// The algorithm of the JWPs the commitment can be used for.
+  pub fn alg(&self) -> ProofAlgorithm {
+    self.alg
+  }
+
+  /// The nonce the proof of knowledge of the holder secret is bound to, which issuers must check.
+  pub fn nonce(&self) -> &str {
+    &self.nonce
+  }
+
+  /// Verifies the proof of knowledge of the holder secret.
+  pub fn verify(&self) -> Result<()> {
+    self.commitment().map(|_| ())
+  }
+
+  /// Verifies the proof of knowledge of the holder secret, returning the commitment to it.
+  pub(crate) fn commitment(&self) -> Result<G1Projective> {
+    match self.alg {
+      ProofAlgorithm::BLS12381_SHA256 => self.commitment_with::<Bls12381Sha256>(),
+      ProofAlgorithm::BLS12381_SHAKE256 => self.commitment_with::<Bls12381Shake256>(),
+      alg => Err(error(format!("unsupported proof algorithm {alg}"))),
+    }
+  }
+
+  fn commitment_with<CS>(&self) -> Result<G1Projective>
+  where
+    CS: BbsCiphersuite,
+  {
+    let bytes: Vec<u8> = jwu::decode_b64(&self.value).map_err(|_| error("invalid holder commitment encoding"))?;
+    let mut reader: Reader<'_> = Reader::new(&bytes);
+    let commitment: G1Projective = reader.point()?;
+    let challenge: Scalar = reader.scalar()?;
+    let response: Scalar = reader.scalar()?;
+    reader.finish()?;
+
+    let announcement: G1Projective = holder_generator::<CS>() * response - commitment * challenge;
+    let expected: Scalar =
+      holder_challenge::<CS>(&commitment_challenge_input(&self.nonce), &[announcement], &[commitment])?;
+    if bool::from(commitment.is_identity()) || expected != challenge {
+      return Err(error("invalid proof of knowledge of the holder secret"));
+    }
+    Ok(commitment)
+  }
+}
+
+fn commitment_challenge_input(nonce: &str) -> Vec<u8> {
+  let mut input: Vec<u8> = COMMITMENT_DST.to_vec();
+  input.extend(nonce.as_bytes());
+  input
+}
+
+/// The pseudonym of a holder for a verifier, disclosed in a holder-bound Presented JWP.
+///
+/// The pseudonym is the same in every presentation of credentials bound to the same holder secret to the verifier,
+/// and unlinkable to the pseudonyms of the holder for other verifiers.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct JptPseudonym {
+  verifier_id: String,
+  value: String,
+}
+
+impl JptPseudonym {
+  /// The identifier of the verifier the pseudonym was derived for.
+  pub fn verifier_id(&self) -> &str {
+    &self.verifier_id
+  }
+
+  /// The pseudonym, encoded in base64url.
+  pub fn value(&self) -> &str {
+    &self.value
+  }
+}
+
+/// The part of the proof of a Presented JWP proving knowledge of the holder secret:
+///
+/// ```text
+/// announcement, response, u8 has pseudonym, [u16 verifier id length, verifier id, pseudonym]
+/// ```
+#[derive(Clone, Debug)]
+pub(crate) struct HolderResponse {
+  /// The announcement for the holder secret in the BBS proof, `J * nonce`.
+  announcement: G1Projective,
+  response: Scalar,
+  pseudonym: Option<(String, G1Projective)>,
+}
+
+impl HolderResponse {
+  pub(crate) fn new(
+    request: &JptHolderProofRequest,
+    holder_proof: &HolderProof,
+    verifier_id: Option<&str>,
+  ) -> Result<Self> {
+    let disclosed: usize = request.bases.iter().filter(|(_, disclosed)| *disclosed).count();
+    if request.bases.len() != holder_proof.announcements.len()
+      || disclosed != holder_proof.outputs.len()
+      || disclosed != usize::from(verifier_id.is_some())
+    {
+      return Err(error("the holder proof does not match its request"));
+    }
+    let announcement: G1Projective = *holder_proof
+      .announcements
+      .first()
+      .ok_or_else(|| error("missing announcement for the holder secret"))?;
+    Ok(Self {
+      announcement,
+      response: holder_proof.response,
+      pseudonym: verifier_id
+        .zip(holder_proof.outputs.first())
+        .map(|(verifier_id, pseudonym)| (verifier_id.to_owned(), *pseudonym)),
+    })
+  }
+
+  /// The term `J * response - announcement` added to the second announcement of the BBS proof, so that it matches the
+  /// announcement the prover hashed, which does not include the holder secret.
+  pub(crate) fn announcement_term<CS>(&self) -> G1Projective
+  where
+    CS: BbsCiphersuite,
+  {
+    holder_generator::<CS>() * self.response - self.announcement
+  }
+
+  /// The challenge the BBS proof must carry, given its challenge input and the challenge it claims.
+  pub(crate) fn challenge<CS>(&self, challenge_input: &[u8], claimed: Scalar) -> Result<Scalar>
+  where
+    CS: BbsCiphersuite,
+  {
+    let mut announcements: Vec<G1Projective> = vec![self.announcement];
+    let mut outputs: Vec<G1Projective> = Vec::new();
+    if let Some((verifier_id, pseudonym)) = &self.pseudonym {
+      announcements.push(pseudonym_base::<CS>(verifier_id) * self.response - pseudonym * claimed);
+      outputs.push(*pseudonym);
+    }
+    holder_challenge::<CS>(challenge_input, &announcements, &outputs)
+  }
+
+  pub(crate) fn pseudonym(&self) -> Option<JptPseudonym> {
+    self.pseudonym.as_ref().map(|(verifier_id, pseudonym)| JptPseudonym {
+      verifier_id: verifier_id.clone(),
+      value: jwu::encode_b64(pseudonym.to_affine().to_compressed()),
+    })
+  }
+
+  pub(crate) fn to_bytes(&self) -> Vec<u8> {
+    let mut bytes: Vec<u8> = Vec::with_capacity(G1_BYTES + SCALAR_BYTES + 1);
+    bytes.extend(self.announcement.to_affine().to_compressed());
+    bytes.extend(self.response.to_be_bytes());
+    match &self.pseudonym {
+      None => bytes.push(0),
+      Some((verifier_id, pseudonym)) => {
+        bytes.push(1);
+        bytes.extend((verifier_id.len() as u16).to_be_bytes());
+        bytes.extend(verifier_id.as_bytes());
+        bytes.extend(pseudonym.to_affine().to_compressed());
+      }
+    }
+    bytes
+  }
+
+  pub(crate) fn from_reader(reader: &mut Reader<'_>) -> Result<Self> {
+    let announcement: G1Projective = reader.point()?;
+    let response: Scalar = reader.scalar()?;
+    let pseudonym: Option<(String, G1Projective)> = match reader.u8()? {
+      0 => None,
+      1 => {
+        let length: usize = reader.u16()? as usize;
+        let verifier_id: String =
+          String::from_utf8(reader.take(length)?.to_vec()).map_err(|_| error("invalid verifier identifier"))?;
+        Some((verifier_id, reader.point()?))
+      }
+      _ => return Err(error("invalid holder binding")),
+    };
+    Ok(Self {
+      announcement,
+      response,
+      pseudonym,
+    })
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use zkryptium::bbsplus::keys::BBSplusSecretKey;
+  use zkryptium::keys::pair::KeyPair;
+  use zkryptium::schemes::algorithms::BBSplus;
+
+  use super::*;
+
+  fn secret() -> BBSplusSecretKey {
+    KeyPair::<BBSplus<Bls12381Sha256>>::random().unwrap().into_parts().0
+  }
+
+  #[test]
+  fn holder_commitments_are_bound_to_nonce() {
+    let secret: BBSplusSecretKey = secret();
+    let request: JptHolderProofRequest =
+      JptHolderCommitment::request(ProofAlgorithm::BLS12381_SHA256, "nonce").unwrap();
+    let proof: Vec<u8> = request.prove(&secret.to_bytes()).unwrap();
+
+    let commitment: JptHolderCommitment =
+      JptHolderCommitment::new(ProofAlgorithm::BLS12381_SHA256, "nonce", &proof).unwrap();
+    assert_eq!(
+      commitment.commitment().unwrap(),
+      holder_generator::<Bls12381Sha256>() * secret.0
+    );
+    assert!(JptHolderCommitment::new(ProofAlgorithm::BLS12381_SHA256, "other", &proof).is_err());
+
+    let mut tampered: JptHolderCommitment = commitment.clone();
+    tampered.nonce = "other".to_owned();
+    assert!(tampered.verify().is_err());
+  }
+
+  #[test]
+  fn pseudonyms_depend_on_verifier_and_secret() {
+    let pseudonym = |secret: &BBSplusSecretKey, verifier_id: &str| {
+      let request: JptHolderProofRequest = JptHolderProofRequest::presentation::<Bls12381Sha256>(
+        ProofAlgorithm::BLS12381_SHA256,
+        b"input".to_vec(),
+        Some(verifier_id),
+      );
+      let proof: Vec<u8> = request.prove(&secret.to_bytes()).unwrap();
+      let holder_proof: HolderProof = request.decode_proof(&proof).unwrap();
+      let response: HolderResponse = HolderResponse::new(&request, &holder_proof, Some(verifier_id)).unwrap();
+      assert_eq!(
+        response
+          .challenge::<Bls12381Sha256>(b"input", holder_proof.challenge)
+          .unwrap(),
+        holder_proof.challenge
+      );
+      response.pseudonym().unwrap()
+    };
+
+    let (alice, bob) = (secret(), secret());
+    assert_eq!(pseudonym(&alice, "verifier"), pseudonym(&alice, "verifier"));
+    assert_ne!(pseudonym(&alice, "verifier"), pseudonym(&alice, "other"));
+    assert_ne!(pseudonym(&alice, "verifier"), pseudonym(&bob, "verifier"));
+  }
+
+  #[test]
+  fn holder_responses_must_match_their_request() {
+    let request: JptHolderProofRequest =
+      JptHolderProofRequest::presentation::<Bls12381Sha256>(ProofAlgorithm::BLS12381_SHA256, b"input".to_vec(), None);
+    let proof: Vec<u8> = request.prove(&secret().to_bytes()).unwrap();
+    let holder_proof: HolderProof = request.decode_proof(&proof).unwrap();
+
+    assert!(HolderResponse::new(&request, &holder_proof, None).is_ok());
+    assert!(HolderResponse::new(&request, &holder_proof, Some("verifier")).is_err());
+  }
+}
@@ -0,0 +1,44 @@
+// Copyright 2020-2024 IOTA Stiftung, Fondazione Links
+// SPDX-License-Identifier: Apache-2.0
+
+//! Predicates over the integer and date claims of JPTs, proven without disclosing the values of the claims.
+//!
+//! Issuers list the claims that holders may prove predicates over when creating the Issued JWP. The BBS messages of
+//! these claims are their integer values, which the holder commits to when presenting. The commitments are linked to
+//! the BBS proof of the Presented JWP, and each predicate is proven by a range proof over the difference between the
+//! committed value and the bound of the predicate.
+//!
+//! Credentials may also be bound to a secret of the holder, see [`JptHolderCommitment`], in which case presenting them
+//! requires the key storage of the holder to prove knowledge of the secret, optionally disclosing a pseudonym of the
+//! holder for the verifier.
+//!
+//! # Warning
+//!
+//! The proofs of this module are constructions of this crate that have not been reviewed, see [`bbs`]. They are only
+//! available with the `jpt-predicates-experimental` feature and must not be used in production.
+
+mod bbs;
+mod encoding;
+mod holder_binding;
+mod predicate;
+mod proof;
+mod range_proof;
+
+pub use self::encoding::JptSigningInput;
+pub use self::holder_binding::JptHolderCommitment;
+pub use self::holder_binding::JptHolderProofRequest;
+pub use self::holder_binding::JptPseudonym;
+pub use self::predicate::JptPredicate;
+pub use self::predicate::JptPredicateOperator;
+pub use self::predicate::JptPredicateValue;
+pub(crate) use self::proof::build_presented_jwp;
+pub(crate) use self::proof::has_extended_proof;
+pub(crate) use self::proof::verify_issued_jwp;
+pub(crate) use self::proof::verify_presented_jwp;
+pub use self::proof::JptHolderBoundPresentation;
+
+use crate::error::Error;
+
+fn error(message: impl Into<String>) -> Error {
+  Error::JptPredicateError(message.into())
+}
@@ -0,0 +1,166 @@
+// Copyright 2020-2024 IOTA Stiftung, Fondazione Links
+// SPDX-License-Identifier: Apache-2.0
+
+use std::fmt::Display;
+use std::fmt::Formatter;
+
+use identity_core::common::Timestamp;
+use serde::Deserialize;
+use serde::Serialize;
+
+/// A predicate over an integer or date claim of a JPT, e.g. `vc.credentialSubject.age >= 18`.
+///
+/// A holder proves a predicate without disclosing the value of the claim, which must have been listed by the issuer
+/// in [`JwpCredentialOptions::predicate_claims`](crate::credential::JwpCredentialOptions::predicate_claims).
+///
+/// Predicate proofs are experimental and have not been reviewed, so they must not be used in production.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct JptPredicate {
+  /// The name of the claim as listed in the Issuer Protected Header, e.g. `vc.credentialSubject.age`.
+  pub claim: String,
+  /// The comparison between the value of the claim and [`Self::value`].
+  #[serde(rename = "op")]
+  pub operator: JptPredicateOperator,
+  /// The value the claim is compared to.
+  pub value: JptPredicateValue,
+}
+
+impl JptPredicate {
+  /// Creates a new [`JptPredicate`] comparing `claim` to `value`.
+  pub fn new(claim: impl Into<String>, operator: JptPredicateOperator, value: impl Into<JptPredicateValue>) -> Self {
+    Self {
+      claim: claim.into(),
+      operator,
+      value: value.into(),
+    }
+  }
+
+  /// Creates a predicate stating that the value of `claim` is greater than `value`.
+  pub fn greater_than(claim: impl Into<String>, value: impl Into<JptPredicateValue>) -> Self {
+    Self::new(claim, JptPredicateOperator::GreaterThan, value)
+  }
+
+  /// Creates a predicate stating that the value of `claim` is greater than or equal to `value`.
+  pub fn greater_than_or_equal(claim: impl Into<String>, value: impl Into<JptPredicateValue>) -> Self {
+    Self::new(claim, JptPredicateOperator::GreaterThanOrEqual, value)
+  }
+
+  /// Creates a predicate stating that the value of `claim` is less than `value`.
+  pub fn less_than(claim: impl Into<String>, value: impl Into<JptPredicateValue>) -> Self {
+    Self::new(claim, JptPredicateOperator::LessThan, value)
+  }
+
+  /// Creates a predicate stating that the value of `claim` is less than or equal to `value`.
+  pub fn less_than_or_equal(claim: impl Into<String>, value: impl Into<JptPredicateValue>) -> Self {
+    Self::new(claim, JptPredicateOperator::LessThanOrEqual, value)
+  }
+
+  /// Returns whether `value` satisfies this predicate.
+  pub(crate) fn is_satisfied_by(&self, value: i64) -> bool {
+    let bound: i64 = self.value.to_i64();
+    match self.operator {
+      JptPredicateOperator::GreaterThan => value > bound,
+      JptPredicateOperator::GreaterThanOrEqual => value >= bound,
+      JptPredicateOperator::LessThan => value < bound,
+      JptPredicateOperator::LessThanOrEqual => value <= bound,
+    }
+  }
+}
+
+impl Display for JptPredicate {
+  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    write!(f, "{} {} {}", self.claim, self.operator, self.value)
+  }
+}
+
+/// The comparison made by a [`JptPredicate`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum JptPredicateOperator {
+  /// The claim is strictly greater than the value.
+  #[serde(rename = "gt")]
+  GreaterThan,
+  /// The claim is greater than or equal to the value.
+  #[serde(rename = "gte")]
+  GreaterThanOrEqual,
+  /// The claim is strictly less than the value.
+  #[serde(rename = "lt")]
+  LessThan,
+  /// The claim is less than or equal to the value.
+  #[serde(rename = "lte")]
+  LessThanOrEqual,
+}
+
+impl JptPredicateOperator {
+  pub(crate) fn to_byte(self) -> u8 {
+    match self {
+      Self::GreaterThan => 0,
+      Self::GreaterThanOrEqual => 1,
+      Self::LessThan => 2,
+      Self::LessThanOrEqual => 3,
+    }
+  }
+
+  pub(crate) fn from_byte(byte: u8) -> Option<Self> {
+    match byte {
+      0 => Some(Self::GreaterThan),
+      1 => Some(Self::GreaterThanOrEqual),
+      2 => Some(Self::LessThan),
+      3 => Some(Self::LessThanOrEqual),
+      _ => None,
+    }
+  }
+}
+
+impl Display for JptPredicateOperator {
+  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    f.write_str(match self {
+      Self::GreaterThan => ">",
+      Self::GreaterThanOrEqual => ">=",
+      Self::LessThan => "<",
+      Self::LessThanOrEqual => "<=",
+    })
+  }
+}
+
+/// The value a claim is compared to by a [`JptPredicate`].
+///
+/// Integers are compared to claims holding JSON integers, dates to claims holding RFC 3339 timestamps or full dates.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum JptPredicateValue {
+  /// An integer.
+  Integer(i64),
+  /// A date, compared with a precision of one second.
+  Date(Timestamp),
+}
+
+impl JptPredicateValue {
+  /// The integer proven against, which is the number of seconds since the Unix epoch for dates.
+  pub(crate) fn to_i64(self) -> i64 {
+    match self {
+      Self::Integer(value) => value,
+      Self::Date(timestamp) => timestamp.to_unix(),
+    }
+  }
+}
+
+impl Display for JptPredicateValue {
+  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    match self {
+      Self::Integer(value) => write!(f, "{value}"),
+      Self::Date(timestamp) => write!(f, "{timestamp}"),
+    }
+  }
+}
+
+impl From<i64> for JptPredicateValue {
+  fn from(value: i64) -> Self {
+    Self::Integer(value)
+  }
+}
+
+impl From<Timestamp> for JptPredicateValue {
+  fn from(value: Timestamp) -> Self {
+    Self::Date(value)
+  }
+}
@@ -0,0 +1,636 @@
+// Copyright 2020-2024 IOTA Stiftung, Fondazione Links
+// SPDX-License-Identifier: Apache-2.0
+
+//! The proofs of JWPs with predicate claims or bound to a holder secret.
+//!
+//! The proof of an Issued JWP is the BBS signature, followed by the encoded [`IntegerClaims`] and, if the JWP is bound
+//! to a holder secret, the commitment to the secret. The proof of a Presented JWP is the BBS proof, followed by the
+//! encoded [`IntegerClaims`], the claims committed to by the holder, each with the predicates proven over it, and, if
+//! the JWP is bound to a holder secret, the [`HolderResponse`]:
+//!
+//! ```text
+//! u16 count
+//! count * (u16 index, commitment, blinding response, u8 count, count * (u8 operator, u8 kind, i64 value, range proof))
+//! [holder response]
+//! ```
+
+use std::collections::BTreeMap;
+use std::collections::BTreeSet;
+
+use bls12_381_plus::group::Curve;
+use bls12_381_plus::G1Projective;
+use bls12_381_plus::Scalar;
+use identity_core::common::Timestamp;
+use identity_verification::jose::jwu;
+use jsonprooftoken::errors::CustomError;
+use jsonprooftoken::jpa::algs::PresentationProofAlgorithm;
+use jsonprooftoken::jpa::algs::ProofAlgorithm;
+use jsonprooftoken::jpt::claims::Claims;
+use jsonprooftoken::jpt::payloads::Payloads;
+use jsonprooftoken::jwk::alg_parameters::JwkAlgorithmParameters;
+use jsonprooftoken::jwk::curves::EllipticCurveTypes;
+use jsonprooftoken::jwk::key::Jwk;
+use jsonprooftoken::jwp::header::IssuerProtectedHeader;
+use jsonprooftoken::jwp::header::PresentationProtectedHeader;
+use jsonprooftoken::jwp::issued::JwpIssued;
+use jsonprooftoken::jwp::issued::JwpIssuedDecoder;
+use jsonprooftoken::jwp::presented::JwpPresented;
+use jsonprooftoken::jwp::presented::JwpPresentedBuilder;
+use jsonprooftoken::jwp::presented::JwpPresentedDecoder;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use zkryptium::bbsplus::ciphersuites::BbsCiphersuite;
+use zkryptium::bbsplus::ciphersuites::Bls12381Sha256;
+use zkryptium::bbsplus::ciphersuites::Bls12381Shake256;
+use zkryptium::bbsplus::keys::BBSplusPublicKey;
+use zkryptium::bbsplus::signature::BBSplusSignature;
+use zkryptium::utils::util::bbsplus_utils::calculate_random_scalars;
+
+use super::bbs;
+use super::bbs::BbsProof;
+use super::bbs::ProofInit;
+use super::encoding::check_magnitude;
+use super::encoding::encode_integer;
+use super::encoding::ClaimKind;
+use super::encoding::IntegerClaims;
+use super::encoding::Reader;
+use super::error;
+use super::holder_binding::HolderProof;
+use super::holder_binding::HolderResponse;
+use super::range_proof::PedersenGenerators;
+use super::range_proof::RangeProof;
+use super::JptHolderProofRequest;
+use super::JptPredicate;
+use super::JptPredicateOperator;
+use super::JptPredicateValue;
+use crate::error::Error;
+use crate::error::Result;
+use crate::validator::VerifiedProof;
+
+/// Whether the proof of an Issued JWP extends the BBS signature, with integer claims or a holder commitment.
+pub(crate) fn has_extended_proof(issued_proof: &[u8]) -> bool {
+  issued_proof.len() > BBSplusSignature::BYTES
+}
+
+/// Proves `predicates` over the claims of the Issued JWP in `jwp_builder`, concealing these claims, and builds the
+/// Presented JWP.
+pub(crate) fn build_presented_jwp(
+  jwp_builder: &mut JwpPresentedBuilder,
+  public_key: &Jwk,
+  predicates: &[JptPredicate],
+) -> Result<JwpPresented> {
+  let pending: PendingProof = PendingProof::new(jwp_builder, public_key, predicates)?;
+  if pending.holder_commitment.is_some() {
+    return Err(Error::JptHolderBindingError(
+      "the credential is bound to a holder secret, which must be proven by the key storage of the holder".to_owned(),
+    ));
+  }
+  let proof: Vec<u8> = match pending.alg {
+    ProofAlgorithm::BLS12381_SHA256 => pending.finish::<Bls12381Sha256>(None)?,
+    ProofAlgorithm::BLS12381_SHAKE256 => pending.finish::<Bls12381Shake256>(None)?,
+    alg => return Err(error(format!("unsupported proof algorithm {alg}"))),
+  };
+
+  jwp_builder
+    .build_with_proof(proof)
+    .map_err(|err| Error::JwpBuildingError(err.into()))
+}
+
+/// A Presented JWP bound to a holder secret, waiting for the proof of knowledge of the secret by the key storage of the
+/// holder.
+#[derive(Debug)]
+pub struct JptHolderBoundPresentation<'a> {
+  jwp_builder: &'a JwpPresentedBuilder,
+  pending: PendingProof,
+  request: JptHolderProofRequest,
+  verifier_id: Option<String>,
+}
+
+impl<'a> JptHolderBoundPresentation<'a> {
+  /// Proves `predicates` over the claims of the Issued JWP in `jwp_builder`, concealing these claims, and prepares the
+  /// proof of knowledge of the holder secret, disclosing the pseudonym of the holder for `verifier_id` if set.
+  pub(crate) fn new(
+    jwp_builder: &'a mut JwpPresentedBuilder,
+    public_key: &Jwk,
+    predicates: &[JptPredicate],
+    verifier_id: Option<&str>,
+  ) -> Result<Self> {
+    let pending: PendingProof = PendingProof::new(jwp_builder, public_key, predicates)?;
+    if pending.holder_commitment.is_none() {
+      return Err(Error::JptHolderBindingError(
+        "the credential is not bound to a holder secret".to_owned(),
+      ));
+    }
+    let challenge_input: Vec<u8> = pending.init.challenge_input().to_vec();
+    let request: JptHolderProofRequest = match pending.alg {
+      ProofAlgorithm::BLS12381_SHA256 => {
+        JptHolderProofRequest::presentation::<Bls12381Sha256>(pending.alg, challenge_input, verifier_id)
+      }
+      ProofAlgorithm::BLS12381_SHAKE256 => {
+        JptHolderProofRequest::presentation::<Bls12381Shake256>(pending.alg, challenge_input, verifier_id)
+      }
+      alg => return Err(error(format!("unsupported proof algorithm {alg}"))),
+    };
+
+    Ok(Self {
+      jwp_builder,
+      pending,
+      request,
+      verifier_id: verifier_id.map(ToOwned::to_owned),
+    })
+  }
+
+  /// The request for the proof of knowledge of the holder secret, to be answered by the key storage of the holder.
+  pub fn request(&self) -> &JptHolderProofRequest {
+    &self.request
+  }
+
+  /// Builds the Presented JWP, given the answer of the key storage of the holder to the
+  /// [`request`](JptHolderBoundPresentation::request).
+  pub fn build(self, holder_proof: &[u8]) -> Result<JwpPresented> {
+    let holder_proof: HolderProof = self.request.decode_proof(holder_proof)?;
+    let response: HolderResponse = HolderResponse::new(&self.request, &holder_proof, self.verifier_id.as_deref())?;
+    let holder: Option<(Scalar, HolderResponse)> = Some((holder_proof.challenge, response));
+    let proof: Vec<u8> = match self.pending.alg {
+      ProofAlgorithm::BLS12381_SHA256 => self.pending.finish::<Bls12381Sha256>(holder)?,
+      ProofAlgorithm::BLS12381_SHAKE256 => self.pending.finish::<Bls12381Shake256>(holder)?,
+      alg => return Err(error(format!("unsupported proof algorithm {alg}"))),
+    };
+
+    self
+      .jwp_builder
+      .build_with_proof(proof)
+      .map_err(|err| Error::JwpBuildingError(err.into()))
+  }
+}
+
+/// A claim committed to by the holder, with the predicates to prove over it.
+#[derive(Clone, Debug)]
+struct CommittedClaim {
+  index: usize,
+  value: i64,
+  commitment: G1Projective,
+  blinding: Scalar,
+  blinding_blinding: Scalar,
+  predicates: Vec<JptPredicate>,
+}
+
+/// The proof of a Presented JWP, up to the computation of the challenge.
+#[derive(Clone, Debug)]
+struct PendingProof {
+  alg: ProofAlgorithm,
+  init: ProofInit,
+  integer_claims: IntegerClaims,
+  committed: Vec<CommittedClaim>,
+  holder_commitment: Option<G1Projective>,
+}
+
+impl PendingProof {
+  fn new(jwp_builder: &mut JwpPresentedBuilder, public_key: &Jwk, predicates: &[JptPredicate]) -> Result<Self> {
+    let (signature, integer_claims, holder_commitment) = decode_issued_proof(jwp_builder.issuer_proof())?;
+    let claims: Claims = claims(jwp_builder.get_issuer_protected_header())?.clone();
+
+    let mut committed: BTreeMap<usize, Vec<JptPredicate>> = BTreeMap::new();
+    for predicate in predicates {
+      let index: usize = claims
+        .get_claim_index(predicate.claim.clone())
+        .ok_or_else(|| error(format!("claim `{}` not found", predicate.claim)))?;
+      let kind: ClaimKind = predicate_kind(&integer_claims, index, predicate)?;
+      if !predicate.is_satisfied_by(kind.decode(&jwp_builder.get_payloads().0[index].0)?) {
+        return Err(error(format!("predicate `{predicate}` is not satisfied")));
+      }
+      jwp_builder
+        .set_undisclosed(&predicate.claim)
+        .map_err(|err| Error::JwpBuildingError(err.into()))?;
+      committed.entry(index).or_default().push(predicate.clone());
+    }
+
+    let pk: BBSplusPublicKey = bbs_public_key(public_key)?;
+    let builder: &JwpPresentedBuilder = jwp_builder;
+    let init = match builder.get_issuer_protected_header().alg() {
+      ProofAlgorithm::BLS12381_SHA256 => Self::init::<Bls12381Sha256>,
+      ProofAlgorithm::BLS12381_SHAKE256 => Self::init::<Bls12381Shake256>,
+      alg => return Err(error(format!("unsupported proof algorithm {alg}"))),
+    };
+    init(builder, &pk, &signature, integer_claims, committed, holder_commitment)
+  }
+
+  /// Commits to the predicate claims and starts the BBS proof, sharing the blindings of the committed values with the
+  /// proofs of knowledge of their openings.
+  fn init<CS>(
+    jwp_builder: &JwpPresentedBuilder,
+    pk: &BBSplusPublicKey,
+    signature: &BBSplusSignature,
+    integer_claims: IntegerClaims,
+    committed: BTreeMap<usize, Vec<JptPredicate>>,
+    holder_commitment: Option<G1Projective>,
+  ) -> Result<Self>
+  where
+    CS: BbsCiphersuite,
+  {
+    let presentation_header: &PresentationProtectedHeader = jwp_builder
+      .get_presentation_protected_header()
+      .ok_or_else(|| error("missing presentation protected header"))?;
+    let header: Vec<u8> = integer_claims.bbs_header(jwp_builder.get_issuer_protected_header())?;
+    let mut linked_header: Vec<u8> = to_json_vec(presentation_header)?;
+
+    let payloads: &Payloads = jwp_builder.get_payloads();
+    let messages: Vec<Scalar> = integer_claims.messages::<CS>(payloads, 0..payloads.0.len())?;
+    let disclosed: BTreeSet<usize> = payloads.get_disclosed_indexes().into_iter().collect();
+    let generators: PedersenGenerators = PedersenGenerators::new::<CS>();
+
+    let random: Vec<Scalar> = calculate_random_scalars(3 * committed.len());
+    let mut committed_claims: Vec<CommittedClaim> = Vec::with_capacity(committed.len());
+    let mut blindings: BTreeMap<usize, Scalar> = BTreeMap::new();
+    for ((index, predicates), random) in committed.into_iter().zip(random.chunks_exact(3)) {
+      let (blinding, message_blinding, blinding_blinding) = (random[0], random[1], random[2]);
+      let kind: ClaimKind = integer_claims.get(index).expect("checked when committing");
+      let commitment: G1Projective = generators.commit(messages[index], blinding);
+      let announcement: G1Projective = generators.commit(message_blinding, blinding_blinding);
+      link_commitment(&mut linked_header, index, &commitment, &announcement);
+      blindings.insert(index, message_blinding);
+      committed_claims.push(CommittedClaim {
+        index,
+        value: kind.decode(&payloads.0[index].0)?,
+        commitment,
+        blinding,
+        blinding_blinding,
+        predicates,
+      });
+    }
+
+    let init: ProofInit = bbs::init_proof::<CS>(
+      pk,
+      signature,
+      &header,
+      &linked_header,
+      &messages,
+      &disclosed,
+      &blindings,
+      holder_commitment.as_ref(),
+    )?;
+
+    Ok(Self {
+      alg: jwp_builder.get_issuer_protected_header().alg(),
+      init,
+      integer_claims,
+      committed: committed_claims,
+      holder_commitment,
+    })
+  }
+
+  /// Completes the proof, with the challenge and response of the proof of knowledge of the holder secret if the JWP
+  /// is bound to one.
+  fn finish<CS>(self, holder: Option<(Scalar, HolderResponse)>) -> Result<Vec<u8>>
+  where
+    CS: BbsCiphersuite,
+  {
+    let challenge: Scalar = match &holder {
+      Some((challenge, _)) => *challenge,
+      None => bbs::challenge::<CS>(self.init.challenge_input())?,
+    };
+    let generators: PedersenGenerators = PedersenGenerators::new::<CS>();
+
+    let mut proof: Vec<u8> = self.init.finish(challenge).to_bytes();
+    proof.extend(self.integer_claims.to_bytes());
+    proof.extend((self.committed.len() as u16).to_be_bytes());
+    for claim in &self.committed {
+      proof.extend((claim.index as u16).to_be_bytes());
+      proof.extend(claim.commitment.to_affine().to_compressed());
+      proof.extend((claim.blinding_blinding + claim.blinding * challenge).to_be_bytes());
+      proof.push(claim.predicates.len() as u8);
+      for predicate in &claim.predicates {
+        let statement: Statement = Statement::new(predicate);
+        let encoded: [u8; 10] = encode_predicate(predicate);
+        let range_proof: RangeProof = RangeProof::prove::<CS>(
+          &generators,
+          &statement.commitment(&generators, &claim.commitment),
+          statement.value(claim.value),
+          statement.blinding(claim.blinding),
+          &range_proof_context(claim.index, &encoded),
+        )?;
+        proof.extend(encoded);
+        proof.extend(range_proof.to_bytes());
+      }
+    }
+    if let Some((_, response)) = holder {
+      proof.extend(response.to_bytes());
+    }
+
+    Ok(proof)
+  }
+}
+
+/// Verifies the proof of a decoded Issued JWP, which may describe integer claims or a holder commitment.
+pub(crate) fn verify_issued_jwp(decoded: &JwpIssuedDecoder, public_key: &Jwk) -> Result<JwpIssued, CustomError> {
+  let issued: JwpIssued = transcode(decoded)?;
+  if !has_extended_proof(issued.get_proof()) {
+    return decoded.verify(public_key);
+  }
+
+  let verify = || -> Result<()> {
+    let (signature, integer_claims, holder_commitment) = decode_issued_proof(issued.get_proof())?;
+    let pk: BBSplusPublicKey = bbs_public_key(public_key)?;
+    let header: Vec<u8> = integer_claims.bbs_header(issued.get_issuer_protected_header())?;
+    let payloads: &Payloads = issued.get_payloads();
+    let indexes = 0..payloads.0.len();
+    let holder_commitment: Option<&G1Projective> = holder_commitment.as_ref();
+    match issued.get_issuer_protected_header().alg() {
+      ProofAlgorithm::BLS12381_SHA256 => {
+        let messages: Vec<Scalar> = integer_claims.messages::<Bls12381Sha256>(payloads, indexes)?;
+        bbs::verify_signature::<Bls12381Sha256>(&pk, &signature, &header, &messages, holder_commitment)
+      }
+      ProofAlgorithm::BLS12381_SHAKE256 => {
+        let messages: Vec<Scalar> = integer_claims.messages::<Bls12381Shake256>(payloads, indexes)?;
+        bbs::verify_signature::<Bls12381Shake256>(&pk, &signature, &header, &messages, holder_commitment)
+      }
+      alg => Err(error(format!("unsupported proof algorithm {alg}"))),
+    }
+  };
+  verify().map_err(verification_error)?;
+
+  Ok(issued)
+}
+
+/// Verifies the proof of a decoded Presented JWP.
+pub(crate) fn verify_presented_jwp(
+  decoded: &JwpPresentedDecoder,
+  public_key: &Jwk,
+) -> Result<(JwpPresented, VerifiedProof), CustomError> {
+  let presented: JwpPresented = transcode(decoded)?;
+  let undisclosed: usize = presented.get_payloads().get_undisclosed_indexes().len();
+  if presented.get_proof().len() == BbsProof::byte_len(undisclosed) {
+    return decoded
+      .verify(public_key)
+      .map(|presented| (presented, VerifiedProof::default()));
+  }
+
+  let verify = || -> Result<VerifiedProof> {
+    let pk: BBSplusPublicKey = bbs_public_key(public_key)?;
+    let alg: ProofAlgorithm = presented.get_issuer_protected_header().alg();
+    let presentation_alg: PresentationProofAlgorithm = alg.into();
+    if presented.get_presentation_protected_header().alg() != presentation_alg {
+      return Err(error("the presentation proof algorithm does not match the issuer's"));
+    }
+    match alg {
+      ProofAlgorithm::BLS12381_SHA256 => verify_extended_proof::<Bls12381Sha256>(&presented, &pk),
+      ProofAlgorithm::BLS12381_SHAKE256 => verify_extended_proof::<Bls12381Shake256>(&presented, &pk),
+      alg => Err(error(format!("unsupported proof algorithm {alg}"))),
+    }
+  };
+  let verified: VerifiedProof = verify().map_err(verification_error)?;
+
+  Ok((presented, verified))
+}
+
+fn verify_extended_proof<CS>(presented: &JwpPresented, pk: &BBSplusPublicKey) -> Result<VerifiedProof>
+where
+  CS: BbsCiphersuite,
+{
+  let payloads: &Payloads = presented.get_payloads();
+  let undisclosed: Vec<usize> = payloads.get_undisclosed_indexes();
+  let claims: &Claims = claims(presented.get_issuer_protected_header())?;
+  let generators: PedersenGenerators = PedersenGenerators::new::<CS>();
+
+  let mut reader: Reader<'_> = Reader::new(presented.get_proof());
+  let bbs_proof: BbsProof = BbsProof::from_reader(&mut reader, undisclosed.len())?;
+  let integer_claims: IntegerClaims = IntegerClaims::from_reader(&mut reader)?;
+  let header: Vec<u8> = integer_claims.bbs_header(presented.get_issuer_protected_header())?;
+  let mut linked_header: Vec<u8> = to_json_vec(presented.get_presentation_protected_header())?;
+  let challenge: Scalar = bbs_proof.challenge();
+
+  let mut statements: Vec<(usize, JptPredicate, G1Projective, RangeProof)> = Vec::new();
+  let mut previous_index: Option<usize> = None;
+  for _ in 0..reader.u16()? {
+    let index: usize = reader.u16()? as usize;
+    if previous_index.map_or(false, |previous| previous >= index) {
+      return Err(error("committed claims are not ordered"));
+    }
+    previous_index = Some(index);
+
+    let position: usize = undisclosed
+      .iter()
+      .position(|&undisclosed| undisclosed == index)
+      .ok_or_else(|| error(format!("committed claim {index} is disclosed")))?;
+    let kind: ClaimKind = integer_claims
+      .get(index)
+      .ok_or_else(|| error(format!("committed claim {index} is not an integer claim")))?;
+    let commitment: G1Projective = reader.point()?;
+    let blinding_response: Scalar = reader.scalar()?;
+    let message_response: Scalar = bbs_proof
+      .m_cap(position)
+      .ok_or_else(|| error(format!("the proof has no response for committed claim {index}")))?;
+    let announcement: G1Projective = generators.commit(message_response, blinding_response) - commitment * challenge;
+    link_commitment(&mut linked_header, index, &commitment, &announcement);
+
+    let claim: &str = claims
+      .0
+      .get(index)
+      .ok_or_else(|| error(format!("committed claim {index} does not exist")))?;
+    for _ in 0..reader.u8()? {
+      let predicate: JptPredicate = decode_predicate(&mut reader, claim)?;
+      if ClaimKind::of_predicate_value(&predicate.value) != kind {
+        return Err(error(format!(
+          "predicate `{predicate}` compares values of different kinds"
+        )));
+      }
+      let range_proof: RangeProof = RangeProof::from_reader(&mut reader)?;
+      statements.push((index, predicate, commitment, range_proof));
+    }
+  }
+  let holder: Option<HolderResponse> = if reader.is_empty() {
+    None
+  } else {
+    Some(HolderResponse::from_reader(&mut reader)?)
+  };
+  reader.finish()?;
+
+  let disclosed_indexes: Vec<usize> = payloads.get_disclosed_indexes();
+  let disclosed_messages: Vec<Scalar> = integer_claims.messages::<CS>(payloads, disclosed_indexes.iter().copied())?;
+  let disclosed: BTreeMap<usize, Scalar> = disclosed_indexes.into_iter().zip(disclosed_messages).collect();
+  bbs::verify_proof::<CS>(
+    pk,
+    &bbs_proof,
+    &header,
+    &linked_header,
+    payloads.0.len(),
+    &disclosed,
+    holder.as_ref(),
+  )?;
+
+  let predicates: Vec<JptPredicate> = statements
+    .into_iter()
+    .map(|(index, predicate, commitment, range_proof)| {
+      let statement: Statement = Statement::new(&predicate);
+      range_proof
+        .verify::<CS>(
+          &generators,
+          &statement.commitment(&generators, &commitment),
+          &range_proof_context(index, &encode_predicate(&predicate)),
+        )
+        .map_err(|_| error(format!("invalid proof of predicate `{predicate}`")))?;
+      Ok(predicate)
+    })
+    .collect::<Result<_>>()?;
+
+  Ok(VerifiedProof {
+    predicates,
+    holder_bound: holder.is_some(),
+    pseudonym: holder.as_ref().and_then(HolderResponse::pseudonym),
+  })
+}
+
+/// The affine map `x = ±value + offset` taking the value of a claim to the integer that is non-negative exactly when
+/// the predicate holds.
+struct Statement {
+  negate: bool,
+  offset: i64,
+}
+
+impl Statement {
+  fn new(predicate: &JptPredicate) -> Self {
+    let bound: i64 = predicate.value.to_i64();
+    match predicate.operator {
+      JptPredicateOperator::GreaterThanOrEqual => Self {
+        negate: false,
+        offset: -bound,
+      },
+      JptPredicateOperator::GreaterThan => Self {
+        negate: false,
+        offset: -bound - 1,
+      },
+      JptPredicateOperator::LessThanOrEqual => Self {
+        negate: true,
+        offset: bound,
+      },
+      JptPredicateOperator::LessThan => Self {
+        negate: true,
+        offset: bound - 1,
+      },
+    }
+  }
+
+  fn value(&self, value: i64) -> u64 {
+    let signed: i64 = if self.negate { -value } else { value };
+    (signed + self.offset) as u64
+  }
+
+  fn blinding(&self, blinding: Scalar) -> Scalar {
+    if self.negate {
+      -blinding
+    } else {
+      blinding
+    }
+  }
+
+  fn commitment(&self, generators: &PedersenGenerators, commitment: &G1Projective) -> G1Projective {
+    let signed: G1Projective = if self.negate { -commitment } else { *commitment };
+    signed + generators.g * encode_integer(self.offset)
+  }
+}
+
+/// Binds a commitment to a predicate claim, and the announcement of the proof of knowledge of its opening, to the BBS
+/// proof through its presentation header.
+fn link_commitment(
+  presentation_header: &mut Vec<u8>,
+  index: usize,
+  commitment: &G1Projective,
+  announcement: &G1Projective,
+) {
+  presentation_header.extend((index as u16).to_be_bytes());
+  presentation_header.extend(commitment.to_affine().to_compressed());
+  presentation_header.extend(announcement.to_affine().to_compressed());
+}
+
+fn range_proof_context(index: usize, encoded_predicate: &[u8]) -> Vec<u8> {
+  let mut context: Vec<u8> = (index as u16).to_be_bytes().to_vec();
+  context.extend(encoded_predicate);
+  context
+}
+
+fn encode_predicate(predicate: &JptPredicate) -> [u8; 10] {
+  let mut encoded: [u8; 10] = [0; 10];
+  encoded[0] = predicate.operator.to_byte();
+  encoded[1] = ClaimKind::of_predicate_value(&predicate.value).to_byte();
+  encoded[2..].copy_from_slice(&predicate.value.to_i64().to_be_bytes());
+  encoded
+}
+
+fn decode_predicate(reader: &mut Reader<'_>, claim: &str) -> Result<JptPredicate> {
+  let operator: JptPredicateOperator =
+    JptPredicateOperator::from_byte(reader.u8()?).ok_or_else(|| error("unknown predicate operator"))?;
+  let kind: ClaimKind = ClaimKind::from_byte(reader.u8()?)?;
+  let value: i64 = reader.i64()?;
+  check_magnitude(value)?;
+  let value: JptPredicateValue = match kind {
+    ClaimKind::Integer => JptPredicateValue::Integer(value),
+    ClaimKind::Date => JptPredicateValue::Date(Timestamp::from_unix(value).map_err(|err| error(err.to_string()))?),
+  };
+  Ok(JptPredicate::new(claim, operator, value))
+}
+
+/// The kind of the predicate claim at `index`, failing if `predicate` cannot be proven over it.
+fn predicate_kind(integer_claims: &IntegerClaims, index: usize, predicate: &JptPredicate) -> Result<ClaimKind> {
+  let kind: ClaimKind = integer_claims
+    .get(index)
+    .ok_or_else(|| error(format!("claim `{}` does not allow predicates", predicate.claim)))?;
+  if ClaimKind::of_predicate_value(&predicate.value) != kind {
+    return Err(error(format!(
+      "predicate `{predicate}` compares values of different kinds"
+    )));
+  }
+  check_magnitude(predicate.value.to_i64())?;
+  Ok(kind)
+}
+
+fn decode_issued_proof(proof: &[u8]) -> Result<(BBSplusSignature, IntegerClaims, Option<G1Projective>)> {
+  let mut reader: Reader<'_> = Reader::new(proof);
+  let signature: &[u8; BBSplusSignature::BYTES] = reader
+    .take(BBSplusSignature::BYTES)?
+    .try_into()
+    .expect("signature length");
+  let signature: BBSplusSignature = BBSplusSignature::from_bytes(signature).map_err(|err| error(err.to_string()))?;
+  let integer_claims: IntegerClaims = IntegerClaims::from_reader(&mut reader)?;
+  let holder_commitment: Option<G1Projective> = if reader.is_empty() { None } else { Some(reader.point()?) };
+  reader.finish()?;
+  Ok((signature, integer_claims, holder_commitment))
+}
+
+fn claims(issuer_header: &IssuerProtectedHeader) -> Result<&Claims> {
+  issuer_header.claims().ok_or_else(|| error("missing claims"))
+}
+
+/// Decodes the public key of a `BLS12381G2` JWK.
+fn bbs_public_key(jwk: &Jwk) -> Result<BBSplusPublicKey> {
+  let params = match &jwk.key_params {
+    JwkAlgorithmParameters::EllipticCurve(params) if params.crv == EllipticCurveTypes::BLS12381G2 => params,
+    _ => return Err(error("predicates require a BLS12381G2 key")),
+  };
+  let coordinate = |coordinate: &str| {
+    jwu::decode_b64(coordinate)
+      .ok()
+      .and_then(|bytes| bytes.try_into().ok())
+      .ok_or_else(|| error("invalid BLS12381G2 coordinate"))
+  };
+  BBSplusPublicKey::from_coordinates(&coordinate(&params.x)?, &coordinate(&params.y)?)
+    .map_err(|err| error(format!("invalid public key: {err}")))
+}
+
+fn to_json_vec<T: Serialize>(header: &T) -> Result<Vec<u8>> {
+  serde_json::to_vec(header).map_err(|err| error(err.to_string()))
+}
+
+/// Converts a decoded JWP into the JWP it represents, as both share their serialization.
+fn transcode<T, U>(decoded: &T) -> Result<U, CustomError>
+where
+  T: Serialize,
+  U: DeserializeOwned,
+{
+  serde_json::to_value(decoded)
+    .and_then(serde_json::from_value)
+    .map_err(|_| CustomError::SerializationError)
+}
+
+fn verification_error(err: Error) -> CustomError {
+  CustomError::ProofVerificationError(err.to_string())
+}
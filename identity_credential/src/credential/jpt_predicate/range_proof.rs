@@ -0,0 +1,353 @@
+// Copyright 2020-2024 IOTA Stiftung, Fondazione Links
+// SPDX-License-Identifier: Apache-2.0
+
+//! Proofs that the value committed to by a Pedersen commitment lies in `[0, 2^64)`.
+//!
+//! The value is decomposed into bits, each committed to separately and proven to be either `0` or `1` by a
+//! Chaum-Pedersen OR proof. The commitment to the most significant bit is derived from the others, so that the bit
+//! commitments always add up to the commitment to the value.
+//!
+//! # Warning
+//!
+//! These range proofs are a construction of this crate that has not been reviewed by cryptographers, and like the
+//! rest of the predicate proofs they are only compiled with the `jpt-predicates-experimental` feature. They must not
+//! be relied on in production until they have been reviewed.
+
+use bls12_381_plus::group::Curve;
+use bls12_381_plus::G1Projective;
+use bls12_381_plus::Scalar;
+use zkryptium::bbsplus::ciphersuites::BbsCiphersuite;
+use zkryptium::utils::util::bbsplus_utils::calculate_random_scalars;
+use zkryptium::utils::util::bbsplus_utils::hash_to_scalar;
+
+use super::bbs::G1_BYTES;
+use super::bbs::SCALAR_BYTES;
+use super::encoding::Reader;
+use super::error;
+use crate::error::Result;
+
+const BITS: usize = 64;
+const GENERATOR_DST: &[u8] = b"JPT_PREDICATE_GENERATOR_";
+const CHALLENGE_DST: &[u8] = b"JPT_RANGE_PROOF_CHALLENGE_";
+
+/// The generators of the Pedersen commitments `g * value + h * blinding` to predicate claims.
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct PedersenGenerators {
+  pub(crate) g: G1Projective,
+  pub(crate) h: G1Projective,
+}
+
+impl PedersenGenerators {
+  pub(crate) fn new<CS>() -> Self
+  where
+    CS: BbsCiphersuite,
+  {
+    let dst: Vec<u8> = [CS::API_ID, GENERATOR_DST].concat();
+    Self {
+      g: G1Projective::hash::<CS::Expander>(b"g", &dst),
+      h: G1Projective::hash::<CS::Expander>(b"h", &dst),
+    }
+  }
+
+  pub(crate) fn commit(&self, value: Scalar, blinding: Scalar) -> G1Projective {
+    self.g * value + self.h * blinding
+  }
+}
+
+/// The proof that a single bit commitment opens to `0` or `1`.
+#[derive(Clone, Debug)]
+struct BitProof {
+  c0: Scalar,
+  z0: Scalar,
+  z1: Scalar,
+}
+
+/// A proof that a commitment opens to a value in `[0, 2^64)`.
+#[derive(Clone, Debug)]
+pub(crate) struct RangeProof {
+  /// The commitments to all bits but the most significant one.
+  commitments: Vec<G1Projective>,
+  bits: Vec<BitProof>,
+  challenge: Scalar,
+}
+
+impl RangeProof {
+  pub(crate) const BYTE_LEN: usize = SCALAR_BYTES + (BITS - 1) * G1_BYTES + BITS * 3 * SCALAR_BYTES;
+
+  /// Proves that `commitment = g * value + h * blinding` opens to `value`, binding the proof to `context`.
+  pub(crate) fn prove<CS>(
+    generators: &PedersenGenerators,
+    commitment: &G1Projective,
+    value: u64,
+    blinding: Scalar,
+    context: &[u8],
+  ) -> Result<Self>
+  where
+    CS: BbsCiphersuite,
+  {
+    let random: Vec<Scalar> = calculate_random_scalars(BITS - 1 + 3 * BITS);
+    let (bit_blindings, nonces) = random.split_at(BITS - 1);
+
+    let mut commitments: Vec<G1Projective> = Vec::with_capacity(BITS - 1);
+    let mut weighted_blindings: Scalar = Scalar::ZERO;
+    for (bit, bit_blinding) in bit_blindings.iter().enumerate() {
+      commitments.push(generators.commit(Scalar::from((value >> bit) & 1), *bit_blinding));
+      weighted_blindings += power_of_two(bit) * bit_blinding;
+    }
+    // Chosen so that the weighted bit commitments add up to `commitment`.
+    let last_blinding: Scalar = (blinding - weighted_blindings) * power_of_two(BITS - 1).invert().unwrap();
+    let all_commitments: Vec<G1Projective> = complete_commitments(commitment, &commitments);
+
+    // For each bit, the real branch uses a fresh nonce, while the other branch is simulated.
+    let mut announcements: Vec<(G1Projective, G1Projective)> = Vec::with_capacity(BITS);
+    let mut simulated: Vec<(Scalar, Scalar)> = Vec::with_capacity(BITS);
+    for (bit, nonce) in nonces.chunks_exact(3).enumerate() {
+      let (k, c_sim, z_sim) = (nonce[0], nonce[1], nonce[2]);
+      let is_one: bool = (value >> bit) & 1 == 1;
+      let real: G1Projective = generators.h * k;
+      let fake: G1Projective = generators.h * z_sim - branch_point(generators, &all_commitments[bit], is_one) * c_sim;
+      announcements.push(if is_one { (fake, real) } else { (real, fake) });
+      simulated.push((c_sim, z_sim));
+    }
+
+    let challenge: Scalar = challenge::<CS>(context, commitment, &all_commitments, &announcements)?;
+
+    let bits: Vec<BitProof> = (0..BITS)
+      .map(|bit| {
+        let bit_blinding: Scalar = bit_blindings.get(bit).copied().unwrap_or(last_blinding);
+        let k: Scalar = nonces[3 * bit];
+        let (c_sim, z_sim) = simulated[bit];
+        let c_real: Scalar = challenge - c_sim;
+        let z_real: Scalar = k + c_real * bit_blinding;
+        if (value >> bit) & 1 == 0 {
+          BitProof {
+            c0: c_real,
+            z0: z_real,
+            z1: z_sim,
+          }
+        } else {
+          BitProof {
+            c0: c_sim,
+            z0: z_sim,
+            z1: z_real,
+          }
+        }
+      })
+      .collect();
+
+    Ok(Self {
+      commitments,
+      bits,
+      challenge,
+    })
+  }
+
+  /// Verifies that `commitment` opens to a value in `[0, 2^64)`.
+  pub(crate) fn verify<CS>(
+    &self,
+    generators: &PedersenGenerators,
+    commitment: &G1Projective,
+    context: &[u8],
+  ) -> Result<()>
+  where
+    CS: BbsCiphersuite,
+  {
+    let all_commitments: Vec<G1Projective> = complete_commitments(commitment, &self.commitments);
+    let announcements: Vec<(G1Projective, G1Projective)> = self
+      .bits
+      .iter()
+      .zip(&all_commitments)
+      .map(|(proof, bit_commitment)| {
+        let c1: Scalar = self.challenge - proof.c0;
+        (
+          generators.h * proof.z0 - branch_point(generators, bit_commitment, true) * proof.c0,
+          generators.h * proof.z1 - branch_point(generators, bit_commitment, false) * c1,
+        )
+      })
+      .collect();
+
+    if challenge::<CS>(context, commitment, &all_commitments, &announcements)? == self.challenge {
+      Ok(())
+    } else {
+      Err(error("invalid range proof"))
+    }
+  }
+
+  pub(crate) fn to_bytes(&self) -> Vec<u8> {
+    let mut bytes: Vec<u8> = Vec::with_capacity(Self::BYTE_LEN);
+    bytes.extend(self.challenge.to_be_bytes());
+    for commitment in &self.commitments {
+      bytes.extend(commitment.to_affine().to_compressed());
+    }
+    for proof in &self.bits {
+      for scalar in [proof.c0, proof.z0, proof.z1] {
+        bytes.extend(scalar.to_be_bytes());
+      }
+    }
+    bytes
+  }
+
+  pub(crate) fn from_reader(reader: &mut Reader<'_>) -> Result<Self> {
+    let challenge: Scalar = reader.scalar()?;
+    let commitments: Vec<G1Projective> = (0..BITS - 1).map(|_| reader.point()).collect::<Result<_>>()?;
+    let bits: Vec<BitProof> = (0..BITS)
+      .map(|_| {
+        Ok(BitProof {
+          c0: reader.scalar()?,
+          z0: reader.scalar()?,
+          z1: reader.scalar()?,
+        })
+      })
+      .collect::<Result<_>>()?;
+    Ok(Self {
+      commitments,
+      bits,
+      challenge,
+    })
+  }
+}
+
+/// The point whose discrete logarithm to `h` is known if the bit committed to by `bit_commitment` is `0` (when
+/// `zero` is set) or `1`.
+fn branch_point(generators: &PedersenGenerators, bit_commitment: &G1Projective, zero: bool) -> G1Projective {
+  if zero {
+    *bit_commitment
+  } else {
+    bit_commitment - generators.g
+  }
+}
+
+/// Appends the commitment to the most significant bit, which is the remainder of `commitment`.
+fn complete_commitments(commitment: &G1Projective, commitments: &[G1Projective]) -> Vec<G1Projective> {
+  let weighted_sum: G1Projective = commitments
+    .iter()
+    .enumerate()
+    .fold(G1Projective::IDENTITY, |sum, (bit, commitment)| {
+      sum + commitment * power_of_two(bit)
+    });
+  let last: G1Projective = (commitment - weighted_sum) * power_of_two(BITS - 1).invert().unwrap();
+  commitments.iter().copied().chain([last]).collect()
+}
+
+fn power_of_two(exponent: usize) -> Scalar {
+  Scalar::from(1u64 << exponent)
+}
+
+fn challenge<CS>(
+  context: &[u8],
+  commitment: &G1Projective,
+  bit_commitments: &[G1Projective],
+  announcements: &[(G1Projective, G1Projective)],
+) -> Result<Scalar>
+where
+  CS: BbsCiphersuite,
+{
+  let mut input: Vec<u8> = context.to_vec();
+  let points = announcements
+    .iter()
+    .flat_map(|(a0, a1)| [a0, a1])
+    .chain(bit_commitments)
+    .chain([commitment]);
+  for point in points {
+    input.extend(point.to_affine().to_compressed());
+  }
+  hash_to_scalar::<CS>(&input, &[CS::API_ID, CHALLENGE_DST].concat()).map_err(|err| error(err.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+  use zkryptium::bbsplus::ciphersuites::Bls12381Sha256;
+
+  use super::*;
+
+  fn commit_and_prove(value: u64) -> (PedersenGenerators, G1Projective, RangeProof) {
+    let generators: PedersenGenerators = PedersenGenerators::new::<Bls12381Sha256>();
+    let blinding: Scalar = calculate_random_scalars(1)[0];
+    let commitment: G1Projective = generators.commit(Scalar::from(value), blinding);
+    let proof: RangeProof =
+      RangeProof::prove::<Bls12381Sha256>(&generators, &commitment, value, blinding, b"context").unwrap();
+    (generators, commitment, proof)
+  }
+
+  #[test]
+  fn range_proofs_are_verified() {
+    for value in [0, 1, 18, u64::MAX] {
+      let (generators, commitment, proof) = commit_and_prove(value);
+      proof
+        .verify::<Bls12381Sha256>(&generators, &commitment, b"context")
+        .unwrap();
+
+      let bytes: Vec<u8> = proof.to_bytes();
+      assert_eq!(bytes.len(), RangeProof::BYTE_LEN);
+      let mut reader: Reader<'_> = Reader::new(&bytes);
+      let decoded: RangeProof = RangeProof::from_reader(&mut reader).unwrap();
+      reader.finish().unwrap();
+      decoded
+        .verify::<Bls12381Sha256>(&generators, &commitment, b"context")
+        .unwrap();
+    }
+  }
+
+  #[test]
+  fn range_proofs_are_bound_to_commitment_and_context() {
+    let (generators, commitment, proof) = commit_and_prove(42);
+    assert!(proof
+      .verify::<Bls12381Sha256>(&generators, &commitment, b"other")
+      .is_err());
+    assert!(proof
+      .verify::<Bls12381Sha256>(&generators, &(commitment + generators.g), b"context")
+      .is_err());
+  }
+
+  #[test]
+  fn negative_values_cannot_be_proven() {
+    let generators: PedersenGenerators = PedersenGenerators::new::<Bls12381Sha256>();
+    let blinding: Scalar = calculate_random_scalars(1)[0];
+    // -1 in the scalar field, whose low 64 bits are all set.
+    let commitment: G1Projective = generators.commit(-Scalar::ONE, blinding);
+    let proof: RangeProof =
+      RangeProof::prove::<Bls12381Sha256>(&generators, &commitment, u64::MAX, blinding, b"context").unwrap();
+    assert!(proof
+      .verify::<Bls12381Sha256>(&generators, &commitment, b"context")
+      .is_err());
+  }
+
+  #[test]
+  fn values_just_outside_the_range_cannot_be_proven() {
+    let generators: PedersenGenerators = PedersenGenerators::new::<Bls12381Sha256>();
+    let blinding: Scalar = calculate_random_scalars(1)[0];
+    // 2^64, one past the largest value in the range.
+    let commitment: G1Projective = generators.commit(Scalar::from(u64::MAX) + Scalar::ONE, blinding);
+    for value in [0, u64::MAX] {
+      let proof: RangeProof =
+        RangeProof::prove::<Bls12381Sha256>(&generators, &commitment, value, blinding, b"context").unwrap();
+      assert!(proof
+        .verify::<Bls12381Sha256>(&generators, &commitment, b"context")
+        .is_err());
+    }
+  }
+
+  #[test]
+  fn forged_bit_commitments_are_rejected() {
+    let (generators, commitment, proof) = commit_and_prove(42);
+
+    // A commitment to `2` in place of the first bit, with the second bit lowered to keep the weighted sum intact.
+    let mut forged: RangeProof = proof.clone();
+    forged.commitments[0] += generators.g * Scalar::from(2u64);
+    forged.commitments[1] -= generators.g;
+    assert!(forged
+      .verify::<Bls12381Sha256>(&generators, &commitment, b"context")
+      .is_err());
+
+    // A bit commitment replaced in the serialized proof.
+    let mut bytes: Vec<u8> = proof.to_bytes();
+    let offset: usize = SCALAR_BYTES + 5 * G1_BYTES;
+    bytes[offset..offset + G1_BYTES]
+      .copy_from_slice(&generators.commit(Scalar::ONE, Scalar::ONE).to_affine().to_compressed());
+    let mut reader: Reader<'_> = Reader::new(&bytes);
+    let decoded: RangeProof = RangeProof::from_reader(&mut reader).unwrap();
+    assert!(decoded
+      .verify::<Bls12381Sha256>(&generators, &commitment, b"context")
+      .is_err());
+  }
+}
@@ -1,6 +1,9 @@
 // Copyright 2020-2024 IOTA Stiftung, Fondazione Links
 // SPDX-License-Identifier: Apache-2.0
 
+#[cfg(feature = "jpt-predicates-experimental")]
+use crate::credential::JptHolderCommitment;
+
 /// Options for creating a JSON Web Proof.
 #[non_exhaustive]
 #[derive(Debug, Default, serde::Serialize, serde::Deserialize, Eq, PartialEq, Clone)]
@@ -12,6 +15,23 @@ pub struct JwpCredentialOptions {
   /// If unset, the kid of the JWK with which the JWP is produced is used.
   #[serde(skip_serializing_if = "Option::is_none")]
   pub kid: Option<String>,
+
+  /// The claims over which holders may prove [`JptPredicate`](crate::credential::JptPredicate)s, e.g.
+  /// `vc.credentialSubject.age`.
+  ///
+  /// These claims must hold integers or dates, which are signed as integers rather than hashes. See
+  /// [`JptSigningInput`](crate::credential::JptSigningInput).
+  #[cfg(feature = "jpt-predicates-experimental")]
+  #[serde(skip_serializing_if = "Vec::is_empty")]
+  pub predicate_claims: Vec<String>,
+
+  /// The commitment to the secret of the holder the JWP is bound to.
+  ///
+  /// Issuers must check that the commitment is bound to a nonce they chose. See
+  /// [`JptHolderCommitment`](crate::credential::JptHolderCommitment).
+  #[cfg(feature = "jpt-predicates-experimental")]
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub holder_commitment: Option<JptHolderCommitment>,
 }
 
 impl JwpCredentialOptions {
@@ -25,4 +45,18 @@ impl JwpCredentialOptions {
     self.kid = Some(value.into());
     self
   }
+
+  /// Allows holders to prove predicates over `claim`.
+  #[cfg(feature = "jpt-predicates-experimental")]
+  pub fn predicate_claim(mut self, claim: impl Into<String>) -> Self {
+    self.predicate_claims.push(claim.into());
+    self
+  }
+
+  /// Binds the JWP to the secret of the holder committed to by `holder_commitment`.
+  #[cfg(feature = "jpt-predicates-experimental")]
+  pub fn holder_commitment(mut self, holder_commitment: JptHolderCommitment) -> Self {
+    self.holder_commitment = Some(holder_commitment);
+    self
+  }
 }
@@ -0,0 +1,133 @@
+// Copyright 2020-2024 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+use serde::Deserialize;
+use serde::Serialize;
+
+use identity_core::common::OneOrMany;
+
+/// A string value tagged with its language and, optionally, its base direction.
+///
+/// [More Info](https://www.w3.org/TR/vc-data-model-2.0/#language-and-base-direction)
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub struct LanguageValue {
+  /// The string value.
+  #[serde(rename = "@value")]
+  pub value: String,
+  /// The BCP47 language tag of `value`.
+  #[serde(rename = "@language", skip_serializing_if = "Option::is_none")]
+  pub language: Option<String>,
+  /// The base direction of `value`, either `ltr` or `rtl`.
+  #[serde(rename = "@direction", skip_serializing_if = "Option::is_none")]
+  pub direction: Option<String>,
+}
+
+impl LanguageValue {
+  /// Creates a new [`LanguageValue`] in the given `language`.
+  pub fn new(value: impl Into<String>, language: impl Into<String>) -> Self {
+    Self {
+      value: value.into(),
+      language: Some(language.into()),
+      direction: None,
+    }
+  }
+}
+
+/// A human-readable string such as the `name` or `description` of a
+/// [`CredentialV2`](crate::credential::CredentialV2), either as a plain string or as one or more
+/// [`LanguageValue`]s.
+///
+/// [More Info](https://www.w3.org/TR/vc-data-model-2.0/#names-and-descriptions)
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(untagged)]
+pub enum LocalizedString {
+  /// A string without language information.
+  Plain(String),
+  /// One or more language-tagged strings.
+  Localized(OneOrMany<LanguageValue>),
+}
+
+impl LocalizedString {
+  /// Returns the value for the given `language`.
+  ///
+  /// A [`LocalizedString::Plain`] value is returned for any `language`.
+  pub fn get(&self, language: &str) -> Option<&str> {
+    match self {
+      Self::Plain(value) => Some(value),
+      Self::Localized(values) => values
+        .iter()
+        .find(|value| {
+          value
+            .language
+            .as_deref()
+            .map(|tag| tag.eq_ignore_ascii_case(language))
+            .unwrap_or(false)
+        })
+        .map(|value| value.value.as_str()),
+    }
+  }
+
+  /// Iterates over all the values regardless of their language.
+  pub fn values(&self) -> impl Iterator<Item = &str> + '_ {
+    let values: Vec<&str> = match self {
+      Self::Plain(value) => vec![value.as_str()],
+      Self::Localized(values) => values.iter().map(|value| value.value.as_str()).collect(),
+    };
+    values.into_iter()
+  }
+}
+
+impl From<String> for LocalizedString {
+  fn from(value: String) -> Self {
+    Self::Plain(value)
+  }
+}
+
+impl From<&str> for LocalizedString {
+  fn from(value: &str) -> Self {
+    Self::Plain(value.to_owned())
+  }
+}
+
+impl From<LanguageValue> for LocalizedString {
+  fn from(value: LanguageValue) -> Self {
+    Self::Localized(OneOrMany::One(value))
+  }
+}
+
+impl From<Vec<LanguageValue>> for LocalizedString {
+  fn from(values: Vec<LanguageValue>) -> Self {
+    Self::Localized(values.into())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use identity_core::convert::FromJson;
+  use identity_core::convert::ToJson;
+
+  use super::*;
+
+  #[test]
+  fn plain_and_localized() {
+    let plain: LocalizedString = LocalizedString::from_json(r#""Example University""#).unwrap();
+    assert_eq!(plain.get("fr"), Some("Example University"));
+
+    let localized: LocalizedString = LocalizedString::from_json(
+      r#"[
+        { "@value": "Example University", "@language": "en" },
+        { "@value": "Université de Exemple", "@language": "fr-CA", "@direction": "ltr" }
+      ]"#,
+    )
+    .unwrap();
+    assert_eq!(localized.get("en"), Some("Example University"));
+    assert_eq!(localized.get("FR-ca"), Some("Université de Exemple"));
+    assert_eq!(localized.get("de"), None);
+    assert_eq!(localized.values().count(), 2);
+
+    let single: LocalizedString = LanguageValue::new("Beispiel", "de").into();
+    let json: String = single.to_json().unwrap();
+    assert_eq!(json, r#"{"@value":"Beispiel","@language":"de"}"#);
+    assert_eq!(LocalizedString::from_json(&json).unwrap(), single);
+  }
+}
@@ -6,11 +6,20 @@
 #![allow(clippy::module_inception)]
 
 mod builder;
+#[cfg(feature = "cose")]
+mod cose;
 mod credential;
+mod credential_v2;
+mod data_model_version;
+mod enveloped_credential;
 mod evidence;
 mod issuer;
 #[cfg(feature = "jpt-bbs-plus")]
 mod jpt;
+#[cfg(feature = "jpt-pq")]
+pub(crate) mod jpt_pq;
+#[cfg(feature = "jpt-predicates-experimental")]
+mod jpt_predicate;
 #[cfg(feature = "jpt-bbs-plus")]
 mod jwp_credential_options;
 mod jws;
@@ -18,6 +27,7 @@ mod jwt;
 mod jwt_serialization;
 mod linked_domain_service;
 mod linked_verifiable_presentation_service;
+mod localized_string;
 mod policy;
 mod proof;
 mod refresh;
@@ -28,11 +38,60 @@ mod status;
 mod subject;
 
 pub use self::builder::CredentialBuilder;
+#[cfg(feature = "cose")]
+pub use self::cose::Cose;
+#[cfg(feature = "cose")]
+pub use self::cose::VC_CONTENT_TYPE;
+#[cfg(feature = "cose")]
+pub use self::cose::VC_COSE_MEDIA_TYPE;
+#[cfg(feature = "cose")]
+pub use self::cose::VP_CONTENT_TYPE;
+#[cfg(feature = "cose")]
+pub use self::cose::VP_COSE_MEDIA_TYPE;
 pub use self::credential::Credential;
+pub(crate) use self::credential_v2::merge_custom_claims;
+#[cfg(feature = "validator")]
+pub(crate) use self::credential_v2::split_registered_claims;
+pub use self::credential_v2::CredentialV2;
+pub use self::credential_v2::VC_JWT_MEDIA_TYPE;
+pub use self::data_model_version::VcDataModelVersion;
+pub use self::enveloped_credential::EnvelopedCredential;
 pub use self::evidence::Evidence;
 pub use self::issuer::Issuer;
 #[cfg(feature = "jpt-bbs-plus")]
 pub use self::jpt::Jpt;
+#[cfg(feature = "jpt-pq")]
+pub use self::jpt_pq::pqc_issuer_protected_header;
+#[cfg(feature = "jpt-pq")]
+pub use self::jpt_pq::JptPqcSigningInput;
+#[cfg(feature = "jpt-pq")]
+pub use self::jpt_pq::JPT_PQC_ALGORITHM;
+#[cfg(feature = "jpt-pq")]
+pub use self::jpt_pq::JPT_PQC_TYPE;
+#[cfg(feature = "jpt-predicates-experimental")]
+pub(crate) use self::jpt_predicate::build_presented_jwp;
+#[cfg(feature = "jpt-predicates-experimental")]
+pub(crate) use self::jpt_predicate::has_extended_proof;
+#[cfg(feature = "jpt-predicates-experimental")]
+pub(crate) use self::jpt_predicate::verify_issued_jwp;
+#[cfg(feature = "jpt-predicates-experimental")]
+pub(crate) use self::jpt_predicate::verify_presented_jwp;
+#[cfg(feature = "jpt-predicates-experimental")]
+pub use self::jpt_predicate::JptHolderBoundPresentation;
+#[cfg(feature = "jpt-predicates-experimental")]
+pub use self::jpt_predicate::JptHolderCommitment;
+#[cfg(feature = "jpt-predicates-experimental")]
+pub use self::jpt_predicate::JptHolderProofRequest;
+#[cfg(feature = "jpt-predicates-experimental")]
+pub use self::jpt_predicate::JptPredicate;
+#[cfg(feature = "jpt-predicates-experimental")]
+pub use self::jpt_predicate::JptPredicateOperator;
+#[cfg(feature = "jpt-predicates-experimental")]
+pub use self::jpt_predicate::JptPredicateValue;
+#[cfg(feature = "jpt-predicates-experimental")]
+pub use self::jpt_predicate::JptPseudonym;
+#[cfg(feature = "jpt-predicates-experimental")]
+pub use self::jpt_predicate::JptSigningInput;
 #[cfg(feature = "jpt-bbs-plus")]
 pub use self::jwp_credential_options::JwpCredentialOptions;
 pub use self::jws::Jws;
@@ -43,6 +102,8 @@ pub(crate) use self::jwt_serialization::CredentialJwtClaims;
 pub(crate) use self::jwt_serialization::IssuanceDateClaims;
 pub use self::linked_domain_service::LinkedDomainService;
 pub use self::linked_verifiable_presentation_service::LinkedVerifiablePresentationService;
+pub use self::localized_string::LanguageValue;
+pub use self::localized_string::LocalizedString;
 pub use self::policy::Policy;
 pub use self::proof::Proof;
 pub use self::refresh::RefreshService;
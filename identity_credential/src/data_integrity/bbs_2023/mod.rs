@@ -0,0 +1,644 @@
+// Copyright 2020-2024 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+//! The selectively disclosable [`bbs-2023`](https://www.w3.org/TR/vc-di-bbs/) cryptosuite.
+//!
+//! The issuer signs every canonical statement of a credential, except those selected by its mandatory JSON pointers,
+//! as an individual BBS message, creating a base proof. The holder derives a proof revealing only the statements
+//! selected by additional JSON pointers of their choice, which can be verified without any interaction with the
+//! issuer.
+//!
+//! Blank node labels are replaced by HMAC based labels, so that the revealed statements do not leak the structure of
+//! the undisclosed parts of the credential. Credentials with blank nodes that cannot be identified from their compact
+//! form, e.g. those of lists, are not supported.
+
+mod proof_value;
+mod selection;
+
+use std::collections::BTreeMap;
+use std::collections::HashMap;
+use std::collections::HashSet;
+
+use crypto::hashes::sha::SHA256;
+use crypto::hashes::sha::SHA256_LEN;
+use crypto::macs::hmac::HMAC_SHA256;
+use identity_core::convert::Base;
+use identity_core::convert::BaseEncoding;
+use identity_core::convert::FromJson;
+use identity_core::convert::ToJson;
+use identity_verification::jwk::BlsCurve;
+use identity_verification::jwk::Jwk;
+use identity_verification::jwk::JwkParamsEc;
+use identity_verification::jwu;
+use jsonprooftoken::jpa::algs::ProofAlgorithm;
+use serde::Serialize;
+use serde_json::Value;
+use zkryptium::bbsplus::ciphersuites::Bls12381Sha256;
+use zkryptium::bbsplus::keys::BBSplusPublicKey;
+use zkryptium::schemes::algorithms::BBSplus;
+use zkryptium::schemes::generics::PoKSignature;
+use zkryptium::schemes::generics::Signature;
+
+use self::proof_value::BaseProofValue;
+use self::proof_value::DerivedProofValue;
+use self::selection::select_json_ld;
+use super::json_ld;
+use super::json_ld::Dataset;
+use super::proof::unsecured_document_value;
+use super::ContextCache;
+use super::Cryptosuite;
+use super::DataIntegrityProof;
+use crate::credential::Credential;
+use crate::credential::Proof;
+use crate::error::Error;
+use crate::error::Result;
+
+type Bbs = BBSplus<Bls12381Sha256>;
+
+/// The length of the key used to compute the HMAC based blank node labels.
+const HMAC_KEY_LEN: usize = 32;
+
+/// The data to be signed by the issuer of a credential to create a `bbs-2023` base proof.
+///
+/// The BBS signature over [`Self::bbs_messages`] and [`Self::bbs_header`] is created with a BLS12-381 G2 key using
+/// the `BLS12381-SHA256` ciphersuite, e.g. through `JwkStorageBbsPlusExt::sign_bbs`, and turned into the base proof
+/// with [`Self::into_proof`].
+///
+/// [More Info](https://www.w3.org/TR/vc-di-bbs/#create-base-proof-bbs-2023)
+#[derive(Clone, Debug)]
+pub struct Bbs2023SigningInput {
+  proof: DataIntegrityProof,
+  bbs_header: Vec<u8>,
+  bbs_messages: Vec<Vec<u8>>,
+  hmac_key: [u8; HMAC_KEY_LEN],
+  mandatory_pointers: Vec<String>,
+}
+
+impl Bbs2023SigningInput {
+  /// Computes the data to be signed for a base proof over `unsecured_document`, configured by `proof`.
+  ///
+  /// The statements selected by the JSON pointers in `mandatory_pointers`, e.g. `/issuer`, are disclosed in every
+  /// derived proof. Any `proof` property of `unsecured_document` is ignored.
+  ///
+  /// # Errors
+  /// Fails if `proof` is not a `bbs-2023` proof, if a JSON-LD context of the document is missing from
+  /// `context_cache` or if a pointer does not match the document.
+  pub fn new<S: Serialize + ?Sized>(
+    unsecured_document: &S,
+    proof: DataIntegrityProof,
+    mandatory_pointers: Vec<String>,
+    context_cache: &ContextCache,
+  ) -> Result<Self> {
+    let document: Value = unsecured_document_value(unsecured_document)?;
+    let mut hmac_key: [u8; HMAC_KEY_LEN] = [0; HMAC_KEY_LEN];
+    crypto::utils::rand::fill(&mut hmac_key).map_err(|err| error(err.to_string()))?;
+    Self::with_hmac_key(&document, proof, mandatory_pointers, hmac_key, context_cache)
+  }
+
+  fn with_hmac_key(
+    document: &Value,
+    proof: DataIntegrityProof,
+    mandatory_pointers: Vec<String>,
+    hmac_key: [u8; HMAC_KEY_LEN],
+    context_cache: &ContextCache,
+  ) -> Result<Self> {
+    if proof.cryptosuite != Cryptosuite::Bbs2023 {
+      return Err(error("expected a bbs-2023 proof"));
+    }
+    let proof_hash: [u8; SHA256_LEN] = proof_hash(&proof, document, context_cache)?;
+
+    let [mandatory]: [Group; 1] = canonicalize_and_group(
+      document,
+      |canonical_id_map| hmac_label_map(&hmac_key, canonical_id_map),
+      [mandatory_pointers.as_slice()],
+      context_cache,
+    )?
+    .groups;
+
+    Ok(Self {
+      proof,
+      bbs_header: [proof_hash, mandatory.hash()].concat(),
+      bbs_messages: mandatory.non_matching_messages(),
+      hmac_key,
+      mandatory_pointers,
+    })
+  }
+
+  /// Returns the BBS header to be signed, binding the proof configuration and the mandatory statements.
+  pub fn bbs_header(&self) -> &[u8] {
+    &self.bbs_header
+  }
+
+  /// Returns the BBS messages to be signed, i.e. the statements that can be selectively disclosed.
+  pub fn bbs_messages(&self) -> &[Vec<u8>] {
+    &self.bbs_messages
+  }
+
+  /// Creates the base proof from the `bbs_signature` over [`Self::bbs_messages`] and [`Self::bbs_header`].
+  ///
+  /// # Errors
+  /// Fails if `public_key` is not a `BLS12381-SHA256` key or if the signature cannot be verified with it.
+  pub fn into_proof(self, bbs_signature: &[u8], public_key: &Jwk) -> Result<DataIntegrityProof> {
+    let public_key: BBSplusPublicKey = bbs_public_key(public_key)?;
+    let signature: Signature<Bbs> = bbs_signature
+      .try_into()
+      .ok()
+      .and_then(|signature| Signature::<Bbs>::from_bytes(signature).ok())
+      .ok_or_else(|| error("invalid BBS signature"))?;
+    signature
+      .verify(
+        &public_key,
+        Some(self.bbs_messages.as_slice()),
+        Some(self.bbs_header.as_slice()),
+      )
+      .map_err(|err| error(format!("invalid BBS signature: {err}")))?;
+
+    let proof_value = BaseProofValue {
+      bbs_signature: bbs_signature.to_vec(),
+      bbs_header: self.bbs_header,
+      public_key: public_key.to_bytes().to_vec(),
+      hmac_key: self.hmac_key.to_vec(),
+      mandatory_pointers: self.mandatory_pointers,
+    };
+    let mut proof: DataIntegrityProof = self.proof;
+    proof.proof_value = Some(proof_value.encode()?);
+    Ok(proof)
+  }
+}
+
+/// Derives a credential secured with a `bbs-2023` derived proof from a `credential` secured with a `bbs-2023` base
+/// proof, disclosing its mandatory statements and the statements selected by `selective_pointers`.
+///
+/// The `presentation_header` is bound to the derived proof, e.g. to include a challenge of the verifier. The revealed
+/// credential must still contain all properties required by [`Credential`].
+///
+/// [More Info](https://www.w3.org/TR/vc-di-bbs/#add-derived-proof-bbs-2023)
+///
+/// # Errors
+/// Fails if the base proof cannot be verified, if a JSON-LD context of the credential is missing from
+/// `context_cache` or if a pointer does not match the credential.
+pub fn derive_bbs_2023_credential<T>(
+  credential: &Credential<T>,
+  selective_pointers: &[String],
+  presentation_header: &[u8],
+  context_cache: &ContextCache,
+) -> Result<Credential<T>>
+where
+  T: ToOwned<Owned = T> + Serialize + serde::de::DeserializeOwned,
+{
+  let proof: DataIntegrityProof = credential
+    .proof
+    .as_ref()
+    .ok_or_else(|| error("the credential has no proof"))
+    .and_then(DataIntegrityProof::try_from)?;
+  let document: Value = unsecured_document_value(credential)?;
+  let reveal_document: Value =
+    derive_document(&document, proof, selective_pointers, presentation_header, context_cache)?;
+  Credential::from_json_value(reveal_document).map_err(|err| {
+    error(format!(
+      "the disclosed statements do not form a valid credential, mandatory properties must be disclosed: {err}"
+    ))
+  })
+}
+
+/// Derives the reveal document of the unsecured `document` secured with the `bbs-2023` base `proof`, secured with a
+/// derived proof.
+fn derive_document(
+  document: &Value,
+  proof: DataIntegrityProof,
+  selective_pointers: &[String],
+  presentation_header: &[u8],
+  context_cache: &ContextCache,
+) -> Result<Value> {
+  if proof.cryptosuite != Cryptosuite::Bbs2023 {
+    return Err(error("expected a bbs-2023 proof"));
+  }
+  let base: BaseProofValue = BaseProofValue::decode(proof_value(&proof)?)?;
+  let public_key: BBSplusPublicKey =
+    BBSplusPublicKey::from_bytes(&base.public_key).map_err(|err| error(format!("invalid public key: {err}")))?;
+
+  let mut combined_pointers: Vec<String> = base.mandatory_pointers.clone();
+  for pointer in selective_pointers {
+    if !combined_pointers.contains(pointer) {
+      combined_pointers.push(pointer.clone());
+    }
+  }
+
+  let grouped: GroupedNQuads<3> = canonicalize_and_group(
+    document,
+    |canonical_id_map| hmac_label_map(&base.hmac_key, canonical_id_map),
+    [
+      base.mandatory_pointers.as_slice(),
+      selective_pointers,
+      combined_pointers.as_slice(),
+    ],
+    context_cache,
+  )?;
+  let [mandatory, selective, combined] = &grouped.groups;
+
+  // The credential must still match the statements signed by the issuer.
+  let bbs_messages: Vec<Vec<u8>> = mandatory.non_matching_messages();
+  if base.bbs_header.get(SHA256_LEN..) != Some(mandatory.hash().as_slice()) {
+    return Err(error("the mandatory statements do not match the base proof"));
+  }
+  Signature::<Bbs>::from_bytes(
+    base
+      .bbs_signature
+      .as_slice()
+      .try_into()
+      .map_err(|_| error("invalid BBS signature"))?,
+  )
+  .and_then(|signature| {
+    signature.verify(
+      &public_key,
+      Some(bbs_messages.as_slice()),
+      Some(base.bbs_header.as_slice()),
+    )
+  })
+  .map_err(|err| error(format!("invalid base proof: {err}")))?;
+
+  // Mandatory statements are referenced by their position among the revealed statements, selectively disclosed
+  // statements by their position among the signed messages.
+  let combined_indexes: Vec<usize> = combined.matching.keys().copied().collect();
+  let mandatory_indexes: Vec<usize> = mandatory
+    .matching
+    .keys()
+    .map(|index| {
+      combined_indexes
+        .binary_search(index)
+        .map_err(|_| error("a mandatory statement is not revealed"))
+    })
+    .collect::<Result<_>>()?;
+  let non_mandatory_indexes: Vec<usize> = mandatory.non_matching.keys().copied().collect();
+  let selective_indexes: Vec<usize> = selective
+    .matching
+    .keys()
+    .filter_map(|index| non_mandatory_indexes.binary_search(index).ok())
+    .collect();
+
+  let bbs_proof: Vec<u8> = PoKSignature::<Bbs>::proof_gen(
+    &public_key,
+    &base.bbs_signature,
+    Some(base.bbs_header.as_slice()),
+    Some(presentation_header),
+    Some(bbs_messages.as_slice()),
+    Some(selective_indexes.as_slice()),
+  )
+  .map_err(|err| error(format!("failed to derive the BBS proof: {err}")))?
+  .to_bytes();
+
+  // Associate the canonical labels of the revealed document with the labels of the issuer.
+  let revealed: &Dataset = combined
+    .dataset
+    .as_ref()
+    .ok_or_else(|| error("at least one statement must be disclosed"))?;
+  let label_map: BTreeMap<u64, u64> = revealed
+    .canonical_id_map()?
+    .into_iter()
+    .map(|(label, canonical_label)| {
+      let issuer_label: &str = grouped.label_map.get(&label).map(String::as_str).unwrap_or_default();
+      Ok((label_index(&canonical_label, "c14n")?, label_index(issuer_label, "b")?))
+    })
+    .collect::<Result<_>>()?;
+
+  let mut derived_proof: DataIntegrityProof = proof;
+  derived_proof.proof_value = Some(
+    DerivedProofValue {
+      bbs_proof,
+      label_map,
+      mandatory_indexes,
+      selective_indexes,
+      presentation_header: presentation_header.to_vec(),
+    }
+    .encode()?,
+  );
+
+  let mut reveal_document: Value =
+    select_json_ld(document, &combined_pointers)?.ok_or_else(|| error("at least one statement must be disclosed"))?;
+  reveal_document["proof"] = Proof::try_from(derived_proof)?.to_json_value().map_err(json_error)?;
+  Ok(reveal_document)
+}
+
+/// Verifies the `bbs-2023` derived `proof` of `unsecured_document` with the `public_key` of the issuer, returning the
+/// presentation header bound to the proof.
+///
+/// [More Info](https://www.w3.org/TR/vc-di-bbs/#verify-derived-proof-bbs-2023)
+pub(crate) fn verify_derived_proof<S: Serialize + ?Sized>(
+  unsecured_document: &S,
+  proof: &DataIntegrityProof,
+  public_key: &Jwk,
+  context_cache: &ContextCache,
+) -> Result<Vec<u8>> {
+  let public_key: BBSplusPublicKey = bbs_public_key(public_key)?;
+  let derived: DerivedProofValue = DerivedProofValue::decode(proof_value(proof)?)?;
+  let document: Value = unsecured_document_value(unsecured_document)?;
+  let proof_hash: [u8; SHA256_LEN] = proof_hash(proof, &document, context_cache)?;
+
+  let dataset: Dataset = Dataset::from_document(&document, context_cache)?;
+  let label_map: HashMap<String, String> = dataset
+    .canonical_id_map()?
+    .into_iter()
+    .map(|(label, canonical_label)| {
+      let index: u64 = label_index(&canonical_label, "c14n")?;
+      let issuer_index: &u64 = derived
+        .label_map
+        .get(&index)
+        .ok_or_else(|| error("the label map does not cover every blank node"))?;
+      Ok((label, format!("b{issuer_index}")))
+    })
+    .collect::<Result<_>>()?;
+  let nquads: Vec<String> = dataset.to_nquads(&label_map)?;
+
+  if derived.mandatory_indexes.iter().any(|index| *index >= nquads.len()) {
+    return Err(error("invalid mandatory index"));
+  }
+  let mut mandatory: Vec<&str> = Vec::new();
+  let mut bbs_messages: Vec<Vec<u8>> = Vec::new();
+  for (index, nquad) in nquads.iter().enumerate() {
+    if derived.mandatory_indexes.contains(&index) {
+      mandatory.push(nquad);
+    } else {
+      bbs_messages.push(nquad.as_bytes().to_vec());
+    }
+  }
+  if bbs_messages.len() != derived.selective_indexes.len() {
+    return Err(error("the number of disclosed statements does not match the proof"));
+  }
+  let bbs_header: Vec<u8> = [proof_hash, sha256(mandatory.concat().as_bytes())].concat();
+
+  PoKSignature::<Bbs>::from_bytes(&derived.bbs_proof)
+    .and_then(|bbs_proof| {
+      bbs_proof.proof_verify(
+        &public_key,
+        Some(bbs_messages.as_slice()),
+        Some(derived.selective_indexes.as_slice()),
+        Some(bbs_header.as_slice()),
+        Some(derived.presentation_header.as_slice()),
+      )
+    })
+    .map_err(|err| error(format!("invalid BBS proof: {err}")))?;
+
+  Ok(derived.presentation_header)
+}
+
+// ====================================================================================================================
+// Helpers
+// ====================================================================================================================
+
+/// The canonical N-Quads of a document, split according to a selection of the document.
+struct Group {
+  matching: BTreeMap<usize, String>,
+  non_matching: BTreeMap<usize, String>,
+  /// The dataset of the selection, whose blank nodes are labeled with the skolem identifiers of the document.
+  dataset: Option<Dataset>,
+}
+
+impl Group {
+  /// Returns the SHA-256 hash of the matching N-Quads.
+  fn hash(&self) -> [u8; SHA256_LEN] {
+    sha256(
+      self
+        .matching
+        .values()
+        .map(String::as_str)
+        .collect::<String>()
+        .as_bytes(),
+    )
+  }
+
+  fn non_matching_messages(&self) -> Vec<Vec<u8>> {
+    self
+      .non_matching
+      .values()
+      .map(|nquad| nquad.as_bytes().to_vec())
+      .collect()
+  }
+}
+
+struct GroupedNQuads<const N: usize> {
+  /// Maps the skolem identifiers of the blank nodes of the document to their final labels.
+  label_map: HashMap<String, String>,
+  groups: [Group; N],
+}
+
+/// Canonicalizes `document` with the blank node labels created by `label_map_factory` from the canonical labels, and
+/// groups its N-Quads by the selections of the given JSON pointers.
+///
+/// [More Info](https://www.w3.org/TR/vc-di-ecdsa/#canonicalizeandgroup)
+fn canonicalize_and_group<const N: usize>(
+  document: &Value,
+  label_map_factory: impl FnOnce(&HashMap<String, String>) -> HashMap<String, String>,
+  groups: [&[String]; N],
+  context_cache: &ContextCache,
+) -> Result<GroupedNQuads<N>> {
+  let skolemized: Value = json_ld::skolemize(document, context_cache)?;
+  let dataset: Dataset = Dataset::from_skolemized_document(&skolemized, context_cache)?;
+  let label_map: HashMap<String, String> = label_map_factory(&dataset.canonical_id_map()?);
+  let nquads: Vec<String> = dataset.to_nquads(&label_map)?;
+
+  let mut result: Vec<Group> = Vec::with_capacity(N);
+  for pointers in groups {
+    let dataset: Option<Dataset> = select_json_ld(&skolemized, pointers)?
+      .map(|selection| Dataset::from_skolemized_document(&selection, context_cache))
+      .transpose()?;
+    let selected: HashSet<String> = match &dataset {
+      Some(dataset) => dataset.to_nquads(&label_map)?.into_iter().collect(),
+      None => HashSet::new(),
+    };
+    let (matching, non_matching) = nquads
+      .iter()
+      .cloned()
+      .enumerate()
+      .partition(|(_, nquad)| selected.contains(nquad));
+    result.push(Group {
+      matching,
+      non_matching,
+      dataset,
+    });
+  }
+
+  Ok(GroupedNQuads {
+    label_map,
+    groups: result
+      .try_into()
+      .unwrap_or_else(|_| unreachable!("one group per selection")),
+  })
+}
+
+/// Replaces the canonical blank node labels by HMAC based labels `bN`, numbered in the order of their HMACs.
+///
+/// [More Info](https://www.w3.org/TR/vc-di-bbs/#createshuffledidlabelmapfunction)
+fn hmac_label_map(hmac_key: &[u8], canonical_id_map: &HashMap<String, String>) -> HashMap<String, String> {
+  let mut hmac_ids: Vec<(String, &String)> = canonical_id_map
+    .iter()
+    .map(|(label, canonical_label)| {
+      let mut mac: [u8; 32] = [0; 32];
+      HMAC_SHA256(canonical_label.as_bytes(), hmac_key, &mut mac);
+      (BaseEncoding::encode_multibase(&mac, Some(Base::Base64Url)), label)
+    })
+    .collect();
+  hmac_ids.sort();
+  hmac_ids
+    .into_iter()
+    .enumerate()
+    .map(|(index, (_, label))| (label.clone(), format!("b{index}")))
+    .collect()
+}
+
+/// Returns the index of a blank node label, e.g. `2` for `c14n2`.
+fn label_index(label: &str, prefix: &str) -> Result<u64> {
+  label
+    .strip_prefix(prefix)
+    .and_then(|index| index.parse().ok())
+    .ok_or_else(|| error(format!("unexpected blank node label `{label}`")))
+}
+
+fn proof_hash(proof: &DataIntegrityProof, document: &Value, context_cache: &ContextCache) -> Result<[u8; SHA256_LEN]> {
+  let proof_config: Value = proof.proof_config(document)?;
+  json_ld::canonicalize(&proof_config, context_cache).map(|canonical| sha256(canonical.as_bytes()))
+}
+
+fn proof_value(proof: &DataIntegrityProof) -> Result<&str> {
+  proof.proof_value.as_deref().ok_or_else(|| error("missing proofValue"))
+}
+
+/// Decodes the BBS public key of a BLS12-381 G2 JWK using the `BLS12381-SHA256` ciphersuite.
+fn bbs_public_key(jwk: &Jwk) -> Result<BBSplusPublicKey> {
+  if jwk.alg() != Some(ProofAlgorithm::BLS12381_SHA256.to_string().as_str()) {
+    return Err(error("bbs-2023 requires a BLS12381-SHA256 key"));
+  }
+  let params: &JwkParamsEc = jwk
+    .try_ec_params()
+    .ok()
+    .filter(|params| params.try_bls_curve().ok() == Some(BlsCurve::BLS12381G2))
+    .ok_or_else(|| error("bbs-2023 requires a BLS12381G2 key"))?;
+  let coordinate = |coordinate: &str| {
+    jwu::decode_b64(coordinate)
+      .ok()
+      .and_then(|bytes| bytes.try_into().ok())
+      .ok_or_else(|| error("invalid BLS12381G2 coordinate"))
+  };
+  BBSplusPublicKey::from_coordinates(&coordinate(&params.x)?, &coordinate(&params.y)?)
+    .map_err(|err| error(format!("invalid public key: {err}")))
+}
+
+fn sha256(data: &[u8]) -> [u8; SHA256_LEN] {
+  let mut digest: [u8; SHA256_LEN] = [0; SHA256_LEN];
+  SHA256(data, &mut digest);
+  digest
+}
+
+fn json_error(err: identity_core::Error) -> Error {
+  Error::DataIntegrityError(err.into())
+}
+
+fn error(message: impl Into<String>) -> Error {
+  Error::DataIntegrityError(message.into().into())
+}
+
+#[cfg(test)]
+mod tests {
+  use identity_core::common::Timestamp;
+  use zkryptium::bbsplus::keys::BBSplusSecretKey;
+
+  use super::*;
+  use crate::data_integrity::ProofPurpose;
+
+  // https://www.w3.org/TR/vc-di-bbs/#test-vectors
+  const BASE_DOCUMENT: &str = include_str!("../../../tests/fixtures/vc-di-bbs-base.json");
+  const DERIVED_DOCUMENT: &str = include_str!("../../../tests/fixtures/vc-di-bbs-derived.json");
+  const SECRET_KEY: &str = "66d36e118832af4c5e28b2dfe1b9577857e57b042a33e06bdea37b811ed09ee0";
+  const PUBLIC_KEY_MULTIBASE: &str = "zUC7DerdEmfZ8f4pFajXgGwJoMkV1ofMTmEG5UoNvnWiPiLuGKNeqgRpLH2TV4Xe5mJ2cXV76gRN7LFQwapF1VFu6x2yrr5ci1mXqC1WNUrnHnLgvfZfMH7h6xP6qsf9EKRQrPQ";
+  const HMAC_KEY: &str = "00112233445566778899AABBCCDDEEFF00112233445566778899AABBCCDDEEFF";
+  const PROOF_HASH: &str = "3a5bbf25d34d90b18c35cd2357be6a6f42301e94fc9e52f77e93b773c5614bdf";
+  const MANDATORY_POINTERS: &[&str] = &[
+    "/issuer",
+    "/credentialSubject/sailNumber",
+    "/credentialSubject/sails/1",
+    "/credentialSubject/boards/0/year",
+    "/credentialSubject/sails/2",
+  ];
+  const SELECTIVE_POINTERS: &[&str] = &["/credentialSubject/boards/0", "/credentialSubject/boards/1"];
+
+  fn decode_hex(hex: &str) -> Vec<u8> {
+    (0..hex.len())
+      .step_by(2)
+      .map(|index| u8::from_str_radix(&hex[index..index + 2], 16).unwrap())
+      .collect()
+  }
+
+  fn pointers(pointers: &[&str]) -> Vec<String> {
+    pointers.iter().map(ToString::to_string).collect()
+  }
+
+  fn context_cache() -> ContextCache {
+    let credentials_v2: Value =
+      serde_json::from_str(include_str!("../../../tests/fixtures/context-credentials-v2.json")).unwrap();
+    ContextCache::new().with_context("https://www.w3.org/ns/credentials/v2", credentials_v2)
+  }
+
+  fn public_jwk(public_key: &BBSplusPublicKey) -> Jwk {
+    let (x, y) = public_key.to_coordinates();
+    let mut params: JwkParamsEc = JwkParamsEc::new();
+    params.crv = BlsCurve::BLS12381G2.name().to_owned();
+    params.x = jwu::encode_b64(x);
+    params.y = jwu::encode_b64(y);
+    let mut jwk: Jwk = Jwk::from_params(params);
+    jwk.set_alg(ProofAlgorithm::BLS12381_SHA256.to_string());
+    jwk
+  }
+
+  #[test]
+  fn test_vc_di_bbs_vectors() {
+    let secret_key: BBSplusSecretKey = BBSplusSecretKey::from_bytes(&decode_hex(SECRET_KEY)).unwrap();
+    let public_key: BBSplusPublicKey = secret_key.public_key();
+    // The did:key of the issuer, with the `bls12_381-g2-pub` multicodec prefix.
+    let multicodec: Vec<u8> = [[0xeb, 0x01].as_slice(), public_key.to_bytes().as_slice()].concat();
+    assert_eq!(BaseEncoding::encode_multibase(&multicodec, None), PUBLIC_KEY_MULTIBASE);
+
+    let context_cache: ContextCache = context_cache();
+    let document: Value = serde_json::from_str(BASE_DOCUMENT).unwrap();
+    let mut proof = DataIntegrityProof::new(
+      Cryptosuite::Bbs2023,
+      format!("did:key:{PUBLIC_KEY_MULTIBASE}#{PUBLIC_KEY_MULTIBASE}"),
+      ProofPurpose::AssertionMethod,
+    );
+    proof.created = Some(Timestamp::parse("2023-08-15T23:36:38Z").unwrap());
+
+    let input: Bbs2023SigningInput = Bbs2023SigningInput::with_hmac_key(
+      &document,
+      proof,
+      pointers(MANDATORY_POINTERS),
+      decode_hex(HMAC_KEY).try_into().unwrap(),
+      &context_cache,
+    )
+    .unwrap();
+    assert_eq!(&input.bbs_header()[..SHA256_LEN], decode_hex(PROOF_HASH).as_slice());
+
+    let signature: Signature<Bbs> = Signature::<Bbs>::sign(
+      Some(input.bbs_messages()),
+      &secret_key,
+      &public_key,
+      Some(input.bbs_header()),
+    )
+    .unwrap();
+    let jwk: Jwk = public_jwk(&public_key);
+    let base_proof: DataIntegrityProof = input.into_proof(&signature.to_bytes(), &jwk).unwrap();
+
+    let mut derived: Value = derive_document(
+      &document,
+      base_proof,
+      &pointers(SELECTIVE_POINTERS),
+      b"presentation header",
+      &context_cache,
+    )
+    .unwrap();
+    let derived_proof: DataIntegrityProof =
+      serde_json::from_value(derived.as_object_mut().unwrap().remove("proof").unwrap()).unwrap();
+    assert_eq!(derived, serde_json::from_str::<Value>(DERIVED_DOCUMENT).unwrap());
+    assert_eq!(
+      verify_derived_proof(&derived, &derived_proof, &jwk, &context_cache).unwrap(),
+      b"presentation header"
+    );
+  }
+}
@@ -0,0 +1,184 @@
+// Copyright 2020-2024 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+use std::collections::BTreeMap;
+
+use ciborium::Value;
+use identity_core::convert::Base;
+use identity_core::convert::BaseEncoding;
+
+use super::error;
+use crate::error::Result;
+
+/// The header of base proof values, i.e. the CBOR tag `0xd95d02`.
+const BASE_PROOF_HEADER: [u8; 3] = [0xd9, 0x5d, 0x02];
+/// The header of derived proof values, i.e. the CBOR tag `0xd95d03`.
+const DERIVED_PROOF_HEADER: [u8; 3] = [0xd9, 0x5d, 0x03];
+
+/// The components of a `bbs-2023` base proof, created by the issuer.
+///
+/// [More Info](https://www.w3.org/TR/vc-di-bbs/#serializebaseproofvalue)
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(super) struct BaseProofValue {
+  pub(super) bbs_signature: Vec<u8>,
+  pub(super) bbs_header: Vec<u8>,
+  pub(super) public_key: Vec<u8>,
+  pub(super) hmac_key: Vec<u8>,
+  pub(super) mandatory_pointers: Vec<String>,
+}
+
+impl BaseProofValue {
+  pub(super) fn encode(&self) -> Result<String> {
+    let components: Value = Value::Array(vec![
+      Value::Bytes(self.bbs_signature.clone()),
+      Value::Bytes(self.bbs_header.clone()),
+      Value::Bytes(self.public_key.clone()),
+      Value::Bytes(self.hmac_key.clone()),
+      Value::Array(self.mandatory_pointers.iter().cloned().map(Value::Text).collect()),
+    ]);
+    encode(BASE_PROOF_HEADER, &components)
+  }
+
+  pub(super) fn decode(proof_value: &str) -> Result<Self> {
+    let [bbs_signature, bbs_header, public_key, hmac_key, mandatory_pointers] = decode(BASE_PROOF_HEADER, proof_value)?;
+    Ok(Self {
+      bbs_signature: into_bytes(bbs_signature)?,
+      bbs_header: into_bytes(bbs_header)?,
+      public_key: into_bytes(public_key)?,
+      hmac_key: into_bytes(hmac_key)?,
+      mandatory_pointers: into_array(mandatory_pointers)?
+        .into_iter()
+        .map(|pointer| pointer.into_text().map_err(|_| error("invalid mandatory pointer")))
+        .collect::<Result<_>>()?,
+    })
+  }
+}
+
+/// The components of a `bbs-2023` derived proof, created by the holder.
+///
+/// The label map associates the canonical blank node labels of the revealed document (`c14nN`) with the blank node
+/// labels used by the issuer (`bM`), compressed to their indexes `N -> M`.
+///
+/// [More Info](https://www.w3.org/TR/vc-di-bbs/#serializederivedproofvalue)
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(super) struct DerivedProofValue {
+  pub(super) bbs_proof: Vec<u8>,
+  pub(super) label_map: BTreeMap<u64, u64>,
+  pub(super) mandatory_indexes: Vec<usize>,
+  pub(super) selective_indexes: Vec<usize>,
+  pub(super) presentation_header: Vec<u8>,
+}
+
+impl DerivedProofValue {
+  pub(super) fn encode(&self) -> Result<String> {
+    let indexes =
+      |indexes: &[usize]| Value::Array(indexes.iter().map(|index| Value::Integer((*index).into())).collect());
+    let components: Value = Value::Array(vec![
+      Value::Bytes(self.bbs_proof.clone()),
+      Value::Map(
+        self
+          .label_map
+          .iter()
+          .map(|(key, value)| (Value::Integer((*key).into()), Value::Integer((*value).into())))
+          .collect(),
+      ),
+      indexes(&self.mandatory_indexes),
+      indexes(&self.selective_indexes),
+      Value::Bytes(self.presentation_header.clone()),
+    ]);
+    encode(DERIVED_PROOF_HEADER, &components)
+  }
+
+  pub(super) fn decode(proof_value: &str) -> Result<Self> {
+    let [bbs_proof, label_map, mandatory_indexes, selective_indexes, presentation_header] =
+      decode(DERIVED_PROOF_HEADER, proof_value)?;
+    let label_map: BTreeMap<u64, u64> = label_map
+      .into_map()
+      .map_err(|_| error("invalid label map"))?
+      .into_iter()
+      .map(|(key, value)| Ok((into_integer(key)?, into_integer(value)?)))
+      .collect::<Result<_>>()?;
+    let indexes = |indexes: Value| -> Result<Vec<usize>> {
+      into_array(indexes)?
+        .into_iter()
+        .map(|index| into_integer(index).and_then(|index| usize::try_from(index).map_err(|_| error("invalid index"))))
+        .collect()
+    };
+    Ok(Self {
+      bbs_proof: into_bytes(bbs_proof)?,
+      label_map,
+      mandatory_indexes: indexes(mandatory_indexes)?,
+      selective_indexes: indexes(selective_indexes)?,
+      presentation_header: into_bytes(presentation_header)?,
+    })
+  }
+}
+
+fn encode(header: [u8; 3], components: &Value) -> Result<String> {
+  let mut bytes: Vec<u8> = header.to_vec();
+  ciborium::ser::into_writer(components, &mut bytes).map_err(|err| error(err.to_string()))?;
+  Ok(BaseEncoding::encode_multibase(&bytes, Some(Base::Base64Url)))
+}
+
+fn decode(header: [u8; 3], proof_value: &str) -> Result<[Value; 5]> {
+  if !proof_value.starts_with('u') {
+    return Err(error("proofValue must be base64url-no-pad multibase encoded"));
+  }
+  let bytes: Vec<u8> = BaseEncoding::decode_multibase(proof_value).map_err(|err| error(err.to_string()))?;
+  let components: &[u8] = bytes
+    .strip_prefix(header.as_slice())
+    .ok_or_else(|| error("unexpected proofValue header"))?;
+  let components: Value = ciborium::de::from_reader(components).map_err(|err| error(err.to_string()))?;
+  into_array(components)?
+    .try_into()
+    .map_err(|_| error("unexpected number of proofValue components"))
+}
+
+fn into_bytes(value: Value) -> Result<Vec<u8>> {
+  value.into_bytes().map_err(|_| error("expected a byte string"))
+}
+
+fn into_array(value: Value) -> Result<Vec<Value>> {
+  value.into_array().map_err(|_| error("expected an array"))
+}
+
+fn into_integer(value: Value) -> Result<u64> {
+  value
+    .into_integer()
+    .ok()
+    .and_then(|integer| u64::try_from(integer).ok())
+    .ok_or_else(|| error("expected an unsigned integer"))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_proof_value_round_trip() {
+    let base = BaseProofValue {
+      bbs_signature: vec![1; 80],
+      bbs_header: vec![2; 64],
+      public_key: vec![3; 96],
+      hmac_key: vec![4; 32],
+      mandatory_pointers: vec!["/issuer".to_owned()],
+    };
+    let encoded: String = base.encode().unwrap();
+    assert!(encoded.starts_with("u2V0C"));
+    assert_eq!(BaseProofValue::decode(&encoded).unwrap(), base);
+
+    let derived = DerivedProofValue {
+      bbs_proof: vec![5; 272],
+      label_map: BTreeMap::from_iter([(0, 2), (1, 0)]),
+      mandatory_indexes: vec![0, 3],
+      selective_indexes: vec![1],
+      presentation_header: Vec::new(),
+    };
+    let encoded: String = derived.encode().unwrap();
+    assert!(encoded.starts_with("u2V0D"));
+    assert_eq!(DerivedProofValue::decode(&encoded).unwrap(), derived);
+
+    // Base and derived proof values cannot be confused.
+    assert!(BaseProofValue::decode(&encoded).is_err());
+  }
+}
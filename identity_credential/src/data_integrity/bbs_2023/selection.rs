@@ -0,0 +1,239 @@
+// Copyright 2020-2024 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+use serde_json::Map;
+use serde_json::Value;
+
+use super::error;
+use crate::error::Result;
+
+/// Selects the parts of the compact JSON-LD `document` identified by the given JSON `pointers`.
+///
+/// Every selected node keeps its `id` and `type`, so that the selection expands to a subset of the statements of the
+/// document. Returns `None` if no pointers are given.
+///
+/// [More Info](https://www.w3.org/TR/vc-di-ecdsa/#selectjsonld)
+pub(super) fn select_json_ld(document: &Value, pointers: &[String]) -> Result<Option<Value>> {
+  if pointers.is_empty() {
+    return Ok(None);
+  }
+  let object: &Map<String, Value> = document
+    .as_object()
+    .ok_or_else(|| error("the document must be a JSON object"))?;
+
+  let mut selection: Value = init_selection(object);
+  if let Some(context) = object.get("@context") {
+    selection["@context"] = context.clone();
+  }
+
+  // Arrays created by the selection may contain holes, which are removed once all pointers have been applied.
+  let mut arrays: Vec<Vec<String>> = Vec::new();
+  for pointer in pointers {
+    let paths: Vec<String> = parse_pointer(pointer)?;
+    select_paths(document, &paths, &mut selection, &mut arrays)?;
+  }
+
+  // Deeper arrays are compacted first, so that the paths of the remaining arrays stay valid.
+  arrays.sort_by_key(|path| std::cmp::Reverse(path.len()));
+  for path in arrays {
+    if let Some(Value::Array(items)) = path.iter().try_fold(&mut selection, |value, path| get_mut(value, path)) {
+      items.retain(|item| !item.is_null());
+    }
+  }
+
+  Ok(Some(selection))
+}
+
+/// Parses a JSON pointer into its reference tokens.
+///
+/// [More Info](https://www.rfc-editor.org/rfc/rfc6901)
+fn parse_pointer(pointer: &str) -> Result<Vec<String>> {
+  let tokens: &str = pointer
+    .strip_prefix('/')
+    .ok_or_else(|| error(format!("invalid JSON pointer `{pointer}`")))?;
+  Ok(
+    tokens
+      .split('/')
+      .map(|token| token.replace("~1", "/").replace("~0", "~"))
+      .collect(),
+  )
+}
+
+/// Creates the selection of a node, retaining the properties that identify it.
+fn init_selection(source: &Map<String, Value>) -> Value {
+  let mut selection: Map<String, Value> = Map::new();
+  for key in ["id", "@id"] {
+    if let Some(id) = source
+      .get(key)
+      .filter(|id| id.as_str().map_or(false, |id| !id.starts_with("_:")))
+    {
+      selection.insert(key.to_owned(), id.clone());
+    }
+  }
+  for key in ["type", "@type"] {
+    if let Some(type_) = source.get(key) {
+      selection.insert(key.to_owned(), type_.clone());
+    }
+  }
+  Value::Object(selection)
+}
+
+fn select_paths(
+  document: &Value,
+  paths: &[String],
+  selection: &mut Value,
+  arrays: &mut Vec<Vec<String>>,
+) -> Result<()> {
+  let (last, parents) = paths.split_last().ok_or_else(|| error("empty JSON pointer"))?;
+
+  let mut value: &Value = document;
+  let mut selected: &mut Value = selection;
+  for (depth, path) in parents.iter().enumerate() {
+    value = get(value, path).ok_or_else(|| error("JSON pointer does not match the document"))?;
+    if get(selected, path).map_or(true, Value::is_null) {
+      let initial: Value = match value {
+        Value::Array(_) => {
+          arrays.push(paths[..=depth].to_vec());
+          Value::Array(Vec::new())
+        }
+        Value::Object(object) => init_selection(object),
+        _ => return Err(error("JSON pointer does not match the document")),
+      };
+      set(selected, path, initial)?;
+    }
+    selected = get_mut(selected, path).ok_or_else(|| error("JSON pointer does not match the document"))?;
+  }
+
+  let target: &Value = get(value, last).ok_or_else(|| error("JSON pointer does not match the document"))?;
+  let selected_target: Value = match target {
+    Value::Object(object) => {
+      let mut merged: Map<String, Value> = match get(selected, last) {
+        Some(Value::Object(existing)) => existing.clone(),
+        _ => Map::new(),
+      };
+      merged.extend(object.iter().map(|(key, value)| (key.clone(), value.clone())));
+      Value::Object(merged)
+    }
+    target => target.clone(),
+  };
+  set(selected, last, selected_target)
+}
+
+fn get<'a>(value: &'a Value, path: &str) -> Option<&'a Value> {
+  match value {
+    Value::Object(object) => object.get(path),
+    Value::Array(items) => path.parse::<usize>().ok().and_then(|index| items.get(index)),
+    _ => None,
+  }
+}
+
+fn get_mut<'a>(value: &'a mut Value, path: &str) -> Option<&'a mut Value> {
+  match value {
+    Value::Object(object) => object.get_mut(path),
+    Value::Array(items) => path.parse::<usize>().ok().and_then(|index| items.get_mut(index)),
+    _ => None,
+  }
+}
+
+fn set(value: &mut Value, path: &str, new: Value) -> Result<()> {
+  match value {
+    Value::Object(object) => {
+      object.insert(path.to_owned(), new);
+    }
+    Value::Array(items) => {
+      let index: usize = path.parse().map_err(|_| error("invalid array index in JSON pointer"))?;
+      if items.len() <= index {
+        items.resize(index + 1, Value::Null);
+      }
+      items[index] = new;
+    }
+    _ => return Err(error("JSON pointer does not match the document")),
+  }
+  Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+  use serde_json::json;
+
+  use super::*;
+
+  fn pointers(pointers: &[&str]) -> Vec<String> {
+    pointers.iter().map(ToString::to_string).collect()
+  }
+
+  #[test]
+  fn test_select_json_ld() {
+    let document = json!({
+      "@context": ["https://www.w3.org/ns/credentials/v2"],
+      "id": "urn:uuid:1",
+      "type": ["VerifiableCredential"],
+      "issuer": "did:example:issuer",
+      "credentialSubject": {
+        "type": "Person",
+        "name": "Alice",
+        "age": 42,
+        "a/b": true,
+        "sailNumbers": [{"sailNumber": "Earth101"}, {"sailNumber": "Mars101"}, {"sailNumber": "Venus101"}]
+      }
+    });
+
+    let selection = select_json_ld(
+      &document,
+      &pointers(&[
+        "/issuer",
+        "/credentialSubject/name",
+        "/credentialSubject/a~1b",
+        "/credentialSubject/sailNumbers/2",
+      ]),
+    )
+    .unwrap()
+    .unwrap();
+    assert_eq!(
+      selection,
+      json!({
+        "@context": ["https://www.w3.org/ns/credentials/v2"],
+        "id": "urn:uuid:1",
+        "type": ["VerifiableCredential"],
+        "issuer": "did:example:issuer",
+        "credentialSubject": {
+          "type": "Person",
+          "name": "Alice",
+          "a/b": true,
+          "sailNumbers": [{"sailNumber": "Venus101"}]
+        }
+      })
+    );
+
+    assert!(select_json_ld(&document, &[]).unwrap().is_none());
+    assert!(select_json_ld(&document, &pointers(&["/credentialSubject/nickname"])).is_err());
+    assert!(select_json_ld(&document, &pointers(&["issuer"])).is_err());
+  }
+
+  #[test]
+  fn test_select_whole_node() {
+    let document = json!({
+      "id": "urn:uuid:1",
+      "credentialSubject": {"id": "_:b0", "name": "Alice", "degree": {"name": "MSc"}}
+    });
+    let selection = select_json_ld(
+      &document,
+      &pointers(&["/credentialSubject/degree/name", "/credentialSubject"]),
+    )
+    .unwrap()
+    .unwrap();
+    assert_eq!(
+      selection,
+      json!({"id": "urn:uuid:1", "credentialSubject": document["credentialSubject"]})
+    );
+
+    // Blank node identifiers are not retained by partial selections.
+    let selection = select_json_ld(&document, &pointers(&["/credentialSubject/name"]))
+      .unwrap()
+      .unwrap();
+    assert_eq!(
+      selection,
+      json!({"id": "urn:uuid:1", "credentialSubject": {"name": "Alice"}})
+    );
+  }
+}
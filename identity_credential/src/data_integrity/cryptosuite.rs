@@ -0,0 +1,143 @@
+// Copyright 2020-2024 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+use core::fmt::Display;
+use core::fmt::Formatter;
+use core::str::FromStr;
+
+use crypto::hashes::sha::SHA256;
+use crypto::hashes::sha::SHA256_LEN;
+use identity_core::convert::ToJson;
+use identity_verification::jose::jws::JwsAlgorithm;
+use serde::Deserialize;
+use serde::Serialize;
+use serde_json::Value;
+
+use super::json_ld;
+use super::ContextCache;
+use crate::error::Error;
+use crate::error::Result;
+
+/// A Data Integrity cryptosuite, identifying the canonicalization, hashing and signature algorithms used to create a
+/// [`DataIntegrityProof`](super::DataIntegrityProof).
+///
+/// [More Info](https://www.w3.org/TR/vc-data-integrity/#cryptographic-suites)
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[non_exhaustive]
+pub enum Cryptosuite {
+  /// Ed25519 signatures over JSON Canonicalization Scheme (JCS) canonicalized documents.
+  ///
+  /// [More Info](https://www.w3.org/TR/vc-di-eddsa/#eddsa-jcs-2022)
+  #[serde(rename = "eddsa-jcs-2022")]
+  EddsaJcs2022,
+  /// Ed25519 signatures over RDF Dataset Canonicalization (RDFC-1.0) canonicalized documents.
+  ///
+  /// [More Info](https://www.w3.org/TR/vc-di-eddsa/#eddsa-rdfc-2022)
+  #[serde(rename = "eddsa-rdfc-2022")]
+  EddsaRdfc2022,
+  /// ML-DSA-44 signatures over JSON Canonicalization Scheme (JCS) canonicalized documents.
+  ///
+  /// This is an experimental profile mirroring `eddsa-jcs-2022`, which is not interoperable with other
+  /// implementations until a post-quantum cryptosuite is standardized.
+  #[serde(rename = "mldsa44-jcs-2024")]
+  MlDsa44Jcs2024,
+  /// Selectively disclosable BBS signatures over RDF Dataset Canonicalization (RDFC-1.0) canonicalized documents,
+  /// created with BLS12-381 G2 keys using the `BLS12381-SHA256` ciphersuite.
+  ///
+  /// [More Info](https://www.w3.org/TR/vc-di-bbs/#bbs-2023)
+  #[serde(rename = "bbs-2023")]
+  Bbs2023,
+}
+
+impl Cryptosuite {
+  /// Returns the name of the cryptosuite.
+  pub const fn name(self) -> &'static str {
+    match self {
+      Self::EddsaJcs2022 => "eddsa-jcs-2022",
+      Self::EddsaRdfc2022 => "eddsa-rdfc-2022",
+      Self::MlDsa44Jcs2024 => "mldsa44-jcs-2024",
+      Self::Bbs2023 => "bbs-2023",
+    }
+  }
+
+  /// Returns the JWS algorithm of the cryptosuite, or `None` for `bbs-2023` whose proofs are not JWS signatures.
+  pub const fn alg(self) -> Option<JwsAlgorithm> {
+    match self {
+      Self::EddsaJcs2022 | Self::EddsaRdfc2022 => Some(JwsAlgorithm::EdDSA),
+      Self::MlDsa44Jcs2024 => Some(JwsAlgorithm::ML_DSA_44),
+      Self::Bbs2023 => None,
+    }
+  }
+
+  /// Returns the default cryptosuite for keys of the given algorithm, if any.
+  ///
+  /// JCS based cryptosuites are preferred since they do not require the JSON-LD contexts of the secured document.
+  pub const fn from_alg(alg: JwsAlgorithm) -> Option<Self> {
+    match alg {
+      JwsAlgorithm::EdDSA => Some(Self::EddsaJcs2022),
+      JwsAlgorithm::ML_DSA_44 => Some(Self::MlDsa44Jcs2024),
+      _ => None,
+    }
+  }
+
+  /// Returns `true` if the cryptosuite canonicalizes documents with RDFC-1.0, requiring their JSON-LD contexts to be
+  /// present in the [`ContextCache`].
+  pub const fn is_rdfc(self) -> bool {
+    matches!(self, Self::EddsaRdfc2022 | Self::Bbs2023)
+  }
+
+  /// Computes the data to be signed for the `unsecured_document` and the `proof_config`, i.e. the concatenation of
+  /// the SHA-256 hashes of their canonical forms.
+  ///
+  /// This does not apply to `bbs-2023`, which signs the canonical statements of the document individually.
+  ///
+  /// [More Info](https://www.w3.org/TR/vc-di-eddsa/#hashing-eddsa-jcs-2022)
+  pub(crate) fn hash_data(
+    self,
+    unsecured_document: &Value,
+    proof_config: &Value,
+    context_cache: &ContextCache,
+  ) -> Result<Vec<u8>> {
+    let mut hash_data: Vec<u8> = vec![0; 2 * SHA256_LEN];
+    let (config_hash, document_hash) = hash_data.split_at_mut(SHA256_LEN);
+    SHA256(
+      &self.canonicalize(proof_config, context_cache)?,
+      config_hash.try_into().unwrap(),
+    );
+    SHA256(
+      &self.canonicalize(unsecured_document, context_cache)?,
+      document_hash.try_into().unwrap(),
+    );
+    Ok(hash_data)
+  }
+
+  fn canonicalize(self, value: &Value, context_cache: &ContextCache) -> Result<Vec<u8>> {
+    if self.is_rdfc() {
+      json_ld::canonicalize(value, context_cache).map(String::into_bytes)
+    } else {
+      value.to_jcs().map_err(|err| Error::DataIntegrityError(err.into()))
+    }
+  }
+}
+
+impl Display for Cryptosuite {
+  fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+    f.write_str(self.name())
+  }
+}
+
+impl FromStr for Cryptosuite {
+  type Err = Error;
+
+  fn from_str(string: &str) -> Result<Self> {
+    match string {
+      "eddsa-jcs-2022" => Ok(Self::EddsaJcs2022),
+      "eddsa-rdfc-2022" => Ok(Self::EddsaRdfc2022),
+      "mldsa44-jcs-2024" => Ok(Self::MlDsa44Jcs2024),
+      "bbs-2023" => Ok(Self::Bbs2023),
+      _ => Err(Error::DataIntegrityError(
+        format!("unsupported cryptosuite `{string}`").into(),
+      )),
+    }
+  }
+}
@@ -0,0 +1,494 @@
+// Copyright 2020-2024 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+use std::collections::BTreeMap;
+use std::collections::HashMap;
+
+use crypto::hashes::sha::SHA256;
+use crypto::hashes::sha::SHA256_LEN;
+
+use super::error;
+use super::rdf::Quad;
+use super::rdf::Term;
+use crate::error::Result;
+
+/// The maximum number of Hash N-Degree Quads invocations, protecting against poisoned datasets.
+const MAX_HASH_N_DEGREE_CALLS: usize = 4096;
+/// The maximum number of permutations of related blank nodes visited by Hash N-Degree Quads, protecting against
+/// poisoned datasets whose blank nodes share a large neighbourhood.
+const MAX_PERMUTATIONS: usize = 65536;
+
+/// Canonicalizes the given RDF dataset according to [RDFC-1.0](https://www.w3.org/TR/rdf-canon/) and serializes it
+/// as sorted N-Quads.
+pub(super) fn canonicalize(quads: Vec<Quad>) -> Result<String> {
+  let canonical_id_map: HashMap<String, String> = issue_canonical_labels(&quads)?;
+  let mut nquads: Vec<String> = quads
+    .iter()
+    .map(|quad| {
+      relabel(quad, |label| {
+        canonical_id_map.get(label).map_or(label, String::as_str).to_owned()
+      })
+      .to_nquad()
+    })
+    .collect();
+  nquads.sort();
+  nquads.dedup();
+  Ok(nquads.concat())
+}
+
+/// Issues the canonical labels of the blank nodes of the given RDF dataset, returning the map from their current
+/// labels to their canonical labels.
+pub(super) fn issue_canonical_labels(quads: &[Quad]) -> Result<HashMap<String, String>> {
+  let mut state = CanonicalizationState::new(quads);
+
+  // Issue canonical identifiers for blank nodes with a unique first degree hash.
+  let mut hash_to_blank_nodes: BTreeMap<String, Vec<String>> = BTreeMap::new();
+  let mut blank_nodes: Vec<String> = state.blank_node_to_quads.keys().cloned().collect();
+  blank_nodes.sort();
+  for blank_node in blank_nodes {
+    let hash: String = state.hash_first_degree_quads(&blank_node);
+    hash_to_blank_nodes.entry(hash).or_default().push(blank_node);
+  }
+
+  let mut shared: Vec<Vec<String>> = Vec::new();
+  for (_, blank_nodes) in hash_to_blank_nodes {
+    if let [blank_node] = blank_nodes.as_slice() {
+      state.canonical_issuer.issue(blank_node);
+    } else {
+      shared.push(blank_nodes);
+    }
+  }
+
+  // Disambiguate the remaining blank nodes through their neighbourhood.
+  for blank_nodes in shared {
+    let mut results: Vec<(String, IdentifierIssuer)> = Vec::new();
+    for blank_node in blank_nodes {
+      if state.canonical_issuer.is_issued(&blank_node) {
+        continue;
+      }
+      let mut issuer = IdentifierIssuer::new("b");
+      issuer.issue(&blank_node);
+      results.push(state.hash_n_degree_quads(&blank_node, issuer)?);
+    }
+    results.sort_by(|(lhs, _), (rhs, _)| lhs.cmp(rhs));
+    for (_, issuer) in results {
+      for existing in issuer.issued_order {
+        state.canonical_issuer.issue(&existing);
+      }
+    }
+  }
+
+  Ok(state.canonical_issuer.issued)
+}
+
+fn sha256_hex(data: &str) -> String {
+  let mut digest: [u8; SHA256_LEN] = [0; SHA256_LEN];
+  SHA256(data.as_bytes(), &mut digest);
+  digest.iter().map(|byte| format!("{byte:02x}")).collect()
+}
+
+pub(super) fn relabel(quad: &Quad, mut label: impl FnMut(&str) -> String) -> Quad {
+  let mut relabel_term = |term: &Term| match term {
+    Term::BlankNode(current) => Term::BlankNode(label(current)),
+    term => term.clone(),
+  };
+  Quad {
+    subject: relabel_term(&quad.subject),
+    predicate: quad.predicate.clone(),
+    object: relabel_term(&quad.object),
+    graph: quad.graph.as_ref().map(relabel_term),
+  }
+}
+
+/// Issues sequential identifiers with a given prefix.
+///
+/// [More Info](https://www.w3.org/TR/rdf-canon/#issue-identifier)
+#[derive(Clone, Debug)]
+struct IdentifierIssuer {
+  prefix: &'static str,
+  issued: HashMap<String, String>,
+  issued_order: Vec<String>,
+}
+
+impl IdentifierIssuer {
+  fn new(prefix: &'static str) -> Self {
+    Self {
+      prefix,
+      issued: HashMap::new(),
+      issued_order: Vec::new(),
+    }
+  }
+
+  fn issue(&mut self, existing: &str) -> String {
+    if let Some(issued) = self.issued.get(existing) {
+      return issued.clone();
+    }
+    let issued: String = format!("{}{}", self.prefix, self.issued_order.len());
+    self.issued.insert(existing.to_owned(), issued.clone());
+    self.issued_order.push(existing.to_owned());
+    issued
+  }
+
+  fn is_issued(&self, existing: &str) -> bool {
+    self.issued.contains_key(existing)
+  }
+
+  fn get(&self, existing: &str) -> Option<&str> {
+    self.issued.get(existing).map(String::as_str)
+  }
+}
+
+struct CanonicalizationState<'a> {
+  blank_node_to_quads: HashMap<String, Vec<&'a Quad>>,
+  first_degree_hashes: HashMap<String, String>,
+  canonical_issuer: IdentifierIssuer,
+  hash_n_degree_calls: usize,
+  permutations: usize,
+}
+
+impl<'a> CanonicalizationState<'a> {
+  fn new(quads: &'a [Quad]) -> Self {
+    let mut blank_node_to_quads: HashMap<String, Vec<&'a Quad>> = HashMap::new();
+    for quad in quads {
+      for label in quad.blank_node_components().filter_map(Term::blank_node_label) {
+        let quads: &mut Vec<&Quad> = blank_node_to_quads.entry(label.to_owned()).or_default();
+        if !quads.iter().any(|existing| std::ptr::eq(*existing, quad)) {
+          quads.push(quad);
+        }
+      }
+    }
+    Self {
+      blank_node_to_quads,
+      first_degree_hashes: HashMap::new(),
+      canonical_issuer: IdentifierIssuer::new("c14n"),
+      hash_n_degree_calls: 0,
+      permutations: 0,
+    }
+  }
+
+  /// [More Info](https://www.w3.org/TR/rdf-canon/#hash-1d-quads)
+  fn hash_first_degree_quads(&mut self, reference: &str) -> String {
+    if let Some(hash) = self.first_degree_hashes.get(reference) {
+      return hash.clone();
+    }
+    let mut nquads: Vec<String> = self.blank_node_to_quads[reference]
+      .iter()
+      .map(|quad| {
+        relabel(quad, |label| {
+          if label == reference {
+            "a".to_owned()
+          } else {
+            "z".to_owned()
+          }
+        })
+        .to_nquad()
+      })
+      .collect();
+    nquads.sort();
+    let hash: String = sha256_hex(&nquads.concat());
+    self.first_degree_hashes.insert(reference.to_owned(), hash.clone());
+    hash
+  }
+
+  /// [More Info](https://www.w3.org/TR/rdf-canon/#hash-related-blank-node)
+  fn hash_related_blank_node(
+    &mut self,
+    related: &str,
+    quad: &Quad,
+    issuer: &IdentifierIssuer,
+    position: char,
+  ) -> String {
+    let identifier: String = match self.canonical_issuer.get(related).or_else(|| issuer.get(related)) {
+      Some(identifier) => format!("_:{identifier}"),
+      None => self.hash_first_degree_quads(related),
+    };
+    let predicate: String = if position == 'g' {
+      String::new()
+    } else {
+      quad.predicate.to_string()
+    };
+    sha256_hex(&format!("{position}{predicate}{identifier}"))
+  }
+
+  /// [More Info](https://www.w3.org/TR/rdf-canon/#hash-nd-quads)
+  fn hash_n_degree_quads(
+    &mut self,
+    identifier: &str,
+    mut issuer: IdentifierIssuer,
+  ) -> Result<(String, IdentifierIssuer)> {
+    self.hash_n_degree_calls += 1;
+    if self.hash_n_degree_calls > MAX_HASH_N_DEGREE_CALLS {
+      return Err(error("the dataset is too complex to canonicalize"));
+    }
+
+    let mut hash_to_related: BTreeMap<String, Vec<String>> = BTreeMap::new();
+    let quads: Vec<&Quad> = self.blank_node_to_quads[identifier].clone();
+    for quad in quads {
+      let components = [
+        ('s', Some(&quad.subject)),
+        ('o', Some(&quad.object)),
+        ('g', quad.graph.as_ref()),
+      ];
+      for (position, term) in components {
+        let Some(Term::BlankNode(related)) = term else {
+          continue;
+        };
+        if related == identifier {
+          continue;
+        }
+        let hash: String = self.hash_related_blank_node(related, quad, &issuer, position);
+        hash_to_related.entry(hash).or_default().push(related.clone());
+      }
+    }
+
+    let mut data_to_hash: String = String::new();
+    for (related_hash, blank_nodes) in hash_to_related {
+      data_to_hash.push_str(&related_hash);
+      let mut chosen_path: String = String::new();
+      let mut chosen_issuer: Option<IdentifierIssuer> = None;
+
+      for permutation in Permutations::new(&blank_nodes) {
+        self.permutations += 1;
+        if self.permutations > MAX_PERMUTATIONS {
+          return Err(error("the dataset is too complex to canonicalize"));
+        }
+
+        let mut issuer_copy: IdentifierIssuer = issuer.clone();
+        let mut path: String = String::new();
+        let mut recursion_list: Vec<&String> = Vec::new();
+        let exceeds_chosen =
+          |path: &str, chosen: &str| !chosen.is_empty() && path.len() >= chosen.len() && path > chosen;
+
+        for &related in &permutation {
+          if let Some(canonical) = self.canonical_issuer.get(related) {
+            path.push_str("_:");
+            path.push_str(canonical);
+          } else {
+            if !issuer_copy.is_issued(related) {
+              recursion_list.push(related);
+            }
+            path.push_str("_:");
+            path.push_str(&issuer_copy.issue(related));
+          }
+          if exceeds_chosen(&path, &chosen_path) {
+            break;
+          }
+        }
+        if exceeds_chosen(&path, &chosen_path) {
+          continue;
+        }
+
+        let mut skip: bool = false;
+        for related in recursion_list {
+          let (hash, result_issuer) = self.hash_n_degree_quads(related, issuer_copy.clone())?;
+          path.push_str("_:");
+          path.push_str(&issuer_copy.issue(related));
+          path.push('<');
+          path.push_str(&hash);
+          path.push('>');
+          issuer_copy = result_issuer;
+          if exceeds_chosen(&path, &chosen_path) {
+            skip = true;
+            break;
+          }
+        }
+        if skip {
+          continue;
+        }
+
+        if chosen_path.is_empty() || path < chosen_path {
+          chosen_path = path;
+          chosen_issuer = Some(issuer_copy);
+        }
+      }
+
+      data_to_hash.push_str(&chosen_path);
+      if let Some(chosen_issuer) = chosen_issuer {
+        issuer = chosen_issuer;
+      }
+    }
+
+    Ok((sha256_hex(&data_to_hash), issuer))
+  }
+}
+
+/// Lazily generates all permutations of `items`, so that the limits on the work of Hash N-Degree Quads apply before
+/// the factorial number of permutations is materialized.
+struct Permutations<'a> {
+  items: &'a [String],
+  /// The positions of the items in the next permutation, or `None` once all permutations have been generated.
+  indexes: Option<Vec<usize>>,
+}
+
+impl<'a> Permutations<'a> {
+  fn new(items: &'a [String]) -> Self {
+    Self {
+      items,
+      indexes: Some((0..items.len()).collect()),
+    }
+  }
+}
+
+impl<'a> Iterator for Permutations<'a> {
+  type Item = Vec<&'a String>;
+
+  fn next(&mut self) -> Option<Self::Item> {
+    let items: &'a [String] = self.items;
+    let indexes: &mut Vec<usize> = self.indexes.as_mut()?;
+    let permutation: Vec<&'a String> = indexes.iter().map(|&index| &items[index]).collect();
+
+    // Advance to the next permutation in lexicographic order of the positions.
+    match indexes.windows(2).rposition(|pair| pair[0] < pair[1]) {
+      Some(pivot) => {
+        let successor: usize = (pivot + 1..indexes.len())
+          .rev()
+          .find(|&successor| indexes[successor] > indexes[pivot])
+          .unwrap_or(pivot + 1);
+        indexes.swap(pivot, successor);
+        indexes[pivot + 1..].reverse();
+      }
+      None => self.indexes = None,
+    }
+    Some(permutation)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn iri(value: &str) -> Term {
+    Term::Iri(value.to_owned())
+  }
+
+  fn blank(label: &str) -> Term {
+    Term::BlankNode(label.to_owned())
+  }
+
+  fn quad(subject: Term, predicate: &str, object: Term) -> Quad {
+    Quad {
+      subject,
+      predicate: iri(predicate),
+      object,
+      graph: None,
+    }
+  }
+
+  /// Parses N-Quads consisting of IRIs and blank nodes only.
+  fn nquads(input: &str) -> Vec<Quad> {
+    let term = |term: &str| match term.strip_prefix("_:") {
+      Some(label) => blank(label),
+      None => iri(term.trim_start_matches('<').trim_end_matches('>')),
+    };
+    input
+      .lines()
+      .map(|line| {
+        let terms: Vec<&str> = line.split_whitespace().collect();
+        quad(term(terms[0]), terms[1].trim_matches(['<', '>']), term(terms[2]))
+      })
+      .collect()
+  }
+
+  #[test]
+  fn test_rdf_canon_unique_hashes() {
+    // https://www.w3.org/TR/rdf-canon/#example-input-with-unique-hashes
+    let input: &str = "\
+<http://example.com/#p> <http://example.com/#q> _:e0 .
+<http://example.com/#p> <http://example.com/#r> _:e1 .
+_:e0 <http://example.com/#s> <http://example.com/#u> .
+_:e1 <http://example.com/#t> <http://example.com/#u> .
+";
+    let expected: &str = "\
+<http://example.com/#p> <http://example.com/#q> _:c14n0 .
+<http://example.com/#p> <http://example.com/#r> _:c14n1 .
+_:c14n0 <http://example.com/#s> <http://example.com/#u> .
+_:c14n1 <http://example.com/#t> <http://example.com/#u> .
+";
+    assert_eq!(canonicalize(nquads(input)).unwrap(), expected);
+  }
+
+  #[test]
+  fn test_rdf_canon_shared_hashes() {
+    // https://www.w3.org/TR/rdf-canon/#example-input-with-shared-hashes
+    let input: &str = "\
+<http://example.com/#p> <http://example.com/#q> _:e0 .
+<http://example.com/#p> <http://example.com/#q> _:e1 .
+_:e0 <http://example.com/#p> _:e2 .
+_:e1 <http://example.com/#p> _:e3 .
+_:e2 <http://example.com/#r> _:e3 .
+";
+    let expected: &str = "\
+<http://example.com/#p> <http://example.com/#q> _:c14n2 .
+<http://example.com/#p> <http://example.com/#q> _:c14n3 .
+_:c14n0 <http://example.com/#r> _:c14n1 .
+_:c14n2 <http://example.com/#p> _:c14n1 .
+_:c14n3 <http://example.com/#p> _:c14n0 .
+";
+    assert_eq!(canonicalize(nquads(input)).unwrap(), expected);
+  }
+
+  #[test]
+  fn test_unique_first_degree_hashes() {
+    let quads = vec![
+      quad(blank("x"), "https://example.org/p", iri("https://example.org/o")),
+      quad(blank("y"), "https://example.org/q", iri("https://example.org/o")),
+    ];
+    let canonical: String = canonicalize(quads).unwrap();
+    let mut labels: Vec<&str> = canonical.lines().map(|line| &line[..7]).collect();
+    labels.sort();
+    assert_eq!(labels, ["_:c14n0", "_:c14n1"]);
+  }
+
+  #[test]
+  fn test_relabeling_invariance() {
+    // Both blank nodes share the same first degree hash and need to be disambiguated by their neighbourhood.
+    let dataset = |first: &str, second: &str, third: &str| {
+      vec![
+        quad(blank(first), "https://example.org/p", blank(second)),
+        quad(blank(second), "https://example.org/p", blank(third)),
+        quad(blank(third), "https://example.org/p", blank(first)),
+        quad(blank(first), "https://example.org/q", iri("https://example.org/o")),
+      ]
+    };
+    let canonical: String = canonicalize(dataset("a", "b", "c")).unwrap();
+    assert_eq!(canonical, canonicalize(dataset("c", "a", "b")).unwrap());
+    assert_eq!(canonical, canonicalize(dataset("b1", "b0", "x")).unwrap());
+    assert_eq!(canonical.lines().count(), 4);
+    assert!(!canonical.contains("_:a") && !canonical.contains("_:b"));
+  }
+
+  #[test]
+  fn test_permutations() {
+    let items: Vec<String> = ["a", "b", "c"].map(ToOwned::to_owned).to_vec();
+    let permutations: Vec<String> = Permutations::new(&items)
+      .map(|permutation| permutation.into_iter().map(String::as_str).collect())
+      .collect();
+    assert_eq!(permutations, ["abc", "acb", "bac", "bca", "cab", "cba"]);
+    assert_eq!(Permutations::new(&[]).count(), 1);
+  }
+
+  #[test]
+  fn test_poisoned_dataset_is_rejected() {
+    // A clique of indistinguishable blank nodes has a factorial number of permutations to visit.
+    let labels: Vec<String> = (0..12).map(|index| format!("n{index}")).collect();
+    let mut quads: Vec<Quad> = Vec::new();
+    for subject in &labels {
+      for object in labels.iter().filter(|object| *object != subject) {
+        quads.push(quad(blank(subject), "https://example.org/p", blank(object)));
+      }
+    }
+    assert!(canonicalize(quads).is_err());
+  }
+
+  #[test]
+  fn test_duplicate_quads_are_removed() {
+    let quads = vec![
+      quad(iri("https://example.org/s"), "https://example.org/p", blank("x")),
+      quad(iri("https://example.org/s"), "https://example.org/p", blank("x")),
+    ];
+    assert_eq!(
+      canonicalize(quads).unwrap(),
+      "<https://example.org/s> <https://example.org/p> _:c14n0 .\n"
+    );
+  }
+}
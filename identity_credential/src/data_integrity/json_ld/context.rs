@@ -0,0 +1,510 @@
+// Copyright 2020-2024 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+use std::collections::HashMap;
+
+use identity_core::common::Url;
+use serde_json::Map;
+use serde_json::Value;
+
+use super::error;
+use super::ContextCache;
+use crate::error::Result;
+
+/// The maximum number of nested remote contexts, protecting against reference cycles.
+const MAX_REMOTE_CONTEXTS: usize = 32;
+
+const KEYWORDS: [&str; 24] = [
+  "@base",
+  "@container",
+  "@context",
+  "@direction",
+  "@graph",
+  "@id",
+  "@import",
+  "@included",
+  "@index",
+  "@json",
+  "@language",
+  "@list",
+  "@nest",
+  "@none",
+  "@prefix",
+  "@propagate",
+  "@protected",
+  "@reverse",
+  "@set",
+  "@type",
+  "@value",
+  "@version",
+  "@vocab",
+  "@default",
+];
+
+/// Containers supported by the processor.
+const CONTAINERS: [&str; 4] = ["@graph", "@language", "@list", "@set"];
+
+pub(super) fn is_keyword(value: &str) -> bool {
+  KEYWORDS.contains(&value)
+}
+
+/// Returns `true` if `value` has the form of a keyword (`@` followed by ALPHA characters only).
+fn looks_like_keyword(value: &str) -> bool {
+  value.strip_prefix('@').map_or(false, |rest| {
+    !rest.is_empty() && rest.chars().all(|c| c.is_ascii_alphabetic())
+  })
+}
+
+pub(super) fn is_absolute_iri(value: &str) -> bool {
+  let Some((scheme, _)) = value.split_once(':') else {
+    return false;
+  };
+  let mut chars = scheme.chars();
+  chars.next().map_or(false, |c| c.is_ascii_alphabetic())
+    && chars.all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.'))
+}
+
+pub(super) fn is_blank_node(value: &str) -> bool {
+  value.starts_with("_:")
+}
+
+/// A term definition of an active context.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub(super) struct TermDefinition {
+  /// The IRI mapping, `None` if the term is explicitly mapped to `null`.
+  pub(super) iri: Option<String>,
+  pub(super) prefix: bool,
+  pub(super) protected: bool,
+  pub(super) type_mapping: Option<String>,
+  pub(super) container: Vec<String>,
+  /// The language mapping, `Some(None)` if the language is explicitly set to `null`.
+  pub(super) language: Option<Option<String>>,
+  /// The property-scoped or type-scoped context.
+  pub(super) context: Option<Value>,
+}
+
+impl TermDefinition {
+  pub(super) fn has_container(&self, container: &str) -> bool {
+    self.container.iter().any(|value| value == container)
+  }
+
+  /// Compares two definitions ignoring their `protected` flag.
+  fn same_definition(&self, other: &Self) -> bool {
+    Self {
+      protected: false,
+      ..self.clone()
+    } == Self {
+      protected: false,
+      ..other.clone()
+    }
+  }
+}
+
+/// An active context.
+///
+/// [More Info](https://www.w3.org/TR/json-ld11-api/#context-processing-algorithms)
+#[derive(Clone, Debug, Default)]
+pub(super) struct Context {
+  base: Option<String>,
+  vocab: Option<String>,
+  pub(super) default_language: Option<String>,
+  pub(super) terms: HashMap<String, TermDefinition>,
+  /// The context to revert to for non-propagated (type-scoped) contexts.
+  pub(super) previous: Option<Box<Context>>,
+}
+
+impl Context {
+  /// Processes the `local` context on top of `self`.
+  pub(super) fn process(
+    &self,
+    local: &Value,
+    context_cache: &ContextCache,
+    override_protected: bool,
+    propagate: bool,
+  ) -> Result<Context> {
+    self.process_remote(local, context_cache, override_protected, propagate, &mut Vec::new())
+  }
+
+  fn process_remote(
+    &self,
+    local: &Value,
+    context_cache: &ContextCache,
+    override_protected: bool,
+    mut propagate: bool,
+    remote_contexts: &mut Vec<String>,
+  ) -> Result<Context> {
+    let mut result: Context = self.clone();
+    if let Some(value) = local.get("@propagate") {
+      propagate = value.as_bool().ok_or_else(|| error("invalid @propagate value"))?;
+    }
+    if !propagate && result.previous.is_none() {
+      result.previous = Some(Box::new(self.clone()));
+    }
+
+    let contexts: &[Value] = match local {
+      Value::Array(contexts) => contexts,
+      context => std::slice::from_ref(context),
+    };
+    for context in contexts {
+      match context {
+        Value::Null => {
+          if !override_protected && result.terms.values().any(|term| term.protected) {
+            return Err(error("invalid context nullification of protected terms"));
+          }
+          let previous: Option<Box<Context>> = (!propagate).then(|| Box::new(result.clone()));
+          result = Context {
+            previous,
+            ..Context::default()
+          };
+        }
+        Value::String(url) => {
+          let url: String = match &result.base {
+            Some(base) if !is_absolute_iri(url) => resolve(base, url)?,
+            _ => url.clone(),
+          };
+          if remote_contexts.len() >= MAX_REMOTE_CONTEXTS {
+            return Err(error("context overflow"));
+          }
+          if remote_contexts.contains(&url) {
+            continue;
+          }
+          let document: &Value = context_cache
+            .get(&url)
+            .ok_or_else(|| error(format!("loading remote context failed: {url} is not cached")))?;
+          let context: &Value = document
+            .get("@context")
+            .ok_or_else(|| error(format!("invalid remote context {url}")))?;
+
+          remote_contexts.push(url);
+          result = result.process_remote(context, context_cache, override_protected, true, remote_contexts)?;
+          remote_contexts.pop();
+        }
+        Value::Object(definitions) => {
+          result.process_definitions(definitions, override_protected, remote_contexts.is_empty())?;
+        }
+        _ => return Err(error("invalid local context")),
+      }
+    }
+    Ok(result)
+  }
+
+  fn process_definitions(
+    &mut self,
+    definitions: &Map<String, Value>,
+    override_protected: bool,
+    is_local: bool,
+  ) -> Result<()> {
+    if let Some(version) = definitions.get("@version") {
+      if version.as_f64() != Some(1.1) {
+        return Err(error("invalid @version value"));
+      }
+    }
+    if definitions.contains_key("@import") {
+      return Err(error("@import is not supported"));
+    }
+    if let Some(base) = definitions.get("@base").filter(|_| is_local) {
+      self.base = match base {
+        Value::Null => None,
+        Value::String(base) if is_absolute_iri(base) => Some(base.clone()),
+        Value::String(base) => match &self.base {
+          Some(current) => Some(resolve(current, base)?),
+          None => return Err(error("invalid base IRI")),
+        },
+        _ => return Err(error("invalid base IRI")),
+      };
+    }
+    if let Some(vocab) = definitions.get("@vocab") {
+      self.vocab = match vocab {
+        Value::Null => None,
+        Value::String(vocab) => Some(
+          self
+            .expand_iri(vocab, true, true)?
+            .filter(|vocab| is_absolute_iri(vocab) || is_blank_node(vocab))
+            .ok_or_else(|| error("invalid vocab mapping"))?,
+        ),
+        _ => return Err(error("invalid vocab mapping")),
+      };
+    }
+    if let Some(language) = definitions.get("@language") {
+      self.default_language = match language {
+        Value::Null => None,
+        Value::String(language) => Some(language.to_lowercase()),
+        _ => return Err(error("invalid default language")),
+      };
+    }
+    let protected: bool = match definitions.get("@protected") {
+      None => false,
+      Some(Value::Bool(protected)) => *protected,
+      Some(_) => return Err(error("invalid @protected value")),
+    };
+
+    let mut definer = TermDefiner {
+      context: self,
+      definitions,
+      defined: HashMap::new(),
+      protected,
+      override_protected,
+    };
+    for term in definitions.keys() {
+      if matches!(
+        term.as_str(),
+        "@base" | "@direction" | "@import" | "@language" | "@propagate" | "@protected" | "@version" | "@vocab"
+      ) {
+        continue;
+      }
+      definer.define(term)?;
+    }
+    Ok(())
+  }
+
+  /// Expands `value` to an IRI, a blank node identifier or a keyword.
+  ///
+  /// [More Info](https://www.w3.org/TR/json-ld11-api/#iri-expansion)
+  pub(super) fn expand_iri(&self, value: &str, document_relative: bool, vocab: bool) -> Result<Option<String>> {
+    if is_keyword(value) {
+      return Ok(Some(value.to_owned()));
+    }
+    if looks_like_keyword(value) {
+      return Ok(None);
+    }
+    if vocab {
+      if let Some(definition) = self.terms.get(value) {
+        return Ok(definition.iri.clone());
+      }
+    }
+    if let Some((prefix, suffix)) = value.split_once(':').filter(|(prefix, _)| !prefix.is_empty()) {
+      if prefix == "_" || suffix.starts_with("//") {
+        return Ok(Some(value.to_owned()));
+      }
+      if let Some(TermDefinition {
+        iri: Some(iri),
+        prefix: true,
+        ..
+      }) = self.terms.get(prefix)
+      {
+        return Ok(Some(format!("{iri}{suffix}")));
+      }
+      if is_absolute_iri(value) {
+        return Ok(Some(value.to_owned()));
+      }
+    }
+    if vocab {
+      if let Some(vocab) = &self.vocab {
+        return Ok(Some(format!("{vocab}{value}")));
+      }
+    }
+    if document_relative {
+      if let Some(base) = &self.base {
+        return resolve(base, value).map(Some);
+      }
+    }
+    Ok(Some(value.to_owned()))
+  }
+}
+
+fn resolve(base: &str, value: &str) -> Result<String> {
+  Url::parse(base)
+    .and_then(|base| base.join(value))
+    .map(|url| url.to_string())
+    .map_err(|_| error(format!("cannot resolve {value} against {base}")))
+}
+
+/// Creates the term definitions of a local context, resolving dependencies between them.
+///
+/// [More Info](https://www.w3.org/TR/json-ld11-api/#create-term-definition)
+struct TermDefiner<'a> {
+  context: &'a mut Context,
+  definitions: &'a Map<String, Value>,
+  /// Whether a term is defined (`true`) or currently being defined (`false`).
+  defined: HashMap<String, bool>,
+  protected: bool,
+  override_protected: bool,
+}
+
+impl TermDefiner<'_> {
+  fn define(&mut self, term: &str) -> Result<()> {
+    match self.defined.get(term) {
+      Some(true) => return Ok(()),
+      Some(false) => return Err(error(format!("cyclic IRI mapping for {term}"))),
+      None => {}
+    }
+    let Some(value) = self.definitions.get(term) else {
+      return Ok(());
+    };
+    self.defined.insert(term.to_owned(), false);
+
+    if term == "@type" {
+      // Only `@container: @set` and `@protected` may be set for `@type`.
+      let valid: bool = value.as_object().map_or(false, |object| {
+        object.iter().all(|(key, value)| match key.as_str() {
+          "@container" => value == "@set",
+          "@protected" => value.is_boolean(),
+          _ => false,
+        })
+      });
+      if !valid {
+        return Err(error("keyword redefinition of @type"));
+      }
+      self.defined.insert(term.to_owned(), true);
+      return Ok(());
+    }
+    if is_keyword(term) {
+      return Err(error(format!("keyword redefinition of {term}")));
+    }
+    if looks_like_keyword(term) {
+      self.defined.insert(term.to_owned(), true);
+      return Ok(());
+    }
+
+    let previous: Option<TermDefinition> = self.context.terms.remove(term);
+    let (definition, simple_term): (Map<String, Value>, bool) = match value {
+      Value::Null => (Map::from_iter([("@id".to_owned(), Value::Null)]), false),
+      Value::String(id) => (Map::from_iter([("@id".to_owned(), Value::String(id.clone()))]), true),
+      Value::Object(definition) => (definition.clone(), false),
+      _ => return Err(error(format!("invalid term definition for {term}"))),
+    };
+
+    let mut result = TermDefinition {
+      protected: match definition.get("@protected") {
+        None => self.protected,
+        Some(Value::Bool(protected)) => *protected,
+        Some(_) => return Err(error("invalid @protected value")),
+      },
+      ..TermDefinition::default()
+    };
+
+    for key in definition.keys() {
+      if !matches!(
+        key.as_str(),
+        "@id" | "@type" | "@container" | "@context" | "@language" | "@direction" | "@prefix" | "@protected"
+      ) {
+        return Err(error(format!("{key} in the definition of {term} is not supported")));
+      }
+    }
+
+    if let Some(type_mapping) = definition.get("@type") {
+      let type_mapping: &str = type_mapping
+        .as_str()
+        .ok_or_else(|| error(format!("invalid type mapping for {term}")))?;
+      let type_mapping: String = self
+        .expand_iri(type_mapping)?
+        .ok_or_else(|| error(format!("invalid type mapping for {term}")))?;
+      if !matches!(type_mapping.as_str(), "@id" | "@json" | "@none" | "@vocab") && !is_absolute_iri(&type_mapping) {
+        return Err(error(format!("invalid type mapping for {term}")));
+      }
+      result.type_mapping = Some(type_mapping);
+    }
+
+    match definition.get("@id") {
+      Some(Value::Null) => {}
+      Some(Value::String(id)) if id != term => {
+        if !is_keyword(id) && looks_like_keyword(id) {
+          self.defined.insert(term.to_owned(), true);
+          return Ok(());
+        }
+        let iri: String = self
+          .expand_iri(id)?
+          .ok_or_else(|| error(format!("invalid IRI mapping for {term}")))?;
+        if iri == "@context" || !(is_keyword(&iri) || is_absolute_iri(&iri) || is_blank_node(&iri)) {
+          return Err(error(format!("invalid IRI mapping for {term}")));
+        }
+        result.prefix = simple_term
+          && !term.contains([':', '/'])
+          && (iri.ends_with([':', '/', '?', '#', '[', ']', '@']) || is_blank_node(&iri));
+        result.iri = Some(iri);
+      }
+      Some(Value::String(_)) | None => {
+        result.iri = Some(self.term_iri(term)?);
+      }
+      Some(_) => return Err(error(format!("invalid IRI mapping for {term}"))),
+    }
+
+    if let Some(container) = definition.get("@container") {
+      let container: Vec<String> = match container {
+        Value::String(container) => vec![container.clone()],
+        Value::Array(containers) => containers
+          .iter()
+          .map(|container| container.as_str().map(ToOwned::to_owned))
+          .collect::<Option<_>>()
+          .ok_or_else(|| error(format!("invalid container mapping for {term}")))?,
+        _ => return Err(error(format!("invalid container mapping for {term}"))),
+      };
+      if let Some(unsupported) = container.iter().find(|value| !CONTAINERS.contains(&value.as_str())) {
+        return Err(error(format!("container {unsupported} is not supported")));
+      }
+      result.container = container;
+    }
+
+    if let Some(context) = definition.get("@context") {
+      result.context = Some(context.clone());
+    }
+
+    if let Some(language) = definition.get("@language") {
+      result.language = match language {
+        Value::Null => Some(None),
+        Value::String(language) => Some(Some(language.to_lowercase())),
+        _ => return Err(error(format!("invalid language mapping for {term}"))),
+      };
+    }
+
+    if let Some(prefix) = definition.get("@prefix") {
+      if term.contains([':', '/']) {
+        return Err(error(format!("invalid term definition for {term}")));
+      }
+      result.prefix = prefix
+        .as_bool()
+        .ok_or_else(|| error(format!("invalid @prefix value for {term}")))?;
+    }
+
+    if let Some(previous) = previous {
+      if previous.protected && !self.override_protected {
+        if !previous.same_definition(&result) {
+          return Err(error(format!("protected term redefinition of {term}")));
+        }
+        result = previous;
+      }
+    }
+
+    self.context.terms.insert(term.to_owned(), result);
+    self.defined.insert(term.to_owned(), true);
+    Ok(())
+  }
+
+  /// Computes the IRI of a term definition without an explicit `@id`.
+  fn term_iri(&mut self, term: &str) -> Result<String> {
+    if let Some((prefix, suffix)) = term.split_once(':').filter(|(prefix, _)| !prefix.is_empty()) {
+      self.define(prefix)?;
+      return match self.context.terms.get(prefix) {
+        Some(TermDefinition { iri: Some(iri), .. }) if prefix != "_" && !suffix.starts_with("//") => {
+          Ok(format!("{iri}{suffix}"))
+        }
+        _ => Ok(term.to_owned()),
+      };
+    }
+    if term.contains('/') {
+      return self
+        .expand_iri(term)?
+        .filter(|iri| is_absolute_iri(iri))
+        .ok_or_else(|| error(format!("invalid IRI mapping for {term}")));
+    }
+    self
+      .context
+      .vocab
+      .as_ref()
+      .map(|vocab| format!("{vocab}{term}"))
+      .ok_or_else(|| error(format!("invalid IRI mapping for {term}")))
+  }
+
+  /// Expands `value` using the vocabulary mapping, defining its dependencies in the local context first.
+  fn expand_iri(&mut self, value: &str) -> Result<Option<String>> {
+    if self.definitions.contains_key(value) && !is_keyword(value) {
+      self.define(value)?;
+    }
+    if let Some((prefix, _)) = value.split_once(':') {
+      if self.definitions.contains_key(prefix) {
+        self.define(prefix)?;
+      }
+    }
+    self.context.expand_iri(value, false, true)
+  }
+}
@@ -0,0 +1,51 @@
+// Copyright 2020-2024 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use serde_json::Value;
+
+/// A local, in-memory store of JSON-LD context documents, used to resolve the remote contexts referenced by a
+/// document during canonicalization.
+///
+/// Contexts are never fetched from the network: every context referenced by a document has to be registered upfront,
+/// otherwise canonicalization fails. This pins the exact context definitions a signature is created and verified
+/// against.
+#[derive(Clone, Debug, Default)]
+pub struct ContextCache {
+  contexts: HashMap<String, Arc<Value>>,
+}
+
+impl ContextCache {
+  /// Creates a new empty [`ContextCache`].
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  /// Registers the context `document` for the given `url`, replacing any previously registered document.
+  ///
+  /// The `document` is the whole JSON-LD document served at `url`, i.e. a JSON object with an `@context` entry.
+  pub fn insert(&mut self, url: impl Into<String>, document: Value) {
+    self.contexts.insert(url.into(), Arc::new(document));
+  }
+
+  /// Registers the context `document` for the given `url` and returns `self`.
+  ///
+  /// See [`ContextCache::insert`].
+  #[must_use]
+  pub fn with_context(mut self, url: impl Into<String>, document: Value) -> Self {
+    self.insert(url, document);
+    self
+  }
+
+  /// Returns the context document registered for `url`, if any.
+  pub fn get(&self, url: &str) -> Option<&Value> {
+    self.contexts.get(url).map(AsRef::as_ref)
+  }
+
+  /// Returns `true` if a context document is registered for `url`.
+  pub fn contains(&self, url: &str) -> bool {
+    self.contexts.contains_key(url)
+  }
+}
@@ -0,0 +1,491 @@
+// Copyright 2020-2024 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+use std::cell::RefCell;
+
+use serde_json::Map;
+use serde_json::Value;
+
+use super::context::is_absolute_iri;
+use super::context::is_blank_node;
+use super::context::is_keyword;
+use super::context::Context;
+use super::context::TermDefinition;
+use super::error;
+use super::ContextCache;
+use crate::error::Result;
+
+/// Expands a JSON-LD `document` into an array of node objects.
+///
+/// [More Info](https://www.w3.org/TR/json-ld11-api/#expansion-algorithm)
+pub(super) fn expand(document: &Value, context_cache: &ContextCache) -> Result<Vec<Value>> {
+  let expander = Expander::new(context_cache);
+  let expanded: Option<Value> = expander.expand(&Context::default(), None, document, false)?;
+
+  Ok(match expanded {
+    None => Vec::new(),
+    Some(Value::Object(mut object)) if object.len() == 1 && object.contains_key("@graph") => {
+      into_array(object.remove("@graph").unwrap_or_default())
+    }
+    Some(value) => into_array(value),
+  })
+}
+
+/// Replaces every blank node of the compact JSON-LD `document` by a `urn:bnid:` IRI, by adding an `@id` to the
+/// node objects that do not have one.
+///
+/// [More Info](https://www.w3.org/TR/vc-di-ecdsa/#skolemizecompactjsonld)
+#[cfg(feature = "bbs-2023")]
+pub(super) fn skolemize(document: &Value, context_cache: &ContextCache) -> Result<Value> {
+  let expander = Expander::new(context_cache);
+  expander.expand(&Context::default(), None, document, false)?;
+  let unidentified: Vec<*const Map<String, Value>> = expander.unidentified.into_inner();
+
+  let mut counter: usize = 0;
+  Ok(skolemize_value(document, &unidentified, &mut counter))
+}
+
+#[cfg(feature = "bbs-2023")]
+fn skolemize_value(value: &Value, unidentified: &[*const Map<String, Value>], counter: &mut usize) -> Value {
+  match value {
+    Value::Array(items) => Value::Array(
+      items
+        .iter()
+        .map(|item| skolemize_value(item, unidentified, counter))
+        .collect(),
+    ),
+    Value::Object(object) => {
+      let mut result: Map<String, Value> = object
+        .iter()
+        .map(|(key, value)| match key.as_str() {
+          "@context" => (key.clone(), value.clone()),
+          _ => (key.clone(), skolemize_value(value, unidentified, counter)),
+        })
+        .collect();
+      if unidentified.iter().any(|node| std::ptr::eq(*node, object)) {
+        result.insert("@id".to_owned(), Value::String(format!("{SKOLEM_PREFIX}b{counter}")));
+        *counter += 1;
+      }
+      Value::Object(result)
+    }
+    value => value.clone(),
+  }
+}
+
+/// The prefix of the IRIs replacing blank nodes in skolemized documents.
+#[cfg(feature = "bbs-2023")]
+pub(super) const SKOLEM_PREFIX: &str = "urn:bnid:";
+
+fn into_array(value: Value) -> Vec<Value> {
+  match value {
+    Value::Array(array) => array,
+    value => vec![value],
+  }
+}
+
+fn is_list_object(value: &Value) -> bool {
+  value.as_object().map_or(false, |object| object.contains_key("@list"))
+}
+
+struct Expander<'a> {
+  context_cache: &'a ContextCache,
+  /// The node objects of the document without an `@id`, identified by their address.
+  unidentified: RefCell<Vec<*const Map<String, Value>>>,
+}
+
+impl<'a> Expander<'a> {
+  fn new(context_cache: &'a ContextCache) -> Self {
+    Self {
+      context_cache,
+      unidentified: RefCell::new(Vec::new()),
+    }
+  }
+
+  fn expand(
+    &self,
+    context: &Context,
+    active_property: Option<&str>,
+    element: &Value,
+    from_map: bool,
+  ) -> Result<Option<Value>> {
+    match element {
+      Value::Null => Ok(None),
+      Value::Array(items) => {
+        let is_list: bool = active_property
+          .and_then(|property| context.terms.get(property))
+          .map_or(false, |definition| definition.has_container("@list"));
+        let mut result: Vec<Value> = Vec::with_capacity(items.len());
+        for item in items {
+          match self.expand(context, active_property, item, from_map)? {
+            Some(Value::Array(expanded)) if is_list => result.push(Value::Object(Map::from_iter([(
+              "@list".to_owned(),
+              Value::Array(expanded),
+            )]))),
+            Some(Value::Array(expanded)) => result.extend(expanded),
+            Some(expanded) => result.push(expanded),
+            None => {}
+          }
+        }
+        Ok(Some(Value::Array(result)))
+      }
+      Value::Object(object) => self.expand_object(context, active_property, object, from_map),
+      scalar => match active_property {
+        None | Some("@graph") => Ok(None),
+        Some(property) => {
+          // Values of `@vocab` typed terms are expanded with the property-scoped context of their term.
+          match context
+            .terms
+            .get(property)
+            .and_then(|definition| definition.context.as_ref())
+          {
+            Some(scoped) => {
+              let active: Context = context.process(scoped, self.context_cache, true, true)?;
+              expand_value(&active, property, scalar).map(Some)
+            }
+            None => expand_value(context, property, scalar).map(Some),
+          }
+        }
+      },
+    }
+  }
+
+  fn expand_object(
+    &self,
+    context: &Context,
+    active_property: Option<&str>,
+    object: &Map<String, Value>,
+    from_map: bool,
+  ) -> Result<Option<Value>> {
+    let property_definition: Option<&TermDefinition> = active_property.and_then(|property| context.terms.get(property));
+    let property_scoped_context: Option<Value> = property_definition.and_then(|definition| definition.context.clone());
+
+    // Revert type-scoped contexts, which do not propagate to nested node objects.
+    let mut active: Context = context.clone();
+    if let Some(previous) = &context.previous {
+      let expanded_keys: Vec<Option<String>> = object
+        .keys()
+        .map(|key| context.expand_iri(key, false, true))
+        .collect::<Result<_>>()?;
+      let is_value: bool = expanded_keys.iter().any(|key| key.as_deref() == Some("@value"));
+      let is_reference: bool = expanded_keys.len() == 1 && expanded_keys[0].as_deref() == Some("@id");
+      if !from_map && !is_value && !is_reference {
+        active = (**previous).clone();
+      }
+    }
+    if let Some(scoped) = &property_scoped_context {
+      active = active.process(scoped, self.context_cache, true, true)?;
+    }
+    if let Some(local) = object.get("@context") {
+      active = active.process(local, self.context_cache, false, true)?;
+    }
+
+    // Apply type-scoped contexts in lexicographical order of the types.
+    let type_scoped_context: Context = active.clone();
+    let mut keys: Vec<&String> = object.keys().collect();
+    keys.sort();
+    for key in &keys {
+      if active.expand_iri(key, false, true)?.as_deref() != Some("@type") {
+        continue;
+      }
+      let mut types: Vec<&str> = match &object[key.as_str()] {
+        Value::String(value) => vec![value.as_str()],
+        Value::Array(values) => values.iter().filter_map(Value::as_str).collect(),
+        _ => Vec::new(),
+      };
+      types.sort_unstable();
+      for type_ in types {
+        if let Some(scoped) = type_scoped_context
+          .terms
+          .get(type_)
+          .and_then(|definition| definition.context.as_ref())
+        {
+          active = active.process(scoped, self.context_cache, false, false)?;
+        }
+      }
+    }
+
+    let mut result: Map<String, Value> = Map::new();
+    for key in keys {
+      if key == "@context" {
+        continue;
+      }
+      let value: &Value = &object[key.as_str()];
+      let expanded_property: String = active
+        .expand_iri(key, false, true)?
+        .filter(|property| is_keyword(property) || property.contains(':'))
+        .ok_or_else(|| error(format!("the property {key} is not defined in the context")))?;
+
+      if is_keyword(&expanded_property) {
+        self.expand_keyword(
+          &active,
+          &type_scoped_context,
+          active_property,
+          &expanded_property,
+          value,
+          &mut result,
+        )?;
+        continue;
+      }
+
+      let definition: Option<&TermDefinition> = active.terms.get(key.as_str());
+      let has_container = |container: &str| definition.map_or(false, |definition| definition.has_container(container));
+      let expanded_value: Option<Value> =
+        if definition.and_then(|definition| definition.type_mapping.as_deref()) == Some("@json") {
+          Some(Value::Object(Map::from_iter([
+            ("@value".to_owned(), value.clone()),
+            ("@type".to_owned(), Value::String("@json".to_owned())),
+          ])))
+        } else if has_container("@language") && value.is_object() {
+          Some(expand_language_map(&active, value)?)
+        } else {
+          self.expand(&active, Some(key), value, false)?
+        };
+      let Some(mut expanded_value) = expanded_value else {
+        continue;
+      };
+
+      if has_container("@list") && !is_list_object(&expanded_value) {
+        expanded_value = Value::Object(Map::from_iter([(
+          "@list".to_owned(),
+          Value::Array(into_array(expanded_value)),
+        )]));
+      }
+      if has_container("@graph") {
+        expanded_value = Value::Array(
+          into_array(expanded_value)
+            .into_iter()
+            .map(|value| Value::Object(Map::from_iter([("@graph".to_owned(), Value::Array(into_array(value)))])))
+            .collect(),
+        );
+      }
+
+      append(&mut result, expanded_property, expanded_value);
+    }
+
+    let expanded: Option<Value> = post_process(result, active_property)?;
+    if let Some(Value::Object(node)) = &expanded {
+      if !["@id", "@value", "@list", "@graph"]
+        .iter()
+        .any(|key| node.contains_key(*key))
+      {
+        self.unidentified.borrow_mut().push(object);
+      }
+    }
+    Ok(expanded)
+  }
+
+  fn expand_keyword(
+    &self,
+    active: &Context,
+    type_scoped_context: &Context,
+    active_property: Option<&str>,
+    keyword: &str,
+    value: &Value,
+    result: &mut Map<String, Value>,
+  ) -> Result<()> {
+    if result.contains_key(keyword) && keyword != "@type" {
+      return Err(error(format!("colliding keywords: {keyword}")));
+    }
+
+    let expanded: Value = match keyword {
+      "@id" => {
+        let id: &str = value.as_str().ok_or_else(|| error("invalid @id value"))?;
+        Value::String(
+          active
+            .expand_iri(id, true, false)?
+            .ok_or_else(|| error("invalid @id value"))?,
+        )
+      }
+      "@type" => {
+        let types: Vec<&str> = match value {
+          Value::String(value) => vec![value.as_str()],
+          Value::Array(values) => values
+            .iter()
+            .map(Value::as_str)
+            .collect::<Option<_>>()
+            .ok_or_else(|| error("invalid type value"))?,
+          _ => return Err(error("invalid type value")),
+        };
+        let mut expanded: Vec<Value> = match result.remove("@type") {
+          Some(Value::Array(existing)) => existing,
+          _ => Vec::new(),
+        };
+        for type_ in types {
+          let type_: String = type_scoped_context
+            .expand_iri(type_, true, true)?
+            .ok_or_else(|| error(format!("the type {type_} is not defined in the context")))?;
+          expanded.push(Value::String(type_));
+        }
+        Value::Array(expanded)
+      }
+      "@graph" => Value::Array(
+        self
+          .expand(active, Some("@graph"), value, false)?
+          .map(into_array)
+          .unwrap_or_default(),
+      ),
+      "@value" => {
+        if value.is_object() || value.is_array() {
+          // Only JSON literals may have structured values, they are expanded through the type mapping.
+          return Err(error("invalid value object value"));
+        }
+        value.clone()
+      }
+      "@language" => Value::String(
+        value
+          .as_str()
+          .ok_or_else(|| error("invalid language-tagged string"))?
+          .to_lowercase(),
+      ),
+      "@direction" | "@index" => {
+        if !value.is_string() {
+          return Err(error(format!("invalid {keyword} value")));
+        }
+        value.clone()
+      }
+      "@list" => {
+        if matches!(active_property, None | Some("@graph")) {
+          return Ok(());
+        }
+        Value::Array(
+          self
+            .expand(active, active_property, value, false)?
+            .map(into_array)
+            .unwrap_or_default(),
+        )
+      }
+      "@set" => match self.expand(active, active_property, value, false)? {
+        Some(expanded) => expanded,
+        None => return Ok(()),
+      },
+      _ => return Err(error(format!("{keyword} is not supported"))),
+    };
+    result.insert(keyword.to_owned(), expanded);
+    Ok(())
+  }
+}
+
+fn append(result: &mut Map<String, Value>, property: String, value: Value) {
+  let Value::Array(values) = result.entry(property).or_insert_with(|| Value::Array(Vec::new())) else {
+    unreachable!("properties are always expanded to arrays");
+  };
+  values.extend(into_array(value));
+}
+
+/// Expands a scalar `value` of the given `active_property`.
+///
+/// [More Info](https://www.w3.org/TR/json-ld11-api/#value-expansion)
+fn expand_value(context: &Context, active_property: &str, value: &Value) -> Result<Value> {
+  let definition: Option<&TermDefinition> = context.terms.get(active_property);
+  let type_mapping: Option<&str> = definition.and_then(|definition| definition.type_mapping.as_deref());
+
+  if let (Some(type_mapping @ ("@id" | "@vocab")), Value::String(id)) = (type_mapping, value) {
+    let id: String = context
+      .expand_iri(id, true, type_mapping == "@vocab")?
+      .ok_or_else(|| error("invalid @id value"))?;
+    return Ok(Value::Object(Map::from_iter([("@id".to_owned(), Value::String(id))])));
+  }
+
+  let mut result: Map<String, Value> = Map::from_iter([("@value".to_owned(), value.clone())]);
+  match type_mapping {
+    Some("@id" | "@vocab" | "@none") | None => {
+      if value.is_string() {
+        let language: Option<&String> = match definition.and_then(|definition| definition.language.as_ref()) {
+          Some(language) => language.as_ref(),
+          None => context.default_language.as_ref(),
+        };
+        if let Some(language) = language {
+          result.insert("@language".to_owned(), Value::String(language.clone()));
+        }
+      }
+    }
+    Some(type_mapping) => {
+      result.insert("@type".to_owned(), Value::String(type_mapping.to_owned()));
+    }
+  }
+  Ok(Value::Object(result))
+}
+
+fn expand_language_map(context: &Context, value: &Value) -> Result<Value> {
+  let mut result: Vec<Value> = Vec::new();
+  let mut languages: Vec<(&String, &Value)> = value.as_object().into_iter().flatten().collect();
+  languages.sort_by_key(|(language, _)| *language);
+  for (language, values) in languages {
+    let expanded_language: Option<String> = context.expand_iri(language, false, true)?;
+    let values: &[Value] = match values {
+      Value::Array(values) => values,
+      value => std::slice::from_ref(value),
+    };
+    for value in values {
+      match value {
+        Value::Null => continue,
+        Value::String(_) => {}
+        _ => return Err(error("invalid language map value")),
+      }
+      let mut item: Map<String, Value> = Map::from_iter([("@value".to_owned(), value.clone())]);
+      if expanded_language.as_deref() != Some("@none") {
+        item.insert("@language".to_owned(), Value::String(language.to_lowercase()));
+      }
+      result.push(Value::Object(item));
+    }
+  }
+  Ok(Value::Array(result))
+}
+
+fn post_process(mut result: Map<String, Value>, active_property: Option<&str>) -> Result<Option<Value>> {
+  if let Some(value) = result.get("@value") {
+    if result
+      .keys()
+      .any(|key| !matches!(key.as_str(), "@direction" | "@index" | "@language" | "@type" | "@value"))
+    {
+      return Err(error("invalid value object"));
+    }
+    let type_: Option<&Value> = result.get("@type");
+    if type_.map_or(false, |type_| type_ == "@json") {
+      return Ok(Some(Value::Object(result)));
+    }
+    if value.is_null() {
+      return Ok(None);
+    }
+    if result.contains_key("@language") && !value.is_string() {
+      return Err(error("invalid language-tagged value"));
+    }
+    if let Some(type_) = type_ {
+      let valid: bool = match type_ {
+        Value::String(type_) => is_absolute_iri(type_) && !is_blank_node(type_),
+        Value::Array(types) => types.len() == 1 && types[0].as_str().map_or(false, is_absolute_iri),
+        _ => false,
+      };
+      if !valid {
+        return Err(error("invalid typed value"));
+      }
+      if let Some(Value::Array(mut types)) = result.remove("@type") {
+        result.insert("@type".to_owned(), types.remove(0));
+      }
+    }
+  } else if result.contains_key("@set") || result.contains_key("@list") {
+    if result
+      .keys()
+      .any(|key| !matches!(key.as_str(), "@set" | "@list" | "@index"))
+    {
+      return Err(error("invalid set or list object"));
+    }
+    if let Some(set) = result.remove("@set") {
+      return Ok(Some(set));
+    }
+  }
+
+  if result.len() == 1 && result.contains_key("@language") {
+    return Ok(None);
+  }
+  if matches!(active_property, None | Some("@graph")) {
+    let is_free_floating: bool = result.is_empty()
+      || result.contains_key("@value")
+      || result.contains_key("@list")
+      || (result.len() == 1 && result.contains_key("@id"));
+    if is_free_floating {
+      return Ok(None);
+    }
+  }
+  Ok(Some(Value::Object(result)))
+}
@@ -0,0 +1,185 @@
+// Copyright 2020-2024 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+//! A minimal JSON-LD 1.1 processor producing the canonical N-Quads of a document.
+//!
+//! Only the subset of JSON-LD required for verifiable credentials is supported. Documents are processed in "safe
+//! mode": properties and types that do not expand to absolute IRIs, as well as unsupported JSON-LD features
+//! (e.g. `@reverse`, `@nest`, `@index` and `@id` maps), are rejected instead of being silently dropped, so that no
+//! data can escape the signature.
+
+mod canonicalization;
+mod context;
+mod context_cache;
+mod expansion;
+mod rdf;
+
+#[cfg(feature = "bbs-2023")]
+use std::collections::HashMap;
+
+use serde_json::Value;
+
+pub use self::context_cache::ContextCache;
+use crate::error::Error;
+use crate::error::Result;
+
+/// Canonicalizes the JSON-LD `document` into N-Quads according to
+/// [RDFC-1.0](https://www.w3.org/TR/rdf-canon/), resolving remote contexts through `context_cache`.
+pub(crate) fn canonicalize(document: &Value, context_cache: &ContextCache) -> Result<String> {
+  let expanded: Vec<Value> = expansion::expand(document, context_cache)?;
+  let quads: Vec<rdf::Quad> = rdf::to_rdf(&expanded)?;
+  canonicalization::canonicalize(quads)
+}
+
+/// Returns a copy of the compact JSON-LD `document` in which every blank node is identified by a `urn:bnid:` IRI.
+#[cfg(feature = "bbs-2023")]
+pub(crate) fn skolemize(document: &Value, context_cache: &ContextCache) -> Result<Value> {
+  expansion::skolemize(document, context_cache)
+}
+
+/// An RDF dataset that can be serialized into N-Quads with custom blank node labels.
+#[cfg(feature = "bbs-2023")]
+pub(crate) struct Dataset(Vec<rdf::Quad>);
+
+#[cfg(feature = "bbs-2023")]
+impl Dataset {
+  /// Converts the JSON-LD `document` into an RDF dataset.
+  pub(crate) fn from_document(document: &Value, context_cache: &ContextCache) -> Result<Self> {
+    let expanded: Vec<Value> = expansion::expand(document, context_cache)?;
+    rdf::to_rdf(&expanded).map(Self)
+  }
+
+  /// Converts a document skolemized with [`skolemize`] into an RDF dataset, in which the blank nodes are labeled with
+  /// their skolem identifiers.
+  ///
+  /// Blank nodes that could not be skolemized, e.g. those of lists or graph containers, are rejected, since they cannot
+  /// be matched against other selections of the same document.
+  pub(crate) fn from_skolemized_document(document: &Value, context_cache: &ContextCache) -> Result<Self> {
+    let mut dataset: Self = Self::from_document(document, context_cache)?;
+    if dataset.0.iter().any(|quad| quad.blank_node_components().next().is_some()) {
+      return Err(error("the document contains blank nodes that cannot be skolemized"));
+    }
+    let deskolemize = |term: &mut rdf::Term| {
+      if let rdf::Term::Iri(iri) = term {
+        if let Some(label) = iri.strip_prefix(expansion::SKOLEM_PREFIX) {
+          *term = rdf::Term::BlankNode(label.to_owned());
+        }
+      }
+    };
+    for quad in dataset.0.iter_mut() {
+      deskolemize(&mut quad.subject);
+      deskolemize(&mut quad.object);
+      if let Some(graph) = quad.graph.as_mut() {
+        deskolemize(graph);
+      }
+    }
+    Ok(dataset)
+  }
+
+  /// Returns the map from the current blank node labels of the dataset to their canonical labels according to
+  /// RDFC-1.0, e.g. `c14n0`.
+  pub(crate) fn canonical_id_map(&self) -> Result<HashMap<String, String>> {
+    canonicalization::issue_canonical_labels(&self.0)
+  }
+
+  /// Serializes the dataset into sorted and deduplicated N-Quads, replacing every blank node label through
+  /// `label_map`.
+  pub(crate) fn to_nquads(&self, label_map: &HashMap<String, String>) -> Result<Vec<String>> {
+    let mut missing: bool = false;
+    let mut nquads: Vec<String> = self
+      .0
+      .iter()
+      .map(|quad| {
+        canonicalization::relabel(quad, |label| {
+          label_map.get(label).cloned().unwrap_or_else(|| {
+            missing = true;
+            String::new()
+          })
+        })
+        .to_nquad()
+      })
+      .collect();
+    if missing {
+      return Err(error("the label map does not cover every blank node"));
+    }
+    nquads.sort();
+    nquads.dedup();
+    Ok(nquads)
+  }
+}
+
+fn error(message: impl Into<String>) -> Error {
+  Error::DataIntegrityError(message.into().into())
+}
+
+#[cfg(test)]
+mod tests {
+  use serde_json::json;
+
+  use super::*;
+
+  fn context() -> Value {
+    json!({
+      "@context": {
+        "@protected": true,
+        "id": "@id",
+        "type": "@type",
+        "name": "https://schema.org/name",
+        "knows": {"@id": "https://schema.org/knows", "@type": "@id"},
+        "age": {"@id": "https://schema.org/age", "@type": "http://www.w3.org/2001/XMLSchema#integer"},
+        "Person": "https://schema.org/Person"
+      }
+    })
+  }
+
+  #[test]
+  fn test_canonicalize_with_cached_context() {
+    let cache = ContextCache::new().with_context("https://example.org/context", context());
+    let document = json!({
+      "@context": "https://example.org/context",
+      "id": "did:example:alice",
+      "type": "Person",
+      "name": "Alice",
+      "age": 42,
+      "knows": {"name": "Bob"}
+    });
+
+    assert_eq!(
+      canonicalize(&document, &cache).unwrap(),
+      concat!(
+        "<did:example:alice> <http://www.w3.org/1999/02/22-rdf-syntax-ns#type> <https://schema.org/Person> .\n",
+        "<did:example:alice> <https://schema.org/age> \"42\"^^<http://www.w3.org/2001/XMLSchema#integer> .\n",
+        "<did:example:alice> <https://schema.org/knows> _:c14n0 .\n",
+        "<did:example:alice> <https://schema.org/name> \"Alice\" .\n",
+        "_:c14n0 <https://schema.org/name> \"Bob\" .\n",
+      )
+    );
+  }
+
+  #[test]
+  fn test_contexts_are_not_fetched() {
+    let document = json!({"@context": "https://example.org/context", "name": "Alice"});
+    assert!(canonicalize(&document, &ContextCache::new()).is_err());
+  }
+
+  #[test]
+  fn test_undefined_terms_are_rejected() {
+    let cache = ContextCache::new().with_context("https://example.org/context", context());
+    let document = json!({
+      "@context": "https://example.org/context",
+      "id": "did:example:alice",
+      "nickname": "Al"
+    });
+    assert!(canonicalize(&document, &cache).is_err());
+  }
+
+  #[test]
+  fn test_protected_terms_cannot_be_redefined() {
+    let cache = ContextCache::new().with_context("https://example.org/context", context());
+    let document = json!({
+      "@context": ["https://example.org/context", {"name": "https://example.org/other"}],
+      "name": "Alice"
+    });
+    assert!(canonicalize(&document, &cache).is_err());
+  }
+}
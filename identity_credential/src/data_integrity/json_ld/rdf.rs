@@ -0,0 +1,328 @@
+// Copyright 2020-2024 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+use std::collections::BTreeSet;
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::fmt::Formatter;
+
+use identity_core::convert::ToJson;
+use serde_json::Map;
+use serde_json::Number;
+use serde_json::Value;
+
+use super::context::is_absolute_iri;
+use super::context::is_blank_node;
+use super::context::is_keyword;
+use super::error;
+use crate::error::Result;
+
+const RDF_TYPE: &str = "http://www.w3.org/1999/02/22-rdf-syntax-ns#type";
+const RDF_FIRST: &str = "http://www.w3.org/1999/02/22-rdf-syntax-ns#first";
+const RDF_REST: &str = "http://www.w3.org/1999/02/22-rdf-syntax-ns#rest";
+const RDF_NIL: &str = "http://www.w3.org/1999/02/22-rdf-syntax-ns#nil";
+const RDF_JSON: &str = "http://www.w3.org/1999/02/22-rdf-syntax-ns#JSON";
+const RDF_LANG_STRING: &str = "http://www.w3.org/1999/02/22-rdf-syntax-ns#langString";
+const XSD_BOOLEAN: &str = "http://www.w3.org/2001/XMLSchema#boolean";
+const XSD_DOUBLE: &str = "http://www.w3.org/2001/XMLSchema#double";
+const XSD_INTEGER: &str = "http://www.w3.org/2001/XMLSchema#integer";
+const XSD_STRING: &str = "http://www.w3.org/2001/XMLSchema#string";
+
+/// An RDF term.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub(super) enum Term {
+  Iri(String),
+  /// A blank node, identified by its label without the `_:` prefix.
+  BlankNode(String),
+  Literal {
+    value: String,
+    datatype: String,
+    language: Option<String>,
+  },
+}
+
+impl Term {
+  pub(super) fn blank_node_label(&self) -> Option<&str> {
+    match self {
+      Self::BlankNode(label) => Some(label),
+      _ => None,
+    }
+  }
+}
+
+impl Display for Term {
+  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    match self {
+      Self::Iri(iri) => write!(f, "<{iri}>"),
+      Self::BlankNode(label) => write!(f, "_:{label}"),
+      Self::Literal {
+        value,
+        datatype,
+        language,
+      } => {
+        f.write_str("\"")?;
+        for c in value.chars() {
+          match c {
+            '"' => f.write_str("\\\"")?,
+            '\\' => f.write_str("\\\\")?,
+            '\n' => f.write_str("\\n")?,
+            '\r' => f.write_str("\\r")?,
+            '\u{0}'..='\u{1f}' | '\u{7f}' => write!(f, "\\u{:04X}", c as u32)?,
+            c => write!(f, "{c}")?,
+          }
+        }
+        f.write_str("\"")?;
+        match language {
+          Some(language) => write!(f, "@{language}"),
+          None if datatype != XSD_STRING => write!(f, "^^<{datatype}>"),
+          None => Ok(()),
+        }
+      }
+    }
+  }
+}
+
+/// An RDF quad, i.e. a triple in the default graph if `graph` is `None`.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub(super) struct Quad {
+  pub(super) subject: Term,
+  pub(super) predicate: Term,
+  pub(super) object: Term,
+  pub(super) graph: Option<Term>,
+}
+
+impl Quad {
+  /// Serializes the quad as an N-Quads statement including the trailing newline.
+  pub(super) fn to_nquad(&self) -> String {
+    match &self.graph {
+      Some(graph) => format!("{} {} {} {} .\n", self.subject, self.predicate, self.object, graph),
+      None => format!("{} {} {} .\n", self.subject, self.predicate, self.object),
+    }
+  }
+
+  /// Returns the components of the quad that may be blank nodes.
+  pub(super) fn blank_node_components(&self) -> impl Iterator<Item = &Term> {
+    [Some(&self.subject), Some(&self.object), self.graph.as_ref()]
+      .into_iter()
+      .flatten()
+      .filter(|term| matches!(term, Term::BlankNode(_)))
+  }
+}
+
+/// Converts an expanded JSON-LD document into an RDF dataset.
+///
+/// [More Info](https://www.w3.org/TR/json-ld11-api/#deserialize-json-ld-to-rdf-algorithm)
+pub(super) fn to_rdf(expanded: &[Value]) -> Result<Vec<Quad>> {
+  let mut builder = DatasetBuilder::default();
+  for node in expanded {
+    let node: &Map<String, Value> = node.as_object().ok_or_else(|| error("invalid node object"))?;
+    builder.node(node, None)?;
+  }
+  Ok(builder.quads.into_iter().collect())
+}
+
+#[derive(Default)]
+struct DatasetBuilder {
+  quads: BTreeSet<Quad>,
+  blank_nodes: HashMap<String, String>,
+  counter: usize,
+}
+
+impl DatasetBuilder {
+  fn fresh_blank_node(&mut self) -> Term {
+    let label: String = format!("b{}", self.counter);
+    self.counter += 1;
+    Term::BlankNode(label)
+  }
+
+  /// Maps an IRI or blank node identifier of the document to an RDF term.
+  fn resource(&mut self, id: &str) -> Result<Term> {
+    if let Some(label) = id.strip_prefix("_:") {
+      if let Some(relabeled) = self.blank_nodes.get(label) {
+        return Ok(Term::BlankNode(relabeled.clone()));
+      }
+      let Term::BlankNode(relabeled) = self.fresh_blank_node() else {
+        unreachable!();
+      };
+      self.blank_nodes.insert(label.to_owned(), relabeled.clone());
+      return Ok(Term::BlankNode(relabeled));
+    }
+    if !is_absolute_iri(id) {
+      return Err(error(format!("relative IRI {id} cannot be converted to RDF")));
+    }
+    Ok(Term::Iri(id.to_owned()))
+  }
+
+  fn node(&mut self, node: &Map<String, Value>, graph: Option<&Term>) -> Result<Term> {
+    let subject: Term = match node.get("@id") {
+      Some(Value::String(id)) => self.resource(id)?,
+      Some(_) => return Err(error("invalid @id value")),
+      None => self.fresh_blank_node(),
+    };
+
+    for type_ in node.get("@type").and_then(Value::as_array).into_iter().flatten() {
+      let type_: &str = type_.as_str().ok_or_else(|| error("invalid type value"))?;
+      let object: Term = self.resource(type_)?;
+      self.insert(subject.clone(), RDF_TYPE, object, graph);
+    }
+
+    for (property, values) in node {
+      if is_keyword(property) {
+        continue;
+      }
+      if is_blank_node(property) || !is_absolute_iri(property) {
+        return Err(error(format!("the property {property} cannot be converted to RDF")));
+      }
+      for value in values.as_array().ok_or_else(|| error("invalid property value"))? {
+        let object: Term = self.object(value, graph)?;
+        self.insert(subject.clone(), property, object, graph);
+      }
+    }
+
+    if let Some(nodes) = node.get("@graph") {
+      for node in nodes.as_array().ok_or_else(|| error("invalid @graph value"))? {
+        let node: &Map<String, Value> = node.as_object().ok_or_else(|| error("invalid node object"))?;
+        self.node(node, Some(&subject))?;
+      }
+    }
+
+    Ok(subject)
+  }
+
+  fn object(&mut self, item: &Value, graph: Option<&Term>) -> Result<Term> {
+    let item: &Map<String, Value> = item.as_object().ok_or_else(|| error("invalid property value"))?;
+    if let Some(value) = item.get("@value") {
+      return literal(value, item);
+    }
+    if let Some(list) = item.get("@list") {
+      return self.list(list.as_array().ok_or_else(|| error("invalid @list value"))?, graph);
+    }
+    self.node(item, graph)
+  }
+
+  fn list(&mut self, items: &[Value], graph: Option<&Term>) -> Result<Term> {
+    let nodes: Vec<Term> = items.iter().map(|_| self.fresh_blank_node()).collect();
+    for (index, item) in items.iter().enumerate() {
+      let object: Term = self.object(item, graph)?;
+      self.insert(nodes[index].clone(), RDF_FIRST, object, graph);
+      let rest: Term = nodes
+        .get(index + 1)
+        .cloned()
+        .unwrap_or_else(|| Term::Iri(RDF_NIL.to_owned()));
+      self.insert(nodes[index].clone(), RDF_REST, rest, graph);
+    }
+    Ok(
+      nodes
+        .into_iter()
+        .next()
+        .unwrap_or_else(|| Term::Iri(RDF_NIL.to_owned())),
+    )
+  }
+
+  fn insert(&mut self, subject: Term, predicate: &str, object: Term, graph: Option<&Term>) {
+    self.quads.insert(Quad {
+      subject,
+      predicate: Term::Iri(predicate.to_owned()),
+      object,
+      graph: graph.cloned(),
+    });
+  }
+}
+
+/// Converts a value object into a literal.
+///
+/// [More Info](https://www.w3.org/TR/json-ld11-api/#object-to-rdf-conversion)
+fn literal(value: &Value, item: &Map<String, Value>) -> Result<Term> {
+  let datatype: Option<&str> = item.get("@type").and_then(Value::as_str);
+  if let Some(datatype) = datatype.filter(|datatype| *datatype != "@json") {
+    if !is_absolute_iri(datatype) {
+      return Err(error(format!("the datatype {datatype} cannot be converted to RDF")));
+    }
+  }
+  let language: Option<String> = item.get("@language").and_then(Value::as_str).map(ToOwned::to_owned);
+
+  let (value, default_datatype): (String, &str) = match (value, datatype) {
+    (value, Some("@json")) => {
+      let canonical: Vec<u8> = value
+        .to_jcs()
+        .map_err(|err| error(format!("invalid JSON literal: {err}")))?;
+      let canonical: String = String::from_utf8(canonical).map_err(|err| error(err.to_string()))?;
+      return Ok(Term::Literal {
+        value: canonical,
+        datatype: RDF_JSON.to_owned(),
+        language: None,
+      });
+    }
+    (Value::Bool(value), _) => (value.to_string(), XSD_BOOLEAN),
+    (Value::Number(number), datatype) => number_lexical_form(number, datatype),
+    (Value::String(value), _) => (value.clone(), XSD_STRING),
+    _ => return Err(error("invalid value object value")),
+  };
+
+  Ok(match language {
+    Some(language) if datatype.is_none() => Term::Literal {
+      value,
+      datatype: RDF_LANG_STRING.to_owned(),
+      language: Some(language),
+    },
+    _ => Term::Literal {
+      value,
+      datatype: datatype.unwrap_or(default_datatype).to_owned(),
+      language: None,
+    },
+  })
+}
+
+fn number_lexical_form(number: &Number, datatype: Option<&str>) -> (String, &'static str) {
+  if datatype != Some(XSD_DOUBLE) {
+    if let Some(integer) = number.as_i64().map(i128::from).or(number.as_u64().map(i128::from)) {
+      return (integer.to_string(), XSD_INTEGER);
+    }
+  }
+  let value: f64 = number.as_f64().unwrap_or_default();
+  if datatype != Some(XSD_DOUBLE) && value.fract() == 0.0 && value.abs() < 1e21 {
+    return (format!("{value:.0}"), XSD_INTEGER);
+  }
+
+  // The canonical lexical form of an xsd:double, e.g. `1.1E0`.
+  let exponential: String = format!("{value:.15e}");
+  let (mantissa, exponent) = exponential.split_once('e').unwrap_or((&exponential, "0"));
+  let mantissa: &str = mantissa.trim_end_matches('0');
+  let mantissa: String = if mantissa.ends_with('.') {
+    format!("{mantissa}0")
+  } else {
+    mantissa.to_owned()
+  };
+  (format!("{mantissa}E{exponent}"), XSD_DOUBLE)
+}
+
+#[cfg(test)]
+mod tests {
+  use serde_json::json;
+
+  use super::*;
+
+  #[test]
+  fn test_number_lexical_form() {
+    let lexical_form = |value: Value, datatype: Option<&str>| match value {
+      Value::Number(number) => number_lexical_form(&number, datatype).0,
+      _ => unreachable!(),
+    };
+    assert_eq!(lexical_form(json!(5), None), "5");
+    assert_eq!(lexical_form(json!(-5.0), None), "-5");
+    assert_eq!(lexical_form(json!(1.1), None), "1.1E0");
+    assert_eq!(lexical_form(json!(1.5e-7), None), "1.5E-7");
+    assert_eq!(lexical_form(json!(1e21), None), "1.0E21");
+    assert_eq!(lexical_form(json!(5), Some(XSD_DOUBLE)), "5.0E0");
+  }
+
+  #[test]
+  fn test_literal_escaping() {
+    let literal = Term::Literal {
+      value: "a\"b\\c\nd\re\tf".to_owned(),
+      datatype: XSD_STRING.to_owned(),
+      language: None,
+    };
+    assert_eq!(literal.to_string(), r#""a\"b\\c\nd\re\u0009f""#);
+  }
+}
@@ -0,0 +1,27 @@
+// Copyright 2020-2024 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+//! Securing credentials and presentations with embedded
+//! [Data Integrity](https://www.w3.org/TR/vc-data-integrity/) proofs.
+//!
+//! JSON-LD contexts are never fetched from the network: documents secured with RDFC based cryptosuites can only be
+//! processed if all of their contexts are present in a [`ContextCache`].
+//!
+//! With the `bbs-2023` feature, credentials can be secured with selectively disclosable BBS proofs created with the
+//! same BLS12-381 G2 keys as JSON Proof Tokens.
+
+#[cfg(feature = "bbs-2023")]
+pub(crate) mod bbs_2023;
+mod cryptosuite;
+mod json_ld;
+mod proof;
+
+#[cfg(feature = "bbs-2023")]
+pub use self::bbs_2023::derive_bbs_2023_credential;
+#[cfg(feature = "bbs-2023")]
+pub use self::bbs_2023::Bbs2023SigningInput;
+pub use self::cryptosuite::Cryptosuite;
+pub use self::json_ld::ContextCache;
+pub use self::proof::DataIntegrityProof;
+pub use self::proof::ProofPurpose;
+pub use self::proof::DATA_INTEGRITY_PROOF_TYPE;
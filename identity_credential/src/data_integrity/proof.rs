@@ -0,0 +1,351 @@
+// Copyright 2020-2024 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+use core::fmt::Display;
+use core::fmt::Formatter;
+
+use identity_core::common::Context;
+use identity_core::common::Object;
+use identity_core::common::OneOrMany;
+use identity_core::common::Timestamp;
+use identity_core::convert::Base;
+use identity_core::convert::BaseEncoding;
+use identity_core::convert::FromJson;
+use identity_core::convert::ToJson;
+use serde::Deserialize;
+use serde::Serialize;
+use serde_json::Value;
+
+use super::ContextCache;
+use super::Cryptosuite;
+use crate::credential::Proof;
+use crate::error::Error;
+use crate::error::Result;
+
+/// The `type` of a [`DataIntegrityProof`].
+pub const DATA_INTEGRITY_PROOF_TYPE: &str = "DataIntegrityProof";
+
+/// The reason a [`DataIntegrityProof`] was created, corresponding to a verification relationship of the signer.
+///
+/// [More Info](https://www.w3.org/TR/vc-data-integrity/#proof-purposes)
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+pub enum ProofPurpose {
+  /// Asserting a claim, e.g. issuing a credential.
+  AssertionMethod,
+  /// Authenticating the signer, e.g. presenting credentials.
+  Authentication,
+  /// Granting a capability.
+  CapabilityInvocation,
+  /// Delegating a capability.
+  CapabilityDelegation,
+}
+
+impl ProofPurpose {
+  /// Returns the name of the proof purpose.
+  pub const fn name(self) -> &'static str {
+    match self {
+      Self::AssertionMethod => "assertionMethod",
+      Self::Authentication => "authentication",
+      Self::CapabilityInvocation => "capabilityInvocation",
+      Self::CapabilityDelegation => "capabilityDelegation",
+    }
+  }
+}
+
+impl Display for ProofPurpose {
+  fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+    f.write_str(self.name())
+  }
+}
+
+/// A Data Integrity proof embedded in a credential or presentation.
+///
+/// A [`Proof`] with the `DataIntegrityProof` type can be converted to and from this representation.
+///
+/// [More Info](https://www.w3.org/TR/vc-data-integrity/#dataintegrityproof)
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DataIntegrityProof {
+  /// The JSON-LD context(s) of the proof, present for JCS based cryptosuites.
+  #[serde(rename = "@context", skip_serializing_if = "Option::is_none")]
+  pub context: Option<OneOrMany<Context>>,
+  /// The type of the proof, i.e. [`DATA_INTEGRITY_PROOF_TYPE`].
+  #[serde(rename = "type")]
+  pub type_: String,
+  /// The cryptosuite used to create the proof.
+  pub cryptosuite: Cryptosuite,
+  /// The date and time the proof was created.
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub created: Option<Timestamp>,
+  /// The date and time the proof expires.
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub expires: Option<Timestamp>,
+  /// The identifier of the verification method that can be used to verify the proof.
+  pub verification_method: String,
+  /// The reason the proof was created.
+  pub proof_purpose: ProofPurpose,
+  /// A value provided by the verifier to prevent replay attacks.
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub challenge: Option<String>,
+  /// The security domain in which the proof is meant to be used.
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub domain: Option<String>,
+  /// A value provided by the signer to make the proof unique.
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub nonce: Option<String>,
+  /// The multibase (base58-btc) encoded signature.
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub proof_value: Option<String>,
+}
+
+impl DataIntegrityProof {
+  /// Creates a new unsigned [`DataIntegrityProof`].
+  pub fn new(cryptosuite: Cryptosuite, verification_method: impl Into<String>, proof_purpose: ProofPurpose) -> Self {
+    Self {
+      context: None,
+      type_: DATA_INTEGRITY_PROOF_TYPE.to_owned(),
+      cryptosuite,
+      created: None,
+      expires: None,
+      verification_method: verification_method.into(),
+      proof_purpose,
+      challenge: None,
+      domain: None,
+      nonce: None,
+      proof_value: None,
+    }
+  }
+
+  /// Computes the data that is signed by this proof over the given `unsecured_document`.
+  ///
+  /// Any `proof` property of `unsecured_document` is ignored. The proof configuration, i.e. this proof without its
+  /// `proofValue`, is hashed under the proof's `@context` if set, and under the document's `@context` otherwise.
+  ///
+  /// # Errors
+  /// Fails if either the document or the proof configuration cannot be canonicalized, e.g. because a JSON-LD context
+  /// required by an RDFC based cryptosuite is missing from `context_cache`.
+  pub fn hash_data<S: Serialize + ?Sized>(
+    &self,
+    unsecured_document: &S,
+    context_cache: &ContextCache,
+  ) -> Result<Vec<u8>> {
+    let document: Value = unsecured_document_value(unsecured_document)?;
+    let proof_config: Value = self.proof_config(&document)?;
+    self.cryptosuite.hash_data(&document, &proof_config, context_cache)
+  }
+
+  /// Returns the proof configuration, i.e. this proof without its `proofValue`, under the `@context` of the proof if
+  /// set, and under the `@context` of `unsecured_document` otherwise.
+  pub(crate) fn proof_config(&self, unsecured_document: &Value) -> Result<Value> {
+    let mut proof_config: Value = self.to_json_value().map_err(data_integrity_error)?;
+    let proof_config_object = proof_config
+      .as_object_mut()
+      .ok_or_else(|| Error::DataIntegrityError("the proof must serialize to a JSON object".into()))?;
+    proof_config_object.remove("proofValue");
+    if let Some(context) = unsecured_document.get("@context").filter(|_| self.context.is_none()) {
+      proof_config_object.insert("@context".to_owned(), context.clone());
+    }
+    Ok(proof_config)
+  }
+
+  /// Sets the `proofValue` to the multibase encoding of `signature`.
+  pub fn set_proof_value(&mut self, signature: &[u8]) {
+    self.proof_value = Some(BaseEncoding::encode_multibase(signature, Some(Base::Base58Btc)));
+  }
+
+  /// Decodes the signature from the multibase (base58-btc) encoded `proofValue`.
+  pub fn decode_proof_value(&self) -> Result<Vec<u8>> {
+    let proof_value: &str = self
+      .proof_value
+      .as_deref()
+      .ok_or_else(|| Error::DataIntegrityError("missing proofValue".into()))?;
+    if !proof_value.starts_with('z') {
+      return Err(Error::DataIntegrityError(
+        "proofValue must be base58-btc multibase encoded".into(),
+      ));
+    }
+    BaseEncoding::decode_multibase(proof_value).map_err(data_integrity_error)
+  }
+}
+
+impl TryFrom<DataIntegrityProof> for Proof {
+  type Error = Error;
+
+  fn try_from(proof: DataIntegrityProof) -> Result<Self> {
+    let mut properties: Object = proof
+      .to_json_value()
+      .and_then(Object::from_json_value)
+      .map_err(data_integrity_error)?;
+    properties.remove("type");
+    Ok(Proof::new(proof.type_, properties))
+  }
+}
+
+impl TryFrom<&Proof> for DataIntegrityProof {
+  type Error = Error;
+
+  fn try_from(proof: &Proof) -> Result<Self> {
+    if proof.type_ != DATA_INTEGRITY_PROOF_TYPE {
+      return Err(Error::DataIntegrityError(
+        format!(
+          "expected proof type `{DATA_INTEGRITY_PROOF_TYPE}`, found `{}`",
+          proof.type_
+        )
+        .into(),
+      ));
+    }
+    proof
+      .to_json_value()
+      .and_then(Self::from_json_value)
+      .map_err(data_integrity_error)
+  }
+}
+
+/// Serializes the secured `document` into a JSON object, ignoring its `proof` property.
+pub(crate) fn unsecured_document_value<S: Serialize + ?Sized>(document: &S) -> Result<Value> {
+  let mut document: Value = document.to_json_value().map_err(data_integrity_error)?;
+  document
+    .as_object_mut()
+    .ok_or_else(|| Error::DataIntegrityError("the secured document must be a JSON object".into()))?
+    .remove("proof");
+  Ok(document)
+}
+
+fn data_integrity_error(error: identity_core::Error) -> Error {
+  Error::DataIntegrityError(error.into())
+}
+
+#[cfg(test)]
+mod tests {
+  use identity_core::convert::FromJson;
+  use identity_core::convert::ToJson;
+
+  use super::*;
+
+  #[test]
+  fn test_proof_conversion() {
+    let mut proof = DataIntegrityProof::new(
+      Cryptosuite::EddsaJcs2022,
+      "did:example:issuer#key-1",
+      ProofPurpose::AssertionMethod,
+    );
+    proof.created = Some(Timestamp::parse("2024-01-01T00:00:00Z").unwrap());
+    proof.set_proof_value(&[1, 2, 3]);
+
+    let generic: Proof = proof.clone().try_into().unwrap();
+    assert_eq!(generic.type_, DATA_INTEGRITY_PROOF_TYPE);
+    assert_eq!(generic.properties["cryptosuite"], "eddsa-jcs-2022");
+    assert_eq!(generic.properties["proofPurpose"], "assertionMethod");
+    assert_eq!(generic.properties["proofValue"], "zLdp");
+    assert_eq!(DataIntegrityProof::try_from(&generic).unwrap(), proof);
+    assert_eq!(proof.decode_proof_value().unwrap(), [1, 2, 3]);
+
+    let other: Proof = Proof::from_json(r#"{"type": "Ed25519Signature2020", "proofValue": "z"}"#).unwrap();
+    assert!(DataIntegrityProof::try_from(&other).is_err());
+    let unsupported: Proof =
+      Proof::from_json_value(serde_json::json!({"type": DATA_INTEGRITY_PROOF_TYPE, "cryptosuite": "ecdsa-rdfc-2019"}))
+        .unwrap();
+    assert!(DataIntegrityProof::try_from(&unsupported).is_err());
+    assert!(generic.to_json().is_ok());
+  }
+
+  #[test]
+  fn test_hash_data_jcs() {
+    // Independent of the embedded proof and of the `proofValue`.
+    let document = serde_json::json!({
+      "@context": ["https://www.w3.org/ns/credentials/v2"],
+      "type": ["VerifiableCredential"],
+      "issuer": "did:example:issuer",
+      "proof": {"type": "DataIntegrityProof"},
+    });
+    let mut proof = DataIntegrityProof::new(
+      Cryptosuite::EddsaJcs2022,
+      "did:example:issuer#key-1",
+      ProofPurpose::AssertionMethod,
+    );
+    let hash_data: Vec<u8> = proof.hash_data(&document, &ContextCache::new()).unwrap();
+    assert_eq!(hash_data.len(), 64);
+
+    let mut unsecured = document.clone();
+    unsecured.as_object_mut().unwrap().remove("proof");
+    proof.set_proof_value(&[0; 64]);
+    assert_eq!(proof.hash_data(&unsecured, &ContextCache::new()).unwrap(), hash_data);
+
+    proof.challenge = Some("challenge".to_owned());
+    assert_ne!(proof.hash_data(&unsecured, &ContextCache::new()).unwrap(), hash_data);
+  }
+
+  /// The terms of the VC Data Model v2.0 and examples contexts used by the test vectors of
+  /// [vc-di-eddsa](https://www.w3.org/TR/vc-di-eddsa/#test-vectors).
+  fn vc_di_eddsa_context_cache() -> ContextCache {
+    let credentials_v2: serde_json::Value =
+      serde_json::from_str(include_str!("../../tests/fixtures/context-credentials-v2.json")).unwrap();
+    let examples_v2 = serde_json::json!({"@context": {"@vocab": "https://www.w3.org/ns/credentials/examples#"}});
+    ContextCache::new()
+      .with_context("https://www.w3.org/ns/credentials/v2", credentials_v2)
+      .with_context("https://www.w3.org/ns/credentials/examples/v2", examples_v2)
+  }
+
+  #[test]
+  fn test_vc_di_eddsa_vectors() {
+    use crypto::signatures::ed25519::PublicKey;
+    use crypto::signatures::ed25519::Signature;
+
+    // https://www.w3.org/TR/vc-di-eddsa/#representation-eddsa-rdfc-2022
+    // https://www.w3.org/TR/vc-di-eddsa/#representation-eddsa-jcs-2022
+    let document = serde_json::json!({
+      "@context": [
+        "https://www.w3.org/ns/credentials/v2",
+        "https://www.w3.org/ns/credentials/examples/v2"
+      ],
+      "id": "urn:uuid:58172aac-d8ba-11ed-83dd-0b3aef56cc33",
+      "type": ["VerifiableCredential", "AlumniCredential"],
+      "name": "Alumni Credential",
+      "description": "A minimum viable example of an Alumni Credential.",
+      "issuer": "https://vc.example/issuers/5678",
+      "validFrom": "2023-01-01T00:00:00Z",
+      "credentialSubject": {
+        "id": "did:example:abcdefgh",
+        "alumniOf": "The School of Examples"
+      }
+    });
+    let public_key_multibase: &str = "z6MkrJVnaZkeFzdQyMZu1cgjg7k1pZZ6pvBQ7XJPt4swbTQ2";
+    let public_key: Vec<u8> = BaseEncoding::decode_multibase(public_key_multibase).unwrap();
+    // Strip the `ed25519-pub` multicodec prefix.
+    let public_key: PublicKey = PublicKey::try_from_bytes(public_key[2..].try_into().unwrap()).unwrap();
+    let context_cache: ContextCache = vc_di_eddsa_context_cache();
+
+    for (cryptosuite, proof_config_hash, proof_value) in [
+      (
+        Cryptosuite::EddsaRdfc2022,
+        "bea7b7acfbad0126b135104024a5f1733e705108f42d59668b05c0c50004c6b0",
+        "z2YwC8z3ap7yx1nZYCg4L3j3ApHsF8kgPdSb5xoS1VR7vPG3F561B52hYnQF9iseabecm3ijx4K1FBTQsCZahKZme",
+      ),
+      (
+        Cryptosuite::EddsaJcs2022,
+        "66ab154f5c2890a140cb8388a22a160454f80575f6eae09e5a097cabe539a1db",
+        "z2HnFSSPPBzR36zdDgK8PbEHeXbR56YF24jwMpt3R1eHXQzJDMWS93FCzpvJpwTWd3GAVFuUfjoJdcnTMuVor51aX",
+      ),
+    ] {
+      let mut proof = DataIntegrityProof::new(
+        cryptosuite,
+        format!("did:key:{public_key_multibase}#{public_key_multibase}"),
+        ProofPurpose::AssertionMethod,
+      );
+      proof.created = Some(Timestamp::parse("2023-02-24T23:36:38Z").unwrap());
+      proof.proof_value = Some(proof_value.to_owned());
+
+      let hash_data: Vec<u8> = proof.hash_data(&document, &context_cache).unwrap();
+      let hex: String = hash_data.iter().map(|byte| format!("{byte:02x}")).collect();
+      assert_eq!(&hex[..64], proof_config_hash, "{cryptosuite}");
+
+      let signature: [u8; 64] = proof.decode_proof_value().unwrap().try_into().unwrap();
+      assert!(
+        public_key.verify(&Signature::from_bytes(signature), &hash_data),
+        "{cryptosuite}"
+      );
+    }
+  }
+}
@@ -31,6 +31,9 @@ pub enum Error {
   /// Caused when constructing a credential with a malformed subject.
   #[error("invalid credential subject")]
   InvalidSubject,
+  /// Caused when constructing a credential whose `validUntil` precedes its `validFrom`.
+  #[error("invalid validity period: validUntil precedes validFrom")]
+  InvalidValidityPeriod,
   /// Caused when trying to construct an invalid status.
   #[error("invalid credential status: {0}")]
   InvalidStatus(String),
@@ -53,6 +56,10 @@ pub enum Error {
   #[error("empty verifiableCredential array in presentation")]
   EmptyVerifiableCredentialArray,
 
+  /// Caused when an `EnvelopedCredential` does not reference a supported secured credential.
+  #[error("invalid enveloped credential: {0}")]
+  InvalidEnvelopedCredential(&'static str),
+
   /// Caused when attempting to convert a JWT to a `Presentation` that has conflicting values
   /// between the registered claims and those in the `vp` object.
   #[error("could not convert JWT to the VP data model: {0}")]
@@ -76,7 +83,39 @@ pub enum Error {
   #[error("could not deserialize JWT claims set")]
   JptClaimsSetDeserializationError(#[source] Box<dyn std::error::Error + Send + Sync + 'static>),
 
+  /// Caused by a failure to (de)serialize the JSON payload of a `Credential` or `Presentation` secured as a
+  /// COSE_Sign1 message.
+  #[error("could not (de)serialize COSE payload")]
+  CosePayloadError(#[source] Box<dyn std::error::Error + Send + Sync + 'static>),
+
+  /// Caused by an mdoc that is not well-formed or cannot be encoded.
+  #[error("invalid mdoc")]
+  InvalidMdoc(#[source] Box<dyn std::error::Error + Send + Sync + 'static>),
+
+  /// Caused by a Data Integrity proof that cannot be created or processed.
+  #[error("data integrity error: {0}")]
+  DataIntegrityError(#[source] Box<dyn std::error::Error + Send + Sync + 'static>),
+
   /// Cause by an invalid attribute path
   #[error("Attribute Not found")]
   SelectiveDisclosureError,
+
+  /// Caused by a predicate over the claims of a JPT that cannot be proven or verified.
+  #[cfg(feature = "jpt-predicates-experimental")]
+  #[error("jpt predicate error: {0}")]
+  JptPredicateError(String),
+
+  /// Caused by a JPT bound to a holder secret that cannot be proven or verified.
+  #[cfg(feature = "jpt-predicates-experimental")]
+  #[error("jpt holder binding error: {0}")]
+  JptHolderBindingError(String),
+
+  /// Caused by a post-quantum JPT that cannot be issued, presented or verified.
+  #[cfg(feature = "jpt-pq")]
+  #[error("post-quantum jpt error: {0}")]
+  JwpPqcError(String),
+
+  /// Caused by a failure to build a JWP.
+  #[error("could not build jwp")]
+  JwpBuildingError(#[source] Box<dyn std::error::Error + Send + Sync + 'static>),
 }
@@ -16,11 +16,21 @@
 
 #[cfg(feature = "credential")]
 pub mod credential;
+#[cfg(feature = "data-integrity")]
+pub mod data_integrity;
 #[cfg(feature = "domain-linkage")]
 pub mod domain_linkage;
 pub mod error;
+#[cfg(feature = "mdoc")]
+pub mod mdoc;
+#[cfg(feature = "openid4vci")]
+pub mod openid4vci;
+#[cfg(feature = "openid4vp")]
+pub mod openid4vp;
 #[cfg(feature = "presentation")]
 pub mod presentation;
+#[cfg(feature = "presentation-exchange")]
+pub mod presentation_exchange;
 #[cfg(feature = "revocation-bitmap")]
 pub mod revocation;
 mod utils;
@@ -30,5 +40,7 @@ pub mod validator;
 pub use error::Error;
 pub use error::Result;
 
+#[cfg(feature = "mdoc")]
+pub use ciborium;
 #[cfg(feature = "sd-jwt")]
 pub use sd_jwt_payload;
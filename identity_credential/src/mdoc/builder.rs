@@ -0,0 +1,175 @@
+// Copyright 2020-2024 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+use std::collections::BTreeMap;
+
+use ciborium::Value;
+use identity_core::common::Timestamp;
+use identity_verification::jose::cose::CoseKey;
+use identity_verification::jose::cose::CoseSign1;
+
+use super::DigestIds;
+use super::IssuerSigned;
+use super::IssuerSignedItem;
+use super::IssuerSignedItemBytes;
+use super::MobileSecurityObject;
+use super::ValidityInfo;
+use super::MSO_DIGEST_ALGORITHM;
+use super::MSO_VERSION;
+use crate::credential::Cose;
+use crate::error::Error;
+use crate::error::Result;
+
+/// A builder for mdoc credentials.
+///
+/// The result of [`build`](Self::build) is an [`UnsignedMdoc`], whose
+/// [`signing_payload`](UnsignedMdoc::signing_payload) has to be signed by the issuer as a COSE_Sign1 message, e.g.
+/// using `CoseDocumentExt::create_cose` (resp. `create_cose_pqc` for PQ algorithms) of `identity_storage`, before
+/// turning it into an [`IssuerSigned`] structure.
+#[derive(Clone, Debug)]
+pub struct MdocBuilder {
+  doc_type: String,
+  elements: BTreeMap<String, Vec<(String, Value)>>,
+  device_key: Option<CoseKey>,
+  valid_from: Option<Timestamp>,
+  valid_until: Option<Timestamp>,
+}
+
+impl MdocBuilder {
+  /// Creates a new [`MdocBuilder`] for a document of the given `doc_type`, e.g. `org.iso.18013.5.1.mDL`.
+  pub fn new(doc_type: impl Into<String>) -> Self {
+    Self {
+      doc_type: doc_type.into(),
+      elements: BTreeMap::new(),
+      device_key: None,
+      valid_from: None,
+      valid_until: None,
+    }
+  }
+
+  /// Adds a data element to the given `name_space`.
+  #[must_use]
+  pub fn element(mut self, name_space: impl Into<String>, identifier: impl Into<String>, value: Value) -> Self {
+    self
+      .elements
+      .entry(name_space.into())
+      .or_default()
+      .push((identifier.into(), value));
+    self
+  }
+
+  /// Sets the public key of the holder's device, which is required to authenticate presentations.
+  #[must_use]
+  pub fn device_key(mut self, value: CoseKey) -> Self {
+    self.device_key = Some(value);
+    self
+  }
+
+  /// Sets the time from which the mdoc is valid. Defaults to the time of building.
+  #[must_use]
+  pub fn valid_from(mut self, value: Timestamp) -> Self {
+    self.valid_from = Some(value);
+    self
+  }
+
+  /// Sets the time until which the mdoc is valid.
+  #[must_use]
+  pub fn valid_until(mut self, value: Timestamp) -> Self {
+    self.valid_until = Some(value);
+    self
+  }
+
+  /// Salts the data elements and computes the [`MobileSecurityObject`] to be signed by the issuer.
+  ///
+  /// # Errors
+  /// Fails if no device key or validity end is set, if the device key contains private key material, or if a data
+  /// element is defined more than once within a name space.
+  pub fn build(self) -> Result<UnsignedMdoc> {
+    let device_key: CoseKey = self
+      .device_key
+      .ok_or_else(|| Error::InvalidMdoc("missing device key".into()))?;
+    if !device_key.is_public() {
+      return Err(Error::InvalidMdoc(
+        "the device key must not contain private key material".into(),
+      ));
+    }
+    let signed: Timestamp = Timestamp::now_utc();
+    let validity_info: ValidityInfo = ValidityInfo::new(
+      signed,
+      self.valid_from.unwrap_or(signed),
+      self.valid_until.ok_or(Error::MissingExpirationDate)?,
+    )?;
+
+    let mut name_spaces: BTreeMap<String, Vec<IssuerSignedItemBytes>> = BTreeMap::new();
+    let mut value_digests: BTreeMap<String, DigestIds> = BTreeMap::new();
+    for (name_space, elements) in self.elements {
+      let mut items: Vec<IssuerSignedItemBytes> = Vec::with_capacity(elements.len());
+      let mut digests: DigestIds = DigestIds::new();
+      for (digest_id, (identifier, value)) in (0..).zip(elements) {
+        if items.iter().any(|item| item.item().element_identifier == identifier) {
+          return Err(Error::InvalidMdoc(
+            format!("duplicate data element {name_space}/{identifier}").into(),
+          ));
+        }
+        let item: IssuerSignedItemBytes =
+          IssuerSignedItemBytes::new(IssuerSignedItem::new(digest_id, identifier, value)?)?;
+        digests.insert(digest_id, item.digest()?);
+        items.push(item);
+      }
+      value_digests.insert(name_space.clone(), digests);
+      name_spaces.insert(name_space, items);
+    }
+
+    let mso = MobileSecurityObject {
+      version: MSO_VERSION.to_owned(),
+      digest_algorithm: MSO_DIGEST_ALGORITHM.to_owned(),
+      value_digests,
+      device_key,
+      doc_type: self.doc_type,
+      validity_info,
+    };
+    let mso_bytes: Vec<u8> = mso.to_vec()?;
+
+    Ok(UnsignedMdoc {
+      name_spaces,
+      mso,
+      mso_bytes,
+    })
+  }
+}
+
+/// An mdoc that still needs to be signed by its issuer.
+#[derive(Clone, Debug)]
+pub struct UnsignedMdoc {
+  name_spaces: BTreeMap<String, Vec<IssuerSignedItemBytes>>,
+  mso: MobileSecurityObject,
+  mso_bytes: Vec<u8>,
+}
+
+impl UnsignedMdoc {
+  /// Returns the [`MobileSecurityObject`].
+  pub fn mso(&self) -> &MobileSecurityObject {
+    &self.mso
+  }
+
+  /// Returns the encoded [`MobileSecurityObject`], which is the payload of the issuer's COSE_Sign1 message.
+  pub fn signing_payload(&self) -> &[u8] {
+    &self.mso_bytes
+  }
+
+  /// Creates the [`IssuerSigned`] structure from the issuer's COSE_Sign1 signature over the
+  /// [`signing_payload`](Self::signing_payload).
+  ///
+  /// # Errors
+  /// Fails if `issuer_auth` is not a COSE_Sign1 message with the expected payload.
+  pub fn into_issuer_signed(self, issuer_auth: Cose) -> Result<IssuerSigned> {
+    let message: CoseSign1 =
+      CoseSign1::from_slice(issuer_auth.as_bytes()).map_err(|err| Error::InvalidMdoc(err.into()))?;
+    if message.payload() != self.mso_bytes {
+      return Err(Error::InvalidMdoc(
+        "the issuer signature does not cover the mobile security object".into(),
+      ));
+    }
+    Ok(IssuerSigned::new(self.name_spaces, issuer_auth))
+  }
+}
@@ -0,0 +1,125 @@
+// Copyright 2020-2024 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+use ciborium::Value;
+use identity_core::common::Timestamp;
+
+use crate::credential::Cose;
+use crate::error::Error;
+use crate::error::Result;
+
+/// The CBOR tag of an embedded CBOR data item (`#6.24(bstr)`).
+const ENCODED_CBOR_TAG: u64 = 24;
+/// The CBOR tag of an RFC 3339 date-time string.
+const TDATE_TAG: u64 = 0;
+
+pub(super) fn to_vec(value: &Value) -> Result<Vec<u8>> {
+  let mut bytes: Vec<u8> = Vec::new();
+  ciborium::ser::into_writer(value, &mut bytes).map_err(|err| Error::InvalidMdoc(err.into()))?;
+  Ok(bytes)
+}
+
+pub(super) fn from_slice(bytes: &[u8]) -> Result<Value> {
+  ciborium::de::from_reader(bytes).map_err(|err| Error::InvalidMdoc(err.into()))
+}
+
+/// Wraps already encoded CBOR `bytes` into a `#6.24(bstr)` data item.
+pub(super) fn encoded_cbor(bytes: Vec<u8>) -> Value {
+  Value::Tag(ENCODED_CBOR_TAG, Box::new(Value::Bytes(bytes)))
+}
+
+/// Unwraps the encoded CBOR bytes of a `#6.24(bstr)` data item.
+pub(super) fn from_encoded_cbor(value: Value) -> Result<Vec<u8>> {
+  match value {
+    Value::Tag(ENCODED_CBOR_TAG, value) => match *value {
+      Value::Bytes(bytes) => Ok(bytes),
+      _ => Err(Error::InvalidMdoc("expected an encoded CBOR byte string".into())),
+    },
+    _ => Err(Error::InvalidMdoc("expected an encoded CBOR data item".into())),
+  }
+}
+
+pub(super) fn tdate(timestamp: Timestamp) -> Value {
+  Value::Tag(TDATE_TAG, Box::new(Value::Text(timestamp.to_rfc3339())))
+}
+
+pub(super) fn from_tdate(value: Value) -> Result<Timestamp> {
+  match value {
+    Value::Tag(TDATE_TAG, value) => match *value {
+      Value::Text(text) => Timestamp::parse(&text).map_err(|err| Error::InvalidMdoc(err.into())),
+      _ => Err(Error::InvalidMdoc("expected a tdate string".into())),
+    },
+    _ => Err(Error::InvalidMdoc("expected a tdate".into())),
+  }
+}
+
+/// Converts a CBOR map with text keys into `(key, value)` pairs, rejecting duplicate keys.
+pub(super) fn into_text_map(value: Value, name: &'static str) -> Result<Vec<(String, Value)>> {
+  let Value::Map(entries) = value else {
+    return Err(Error::InvalidMdoc(format!("expected {name} to be a map").into()));
+  };
+
+  let mut map: Vec<(String, Value)> = Vec::with_capacity(entries.len());
+  for (key, value) in entries {
+    let Value::Text(key) = key else {
+      return Err(Error::InvalidMdoc(format!("expected text keys in {name}").into()));
+    };
+    if map.iter().any(|(existing, _)| *existing == key) {
+      return Err(Error::InvalidMdoc(format!("duplicate key {key} in {name}").into()));
+    }
+    map.push((key, value));
+  }
+  Ok(map)
+}
+
+/// Removes the entry for `key` from a map obtained through [`into_text_map`].
+pub(super) fn take(map: &mut Vec<(String, Value)>, key: &'static str) -> Result<Value> {
+  map
+    .iter()
+    .position(|(existing, _)| existing == key)
+    .map(|index| map.swap_remove(index).1)
+    .ok_or_else(|| Error::InvalidMdoc(format!("missing {key}").into()))
+}
+
+pub(super) fn into_text(value: Value) -> Result<String> {
+  match value {
+    Value::Text(text) => Ok(text),
+    _ => Err(Error::InvalidMdoc("expected a text string".into())),
+  }
+}
+
+pub(super) fn into_bytes(value: Value) -> Result<Vec<u8>> {
+  match value {
+    Value::Bytes(bytes) => Ok(bytes),
+    _ => Err(Error::InvalidMdoc("expected a byte string".into())),
+  }
+}
+
+pub(super) fn into_u64(value: Value) -> Result<u64> {
+  value
+    .as_integer()
+    .and_then(|int| u64::try_from(int).ok())
+    .ok_or_else(|| Error::InvalidMdoc("expected an unsigned integer".into()))
+}
+
+pub(super) fn text_map(entries: impl IntoIterator<Item = (String, Value)>) -> Value {
+  Value::Map(
+    entries
+      .into_iter()
+      .map(|(key, value)| (Value::Text(key), value))
+      .collect(),
+  )
+}
+
+/// Decodes a COSE message into a CBOR data item, stripping its tag, if any.
+pub(super) fn from_cose(cose: &Cose) -> Result<Value> {
+  match from_slice(cose.as_bytes())? {
+    Value::Tag(_, value) => Ok(*value),
+    value => Ok(value),
+  }
+}
+
+/// Encodes an untagged COSE message.
+pub(super) fn into_cose(value: Value) -> Result<Cose> {
+  to_vec(&value).map(Cose::new)
+}
@@ -0,0 +1,113 @@
+// Copyright 2020-2024 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+use ciborium::Value;
+
+use super::cbor;
+use super::IssuerSigned;
+use crate::credential::Cose;
+use crate::error::Result;
+
+const DEVICE_AUTHENTICATION_CONTEXT: &str = "DeviceAuthentication";
+
+/// A single document of an mdoc presentation, consisting of the disclosed [`IssuerSigned`] data elements and the
+/// holder's device signature.
+///
+/// [More Info](https://www.iso.org/standard/69084.html) (ISO/IEC 18013-5, section 8.3.2.1.2.2)
+#[derive(Clone, Debug, PartialEq)]
+pub struct MdocDocument {
+  doc_type: String,
+  issuer_signed: IssuerSigned,
+  device_signature: Option<Cose>,
+}
+
+impl MdocDocument {
+  /// Creates a new [`MdocDocument`] without a device signature.
+  pub fn new(doc_type: impl Into<String>, issuer_signed: IssuerSigned) -> Self {
+    Self {
+      doc_type: doc_type.into(),
+      issuer_signed,
+      device_signature: None,
+    }
+  }
+
+  /// Returns the document type.
+  pub fn doc_type(&self) -> &str {
+    &self.doc_type
+  }
+
+  /// Returns the disclosed issuer signed data elements.
+  pub fn issuer_signed(&self) -> &IssuerSigned {
+    &self.issuer_signed
+  }
+
+  /// Returns the holder's device signature, which is a COSE_Sign1 message with a detached payload.
+  pub fn device_signature(&self) -> Option<&Cose> {
+    self.device_signature.as_ref()
+  }
+
+  /// Sets the holder's device signature over the [`device_authentication_bytes`](Self::device_authentication_bytes).
+  #[must_use]
+  pub fn with_device_signature(mut self, device_signature: Cose) -> Self {
+    self.device_signature = Some(device_signature);
+    self
+  }
+
+  /// Computes the `DeviceAuthenticationBytes`, i.e. the detached payload of the device signature, binding the
+  /// presentation to the given `session_transcript`.
+  ///
+  /// The session transcript is agreed upon by holder and verifier and must be unique per presentation, so that
+  /// device signatures cannot be replayed.
+  pub fn device_authentication_bytes(&self, session_transcript: &Value) -> Result<Vec<u8>> {
+    let device_name_spaces: Value = cbor::encoded_cbor(cbor::to_vec(&Value::Map(Vec::new()))?);
+    let device_authentication: Value = Value::Array(vec![
+      Value::Text(DEVICE_AUTHENTICATION_CONTEXT.to_owned()),
+      session_transcript.clone(),
+      Value::Text(self.doc_type.clone()),
+      device_name_spaces,
+    ]);
+    cbor::to_vec(&cbor::encoded_cbor(cbor::to_vec(&device_authentication)?))
+  }
+
+  /// Encodes `self` as CBOR.
+  pub fn to_vec(&self) -> Result<Vec<u8>> {
+    let mut document: Vec<(String, Value)> = vec![
+      ("docType".to_owned(), Value::Text(self.doc_type.clone())),
+      ("issuerSigned".to_owned(), self.issuer_signed.to_cbor_value()?),
+    ];
+    if let Some(device_signature) = &self.device_signature {
+      let device_auth: Value = cbor::text_map([("deviceSignature".to_owned(), cbor::from_cose(device_signature)?)]);
+      let device_signed: Value = cbor::text_map([
+        (
+          "nameSpaces".to_owned(),
+          cbor::encoded_cbor(cbor::to_vec(&Value::Map(Vec::new()))?),
+        ),
+        ("deviceAuth".to_owned(), device_auth),
+      ]);
+      document.push(("deviceSigned".to_owned(), device_signed));
+    }
+    cbor::to_vec(&cbor::text_map(document))
+  }
+
+  /// Decodes an [`MdocDocument`] from CBOR.
+  pub fn from_slice(bytes: &[u8]) -> Result<Self> {
+    let mut document: Vec<(String, Value)> = cbor::into_text_map(cbor::from_slice(bytes)?, "Document")?;
+    let doc_type: String = cbor::into_text(cbor::take(&mut document, "docType")?)?;
+    let issuer_signed: IssuerSigned = IssuerSigned::from_cbor_value(cbor::take(&mut document, "issuerSigned")?)?;
+    let device_signature: Option<Cose> = match cbor::take(&mut document, "deviceSigned") {
+      Ok(device_signed) => {
+        let mut device_signed: Vec<(String, Value)> = cbor::into_text_map(device_signed, "DeviceSigned")?;
+        let mut device_auth: Vec<(String, Value)> =
+          cbor::into_text_map(cbor::take(&mut device_signed, "deviceAuth")?, "DeviceAuth")?;
+        Some(cbor::into_cose(cbor::take(&mut device_auth, "deviceSignature")?)?)
+      }
+      Err(_) => None,
+    };
+
+    Ok(Self {
+      doc_type,
+      issuer_signed,
+      device_signature,
+    })
+  }
+}
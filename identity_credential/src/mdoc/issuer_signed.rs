@@ -0,0 +1,113 @@
+// Copyright 2020-2024 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+use std::collections::BTreeMap;
+
+use ciborium::Value;
+
+use super::cbor;
+use super::IssuerSignedItemBytes;
+use crate::credential::Cose;
+use crate::error::Error;
+use crate::error::Result;
+
+/// The issuer signed part of an mdoc: the disclosed data elements together with the issuer's signature over the
+/// `MobileSecurityObject`.
+///
+/// [More Info](https://www.iso.org/standard/69084.html) (ISO/IEC 18013-5, section 8.3.2.1.2.2)
+#[derive(Clone, Debug, PartialEq)]
+pub struct IssuerSigned {
+  name_spaces: BTreeMap<String, Vec<IssuerSignedItemBytes>>,
+  issuer_auth: Cose,
+}
+
+impl IssuerSigned {
+  pub(crate) fn new(name_spaces: BTreeMap<String, Vec<IssuerSignedItemBytes>>, issuer_auth: Cose) -> Self {
+    Self {
+      name_spaces,
+      issuer_auth,
+    }
+  }
+
+  /// Returns the disclosed data elements, indexed by name space.
+  pub fn name_spaces(&self) -> &BTreeMap<String, Vec<IssuerSignedItemBytes>> {
+    &self.name_spaces
+  }
+
+  /// Returns the issuer's COSE_Sign1 signature over the `MobileSecurityObject`.
+  pub fn issuer_auth(&self) -> &Cose {
+    &self.issuer_auth
+  }
+
+  /// Returns a copy of `self` that only discloses the data elements identified by the given
+  /// `(name_space, element_identifier)` pairs.
+  ///
+  /// # Errors
+  /// Fails if one of the requested data elements is not contained in `self`.
+  pub fn disclose<'a>(&self, elements: impl IntoIterator<Item = (&'a str, &'a str)>) -> Result<Self> {
+    let mut name_spaces: BTreeMap<String, Vec<IssuerSignedItemBytes>> = BTreeMap::new();
+    for (name_space, element_identifier) in elements {
+      let item: &IssuerSignedItemBytes = self
+        .name_spaces
+        .get(name_space)
+        .and_then(|items| {
+          items
+            .iter()
+            .find(|item| item.item().element_identifier == element_identifier)
+        })
+        .ok_or_else(|| Error::InvalidMdoc(format!("unknown data element {name_space}/{element_identifier}").into()))?;
+
+      let disclosed: &mut Vec<IssuerSignedItemBytes> = name_spaces.entry(name_space.to_owned()).or_default();
+      if !disclosed.contains(item) {
+        disclosed.push(item.clone());
+      }
+    }
+
+    Ok(Self {
+      name_spaces,
+      issuer_auth: self.issuer_auth.clone(),
+    })
+  }
+
+  /// Encodes `self` as CBOR.
+  pub fn to_vec(&self) -> Result<Vec<u8>> {
+    cbor::to_vec(&self.to_cbor_value()?)
+  }
+
+  /// Decodes an [`IssuerSigned`] structure from CBOR.
+  pub fn from_slice(bytes: &[u8]) -> Result<Self> {
+    Self::from_cbor_value(cbor::from_slice(bytes)?)
+  }
+
+  pub(crate) fn to_cbor_value(&self) -> Result<Value> {
+    let name_spaces: Value = cbor::text_map(self.name_spaces.iter().map(|(name_space, items)| {
+      let items: Vec<Value> = items.iter().map(IssuerSignedItemBytes::to_cbor_value).collect();
+      (name_space.clone(), Value::Array(items))
+    }));
+    Ok(cbor::text_map([
+      ("nameSpaces".to_owned(), name_spaces),
+      ("issuerAuth".to_owned(), cbor::from_cose(&self.issuer_auth)?),
+    ]))
+  }
+
+  pub(crate) fn from_cbor_value(value: Value) -> Result<Self> {
+    let mut map: Vec<(String, Value)> = cbor::into_text_map(value, "IssuerSigned")?;
+
+    let mut name_spaces: BTreeMap<String, Vec<IssuerSignedItemBytes>> = BTreeMap::new();
+    for (name_space, items) in cbor::into_text_map(cbor::take(&mut map, "nameSpaces")?, "nameSpaces")? {
+      let Value::Array(items) = items else {
+        return Err(Error::InvalidMdoc("expected IssuerSignedItems to be an array".into()));
+      };
+      let items: Vec<IssuerSignedItemBytes> = items
+        .into_iter()
+        .map(IssuerSignedItemBytes::from_cbor_value)
+        .collect::<Result<_>>()?;
+      name_spaces.insert(name_space, items);
+    }
+
+    Ok(Self {
+      name_spaces,
+      issuer_auth: cbor::into_cose(cbor::take(&mut map, "issuerAuth")?)?,
+    })
+  }
+}
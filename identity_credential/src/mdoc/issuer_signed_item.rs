@@ -0,0 +1,112 @@
+// Copyright 2020-2024 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+use ciborium::Value;
+use crypto::hashes::sha::SHA256;
+use crypto::hashes::sha::SHA256_LEN;
+
+use super::cbor;
+use crate::error::Error;
+use crate::error::Result;
+
+/// Length in bytes of the random salt of an [`IssuerSignedItem`].
+const SALT_LEN: usize = 32;
+
+/// A single salted data element of an mdoc.
+///
+/// [More Info](https://www.iso.org/standard/69084.html) (ISO/IEC 18013-5, section 8.3.2.1.2.2)
+#[derive(Clone, Debug, PartialEq)]
+#[non_exhaustive]
+pub struct IssuerSignedItem {
+  /// Identifier of the digest of this item in the `MobileSecurityObject`.
+  pub digest_id: u64,
+  /// Random salt.
+  pub random: Vec<u8>,
+  /// Identifier of the data element.
+  pub element_identifier: String,
+  /// Value of the data element.
+  pub element_value: Value,
+}
+
+impl IssuerSignedItem {
+  /// Creates a new [`IssuerSignedItem`] with a freshly generated random salt.
+  pub fn new(digest_id: u64, element_identifier: impl Into<String>, element_value: Value) -> Result<Self> {
+    let mut random: Vec<u8> = vec![0; SALT_LEN];
+    crypto::utils::rand::fill(&mut random).map_err(|err| Error::InvalidMdoc(err.to_string().into()))?;
+    Ok(Self {
+      digest_id,
+      random,
+      element_identifier: element_identifier.into(),
+      element_value,
+    })
+  }
+
+  fn to_cbor_value(&self) -> Value {
+    cbor::text_map([
+      ("digestID".to_owned(), Value::Integer(self.digest_id.into())),
+      ("random".to_owned(), Value::Bytes(self.random.clone())),
+      (
+        "elementIdentifier".to_owned(),
+        Value::Text(self.element_identifier.clone()),
+      ),
+      ("elementValue".to_owned(), self.element_value.clone()),
+    ])
+  }
+
+  fn from_cbor_value(value: Value) -> Result<Self> {
+    let mut map: Vec<(String, Value)> = cbor::into_text_map(value, "IssuerSignedItem")?;
+    Ok(Self {
+      digest_id: cbor::into_u64(cbor::take(&mut map, "digestID")?)?,
+      random: cbor::into_bytes(cbor::take(&mut map, "random")?)?,
+      element_identifier: cbor::into_text(cbor::take(&mut map, "elementIdentifier")?)?,
+      element_value: cbor::take(&mut map, "elementValue")?,
+    })
+  }
+}
+
+/// An [`IssuerSignedItem`] together with its encoded form (`IssuerSignedItemBytes`), over which the digest in the
+/// `MobileSecurityObject` is computed.
+///
+/// The encoded form is retained when decoding, so that digests are always computed over the bytes chosen by the
+/// issuer.
+#[derive(Clone, Debug, PartialEq)]
+pub struct IssuerSignedItemBytes {
+  item: IssuerSignedItem,
+  bytes: Vec<u8>,
+}
+
+impl IssuerSignedItemBytes {
+  /// Encodes the given `item`.
+  pub fn new(item: IssuerSignedItem) -> Result<Self> {
+    let bytes: Vec<u8> = cbor::to_vec(&item.to_cbor_value())?;
+    Ok(Self { item, bytes })
+  }
+
+  /// Returns the decoded item.
+  pub fn item(&self) -> &IssuerSignedItem {
+    &self.item
+  }
+
+  /// Returns the encoded item.
+  pub fn as_bytes(&self) -> &[u8] {
+    &self.bytes
+  }
+
+  /// Computes the SHA-256 digest of the `#6.24(bstr)` wrapped item.
+  pub fn digest(&self) -> Result<Vec<u8>> {
+    let encoded: Vec<u8> = cbor::to_vec(&self.to_cbor_value())?;
+    let mut digest: [u8; SHA256_LEN] = [0; SHA256_LEN];
+    SHA256(&encoded, &mut digest);
+    Ok(digest.to_vec())
+  }
+
+  pub(crate) fn to_cbor_value(&self) -> Value {
+    cbor::encoded_cbor(self.bytes.clone())
+  }
+
+  pub(crate) fn from_cbor_value(value: Value) -> Result<Self> {
+    let bytes: Vec<u8> = cbor::from_encoded_cbor(value)?;
+    let item: IssuerSignedItem = IssuerSignedItem::from_cbor_value(cbor::from_slice(&bytes)?)?;
+    Ok(Self { item, bytes })
+  }
+}
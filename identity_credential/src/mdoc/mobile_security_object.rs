@@ -0,0 +1,143 @@
+// Copyright 2020-2024 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+use std::collections::BTreeMap;
+
+use ciborium::Value;
+use identity_core::common::Timestamp;
+use identity_verification::jose::cose::CoseKey;
+
+use super::cbor;
+use crate::error::Error;
+use crate::error::Result;
+
+/// The version of the [`MobileSecurityObject`] structure.
+pub const MSO_VERSION: &str = "1.0";
+/// The digest algorithm used for the [`MobileSecurityObject::value_digests`].
+pub const MSO_DIGEST_ALGORITHM: &str = "SHA-256";
+
+/// The digests of the issuer signed items of a single name space, indexed by their digest id.
+pub type DigestIds = BTreeMap<u64, Vec<u8>>;
+
+/// Validity information of a [`MobileSecurityObject`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct ValidityInfo {
+  /// The time at which the [`MobileSecurityObject`] was signed.
+  pub signed: Timestamp,
+  /// The time from which the mdoc is valid.
+  pub valid_from: Timestamp,
+  /// The time until which the mdoc is valid.
+  pub valid_until: Timestamp,
+}
+
+impl ValidityInfo {
+  /// Creates a new [`ValidityInfo`].
+  ///
+  /// # Errors
+  /// Fails if `valid_until` precedes `valid_from`.
+  pub fn new(signed: Timestamp, valid_from: Timestamp, valid_until: Timestamp) -> Result<Self> {
+    if valid_until < valid_from {
+      return Err(Error::InvalidValidityPeriod);
+    }
+    Ok(Self {
+      signed,
+      valid_from,
+      valid_until,
+    })
+  }
+}
+
+/// The Mobile Security Object (MSO), i.e. the payload signed by the issuer of an mdoc.
+///
+/// [More Info](https://www.iso.org/standard/69084.html) (ISO/IEC 18013-5, section 9.1.2.4)
+#[derive(Clone, Debug, PartialEq)]
+#[non_exhaustive]
+pub struct MobileSecurityObject {
+  /// The version of the structure, see [`MSO_VERSION`].
+  pub version: String,
+  /// The algorithm used to compute the `value_digests`, see [`MSO_DIGEST_ALGORITHM`].
+  pub digest_algorithm: String,
+  /// The digests of all issuer signed items, indexed by name space.
+  pub value_digests: BTreeMap<String, DigestIds>,
+  /// The public key of the holder's device.
+  pub device_key: CoseKey,
+  /// The document type.
+  pub doc_type: String,
+  /// Validity information.
+  pub validity_info: ValidityInfo,
+}
+
+impl MobileSecurityObject {
+  /// Encodes the MSO as `MobileSecurityObjectBytes`, i.e. the payload of the issuer's COSE_Sign1 signature.
+  pub fn to_vec(&self) -> Result<Vec<u8>> {
+    let value_digests: Value = cbor::text_map(self.value_digests.iter().map(|(name_space, digests)| {
+      let digests: Value = Value::Map(
+        digests
+          .iter()
+          .map(|(digest_id, digest)| (Value::Integer((*digest_id).into()), Value::Bytes(digest.clone())))
+          .collect(),
+      );
+      (name_space.clone(), digests)
+    }));
+    let device_key: Value = cbor::from_slice(&self.device_key.to_vec().map_err(|err| Error::InvalidMdoc(err.into()))?)?;
+    let validity_info: Value = cbor::text_map([
+      ("signed".to_owned(), cbor::tdate(self.validity_info.signed)),
+      ("validFrom".to_owned(), cbor::tdate(self.validity_info.valid_from)),
+      ("validUntil".to_owned(), cbor::tdate(self.validity_info.valid_until)),
+    ]);
+
+    let mso: Value = cbor::text_map([
+      ("version".to_owned(), Value::Text(self.version.clone())),
+      ("digestAlgorithm".to_owned(), Value::Text(self.digest_algorithm.clone())),
+      ("valueDigests".to_owned(), value_digests),
+      (
+        "deviceKeyInfo".to_owned(),
+        cbor::text_map([("deviceKey".to_owned(), device_key)]),
+      ),
+      ("docType".to_owned(), Value::Text(self.doc_type.clone())),
+      ("validityInfo".to_owned(), validity_info),
+    ]);
+    cbor::to_vec(&cbor::encoded_cbor(cbor::to_vec(&mso)?))
+  }
+
+  /// Decodes an MSO from `MobileSecurityObjectBytes`.
+  pub fn from_slice(bytes: &[u8]) -> Result<Self> {
+    let mso: Value = cbor::from_slice(&cbor::from_encoded_cbor(cbor::from_slice(bytes)?)?)?;
+    let mut mso: Vec<(String, Value)> = cbor::into_text_map(mso, "MobileSecurityObject")?;
+
+    let mut value_digests: BTreeMap<String, DigestIds> = BTreeMap::new();
+    for (name_space, digests) in cbor::into_text_map(cbor::take(&mut mso, "valueDigests")?, "valueDigests")? {
+      let Value::Map(digests) = digests else {
+        return Err(Error::InvalidMdoc("expected DigestIDs to be a map".into()));
+      };
+      let digests: DigestIds = digests
+        .into_iter()
+        .map(|(digest_id, digest)| Ok((cbor::into_u64(digest_id)?, cbor::into_bytes(digest)?)))
+        .collect::<Result<_>>()?;
+      value_digests.insert(name_space, digests);
+    }
+
+    let mut device_key_info: Vec<(String, Value)> =
+      cbor::into_text_map(cbor::take(&mut mso, "deviceKeyInfo")?, "deviceKeyInfo")?;
+    let device_key: CoseKey = CoseKey::from_slice(&cbor::to_vec(&cbor::take(&mut device_key_info, "deviceKey")?)?)
+      .map_err(|err| Error::InvalidMdoc(err.into()))?;
+
+    let mut validity_info: Vec<(String, Value)> =
+      cbor::into_text_map(cbor::take(&mut mso, "validityInfo")?, "validityInfo")?;
+    let validity_info = ValidityInfo {
+      signed: cbor::from_tdate(cbor::take(&mut validity_info, "signed")?)?,
+      valid_from: cbor::from_tdate(cbor::take(&mut validity_info, "validFrom")?)?,
+      valid_until: cbor::from_tdate(cbor::take(&mut validity_info, "validUntil")?)?,
+    };
+
+    Ok(Self {
+      version: cbor::into_text(cbor::take(&mut mso, "version")?)?,
+      digest_algorithm: cbor::into_text(cbor::take(&mut mso, "digestAlgorithm")?)?,
+      value_digests,
+      device_key,
+      doc_type: cbor::into_text(cbor::take(&mut mso, "docType")?)?,
+      validity_info,
+    })
+  }
+}
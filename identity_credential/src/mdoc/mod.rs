@@ -0,0 +1,27 @@
+// Copyright 2020-2024 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+//! Selectively disclosable credentials in the mobile document (mdoc) format of
+//! [ISO/IEC 18013-5](https://www.iso.org/standard/69084.html).
+//!
+//! Every data element is wrapped in an [`IssuerSignedItem`] together with a random salt. The issuer only signs the
+//! salted digests of these items, contained in a [`MobileSecurityObject`], which allows a holder to disclose any
+//! subset of the data elements. The holder is bound to the credential through the device key included in the
+//! [`MobileSecurityObject`], which is used to sign the [device
+//! authentication](MdocDocument::device_authentication_bytes) structure of a presentation.
+
+mod builder;
+mod cbor;
+mod document;
+mod issuer_signed;
+mod issuer_signed_item;
+mod mobile_security_object;
+
+pub use self::builder::*;
+pub use self::document::*;
+pub use self::issuer_signed::*;
+pub use self::issuer_signed_item::*;
+pub use self::mobile_security_object::*;
+
+/// The media type of an [`MdocDocument`].
+pub const MDOC_MEDIA_TYPE: &str = "application/mdoc";
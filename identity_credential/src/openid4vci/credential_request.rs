@@ -0,0 +1,93 @@
+// Copyright 2020-2024 IOTA Stiftung, Fondazione Links
+// SPDX-License-Identifier: Apache-2.0
+
+use serde::Deserialize;
+use serde::Serialize;
+use serde_json::Value;
+
+use super::CredentialConfiguration;
+use super::CredentialDefinition;
+use super::CredentialFormat;
+use super::JWT_PROOF_TYPE;
+
+/// A request to the [credential endpoint](https://openid.net/specs/openid-4-verifiable-credential-issuance-1_0-13.html#name-credential-request),
+/// authorized by the access token obtained from the token endpoint.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CredentialRequest {
+  /// The format of the requested credential.
+  pub format: CredentialFormat,
+  /// The types of a requested `jwt_vc_json` or `jpt` credential.
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub credential_definition: Option<CredentialDefinition>,
+  /// The type of a requested `vc+sd-jwt` credential.
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub vct: Option<String>,
+  /// The proof of possession of the key the credential is bound to.
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub proof: Option<CredentialRequestProof>,
+}
+
+impl CredentialRequest {
+  /// Creates a new [`CredentialRequest`] for a credential described by `configuration`, bound to the key that
+  /// signed `proof_jwt`.
+  pub fn new(configuration: &CredentialConfiguration, proof_jwt: impl Into<String>) -> Self {
+    Self {
+      format: configuration.format,
+      credential_definition: configuration
+        .credential_definition
+        .as_ref()
+        .map(|definition| CredentialDefinition::new(definition.types.clone())),
+      vct: configuration.vct.clone(),
+      proof: Some(CredentialRequestProof::jwt(proof_jwt)),
+    }
+  }
+
+  /// Returns whether this request asks for a credential described by `configuration`.
+  pub fn matches(&self, configuration: &CredentialConfiguration) -> bool {
+    if self.format != configuration.format {
+      return false;
+    }
+    match self.format {
+      CredentialFormat::SdJwt => self.vct.is_some() && self.vct == configuration.vct,
+      CredentialFormat::JwtVcJson | CredentialFormat::Jpt => {
+        match (&self.credential_definition, &configuration.credential_definition) {
+          (Some(requested), Some(supported)) => requested.types == supported.types,
+          _ => false,
+        }
+      }
+    }
+  }
+}
+
+/// A proof of possession of the key a credential is bound to.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CredentialRequestProof {
+  /// The type of the proof, see [`JWT_PROOF_TYPE`].
+  pub proof_type: String,
+  /// The proof secured as a JWT, if `proof_type` is `jwt`.
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub jwt: Option<String>,
+}
+
+impl CredentialRequestProof {
+  /// Creates a new proof of possession secured as a JWT.
+  pub fn jwt(jwt: impl Into<String>) -> Self {
+    Self {
+      proof_type: JWT_PROOF_TYPE.to_owned(),
+      jwt: Some(jwt.into()),
+    }
+  }
+}
+
+/// A successful response of the credential endpoint.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CredentialResponse {
+  /// The issued credential: a string for JWT, SD-JWT and JPT credentials.
+  pub credential: Value,
+  /// The nonce to be used in the proof of possession of the next credential request.
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub c_nonce: Option<String>,
+  /// The lifetime of `c_nonce` in seconds.
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub c_nonce_expires_in: Option<u64>,
+}
@@ -0,0 +1,118 @@
+// Copyright 2020-2024 IOTA Stiftung, Fondazione Links
+// SPDX-License-Identifier: Apache-2.0
+
+use std::fmt::Display;
+
+use serde::Deserialize;
+use serde::Serialize;
+
+/// The error codes returned by the token and credential endpoints of a credential issuer.
+///
+/// See [OAuth 2.0 token errors](https://www.rfc-editor.org/rfc/rfc6749#section-5.2) and
+/// [credential errors](https://openid.net/specs/openid-4-verifiable-credential-issuance-1_0-13.html#name-credential-error-response).
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize, strum::IntoStaticStr)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+#[non_exhaustive]
+pub enum Oid4vciErrorCode {
+  /// The request is missing a parameter or is otherwise malformed.
+  InvalidRequest,
+  /// The pre-authorized code or transaction code is invalid or expired.
+  InvalidGrant,
+  /// The grant type is not supported by the authorization server.
+  UnsupportedGrantType,
+  /// The access token is invalid or expired.
+  InvalidToken,
+  /// The credential request is malformed.
+  InvalidCredentialRequest,
+  /// The requested credential type is not supported, or not covered by the access token.
+  UnsupportedCredentialType,
+  /// The requested credential format is not supported.
+  UnsupportedCredentialFormat,
+  /// The proof of possession is missing or invalid.
+  InvalidProof,
+  /// The proof of possession does not contain the expected `c_nonce`; a fresh one is returned.
+  InvalidNonce,
+  /// The credential could not be issued.
+  ServerError,
+}
+
+impl Display for Oid4vciErrorCode {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    f.write_str(<&'static str>::from(self))
+  }
+}
+
+/// The error response of the token or credential endpoint of a credential issuer.
+///
+/// This type is serialized as the JSON body of the HTTP error response.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Oid4vciError {
+  /// The error code.
+  pub error: Oid4vciErrorCode,
+  /// A human-readable description of the error.
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub error_description: Option<String>,
+  /// A fresh nonce to be used in the proof of possession of a subsequent credential request.
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub c_nonce: Option<String>,
+  /// The lifetime of `c_nonce` in seconds.
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub c_nonce_expires_in: Option<u64>,
+}
+
+impl Oid4vciError {
+  /// Creates a new [`Oid4vciError`] with the given `error` code.
+  pub fn new(error: Oid4vciErrorCode) -> Self {
+    Self {
+      error,
+      error_description: None,
+      c_nonce: None,
+      c_nonce_expires_in: None,
+    }
+  }
+
+  /// Sets the human-readable description of the error.
+  pub fn with_description(mut self, description: impl Into<String>) -> Self {
+    self.error_description = Some(description.into());
+    self
+  }
+
+  /// Returns the HTTP status code to send the error response with.
+  pub fn status_code(&self) -> u16 {
+    match self.error {
+      Oid4vciErrorCode::InvalidToken => 401,
+      Oid4vciErrorCode::ServerError => 500,
+      _ => 400,
+    }
+  }
+}
+
+impl Display for Oid4vciError {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    match &self.error_description {
+      Some(description) => write!(f, "{}: {description}", self.error),
+      None => write!(f, "{}", self.error),
+    }
+  }
+}
+
+impl std::error::Error for Oid4vciError {}
+
+/// Error type that represents the failures of the wallet side of the issuance flow.
+#[derive(Debug, thiserror::Error, strum::IntoStaticStr)]
+#[non_exhaustive]
+pub enum Oid4vciWalletError {
+  /// The credential offer is malformed, or does not match the metadata of the credential issuer.
+  #[error("invalid credential offer: {0}")]
+  InvalidOffer(String),
+  /// The wallet tried to request a credential before obtaining an access token.
+  #[error("no access token has been obtained")]
+  MissingAccessToken,
+  /// The credential issuer returned an error response.
+  #[error("the credential issuer returned an error")]
+  Issuer(#[source] Oid4vciError),
+  /// The credential issuer could not be reached.
+  #[error("the credential issuer could not be reached")]
+  Client(#[source] Box<dyn std::error::Error + Send + Sync + 'static>),
+}
@@ -0,0 +1,433 @@
+// Copyright 2020-2024 IOTA Stiftung, Fondazione Links
+// SPDX-License-Identifier: Apache-2.0
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use async_trait::async_trait;
+use identity_core::common::Duration;
+use identity_core::common::Object;
+use identity_core::common::Timestamp;
+use identity_document::document::CoreDocument;
+use identity_verification::jws::JwsVerifier;
+use identity_verification::jwu::encode_b64;
+use serde_json::Value;
+
+use super::AuthorizationServerMetadata;
+use super::CredentialConfiguration;
+use super::CredentialIssuerMetadata;
+use super::CredentialOffer;
+use super::CredentialRequest;
+use super::CredentialResponse;
+use super::Grants;
+use super::HolderBinding;
+use super::Oid4vciError;
+use super::Oid4vciErrorCode;
+use super::PreAuthorizedCodeGrant;
+use super::ProofJwtClaims;
+use super::ProofJwtValidationOptions;
+use super::ProofJwtValidator;
+use super::TokenRequest;
+use super::TokenResponse;
+use super::TxCode;
+use super::JWT_PROOF_TYPE;
+use super::PRE_AUTHORIZED_CODE_GRANT_TYPE;
+
+/// Error returned by a [`CredentialSigner`].
+pub type CredentialSignerError = Box<dyn std::error::Error + Send + Sync + 'static>;
+
+/// The credential the [`CredentialSigner`] of a [`CredentialIssuer`] must issue.
+#[derive(Debug)]
+#[non_exhaustive]
+pub struct IssuanceRequest<'a> {
+  /// The id of the requested credential configuration.
+  pub configuration_id: &'a str,
+  /// The requested credential configuration.
+  pub configuration: &'a CredentialConfiguration,
+  /// The key the credential must be bound to.
+  pub holder: &'a HolderBinding,
+  /// The claims about the holder recorded when the credential was offered.
+  pub claims: &'a Object,
+}
+
+/// Secures the credentials issued by a [`CredentialIssuer`].
+///
+/// Implementations typically build a [`Credential`](crate::credential::Credential) from the claims of the
+/// [`IssuanceRequest`] and sign it with a key of the issuer's DID document, using an algorithm advertised in
+/// [`CredentialConfiguration::credential_signing_alg_values_supported`].
+#[async_trait]
+pub trait CredentialSigner: Send + Sync {
+  /// Issues the credential described by `request`, returning it as it is embedded in the credential response:
+  /// a string for JWT, SD-JWT and JPT credentials.
+  async fn sign(&self, request: IssuanceRequest<'_>) -> Result<Value, CredentialSignerError>;
+}
+
+/// A credential offer that has not been redeemed yet.
+#[derive(Debug)]
+struct PendingOffer {
+  configuration_ids: Vec<String>,
+  tx_code: Option<String>,
+  claims: Object,
+  expires_at: Timestamp,
+}
+
+/// The state of an access token.
+#[derive(Debug)]
+struct AccessGrant {
+  configuration_ids: Vec<String>,
+  claims: Object,
+  expires_at: Timestamp,
+  c_nonce: String,
+  c_nonce_expires_at: Timestamp,
+}
+
+/// The transport-agnostic endpoints of an [OpenID for Verifiable Credential Issuance](https://openid.net/specs/openid-4-verifiable-credential-issuance-1_0-13.html)
+/// credential issuer supporting the pre-authorized code flow.
+///
+/// The issuer acts as its own authorization server. Offers, access tokens and nonces are kept in memory.
+pub struct CredentialIssuer<S, V> {
+  metadata: CredentialIssuerMetadata,
+  signer: S,
+  proof_validator: ProofJwtValidator<V>,
+  offer_lifetime: Duration,
+  access_token_lifetime: Duration,
+  c_nonce_lifetime: Duration,
+  offers: Mutex<HashMap<String, PendingOffer>>,
+  grants: Mutex<HashMap<String, AccessGrant>>,
+}
+
+impl<S, V> CredentialIssuer<S, V>
+where
+  S: CredentialSigner,
+  V: JwsVerifier,
+{
+  /// Creates a new [`CredentialIssuer`] issuing the credentials described by `metadata` with `signer` and
+  /// verifying the proofs of possession of holders with `signature_verifier`.
+  pub fn new(metadata: CredentialIssuerMetadata, signer: S, signature_verifier: V) -> Self {
+    Self {
+      metadata,
+      signer,
+      proof_validator: ProofJwtValidator::with_signature_verifier(signature_verifier),
+      offer_lifetime: Duration::minutes(10),
+      access_token_lifetime: Duration::minutes(10),
+      c_nonce_lifetime: Duration::minutes(5),
+      offers: Mutex::new(HashMap::new()),
+      grants: Mutex::new(HashMap::new()),
+    }
+  }
+
+  /// Sets how long credential offers and access tokens remain valid.
+  ///
+  /// # Errors
+  ///
+  /// Fails if a lifetime is too long to be represented as an expiration time.
+  pub fn with_lifetimes(
+    mut self,
+    offer_lifetime: Duration,
+    access_token_lifetime: Duration,
+  ) -> Result<Self, Oid4vciError> {
+    expiry(offer_lifetime)?;
+    expiry(access_token_lifetime)?;
+    self.offer_lifetime = offer_lifetime;
+    self.access_token_lifetime = access_token_lifetime;
+    Ok(self)
+  }
+
+  /// Returns the credential issuer metadata, to be served at [`CredentialIssuerMetadata::well_known_url`].
+  pub fn metadata(&self) -> &CredentialIssuerMetadata {
+    &self.metadata
+  }
+
+  /// Returns the authorization server metadata, to be served at `/.well-known/oauth-authorization-server`.
+  pub fn authorization_server_metadata(&self) -> AuthorizationServerMetadata {
+    AuthorizationServerMetadata::new(self.metadata.credential_issuer.clone())
+  }
+
+  /// Offers the credentials identified by `configuration_ids` about a holder described by `claims`.
+  ///
+  /// If `tx_code` is set, the wallet must send it together with the pre-authorized code; it must be delivered to
+  /// the holder over a channel different from that of the offer.
+  pub fn create_offer(
+    &self,
+    configuration_ids: Vec<String>,
+    claims: Object,
+    tx_code: Option<String>,
+  ) -> Result<CredentialOffer, Oid4vciError> {
+    if configuration_ids.is_empty() {
+      return Err(Oid4vciError::new(Oid4vciErrorCode::InvalidRequest).with_description("no credential is offered"));
+    }
+    if let Some(unknown) = configuration_ids
+      .iter()
+      .find(|id| self.metadata.configuration(id).is_none())
+    {
+      return Err(
+        Oid4vciError::new(Oid4vciErrorCode::UnsupportedCredentialType)
+          .with_description(format!("unknown credential configuration `{unknown}`")),
+      );
+    }
+
+    let pre_authorized_code: String = random_token()?;
+    let tx_code_description: Option<TxCode> = tx_code.as_ref().map(|tx_code| TxCode {
+      input_mode: Some(
+        if tx_code.chars().all(|c| c.is_ascii_digit()) {
+          "numeric"
+        } else {
+          "text"
+        }
+        .to_owned(),
+      ),
+      length: Some(tx_code.chars().count()),
+      description: None,
+    });
+    let expires_at: Timestamp = expiry(self.offer_lifetime)?;
+    let mut offers = self.offers.lock().expect("the issuer lock is never poisoned");
+    let now: Timestamp = Timestamp::now_utc();
+    offers.retain(|_, offer| offer.expires_at >= now);
+    offers.insert(
+      pre_authorized_code.clone(),
+      PendingOffer {
+        configuration_ids: configuration_ids.clone(),
+        tx_code,
+        claims,
+        expires_at,
+      },
+    );
+    drop(offers);
+
+    Ok(CredentialOffer {
+      credential_issuer: self.metadata.credential_issuer.clone(),
+      credential_configuration_ids: configuration_ids,
+      grants: Some(Grants {
+        pre_authorized_code: Some(PreAuthorizedCodeGrant {
+          pre_authorized_code,
+          tx_code: tx_code_description,
+        }),
+      }),
+    })
+  }
+
+  /// Handles a request to the token endpoint, exchanging a pre-authorized code for an access token.
+  ///
+  /// Pre-authorized codes can be redeemed only once, and are invalidated by a request with a wrong transaction code.
+  pub fn token(&self, request: &TokenRequest) -> Result<TokenResponse, Oid4vciError> {
+    if request.grant_type != PRE_AUTHORIZED_CODE_GRANT_TYPE {
+      return Err(
+        Oid4vciError::new(Oid4vciErrorCode::UnsupportedGrantType)
+          .with_description(format!("unsupported grant type `{}`", request.grant_type)),
+      );
+    }
+    let code: &str = request.pre_authorized_code.as_deref().ok_or_else(|| {
+      Oid4vciError::new(Oid4vciErrorCode::InvalidRequest).with_description("missing `pre-authorized_code`")
+    })?;
+
+    let offer: PendingOffer = self
+      .offers
+      .lock()
+      .expect("the issuer lock is never poisoned")
+      .remove(code)
+      .ok_or_else(|| {
+        Oid4vciError::new(Oid4vciErrorCode::InvalidGrant).with_description("unknown pre-authorized code")
+      })?;
+    if offer.expires_at < Timestamp::now_utc() {
+      return Err(
+        Oid4vciError::new(Oid4vciErrorCode::InvalidGrant).with_description("the pre-authorized code expired"),
+      );
+    }
+    if offer.tx_code.is_some() && offer.tx_code != request.tx_code {
+      return Err(Oid4vciError::new(Oid4vciErrorCode::InvalidGrant).with_description("invalid transaction code"));
+    }
+
+    let access_token: String = random_token()?;
+    let c_nonce: String = random_token()?;
+    let expires_at: Timestamp = expiry(self.access_token_lifetime)?;
+    let c_nonce_expires_at: Timestamp = expiry(self.c_nonce_lifetime)?;
+    let mut grants = self.grants.lock().expect("the issuer lock is never poisoned");
+    let now: Timestamp = Timestamp::now_utc();
+    grants.retain(|_, grant| grant.expires_at >= now);
+    grants.insert(
+      access_token.clone(),
+      AccessGrant {
+        configuration_ids: offer.configuration_ids,
+        claims: offer.claims,
+        expires_at,
+        c_nonce: c_nonce.clone(),
+        c_nonce_expires_at,
+      },
+    );
+    drop(grants);
+
+    Ok(TokenResponse {
+      access_token,
+      token_type: "Bearer".to_owned(),
+      expires_in: Some(seconds(self.access_token_lifetime)),
+      c_nonce: Some(c_nonce),
+      c_nonce_expires_in: Some(seconds(self.c_nonce_lifetime)),
+    })
+  }
+
+  /// Handles a request to the credential endpoint authorized by `access_token`, the value of the `Bearer`
+  /// authorization header.
+  ///
+  /// The proof of possession of the request is validated against the `c_nonce` last returned to the wallet. If it
+  /// references the holder key by `kid`, the DID document of the holder must be among `holder_documents`.
+  pub async fn credential<DOC>(
+    &self,
+    access_token: &str,
+    request: &CredentialRequest,
+    holder_documents: &[DOC],
+  ) -> Result<CredentialResponse, Oid4vciError>
+  where
+    DOC: AsRef<CoreDocument>,
+  {
+    // Validate the request while holding the lock, and rotate the nonce so that a proof cannot be replayed.
+    let (configuration_id, configuration, holder, claims) = {
+      let mut grants = self.grants.lock().expect("the issuer lock is never poisoned");
+      let now: Timestamp = Timestamp::now_utc();
+      grants.retain(|_, grant| grant.expires_at >= now);
+      let grant: &mut AccessGrant = grants
+        .get_mut(access_token)
+        .filter(|grant| grant.expires_at >= Timestamp::now_utc())
+        .ok_or_else(|| {
+          Oid4vciError::new(Oid4vciErrorCode::InvalidToken).with_description("invalid or expired access token")
+        })?;
+
+      let (configuration_id, configuration): (String, &CredentialConfiguration) = grant
+        .configuration_ids
+        .iter()
+        .filter_map(|id| Some((id.clone(), self.metadata.configuration(id)?)))
+        .find(|(_, configuration)| request.matches(configuration))
+        .ok_or_else(|| {
+          let code: Oid4vciErrorCode = if grant
+            .configuration_ids
+            .iter()
+            .filter_map(|id| self.metadata.configuration(id))
+            .any(|configuration| configuration.format == request.format)
+          {
+            Oid4vciErrorCode::UnsupportedCredentialType
+          } else {
+            Oid4vciErrorCode::UnsupportedCredentialFormat
+          };
+          Oid4vciError::new(code).with_description("the access token does not authorize the requested credential")
+        })?;
+
+      let holder: HolderBinding = match self.validate_proof(grant, configuration, request, holder_documents) {
+        Ok(holder) => holder,
+        Err(mut err) => {
+          if err.error == Oid4vciErrorCode::InvalidNonce {
+            let c_nonce: String = random_token()?;
+            grant.c_nonce = c_nonce.clone();
+            grant.c_nonce_expires_at = expiry(self.c_nonce_lifetime)?;
+            err.c_nonce = Some(c_nonce);
+            err.c_nonce_expires_in = Some(seconds(self.c_nonce_lifetime));
+          }
+          return Err(err);
+        }
+      };
+
+      grant.c_nonce = random_token()?;
+      grant.c_nonce_expires_at = expiry(self.c_nonce_lifetime)?;
+      (configuration_id, configuration.clone(), holder, grant.claims.clone())
+    };
+
+    let credential: Value = self
+      .signer
+      .sign(IssuanceRequest {
+        configuration_id: &configuration_id,
+        configuration: &configuration,
+        holder: &holder,
+        claims: &claims,
+      })
+      .await
+      .map_err(|err| Oid4vciError::new(Oid4vciErrorCode::ServerError).with_description(err.to_string()))?;
+
+    let c_nonce: Option<String> = self
+      .grants
+      .lock()
+      .expect("the issuer lock is never poisoned")
+      .get(access_token)
+      .map(|grant| grant.c_nonce.clone());
+    Ok(CredentialResponse {
+      credential,
+      c_nonce_expires_in: c_nonce.as_ref().map(|_| seconds(self.c_nonce_lifetime)),
+      c_nonce,
+    })
+  }
+
+  fn validate_proof<DOC>(
+    &self,
+    grant: &AccessGrant,
+    configuration: &CredentialConfiguration,
+    request: &CredentialRequest,
+    holder_documents: &[DOC],
+  ) -> Result<HolderBinding, Oid4vciError>
+  where
+    DOC: AsRef<CoreDocument>,
+  {
+    let proof_jwt: &str = match &request.proof {
+      Some(proof) if proof.proof_type == JWT_PROOF_TYPE => proof
+        .jwt
+        .as_deref()
+        .ok_or_else(|| Oid4vciError::new(Oid4vciErrorCode::InvalidProof).with_description("missing `jwt`"))?,
+      Some(proof) => {
+        return Err(
+          Oid4vciError::new(Oid4vciErrorCode::InvalidProof)
+            .with_description(format!("unsupported proof type `{}`", proof.proof_type)),
+        )
+      }
+      None => {
+        return Err(Oid4vciError::new(Oid4vciErrorCode::InvalidProof).with_description("missing proof of possession"))
+      }
+    };
+    let allowed_algorithms: Vec<String> = configuration
+      .jwt_proof_algs()
+      .ok_or_else(|| {
+        Oid4vciError::new(Oid4vciErrorCode::InvalidProof).with_description("the credential does not accept JWT proofs")
+      })?
+      .to_vec();
+
+    let options: ProofJwtValidationOptions =
+      ProofJwtValidationOptions::new(self.metadata.credential_issuer.as_str()).allowed_algorithms(allowed_algorithms);
+    let nonce_expired: bool = grant.c_nonce_expires_at < Timestamp::now_utc();
+    let options: ProofJwtValidationOptions = if nonce_expired {
+      options
+    } else {
+      options.nonce(grant.c_nonce.clone())
+    };
+    let (holder, _): (HolderBinding, ProofJwtClaims) =
+      self.proof_validator.validate(proof_jwt, holder_documents, &options)?;
+    if nonce_expired {
+      return Err(Oid4vciError::new(Oid4vciErrorCode::InvalidNonce).with_description("the `c_nonce` expired"));
+    }
+    Ok(holder)
+  }
+}
+
+impl<S, V> std::fmt::Debug for CredentialIssuer<S, V> {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    f.debug_struct("CredentialIssuer")
+      .field("metadata", &self.metadata)
+      .finish_non_exhaustive()
+  }
+}
+
+/// Returns a fresh, unguessable token.
+fn random_token() -> Result<String, Oid4vciError> {
+  let mut token: [u8; 32] = [0; 32];
+  crypto::utils::rand::fill(&mut token)
+    .map_err(|err| Oid4vciError::new(Oid4vciErrorCode::ServerError).with_description(err.to_string()))?;
+  Ok(encode_b64(token))
+}
+
+/// Returns the expiration time of an offer, token or nonce issued now.
+fn expiry(lifetime: Duration) -> Result<Timestamp, Oid4vciError> {
+  Timestamp::now_utc().checked_add(lifetime).ok_or_else(|| {
+    Oid4vciError::new(Oid4vciErrorCode::ServerError).with_description("the lifetime exceeds the supported time range")
+  })
+}
+
+fn seconds(duration: Duration) -> u64 {
+  let now: Timestamp = Timestamp::now_utc();
+  now
+    .checked_add(duration)
+    .map_or(0, |later| (later.to_unix() - now.to_unix()).max(0) as u64)
+}
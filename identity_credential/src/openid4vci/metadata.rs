@@ -0,0 +1,277 @@
+// Copyright 2020-2024 IOTA Stiftung, Fondazione Links
+// SPDX-License-Identifier: Apache-2.0
+
+use std::collections::BTreeMap;
+use std::fmt::Display;
+
+use identity_core::common::Object;
+use identity_core::common::Url;
+use serde::Deserialize;
+use serde::Serialize;
+
+use super::PRE_AUTHORIZED_CODE_GRANT_TYPE;
+
+/// The format of a credential issued through OpenID for Verifiable Credential Issuance.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[non_exhaustive]
+pub enum CredentialFormat {
+  /// A credential of the VC Data Model secured as a JWT.
+  #[serde(rename = "jwt_vc_json")]
+  JwtVcJson,
+  /// A selectively disclosable credential secured as an SD-JWT.
+  #[serde(rename = "vc+sd-jwt")]
+  SdJwt,
+  /// A selectively disclosable credential secured as a JSON Proof Token.
+  #[serde(rename = "jpt")]
+  Jpt,
+}
+
+impl CredentialFormat {
+  /// Returns the string representation of the format.
+  pub const fn as_str(&self) -> &'static str {
+    match self {
+      Self::JwtVcJson => "jwt_vc_json",
+      Self::SdJwt => "vc+sd-jwt",
+      Self::Jpt => "jpt",
+    }
+  }
+}
+
+impl Display for CredentialFormat {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    f.write_str(self.as_str())
+  }
+}
+
+/// The [metadata](https://openid.net/specs/openid-4-verifiable-credential-issuance-1_0-13.html#name-credential-issuer-metadata)
+/// of a credential issuer, published at `/.well-known/openid-credential-issuer`.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CredentialIssuerMetadata {
+  /// The identifier of the credential issuer.
+  pub credential_issuer: Url,
+  /// The authorization servers trusted by the credential issuer. If absent, the issuer is its own authorization
+  /// server.
+  #[serde(default, skip_serializing_if = "Vec::is_empty")]
+  pub authorization_servers: Vec<Url>,
+  /// The URL of the credential endpoint.
+  pub credential_endpoint: Url,
+  /// The credentials the issuer can issue, by credential configuration id.
+  pub credential_configurations_supported: BTreeMap<String, CredentialConfiguration>,
+  /// Display properties of the issuer.
+  #[serde(default, skip_serializing_if = "Vec::is_empty")]
+  pub display: Vec<Object>,
+}
+
+impl CredentialIssuerMetadata {
+  /// Creates new [`CredentialIssuerMetadata`] for the issuer identified by `credential_issuer`, whose credential
+  /// endpoint is `<credential_issuer>/credential`.
+  pub fn new(credential_issuer: Url) -> Self {
+    let credential_endpoint: Url = endpoint(&credential_issuer, "credential");
+    Self {
+      credential_issuer,
+      authorization_servers: Vec::new(),
+      credential_endpoint,
+      credential_configurations_supported: BTreeMap::new(),
+      display: Vec::new(),
+    }
+  }
+
+  /// Adds the credential configuration identified by `id`.
+  pub fn with_configuration(mut self, id: impl Into<String>, configuration: CredentialConfiguration) -> Self {
+    self
+      .credential_configurations_supported
+      .insert(id.into(), configuration);
+    self
+  }
+
+  /// Returns the credential configuration identified by `id`.
+  pub fn configuration(&self, id: &str) -> Option<&CredentialConfiguration> {
+    self.credential_configurations_supported.get(id)
+  }
+
+  /// Returns the URL at which this metadata is published.
+  pub fn well_known_url(&self) -> Url {
+    endpoint(&self.credential_issuer, ".well-known/openid-credential-issuer")
+  }
+}
+
+/// The metadata of an [OAuth 2.0 authorization server](https://www.rfc-editor.org/rfc/rfc8414) issuing access
+/// tokens for the credential endpoint, published at `/.well-known/oauth-authorization-server`.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AuthorizationServerMetadata {
+  /// The identifier of the authorization server.
+  pub issuer: Url,
+  /// The URL of the token endpoint.
+  pub token_endpoint: Url,
+  /// The supported grant types.
+  #[serde(default, skip_serializing_if = "Vec::is_empty")]
+  pub grant_types_supported: Vec<String>,
+  /// Whether a token request with a pre-authorized code may omit the client identifier.
+  #[serde(
+    rename = "pre-authorized_grant_anonymous_access_supported",
+    default,
+    skip_serializing_if = "std::ops::Not::not"
+  )]
+  pub pre_authorized_grant_anonymous_access_supported: bool,
+}
+
+impl AuthorizationServerMetadata {
+  /// Creates new [`AuthorizationServerMetadata`] for the authorization server identified by `issuer`, whose token
+  /// endpoint is `<issuer>/token` and which supports anonymous pre-authorized code grants.
+  pub fn new(issuer: Url) -> Self {
+    let token_endpoint: Url = endpoint(&issuer, "token");
+    Self {
+      issuer,
+      token_endpoint,
+      grant_types_supported: vec![PRE_AUTHORIZED_CODE_GRANT_TYPE.to_owned()],
+      pre_authorized_grant_anonymous_access_supported: true,
+    }
+  }
+}
+
+/// A [credential configuration](https://openid.net/specs/openid-4-verifiable-credential-issuance-1_0-13.html#name-credentials-supported-param)
+/// describing a credential the issuer can issue.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CredentialConfiguration {
+  /// The format of the credential.
+  pub format: CredentialFormat,
+  /// The OAuth 2.0 scope requesting the credential.
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub scope: Option<String>,
+  /// How the credential is bound to the holder, e.g. `jwk` or `did:iota`.
+  #[serde(default, skip_serializing_if = "Vec::is_empty")]
+  pub cryptographic_binding_methods_supported: Vec<String>,
+  /// The algorithms the issuer secures the credential with.
+  #[serde(default, skip_serializing_if = "Vec::is_empty")]
+  pub credential_signing_alg_values_supported: Vec<String>,
+  /// The accepted proofs of possession of the holder key, by proof type.
+  #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+  pub proof_types_supported: BTreeMap<String, ProofTypeMetadata>,
+  /// The types and claims of a `jwt_vc_json` or `jpt` credential.
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub credential_definition: Option<CredentialDefinition>,
+  /// The type of a `vc+sd-jwt` credential.
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub vct: Option<String>,
+  /// Display properties of the credential.
+  #[serde(default, skip_serializing_if = "Vec::is_empty")]
+  pub display: Vec<Object>,
+}
+
+impl CredentialConfiguration {
+  /// Creates a new [`CredentialConfiguration`] for a credential of the given `format`.
+  pub fn new(format: CredentialFormat) -> Self {
+    Self {
+      format,
+      scope: None,
+      cryptographic_binding_methods_supported: Vec::new(),
+      credential_signing_alg_values_supported: Vec::new(),
+      proof_types_supported: BTreeMap::new(),
+      credential_definition: None,
+      vct: None,
+      display: Vec::new(),
+    }
+  }
+
+  /// Creates a new `jwt_vc_json` [`CredentialConfiguration`] for credentials of the given `types`.
+  pub fn jwt_vc_json(types: Vec<String>) -> Self {
+    let mut configuration: Self = Self::new(CredentialFormat::JwtVcJson);
+    configuration.credential_definition = Some(CredentialDefinition::new(types));
+    configuration
+  }
+
+  /// Creates a new `vc+sd-jwt` [`CredentialConfiguration`] for credentials of type `vct`.
+  pub fn sd_jwt(vct: impl Into<String>) -> Self {
+    let mut configuration: Self = Self::new(CredentialFormat::SdJwt);
+    configuration.vct = Some(vct.into());
+    configuration
+  }
+
+  /// Creates a new `jpt` [`CredentialConfiguration`] for credentials of the given `types`.
+  pub fn jpt(types: Vec<String>) -> Self {
+    let mut configuration: Self = Self::new(CredentialFormat::Jpt);
+    configuration.credential_definition = Some(CredentialDefinition::new(types));
+    configuration
+  }
+
+  /// Sets the algorithms the issuer secures the credential with, e.g. `ML-DSA-44` or
+  /// `id-MLDSA44-Ed25519-SHA512` for post-quantum and hybrid JWTs.
+  pub fn with_signing_algs<I, A>(mut self, algs: I) -> Self
+  where
+    I: IntoIterator<Item = A>,
+    A: ToString,
+  {
+    self.credential_signing_alg_values_supported = algs.into_iter().map(|alg| alg.to_string()).collect();
+    self
+  }
+
+  /// Accepts proofs of possession secured as JWTs signed with one of `algs`.
+  pub fn with_jwt_proof_algs<I, A>(mut self, algs: I) -> Self
+  where
+    I: IntoIterator<Item = A>,
+    A: ToString,
+  {
+    self.proof_types_supported.insert(
+      JWT_PROOF_TYPE.to_owned(),
+      ProofTypeMetadata {
+        proof_signing_alg_values_supported: algs.into_iter().map(|alg| alg.to_string()).collect(),
+      },
+    );
+    self
+  }
+
+  /// Sets how the credential is bound to the holder.
+  pub fn with_binding_methods<I, A>(mut self, methods: I) -> Self
+  where
+    I: IntoIterator<Item = A>,
+    A: ToString,
+  {
+    self.cryptographic_binding_methods_supported = methods.into_iter().map(|method| method.to_string()).collect();
+    self
+  }
+
+  /// Returns the algorithms accepted for proofs of possession secured as JWTs, if such proofs are accepted.
+  pub fn jwt_proof_algs(&self) -> Option<&[String]> {
+    self
+      .proof_types_supported
+      .get(JWT_PROOF_TYPE)
+      .map(|proof_type| proof_type.proof_signing_alg_values_supported.as_slice())
+  }
+}
+
+/// The `proof_type` of a proof of possession secured as a JWT.
+pub const JWT_PROOF_TYPE: &str = "jwt";
+
+/// The algorithms accepted for a proof type.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProofTypeMetadata {
+  /// The accepted signature algorithms.
+  pub proof_signing_alg_values_supported: Vec<String>,
+}
+
+/// The types, and optionally the claims, of a credential of the VC Data Model.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CredentialDefinition {
+  /// The types of the credential.
+  #[serde(rename = "type")]
+  pub types: Vec<String>,
+  /// Metadata of the claims of the credential subject.
+  #[serde(rename = "credentialSubject", default, skip_serializing_if = "Option::is_none")]
+  pub credential_subject: Option<Object>,
+}
+
+impl CredentialDefinition {
+  /// Creates a new [`CredentialDefinition`] for the given `types`.
+  pub fn new(types: Vec<String>) -> Self {
+    Self {
+      types,
+      credential_subject: None,
+    }
+  }
+}
+
+/// Joins `path` to `base`, treating `base` as a directory.
+fn endpoint(base: &Url, path: &str) -> Url {
+  let base: String = base.as_str().trim_end_matches('/').to_owned();
+  Url::parse(format!("{base}/{path}")).expect("appending a path segment to a URL yields a valid URL")
+}
@@ -0,0 +1,39 @@
+// Copyright 2020-2024 IOTA Stiftung, Fondazione Links
+// SPDX-License-Identifier: Apache-2.0
+
+//! Issuing credentials with
+//! [OpenID for Verifiable Credential Issuance](https://openid.net/specs/openid-4-verifiable-credential-issuance-1_0-13.html)
+//! using the pre-authorized code flow.
+//!
+//! A [`CredentialIssuer`] publishes its [`CredentialIssuerMetadata`], creates [`CredentialOffer`]s and handles the
+//! requests of the token and credential endpoints. The handlers are transport-agnostic: they take and return the
+//! request and response bodies, leaving HTTP to the caller. Issued credentials are secured by a
+//! [`CredentialSigner`], while the proofs of possession of the holder key are validated by a
+//! [`ProofJwtValidator`], supporting post-quantum and composite algorithms through its [`JwsVerifier`].
+//!
+//! A wallet follows the flow with a [`WalletIssuance`], reaching the issuer through an [`IssuerClient`].
+//! [`LocalIssuerClient`] connects a wallet to a [`CredentialIssuer`] in the same process.
+//!
+//! Credentials in the `jwt_vc_json`, `vc+sd-jwt` and `jpt` formats are supported.
+//!
+//! [`JwsVerifier`]: identity_verification::jws::JwsVerifier
+
+mod credential_request;
+mod error;
+mod issuer;
+mod metadata;
+mod offer;
+mod proof;
+#[cfg(test)]
+mod tests;
+mod token;
+mod wallet;
+
+pub use self::credential_request::*;
+pub use self::error::*;
+pub use self::issuer::*;
+pub use self::metadata::*;
+pub use self::offer::*;
+pub use self::proof::*;
+pub use self::token::*;
+pub use self::wallet::*;
@@ -0,0 +1,93 @@
+// Copyright 2020-2024 IOTA Stiftung, Fondazione Links
+// SPDX-License-Identifier: Apache-2.0
+
+use identity_core::common::Url;
+use identity_core::convert::FromJson;
+use identity_core::convert::ToJson;
+use serde::Deserialize;
+use serde::Serialize;
+
+use super::Oid4vciWalletError;
+
+/// The URL scheme of a credential offer passed by value.
+pub const CREDENTIAL_OFFER_SCHEME: &str = "openid-credential-offer";
+
+/// A [credential offer](https://openid.net/specs/openid-4-verifiable-credential-issuance-1_0-13.html#name-credential-offer)
+/// made by a credential issuer to a wallet.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CredentialOffer {
+  /// The identifier of the credential issuer.
+  pub credential_issuer: Url,
+  /// The ids of the offered credential configurations, see
+  /// [`CredentialIssuerMetadata`](super::CredentialIssuerMetadata).
+  pub credential_configuration_ids: Vec<String>,
+  /// The grants the wallet may use to obtain an access token.
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub grants: Option<Grants>,
+}
+
+impl CredentialOffer {
+  /// Returns the pre-authorized code grant of the offer, if any.
+  pub fn pre_authorized_code(&self) -> Option<&PreAuthorizedCodeGrant> {
+    self.grants.as_ref()?.pre_authorized_code.as_ref()
+  }
+
+  /// Encodes the offer by value in an `openid-credential-offer://` URI, to be presented e.g. as a QR code.
+  pub fn to_uri(&self) -> String {
+    let offer: String = self.to_json().expect("a credential offer always serializes to JSON");
+    let query: String = url::form_urlencoded::Serializer::new(String::new())
+      .append_pair("credential_offer", &offer)
+      .finish();
+    format!("{CREDENTIAL_OFFER_SCHEME}://?{query}")
+  }
+
+  /// Decodes an offer passed by value in an `openid-credential-offer://` URI.
+  pub fn from_uri(uri: &str) -> Result<Self, Oid4vciWalletError> {
+    let query: &str = uri
+      .strip_prefix(CREDENTIAL_OFFER_SCHEME)
+      .and_then(|rest| rest.strip_prefix("://"))
+      .map(|rest| rest.trim_start_matches('/').trim_start_matches('?'))
+      .ok_or_else(|| Oid4vciWalletError::InvalidOffer(format!("expected a `{CREDENTIAL_OFFER_SCHEME}` URI")))?;
+    let offer: String = url::form_urlencoded::parse(query.as_bytes())
+      .find_map(|(key, value)| (key == "credential_offer").then_some(value.into_owned()))
+      .ok_or_else(|| Oid4vciWalletError::InvalidOffer("missing `credential_offer` parameter".to_owned()))?;
+    Self::from_json(&offer).map_err(|err| Oid4vciWalletError::InvalidOffer(err.to_string()))
+  }
+}
+
+/// The grants of a [`CredentialOffer`].
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Grants {
+  /// The pre-authorized code grant.
+  #[serde(
+    rename = "urn:ietf:params:oauth:grant-type:pre-authorized_code",
+    default,
+    skip_serializing_if = "Option::is_none"
+  )]
+  pub pre_authorized_code: Option<PreAuthorizedCodeGrant>,
+}
+
+/// A pre-authorized code the wallet exchanges for an access token at the token endpoint.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PreAuthorizedCodeGrant {
+  /// The pre-authorized code.
+  #[serde(rename = "pre-authorized_code")]
+  pub pre_authorized_code: String,
+  /// Describes the transaction code the wallet must send together with the pre-authorized code, if any.
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub tx_code: Option<TxCode>,
+}
+
+/// Describes a transaction code delivered to the holder out of band, e.g. by e-mail.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TxCode {
+  /// Whether the code is `numeric` or `text`.
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub input_mode: Option<String>,
+  /// The length of the code.
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub length: Option<usize>,
+  /// Guidance for the holder on how to obtain the code.
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub description: Option<String>,
+}
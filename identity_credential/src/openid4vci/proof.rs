@@ -0,0 +1,239 @@
+// Copyright 2020-2024 IOTA Stiftung, Fondazione Links
+// SPDX-License-Identifier: Apache-2.0
+
+use identity_core::common::Duration;
+use identity_core::common::Timestamp;
+use identity_core::convert::FromJson;
+use identity_did::DIDUrl;
+use identity_document::document::CoreDocument;
+use identity_verification::jwk::Jwk;
+use identity_verification::jws::DecodedJws;
+use identity_verification::jws::Decoder;
+use identity_verification::jws::JwsAlgorithm;
+use identity_verification::jws::JwsValidationItem;
+use identity_verification::jws::JwsVerifier;
+use identity_verification::MethodData;
+use serde::Deserialize;
+use serde::Serialize;
+
+use super::Oid4vciError;
+use super::Oid4vciErrorCode;
+
+/// The `typ` header parameter of a proof of possession secured as a JWT.
+pub const PROOF_JWT_TYP: &str = "openid4vci-proof+jwt";
+
+/// The claims of a [proof of possession](https://openid.net/specs/openid-4-verifiable-credential-issuance-1_0-13.html#name-jwt-proof-type)
+/// secured as a JWT.
+///
+/// The wallet signs these claims with the holder key, setting the `typ` header parameter to [`PROOF_JWT_TYP`] and
+/// either the `kid` header parameter to the DID URL of the verification method or the `jwk` header parameter to
+/// the public key.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProofJwtClaims {
+  /// The client identifier of the wallet, if any.
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub iss: Option<String>,
+  /// The identifier of the credential issuer.
+  pub aud: String,
+  /// The time at which the proof was created, in seconds since the Unix epoch.
+  pub iat: i64,
+  /// The `c_nonce` provided by the credential issuer.
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub nonce: Option<String>,
+}
+
+impl ProofJwtClaims {
+  /// Creates new [`ProofJwtClaims`] for the credential issuer `aud`, created now.
+  pub fn new(aud: impl Into<String>, nonce: Option<String>) -> Self {
+    Self {
+      iss: None,
+      aud: aud.into(),
+      iat: Timestamp::now_utc().to_unix(),
+      nonce,
+    }
+  }
+}
+
+/// The key a credential is bound to, as proven by a proof of possession.
+#[allow(clippy::large_enum_variant)]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum HolderBinding {
+  /// The verification method of a DID document, referenced by the `kid` of the proof.
+  Did(DIDUrl),
+  /// The public key embedded as `jwk` in the proof.
+  Jwk(Jwk),
+}
+
+/// Options to validate a proof of possession secured as a JWT.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct ProofJwtValidationOptions {
+  /// The expected audience, i.e. the identifier of the credential issuer.
+  pub audience: String,
+  /// The expected `c_nonce`.
+  pub nonce: Option<String>,
+  /// The accepted signature algorithms. If empty, every algorithm is accepted.
+  pub allowed_algorithms: Vec<String>,
+  /// The maximum age of the proof.
+  pub max_age: Duration,
+  /// The maximum amount by which `iat` may lie in the future, to account for clock skew.
+  pub leeway: Duration,
+}
+
+impl ProofJwtValidationOptions {
+  /// Creates new [`ProofJwtValidationOptions`] for proofs addressed to `audience`, accepting proofs created in the
+  /// last five minutes.
+  pub fn new(audience: impl Into<String>) -> Self {
+    Self {
+      audience: audience.into(),
+      nonce: None,
+      allowed_algorithms: Vec::new(),
+      max_age: Duration::minutes(5),
+      leeway: Duration::seconds(60),
+    }
+  }
+
+  /// Sets the expected `c_nonce`.
+  pub fn nonce(mut self, nonce: impl Into<String>) -> Self {
+    self.nonce = Some(nonce.into());
+    self
+  }
+
+  /// Sets the accepted signature algorithms.
+  pub fn allowed_algorithms(mut self, algorithms: Vec<String>) -> Self {
+    self.allowed_algorithms = algorithms;
+    self
+  }
+
+  /// Sets the maximum age of the proof.
+  pub fn max_age(mut self, max_age: Duration) -> Self {
+    self.max_age = max_age;
+    self
+  }
+}
+
+/// A validator of proofs of possession secured as JWTs, delegating signature verification to `V`.
+///
+/// Proofs signed with a composite algorithm such as `id-MLDSA44-Ed25519-SHA512` are verified by calling `V` once
+/// for the traditional and once for the post-quantum component, so `V` must support both component algorithms.
+#[derive(Debug)]
+pub struct ProofJwtValidator<V>(V);
+
+impl<V: JwsVerifier> ProofJwtValidator<V> {
+  /// Creates a new [`ProofJwtValidator`] that delegates cryptographic signature verification to the given
+  /// `signature_verifier`.
+  pub fn with_signature_verifier(signature_verifier: V) -> Self {
+    Self(signature_verifier)
+  }
+
+  /// Validates `proof_jwt` and returns the key it proves possession of together with its claims.
+  ///
+  /// If the proof references the holder key by `kid`, the DID document of the holder must be among
+  /// `holder_documents`. The returned [`Oid4vciError`] is either `invalid_proof` or, if the proof does not contain
+  /// the expected nonce, `invalid_nonce`.
+  pub fn validate<DOC>(
+    &self,
+    proof_jwt: &str,
+    holder_documents: &[DOC],
+    options: &ProofJwtValidationOptions,
+  ) -> Result<(HolderBinding, ProofJwtClaims), Oid4vciError>
+  where
+    DOC: AsRef<CoreDocument>,
+  {
+    let decoded: JwsValidationItem<'_> = Decoder::new()
+      .decode_compact_serialization(proof_jwt.as_bytes(), None)
+      .map_err(|err| invalid_proof(format!("could not decode the proof: {err}")))?;
+    let header = decoded
+      .protected_header()
+      .ok_or_else(|| invalid_proof("missing protected header"))?;
+    if header.typ() != Some(PROOF_JWT_TYP) {
+      return Err(invalid_proof(format!(
+        "the `typ` of the proof must be `{PROOF_JWT_TYP}`"
+      )));
+    }
+    let alg: JwsAlgorithm = decoded.alg().ok_or_else(|| invalid_proof("missing `alg`"))?;
+    if !options.allowed_algorithms.is_empty()
+      && !options.allowed_algorithms.iter().any(|allowed| *allowed == alg.name())
+    {
+      return Err(invalid_proof(format!("algorithm `{alg}` is not accepted")));
+    }
+
+    let kid: Option<String> = header.kid().map(ToOwned::to_owned);
+    let jwk: Option<Jwk> = header.jwk().cloned();
+
+    let (binding, decoded): (HolderBinding, DecodedJws<'_>) = match (kid, jwk) {
+      (Some(kid), None) => {
+        let method_id: DIDUrl =
+          DIDUrl::parse(&kid).map_err(|err| invalid_proof(format!("could not parse `kid` as a DID URL: {err}")))?;
+        let document: &CoreDocument = holder_documents
+          .iter()
+          .map(AsRef::as_ref)
+          .find(|document| document.id() == method_id.did())
+          .ok_or_else(|| invalid_proof("the DID document of the holder is not available"))?;
+        let data: &MethodData = document
+          .resolve_method(&method_id, None)
+          .map(|method| method.data())
+          .ok_or_else(|| invalid_proof("the `kid` does not reference a verification method of the holder"))?;
+        let decoded: DecodedJws<'_> = match (data.public_key_jwk(), data.composite_public_key()) {
+          (Some(jwk), _) => decoded.verify(&self.0, jwk),
+          (None, Some(composite)) => decoded.verify_hybrid(
+            &self.0,
+            &self.0,
+            composite.traditional_public_key(),
+            composite.pq_public_key(),
+          ),
+          (None, None) => {
+            return Err(invalid_proof(
+              "the verification method of the holder does not contain a JWK",
+            ))
+          }
+        }
+        .map_err(|err| invalid_proof(format!("invalid signature: {err}")))?;
+        (HolderBinding::Did(method_id), decoded)
+      }
+      (None, Some(jwk)) => {
+        if !jwk.is_public() {
+          return Err(invalid_proof("the `jwk` of the proof must be a public key"));
+        }
+        let decoded: DecodedJws<'_> = decoded
+          .verify(&self.0, &jwk)
+          .map_err(|err| invalid_proof(format!("invalid signature: {err}")))?;
+        (HolderBinding::Jwk(jwk), decoded)
+      }
+      _ => return Err(invalid_proof("the proof must contain exactly one of `kid` and `jwk`")),
+    };
+
+    let claims: ProofJwtClaims = ProofJwtClaims::from_json_slice(&decoded.claims)
+      .map_err(|err| invalid_proof(format!("invalid claims: {err}")))?;
+    // Identifiers of credential issuers are compared regardless of a trailing slash.
+    if claims.aud.trim_end_matches('/') != options.audience.trim_end_matches('/') {
+      return Err(invalid_proof("the `aud` of the proof is not the credential issuer"));
+    }
+
+    let now: Timestamp = Timestamp::now_utc();
+    let issued_at: Timestamp =
+      Timestamp::from_unix(claims.iat).map_err(|_| invalid_proof("the `iat` of the proof is not a valid time"))?;
+    let not_after: Option<Timestamp> = now.checked_add(options.leeway);
+    let not_before: Option<Timestamp> = now.checked_sub(options.max_age);
+    if not_after.map_or(false, |not_after| issued_at > not_after)
+      || not_before.map_or(false, |not_before| issued_at < not_before)
+    {
+      return Err(invalid_proof(
+        "the `iat` of the proof is not within the accepted time frame",
+      ));
+    }
+
+    if options.nonce.is_some() && claims.nonce != options.nonce {
+      return Err(
+        Oid4vciError::new(Oid4vciErrorCode::InvalidNonce)
+          .with_description("the proof does not contain the expected `c_nonce`"),
+      );
+    }
+
+    Ok((binding, claims))
+  }
+}
+
+fn invalid_proof(description: impl Into<String>) -> Oid4vciError {
+  Oid4vciError::new(Oid4vciErrorCode::InvalidProof).with_description(description)
+}
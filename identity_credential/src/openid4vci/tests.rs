@@ -0,0 +1,458 @@
+// Copyright 2020-2024 IOTA Stiftung, Fondazione Links
+// SPDX-License-Identifier: Apache-2.0
+
+use async_trait::async_trait;
+use crypto::signatures::ed25519::SecretKey;
+use identity_core::common::Duration;
+use identity_core::common::Object;
+use identity_core::common::Url;
+use identity_core::convert::FromJson;
+use identity_core::convert::ToJson;
+use identity_did::DID;
+use identity_document::document::CoreDocument;
+use identity_eddsa_verifier::EdDSAJwsVerifier;
+use identity_verification::jws::CharSet;
+use identity_verification::jws::CompactJwsEncoder;
+use identity_verification::jws::CompactJwsEncodingOptions;
+use identity_verification::jws::JwsAlgorithm;
+use identity_verification::jws::JwsHeader;
+use serde_json::json;
+use serde_json::Value;
+
+use super::*;
+use crate::credential::Credential;
+use crate::credential::CredentialBuilder;
+use crate::credential::Jwt;
+use crate::credential::Subject;
+use crate::validator::test_utils::encode_public_ed25519_jwk;
+use crate::validator::test_utils::generate_jwk_document_with_keys;
+use crate::validator::FailFast;
+use crate::validator::JwtCredentialValidationOptions;
+use crate::validator::JwtCredentialValidator;
+
+const ISSUER_URL: &str = "https://issuer.example.com";
+const JWT_CONFIGURATION: &str = "UniversityDegree_jwt_vc_json";
+const SD_JWT_CONFIGURATION: &str = "UniversityDegree_sd_jwt";
+const JPT_CONFIGURATION: &str = "UniversityDegree_jpt";
+
+fn sign_jws(header: &JwsHeader, payload: &[u8], secret_key: &SecretKey) -> String {
+  let options: CompactJwsEncodingOptions = CompactJwsEncodingOptions::NonDetached {
+    charset_requirements: CharSet::Default,
+  };
+  let encoder: CompactJwsEncoder<'_> = CompactJwsEncoder::new_with_options(payload, header, options).unwrap();
+  let signature: [u8; 64] = secret_key.sign(encoder.signing_input()).to_bytes();
+  encoder.into_jws(&signature)
+}
+
+/// Signs `claims` as a proof of possession, referencing the holder key by `kid` or embedding it as `jwk`.
+fn proof_jwt(claims: &ProofJwtClaims, secret_key: &SecretKey, kid: Option<&str>) -> String {
+  let mut header: JwsHeader = JwsHeader::new();
+  header.set_alg(JwsAlgorithm::EdDSA);
+  header.set_typ(PROOF_JWT_TYP);
+  match kid {
+    Some(kid) => header.set_kid(kid),
+    None => header.set_jwk(encode_public_ed25519_jwk(&secret_key.public_key())),
+  }
+  sign_jws(&header, claims.to_json().unwrap().as_bytes(), secret_key)
+}
+
+/// Issues JWT credentials signed with the key of `document`, and placeholders for the other formats.
+struct TestSigner {
+  document: CoreDocument,
+  secret_key: SecretKey,
+  fragment: String,
+}
+
+#[async_trait]
+impl CredentialSigner for TestSigner {
+  async fn sign(&self, request: IssuanceRequest<'_>) -> Result<Value, CredentialSignerError> {
+    let subject_id: String = match request.holder {
+      HolderBinding::Did(method_id) => method_id.did().to_string(),
+      HolderBinding::Jwk(jwk) => format!("urn:jwk:{}", jwk.thumbprint_sha256_b64()),
+    };
+    let mut subject: Object = request.claims.clone();
+    subject.insert("id".to_owned(), Value::String(subject_id));
+
+    match request.configuration.format {
+      CredentialFormat::JwtVcJson => {
+        let credential: Credential = CredentialBuilder::default()
+          .issuer(Url::parse(self.document.id().as_str())?)
+          .type_("UniversityDegreeCredential")
+          .subject(Subject::from_json_value(Value::Object(subject.into_iter().collect()))?)
+          .build()?;
+        let mut header: JwsHeader = JwsHeader::new();
+        header.set_alg(JwsAlgorithm::EdDSA);
+        header.set_kid(format!("{}{}", self.document.id(), self.fragment));
+        let payload: String = credential.serialize_jwt(None)?;
+        Ok(Value::String(sign_jws(&header, payload.as_bytes(), &self.secret_key)))
+      }
+      format => Ok(Value::String(format!("{format}:{}", request.configuration_id))),
+    }
+  }
+}
+
+fn metadata() -> CredentialIssuerMetadata {
+  let algs = [
+    JwsAlgorithm::EdDSA,
+    JwsAlgorithm::ML_DSA_44,
+    JwsAlgorithm::IdMldsa44Ed25519Sha512,
+  ];
+  let types: Vec<String> = vec![
+    "VerifiableCredential".to_owned(),
+    "UniversityDegreeCredential".to_owned(),
+  ];
+  CredentialIssuerMetadata::new(Url::parse(ISSUER_URL).unwrap())
+    .with_configuration(
+      JWT_CONFIGURATION,
+      CredentialConfiguration::jwt_vc_json(types.clone())
+        .with_binding_methods(["did:example", "jwk"])
+        .with_signing_algs(algs)
+        .with_jwt_proof_algs(algs),
+    )
+    .with_configuration(
+      SD_JWT_CONFIGURATION,
+      CredentialConfiguration::sd_jwt("UniversityDegreeCredential")
+        .with_signing_algs(algs)
+        .with_jwt_proof_algs(algs),
+    )
+    .with_configuration(
+      JPT_CONFIGURATION,
+      CredentialConfiguration::jpt(types)
+        .with_signing_algs(["BBS", "SU-ES256"])
+        .with_jwt_proof_algs(algs),
+    )
+}
+
+fn issuer() -> (CredentialIssuer<TestSigner, EdDSAJwsVerifier>, CoreDocument) {
+  let (document, secret_key, fragment) = generate_jwk_document_with_keys();
+  let signer: TestSigner = TestSigner {
+    document: document.clone(),
+    secret_key,
+    fragment,
+  };
+  (
+    CredentialIssuer::new(metadata(), signer, EdDSAJwsVerifier::default()),
+    document,
+  )
+}
+
+fn degree_claims() -> Object {
+  Object::from_json_value(json!({ "degree": { "type": "BachelorDegree", "name": "Bachelor of Science" } })).unwrap()
+}
+
+#[test]
+fn metadata_advertises_pq_algorithms() {
+  let metadata: Value = metadata().to_json_value().unwrap();
+  let configuration: &Value = &metadata["credential_configurations_supported"][JWT_CONFIGURATION];
+  assert_eq!(configuration["format"], json!("jwt_vc_json"));
+  assert_eq!(
+    configuration["credential_signing_alg_values_supported"],
+    json!(["EdDSA", "ML-DSA-44", "id-MLDSA44-Ed25519-SHA512"])
+  );
+  assert_eq!(
+    configuration["proof_types_supported"]["jwt"]["proof_signing_alg_values_supported"][2],
+    json!("id-MLDSA44-Ed25519-SHA512")
+  );
+  assert_eq!(
+    metadata["credential_endpoint"],
+    json!("https://issuer.example.com/credential")
+  );
+  assert_eq!(
+    CredentialIssuerMetadata::from_json_value(metadata).unwrap(),
+    self::metadata()
+  );
+}
+
+#[test]
+fn offer_uri_round_trip() {
+  let (issuer, _) = issuer();
+  let offer: CredentialOffer = issuer
+    .create_offer(
+      vec![JWT_CONFIGURATION.to_owned()],
+      degree_claims(),
+      Some("493536".to_owned()),
+    )
+    .unwrap();
+  let tx_code: &TxCode = offer.pre_authorized_code().unwrap().tx_code.as_ref().unwrap();
+  assert_eq!(tx_code.input_mode.as_deref(), Some("numeric"));
+  assert_eq!(tx_code.length, Some(6));
+
+  let uri: String = offer.to_uri();
+  assert!(uri.starts_with("openid-credential-offer://?credential_offer="));
+  assert_eq!(CredentialOffer::from_uri(&uri).unwrap(), offer);
+  assert!(CredentialOffer::from_uri("https://example.com?credential_offer=%7B%7D").is_err());
+
+  assert_eq!(
+    issuer
+      .create_offer(vec!["unknown".to_owned()], Object::new(), None)
+      .unwrap_err()
+      .error,
+    Oid4vciErrorCode::UnsupportedCredentialType
+  );
+}
+
+#[test]
+fn token_endpoint() {
+  let (issuer, _) = issuer();
+  let offer: CredentialOffer = issuer
+    .create_offer(
+      vec![JWT_CONFIGURATION.to_owned()],
+      degree_claims(),
+      Some("1234".to_owned()),
+    )
+    .unwrap();
+  let code: &str = &offer.pre_authorized_code().unwrap().pre_authorized_code;
+
+  let request: TokenRequest = TokenRequest::pre_authorized(code, Some("1234".to_owned()));
+  assert_eq!(
+    TokenRequest::from_form_urlencoded(&request.to_form_urlencoded()).unwrap(),
+    request
+  );
+
+  let mut unsupported: TokenRequest = request.clone();
+  unsupported.grant_type = "authorization_code".to_owned();
+  assert_eq!(
+    issuer.token(&unsupported).unwrap_err().error,
+    Oid4vciErrorCode::UnsupportedGrantType
+  );
+
+  let response: TokenResponse = issuer.token(&request).unwrap();
+  assert_eq!(response.token_type, "Bearer");
+  assert!(response.c_nonce.is_some());
+
+  // Pre-authorized codes can only be redeemed once.
+  assert_eq!(
+    issuer.token(&request).unwrap_err().error,
+    Oid4vciErrorCode::InvalidGrant
+  );
+
+  // A wrong transaction code invalidates the offer.
+  let offer: CredentialOffer = issuer
+    .create_offer(
+      vec![JWT_CONFIGURATION.to_owned()],
+      degree_claims(),
+      Some("1234".to_owned()),
+    )
+    .unwrap();
+  let code: &str = &offer.pre_authorized_code().unwrap().pre_authorized_code;
+  let wrong: TokenRequest = TokenRequest::pre_authorized(code, Some("4321".to_owned()));
+  assert_eq!(issuer.token(&wrong).unwrap_err().error, Oid4vciErrorCode::InvalidGrant);
+  let right: TokenRequest = TokenRequest::pre_authorized(code, Some("1234".to_owned()));
+  assert_eq!(issuer.token(&right).unwrap_err().error, Oid4vciErrorCode::InvalidGrant);
+}
+
+#[test]
+fn unrepresentable_lifetimes_are_rejected() {
+  let (issuer, _) = issuer();
+  assert_eq!(
+    issuer
+      .with_lifetimes(Duration::weeks(u32::MAX), Duration::minutes(10))
+      .unwrap_err()
+      .error,
+    Oid4vciErrorCode::ServerError
+  );
+}
+
+#[tokio::test]
+async fn pre_authorized_code_flow() {
+  let (issuer, issuer_document) = issuer();
+  let (holder_document, holder_key, holder_fragment) = generate_jwk_document_with_keys();
+  let kid: String = format!("{}{}", holder_document.id(), holder_fragment);
+  let client = LocalIssuerClient::new(&issuer, vec![holder_document.clone()]);
+
+  let offer: CredentialOffer = issuer
+    .create_offer(
+      vec![
+        JWT_CONFIGURATION.to_owned(),
+        SD_JWT_CONFIGURATION.to_owned(),
+        JPT_CONFIGURATION.to_owned(),
+      ],
+      degree_claims(),
+      Some("1234".to_owned()),
+    )
+    .unwrap();
+  let offer: CredentialOffer = CredentialOffer::from_uri(&offer.to_uri()).unwrap();
+
+  let mut wallet: WalletIssuance = WalletIssuance::accept_offer(&client, offer).await.unwrap();
+  assert_eq!(wallet.offered_credentials().count(), 3);
+  assert!(matches!(
+    wallet.redeem(&client, None).await.unwrap_err(),
+    Oid4vciWalletError::InvalidOffer(_)
+  ));
+  assert!(matches!(
+    wallet
+      .request_credential(&client, JWT_CONFIGURATION, "proof")
+      .await
+      .unwrap_err(),
+    Oid4vciWalletError::MissingAccessToken
+  ));
+  wallet.redeem(&client, Some("1234".to_owned())).await.unwrap();
+
+  // A JWT credential bound to the DID of the holder.
+  let proof: String = proof_jwt(&wallet.proof_claims(), &holder_key, Some(&kid));
+  let response: CredentialResponse = wallet
+    .request_credential(&client, JWT_CONFIGURATION, proof.clone())
+    .await
+    .unwrap();
+  let credential: Jwt = Jwt::new(response.credential.as_str().unwrap().to_owned());
+  let credential: Credential = JwtCredentialValidator::with_signature_verifier(EdDSAJwsVerifier::default())
+    .validate::<_, Object>(
+      &credential,
+      &issuer_document,
+      &JwtCredentialValidationOptions::default(),
+      FailFast::FirstError,
+    )
+    .unwrap()
+    .credential;
+  let subject: &Subject = credential.credential_subject.first().unwrap();
+  assert_eq!(subject.id.as_ref().unwrap().as_str(), holder_document.id().as_str());
+  assert_eq!(subject.properties["degree"]["type"], json!("BachelorDegree"));
+
+  // The nonce is rotated after every credential request, so a proof cannot be replayed.
+  let err: Oid4vciWalletError = wallet
+    .request_credential(&client, SD_JWT_CONFIGURATION, proof)
+    .await
+    .unwrap_err();
+  let Oid4vciWalletError::Issuer(err) = err else {
+    panic!("expected an error response");
+  };
+  assert_eq!(err.error, Oid4vciErrorCode::InvalidNonce);
+  assert!(err.c_nonce.is_some());
+
+  // An SD-JWT bound to a JWK, requested with the fresh nonce.
+  let proof: String = proof_jwt(&wallet.proof_claims(), &holder_key, None);
+  let response: CredentialResponse = wallet
+    .request_credential(&client, SD_JWT_CONFIGURATION, proof)
+    .await
+    .unwrap();
+  assert_eq!(response.credential, json!("vc+sd-jwt:UniversityDegree_sd_jwt"));
+
+  // A proof addressed to another credential issuer is rejected.
+  let mut claims: ProofJwtClaims = wallet.proof_claims();
+  claims.aud = "https://other.example.com".to_owned();
+  let err: Oid4vciWalletError = wallet
+    .request_credential(&client, JPT_CONFIGURATION, proof_jwt(&claims, &holder_key, Some(&kid)))
+    .await
+    .unwrap_err();
+  assert!(matches!(
+    err,
+    Oid4vciWalletError::Issuer(Oid4vciError {
+      error: Oid4vciErrorCode::InvalidProof,
+      ..
+    })
+  ));
+
+  // A proof signed with a key other than the one referenced by `kid` is rejected.
+  let other_key: SecretKey = SecretKey::generate().unwrap();
+  let err: Oid4vciWalletError = wallet
+    .request_credential(
+      &client,
+      JPT_CONFIGURATION,
+      proof_jwt(&wallet.proof_claims(), &other_key, Some(&kid)),
+    )
+    .await
+    .unwrap_err();
+  assert!(matches!(
+    err,
+    Oid4vciWalletError::Issuer(Oid4vciError {
+      error: Oid4vciErrorCode::InvalidProof,
+      ..
+    })
+  ));
+
+  let proof: String = proof_jwt(&wallet.proof_claims(), &holder_key, Some(&kid));
+  let response: CredentialResponse = wallet
+    .request_credential(&client, JPT_CONFIGURATION, proof)
+    .await
+    .unwrap();
+  assert_eq!(response.credential, json!("jpt:UniversityDegree_jpt"));
+}
+
+#[tokio::test]
+async fn credential_endpoint_rejects_unauthorized_requests() {
+  let (issuer, _) = issuer();
+  let (holder_document, holder_key, _) = generate_jwk_document_with_keys();
+  let offer: CredentialOffer = issuer
+    .create_offer(vec![SD_JWT_CONFIGURATION.to_owned()], degree_claims(), None)
+    .unwrap();
+  let token: TokenResponse = issuer
+    .token(&TokenRequest::pre_authorized(
+      offer.pre_authorized_code().unwrap().pre_authorized_code.clone(),
+      None,
+    ))
+    .unwrap();
+  let claims: ProofJwtClaims = ProofJwtClaims::new(ISSUER_URL, token.c_nonce.clone());
+  let proof: String = proof_jwt(&claims, &holder_key, None);
+  let holder_documents: [CoreDocument; 1] = [holder_document];
+
+  let configuration: &CredentialConfiguration = issuer.metadata().configuration(SD_JWT_CONFIGURATION).unwrap();
+  let request: CredentialRequest = CredentialRequest::new(configuration, proof.clone());
+  assert_eq!(
+    issuer
+      .credential("unknown token", &request, &holder_documents)
+      .await
+      .unwrap_err()
+      .error,
+    Oid4vciErrorCode::InvalidToken
+  );
+
+  // The access token only authorizes the offered credentials.
+  let configuration: &CredentialConfiguration = issuer.metadata().configuration(JWT_CONFIGURATION).unwrap();
+  let request: CredentialRequest = CredentialRequest::new(configuration, proof.clone());
+  assert_eq!(
+    issuer
+      .credential(&token.access_token, &request, &holder_documents)
+      .await
+      .unwrap_err()
+      .error,
+    Oid4vciErrorCode::UnsupportedCredentialFormat
+  );
+
+  let mut request: CredentialRequest =
+    CredentialRequest::new(issuer.metadata().configuration(SD_JWT_CONFIGURATION).unwrap(), proof);
+  request.vct = Some("DrivingLicenseCredential".to_owned());
+  assert_eq!(
+    issuer
+      .credential(&token.access_token, &request, &holder_documents)
+      .await
+      .unwrap_err()
+      .error,
+    Oid4vciErrorCode::UnsupportedCredentialType
+  );
+
+  request.vct = Some("UniversityDegreeCredential".to_owned());
+  request.proof = None;
+  assert_eq!(
+    issuer
+      .credential(&token.access_token, &request, &holder_documents)
+      .await
+      .unwrap_err()
+      .error,
+    Oid4vciErrorCode::InvalidProof
+  );
+}
+
+#[test]
+fn proof_must_have_the_proof_typ() {
+  let (_, holder_key, _) = generate_jwk_document_with_keys();
+  let claims: ProofJwtClaims = ProofJwtClaims::new(ISSUER_URL, None);
+  let mut header: JwsHeader = JwsHeader::new();
+  header.set_alg(JwsAlgorithm::EdDSA);
+  header.set_typ("JWT");
+  header.set_jwk(encode_public_ed25519_jwk(&holder_key.public_key()));
+  let jwt: String = sign_jws(&header, claims.to_json().unwrap().as_bytes(), &holder_key);
+
+  let validator = ProofJwtValidator::with_signature_verifier(EdDSAJwsVerifier::default());
+  let options: ProofJwtValidationOptions = ProofJwtValidationOptions::new(ISSUER_URL);
+  assert_eq!(
+    validator
+      .validate::<CoreDocument>(&jwt, &[], &options)
+      .unwrap_err()
+      .error,
+    Oid4vciErrorCode::InvalidProof
+  );
+
+  let jwt: String = proof_jwt(&claims, &holder_key, None);
+  let (binding, _) = validator.validate::<CoreDocument>(&jwt, &[], &options).unwrap();
+  assert!(matches!(binding, HolderBinding::Jwk(_)));
+}
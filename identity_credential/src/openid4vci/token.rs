@@ -0,0 +1,92 @@
+// Copyright 2020-2024 IOTA Stiftung, Fondazione Links
+// SPDX-License-Identifier: Apache-2.0
+
+use serde::Deserialize;
+use serde::Serialize;
+
+use super::Oid4vciError;
+use super::Oid4vciErrorCode;
+
+/// The grant type of a token request exchanging a pre-authorized code.
+pub const PRE_AUTHORIZED_CODE_GRANT_TYPE: &str = "urn:ietf:params:oauth:grant-type:pre-authorized_code";
+
+/// A request to the token endpoint, sent as `application/x-www-form-urlencoded`.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TokenRequest {
+  /// The grant type, see [`PRE_AUTHORIZED_CODE_GRANT_TYPE`].
+  pub grant_type: String,
+  /// The pre-authorized code of the credential offer.
+  #[serde(rename = "pre-authorized_code", default, skip_serializing_if = "Option::is_none")]
+  pub pre_authorized_code: Option<String>,
+  /// The transaction code delivered to the holder out of band.
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub tx_code: Option<String>,
+}
+
+impl TokenRequest {
+  /// Creates a new [`TokenRequest`] exchanging `pre_authorized_code`, together with the transaction code `tx_code`
+  /// if the offer requires one.
+  pub fn pre_authorized(pre_authorized_code: impl Into<String>, tx_code: Option<String>) -> Self {
+    Self {
+      grant_type: PRE_AUTHORIZED_CODE_GRANT_TYPE.to_owned(),
+      pre_authorized_code: Some(pre_authorized_code.into()),
+      tx_code,
+    }
+  }
+
+  /// Encodes the request as the `application/x-www-form-urlencoded` body of the HTTP request.
+  pub fn to_form_urlencoded(&self) -> String {
+    let mut serializer = url::form_urlencoded::Serializer::new(String::new());
+    serializer.append_pair("grant_type", &self.grant_type);
+    if let Some(pre_authorized_code) = &self.pre_authorized_code {
+      serializer.append_pair("pre-authorized_code", pre_authorized_code);
+    }
+    if let Some(tx_code) = &self.tx_code {
+      serializer.append_pair("tx_code", tx_code);
+    }
+    serializer.finish()
+  }
+
+  /// Decodes a request from the `application/x-www-form-urlencoded` body of an HTTP request.
+  pub fn from_form_urlencoded(body: &str) -> Result<Self, Oid4vciError> {
+    let mut grant_type: Option<String> = None;
+    let mut pre_authorized_code: Option<String> = None;
+    let mut tx_code: Option<String> = None;
+    for (key, value) in url::form_urlencoded::parse(body.as_bytes()) {
+      let parameter: &mut Option<String> = match key.as_ref() {
+        "grant_type" => &mut grant_type,
+        "pre-authorized_code" => &mut pre_authorized_code,
+        "tx_code" => &mut tx_code,
+        _ => continue,
+      };
+      if parameter.replace(value.into_owned()).is_some() {
+        return Err(Oid4vciError::new(Oid4vciErrorCode::InvalidRequest).with_description(format!("duplicate `{key}`")));
+      }
+    }
+
+    Ok(Self {
+      grant_type: grant_type
+        .ok_or_else(|| Oid4vciError::new(Oid4vciErrorCode::InvalidRequest).with_description("missing `grant_type`"))?,
+      pre_authorized_code,
+      tx_code,
+    })
+  }
+}
+
+/// A successful response of the token endpoint.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TokenResponse {
+  /// The access token authorizing requests to the credential endpoint.
+  pub access_token: String,
+  /// The type of the access token, always `Bearer`.
+  pub token_type: String,
+  /// The lifetime of the access token in seconds.
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub expires_in: Option<u64>,
+  /// The nonce to be used in the proof of possession of the first credential request.
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub c_nonce: Option<String>,
+  /// The lifetime of `c_nonce` in seconds.
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub c_nonce_expires_in: Option<u64>,
+}
@@ -0,0 +1,266 @@
+// Copyright 2020-2024 IOTA Stiftung, Fondazione Links
+// SPDX-License-Identifier: Apache-2.0
+
+use async_trait::async_trait;
+use identity_core::common::Url;
+use identity_document::document::CoreDocument;
+use identity_verification::jws::JwsVerifier;
+
+use super::CredentialConfiguration;
+use super::CredentialIssuer;
+use super::CredentialIssuerMetadata;
+use super::CredentialOffer;
+use super::CredentialRequest;
+use super::CredentialResponse;
+use super::CredentialSigner;
+use super::Oid4vciError;
+use super::Oid4vciWalletError;
+use super::ProofJwtClaims;
+use super::TokenRequest;
+use super::TokenResponse;
+
+/// Error returned by an [`IssuerClient`] when the credential issuer cannot be reached.
+pub type IssuerClientError = Box<dyn std::error::Error + Send + Sync + 'static>;
+
+/// The outcome of a request sent by an [`IssuerClient`]: the response of the credential issuer, or the error
+/// response it returned.
+pub type IssuerResponse<T> = Result<Result<T, Oid4vciError>, IssuerClientError>;
+
+/// A client used by a wallet to reach the endpoints of a credential issuer.
+#[async_trait]
+pub trait IssuerClient: Send + Sync {
+  /// Fetches the metadata of the credential issuer identified by `credential_issuer`.
+  async fn metadata(&self, credential_issuer: &Url) -> IssuerResponse<CredentialIssuerMetadata>;
+
+  /// Sends `request` to the token endpoint of the credential issuer identified by `credential_issuer`.
+  async fn token(&self, credential_issuer: &Url, request: &TokenRequest) -> IssuerResponse<TokenResponse>;
+
+  /// Sends `request` to the credential endpoint `credential_endpoint`, authorized by `access_token`.
+  async fn credential(
+    &self,
+    credential_endpoint: &Url,
+    access_token: &str,
+    request: &CredentialRequest,
+  ) -> IssuerResponse<CredentialResponse>;
+}
+
+/// An [`IssuerClient`] calling the handlers of a [`CredentialIssuer`] in the same process instead of sending HTTP
+/// requests.
+///
+/// Useful in tests and to exercise the complete issuance flow without a transport. The DID documents of the holders
+/// whose proofs reference their key by `kid` must be provided upfront.
+#[derive(Debug)]
+pub struct LocalIssuerClient<'a, S, V, DOC> {
+  issuer: &'a CredentialIssuer<S, V>,
+  holder_documents: Vec<DOC>,
+}
+
+impl<'a, S, V, DOC> LocalIssuerClient<'a, S, V, DOC> {
+  /// Creates a new [`LocalIssuerClient`] serving the requests of a wallet with `issuer`.
+  pub fn new(issuer: &'a CredentialIssuer<S, V>, holder_documents: Vec<DOC>) -> Self {
+    Self {
+      issuer,
+      holder_documents,
+    }
+  }
+}
+
+#[async_trait]
+impl<S, V, DOC> IssuerClient for LocalIssuerClient<'_, S, V, DOC>
+where
+  S: CredentialSigner,
+  V: JwsVerifier + Send + Sync,
+  DOC: AsRef<CoreDocument> + Send + Sync,
+{
+  async fn metadata(&self, credential_issuer: &Url) -> IssuerResponse<CredentialIssuerMetadata> {
+    check_issuer(self.issuer, credential_issuer)?;
+    Ok(Ok(self.issuer.metadata().clone()))
+  }
+
+  async fn token(&self, credential_issuer: &Url, request: &TokenRequest) -> IssuerResponse<TokenResponse> {
+    check_issuer(self.issuer, credential_issuer)?;
+    // Round-trip the request through its wire encoding, as a token endpoint receives it.
+    let request: TokenRequest = match TokenRequest::from_form_urlencoded(&request.to_form_urlencoded()) {
+      Ok(request) => request,
+      Err(err) => return Ok(Err(err)),
+    };
+    Ok(self.issuer.token(&request))
+  }
+
+  async fn credential(
+    &self,
+    credential_endpoint: &Url,
+    access_token: &str,
+    request: &CredentialRequest,
+  ) -> IssuerResponse<CredentialResponse> {
+    if credential_endpoint != &self.issuer.metadata().credential_endpoint {
+      return Err(format!("no credential endpoint is served at `{credential_endpoint}`").into());
+    }
+    Ok(
+      self
+        .issuer
+        .credential(access_token, request, &self.holder_documents)
+        .await,
+    )
+  }
+}
+
+fn check_issuer<S, V>(issuer: &CredentialIssuer<S, V>, credential_issuer: &Url) -> Result<(), IssuerClientError>
+where
+  S: CredentialSigner,
+  V: JwsVerifier,
+{
+  if &issuer.metadata().credential_issuer == credential_issuer {
+    Ok(())
+  } else {
+    Err(format!("no credential issuer is served at `{credential_issuer}`").into())
+  }
+}
+
+/// The wallet side of the pre-authorized code flow of
+/// [OpenID for Verifiable Credential Issuance](https://openid.net/specs/openid-4-verifiable-credential-issuance-1_0-13.html).
+///
+/// The wallet accepts a [`CredentialOffer`] with [`WalletIssuance::accept_offer`], redeems its pre-authorized code
+/// with [`WalletIssuance::redeem`] and requests every offered credential with
+/// [`WalletIssuance::request_credential`]. Before each credential request, the holder signs the claims returned by
+/// [`WalletIssuance::proof_claims`] with the key the credential is bound to, setting the `typ` header parameter to
+/// [`PROOF_JWT_TYP`](super::PROOF_JWT_TYP) and using an algorithm returned by
+/// [`CredentialConfiguration::jwt_proof_algs`], e.g. with `JwkDocumentExt::create_jws` of `identity_storage`.
+#[derive(Clone, Debug)]
+pub struct WalletIssuance {
+  offer: CredentialOffer,
+  metadata: CredentialIssuerMetadata,
+  token: Option<TokenResponse>,
+  c_nonce: Option<String>,
+}
+
+impl WalletIssuance {
+  /// Fetches the metadata of the credential issuer of `offer` and checks that the offered credentials are supported
+  /// by the issuer.
+  pub async fn accept_offer<C>(client: &C, offer: CredentialOffer) -> Result<Self, Oid4vciWalletError>
+  where
+    C: IssuerClient + ?Sized,
+  {
+    let metadata: CredentialIssuerMetadata = unwrap_response(client.metadata(&offer.credential_issuer).await)?;
+    if metadata.credential_issuer != offer.credential_issuer {
+      return Err(Oid4vciWalletError::InvalidOffer(
+        "the metadata belongs to a different credential issuer".to_owned(),
+      ));
+    }
+    if let Some(unknown) = offer
+      .credential_configuration_ids
+      .iter()
+      .find(|id| metadata.configuration(id).is_none())
+    {
+      return Err(Oid4vciWalletError::InvalidOffer(format!(
+        "the credential issuer does not support the offered credential `{unknown}`"
+      )));
+    }
+
+    Ok(Self {
+      offer,
+      metadata,
+      token: None,
+      c_nonce: None,
+    })
+  }
+
+  /// Returns the accepted credential offer.
+  pub fn offer(&self) -> &CredentialOffer {
+    &self.offer
+  }
+
+  /// Returns the metadata of the credential issuer.
+  pub fn metadata(&self) -> &CredentialIssuerMetadata {
+    &self.metadata
+  }
+
+  /// Returns the configurations of the offered credentials, by id.
+  pub fn offered_credentials(&self) -> impl Iterator<Item = (&str, &CredentialConfiguration)> + '_ {
+    self
+      .offer
+      .credential_configuration_ids
+      .iter()
+      .filter_map(|id| Some((id.as_str(), self.metadata.configuration(id)?)))
+  }
+
+  /// Exchanges the pre-authorized code of the offer, together with the transaction code `tx_code` if the offer
+  /// requires one, for an access token.
+  pub async fn redeem<C>(&mut self, client: &C, tx_code: Option<String>) -> Result<&TokenResponse, Oid4vciWalletError>
+  where
+    C: IssuerClient + ?Sized,
+  {
+    let grant = self
+      .offer
+      .pre_authorized_code()
+      .ok_or_else(|| Oid4vciWalletError::InvalidOffer("the offer does not contain a pre-authorized code".to_owned()))?;
+    if grant.tx_code.is_some() && tx_code.is_none() {
+      return Err(Oid4vciWalletError::InvalidOffer(
+        "the offer requires a transaction code".to_owned(),
+      ));
+    }
+    let request: TokenRequest = TokenRequest::pre_authorized(grant.pre_authorized_code.clone(), tx_code);
+    let token: TokenResponse = unwrap_response(client.token(&self.offer.credential_issuer, &request).await)?;
+
+    self.c_nonce = token.c_nonce.clone();
+    Ok(self.token.insert(token))
+  }
+
+  /// Returns the claims of the proof of possession for the next credential request, containing the latest
+  /// `c_nonce` returned by the credential issuer.
+  pub fn proof_claims(&self) -> ProofJwtClaims {
+    ProofJwtClaims::new(self.metadata.credential_issuer.as_str(), self.c_nonce.clone())
+  }
+
+  /// Requests the offered credential identified by `configuration_id`, bound to the key that signed `proof_jwt`.
+  ///
+  /// If the credential issuer rejects the proof because of an invalid nonce, the fresh `c_nonce` it returns is
+  /// retained: a new proof created from [`WalletIssuance::proof_claims`] can be sent.
+  pub async fn request_credential<C>(
+    &mut self,
+    client: &C,
+    configuration_id: &str,
+    proof_jwt: impl Into<String>,
+  ) -> Result<CredentialResponse, Oid4vciWalletError>
+  where
+    C: IssuerClient + ?Sized,
+  {
+    let access_token: &str = self
+      .token
+      .as_ref()
+      .map(|token| token.access_token.as_str())
+      .ok_or(Oid4vciWalletError::MissingAccessToken)?;
+    let configuration: &CredentialConfiguration = self
+      .offer
+      .credential_configuration_ids
+      .iter()
+      .any(|id| id == configuration_id)
+      .then(|| self.metadata.configuration(configuration_id))
+      .flatten()
+      .ok_or_else(|| Oid4vciWalletError::InvalidOffer(format!("the credential `{configuration_id}` is not offered")))?;
+
+    let request: CredentialRequest = CredentialRequest::new(configuration, proof_jwt);
+    let response: IssuerResponse<CredentialResponse> = client
+      .credential(&self.metadata.credential_endpoint, access_token, &request)
+      .await;
+    match response {
+      Ok(Ok(response)) => {
+        self.c_nonce = response.c_nonce.clone();
+        Ok(response)
+      }
+      Ok(Err(err)) => {
+        if err.c_nonce.is_some() {
+          self.c_nonce = err.c_nonce.clone();
+        }
+        Err(Oid4vciWalletError::Issuer(err))
+      }
+      Err(err) => Err(Oid4vciWalletError::Client(err)),
+    }
+  }
+}
+
+fn unwrap_response<T>(response: IssuerResponse<T>) -> Result<T, Oid4vciWalletError> {
+  response
+    .map_err(Oid4vciWalletError::Client)?
+    .map_err(Oid4vciWalletError::Issuer)
+}
@@ -0,0 +1,115 @@
+// Copyright 2020-2024 IOTA Stiftung, Fondazione Links
+// SPDX-License-Identifier: Apache-2.0
+
+use std::fmt::Display;
+
+use serde::Deserialize;
+use serde::Serialize;
+
+use crate::presentation_exchange::PresentationExchangeError;
+
+/// The error codes of an authorization request or response of OpenID for Verifiable Presentations.
+///
+/// See [OAuth 2.0 authorization errors](https://www.rfc-editor.org/rfc/rfc6749#section-4.1.2.1),
+/// [request object errors](https://www.rfc-editor.org/rfc/rfc9101#section-6.3) and
+/// [OpenID4VP errors](https://openid.net/specs/openid-4-verifiable-presentations-1_0-20.html#name-error-response).
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize, strum::IntoStaticStr)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+#[non_exhaustive]
+pub enum Oid4vpErrorCode {
+  /// The request or response is missing a parameter or is otherwise malformed, or the presentation is invalid.
+  InvalidRequest,
+  /// The client identifier of the verifier is not valid or not supported.
+  InvalidClient,
+  /// The request object is malformed or its signature is invalid.
+  InvalidRequestObject,
+  /// The holder did not consent to the presentation.
+  AccessDenied,
+  /// The wallet or verifier does not support the requested or presented claim formats.
+  VpFormatsNotSupported,
+  /// The response could not be processed.
+  ServerError,
+}
+
+impl Display for Oid4vpErrorCode {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    f.write_str(<&'static str>::from(self))
+  }
+}
+
+/// The error response of OpenID for Verifiable Presentations.
+///
+/// This type is either returned by the response endpoint of a verifier as the JSON body of the HTTP error response,
+/// or reported by a wallet rejecting an authorization request.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Oid4vpError {
+  /// The error code.
+  pub error: Oid4vpErrorCode,
+  /// A human-readable description of the error.
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub error_description: Option<String>,
+  /// The `state` of the authorization request the error refers to.
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub state: Option<String>,
+}
+
+impl Oid4vpError {
+  /// Creates a new [`Oid4vpError`] with the given `error` code.
+  pub fn new(error: Oid4vpErrorCode) -> Self {
+    Self {
+      error,
+      error_description: None,
+      state: None,
+    }
+  }
+
+  /// Sets the human-readable description of the error.
+  pub fn with_description(mut self, description: impl Into<String>) -> Self {
+    self.error_description = Some(description.into());
+    self
+  }
+
+  /// Sets the `state` of the authorization request the error refers to.
+  pub fn with_state(mut self, state: impl Into<String>) -> Self {
+    self.state = Some(state.into());
+    self
+  }
+
+  /// Returns the HTTP status code to send the error response with.
+  pub fn status_code(&self) -> u16 {
+    match self.error {
+      Oid4vpErrorCode::ServerError => 500,
+      _ => 400,
+    }
+  }
+}
+
+impl Display for Oid4vpError {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    match &self.error_description {
+      Some(description) => write!(f, "{}: {description}", self.error),
+      None => write!(f, "{}", self.error),
+    }
+  }
+}
+
+impl std::error::Error for Oid4vpError {}
+
+/// Error type that represents the failures of the wallet side of the presentation flow.
+#[derive(Debug, thiserror::Error, strum::IntoStaticStr)]
+#[non_exhaustive]
+pub enum Oid4vpWalletError {
+  /// The authorization request of the verifier is malformed or not authentic.
+  #[error("invalid authorization request")]
+  InvalidRequest(#[source] Oid4vpError),
+  /// The credentials of the wallet do not satisfy the presentation definition.
+  #[error("the presentation definition cannot be satisfied")]
+  Submission(#[source] PresentationExchangeError),
+  /// The verifier rejected the presentation.
+  #[error("the verifier returned an error")]
+  Verifier(#[source] Oid4vpError),
+  /// The verifier could not be reached.
+  #[error("the verifier could not be reached")]
+  Client(#[source] Box<dyn std::error::Error + Send + Sync + 'static>),
+}
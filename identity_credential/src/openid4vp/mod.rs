@@ -0,0 +1,36 @@
+// Copyright 2020-2024 IOTA Stiftung, Fondazione Links
+// SPDX-License-Identifier: Apache-2.0
+
+//! Requesting and presenting credentials with
+//! [OpenID for Verifiable Presentations](https://openid.net/specs/openid-4-verifiable-presentations-1_0-20.html)
+//! using the `direct_post` response mode.
+//!
+//! A [`PresentationVerifier`] creates [`AuthorizationRequest`]s asking for credentials described by a
+//! [`PresentationDefinition`](crate::presentation_exchange::PresentationDefinition), which are signed as request
+//! objects and passed to the wallet in an [`AuthorizationRequestUri`]. The [`AuthorizationResponse`] posted by the
+//! wallet is validated by the verifier: the presentations must satisfy the definition, be bound to the nonce of the
+//! request and contain valid credentials. The handlers are transport-agnostic: they take and return the request and
+//! response bodies, leaving HTTP to the caller.
+//!
+//! A wallet validates request objects with a [`RequestObjectValidator`], supporting post-quantum and composite
+//! algorithms through its [`JwsVerifier`], and follows the flow with a [`WalletPresentation`], reaching the verifier
+//! through a [`VerifierClient`]. [`LocalVerifierClient`] connects a wallet to a [`PresentationVerifier`] in the same
+//! process.
+//!
+//! Presentations in the `jwt_vp_json`, `vc+sd-jwt` and `jpt` formats are supported.
+//!
+//! [`JwsVerifier`]: identity_verification::jws::JwsVerifier
+
+mod error;
+mod request;
+mod response;
+#[cfg(test)]
+mod tests;
+mod verifier;
+mod wallet;
+
+pub use self::error::*;
+pub use self::request::*;
+pub use self::response::*;
+pub use self::verifier::*;
+pub use self::wallet::*;
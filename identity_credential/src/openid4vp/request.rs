@@ -0,0 +1,330 @@
+// Copyright 2020-2024 IOTA Stiftung, Fondazione Links
+// SPDX-License-Identifier: Apache-2.0
+
+use identity_core::common::Object;
+use identity_core::common::Timestamp;
+use identity_core::common::Url;
+use identity_core::convert::FromJson;
+use identity_did::DIDUrl;
+use identity_did::DID;
+use identity_document::document::CoreDocument;
+use identity_verification::jws::DecodedJws;
+use identity_verification::jws::Decoder;
+use identity_verification::jws::JwsValidationItem;
+use identity_verification::jws::JwsVerifier;
+use identity_verification::MethodData;
+use serde::Deserialize;
+use serde::Serialize;
+
+use super::Oid4vpError;
+use super::Oid4vpErrorCode;
+use crate::presentation_exchange::PresentationDefinition;
+
+/// The URL scheme of an authorization request sent to a wallet.
+pub const AUTHORIZATION_REQUEST_SCHEME: &str = "openid4vp";
+
+/// The `typ` header parameter of a request object.
+pub const REQUEST_OBJECT_TYP: &str = "oauth-authz-req+jwt";
+
+/// The `response_type` requesting a `vp_token`.
+pub const VP_TOKEN_RESPONSE_TYPE: &str = "vp_token";
+
+/// The `client_id_scheme` of a verifier identified by a DID, whose request objects are signed with a key of its DID
+/// document.
+pub const DID_CLIENT_ID_SCHEME: &str = "did";
+
+/// The `aud` of a request object addressed to any wallet.
+pub const SELF_ISSUED_AUDIENCE: &str = "https://self-issued.me/v2";
+
+/// How the wallet returns the authorization response to the verifier.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[non_exhaustive]
+pub enum ResponseMode {
+  /// The response is sent with an HTTP `POST` request to the `response_uri` of the verifier.
+  #[serde(rename = "direct_post")]
+  DirectPost,
+}
+
+/// An [authorization request](https://openid.net/specs/openid-4-verifiable-presentations-1_0-20.html#name-authorization-request)
+/// asking a wallet to present credentials satisfying a [`PresentationDefinition`].
+///
+/// The verifier signs the JSON serialization of this type as a request object, setting the `typ` header parameter
+/// to [`REQUEST_OBJECT_TYP`] and the `kid` header parameter to the DID URL of a verification method of its DID
+/// document, e.g. with `JwkDocumentExt::create_jws` of `identity_storage`. Any JWS algorithm supported by the
+/// wallet can be used, including ML-DSA and composite algorithms.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct AuthorizationRequest {
+  /// The requested response, always [`VP_TOKEN_RESPONSE_TYPE`].
+  pub response_type: String,
+  /// The identifier of the verifier, i.e. its DID.
+  pub client_id: String,
+  /// How `client_id` is to be interpreted, see [`DID_CLIENT_ID_SCHEME`].
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub client_id_scheme: Option<String>,
+  /// How the wallet returns the response.
+  pub response_mode: ResponseMode,
+  /// The URL the wallet posts the response to.
+  pub response_uri: Url,
+  /// The nonce the presentation must be bound to.
+  pub nonce: String,
+  /// The value correlating the response with this request.
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub state: Option<String>,
+  /// The credentials requested by the verifier.
+  pub presentation_definition: PresentationDefinition,
+  /// Metadata of the verifier, e.g. the supported `vp_formats`.
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub client_metadata: Option<Object>,
+  /// The issuer of the request object, i.e. `client_id`.
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub iss: Option<String>,
+  /// The audience of the request object.
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub aud: Option<String>,
+  /// The time at which the request object was created, in seconds since the Unix epoch.
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub iat: Option<i64>,
+  /// The time after which the request object must not be accepted, in seconds since the Unix epoch.
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub exp: Option<i64>,
+}
+
+impl AuthorizationRequest {
+  /// Creates a new `direct_post` [`AuthorizationRequest`] of the verifier identified by the DID `client_id`,
+  /// created now and addressed to any wallet.
+  pub fn new(
+    client_id: impl Into<String>,
+    response_uri: Url,
+    nonce: impl Into<String>,
+    presentation_definition: PresentationDefinition,
+  ) -> Self {
+    let client_id: String = client_id.into();
+    Self {
+      response_type: VP_TOKEN_RESPONSE_TYPE.to_owned(),
+      iss: Some(client_id.clone()),
+      client_id,
+      client_id_scheme: Some(DID_CLIENT_ID_SCHEME.to_owned()),
+      response_mode: ResponseMode::DirectPost,
+      response_uri,
+      nonce: nonce.into(),
+      state: None,
+      presentation_definition,
+      client_metadata: None,
+      aud: Some(SELF_ISSUED_AUDIENCE.to_owned()),
+      iat: Some(Timestamp::now_utc().to_unix()),
+      exp: None,
+    }
+  }
+
+  /// Sets the value correlating the response with this request.
+  pub fn with_state(mut self, state: impl Into<String>) -> Self {
+    self.state = Some(state.into());
+    self
+  }
+
+  /// Sets the time after which the request object must not be accepted.
+  pub fn with_expiration(mut self, expiration: Timestamp) -> Self {
+    self.exp = Some(expiration.to_unix());
+    self
+  }
+
+  /// Sets the metadata of the verifier.
+  pub fn with_client_metadata(mut self, client_metadata: Object) -> Self {
+    self.client_metadata = Some(client_metadata);
+    self
+  }
+}
+
+/// How a request object is passed to the wallet.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum RequestObject {
+  /// The signed request object itself.
+  Value(String),
+  /// The URL the wallet fetches the signed request object from.
+  Reference(Url),
+}
+
+/// An `openid4vp://` URI passing a request object to a wallet, e.g. as a QR code.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AuthorizationRequestUri {
+  /// The identifier of the verifier.
+  pub client_id: String,
+  /// The request object, by value or by reference.
+  pub request: RequestObject,
+}
+
+impl AuthorizationRequestUri {
+  /// Creates a new [`AuthorizationRequestUri`] passing `request` on behalf of the verifier `client_id`.
+  pub fn new(client_id: impl Into<String>, request: RequestObject) -> Self {
+    Self {
+      client_id: client_id.into(),
+      request,
+    }
+  }
+
+  /// Encodes the request in an `openid4vp://` URI.
+  pub fn to_uri(&self) -> String {
+    let mut serializer = url::form_urlencoded::Serializer::new(String::new());
+    serializer.append_pair("client_id", &self.client_id);
+    match &self.request {
+      RequestObject::Value(request) => serializer.append_pair("request", request),
+      RequestObject::Reference(request_uri) => serializer.append_pair("request_uri", request_uri.as_str()),
+    };
+    format!("{AUTHORIZATION_REQUEST_SCHEME}://?{}", serializer.finish())
+  }
+
+  /// Decodes a request passed in an `openid4vp://` URI.
+  pub fn from_uri(uri: &str) -> Result<Self, Oid4vpError> {
+    let query: &str = uri
+      .strip_prefix(AUTHORIZATION_REQUEST_SCHEME)
+      .and_then(|rest| rest.strip_prefix("://"))
+      .map(|rest| rest.trim_start_matches('/').trim_start_matches('?'))
+      .ok_or_else(|| invalid_request(format!("expected a `{AUTHORIZATION_REQUEST_SCHEME}` URI")))?;
+
+    let mut client_id: Option<String> = None;
+    let mut request: Option<RequestObject> = None;
+    for (key, value) in url::form_urlencoded::parse(query.as_bytes()) {
+      let duplicate: bool = match key.as_ref() {
+        "client_id" => client_id.replace(value.into_owned()).is_some(),
+        "request" => request.replace(RequestObject::Value(value.into_owned())).is_some(),
+        "request_uri" => {
+          let request_uri: Url =
+            Url::parse(value.as_ref()).map_err(|err| invalid_request(format!("invalid `request_uri`: {err}")))?;
+          request.replace(RequestObject::Reference(request_uri)).is_some()
+        }
+        _ => false,
+      };
+      if duplicate {
+        return Err(invalid_request(
+          "the URI must contain one `client_id` and one of `request` and `request_uri`",
+        ));
+      }
+    }
+
+    Ok(Self {
+      client_id: client_id.ok_or_else(|| invalid_request("missing `client_id`"))?,
+      request: request.ok_or_else(|| invalid_request("missing `request` or `request_uri`"))?,
+    })
+  }
+}
+
+/// A validator of request objects of verifiers using the [`DID_CLIENT_ID_SCHEME`], delegating signature
+/// verification to `V`.
+///
+/// Request objects signed with a composite algorithm such as `id-MLDSA44-Ed25519-SHA512` are verified by calling
+/// `V` once for the traditional and once for the post-quantum component, so `V` must support both component
+/// algorithms.
+#[derive(Debug)]
+pub struct RequestObjectValidator<V>(V);
+
+impl<V: JwsVerifier> RequestObjectValidator<V> {
+  /// Creates a new [`RequestObjectValidator`] that delegates cryptographic signature verification to the given
+  /// `signature_verifier`.
+  pub fn with_signature_verifier(signature_verifier: V) -> Self {
+    Self(signature_verifier)
+  }
+
+  /// Validates `request_object` and returns the authorization request it contains.
+  ///
+  /// The request object must be signed with a verification method of the DID document of the verifier, which must
+  /// be among `verifier_documents`, and its `client_id` must be the DID of the verifier. The returned
+  /// [`Oid4vpError`] is either `invalid_request_object` or, if the request is authentic but cannot be served,
+  /// `invalid_request`.
+  pub fn validate<DOC>(
+    &self,
+    request_object: &str,
+    verifier_documents: &[DOC],
+  ) -> Result<AuthorizationRequest, Oid4vpError>
+  where
+    DOC: AsRef<CoreDocument>,
+  {
+    let decoded: JwsValidationItem<'_> = Decoder::new()
+      .decode_compact_serialization(request_object.as_bytes(), None)
+      .map_err(|err| invalid_request_object(format!("could not decode the request object: {err}")))?;
+    let header = decoded
+      .protected_header()
+      .ok_or_else(|| invalid_request_object("missing protected header"))?;
+    if header.typ().map_or(false, |typ| typ != REQUEST_OBJECT_TYP) {
+      return Err(invalid_request_object(format!(
+        "the `typ` of the request object must be `{REQUEST_OBJECT_TYP}`"
+      )));
+    }
+    let kid: &str = header
+      .kid()
+      .ok_or_else(|| invalid_request_object("the request object must reference the key of the verifier by `kid`"))?;
+    let method_id: DIDUrl =
+      DIDUrl::parse(kid).map_err(|err| invalid_request_object(format!("could not parse `kid` as a DID URL: {err}")))?;
+    let document: &CoreDocument = verifier_documents
+      .iter()
+      .map(AsRef::as_ref)
+      .find(|document| document.id() == method_id.did())
+      .ok_or_else(|| invalid_request_object("the DID document of the verifier is not available"))?;
+    let data: &MethodData = document
+      .resolve_method(&method_id, None)
+      .map(|method| method.data())
+      .ok_or_else(|| invalid_request_object("the `kid` does not reference a verification method of the verifier"))?;
+    let decoded: DecodedJws<'_> = match (data.public_key_jwk(), data.composite_public_key()) {
+      (Some(jwk), _) => decoded.verify(&self.0, jwk),
+      (None, Some(composite)) => decoded.verify_hybrid(
+        &self.0,
+        &self.0,
+        composite.traditional_public_key(),
+        composite.pq_public_key(),
+      ),
+      (None, None) => {
+        return Err(invalid_request_object(
+          "the verification method of the verifier does not contain a JWK",
+        ))
+      }
+    }
+    .map_err(|err| invalid_request_object(format!("invalid signature: {err}")))?;
+
+    let request: AuthorizationRequest = AuthorizationRequest::from_json_slice(&decoded.claims)
+      .map_err(|err| invalid_request_object(format!("invalid claims: {err}")))?;
+    if request.client_id != method_id.did().as_str() {
+      return Err(invalid_request_object(
+        "the request object is not signed by the verifier identified by `client_id`",
+      ));
+    }
+    if request
+      .client_id_scheme
+      .as_deref()
+      .map_or(false, |scheme| scheme != DID_CLIENT_ID_SCHEME)
+    {
+      return Err(
+        Oid4vpError::new(Oid4vpErrorCode::InvalidClient).with_description(format!(
+          "only the `{DID_CLIENT_ID_SCHEME}` client identifier scheme is supported"
+        )),
+      );
+    }
+    if request.iss.as_ref().map_or(false, |iss| *iss != request.client_id) {
+      return Err(invalid_request_object(
+        "the `iss` of the request object is not `client_id`",
+      ));
+    }
+    if request.aud.as_deref().map_or(false, |aud| aud != SELF_ISSUED_AUDIENCE) {
+      return Err(invalid_request_object(
+        "the request object is addressed to a different audience",
+      ));
+    }
+    if request.exp.map_or(false, |exp| exp < Timestamp::now_utc().to_unix()) {
+      return Err(invalid_request_object("the request object has expired"));
+    }
+    if request.response_type != VP_TOKEN_RESPONSE_TYPE {
+      return Err(invalid_request(format!(
+        "unsupported `response_type` `{}`",
+        request.response_type
+      )));
+    }
+
+    Ok(request)
+  }
+}
+
+fn invalid_request(description: impl Into<String>) -> Oid4vpError {
+  Oid4vpError::new(Oid4vpErrorCode::InvalidRequest).with_description(description)
+}
+
+fn invalid_request_object(description: impl Into<String>) -> Oid4vpError {
+  Oid4vpError::new(Oid4vpErrorCode::InvalidRequestObject).with_description(description)
+}
@@ -0,0 +1,105 @@
+// Copyright 2020-2024 IOTA Stiftung, Fondazione Links
+// SPDX-License-Identifier: Apache-2.0
+
+use identity_core::common::Url;
+use identity_core::convert::FromJson;
+use identity_core::convert::ToJson;
+use serde::Deserialize;
+use serde::Serialize;
+use serde_json::Value;
+
+use super::Oid4vpError;
+use super::Oid4vpErrorCode;
+use crate::presentation_exchange::PresentationSubmission;
+
+/// An [authorization response](https://openid.net/specs/openid-4-verifiable-presentations-1_0-20.html#name-response)
+/// carrying the presentations of a wallet, sent as `application/x-www-form-urlencoded` to the `response_uri` of the
+/// verifier.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct AuthorizationResponse {
+  /// The presentations: a compact JWT, SD-JWT or JPT as a JSON string, or multiple presentations as a JSON array.
+  pub vp_token: Value,
+  /// Describes how the presentations satisfy the presentation definition of the request.
+  pub presentation_submission: PresentationSubmission,
+  /// The `state` of the authorization request.
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub state: Option<String>,
+}
+
+impl AuthorizationResponse {
+  /// Creates a new [`AuthorizationResponse`] answering the request identified by `state`.
+  pub fn new(vp_token: Value, presentation_submission: PresentationSubmission, state: Option<String>) -> Self {
+    Self {
+      vp_token,
+      presentation_submission,
+      state,
+    }
+  }
+
+  /// Encodes the response as the `application/x-www-form-urlencoded` body of the HTTP request.
+  pub fn to_form_urlencoded(&self) -> String {
+    let vp_token: String = match &self.vp_token {
+      Value::String(vp_token) => vp_token.clone(),
+      vp_token => vp_token.to_string(),
+    };
+    let presentation_submission: String = self
+      .presentation_submission
+      .to_json()
+      .expect("a presentation submission always serializes to JSON");
+
+    let mut serializer = url::form_urlencoded::Serializer::new(String::new());
+    serializer.append_pair("vp_token", &vp_token);
+    serializer.append_pair("presentation_submission", &presentation_submission);
+    if let Some(state) = &self.state {
+      serializer.append_pair("state", state);
+    }
+    serializer.finish()
+  }
+
+  /// Decodes a response from the `application/x-www-form-urlencoded` body of an HTTP request.
+  pub fn from_form_urlencoded(body: &str) -> Result<Self, Oid4vpError> {
+    let mut vp_token: Option<String> = None;
+    let mut presentation_submission: Option<String> = None;
+    let mut state: Option<String> = None;
+    for (key, value) in url::form_urlencoded::parse(body.as_bytes()) {
+      let parameter: &mut Option<String> = match key.as_ref() {
+        "vp_token" => &mut vp_token,
+        "presentation_submission" => &mut presentation_submission,
+        "state" => &mut state,
+        _ => continue,
+      };
+      if parameter.replace(value.into_owned()).is_some() {
+        return Err(invalid_request(format!("duplicate `{key}`")));
+      }
+    }
+
+    let vp_token: String = vp_token.ok_or_else(|| invalid_request("missing `vp_token`"))?;
+    // Multiple presentations are sent as a JSON array, a single compact presentation as it is.
+    let vp_token: Value = match serde_json::from_str::<Value>(&vp_token) {
+      Ok(value @ (Value::Array(_) | Value::Object(_))) => value,
+      _ => Value::String(vp_token),
+    };
+    let presentation_submission: PresentationSubmission = PresentationSubmission::from_json(
+      &presentation_submission.ok_or_else(|| invalid_request("missing `presentation_submission`"))?,
+    )
+    .map_err(|err| invalid_request(format!("invalid `presentation_submission`: {err}")))?;
+
+    Ok(Self {
+      vp_token,
+      presentation_submission,
+      state,
+    })
+  }
+}
+
+/// The response of the verifier to a successful `direct_post` request.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DirectPostResponse {
+  /// The URL the wallet redirects the holder to, if any.
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub redirect_uri: Option<Url>,
+}
+
+fn invalid_request(description: impl Into<String>) -> Oid4vpError {
+  Oid4vpError::new(Oid4vpErrorCode::InvalidRequest).with_description(description)
+}
@@ -0,0 +1,459 @@
+// Copyright 2020-2024 IOTA Stiftung, Fondazione Links
+// SPDX-License-Identifier: Apache-2.0
+
+use crypto::signatures::ed25519::SecretKey;
+use identity_core::common::Duration;
+use identity_core::common::Object;
+use identity_core::common::Url;
+use identity_core::convert::FromJson;
+use identity_core::convert::ToJson;
+use identity_did::DID;
+use identity_document::document::CoreDocument;
+use identity_eddsa_verifier::EdDSAJwsVerifier;
+use identity_verification::jws::CharSet;
+use identity_verification::jws::CompactJwsEncoder;
+use identity_verification::jws::CompactJwsEncodingOptions;
+use identity_verification::jws::JwsAlgorithm;
+use identity_verification::jws::JwsHeader;
+use serde_json::json;
+use serde_json::Value;
+
+use super::*;
+use crate::credential::Credential;
+use crate::credential::CredentialBuilder;
+use crate::credential::Jwt;
+use crate::credential::Subject;
+use crate::presentation::JwtPresentationOptions;
+use crate::presentation::Presentation;
+use crate::presentation_exchange::Constraints;
+use crate::presentation_exchange::Field;
+use crate::presentation_exchange::FormattedCredential;
+use crate::presentation_exchange::InputDescriptor;
+use crate::presentation_exchange::PresentationDefinition;
+use crate::presentation_exchange::Submission;
+use crate::presentation_exchange::SubmissionLayout;
+use crate::validator::test_utils::generate_jwk_document_with_keys;
+
+const RESPONSE_URI: &str = "https://verifier.example.com/response";
+
+fn sign_jws(header: &JwsHeader, payload: &[u8], secret_key: &SecretKey) -> String {
+  let options: CompactJwsEncodingOptions = CompactJwsEncodingOptions::NonDetached {
+    charset_requirements: CharSet::Default,
+  };
+  let encoder: CompactJwsEncoder<'_> = CompactJwsEncoder::new_with_options(payload, header, options).unwrap();
+  let signature: [u8; 64] = secret_key.sign(encoder.signing_input()).to_bytes();
+  encoder.into_jws(&signature)
+}
+
+/// A DID document together with the key and fragment of its verification method.
+struct Party {
+  document: CoreDocument,
+  secret_key: SecretKey,
+  fragment: String,
+}
+
+impl Party {
+  fn new() -> Self {
+    let (document, secret_key, fragment) = generate_jwk_document_with_keys();
+    Self {
+      document,
+      secret_key,
+      fragment,
+    }
+  }
+
+  fn kid(&self) -> String {
+    format!("{}{}", self.document.id(), self.fragment)
+  }
+
+  fn sign(&self, typ: Option<&str>, nonce: Option<&str>, payload: &[u8]) -> String {
+    let mut header: JwsHeader = JwsHeader::new();
+    header.set_alg(JwsAlgorithm::EdDSA);
+    header.set_kid(self.kid());
+    if let Some(typ) = typ {
+      header.set_typ(typ);
+    }
+    if let Some(nonce) = nonce {
+      header.set_nonce(nonce);
+    }
+    sign_jws(&header, payload, &self.secret_key)
+  }
+
+  fn sign_request(&self, request: &AuthorizationRequest) -> String {
+    self.sign(Some(REQUEST_OBJECT_TYP), None, request.to_json().unwrap().as_bytes())
+  }
+}
+
+fn degree_credential(issuer: &Party, holder: &Party) -> Jwt {
+  let credential: Credential = CredentialBuilder::default()
+    .issuer(Url::parse(issuer.document.id().as_str()).unwrap())
+    .type_("UniversityDegreeCredential")
+    .subject(
+      Subject::from_json_value(json!({
+        "id": holder.document.id().as_str(),
+        "degree": { "type": "BachelorDegree", "name": "Bachelor of Science" }
+      }))
+      .unwrap(),
+    )
+    .build()
+    .unwrap();
+  Jwt::new(issuer.sign(None, None, credential.serialize_jwt(None).unwrap().as_bytes()))
+}
+
+fn definition() -> PresentationDefinition {
+  let field: Field = Field::new(vec!["$.vc.type".to_owned()])
+    .with_filter(json!({ "type": "array", "contains": { "const": "UniversityDegreeCredential" } }));
+  PresentationDefinition::new(
+    "degree-definition",
+    vec![InputDescriptor::new("degree", Constraints::new(vec![field]))],
+  )
+}
+
+fn verifier(party: &Party) -> PresentationVerifier<EdDSAJwsVerifier> {
+  PresentationVerifier::new(
+    party.document.id().as_str(),
+    Url::parse(RESPONSE_URI).unwrap(),
+    EdDSAJwsVerifier::default(),
+  )
+}
+
+/// Creates the JWT VP of `holder` containing `credentials`, with the given audience and nonce.
+fn jwt_presentation(holder: &Party, credentials: &[Value], options: &JwtPresentationOptions, nonce: &str) -> String {
+  let mut builder = Presentation::builder(Url::parse(holder.document.id().as_str()).unwrap(), Object::new());
+  for credential in credentials {
+    builder = builder.credential(Jwt::new(credential.as_str().unwrap().to_owned()));
+  }
+  let presentation: Presentation<Jwt> = builder.build().unwrap();
+  holder.sign(
+    None,
+    Some(nonce),
+    presentation.serialize_jwt(options).unwrap().as_bytes(),
+  )
+}
+
+/// Selects the credentials of `wallet` for `presentation` and wraps them in a JWT VP bound to the request.
+fn jwt_vp_response(
+  presentation: &WalletPresentation,
+  holder: &Party,
+  wallet: &[FormattedCredential],
+) -> (Value, Submission) {
+  let submission: Submission = presentation
+    .create_submission(wallet, SubmissionLayout::JwtPresentation)
+    .unwrap();
+  let credentials: Vec<Value> = submission
+    .credentials
+    .iter()
+    .map(|index| wallet[*index].credential().clone())
+    .collect();
+  let vp: String = jwt_presentation(
+    holder,
+    &credentials,
+    &presentation.jwt_presentation_options().unwrap(),
+    presentation.nonce(),
+  );
+  (Value::String(vp), submission)
+}
+
+#[test]
+fn request_uri_round_trip() {
+  let verifier_party: Party = Party::new();
+  let verifier: PresentationVerifier<EdDSAJwsVerifier> = verifier(&verifier_party);
+  let request: AuthorizationRequest = verifier.create_request(definition()).unwrap();
+  assert_eq!(request.response_type, VP_TOKEN_RESPONSE_TYPE);
+  assert_eq!(request.response_mode, ResponseMode::DirectPost);
+  assert!(request.state.is_some());
+  let request_object: String = verifier_party.sign_request(&request);
+
+  let uri: AuthorizationRequestUri =
+    AuthorizationRequestUri::new(verifier.client_id(), RequestObject::Value(request_object.clone()));
+  let encoded: String = uri.to_uri();
+  assert!(encoded.starts_with("openid4vp://?client_id=did%3Aexample%3A"));
+  assert_eq!(AuthorizationRequestUri::from_uri(&encoded).unwrap(), uri);
+  assert!(AuthorizationRequestUri::from_uri("openid4vp://?request=abc").is_err());
+  assert!(AuthorizationRequestUri::from_uri("https://example.com?client_id=abc&request=abc").is_err());
+
+  let validator = RequestObjectValidator::with_signature_verifier(EdDSAJwsVerifier::default());
+  let verifier_documents: [CoreDocument; 1] = [verifier_party.document.clone()];
+  assert_eq!(
+    validator.validate(&request_object, &verifier_documents).unwrap(),
+    request
+  );
+
+  // A request object claiming the identity of the verifier must be signed with a key of the verifier.
+  let impostor: Party = Party::new();
+  let forged: String = impostor.sign_request(&request);
+  let documents: [CoreDocument; 2] = [verifier_party.document.clone(), impostor.document.clone()];
+  assert_eq!(
+    validator.validate(&forged, &documents).unwrap_err().error,
+    Oid4vpErrorCode::InvalidRequestObject
+  );
+
+  // A request object whose signature does not match the referenced key is rejected.
+  let mut header: JwsHeader = JwsHeader::new();
+  header.set_alg(JwsAlgorithm::EdDSA);
+  header.set_typ(REQUEST_OBJECT_TYP);
+  header.set_kid(verifier_party.kid());
+  let forged: String = sign_jws(&header, request.to_json().unwrap().as_bytes(), &impostor.secret_key);
+  assert_eq!(
+    validator.validate(&forged, &verifier_documents).unwrap_err().error,
+    Oid4vpErrorCode::InvalidRequestObject
+  );
+}
+
+#[tokio::test]
+async fn jwt_vp_direct_post_flow() {
+  let verifier_party: Party = Party::new();
+  let issuer: Party = Party::new();
+  let holder: Party = Party::new();
+  let verifier: PresentationVerifier<EdDSAJwsVerifier> = verifier(&verifier_party);
+
+  // The request object is passed by reference.
+  let request: AuthorizationRequest = verifier.create_request(definition()).unwrap();
+  let request_uri: Url = Url::parse("https://verifier.example.com/request/1").unwrap();
+  let client = LocalVerifierClient::new(&verifier, vec![issuer.document.clone(), holder.document.clone()])
+    .with_request_object(&request_uri, verifier_party.sign_request(&request));
+  let uri: String = AuthorizationRequestUri::new(verifier.client_id(), RequestObject::Reference(request_uri)).to_uri();
+
+  let validator = RequestObjectValidator::with_signature_verifier(EdDSAJwsVerifier::default());
+  let presentation: WalletPresentation = WalletPresentation::from_uri(
+    &client,
+    &uri,
+    &validator,
+    std::slice::from_ref(&verifier_party.document),
+  )
+  .await
+  .unwrap();
+  assert_eq!(presentation.request(), &request);
+
+  let wallet: Vec<FormattedCredential> =
+    vec![FormattedCredential::from_jwt(&degree_credential(&issuer, &holder)).unwrap()];
+  let (vp_token, submission) = jwt_vp_response(&presentation, &holder, &wallet);
+  presentation
+    .submit(&client, vp_token.clone(), submission.presentation_submission.clone())
+    .await
+    .unwrap();
+
+  // Every request can only be answered once.
+  let err: Oid4vpWalletError = presentation
+    .submit(&client, vp_token, submission.presentation_submission)
+    .await
+    .unwrap_err();
+  assert!(matches!(
+    err,
+    Oid4vpWalletError::Verifier(Oid4vpError {
+      error: Oid4vpErrorCode::InvalidRequest,
+      ..
+    })
+  ));
+
+  // The verifier reports the submitted credentials and the holder.
+  let request: AuthorizationRequest = verifier.create_request(definition()).unwrap();
+  let presentation: WalletPresentation = WalletPresentation::from_request_object(
+    &verifier_party.sign_request(&request),
+    &validator,
+    std::slice::from_ref(&verifier_party.document),
+  )
+  .unwrap();
+  let (vp_token, submission) = jwt_vp_response(&presentation, &holder, &wallet);
+  let response: AuthorizationResponse =
+    AuthorizationResponse::new(vp_token, submission.presentation_submission, request.state.clone());
+  let documents: [CoreDocument; 2] = [issuer.document.clone(), holder.document.clone()];
+  let verified: VerifiedPresentation = verifier
+    .direct_post(&response.to_form_urlencoded(), &documents)
+    .unwrap();
+  assert_eq!(Some(verified.state), request.state);
+  assert_eq!(verified.holders, vec![holder.document.id().clone()]);
+  assert_eq!(verified.descriptors[0].input_descriptor_id, "degree");
+  assert_eq!(verified.descriptors[0].credential, wallet[0]);
+}
+
+#[test]
+fn presentation_must_be_bound_to_the_request() {
+  let verifier_party: Party = Party::new();
+  let issuer: Party = Party::new();
+  let holder: Party = Party::new();
+  let verifier: PresentationVerifier<EdDSAJwsVerifier> = verifier(&verifier_party);
+  let documents: [CoreDocument; 2] = [issuer.document.clone(), holder.document.clone()];
+  let credential: Jwt = degree_credential(&issuer, &holder);
+  let wallet: Vec<FormattedCredential> = vec![FormattedCredential::from_jwt(&credential).unwrap()];
+  let credential: Value = Value::String(credential.as_str().to_owned());
+
+  let respond = |options: &JwtPresentationOptions, nonce: Option<&str>| -> Result<VerifiedPresentation, Oid4vpError> {
+    let request: AuthorizationRequest = verifier.create_request(definition()).unwrap();
+    let submission: Submission = request
+      .presentation_definition
+      .create_submission("submission", &wallet, SubmissionLayout::JwtPresentation)
+      .unwrap();
+    let vp: String = jwt_presentation(
+      &holder,
+      std::slice::from_ref(&credential),
+      options,
+      nonce.unwrap_or(&request.nonce),
+    );
+    let response: AuthorizationResponse = AuthorizationResponse::new(
+      Value::String(vp),
+      submission.presentation_submission,
+      request.state.clone(),
+    );
+    verifier.direct_post(&response.to_form_urlencoded(), &documents)
+  };
+
+  let audience: Url = Url::parse(verifier.client_id()).unwrap();
+  let bound: JwtPresentationOptions = JwtPresentationOptions::default().audience(audience);
+  assert!(respond(&bound, None).is_ok());
+
+  // The nonce of the request must be signed.
+  let err: Oid4vpError = respond(&bound, Some("other nonce")).unwrap_err();
+  assert_eq!(err.error, Oid4vpErrorCode::InvalidRequest);
+  assert!(err.state.is_some());
+
+  // The presentation must be addressed to the verifier.
+  let other: JwtPresentationOptions =
+    JwtPresentationOptions::default().audience(Url::parse("did:example:other").unwrap());
+  assert!(respond(&other, None).is_err());
+  assert!(respond(&JwtPresentationOptions::default(), None).is_err());
+
+  // Responses to unknown requests are rejected.
+  let body: String = AuthorizationResponse::new(
+    json!("vp"),
+    definition()
+      .create_submission("submission", &wallet, SubmissionLayout::VpToken)
+      .unwrap()
+      .presentation_submission,
+    Some("unknown".to_owned()),
+  )
+  .to_form_urlencoded();
+  assert_eq!(
+    verifier.direct_post(&body, &documents).unwrap_err().error,
+    Oid4vpErrorCode::InvalidRequest
+  );
+  assert!(verifier.direct_post("state=abc", &documents).is_err());
+}
+
+#[test]
+fn unrepresentable_request_lifetime_is_rejected() {
+  let verifier: PresentationVerifier<EdDSAJwsVerifier> = verifier(&Party::new());
+  assert_eq!(
+    verifier
+      .with_request_lifetime(Duration::weeks(u32::MAX))
+      .unwrap_err()
+      .error,
+    Oid4vpErrorCode::ServerError
+  );
+}
+
+#[test]
+fn unbound_credentials_are_not_accepted() {
+  let verifier_party: Party = Party::new();
+  let issuer: Party = Party::new();
+  let holder: Party = Party::new();
+  let verifier: PresentationVerifier<EdDSAJwsVerifier> = verifier(&verifier_party);
+  let credential: Jwt = degree_credential(&issuer, &holder);
+  let wallet: Vec<FormattedCredential> = vec![FormattedCredential::from_jwt(&credential).unwrap()];
+
+  // A credential presented on its own is not bound to the nonce of the request.
+  let request: AuthorizationRequest = verifier.create_request(definition()).unwrap();
+  let submission: Submission = request
+    .presentation_definition
+    .create_submission("submission", &wallet, SubmissionLayout::VpToken)
+    .unwrap();
+  let response: AuthorizationResponse = AuthorizationResponse::new(
+    Value::String(credential.as_str().to_owned()),
+    submission.presentation_submission,
+    request.state,
+  );
+  let decoded: AuthorizationResponse =
+    AuthorizationResponse::from_form_urlencoded(&response.to_form_urlencoded()).unwrap();
+  assert_eq!(decoded, response);
+  assert_eq!(
+    verifier
+      .verify_response(&decoded, &[issuer.document, holder.document])
+      .unwrap_err()
+      .error,
+    Oid4vpErrorCode::VpFormatsNotSupported
+  );
+}
+
+#[cfg(feature = "sd-jwt")]
+#[tokio::test]
+async fn sd_jwt_direct_post_flow() {
+  use sd_jwt_payload::KeyBindingJwtClaims;
+  use sd_jwt_payload::SdJwt;
+  use sd_jwt_payload::SdObjectEncoder;
+
+  let verifier_party: Party = Party::new();
+  let issuer: Party = Party::new();
+  let holder: Party = Party::new();
+  let verifier: PresentationVerifier<EdDSAJwsVerifier> = verifier(&verifier_party);
+  let client = LocalVerifierClient::new(&verifier, vec![issuer.document.clone(), holder.document.clone()]);
+
+  let credential: Credential = CredentialBuilder::default()
+    .issuer(Url::parse(issuer.document.id().as_str()).unwrap())
+    .type_("UniversityDegreeCredential")
+    .subject(
+      Subject::from_json_value(json!({
+        "id": holder.document.id().as_str(),
+        "degree": { "type": "BachelorDegree", "name": "Bachelor of Science" }
+      }))
+      .unwrap(),
+    )
+    .build()
+    .unwrap();
+  let mut encoder: SdObjectEncoder = SdObjectEncoder::new(&credential.serialize_jwt(None).unwrap()).unwrap();
+  let disclosure: String = encoder
+    .conceal("/vc/credentialSubject/degree", None)
+    .unwrap()
+    .to_string();
+  encoder.add_sd_alg_property();
+  let jwt: String = issuer.sign(None, None, encoder.try_to_string().unwrap().as_bytes());
+  let mut sd_jwt: SdJwt = SdJwt::new(jwt, vec![disclosure], None);
+  let wallet: Vec<FormattedCredential> = vec![FormattedCredential::from_sd_jwt(&sd_jwt).unwrap()];
+
+  let request: AuthorizationRequest = verifier.create_request(definition()).unwrap();
+  let validator = RequestObjectValidator::with_signature_verifier(EdDSAJwsVerifier::default());
+  let presentation: WalletPresentation = WalletPresentation::from_request_object(
+    &verifier_party.sign_request(&request),
+    &validator,
+    std::slice::from_ref(&verifier_party.document),
+  )
+  .unwrap();
+  let submission: Submission = presentation
+    .create_submission(&wallet, SubmissionLayout::VpToken)
+    .unwrap();
+  assert_eq!(submission.presentation_submission.descriptor_map[0].path, "$");
+
+  // An SD-JWT without a KB-JWT is not bound to the request.
+  let err: Oid4vpWalletError = presentation
+    .submit(
+      &client,
+      Value::String(sd_jwt.presentation()),
+      submission.presentation_submission.clone(),
+    )
+    .await
+    .unwrap_err();
+  assert!(matches!(err, Oid4vpWalletError::Verifier(_)));
+
+  let request: AuthorizationRequest = verifier.create_request(definition()).unwrap();
+  let presentation: WalletPresentation = WalletPresentation::from_request_object(
+    &verifier_party.sign_request(&request),
+    &validator,
+    std::slice::from_ref(&verifier_party.document),
+  )
+  .unwrap();
+  let claims: KeyBindingJwtClaims = presentation.key_binding_claims(&sd_jwt);
+  assert_eq!(claims.aud, verifier.client_id());
+  let kb_jwt: String = holder.sign(
+    Some(KeyBindingJwtClaims::KB_JWT_HEADER_TYP),
+    None,
+    claims.to_json().unwrap().as_bytes(),
+  );
+  sd_jwt.key_binding_jwt = Some(kb_jwt);
+  presentation
+    .submit(
+      &client,
+      Value::String(sd_jwt.presentation()),
+      submission.presentation_submission,
+    )
+    .await
+    .unwrap();
+}
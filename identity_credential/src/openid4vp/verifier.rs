@@ -0,0 +1,459 @@
+// Copyright 2020-2024 IOTA Stiftung, Fondazione Links
+// SPDX-License-Identifier: Apache-2.0
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use identity_core::common::Duration;
+use identity_core::common::Object;
+use identity_core::common::Timestamp;
+use identity_core::common::Url;
+use identity_did::CoreDID;
+use identity_did::DIDUrl;
+#[cfg(feature = "sd-jwt")]
+use identity_did::DID;
+use identity_document::document::CoreDocument;
+use identity_document::verifiable::JwsVerificationOptions;
+use identity_verification::jws::Decoder;
+use identity_verification::jws::JwsVerifier;
+use identity_verification::jwu::encode_b64;
+
+use super::AuthorizationRequest;
+use super::AuthorizationResponse;
+use super::Oid4vpError;
+use super::Oid4vpErrorCode;
+use crate::credential::Jwt;
+use crate::presentation_exchange::ClaimFormat;
+use crate::presentation_exchange::EvaluatedDescriptor;
+use crate::presentation_exchange::JsonPath;
+use crate::presentation_exchange::PresentationDefinition;
+use crate::presentation_exchange::PresentationSubmission;
+use crate::validator::DecodedJwtPresentation;
+use crate::validator::FailFast;
+use crate::validator::JwtCredentialValidationOptions;
+use crate::validator::JwtCredentialValidator;
+use crate::validator::JwtCredentialValidatorHybrid;
+use crate::validator::JwtCredentialValidatorUtils;
+use crate::validator::JwtPresentationValidationOptions;
+use crate::validator::JwtPresentationValidator;
+use crate::validator::JwtPresentationValidatorHybrid;
+use crate::validator::JwtPresentationValidatorUtils;
+use crate::validator::SubjectHolderRelationship;
+
+/// The outcome of a successfully verified [`AuthorizationResponse`].
+#[derive(Clone, Debug, PartialEq)]
+#[non_exhaustive]
+pub struct VerifiedPresentation {
+  /// The `state` of the answered authorization request.
+  pub state: String,
+  /// The submission describing the presentations.
+  pub presentation_submission: PresentationSubmission,
+  /// The credentials submitted for each input descriptor.
+  pub descriptors: Vec<EvaluatedDescriptor>,
+  /// The DIDs of the holders that bound the presentations to their keys.
+  pub holders: Vec<CoreDID>,
+}
+
+struct PendingRequest {
+  nonce: String,
+  presentation_definition: PresentationDefinition,
+  expires_at: Timestamp,
+}
+
+/// The verifier side of [OpenID for Verifiable Presentations](https://openid.net/specs/openid-4-verifiable-presentations-1_0-20.html)
+/// using the `direct_post` response mode.
+///
+/// The verifier creates an [`AuthorizationRequest`] for every presentation it asks for with
+/// [`PresentationVerifier::create_request`], which the caller signs as a request object and passes to the wallet.
+/// The body the wallet posts to the response endpoint is handed to [`PresentationVerifier::direct_post`], which
+/// answers with a [`DirectPostResponse`](super::DirectPostResponse) on success and with the returned
+/// [`Oid4vpError`] otherwise.
+///
+/// Presentations are accepted as JWT VPs, SD-JWTs with a Key Binding JWT (feature `sd-jwt`) and JPTs (feature
+/// `jpt-bbs-plus`), and must be bound to the nonce of the request and, where the format allows, to the verifier as
+/// audience. Signatures with composite algorithms such as `id-MLDSA44-Ed25519-SHA512` are verified by calling the
+/// [`JwsVerifier`] once for the traditional and once for the post-quantum component.
+pub struct PresentationVerifier<V> {
+  client_id: String,
+  response_uri: Url,
+  signature_verifier: V,
+  request_lifetime: Duration,
+  requests: Mutex<HashMap<String, PendingRequest>>,
+}
+
+impl<V> PresentationVerifier<V> {
+  /// Creates a new [`PresentationVerifier`] identified by the DID `client_id`, whose response endpoint is
+  /// `response_uri`. Requests can be answered for five minutes.
+  pub fn new(client_id: impl Into<String>, response_uri: Url, signature_verifier: V) -> Self {
+    Self {
+      client_id: client_id.into(),
+      response_uri,
+      signature_verifier,
+      request_lifetime: Duration::minutes(5),
+      requests: Mutex::new(HashMap::new()),
+    }
+  }
+
+  /// Sets how long a request can be answered.
+  ///
+  /// # Errors
+  ///
+  /// Fails if `request_lifetime` is too long to be represented as an expiration time.
+  pub fn with_request_lifetime(mut self, request_lifetime: Duration) -> Result<Self, Oid4vpError> {
+    expiry(request_lifetime)?;
+    self.request_lifetime = request_lifetime;
+    Ok(self)
+  }
+
+  /// Returns the identifier of the verifier.
+  pub fn client_id(&self) -> &str {
+    &self.client_id
+  }
+
+  /// Returns the URL of the response endpoint of the verifier.
+  pub fn response_uri(&self) -> &Url {
+    &self.response_uri
+  }
+
+  /// Creates an [`AuthorizationRequest`] asking for credentials satisfying `presentation_definition`, with a fresh
+  /// nonce and state.
+  pub fn create_request(
+    &self,
+    presentation_definition: PresentationDefinition,
+  ) -> Result<AuthorizationRequest, Oid4vpError> {
+    let nonce: String = random_token()?;
+    let state: String = random_token()?;
+    let expires_at: Timestamp = expiry(self.request_lifetime)?;
+
+    let request: AuthorizationRequest = AuthorizationRequest::new(
+      self.client_id.clone(),
+      self.response_uri.clone(),
+      nonce.clone(),
+      presentation_definition.clone(),
+    )
+    .with_state(state.clone())
+    .with_expiration(expires_at);
+
+    let mut requests = self.requests.lock().expect("the verifier lock is never poisoned");
+    let now: Timestamp = Timestamp::now_utc();
+    requests.retain(|_, request| request.expires_at >= now);
+    requests.insert(
+      state,
+      PendingRequest {
+        nonce,
+        presentation_definition,
+        expires_at,
+      },
+    );
+    Ok(request)
+  }
+}
+
+impl<V: JwsVerifier> PresentationVerifier<V> {
+  /// Handles the `application/x-www-form-urlencoded` `body` posted to the response endpoint.
+  ///
+  /// See [`PresentationVerifier::verify_response`].
+  pub fn direct_post<DOC>(&self, body: &str, documents: &[DOC]) -> Result<VerifiedPresentation, Oid4vpError>
+  where
+    DOC: AsRef<CoreDocument>,
+  {
+    self.verify_response(&AuthorizationResponse::from_form_urlencoded(body)?, documents)
+  }
+
+  /// Verifies that `response` answers a pending request of this verifier.
+  ///
+  /// Every request can be answered once. The presentations must satisfy the presentation definition of the request
+  /// and be bound to its nonce, and the credentials they contain must be valid and issued to their holder. The DID
+  /// documents of the holders and issuers must be among `documents`.
+  pub fn verify_response<DOC>(
+    &self,
+    response: &AuthorizationResponse,
+    documents: &[DOC],
+  ) -> Result<VerifiedPresentation, Oid4vpError>
+  where
+    DOC: AsRef<CoreDocument>,
+  {
+    let state: &str = response
+      .state
+      .as_deref()
+      .ok_or_else(|| invalid_request("missing `state`"))?;
+    let request: PendingRequest = self
+      .requests
+      .lock()
+      .expect("the verifier lock is never poisoned")
+      .remove(state)
+      .ok_or_else(|| invalid_request("the `state` does not belong to a pending request").with_state(state))?;
+
+    self
+      .verify_pending(&request, response, documents)
+      .map(|(descriptors, holders)| VerifiedPresentation {
+        state: state.to_owned(),
+        presentation_submission: response.presentation_submission.clone(),
+        descriptors,
+        holders,
+      })
+      .map_err(|err| err.with_state(state))
+  }
+
+  fn verify_pending<DOC>(
+    &self,
+    request: &PendingRequest,
+    response: &AuthorizationResponse,
+    documents: &[DOC],
+  ) -> Result<(Vec<EvaluatedDescriptor>, Vec<CoreDID>), Oid4vpError>
+  where
+    DOC: AsRef<CoreDocument>,
+  {
+    if Timestamp::now_utc() > request.expires_at {
+      return Err(invalid_request("the authorization request has expired"));
+    }
+    let descriptors: Vec<EvaluatedDescriptor> = request
+      .presentation_definition
+      .evaluate(&response.presentation_submission, &response.vp_token)
+      .map_err(|err| invalid_request(err.to_string()))?;
+
+    // Every presentation of the `vp_token` is verified once, however many descriptors it satisfies.
+    let mut presentations: Vec<(&str, &ClaimFormat)> = Vec::new();
+    for mapping in &response.presentation_submission.descriptor_map {
+      if !presentations.contains(&(mapping.path.as_str(), &mapping.format)) {
+        presentations.push((mapping.path.as_str(), &mapping.format));
+      }
+    }
+
+    let mut holders: Vec<CoreDID> = Vec::new();
+    for (path, format) in presentations {
+      let selected: Vec<&serde_json::Value> = JsonPath::parse(path)
+        .map_err(|err| invalid_request(err.to_string()))?
+        .query(&response.vp_token);
+      let presentation: &str = match selected.as_slice() {
+        [presentation] => presentation
+          .as_str()
+          .ok_or_else(|| invalid_request(format!("the `{format}` presentation at `{path}` is not a string")))?,
+        _ => return Err(invalid_request(format!("no presentation found at `{path}`"))),
+      };
+
+      let holder: Option<CoreDID> = match format {
+        ClaimFormat::JwtVp | ClaimFormat::JwtVpJson => {
+          Some(self.verify_jwt_presentation(presentation, &request.nonce, documents)?)
+        }
+        #[cfg(feature = "sd-jwt")]
+        ClaimFormat::SdJwt => Some(self.verify_sd_jwt(presentation, &request.nonce, documents)?),
+        #[cfg(feature = "jpt-bbs-plus")]
+        ClaimFormat::Jpt => {
+          self.verify_jpt(presentation, &request.nonce, documents)?;
+          None
+        }
+        format => {
+          return Err(
+            Oid4vpError::new(Oid4vpErrorCode::VpFormatsNotSupported)
+              .with_description(format!("`{format}` presentations are not supported")),
+          )
+        }
+      };
+      if let Some(holder) = holder.filter(|holder| !holders.contains(holder)) {
+        holders.push(holder);
+      }
+    }
+
+    Ok((descriptors, holders))
+  }
+
+  fn verify_jwt_presentation<DOC>(
+    &self,
+    presentation: &str,
+    nonce: &str,
+    documents: &[DOC],
+  ) -> Result<CoreDID, Oid4vpError>
+  where
+    DOC: AsRef<CoreDocument>,
+  {
+    let presentation: Jwt = Jwt::new(presentation.to_owned());
+    let holder_did: CoreDID = JwtPresentationValidatorUtils::extract_holder(&presentation)
+      .map_err(|err| invalid_request(format!("could not determine the holder: {err}")))?;
+    let holder: &CoreDocument = document(documents, &holder_did)?;
+
+    let options: JwtPresentationValidationOptions =
+      JwtPresentationValidationOptions::new().presentation_verifier_options(JwsVerificationOptions::new().nonce(nonce));
+    let decoded: DecodedJwtPresentation<Jwt> = if is_composite(holder, presentation.as_str()) {
+      JwtPresentationValidatorHybrid::with_signature_verifiers(&self.signature_verifier, &self.signature_verifier)
+        .validate(&presentation, holder, &options)
+    } else {
+      JwtPresentationValidator::with_signature_verifier(&self.signature_verifier).validate(
+        &presentation,
+        holder,
+        &options,
+      )
+    }
+    .map_err(|err| invalid_request(format!("invalid presentation: {err}")))?;
+    if decoded.aud.as_ref().map(|aud| aud.as_str()) != Some(self.client_id.as_str()) {
+      return Err(invalid_request("the `aud` of the presentation is not the verifier"));
+    }
+
+    let options: JwtCredentialValidationOptions = JwtCredentialValidationOptions::new().subject_holder_relationship(
+      decoded.presentation.holder.clone(),
+      SubjectHolderRelationship::AlwaysSubject,
+    );
+    for credential in &decoded.presentation.verifiable_credential {
+      let issuer_did: CoreDID = JwtCredentialValidatorUtils::extract_issuer_from_jwt(credential)
+        .map_err(|err| invalid_request(format!("could not determine the issuer of a credential: {err}")))?;
+      let issuer: &CoreDocument = document(documents, &issuer_did)?;
+      if is_composite(issuer, credential.as_str()) {
+        JwtCredentialValidatorHybrid::with_signature_verifiers(&self.signature_verifier, &self.signature_verifier)
+          .validate::<_, Object>(credential, issuer, &options, FailFast::FirstError)
+      } else {
+        JwtCredentialValidator::with_signature_verifier(&self.signature_verifier).validate::<_, Object>(
+          credential,
+          issuer,
+          &options,
+          FailFast::FirstError,
+        )
+      }
+      .map_err(|err| invalid_request(format!("invalid credential: {err}")))?;
+    }
+
+    Ok(holder_did)
+  }
+
+  #[cfg(feature = "sd-jwt")]
+  fn verify_sd_jwt<DOC>(&self, presentation: &str, nonce: &str, documents: &[DOC]) -> Result<CoreDID, Oid4vpError>
+  where
+    DOC: AsRef<CoreDocument>,
+  {
+    use sd_jwt_payload::SdJwt;
+    use sd_jwt_payload::SdObjectDecoder;
+
+    use crate::validator::KeyBindingJWTValidationOptions;
+    use crate::validator::SdJwtCredentialValidator;
+
+    let sd_jwt: SdJwt =
+      SdJwt::parse(presentation).map_err(|err| invalid_request(format!("could not decode the SD-JWT: {err}")))?;
+    let key_binding_jwt: &str = sd_jwt
+      .key_binding_jwt
+      .as_deref()
+      .ok_or_else(|| invalid_request("the SD-JWT is not bound to the holder by a KB-JWT"))?;
+    let holder_did: CoreDID = kid_did(key_binding_jwt)
+      .ok_or_else(|| invalid_request("the KB-JWT must reference the key of the holder by `kid`"))?;
+    let holder: &CoreDocument = document(documents, &holder_did)?;
+    let issuer_did: CoreDID = JwtCredentialValidatorUtils::extract_issuer_from_jwt(&Jwt::new(sd_jwt.jwt.clone()))
+      .map_err(|err| invalid_request(format!("could not determine the issuer of the credential: {err}")))?;
+    let issuer: &CoreDocument = document(documents, &issuer_did)?;
+
+    let validator =
+      SdJwtCredentialValidator::with_signature_verifier(&self.signature_verifier, SdObjectDecoder::new_with_sha256());
+    let options: JwtCredentialValidationOptions = JwtCredentialValidationOptions::new()
+      .subject_holder_relationship(Url::from(holder_did.to_url()), SubjectHolderRelationship::AlwaysSubject);
+    validator
+      .validate_credential::<_, Object>(&sd_jwt, issuer, &options, FailFast::FirstError)
+      .map_err(|err| invalid_request(format!("invalid credential: {err}")))?;
+    let options: KeyBindingJWTValidationOptions = KeyBindingJWTValidationOptions::new()
+      .nonce(nonce)
+      .aud(self.client_id.as_str());
+    validator
+      .validate_key_binding_jwt(&sd_jwt, holder, &options)
+      .map_err(|err| invalid_request(format!("invalid KB-JWT: {err}")))?;
+
+    Ok(holder_did)
+  }
+
+  #[cfg(feature = "jpt-bbs-plus")]
+  fn verify_jpt<DOC>(&self, presentation: &str, nonce: &str, documents: &[DOC]) -> Result<(), Oid4vpError>
+  where
+    DOC: AsRef<CoreDocument>,
+  {
+    use crate::credential::Jpt;
+    use crate::validator::JptPresentationValidationOptions;
+    use crate::validator::JptPresentationValidator;
+    use crate::validator::JptPresentationValidatorUtils;
+
+    let presentation: Jpt = Jpt::new(presentation.to_owned());
+    let issuer_did: CoreDID = JptPresentationValidatorUtils::extract_issuer_from_presented_jpt(&presentation)
+      .map_err(|err| invalid_request(format!("could not determine the issuer of the credential: {err}")))?;
+    let issuer: &CoreDocument = document(documents, &issuer_did)?;
+    let options: JptPresentationValidationOptions = JptPresentationValidationOptions::new().nonce(nonce);
+
+    #[cfg(feature = "jpt-pq")]
+    if is_pqc_jpt(&presentation) {
+      return JptPresentationValidator::validate_pqc::<_, Object, _, _>(
+        &presentation,
+        issuer,
+        &options,
+        FailFast::FirstError,
+        &self.signature_verifier,
+        &self.signature_verifier,
+      )
+      .map(|_| ())
+      .map_err(|err| invalid_request(format!("invalid presentation: {err}")));
+    }
+
+    JptPresentationValidator::validate::<_, Object>(&presentation, issuer, &options, FailFast::FirstError)
+      .map(|_| ())
+      .map_err(|err| invalid_request(format!("invalid presentation: {err}")))
+  }
+}
+
+impl<V> std::fmt::Debug for PresentationVerifier<V> {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    f.debug_struct("PresentationVerifier")
+      .field("client_id", &self.client_id)
+      .field("response_uri", &self.response_uri)
+      .field("request_lifetime", &self.request_lifetime)
+      .finish_non_exhaustive()
+  }
+}
+
+/// Returns the DID document identified by `did` among `documents`.
+fn document<'a, DOC>(documents: &'a [DOC], did: &CoreDID) -> Result<&'a CoreDocument, Oid4vpError>
+where
+  DOC: AsRef<CoreDocument>,
+{
+  documents
+    .iter()
+    .map(AsRef::as_ref)
+    .find(|document| document.id() == did)
+    .ok_or_else(|| invalid_request(format!("the DID document of `{did}` is not available")))
+}
+
+/// Returns the DID of the verification method referenced by the `kid` of `jws`.
+#[cfg(feature = "sd-jwt")]
+fn kid_did(jws: &str) -> Option<CoreDID> {
+  let decoded = Decoder::new().decode_compact_serialization(jws.as_bytes(), None).ok()?;
+  DIDUrl::parse(decoded.kid()?).ok().map(|kid| kid.did().clone())
+}
+
+/// Returns whether the `kid` of `jws` references a verification method of `document` with a composite key.
+fn is_composite(document: &CoreDocument, jws: &str) -> bool {
+  Decoder::new()
+    .decode_compact_serialization(jws.as_bytes(), None)
+    .ok()
+    .and_then(|decoded| DIDUrl::parse(decoded.kid()?).ok())
+    .and_then(|kid| document.resolve_method(&kid, None))
+    .map_or(false, |method| method.data().composite_public_key().is_some())
+}
+
+#[cfg(feature = "jpt-pq")]
+fn is_pqc_jpt(presentation: &crate::credential::Jpt) -> bool {
+  use jsonprooftoken::encoding::SerializationType;
+  use jsonprooftoken::jwp::presented::JwpPresentedDecoder;
+
+  JwpPresentedDecoder::decode(presentation.as_str(), SerializationType::COMPACT).map_or(false, |decoded| {
+    crate::credential::jpt_pq::is_pqc(decoded.get_issuer_header())
+  })
+}
+
+fn random_token() -> Result<String, Oid4vpError> {
+  let mut token: [u8; 32] = [0; 32];
+  crypto::utils::rand::fill(&mut token)
+    .map_err(|err| Oid4vpError::new(Oid4vpErrorCode::ServerError).with_description(err.to_string()))?;
+  Ok(encode_b64(token))
+}
+
+/// Returns the expiration time of a request created now.
+fn expiry(lifetime: Duration) -> Result<Timestamp, Oid4vpError> {
+  Timestamp::now_utc().checked_add(lifetime).ok_or_else(|| {
+    Oid4vpError::new(Oid4vpErrorCode::ServerError).with_description("the lifetime exceeds the supported time range")
+  })
+}
+
+fn invalid_request(description: impl Into<String>) -> Oid4vpError {
+  Oid4vpError::new(Oid4vpErrorCode::InvalidRequest).with_description(description)
+}
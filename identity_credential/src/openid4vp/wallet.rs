@@ -0,0 +1,258 @@
+// Copyright 2020-2024 IOTA Stiftung, Fondazione Links
+// SPDX-License-Identifier: Apache-2.0
+
+use std::collections::HashMap;
+
+use async_trait::async_trait;
+use identity_core::common::Url;
+use identity_document::document::CoreDocument;
+use identity_verification::jws::JwsVerifier;
+use identity_verification::jwu::encode_b64;
+use serde_json::Value;
+
+use super::AuthorizationRequest;
+use super::AuthorizationRequestUri;
+use super::AuthorizationResponse;
+use super::DirectPostResponse;
+use super::Oid4vpError;
+use super::Oid4vpErrorCode;
+use super::Oid4vpWalletError;
+use super::PresentationVerifier;
+use super::RequestObject;
+use super::RequestObjectValidator;
+use crate::presentation::JwtPresentationOptions;
+use crate::presentation_exchange::FormattedCredential;
+use crate::presentation_exchange::PresentationSubmission;
+use crate::presentation_exchange::Submission;
+use crate::presentation_exchange::SubmissionLayout;
+
+/// Error returned by a [`VerifierClient`] when the verifier cannot be reached.
+pub type VerifierClientError = Box<dyn std::error::Error + Send + Sync + 'static>;
+
+/// The outcome of a request sent by a [`VerifierClient`]: the response of the verifier, or the error response it
+/// returned.
+pub type VerifierResponse<T> = Result<Result<T, Oid4vpError>, VerifierClientError>;
+
+/// A client used by a wallet to reach the endpoints of a verifier.
+#[async_trait]
+pub trait VerifierClient: Send + Sync {
+  /// Fetches the request object passed by reference as `request_uri`.
+  async fn request_object(&self, request_uri: &Url) -> Result<String, VerifierClientError>;
+
+  /// Posts `response` to the response endpoint `response_uri`.
+  async fn direct_post(
+    &self,
+    response_uri: &Url,
+    response: &AuthorizationResponse,
+  ) -> VerifierResponse<DirectPostResponse>;
+}
+
+/// A [`VerifierClient`] calling the handlers of a [`PresentationVerifier`] in the same process instead of sending
+/// HTTP requests.
+///
+/// Useful in tests and to exercise the complete presentation flow without a transport. The DID documents of the
+/// holders and issuers must be provided upfront, as well as the request objects passed by reference.
+#[derive(Debug)]
+pub struct LocalVerifierClient<'a, V, DOC> {
+  verifier: &'a PresentationVerifier<V>,
+  documents: Vec<DOC>,
+  request_objects: HashMap<String, String>,
+}
+
+impl<'a, V, DOC> LocalVerifierClient<'a, V, DOC> {
+  /// Creates a new [`LocalVerifierClient`] serving the requests of a wallet with `verifier`.
+  pub fn new(verifier: &'a PresentationVerifier<V>, documents: Vec<DOC>) -> Self {
+    Self {
+      verifier,
+      documents,
+      request_objects: HashMap::new(),
+    }
+  }
+
+  /// Serves `request_object` at `request_uri`.
+  pub fn with_request_object(mut self, request_uri: &Url, request_object: impl Into<String>) -> Self {
+    self
+      .request_objects
+      .insert(request_uri.to_string(), request_object.into());
+    self
+  }
+}
+
+#[async_trait]
+impl<V, DOC> VerifierClient for LocalVerifierClient<'_, V, DOC>
+where
+  V: JwsVerifier + Send + Sync,
+  DOC: AsRef<CoreDocument> + Send + Sync,
+{
+  async fn request_object(&self, request_uri: &Url) -> Result<String, VerifierClientError> {
+    self
+      .request_objects
+      .get(request_uri.as_str())
+      .cloned()
+      .ok_or_else(|| format!("no request object is served at `{request_uri}`").into())
+  }
+
+  async fn direct_post(
+    &self,
+    response_uri: &Url,
+    response: &AuthorizationResponse,
+  ) -> VerifierResponse<DirectPostResponse> {
+    if response_uri != self.verifier.response_uri() {
+      return Err(format!("no response endpoint is served at `{response_uri}`").into());
+    }
+    // Post the response in its wire encoding, as a response endpoint receives it.
+    Ok(
+      self
+        .verifier
+        .direct_post(&response.to_form_urlencoded(), &self.documents)
+        .map(|_| DirectPostResponse::default()),
+    )
+  }
+}
+
+/// The wallet side of [OpenID for Verifiable Presentations](https://openid.net/specs/openid-4-verifiable-presentations-1_0-20.html)
+/// using the `direct_post` response mode.
+///
+/// The wallet validates the request object of the verifier with [`WalletPresentation::from_uri`] or
+/// [`WalletPresentation::from_request_object`], selects the credentials satisfying its presentation definition with
+/// [`WalletPresentation::create_submission`] and sends the presentations with [`WalletPresentation::submit`].
+///
+/// The presentations must be bound to the nonce and audience of the request:
+/// - a JWT VP is created with [`WalletPresentation::jwt_presentation_options`] and signed setting the `nonce` header
+///   parameter to [`WalletPresentation::nonce`], e.g. with `JwkDocumentExt::create_presentation_jwt` of
+///   `identity_storage`,
+/// - an SD-JWT is presented with a Key Binding JWT signing the claims returned by
+///   `WalletPresentation::key_binding_claims` (feature `sd-jwt`),
+/// - a JPT is presented setting the nonce of the `JwpPresentationOptions` to [`WalletPresentation::nonce`].
+#[derive(Clone, Debug)]
+pub struct WalletPresentation {
+  request: AuthorizationRequest,
+}
+
+impl WalletPresentation {
+  /// Validates the request object passed in the `openid4vp://` URI `uri`, fetching it with `client` if it is passed
+  /// by reference.
+  ///
+  /// See [`RequestObjectValidator::validate`].
+  pub async fn from_uri<C, V, DOC>(
+    client: &C,
+    uri: &str,
+    validator: &RequestObjectValidator<V>,
+    verifier_documents: &[DOC],
+  ) -> Result<Self, Oid4vpWalletError>
+  where
+    C: VerifierClient + ?Sized,
+    V: JwsVerifier,
+    DOC: AsRef<CoreDocument>,
+  {
+    let uri: AuthorizationRequestUri =
+      AuthorizationRequestUri::from_uri(uri).map_err(Oid4vpWalletError::InvalidRequest)?;
+    let request_object: String = match uri.request {
+      RequestObject::Value(request_object) => request_object,
+      RequestObject::Reference(request_uri) => client
+        .request_object(&request_uri)
+        .await
+        .map_err(Oid4vpWalletError::Client)?,
+    };
+
+    let presentation: Self = Self::from_request_object(&request_object, validator, verifier_documents)?;
+    if presentation.request.client_id != uri.client_id {
+      return Err(Oid4vpWalletError::InvalidRequest(
+        Oid4vpError::new(Oid4vpErrorCode::InvalidClient)
+          .with_description("the `client_id` of the URI does not match the request object"),
+      ));
+    }
+    Ok(presentation)
+  }
+
+  /// Validates `request_object` with `validator`.
+  ///
+  /// See [`RequestObjectValidator::validate`].
+  pub fn from_request_object<V, DOC>(
+    request_object: &str,
+    validator: &RequestObjectValidator<V>,
+    verifier_documents: &[DOC],
+  ) -> Result<Self, Oid4vpWalletError>
+  where
+    V: JwsVerifier,
+    DOC: AsRef<CoreDocument>,
+  {
+    validator
+      .validate(request_object, verifier_documents)
+      .map(|request| Self { request })
+      .map_err(Oid4vpWalletError::InvalidRequest)
+  }
+
+  /// Returns the validated authorization request.
+  pub fn request(&self) -> &AuthorizationRequest {
+    &self.request
+  }
+
+  /// Returns the nonce the presentations must be bound to.
+  pub fn nonce(&self) -> &str {
+    &self.request.nonce
+  }
+
+  /// Selects credentials of `wallet` satisfying the presentation definition of the request.
+  ///
+  /// See [`PresentationDefinition::create_submission`](crate::presentation_exchange::PresentationDefinition::create_submission).
+  pub fn create_submission(
+    &self,
+    wallet: &[FormattedCredential],
+    layout: SubmissionLayout,
+  ) -> Result<Submission, Oid4vpWalletError> {
+    let mut id: [u8; 16] = [0; 16];
+    crypto::utils::rand::fill(&mut id).map_err(|err| Oid4vpWalletError::Client(err.to_string().into()))?;
+    self
+      .request
+      .presentation_definition
+      .create_submission(encode_b64(id), wallet, layout)
+      .map_err(Oid4vpWalletError::Submission)
+  }
+
+  /// Returns the options of a JWT VP addressed to the verifier.
+  pub fn jwt_presentation_options(&self) -> Result<JwtPresentationOptions, Oid4vpWalletError> {
+    let audience: Url = Url::parse(&self.request.client_id).map_err(|err| {
+      Oid4vpWalletError::InvalidRequest(
+        Oid4vpError::new(Oid4vpErrorCode::InvalidClient).with_description(format!("invalid `client_id`: {err}")),
+      )
+    })?;
+    Ok(JwtPresentationOptions::default().audience(audience))
+  }
+
+  /// Returns the claims of the Key Binding JWT of `sd_jwt`, bound to the nonce of the request and addressed to the
+  /// verifier.
+  ///
+  /// The holder signs the claims with the key the SD-JWT is bound to, setting the `typ` header parameter to
+  /// [`KeyBindingJwtClaims::KB_JWT_HEADER_TYP`](sd_jwt_payload::KeyBindingJwtClaims::KB_JWT_HEADER_TYP).
+  #[cfg(feature = "sd-jwt")]
+  pub fn key_binding_claims(&self, sd_jwt: &sd_jwt_payload::SdJwt) -> sd_jwt_payload::KeyBindingJwtClaims {
+    sd_jwt_payload::KeyBindingJwtClaims::new(
+      &sd_jwt_payload::Sha256Hasher::new(),
+      sd_jwt.jwt.clone(),
+      sd_jwt.disclosures.clone(),
+      self.request.nonce.clone(),
+      self.request.client_id.clone(),
+      identity_core::common::Timestamp::now_utc().to_unix(),
+    )
+  }
+
+  /// Posts `vp_token`, described by `presentation_submission`, to the response endpoint of the verifier.
+  pub async fn submit<C>(
+    &self,
+    client: &C,
+    vp_token: Value,
+    presentation_submission: PresentationSubmission,
+  ) -> Result<DirectPostResponse, Oid4vpWalletError>
+  where
+    C: VerifierClient + ?Sized,
+  {
+    let response: AuthorizationResponse =
+      AuthorizationResponse::new(vp_token, presentation_submission, self.request.state.clone());
+    client
+      .direct_post(&self.request.response_uri, &response)
+      .await
+      .map_err(Oid4vpWalletError::Client)?
+      .map_err(Oid4vpWalletError::Verifier)
+  }
+}
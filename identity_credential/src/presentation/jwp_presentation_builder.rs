@@ -1,10 +1,24 @@
 // Copyright 2020-2024 IOTA Stiftung, Fondazione Links
 // SPDX-License-Identifier: Apache-2.0
 
+#[cfg(feature = "jpt-predicates-experimental")]
+use crate::credential::build_presented_jwp;
+#[cfg(feature = "jpt-predicates-experimental")]
+use crate::credential::has_extended_proof;
+#[cfg(feature = "jpt-predicates-experimental")]
+use crate::credential::JptHolderBoundPresentation;
+#[cfg(feature = "jpt-predicates-experimental")]
+use crate::credential::JptPredicate;
 use crate::error::Error;
 use crate::error::Result;
+#[cfg(feature = "jpt-predicates-experimental")]
+use jsonprooftoken::jpa::algs::ProofAlgorithm;
+#[cfg(feature = "jpt-predicates-experimental")]
+use jsonprooftoken::jwk::key::Jwk;
 use jsonprooftoken::jwp::header::PresentationProtectedHeader;
 use jsonprooftoken::jwp::issued::JwpIssued;
+#[cfg(any(feature = "jpt-predicates-experimental", feature = "jpt-pq"))]
+use jsonprooftoken::jwp::presented::JwpPresented;
 use jsonprooftoken::jwp::presented::JwpPresentedBuilder;
 
 /// Used to construct a JwpPresentedBuilder and handle the selective disclosure of attributes.
@@ -121,4 +135,55 @@ impl SelectiveDisclosurePresentation {
   pub fn builder(&self) -> &JwpPresentedBuilder {
     &self.jwp_builder
   }
+
+  /// Build the Presented JWP, proving `predicates` over claims of the credential without disclosing them.
+  ///
+  /// The claims of the predicates are concealed, and must have been listed by the issuer in
+  /// [`JwpCredentialOptions::predicate_claims`](crate::credential::JwpCredentialOptions::predicate_claims).
+  #[cfg(feature = "jpt-predicates-experimental")]
+  pub fn build(&mut self, public_key: &Jwk, predicates: &[JptPredicate]) -> Result<JwpPresented, Error> {
+    if self.jwp_builder.get_issuer_protected_header().alg() == ProofAlgorithm::MAC_H256 {
+      return Err(Error::JwpBuildingError(
+        "JWPs secured with salted hashes are presented with a signature of the holder".into(),
+      ));
+    }
+    if predicates.is_empty() && !has_extended_proof(self.jwp_builder.issuer_proof()) {
+      self
+        .jwp_builder
+        .build(public_key)
+        .map_err(|err| Error::JwpBuildingError(err.into()))
+    } else {
+      build_presented_jwp(&mut self.jwp_builder, public_key, predicates)
+    }
+  }
+
+  /// Prepare the Presented JWP of a credential bound to a holder secret, proving `predicates` as in
+  /// [`build`](SelectiveDisclosurePresentation::build) and disclosing the pseudonym of the holder for `verifier_id` if
+  /// set.
+  ///
+  /// The returned presentation is built once the key storage of the holder answers its
+  /// [`request`](JptHolderBoundPresentation::request).
+  #[cfg(feature = "jpt-predicates-experimental")]
+  pub fn holder_bound(
+    &mut self,
+    public_key: &Jwk,
+    predicates: &[JptPredicate],
+    verifier_id: Option<&str>,
+  ) -> Result<JptHolderBoundPresentation<'_>, Error> {
+    JptHolderBoundPresentation::new(&mut self.jwp_builder, public_key, predicates, verifier_id)
+  }
+
+  /// The bytes the holder signs to present a post-quantum JWP secured with salted hashes, covering the presentation
+  /// protected header.
+  #[cfg(feature = "jpt-pq")]
+  pub fn pqc_signing_input(&self) -> Result<Vec<u8>, Error> {
+    crate::credential::jpt_pq::presentation_signing_input(&self.jwp_builder)
+  }
+
+  /// Build the Presented JWP of a post-quantum JWP secured with salted hashes, given the signature of the holder over
+  /// [`pqc_signing_input`](SelectiveDisclosurePresentation::pqc_signing_input).
+  #[cfg(feature = "jpt-pq")]
+  pub fn build_pqc(&self, holder_signature: &[u8]) -> Result<JwpPresented, Error> {
+    crate::credential::jpt_pq::build_presented_jwp(&self.jwp_builder, holder_signature)
+  }
 }
@@ -5,6 +5,9 @@ use identity_core::common::Url;
 use serde::Deserialize;
 use serde::Serialize;
 
+#[cfg(feature = "jpt-predicates-experimental")]
+use crate::credential::JptPredicate;
+
 /// Options to be set in the JWT claims of a verifiable presentation.
 #[derive(Clone, Debug, Serialize, Deserialize, Default)]
 pub struct JwpPresentationOptions {
@@ -16,6 +19,17 @@ pub struct JwpPresentationOptions {
   /// The nonce to be placed in the Presentation Protected Header.
   #[serde(skip_serializing_if = "Option::is_none")]
   pub nonce: Option<String>,
+
+  /// The predicates to prove over claims of the credential, whose values are concealed.
+  #[cfg(feature = "jpt-predicates-experimental")]
+  #[serde(default, skip_serializing_if = "Vec::is_empty")]
+  pub predicates: Vec<JptPredicate>,
+
+  /// The identifier of the verifier, for which the pseudonym of the holder is disclosed when presenting a credential
+  /// bound to a holder secret.
+  #[cfg(feature = "jpt-predicates-experimental")]
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub verifier_id: Option<String>,
 }
 
 impl JwpPresentationOptions {
@@ -30,4 +44,18 @@ impl JwpPresentationOptions {
     self.nonce = Some(value.into());
     self
   }
+
+  /// Adds a predicate to prove over a claim of the credential.
+  #[cfg(feature = "jpt-predicates-experimental")]
+  pub fn predicate(mut self, predicate: JptPredicate) -> Self {
+    self.predicates.push(predicate);
+    self
+  }
+
+  /// Discloses the pseudonym of the holder for the verifier identified by `verifier_id`.
+  #[cfg(feature = "jpt-predicates-experimental")]
+  pub fn verifier_id(mut self, verifier_id: impl Into<String>) -> Self {
+    self.verifier_id = Some(verifier_id.into());
+    self
+  }
 }
@@ -10,20 +10,32 @@ use identity_core::common::Context;
 use identity_core::common::Object;
 use identity_core::common::OneOrMany;
 use identity_core::common::Url;
+use identity_core::common::Value;
+#[cfg(feature = "validator")]
+use identity_core::convert::FromJson;
+use identity_core::convert::ToJson;
 use serde::de::DeserializeOwned;
 
+use crate::credential::merge_custom_claims;
+#[cfg(feature = "validator")]
+use crate::credential::split_registered_claims;
 use crate::credential::IssuanceDateClaims;
 use crate::credential::Jwt;
 use crate::credential::Policy;
 use crate::credential::Proof;
 use crate::credential::RefreshService;
-use crate::presentation::Presentation;
 #[cfg(feature = "validator")]
+use crate::credential::VcDataModelVersion;
+use crate::presentation::Presentation;
 use crate::Error;
 use crate::Result;
 
 use super::JwtPresentationOptions;
 
+/// The media type of a VC Data Model v2.0 [`Presentation`] secured as a JWT, as defined by
+/// [VC-JOSE-COSE](https://www.w3.org/TR/vc-jose-cose/#securing-vps-with-jose).
+pub const VP_JWT_MEDIA_TYPE: &str = "vp+jwt";
+
 #[derive(Serialize, Deserialize)]
 pub(crate) struct PresentationJwtClaims<'presentation, CRED, T = Object>
 where
@@ -99,6 +111,44 @@ where
   }
 }
 
+/// Serializes a VC Data Model v2.0 [`Presentation`] as a JWT claims set in accordance with
+/// [VC-JOSE-COSE](https://www.w3.org/TR/vc-jose-cose/#securing-vps-with-jose).
+///
+/// The presentation itself is the claims set, the registered claims and custom claims derived from `options`
+/// are added next to its properties.
+pub(super) fn serialize_jwt_v2<CRED, T>(
+  presentation: &Presentation<CRED, T>,
+  options: &JwtPresentationOptions,
+) -> Result<String>
+where
+  T: Serialize,
+  CRED: Serialize,
+{
+  let mut claims: Object = match presentation.to_json_value() {
+    Ok(Value::Object(claims)) => claims.into_iter().collect(),
+    Ok(_) => return Err(Error::JwtClaimsSetSerializationError("expected a JSON object".into())),
+    Err(err) => return Err(Error::JwtClaimsSetSerializationError(err.into())),
+  };
+
+  let mut registered_claims: Object = Object::new();
+  if let Some(expiration_date) = options.expiration_date {
+    registered_claims.insert("exp".to_owned(), expiration_date.to_unix().into());
+  }
+  if let Some(issuance_date) = options.issuance_date {
+    registered_claims.insert("nbf".to_owned(), issuance_date.to_unix().into());
+  }
+  if let Some(audience) = options.audience.as_ref() {
+    registered_claims.insert("aud".to_owned(), audience.to_string().into());
+  }
+
+  merge_custom_claims(&mut claims, Some(registered_claims))?;
+  merge_custom_claims(&mut claims, options.custom_claims.clone())?;
+
+  claims
+    .to_json()
+    .map_err(|err| Error::JwtClaimsSetSerializationError(err.into()))
+}
+
 #[derive(Serialize, Deserialize)]
 pub(crate) struct InnerPresentation<'presentation, CRED = Jwt, T = Object>
 where
@@ -141,6 +191,85 @@ where
   CRED: ToOwned<Owned = CRED> + Serialize + DeserializeOwned + Clone,
   T: ToOwned<Owned = T> + Serialize + DeserializeOwned,
 {
+  /// Parses the claims set of a JWT secured presentation.
+  ///
+  /// The encoding is selected by the presence of a top-level `@context`: VC Data Model v2.0 presentations are
+  /// the claims set themselves, whereas v1.1 presentations are mapped to the `vp` claim.
+  pub(crate) fn from_jwt_claims(claims: &[u8]) -> Result<Self> {
+    let mut claims: Object =
+      Object::from_json_slice(claims).map_err(|err| Error::JwtClaimsSetDeserializationError(err.into()))?;
+
+    if !claims.contains_key("@context") {
+      return Self::from_json_value(Value::Object(claims.into_iter().collect()))
+        .map_err(|err| Error::JwtClaimsSetDeserializationError(err.into()));
+    }
+
+    let registered_claims: Object = split_registered_claims(&mut claims).unwrap_or_default();
+    let presentation: Presentation<CRED, T> =
+      Presentation::from_json_value(Value::Object(claims.into_iter().collect()))
+        .map_err(|err| Error::JwtClaimsSetDeserializationError(err.into()))?;
+    if presentation.data_model_version() != Some(VcDataModelVersion::V2_0) {
+      return Err(Error::MissingBaseContext);
+    }
+
+    let timestamp = |name: &str| -> Result<Option<i64>> {
+      registered_claims
+        .get(name)
+        .map(|value| value.as_i64().ok_or(Error::TimestampConversionError))
+        .transpose()
+    };
+    let url = |name: &str| -> Result<Option<Url>> {
+      registered_claims
+        .get(name)
+        .map(|value| {
+          value
+            .as_str()
+            .and_then(|value| Url::parse(value).ok())
+            .ok_or(Error::InconsistentPresentationJwtClaims("invalid registered claim"))
+        })
+        .transpose()
+    };
+
+    let exp: Option<i64> = timestamp("exp")?;
+    let iat: Option<i64> = timestamp("iat")?;
+    let nbf: Option<i64> = timestamp("nbf")?;
+    let aud: Option<Url> = url("aud")?;
+    let iss: Option<Url> = url("iss")?;
+    let jti: Option<Url> = url("jti")?;
+
+    let Presentation {
+      context,
+      id,
+      types,
+      verifiable_credential,
+      holder,
+      refresh_service,
+      terms_of_use,
+      properties,
+      proof,
+    } = presentation;
+
+    Ok(Self {
+      exp,
+      iss: Cow::Owned(iss.unwrap_or_else(|| holder.clone())),
+      issuance_date: (iat.is_some() || nbf.is_some()).then_some(IssuanceDateClaims { iat, nbf }),
+      jti: jti.or_else(|| id.clone()).map(Cow::Owned),
+      aud,
+      vp: InnerPresentation {
+        context: Cow::Owned(context),
+        id,
+        types: Cow::Owned(types),
+        verifiable_credential: Cow::Owned(verifiable_credential),
+        holder: Some(holder),
+        refresh_service: Cow::Owned(refresh_service),
+        terms_of_use: Cow::Owned(terms_of_use),
+        properties: Cow::Owned(properties),
+        proof: proof.map(Cow::Owned),
+      },
+      custom: None,
+    })
+  }
+
   pub(crate) fn try_into_presentation(self) -> Result<Presentation<CRED, T>> {
     self.check_consistency()?;
     let Self {
@@ -17,6 +17,7 @@ mod presentation_builder;
 #[cfg(feature = "jpt-bbs-plus")]
 pub use self::jwp_presentation_builder::SelectiveDisclosurePresentation;
 pub use self::jwt_presentation_options::JwtPresentationOptions;
+pub use self::jwt_serialization::VP_JWT_MEDIA_TYPE;
 pub use self::presentation::Presentation;
 pub use self::presentation_builder::PresentationBuilder;
 #[cfg(feature = "jpt-bbs-plus")]
@@ -19,6 +19,7 @@ use crate::credential::Credential;
 use crate::credential::Policy;
 use crate::credential::Proof;
 use crate::credential::RefreshService;
+use crate::credential::VcDataModelVersion;
 use crate::error::Error;
 use crate::error::Result;
 
@@ -76,6 +77,12 @@ impl<CRED, T> Presentation<CRED, T> {
     Credential::<Object>::base_context()
   }
 
+  /// Returns the version of the VC Data Model this `Presentation` conforms to, as determined by its
+  /// first JSON-LD context.
+  pub fn data_model_version(&self) -> Option<VcDataModelVersion> {
+    VcDataModelVersion::from_context(self.context.iter())
+  }
+
   /// Returns the base type for `Presentation`s.
   pub const fn base_type() -> &'static str {
     "VerifiablePresentation"
@@ -113,10 +120,9 @@ impl<CRED, T> Presentation<CRED, T> {
   /// This does not check the semantic structure of the contained credentials. This needs to be done as part of
   /// signature validation on the credentials as they are encoded as JWTs.
   pub fn check_structure(&self) -> Result<()> {
-    // Ensure the base context is present and in the correct location
-    match self.context.get(0) {
-      Some(context) if context == Self::base_context() => {}
-      Some(_) | None => return Err(Error::MissingBaseContext),
+    // Ensure the base context of either version of the data model is present and in the correct location
+    if self.data_model_version().is_none() {
+      return Err(Error::MissingBaseContext);
     }
 
     // The set of types MUST contain the base type
@@ -126,8 +132,13 @@ impl<CRED, T> Presentation<CRED, T> {
     Ok(())
   }
 
-  /// Serializes the [`Presentation`] as a JWT claims set
-  /// in accordance with [VC Data Model v1.1](https://www.w3.org/TR/vc-data-model/#json-web-token).
+  /// Serializes the [`Presentation`] as a JWT claims set.
+  ///
+  /// The encoding is selected by the version of the data model the presentation conforms to:
+  /// - [VC Data Model v1.1](https://www.w3.org/TR/vc-data-model/#json-web-token) presentations are mapped to the `vp`
+  ///   claim,
+  /// - [VC Data Model v2.0](https://www.w3.org/TR/vc-jose-cose/#securing-vps-with-jose) presentations are the claims
+  ///   set themselves, to be secured with a `typ` of [`VP_JWT_MEDIA_TYPE`](crate::presentation::VP_JWT_MEDIA_TYPE).
   ///
   /// The resulting string can be used as the payload of a JWS when issuing the credential.  
   pub fn serialize_jwt(&self, options: &JwtPresentationOptions) -> Result<String>
@@ -135,6 +146,10 @@ impl<CRED, T> Presentation<CRED, T> {
     T: ToOwned<Owned = T> + serde::Serialize + serde::de::DeserializeOwned,
     CRED: ToOwned<Owned = CRED> + serde::Serialize + serde::de::DeserializeOwned + Clone,
   {
+    if self.data_model_version() == Some(VcDataModelVersion::V2_0) {
+      return super::jwt_serialization::serialize_jwt_v2(self, options);
+    }
+
     let jwt_representation: PresentationJwtClaims<'_, CRED, T> = PresentationJwtClaims::new(self, options)?;
     jwt_representation
       .to_json()
@@ -8,6 +8,7 @@ use identity_core::common::Value;
 
 use crate::credential::Policy;
 use crate::credential::RefreshService;
+use crate::credential::VcDataModelVersion;
 use crate::error::Result;
 
 use super::Presentation;
@@ -86,6 +87,20 @@ impl<CRED, T> PresentationBuilder<CRED, T> {
   pub fn build(self) -> Result<Presentation<CRED, T>> {
     Presentation::from_builder(self)
   }
+
+  /// Returns a new `Presentation` conforming to [VC Data Model v2.0](https://www.w3.org/TR/vc-data-model-2.0/)
+  /// based on the `PresentationBuilder` configuration.
+  ///
+  /// If the first context of the builder is the VC Data Model v1.1 base context, it is replaced by the v2.0 one.
+  pub fn build_v2(mut self) -> Result<Presentation<CRED, T>> {
+    match self.context.first_mut() {
+      Some(first) if first == VcDataModelVersion::V1_1.base_context() => {
+        *first = VcDataModelVersion::V2_0.base_context().clone()
+      }
+      _ => (),
+    }
+    Presentation::from_builder(self)
+  }
 }
 
 impl PresentationBuilder<Object> {
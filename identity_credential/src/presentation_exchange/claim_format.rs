@@ -0,0 +1,297 @@
+// Copyright 2020-2024 IOTA Stiftung, Fondazione Links
+// SPDX-License-Identifier: Apache-2.0
+
+use std::fmt::Display;
+use std::str::FromStr;
+
+use identity_core::common::Object;
+use identity_verification::jws::Decoder;
+use identity_verification::jws::JwsValidationItem;
+use serde::Deserialize;
+use serde::Serialize;
+use serde_json::Value;
+
+use super::PresentationExchangeError;
+use crate::credential::Jwt;
+
+/// A [claim format designation](https://identity.foundation/claim-format-registry/) identifying how a credential or
+/// presentation is encoded and secured.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum ClaimFormat {
+  /// A credential secured as a JWT (`jwt_vc`).
+  JwtVc,
+  /// A credential of the VC Data Model secured as a JWT (`jwt_vc_json`).
+  JwtVcJson,
+  /// A presentation secured as a JWT (`jwt_vp`).
+  JwtVp,
+  /// A presentation of the VC Data Model secured as a JWT (`jwt_vp_json`).
+  JwtVpJson,
+  /// A credential secured with a Data Integrity proof (`ldp_vc`).
+  LdpVc,
+  /// A presentation secured with a Data Integrity proof (`ldp_vp`).
+  LdpVp,
+  /// A selectively disclosable credential secured as an SD-JWT (`vc+sd-jwt`).
+  SdJwt,
+  /// A selectively disclosable credential secured as a JSON Proof Token (`jpt`).
+  Jpt,
+  /// Any other claim format.
+  Other(String),
+}
+
+impl ClaimFormat {
+  /// Returns the string representation of the claim format.
+  pub fn as_str(&self) -> &str {
+    match self {
+      Self::JwtVc => "jwt_vc",
+      Self::JwtVcJson => "jwt_vc_json",
+      Self::JwtVp => "jwt_vp",
+      Self::JwtVpJson => "jwt_vp_json",
+      Self::LdpVc => "ldp_vc",
+      Self::LdpVp => "ldp_vp",
+      Self::SdJwt => "vc+sd-jwt",
+      Self::Jpt => "jpt",
+      Self::Other(format) => format,
+    }
+  }
+
+  /// Returns `true` if credentials in this format allow the holder to disclose only some of their claims.
+  pub fn is_selectively_disclosable(&self) -> bool {
+    matches!(self, Self::SdJwt | Self::Jpt)
+  }
+
+  /// Returns `true` if this is a presentation format that embeds credentials.
+  pub fn is_presentation(&self) -> bool {
+    matches!(self, Self::JwtVp | Self::JwtVpJson | Self::LdpVp)
+  }
+}
+
+impl FromStr for ClaimFormat {
+  type Err = std::convert::Infallible;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    Ok(match s {
+      "jwt_vc" => Self::JwtVc,
+      "jwt_vc_json" => Self::JwtVcJson,
+      "jwt_vp" => Self::JwtVp,
+      "jwt_vp_json" => Self::JwtVpJson,
+      "ldp_vc" => Self::LdpVc,
+      "ldp_vp" => Self::LdpVp,
+      "vc+sd-jwt" => Self::SdJwt,
+      "jpt" => Self::Jpt,
+      other => Self::Other(other.to_owned()),
+    })
+  }
+}
+
+impl Display for ClaimFormat {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    f.write_str(self.as_str())
+  }
+}
+
+impl Serialize for ClaimFormat {
+  fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(self.as_str())
+  }
+}
+
+impl<'de> Deserialize<'de> for ClaimFormat {
+  fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+    let format: String = String::deserialize(deserializer)?;
+    Ok(Self::from_str(&format).unwrap_or_else(|never| match never {}))
+  }
+}
+
+/// The algorithms and proof types a verifier accepts for a [`ClaimFormat`].
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ClaimFormatDesignation {
+  /// The accepted JWS or JWP algorithms.
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub alg: Option<Vec<String>>,
+  /// The accepted Data Integrity proof types.
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub proof_type: Option<Vec<String>>,
+  /// Additional format-specific properties.
+  #[serde(flatten)]
+  pub properties: Object,
+}
+
+impl ClaimFormatDesignation {
+  /// Returns whether a credential secured with `alg` and `proof_type` is acceptable.
+  pub(crate) fn accepts(&self, alg: Option<&str>, proof_type: Option<&str>) -> bool {
+    let accepted = |allowed: &Option<Vec<String>>, actual: Option<&str>| match (allowed, actual) {
+      (None, _) => true,
+      (Some(allowed), Some(actual)) => allowed.iter().any(|allowed| allowed == actual),
+      (Some(_), None) => false,
+    };
+    accepted(&self.alg, alg) && accepted(&self.proof_type, proof_type)
+  }
+}
+
+/// A credential or presentation in a given [`ClaimFormat`], together with its decoded claims.
+///
+/// Decoding does **not** verify any signature or proof: the holder uses this type to describe its own credentials,
+/// and the verifier must validate the credentials returned by
+/// [`PresentationDefinition::evaluate`](crate::presentation_exchange::PresentationDefinition::evaluate) with the
+/// validator of their format.
+#[derive(Clone, Debug, PartialEq)]
+pub struct FormattedCredential {
+  format: ClaimFormat,
+  credential: Value,
+  claims: Value,
+  alg: Option<String>,
+  proof_type: Option<String>,
+}
+
+impl FormattedCredential {
+  /// Decodes `credential`, which is a string for JWT-based formats and a JSON object for Data Integrity formats.
+  pub fn new(format: ClaimFormat, credential: Value) -> Result<Self, PresentationExchangeError> {
+    let decoding_error = |reason: String| PresentationExchangeError::DecodingError {
+      format: format.to_string(),
+      reason,
+    };
+
+    let (claims, alg, proof_type): (Value, Option<String>, Option<String>) = match &format {
+      ClaimFormat::JwtVc | ClaimFormat::JwtVcJson | ClaimFormat::JwtVp | ClaimFormat::JwtVpJson => {
+        let (claims, alg) = decode_jwt(as_token(&credential).map_err(decoding_error)?).map_err(decoding_error)?;
+        (claims, alg, None)
+      }
+      ClaimFormat::LdpVc | ClaimFormat::LdpVp => {
+        if !credential.is_object() {
+          return Err(decoding_error("expected a JSON object".to_owned()));
+        }
+        let proof_type: Option<String> = credential
+          .get("proof")
+          .and_then(|proof| proof.get("type"))
+          .and_then(Value::as_str)
+          .map(ToOwned::to_owned);
+        (credential.clone(), None, proof_type)
+      }
+      #[cfg(feature = "sd-jwt")]
+      ClaimFormat::SdJwt => {
+        let (claims, alg) = decode_sd_jwt(as_token(&credential).map_err(decoding_error)?).map_err(decoding_error)?;
+        (claims, alg, None)
+      }
+      #[cfg(feature = "jpt-bbs-plus")]
+      ClaimFormat::Jpt => {
+        let (claims, alg) = decode_jpt(as_token(&credential).map_err(decoding_error)?).map_err(decoding_error)?;
+        (claims, alg, None)
+      }
+      format => return Err(PresentationExchangeError::UnsupportedFormat(format.to_string())),
+    };
+
+    Ok(Self {
+      format,
+      credential,
+      claims,
+      alg,
+      proof_type,
+    })
+  }
+
+  /// Decodes a credential secured as a JWT.
+  pub fn from_jwt(credential: &Jwt) -> Result<Self, PresentationExchangeError> {
+    Self::new(ClaimFormat::JwtVc, Value::String(credential.as_str().to_owned()))
+  }
+
+  /// Decodes a credential secured as an SD-JWT, considering the claims of all its disclosures.
+  #[cfg(feature = "sd-jwt")]
+  pub fn from_sd_jwt(credential: &sd_jwt_payload::SdJwt) -> Result<Self, PresentationExchangeError> {
+    Self::new(ClaimFormat::SdJwt, Value::String(credential.presentation()))
+  }
+
+  /// Decodes a credential secured as a JSON Proof Token.
+  #[cfg(feature = "jpt-bbs-plus")]
+  pub fn from_jpt(credential: &crate::credential::Jpt) -> Result<Self, PresentationExchangeError> {
+    Self::new(ClaimFormat::Jpt, Value::String(credential.as_str().to_owned()))
+  }
+
+  /// Returns the claim format of the credential.
+  pub fn format(&self) -> &ClaimFormat {
+    &self.format
+  }
+
+  /// Returns the credential as it is embedded in a presentation.
+  pub fn credential(&self) -> &Value {
+    &self.credential
+  }
+
+  /// Returns the decoded claims of the credential that are evaluated against input descriptors.
+  ///
+  /// For JWT-based formats these are the claims of the token, e.g. the credential is found under `vc`.
+  pub fn claims(&self) -> &Value {
+    &self.claims
+  }
+
+  /// Returns the algorithm the credential is secured with, if any.
+  pub fn alg(&self) -> Option<&str> {
+    self.alg.as_deref()
+  }
+
+  /// Returns the type of the Data Integrity proof of the credential, if any.
+  pub fn proof_type(&self) -> Option<&str> {
+    self.proof_type.as_deref()
+  }
+}
+
+fn as_token(credential: &Value) -> Result<&str, String> {
+  credential
+    .as_str()
+    .map(str::trim)
+    .ok_or_else(|| "expected a string".to_owned())
+}
+
+fn decode_jwt(token: &str) -> Result<(Value, Option<String>), String> {
+  let decoded: JwsValidationItem<'_> = Decoder::new()
+    .decode_compact_serialization(token.as_bytes(), None)
+    .map_err(|err| err.to_string())?;
+  let claims: Value = serde_json::from_slice(decoded.claims()).map_err(|err| err.to_string())?;
+  Ok((claims, decoded.alg().map(|alg| alg.name().to_owned())))
+}
+
+#[cfg(feature = "sd-jwt")]
+fn decode_sd_jwt(token: &str) -> Result<(Value, Option<String>), String> {
+  use sd_jwt_payload::SdJwt;
+  use sd_jwt_payload::SdObjectDecoder;
+
+  let sd_jwt: SdJwt = SdJwt::parse(token).map_err(|err| err.to_string())?;
+  let (claims, alg) = decode_jwt(&sd_jwt.jwt)?;
+  let claims: &serde_json::Map<String, Value> = claims.as_object().ok_or("expected a JSON object as payload")?;
+  let claims = SdObjectDecoder::new_with_sha256()
+    .decode(claims, &sd_jwt.disclosures)
+    .map_err(|err| err.to_string())?;
+  Ok((Value::Object(claims), alg))
+}
+
+#[cfg(feature = "jpt-bbs-plus")]
+fn decode_jpt(token: &str) -> Result<(Value, Option<String>), String> {
+  use jsonprooftoken::encoding::SerializationType;
+  use jsonprooftoken::jpt::claims::JptClaims;
+  use jsonprooftoken::jwp::issued::JwpIssuedDecoder;
+  use jsonprooftoken::jwp::presented::JwpPresentedDecoder;
+
+  // A holder stores issued JPTs, while a verifier receives presented ones.
+  let (claims, payloads, alg) = match JwpPresentedDecoder::decode(token, SerializationType::COMPACT) {
+    Ok(presented) => {
+      let header = presented.get_issuer_header();
+      (
+        header.claims().cloned(),
+        presented.get_payloads().clone(),
+        header.alg().to_string(),
+      )
+    }
+    Err(_) => {
+      let issued = JwpIssuedDecoder::decode(token, SerializationType::COMPACT).map_err(|err| err.to_string())?;
+      let header = issued.get_header();
+      (
+        header.claims().cloned(),
+        issued.get_payloads().clone(),
+        header.alg().to_string(),
+      )
+    }
+  };
+  let claims = claims.ok_or("claims not present")?;
+  let claims: Value =
+    serde_json::to_value(JptClaims::from_claims_and_payloads(&claims, &payloads)).map_err(|err| err.to_string())?;
+  Ok((claims, Some(alg)))
+}
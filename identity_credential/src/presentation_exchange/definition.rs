@@ -0,0 +1,320 @@
+// Copyright 2020-2024 IOTA Stiftung, Fondazione Links
+// SPDX-License-Identifier: Apache-2.0
+
+use std::collections::BTreeMap;
+use std::collections::BTreeSet;
+
+use serde::Deserialize;
+use serde::Serialize;
+use serde_json::Value;
+
+use super::json_schema;
+use super::ClaimFormat;
+use super::ClaimFormatDesignation;
+use super::FormattedCredential;
+use super::JsonPath;
+use super::PresentationExchangeError;
+
+/// The claim formats, and their algorithms or proof types, accepted by a verifier.
+pub type ClaimFormats = BTreeMap<ClaimFormat, ClaimFormatDesignation>;
+
+/// A [Presentation Definition](https://identity.foundation/presentation-exchange/spec/v2.0.0/#presentation-definition)
+/// describing the proofs a verifier requires from a holder.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct PresentationDefinition {
+  /// The unique identifier of the definition.
+  pub id: String,
+  /// A human-friendly name of the definition.
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub name: Option<String>,
+  /// The purpose for which the proofs are requested.
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub purpose: Option<String>,
+  /// The claim formats accepted for all input descriptors.
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub format: Option<ClaimFormats>,
+  /// Rules on which combinations of input descriptors must be submitted.
+  ///
+  /// If absent, every input descriptor must be submitted.
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub submission_requirements: Option<Vec<SubmissionRequirement>>,
+  /// The credentials requested by the verifier.
+  pub input_descriptors: Vec<InputDescriptor>,
+}
+
+impl PresentationDefinition {
+  /// Creates a new [`PresentationDefinition`] requesting all `input_descriptors`.
+  pub fn new(id: impl Into<String>, input_descriptors: Vec<InputDescriptor>) -> Self {
+    Self {
+      id: id.into(),
+      name: None,
+      purpose: None,
+      format: None,
+      submission_requirements: None,
+      input_descriptors,
+    }
+  }
+
+  /// Returns the input descriptor identified by `id`.
+  pub fn input_descriptor(&self, id: &str) -> Option<&InputDescriptor> {
+    self.input_descriptors.iter().find(|descriptor| descriptor.id == id)
+  }
+
+  /// Returns the ids of the input descriptors belonging to `group`.
+  pub(crate) fn group(&self, group: &str) -> impl Iterator<Item = &str> + '_ {
+    let group: String = group.to_owned();
+    self
+      .input_descriptors
+      .iter()
+      .filter(move |descriptor| descriptor.group.contains(&group))
+      .map(|descriptor| descriptor.id.as_str())
+  }
+}
+
+/// An [Input Descriptor](https://identity.foundation/presentation-exchange/spec/v2.0.0/#input-descriptor-object)
+/// describing a single credential requested by a verifier.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct InputDescriptor {
+  /// The unique identifier of the input descriptor within the presentation definition.
+  pub id: String,
+  /// A human-friendly name of the input descriptor.
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub name: Option<String>,
+  /// The purpose for which the credential is requested.
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub purpose: Option<String>,
+  /// The claim formats accepted for this input descriptor, overriding those of the definition.
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub format: Option<ClaimFormats>,
+  /// The groups referenced by the `from` property of submission requirements.
+  #[serde(default, skip_serializing_if = "Vec::is_empty")]
+  pub group: Vec<String>,
+  /// The constraints a credential must satisfy.
+  #[serde(default)]
+  pub constraints: Constraints,
+}
+
+impl InputDescriptor {
+  /// Creates a new [`InputDescriptor`] with the given `constraints`.
+  pub fn new(id: impl Into<String>, constraints: Constraints) -> Self {
+    Self {
+      id: id.into(),
+      name: None,
+      purpose: None,
+      format: None,
+      group: Vec::new(),
+      constraints,
+    }
+  }
+
+  /// Checks whether `credential` is acceptable for this input descriptor, given the claim formats
+  /// `definition_format` of the enclosing presentation definition.
+  ///
+  /// Returns [`PresentationExchangeError::ConstraintsNotSatisfied`] if it is not.
+  pub fn check(
+    &self,
+    definition_format: Option<&ClaimFormats>,
+    credential: &FormattedCredential,
+  ) -> Result<(), PresentationExchangeError> {
+    let not_satisfied = |reason: String| PresentationExchangeError::ConstraintsNotSatisfied {
+      input_descriptor: self.id.clone(),
+      reason,
+    };
+
+    if let Some(formats) = self.format.as_ref().or(definition_format) {
+      let accepted: bool = formats
+        .get(credential.format())
+        .map(|designation| designation.accepts(credential.alg(), credential.proof_type()))
+        .unwrap_or(false);
+      if !accepted {
+        return Err(not_satisfied(format!(
+          "claim format `{}` with algorithm `{}` is not accepted",
+          credential.format(),
+          credential.alg().or(credential.proof_type()).unwrap_or("none")
+        )));
+      }
+    }
+
+    if self.constraints.limit_disclosure == Some(LimitDisclosure::Required)
+      && !credential.format().is_selectively_disclosable()
+    {
+      return Err(not_satisfied(format!(
+        "claim format `{}` does not support limited disclosure",
+        credential.format()
+      )));
+    }
+
+    for field in self.constraints.fields.iter().filter(|field| !field.optional) {
+      if !field.is_satisfied(credential.claims())? {
+        let field_name: String = field.id.clone().unwrap_or_else(|| field.path.join(", "));
+        return Err(not_satisfied(format!("no value satisfies the field `{field_name}`")));
+      }
+    }
+
+    Ok(())
+  }
+}
+
+/// The [constraints](https://identity.foundation/presentation-exchange/spec/v2.0.0/#input-descriptor-object) of an
+/// [`InputDescriptor`].
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Constraints {
+  /// Whether the holder must only disclose the claims selected by [`Constraints::fields`].
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub limit_disclosure: Option<LimitDisclosure>,
+  /// The claims a credential must contain.
+  #[serde(default, skip_serializing_if = "Vec::is_empty")]
+  pub fields: Vec<Field>,
+}
+
+impl Constraints {
+  /// Creates new [`Constraints`] from `fields`.
+  pub fn new(fields: Vec<Field>) -> Self {
+    Self {
+      limit_disclosure: None,
+      fields,
+    }
+  }
+}
+
+/// Whether the holder must limit the disclosed claims to those requested.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LimitDisclosure {
+  /// Only the requested claims may be disclosed.
+  Required,
+  /// Only the requested claims should be disclosed.
+  Preferred,
+}
+
+/// A [field](https://identity.foundation/presentation-exchange/spec/v2.0.0/#input-descriptor-object) selecting a
+/// claim of a credential.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Field {
+  /// The unique identifier of the field.
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub id: Option<String>,
+  /// JSONPath expressions selecting the claim, evaluated in order until one selects a value satisfying the filter.
+  pub path: Vec<String>,
+  /// The purpose for which the claim is requested.
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub purpose: Option<String>,
+  /// A human-friendly name of the claim.
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub name: Option<String>,
+  /// A JSON Schema the claim must be valid against.
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub filter: Option<Value>,
+  /// Whether the claim may be absent.
+  #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+  pub optional: bool,
+  /// Whether the verifier intends to retain the claim.
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub intent_to_retain: Option<bool>,
+}
+
+impl Field {
+  /// Creates a new [`Field`] selecting the claim at one of `path`.
+  pub fn new(path: Vec<String>) -> Self {
+    Self {
+      id: None,
+      path,
+      purpose: None,
+      name: None,
+      filter: None,
+      optional: false,
+      intent_to_retain: None,
+    }
+  }
+
+  /// Sets the JSON Schema the claim must be valid against.
+  pub fn with_filter(mut self, filter: Value) -> Self {
+    self.filter = Some(filter);
+    self
+  }
+
+  /// Returns whether one of the paths selects a claim of `claims` satisfying the filter.
+  pub(crate) fn is_satisfied(&self, claims: &Value) -> Result<bool, PresentationExchangeError> {
+    for path in &self.path {
+      let path: JsonPath = JsonPath::parse(path)?;
+      for value in path.query(claims) {
+        let valid: bool = match &self.filter {
+          Some(filter) => json_schema::is_valid(filter, value)?,
+          None => true,
+        };
+        if valid {
+          return Ok(true);
+        }
+      }
+    }
+    Ok(false)
+  }
+}
+
+/// A [Submission Requirement](https://identity.foundation/presentation-exchange/spec/v2.0.0/#submission-requirements)
+/// stating which combinations of input descriptors must be submitted.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SubmissionRequirement {
+  /// A human-friendly name of the requirement.
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub name: Option<String>,
+  /// The purpose of the requirement.
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub purpose: Option<String>,
+  /// Whether all or some of the referenced inputs must be submitted.
+  pub rule: SubmissionRequirementRule,
+  /// The exact number of inputs to submit when the rule is [`SubmissionRequirementRule::Pick`].
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub count: Option<usize>,
+  /// The minimum number of inputs to submit when the rule is [`SubmissionRequirementRule::Pick`].
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub min: Option<usize>,
+  /// The maximum number of inputs to submit when the rule is [`SubmissionRequirementRule::Pick`].
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub max: Option<usize>,
+  /// The group of input descriptors the requirement applies to.
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub from: Option<String>,
+  /// The nested requirements the requirement applies to, if `from` is absent.
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub from_nested: Option<Vec<SubmissionRequirement>>,
+}
+
+/// The rule of a [`SubmissionRequirement`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SubmissionRequirementRule {
+  /// All inputs must be submitted.
+  All,
+  /// A number of inputs constrained by `count`, `min` and `max` must be submitted.
+  Pick,
+}
+
+impl SubmissionRequirement {
+  /// Returns whether submitting the input descriptors `submitted` satisfies this requirement.
+  pub(crate) fn is_satisfied(&self, definition: &PresentationDefinition, submitted: &BTreeSet<&str>) -> bool {
+    let (total, satisfied): (usize, usize) = if let Some(group) = &self.from {
+      definition.group(group).fold((0, 0), |(total, satisfied), id| {
+        (total + 1, satisfied + usize::from(submitted.contains(id)))
+      })
+    } else {
+      let nested: &[SubmissionRequirement] = self.from_nested.as_deref().unwrap_or_default();
+      nested.iter().fold((0, 0), |(total, satisfied), requirement| {
+        let is_satisfied: bool = requirement.is_satisfied(definition, submitted);
+        (total + 1, satisfied + usize::from(is_satisfied))
+      })
+    };
+
+    match self.rule {
+      SubmissionRequirementRule::All => satisfied == total,
+      SubmissionRequirementRule::Pick => self.accepts_count(satisfied),
+    }
+  }
+
+  /// Returns whether picking `picked` inputs satisfies the `count`, `min` and `max` of this requirement.
+  pub(crate) fn accepts_count(&self, picked: usize) -> bool {
+    self.count.map_or(true, |count| picked == count)
+      && self.min.map_or(true, |min| picked >= min)
+      && self.max.map_or(true, |max| picked <= max)
+  }
+}
@@ -0,0 +1,56 @@
+// Copyright 2020-2024 IOTA Stiftung, Fondazione Links
+// SPDX-License-Identifier: Apache-2.0
+
+use thiserror::Error;
+
+/// [Error](std::error::Error) type that represents the possible errors that can be
+/// encountered when dealing with Presentation Exchange definitions and submissions.
+#[derive(Clone, Debug, Error, strum::IntoStaticStr, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum PresentationExchangeError {
+  /// A `path` is not a JSONPath expression supported by this implementation.
+  #[error("invalid JSONPath expression `{path}`: {reason}")]
+  InvalidJsonPath {
+    /// The invalid expression.
+    path: String,
+    /// Why the expression is invalid.
+    reason: String,
+  },
+  /// A `filter` is not a valid JSON Schema.
+  #[error("invalid filter: {0}")]
+  InvalidFilter(String),
+  /// A credential could not be decoded according to its claim format.
+  #[error("could not decode `{format}` credential: {reason}")]
+  DecodingError {
+    /// The claim format of the credential.
+    format: String,
+    /// Why decoding failed.
+    reason: String,
+  },
+  /// The claim format is not supported, or the crate feature supporting it is not enabled.
+  #[error("unsupported claim format `{0}`")]
+  UnsupportedFormat(String),
+  /// The submission refers to a different presentation definition.
+  #[error("the submission refers to presentation definition `{0}`")]
+  DefinitionMismatch(String),
+  /// The submission refers to an input descriptor that is not part of the presentation definition.
+  #[error("unknown input descriptor `{0}`")]
+  UnknownInputDescriptor(String),
+  /// A `path` of the submission does not select exactly one element of the presentation.
+  #[error("the path `{0}` does not select exactly one element")]
+  PathNotFound(String),
+  /// A submitted credential does not satisfy the input descriptor it is mapped to.
+  #[error("the credential submitted for input descriptor `{input_descriptor}` is not acceptable: {reason}")]
+  ConstraintsNotSatisfied {
+    /// The id of the input descriptor.
+    input_descriptor: String,
+    /// Why the credential is not acceptable.
+    reason: String,
+  },
+  /// An input descriptor required by the definition is not submitted, or no credential satisfies it.
+  #[error("no credential is submitted for input descriptor `{0}`")]
+  MissingInputDescriptor(String),
+  /// The submitted input descriptors do not satisfy the submission requirements of the definition.
+  #[error("the submission requirements are not satisfied")]
+  SubmissionRequirementsNotSatisfied,
+}
@@ -0,0 +1,105 @@
+// Copyright 2020-2024 IOTA Stiftung, Fondazione Links
+// SPDX-License-Identifier: Apache-2.0
+
+use std::collections::BTreeSet;
+
+use serde_json::Value;
+
+use super::FormattedCredential;
+use super::InputDescriptor;
+use super::InputDescriptorMapping;
+use super::JsonPath;
+use super::PresentationDefinition;
+use super::PresentationExchangeError;
+use super::PresentationSubmission;
+
+/// A credential of a presentation that satisfies an input descriptor.
+#[derive(Clone, Debug, PartialEq)]
+pub struct EvaluatedDescriptor {
+  /// The id of the input descriptor.
+  pub input_descriptor_id: String,
+  /// The credential submitted for the input descriptor.
+  pub credential: FormattedCredential,
+}
+
+impl PresentationDefinition {
+  /// Checks that `vp_token`, described by `submission`, satisfies this definition and returns the credentials
+  /// submitted for each input descriptor.
+  ///
+  /// `vp_token` is the presentation as received from the holder: a compact JWT, SD-JWT or JPT is passed as a JSON
+  /// string, and multiple presentations as a JSON array.
+  ///
+  /// # Warning
+  /// Only the claims of the credentials are evaluated. Signatures, holder bindings and credential statuses are
+  /// **not** verified: every returned credential, and the enclosing presentation, must still be validated with the
+  /// validator of its claim format.
+  pub fn evaluate(
+    &self,
+    submission: &PresentationSubmission,
+    vp_token: &Value,
+  ) -> Result<Vec<EvaluatedDescriptor>, PresentationExchangeError> {
+    if submission.definition_id != self.id {
+      return Err(PresentationExchangeError::DefinitionMismatch(
+        submission.definition_id.clone(),
+      ));
+    }
+
+    let mut evaluated: Vec<EvaluatedDescriptor> = Vec::with_capacity(submission.descriptor_map.len());
+    for mapping in &submission.descriptor_map {
+      let descriptor: &InputDescriptor = self
+        .input_descriptor(&mapping.id)
+        .ok_or_else(|| PresentationExchangeError::UnknownInputDescriptor(mapping.id.clone()))?;
+      let credential: FormattedCredential = resolve(mapping, vp_token)?;
+      descriptor.check(self.format.as_ref(), &credential)?;
+      evaluated.push(EvaluatedDescriptor {
+        input_descriptor_id: mapping.id.clone(),
+        credential,
+      });
+    }
+
+    let submitted: BTreeSet<&str> = evaluated
+      .iter()
+      .map(|evaluated| evaluated.input_descriptor_id.as_str())
+      .collect();
+    match &self.submission_requirements {
+      None => {
+        if let Some(missing) = self
+          .input_descriptors
+          .iter()
+          .find(|descriptor| !submitted.contains(descriptor.id.as_str()))
+        {
+          return Err(PresentationExchangeError::MissingInputDescriptor(missing.id.clone()));
+        }
+      }
+      Some(requirements) => {
+        if !requirements
+          .iter()
+          .all(|requirement| requirement.is_satisfied(self, &submitted))
+        {
+          return Err(PresentationExchangeError::SubmissionRequirementsNotSatisfied);
+        }
+      }
+    }
+
+    Ok(evaluated)
+  }
+}
+
+/// Resolves the credential selected by `mapping` in `root`, following nested mappings.
+fn resolve(mapping: &InputDescriptorMapping, root: &Value) -> Result<FormattedCredential, PresentationExchangeError> {
+  let path: JsonPath = JsonPath::parse(&mapping.path)?;
+  let selected: &Value = match path.query(root).as_slice() {
+    [selected] => selected,
+    _ => return Err(PresentationExchangeError::PathNotFound(mapping.path.clone())),
+  };
+  let element: FormattedCredential = FormattedCredential::new(mapping.format.clone(), selected.clone())?;
+
+  match &mapping.path_nested {
+    Some(nested) if nested.id != mapping.id => {
+      Err(PresentationExchangeError::UnknownInputDescriptor(nested.id.clone()))
+    }
+    // The path of a nested mapping is relative to the claims of the enclosing presentation.
+    Some(nested) => resolve(nested, element.claims()),
+    None => Ok(element),
+  }
+}
@@ -0,0 +1,318 @@
+// Copyright 2020-2024 IOTA Stiftung, Fondazione Links
+// SPDX-License-Identifier: Apache-2.0
+
+use std::fmt::Display;
+use std::str::FromStr;
+
+use serde_json::Value;
+
+use super::PresentationExchangeError;
+
+/// A parsed [JSONPath](https://www.rfc-editor.org/rfc/rfc9535) expression, as used by the `path` properties of
+/// Presentation Exchange.
+///
+/// The supported subset consists of the root identifier `$`, member names in dot (`.name`) and bracket
+/// (`['name']`) notation, array indices (`[0]`, `[-1]`), wildcards (`.*`, `[*]`), union of names or indices
+/// (`['a','b']`, `[0,1]`) and descendant segments (`..name`, `..*`). Filter expressions are not supported.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct JsonPath {
+  expression: String,
+  segments: Vec<Segment>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct Segment {
+  descendant: bool,
+  selectors: Vec<Selector>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Selector {
+  Name(String),
+  Index(i64),
+  Wildcard,
+}
+
+impl JsonPath {
+  /// Parses a JSONPath `expression`.
+  pub fn parse(expression: &str) -> Result<Self, PresentationExchangeError> {
+    Parser::new(expression).parse()
+  }
+
+  /// Returns the string representation of the expression.
+  pub fn as_str(&self) -> &str {
+    &self.expression
+  }
+
+  /// Returns the nodes of `value` selected by this expression.
+  pub fn query<'a>(&self, value: &'a Value) -> Vec<&'a Value> {
+    let mut nodes: Vec<&'a Value> = vec![value];
+    for segment in &self.segments {
+      let inputs: Vec<&'a Value> = if segment.descendant {
+        nodes.into_iter().flat_map(descendants).collect()
+      } else {
+        nodes
+      };
+      nodes = inputs
+        .into_iter()
+        .flat_map(|node| segment.selectors.iter().flat_map(move |selector| selector.select(node)))
+        .collect();
+    }
+    nodes
+  }
+}
+
+impl FromStr for JsonPath {
+  type Err = PresentationExchangeError;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    Self::parse(s)
+  }
+}
+
+impl Display for JsonPath {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    f.write_str(&self.expression)
+  }
+}
+
+impl Selector {
+  fn select<'a>(&self, value: &'a Value) -> Vec<&'a Value> {
+    match (self, value) {
+      (Self::Name(name), Value::Object(object)) => object.get(name).into_iter().collect(),
+      (Self::Index(index), Value::Array(array)) => {
+        let index: i64 = if *index < 0 { array.len() as i64 + index } else { *index };
+        usize::try_from(index)
+          .ok()
+          .and_then(|index| array.get(index))
+          .into_iter()
+          .collect()
+      }
+      (Self::Wildcard, Value::Object(object)) => object.values().collect(),
+      (Self::Wildcard, Value::Array(array)) => array.iter().collect(),
+      _ => Vec::new(),
+    }
+  }
+}
+
+/// Returns `value` followed by all its descendants, in document order.
+fn descendants(value: &Value) -> Vec<&Value> {
+  let mut nodes: Vec<&Value> = vec![value];
+  match value {
+    Value::Object(object) => nodes.extend(object.values().flat_map(descendants)),
+    Value::Array(array) => nodes.extend(array.iter().flat_map(descendants)),
+    _ => (),
+  }
+  nodes
+}
+
+struct Parser<'a> {
+  expression: &'a str,
+  chars: std::iter::Peekable<std::str::CharIndices<'a>>,
+}
+
+impl<'a> Parser<'a> {
+  fn new(expression: &'a str) -> Self {
+    Self {
+      expression,
+      chars: expression.char_indices().peekable(),
+    }
+  }
+
+  fn error(&self, reason: impl Into<String>) -> PresentationExchangeError {
+    PresentationExchangeError::InvalidJsonPath {
+      path: self.expression.to_owned(),
+      reason: reason.into(),
+    }
+  }
+
+  fn parse(mut self) -> Result<JsonPath, PresentationExchangeError> {
+    if self.next_char() != Some('$') {
+      return Err(self.error("expected the root identifier `$`"));
+    }
+
+    let mut segments: Vec<Segment> = Vec::new();
+    while let Some(c) = self.next_char() {
+      let segment: Segment = match c {
+        '.' => {
+          let descendant: bool = self.eat('.');
+          if descendant && self.eat('[') {
+            Segment {
+              descendant,
+              selectors: self.bracketed_selectors()?,
+            }
+          } else {
+            Segment {
+              descendant,
+              selectors: vec![self.dot_selector()?],
+            }
+          }
+        }
+        '[' => Segment {
+          descendant: false,
+          selectors: self.bracketed_selectors()?,
+        },
+        c => return Err(self.error(format!("unexpected character `{c}`"))),
+      };
+      segments.push(segment);
+    }
+
+    Ok(JsonPath {
+      expression: self.expression.to_owned(),
+      segments,
+    })
+  }
+
+  fn next_char(&mut self) -> Option<char> {
+    self.chars.next().map(|(_, c)| c)
+  }
+
+  fn eat(&mut self, expected: char) -> bool {
+    if self.chars.peek().map(|(_, c)| *c) == Some(expected) {
+      self.chars.next();
+      true
+    } else {
+      false
+    }
+  }
+
+  fn skip_whitespace(&mut self) {
+    while matches!(self.chars.peek(), Some((_, c)) if c.is_whitespace()) {
+      self.chars.next();
+    }
+  }
+
+  fn dot_selector(&mut self) -> Result<Selector, PresentationExchangeError> {
+    if self.eat('*') {
+      return Ok(Selector::Wildcard);
+    }
+    let mut name: String = String::new();
+    while let Some(c) = self.chars.peek().map(|(_, c)| *c) {
+      if c == '.' || c == '[' {
+        break;
+      }
+      if !(c.is_alphanumeric() || c == '_' || c == '-' || c == '$' || c == '@') {
+        return Err(self.error(format!("unexpected character `{c}` in member name")));
+      }
+      name.push(c);
+      self.chars.next();
+    }
+    if name.is_empty() {
+      return Err(self.error("expected a member name"));
+    }
+    Ok(Selector::Name(name))
+  }
+
+  fn bracketed_selectors(&mut self) -> Result<Vec<Selector>, PresentationExchangeError> {
+    let mut selectors: Vec<Selector> = Vec::new();
+    loop {
+      self.skip_whitespace();
+      let selector: Selector = match self.chars.peek().map(|(_, c)| *c) {
+        Some('*') => {
+          self.chars.next();
+          Selector::Wildcard
+        }
+        Some(quote @ ('\'' | '"')) => {
+          self.chars.next();
+          Selector::Name(self.quoted_name(quote)?)
+        }
+        Some(c) if c == '-' || c.is_ascii_digit() => Selector::Index(self.index()?),
+        Some('?') => return Err(self.error("filter expressions are not supported")),
+        _ => return Err(self.error("expected a selector")),
+      };
+      selectors.push(selector);
+
+      self.skip_whitespace();
+      match self.next_char() {
+        Some(',') => continue,
+        Some(']') => return Ok(selectors),
+        _ => return Err(self.error("expected `,` or `]`")),
+      }
+    }
+  }
+
+  fn quoted_name(&mut self, quote: char) -> Result<String, PresentationExchangeError> {
+    let mut name: String = String::new();
+    loop {
+      match self.next_char() {
+        Some('\\') => match self.next_char() {
+          Some(c @ ('\\' | '\'' | '"' | '/')) => name.push(c),
+          Some('b') => name.push('\u{0008}'),
+          Some('f') => name.push('\u{000C}'),
+          Some('n') => name.push('\n'),
+          Some('r') => name.push('\r'),
+          Some('t') => name.push('\t'),
+          _ => return Err(self.error("invalid escape sequence")),
+        },
+        Some(c) if c == quote => return Ok(name),
+        Some(c) => name.push(c),
+        None => return Err(self.error("unterminated string literal")),
+      }
+    }
+  }
+
+  fn index(&mut self) -> Result<i64, PresentationExchangeError> {
+    let mut digits: String = String::new();
+    while let Some((_, c)) = self.chars.peek() {
+      if !(c.is_ascii_digit() || (digits.is_empty() && *c == '-')) {
+        break;
+      }
+      digits.push(*c);
+      self.chars.next();
+    }
+    digits
+      .parse()
+      .map_err(|_| self.error(format!("invalid index `{digits}`")))
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use serde_json::json;
+
+  use super::*;
+
+  #[test]
+  fn query_supported_syntax() {
+    let value: Value = json!({
+      "vc": {
+        "type": ["VerifiableCredential", "UniversityDegreeCredential"],
+        "credentialSubject": { "degree": { "type": "BachelorDegree", "name": "BSc" }, "first name": "Alice" }
+      }
+    });
+    let query = |path: &str| {
+      JsonPath::parse(path)
+        .unwrap()
+        .query(&value)
+        .into_iter()
+        .cloned()
+        .collect::<Vec<_>>()
+    };
+
+    assert_eq!(query("$.vc.type[1]"), vec![json!("UniversityDegreeCredential")]);
+    assert_eq!(
+      query("$['vc'][\"type\"][-1]"),
+      vec![json!("UniversityDegreeCredential")]
+    );
+    assert_eq!(query("$.vc.credentialSubject['first name']"), vec![json!("Alice")]);
+    assert_eq!(query("$.vc.type[*]").len(), 2);
+    assert_eq!(query("$.vc.type[0, 1]").len(), 2);
+    assert_eq!(query("$..degree.name"), vec![json!("BSc")]);
+    assert_eq!(query("$..type").len(), 2);
+    assert!(query("$.vc.missing").is_empty());
+    assert_eq!(query("$"), vec![value.clone()]);
+  }
+
+  #[test]
+  fn reject_unsupported_syntax() {
+    for path in ["vc.type", "$.", "$[?(@.type)]", "$['unterminated]", "$.a b"] {
+      assert!(
+        matches!(
+          JsonPath::parse(path),
+          Err(PresentationExchangeError::InvalidJsonPath { .. })
+        ),
+        "{path}"
+      );
+    }
+  }
+}
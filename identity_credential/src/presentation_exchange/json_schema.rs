@@ -0,0 +1,368 @@
+// Copyright 2020-2024 IOTA Stiftung, Fondazione Links
+// SPDX-License-Identifier: Apache-2.0
+
+//! Evaluation of the JSON Schema `filter`s of Presentation Exchange fields.
+//!
+//! The supported keywords are `type`, `const`, `enum`, `pattern`, `minLength`, `maxLength`, `format` (`date` and
+//! `date-time`), `formatMinimum`, `formatMaximum`, `formatExclusiveMinimum`, `formatExclusiveMaximum`,
+//! `minimum`, `maximum`, `exclusiveMinimum`, `exclusiveMaximum`, `items`, `contains`, `minItems`, `maxItems`,
+//! `properties`, `required`, `not`, `allOf`, `anyOf` and `oneOf`. Other keywords are ignored.
+
+use identity_core::common::Timestamp;
+use regex::Regex;
+use serde_json::Map;
+use serde_json::Value;
+
+use super::PresentationExchangeError;
+
+type SchemaResult<T = bool> = Result<T, PresentationExchangeError>;
+type Check = fn(&Map<String, Value>, &Value) -> SchemaResult;
+/// A keyword bounding a value, with the comparison the value has to satisfy against the bound.
+type Bound<T> = (&'static str, fn(T, T) -> bool);
+
+/// Returns whether `instance` is valid against `schema`.
+pub(crate) fn is_valid(schema: &Value, instance: &Value) -> SchemaResult {
+  match schema {
+    Value::Bool(valid) => Ok(*valid),
+    Value::Object(schema) => is_valid_object(schema, instance),
+    _ => Err(invalid("a schema must be an object or a boolean")),
+  }
+}
+
+fn is_valid_object(schema: &Map<String, Value>, instance: &Value) -> SchemaResult {
+  let checks: [Check; 6] = [
+    check_generic,
+    check_string,
+    check_number,
+    check_array,
+    check_object,
+    check_composition,
+  ];
+  for check in checks {
+    if !check(schema, instance)? {
+      return Ok(false);
+    }
+  }
+  Ok(true)
+}
+
+fn check_generic(schema: &Map<String, Value>, instance: &Value) -> SchemaResult {
+  if let Some(types) = schema.get("type") {
+    let matches_type = |type_: &Value| -> SchemaResult {
+      let type_: &str = type_.as_str().ok_or_else(|| invalid("`type` must contain strings"))?;
+      Ok(match type_ {
+        "null" => instance.is_null(),
+        "boolean" => instance.is_boolean(),
+        "object" => instance.is_object(),
+        "array" => instance.is_array(),
+        "string" => instance.is_string(),
+        "number" => instance.is_number(),
+        "integer" => instance.as_f64().map_or(false, |number| number.fract() == 0.0),
+        other => return Err(invalid(format!("unknown type `{other}`"))),
+      })
+    };
+    let valid: bool = match types {
+      Value::Array(types) => types
+        .iter()
+        .map(matches_type)
+        .collect::<SchemaResult<Vec<bool>>>()?
+        .contains(&true),
+      type_ => matches_type(type_)?,
+    };
+    if !valid {
+      return Ok(false);
+    }
+  }
+  if let Some(constant) = schema.get("const") {
+    if !json_eq(constant, instance) {
+      return Ok(false);
+    }
+  }
+  if let Some(values) = schema.get("enum") {
+    let values: &Vec<Value> = values.as_array().ok_or_else(|| invalid("`enum` must be an array"))?;
+    if !values.iter().any(|value| json_eq(value, instance)) {
+      return Ok(false);
+    }
+  }
+  Ok(true)
+}
+
+fn check_string(schema: &Map<String, Value>, instance: &Value) -> SchemaResult {
+  let Some(string) = instance.as_str() else {
+    return Ok(true);
+  };
+  let length: u64 = string.chars().count() as u64;
+  if let Some(min_length) = schema.get("minLength") {
+    if length < as_u64(min_length, "minLength")? {
+      return Ok(false);
+    }
+  }
+  if let Some(max_length) = schema.get("maxLength") {
+    if length > as_u64(max_length, "maxLength")? {
+      return Ok(false);
+    }
+  }
+  if let Some(pattern) = schema.get("pattern") {
+    let pattern: &str = pattern.as_str().ok_or_else(|| invalid("`pattern` must be a string"))?;
+    let regex: Regex = Regex::new(pattern).map_err(|err| invalid(format!("invalid `pattern`: {err}")))?;
+    if !regex.is_match(string) {
+      return Ok(false);
+    }
+  }
+  let Some(format) = schema.get("format").and_then(Value::as_str) else {
+    return Ok(true);
+  };
+  if !matches!(format, "date" | "date-time") {
+    return Ok(true);
+  }
+  let Some(timestamp) = parse_format(format, string) else {
+    return Ok(false);
+  };
+  // Date-times are compared as points in time, so that bounds with a different UTC offset than the value apply.
+  let bounds: [Bound<Timestamp>; 4] = [
+    ("formatMinimum", |value, bound| value >= bound),
+    ("formatMaximum", |value, bound| value <= bound),
+    ("formatExclusiveMinimum", |value, bound| value > bound),
+    ("formatExclusiveMaximum", |value, bound| value < bound),
+  ];
+  for (keyword, within) in bounds {
+    if let Some(bound) = schema.get(keyword) {
+      let bound: Timestamp = bound
+        .as_str()
+        .and_then(|bound| parse_format(format, bound))
+        .ok_or_else(|| invalid(format!("`{keyword}` must be a {format} string")))?;
+      if !within(timestamp, bound) {
+        return Ok(false);
+      }
+    }
+  }
+  Ok(true)
+}
+
+/// Parses a string of the `date` or `date-time` format, returning `None` if it does not conform to the format.
+fn parse_format(format: &str, string: &str) -> Option<Timestamp> {
+  match format {
+    "date-time" => Timestamp::parse(string).ok(),
+    "date" if string.len() == 10 => Timestamp::parse(&format!("{string}T00:00:00Z")).ok(),
+    _ => None,
+  }
+}
+
+fn check_number(schema: &Map<String, Value>, instance: &Value) -> SchemaResult {
+  let Some(number) = instance.as_f64() else {
+    return Ok(true);
+  };
+  let bounds: [Bound<f64>; 4] = [
+    ("minimum", |value, bound| value >= bound),
+    ("maximum", |value, bound| value <= bound),
+    ("exclusiveMinimum", |value, bound| value > bound),
+    ("exclusiveMaximum", |value, bound| value < bound),
+  ];
+  for (keyword, within) in bounds {
+    if let Some(bound) = schema.get(keyword) {
+      let bound: f64 = bound
+        .as_f64()
+        .ok_or_else(|| invalid(format!("`{keyword}` must be a number")))?;
+      if !within(number, bound) {
+        return Ok(false);
+      }
+    }
+  }
+  Ok(true)
+}
+
+fn check_array(schema: &Map<String, Value>, instance: &Value) -> SchemaResult {
+  let Some(array) = instance.as_array() else {
+    return Ok(true);
+  };
+  if let Some(min_items) = schema.get("minItems") {
+    if (array.len() as u64) < as_u64(min_items, "minItems")? {
+      return Ok(false);
+    }
+  }
+  if let Some(max_items) = schema.get("maxItems") {
+    if (array.len() as u64) > as_u64(max_items, "maxItems")? {
+      return Ok(false);
+    }
+  }
+  if let Some(items) = schema.get("items") {
+    for item in array {
+      if !is_valid(items, item)? {
+        return Ok(false);
+      }
+    }
+  }
+  if let Some(contains) = schema.get("contains") {
+    let mut contained: bool = false;
+    for item in array {
+      if is_valid(contains, item)? {
+        contained = true;
+        break;
+      }
+    }
+    if !contained {
+      return Ok(false);
+    }
+  }
+  Ok(true)
+}
+
+fn check_object(schema: &Map<String, Value>, instance: &Value) -> SchemaResult {
+  let Some(object) = instance.as_object() else {
+    return Ok(true);
+  };
+  if let Some(required) = schema.get("required") {
+    let required: &Vec<Value> = required
+      .as_array()
+      .ok_or_else(|| invalid("`required` must be an array"))?;
+    for property in required {
+      let property: &str = property
+        .as_str()
+        .ok_or_else(|| invalid("`required` must contain strings"))?;
+      if !object.contains_key(property) {
+        return Ok(false);
+      }
+    }
+  }
+  if let Some(properties) = schema.get("properties") {
+    let properties: &Map<String, Value> = properties
+      .as_object()
+      .ok_or_else(|| invalid("`properties` must be an object"))?;
+    for (property, property_schema) in properties {
+      if let Some(value) = object.get(property) {
+        if !is_valid(property_schema, value)? {
+          return Ok(false);
+        }
+      }
+    }
+  }
+  Ok(true)
+}
+
+fn check_composition(schema: &Map<String, Value>, instance: &Value) -> SchemaResult {
+  if let Some(not) = schema.get("not") {
+    if is_valid(not, instance)? {
+      return Ok(false);
+    }
+  }
+  let count_valid = |keyword: &str| -> SchemaResult<Option<(usize, usize)>> {
+    let Some(schemas) = schema.get(keyword) else {
+      return Ok(None);
+    };
+    let schemas: &Vec<Value> = schemas
+      .as_array()
+      .ok_or_else(|| invalid(format!("`{keyword}` must be an array")))?;
+    let mut valid: usize = 0;
+    for schema in schemas {
+      valid += is_valid(schema, instance)? as usize;
+    }
+    Ok(Some((valid, schemas.len())))
+  };
+  if matches!(count_valid("allOf")?, Some((valid, total)) if valid != total) {
+    return Ok(false);
+  }
+  if matches!(count_valid("anyOf")?, Some((valid, _)) if valid == 0) {
+    return Ok(false);
+  }
+  if matches!(count_valid("oneOf")?, Some((valid, _)) if valid != 1) {
+    return Ok(false);
+  }
+  Ok(true)
+}
+
+/// JSON Schema equality, under which numbers are compared by value.
+fn json_eq(left: &Value, right: &Value) -> bool {
+  match (left, right) {
+    (Value::Number(left), Value::Number(right)) => left.as_f64() == right.as_f64(),
+    (Value::Array(left), Value::Array(right)) => {
+      left.len() == right.len() && left.iter().zip(right).all(|(left, right)| json_eq(left, right))
+    }
+    (Value::Object(left), Value::Object(right)) => {
+      left.len() == right.len()
+        && left
+          .iter()
+          .all(|(key, left)| right.get(key).map_or(false, |right| json_eq(left, right)))
+    }
+    (left, right) => left == right,
+  }
+}
+
+fn as_u64(value: &Value, keyword: &str) -> SchemaResult<u64> {
+  value
+    .as_u64()
+    .ok_or_else(|| invalid(format!("`{keyword}` must be a non-negative integer")))
+}
+
+fn invalid(reason: impl Into<String>) -> PresentationExchangeError {
+  PresentationExchangeError::InvalidFilter(reason.into())
+}
+
+#[cfg(test)]
+mod tests {
+  use serde_json::json;
+
+  use super::*;
+
+  #[test]
+  fn evaluate_filters() {
+    let valid = |schema: Value, instance: Value| is_valid(&schema, &instance).unwrap();
+
+    assert!(valid(json!({ "type": "string", "const": "BSc" }), json!("BSc")));
+    assert!(!valid(json!({ "type": "integer" }), json!(1.5)));
+    assert!(valid(json!({ "type": ["string", "null"] }), json!(null)));
+    assert!(valid(json!({ "enum": [1, 2] }), json!(2.0)));
+    assert!(valid(json!({ "pattern": "^did:iota:" }), json!("did:iota:0x01")));
+    assert!(!valid(json!({ "minLength": 4 }), json!("abc")));
+    assert!(valid(json!({ "minimum": 18, "exclusiveMaximum": 65 }), json!(18)));
+    assert!(!valid(json!({ "minimum": 18 }), json!(17)));
+    assert!(valid(
+      json!({ "type": "array", "contains": { "const": "UniversityDegreeCredential" } }),
+      json!(["VerifiableCredential", "UniversityDegreeCredential"])
+    ));
+    assert!(valid(
+      json!({ "type": "string", "format": "date", "formatMaximum": "2006-01-01" }),
+      json!("2000-05-17")
+    ));
+    assert!(!valid(json!({ "format": "date" }), json!("2000-13-17")));
+    // 2024-01-01T00:00:00+02:00 is 2023-12-31T22:00:00Z, which is lexicographically greater but chronologically lower.
+    let offset = json!("2024-01-01T00:00:00+02:00");
+    assert!(!valid(
+      json!({ "format": "date-time", "formatMinimum": "2024-01-01T00:00:00Z" }),
+      offset.clone()
+    ));
+    assert!(valid(
+      json!({ "format": "date-time", "formatExclusiveMaximum": "2024-01-01T00:00:00Z" }),
+      offset
+    ));
+    assert!(valid(
+      json!({ "type": "object", "required": ["name"], "properties": { "name": { "type": "string" } } }),
+      json!({ "name": "BSc" })
+    ));
+    assert!(valid(json!({ "not": { "const": 1 } }), json!(2)));
+    assert!(!valid(
+      json!({ "oneOf": [{ "type": "number" }, { "minimum": 0 }] }),
+      json!(1)
+    ));
+    assert!(valid(
+      json!({ "anyOf": [{ "type": "string" }, { "minimum": 0 }] }),
+      json!(1)
+    ));
+  }
+
+  #[test]
+  fn reject_invalid_filters() {
+    for schema in [json!({ "type": "float" }), json!({ "pattern": "(" }), json!("string")] {
+      assert!(matches!(
+        is_valid(&schema, &json!("value")),
+        Err(PresentationExchangeError::InvalidFilter(_))
+      ));
+    }
+    assert!(matches!(
+      is_valid(
+        &json!({ "format": "date", "formatMinimum": "yesterday" }),
+        &json!("2000-05-17")
+      ),
+      Err(PresentationExchangeError::InvalidFilter(_))
+    ));
+  }
+}
@@ -0,0 +1,202 @@
+// Copyright 2020-2024 IOTA Stiftung, Fondazione Links
+// SPDX-License-Identifier: Apache-2.0
+
+use std::collections::BTreeMap;
+use std::collections::BTreeSet;
+
+use super::ClaimFormat;
+use super::FormattedCredential;
+use super::InputDescriptorMapping;
+use super::PresentationDefinition;
+use super::PresentationExchangeError;
+use super::PresentationSubmission;
+use super::SubmissionRequirement;
+use super::SubmissionRequirementRule;
+
+/// The credentials of a wallet satisfying an input descriptor.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct InputDescriptorMatch {
+  /// The id of the input descriptor.
+  pub input_descriptor_id: String,
+  /// The indices of the credentials of the wallet satisfying the input descriptor.
+  pub credentials: Vec<usize>,
+}
+
+/// How the holder embeds the selected credentials in its response.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SubmissionLayout {
+  /// The credentials are the `verifiableCredential`s of a presentation secured as a JWT (`jwt_vp`).
+  JwtPresentation,
+  /// The credentials are submitted as they are: a single credential on its own, otherwise as a JSON array.
+  ///
+  /// This is the layout of the `vp_token` of OpenID for Verifiable Presentations when submitting SD-JWTs or JPTs.
+  VpToken,
+}
+
+/// A [`PresentationSubmission`] together with the credentials the holder must present.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Submission {
+  /// The submission describing the presentation.
+  pub presentation_submission: PresentationSubmission,
+  /// The indices of the credentials of the wallet, in the order in which they must be embedded in the presentation.
+  pub credentials: Vec<usize>,
+}
+
+impl PresentationDefinition {
+  /// Returns, for every input descriptor, the credentials of `wallet` that satisfy it.
+  pub fn match_credentials(
+    &self,
+    wallet: &[FormattedCredential],
+  ) -> Result<Vec<InputDescriptorMatch>, PresentationExchangeError> {
+    self
+      .input_descriptors
+      .iter()
+      .map(|descriptor| {
+        let mut credentials: Vec<usize> = Vec::new();
+        for (index, credential) in wallet.iter().enumerate() {
+          match descriptor.check(self.format.as_ref(), credential) {
+            Ok(()) => credentials.push(index),
+            Err(PresentationExchangeError::ConstraintsNotSatisfied { .. }) => {}
+            Err(err) => return Err(err),
+          }
+        }
+        Ok(InputDescriptorMatch {
+          input_descriptor_id: descriptor.id.clone(),
+          credentials,
+        })
+      })
+      .collect()
+  }
+
+  /// Selects credentials of `wallet` satisfying this definition and describes them in a [`PresentationSubmission`]
+  /// identified by `submission_id`.
+  ///
+  /// For every selected input descriptor the first matching credential is used. If the definition has submission
+  /// requirements, input descriptors are selected in order until each requirement is satisfied.
+  pub fn create_submission(
+    &self,
+    submission_id: impl Into<String>,
+    wallet: &[FormattedCredential],
+    layout: SubmissionLayout,
+  ) -> Result<Submission, PresentationExchangeError> {
+    let matches: BTreeMap<String, usize> = self
+      .match_credentials(wallet)?
+      .into_iter()
+      .filter_map(|matched| Some((matched.input_descriptor_id, *matched.credentials.first()?)))
+      .collect();
+
+    let selected: BTreeSet<&str> = match &self.submission_requirements {
+      None => self
+        .input_descriptors
+        .iter()
+        .map(|descriptor| {
+          matches
+            .contains_key(&descriptor.id)
+            .then_some(descriptor.id.as_str())
+            .ok_or_else(|| PresentationExchangeError::MissingInputDescriptor(descriptor.id.clone()))
+        })
+        .collect::<Result<_, _>>()?,
+      Some(requirements) => {
+        let mut selected: BTreeSet<&str> = BTreeSet::new();
+        for requirement in requirements {
+          let picked: BTreeSet<&str> =
+            select(self, requirement, &matches).ok_or(PresentationExchangeError::SubmissionRequirementsNotSatisfied)?;
+          selected.extend(picked);
+        }
+        selected
+      }
+    };
+
+    let mut credentials: Vec<usize> = Vec::new();
+    let mut descriptor_map: Vec<InputDescriptorMapping> = Vec::new();
+    // Keep the order of the input descriptors of the definition.
+    for descriptor in self
+      .input_descriptors
+      .iter()
+      .filter(|descriptor| selected.contains(descriptor.id.as_str()))
+    {
+      let wallet_index: usize = matches[&descriptor.id];
+      let position: usize = match credentials.iter().position(|index| *index == wallet_index) {
+        Some(position) => position,
+        None => {
+          credentials.push(wallet_index);
+          credentials.len() - 1
+        }
+      };
+      descriptor_map.push(InputDescriptorMapping {
+        id: descriptor.id.clone(),
+        format: wallet[wallet_index].format().clone(),
+        path: format!("$[{position}]"),
+        path_nested: None,
+      });
+    }
+
+    for mapping in descriptor_map.iter_mut() {
+      match layout {
+        SubmissionLayout::JwtPresentation => {
+          let nested: InputDescriptorMapping = InputDescriptorMapping {
+            id: mapping.id.clone(),
+            format: mapping.format.clone(),
+            path: format!("$.vp.verifiableCredential{}", &mapping.path[1..]),
+            path_nested: None,
+          };
+          mapping.format = ClaimFormat::JwtVp;
+          mapping.path = "$".to_owned();
+          mapping.path_nested = Some(Box::new(nested));
+        }
+        SubmissionLayout::VpToken if credentials.len() == 1 => mapping.path = "$".to_owned(),
+        SubmissionLayout::VpToken => {}
+      }
+    }
+
+    Ok(Submission {
+      presentation_submission: PresentationSubmission {
+        id: submission_id.into(),
+        definition_id: self.id.clone(),
+        descriptor_map,
+      },
+      credentials,
+    })
+  }
+}
+
+/// Greedily selects input descriptors with a matching credential that satisfy `requirement`.
+fn select<'a>(
+  definition: &'a PresentationDefinition,
+  requirement: &SubmissionRequirement,
+  matches: &BTreeMap<String, usize>,
+) -> Option<BTreeSet<&'a str>> {
+  // Every input of the requirement, and the descriptors to submit for it if it can be satisfied.
+  let inputs: Vec<Option<BTreeSet<&'a str>>> = if let Some(group) = &requirement.from {
+    definition
+      .group(group)
+      .map(|id| matches.contains_key(id).then(|| BTreeSet::from([id])))
+      .collect()
+  } else {
+    requirement
+      .from_nested
+      .as_deref()
+      .unwrap_or_default()
+      .iter()
+      .map(|nested| select(definition, nested, matches))
+      .collect()
+  };
+
+  match requirement.rule {
+    SubmissionRequirementRule::All => inputs.into_iter().try_fold(BTreeSet::new(), |mut selected, input| {
+      selected.extend(input?);
+      Some(selected)
+    }),
+    SubmissionRequirementRule::Pick => {
+      let available: Vec<BTreeSet<&'a str>> = inputs.into_iter().flatten().collect();
+      let wanted: usize = requirement
+        .count
+        .or(requirement.max)
+        .unwrap_or(available.len())
+        .min(available.len());
+      requirement
+        .accepts_count(wanted)
+        .then(|| available.into_iter().take(wanted).flatten().collect())
+    }
+  }
+}
@@ -0,0 +1,33 @@
+// Copyright 2020-2024 IOTA Stiftung, Fondazione Links
+// SPDX-License-Identifier: Apache-2.0
+
+//! Requesting and submitting credentials with
+//! [DIF Presentation Exchange 2.0](https://identity.foundation/presentation-exchange/spec/v2.0.0/).
+//!
+//! A verifier describes the credentials it requires in a [`PresentationDefinition`]: every [`InputDescriptor`]
+//! selects claims of a credential with JSONPath expressions ([`JsonPath`]) and constrains their values with JSON
+//! Schema filters. The holder wraps the credentials of its wallet in [`FormattedCredential`]s, finds those
+//! satisfying the definition with [`PresentationDefinition::match_credentials`] and describes the selected ones
+//! in a [`PresentationSubmission`] with [`PresentationDefinition::create_submission`]. The verifier checks the
+//! received presentation with [`PresentationDefinition::evaluate`].
+//!
+//! Credentials secured as JWTs, SD-JWTs (feature `sd-jwt`) and JPTs (feature `jpt-bbs-plus`) are supported.
+
+mod claim_format;
+mod definition;
+mod error;
+mod evaluator;
+mod json_path;
+mod json_schema;
+mod matcher;
+mod submission;
+#[cfg(test)]
+mod tests;
+
+pub use self::claim_format::*;
+pub use self::definition::*;
+pub use self::error::*;
+pub use self::evaluator::*;
+pub use self::json_path::*;
+pub use self::matcher::*;
+pub use self::submission::*;
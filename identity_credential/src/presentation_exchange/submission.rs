@@ -0,0 +1,33 @@
+// Copyright 2020-2024 IOTA Stiftung, Fondazione Links
+// SPDX-License-Identifier: Apache-2.0
+
+use serde::Deserialize;
+use serde::Serialize;
+
+use super::ClaimFormat;
+
+/// A [Presentation Submission](https://identity.foundation/presentation-exchange/spec/v2.0.0/#presentation-submission)
+/// describing how the credentials of a presentation satisfy a [`PresentationDefinition`](super::PresentationDefinition).
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PresentationSubmission {
+  /// The unique identifier of the submission.
+  pub id: String,
+  /// The id of the presentation definition the submission responds to.
+  pub definition_id: String,
+  /// The mappings of input descriptors to credentials of the presentation.
+  pub descriptor_map: Vec<InputDescriptorMapping>,
+}
+
+/// Maps an input descriptor to the credential of a presentation that satisfies it.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct InputDescriptorMapping {
+  /// The id of the input descriptor.
+  pub id: String,
+  /// The claim format of the element selected by `path`.
+  pub format: ClaimFormat,
+  /// A JSONPath expression selecting the element, relative to the presentation or to the enclosing mapping.
+  pub path: String,
+  /// A mapping selecting a credential embedded in the element selected by `path`.
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub path_nested: Option<Box<InputDescriptorMapping>>,
+}
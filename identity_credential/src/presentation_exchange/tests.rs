@@ -0,0 +1,306 @@
+// Copyright 2020-2024 IOTA Stiftung, Fondazione Links
+// SPDX-License-Identifier: Apache-2.0
+
+use identity_verification::jwu::encode_b64;
+use serde_json::json;
+use serde_json::Value;
+
+use super::*;
+use crate::credential::Jwt;
+
+// Signatures are not verified by Presentation Exchange, a placeholder suffices.
+fn jwt(claims: Value) -> Jwt {
+  let header: Value = json!({ "alg": "EdDSA", "typ": "JWT" });
+  Jwt::new(format!(
+    "{}.{}.{}",
+    encode_b64(header.to_string()),
+    encode_b64(claims.to_string()),
+    encode_b64("signature")
+  ))
+}
+
+fn credential_claims(type_: &str, subject: Value) -> Value {
+  json!({
+    "iss": "did:example:issuer",
+    "sub": "did:example:holder",
+    "vc": {
+      "@context": ["https://www.w3.org/2018/credentials/v1"],
+      "type": ["VerifiableCredential", type_],
+      "credentialSubject": subject,
+    }
+  })
+}
+
+fn wallet() -> Vec<FormattedCredential> {
+  let degree: Jwt = jwt(credential_claims(
+    "UniversityDegreeCredential",
+    json!({ "degree": { "type": "BachelorDegree", "name": "Bachelor of Science and Arts" } }),
+  ));
+  let license: Jwt = jwt(credential_claims(
+    "DrivingLicenseCredential",
+    json!({ "birthDate": "1990-05-17", "licenseClass": "B" }),
+  ));
+  vec![
+    FormattedCredential::from_jwt(&license).unwrap(),
+    FormattedCredential::from_jwt(&degree).unwrap(),
+  ]
+}
+
+fn type_descriptor(id: &str, type_: &str) -> InputDescriptor {
+  let field: Field = Field::new(vec!["$.vc.type".to_owned(), "$.type".to_owned()])
+    .with_filter(json!({ "type": "array", "contains": { "const": type_ } }));
+  InputDescriptor::new(id, Constraints::new(vec![field]))
+}
+
+fn definition() -> PresentationDefinition {
+  let mut license: InputDescriptor = type_descriptor("license", "DrivingLicenseCredential");
+  license.constraints.fields.push(
+    Field::new(vec!["$.vc.credentialSubject.birthDate".to_owned()])
+      .with_filter(json!({ "type": "string", "format": "date", "formatMaximum": "2006-01-01" })),
+  );
+  PresentationDefinition::new(
+    "definition",
+    vec![type_descriptor("degree", "UniversityDegreeCredential"), license],
+  )
+}
+
+#[test]
+fn jwt_presentation_round_trip() {
+  let wallet: Vec<FormattedCredential> = wallet();
+  let definition: PresentationDefinition = definition();
+
+  let matches: Vec<InputDescriptorMatch> = definition.match_credentials(&wallet).unwrap();
+  assert_eq!(matches[0].credentials, vec![1]);
+  assert_eq!(matches[1].credentials, vec![0]);
+
+  let submission: Submission = definition
+    .create_submission("submission", &wallet, SubmissionLayout::JwtPresentation)
+    .unwrap();
+  assert_eq!(submission.credentials, vec![1, 0]);
+  let mapping: &InputDescriptorMapping = &submission.presentation_submission.descriptor_map[1];
+  assert_eq!(mapping.format, ClaimFormat::JwtVp);
+  assert_eq!(
+    mapping.path_nested.as_ref().unwrap().path,
+    "$.vp.verifiableCredential[1]"
+  );
+
+  let presented: Vec<&Value> = submission
+    .credentials
+    .iter()
+    .map(|index| wallet[*index].credential())
+    .collect();
+  let vp: Jwt = jwt(json!({
+    "iss": "did:example:holder",
+    "vp": { "type": ["VerifiablePresentation"], "verifiableCredential": presented }
+  }));
+  let vp_token: Value = Value::String(vp.as_str().to_owned());
+
+  let evaluated: Vec<EvaluatedDescriptor> = definition
+    .evaluate(&submission.presentation_submission, &vp_token)
+    .unwrap();
+  assert_eq!(evaluated[0].input_descriptor_id, "degree");
+  assert_eq!(evaluated[0].credential, wallet[1]);
+
+  // The submission must refer to the credentials satisfying each descriptor.
+  let mut swapped: PresentationSubmission = submission.presentation_submission.clone();
+  swapped.descriptor_map.swap(0, 1);
+  swapped.descriptor_map[0].id = "degree".to_owned();
+  swapped.descriptor_map[1].id = "license".to_owned();
+  swapped.descriptor_map[0].path_nested.as_mut().unwrap().id = "degree".to_owned();
+  swapped.descriptor_map[1].path_nested.as_mut().unwrap().id = "license".to_owned();
+  assert!(matches!(
+    definition.evaluate(&swapped, &vp_token).unwrap_err(),
+    PresentationExchangeError::ConstraintsNotSatisfied { .. }
+  ));
+
+  let mut partial: PresentationSubmission = submission.presentation_submission.clone();
+  partial.descriptor_map.pop();
+  assert_eq!(
+    definition.evaluate(&partial, &vp_token).unwrap_err(),
+    PresentationExchangeError::MissingInputDescriptor("license".to_owned())
+  );
+
+  let mut other: PresentationSubmission = submission.presentation_submission;
+  other.definition_id = "other".to_owned();
+  assert!(matches!(
+    definition.evaluate(&other, &vp_token).unwrap_err(),
+    PresentationExchangeError::DefinitionMismatch(_)
+  ));
+}
+
+#[test]
+fn formats_and_submission_requirements() {
+  let wallet: Vec<FormattedCredential> = wallet();
+  let mut definition: PresentationDefinition = definition();
+  for descriptor in definition.input_descriptors.iter_mut() {
+    descriptor.group.push("A".to_owned());
+  }
+  definition.submission_requirements = Some(vec![SubmissionRequirement {
+    name: None,
+    purpose: None,
+    rule: SubmissionRequirementRule::Pick,
+    count: Some(1),
+    min: None,
+    max: None,
+    from: Some("A".to_owned()),
+    from_nested: None,
+  }]);
+
+  let submission: Submission = definition
+    .create_submission("submission", &wallet, SubmissionLayout::VpToken)
+    .unwrap();
+  assert_eq!(submission.credentials, vec![1]);
+  assert_eq!(submission.presentation_submission.descriptor_map[0].path, "$");
+  definition
+    .evaluate(&submission.presentation_submission, wallet[1].credential())
+    .unwrap();
+
+  // Only ES256 is accepted.
+  definition.format = Some(ClaimFormats::from([(
+    ClaimFormat::JwtVc,
+    ClaimFormatDesignation {
+      alg: Some(vec!["ES256".to_owned()]),
+      ..Default::default()
+    },
+  )]));
+  assert!(definition.match_credentials(&wallet).unwrap()[0].credentials.is_empty());
+  assert_eq!(
+    definition
+      .create_submission("submission", &wallet, SubmissionLayout::VpToken)
+      .unwrap_err(),
+    PresentationExchangeError::SubmissionRequirementsNotSatisfied
+  );
+}
+
+#[test]
+fn definition_serialization() {
+  let definition: PresentationDefinition = serde_json::from_value(json!({
+    "id": "definition",
+    "format": { "vc+sd-jwt": { "alg": ["EdDSA"] }, "jwt_vc_json": { "alg": ["ES256"] } },
+    "input_descriptors": [{
+      "id": "degree",
+      "constraints": {
+        "limit_disclosure": "required",
+        "fields": [{ "path": ["$.vc.type"], "filter": { "type": "array" }, "optional": true }]
+      }
+    }]
+  }))
+  .unwrap();
+  let formats: &ClaimFormats = definition.format.as_ref().unwrap();
+  assert!(formats.contains_key(&ClaimFormat::SdJwt));
+  assert!(formats.contains_key(&ClaimFormat::JwtVcJson));
+  assert_eq!(
+    definition.input_descriptors[0].constraints.limit_disclosure,
+    Some(LimitDisclosure::Required)
+  );
+  assert_eq!(
+    serde_json::to_value(&definition).unwrap()["input_descriptors"][0]["constraints"]["fields"][0]["optional"],
+    json!(true)
+  );
+}
+
+#[cfg(feature = "sd-jwt")]
+#[test]
+fn sd_jwt_limited_disclosure() {
+  use sd_jwt_payload::SdJwt;
+  use sd_jwt_payload::SdObjectEncoder;
+
+  let mut encoder: SdObjectEncoder = SdObjectEncoder::try_from_serializable(credential_claims(
+    "UniversityDegreeCredential",
+    json!({ "degree": { "type": "BachelorDegree" }, "gpa": "3.8" }),
+  ))
+  .unwrap();
+  let degree = encoder.conceal("/vc/credentialSubject/degree", None).unwrap();
+  let gpa = encoder.conceal("/vc/credentialSubject/gpa", None).unwrap();
+  encoder.add_sd_alg_property();
+  let token: Jwt = jwt(Value::Object(encoder.object().unwrap().clone()));
+  let issued: SdJwt = SdJwt::new(
+    token.as_str().to_owned(),
+    vec![degree.into_string(), gpa.to_string()],
+    None,
+  );
+  // The holder only discloses the degree.
+  let mut presented: SdJwt = issued.clone();
+  presented.disclosures.pop();
+
+  let mut descriptor: InputDescriptor = type_descriptor("degree", "UniversityDegreeCredential");
+  descriptor.constraints.limit_disclosure = Some(LimitDisclosure::Required);
+  descriptor
+    .constraints
+    .fields
+    .push(Field::new(vec!["$.vc.credentialSubject.degree.type".to_owned()]));
+  let definition: PresentationDefinition = PresentationDefinition::new("definition", vec![descriptor]);
+
+  let mut wallet: Vec<FormattedCredential> = wallet();
+  wallet.push(FormattedCredential::from_sd_jwt(&issued).unwrap());
+  assert_eq!(wallet[2].claims()["vc"]["credentialSubject"]["gpa"], json!("3.8"));
+
+  // The JWT degree credential cannot limit disclosure.
+  let submission: Submission = definition
+    .create_submission("submission", &wallet, SubmissionLayout::VpToken)
+    .unwrap();
+  assert_eq!(submission.credentials, vec![2]);
+  assert_eq!(
+    submission.presentation_submission.descriptor_map[0].format,
+    ClaimFormat::SdJwt
+  );
+
+  let vp_token: Value = Value::String(presented.presentation());
+  let evaluated: Vec<EvaluatedDescriptor> = definition
+    .evaluate(&submission.presentation_submission, &vp_token)
+    .unwrap();
+  let claims: &Value = evaluated[0].credential.claims();
+  assert_eq!(
+    claims["vc"]["credentialSubject"]["degree"]["type"],
+    json!("BachelorDegree")
+  );
+  assert!(claims["vc"]["credentialSubject"].get("gpa").is_none());
+}
+
+#[cfg(feature = "jpt-bbs-plus")]
+#[test]
+fn jpt_credentials() {
+  use jsonprooftoken::encoding::SerializationType;
+  use jsonprooftoken::jpa::algs::ProofAlgorithm;
+  use jsonprooftoken::jpt::claims::JptClaims;
+  use jsonprooftoken::jwp::header::IssuerProtectedHeader;
+  use jsonprooftoken::jwp::issued::JwpIssuedBuilder;
+
+  use crate::credential::Jpt;
+
+  let mut claims: JptClaims = JptClaims::new();
+  claims.set_iss("did:example:issuer".to_owned());
+  claims.set_claim(
+    Some("vc"),
+    json!({
+      "type": ["VerifiableCredential", "UniversityDegreeCredential"],
+      "credentialSubject": { "degree": { "type": "BachelorDegree" } }
+    }),
+    true,
+  );
+  // Proofs are not verified by Presentation Exchange, a placeholder suffices.
+  let jpt: Jpt = Jpt::new(
+    JwpIssuedBuilder::new(IssuerProtectedHeader::new(ProofAlgorithm::BLS12381_SHA256), claims)
+      .build_with_proof(vec![0; 32])
+      .unwrap()
+      .encode(SerializationType::COMPACT)
+      .unwrap(),
+  );
+  let credential: FormattedCredential = FormattedCredential::from_jpt(&jpt).unwrap();
+  assert_eq!(credential.alg(), Some("BBS-BLS12381-SHA256"));
+
+  let mut definition: PresentationDefinition = definition();
+  definition.input_descriptors.pop();
+  definition.input_descriptors[0].constraints.limit_disclosure = Some(LimitDisclosure::Required);
+  let wallet: Vec<FormattedCredential> = vec![credential];
+  let submission: Submission = definition
+    .create_submission("submission", &wallet, SubmissionLayout::VpToken)
+    .unwrap();
+  assert_eq!(
+    submission.presentation_submission.descriptor_map[0].format,
+    ClaimFormat::Jpt
+  );
+  definition
+    .evaluate(&submission.presentation_submission, wallet[0].credential())
+    .unwrap();
+}
@@ -4,5 +4,7 @@
 //! Implementation of a new Revocation mechanism for ZK Verifiable Credentials.
 
 mod revocation_timeframe_status;
+mod timeframe_attestation;
 
 pub use revocation_timeframe_status::*;
+pub use timeframe_attestation::*;
@@ -0,0 +1,101 @@
+// Copyright 2020-2024 IOTA Stiftung, Fondazione Links
+// SPDX-License-Identifier: Apache-2.0
+
+use identity_core::common::Duration;
+use identity_core::common::Timestamp;
+use identity_core::common::Url;
+use serde::Deserialize;
+use serde::Serialize;
+
+use crate::error::Error;
+use crate::error::Result;
+
+/// Claims of a validity timeframe attestation, a JWT with which the issuer of a JWT credential using
+/// [`RevocationTimeframeStatus`](super::RevocationTimeframeStatus) refreshes the validity timeframe of the credential
+/// without reissuing it.
+///
+/// The attestation is bound to the `id` of the credential and supersedes the timeframe set in its `credentialStatus`.
+#[derive(Debug, Clone, Serialize, Deserialize, Hash, Eq, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct TimeframeAttestationClaims {
+  iss: Url,
+  sub: Url,
+  start_validity_timeframe: Timestamp,
+  end_validity_timeframe: Timestamp,
+}
+
+impl TimeframeAttestationClaims {
+  /// The `typ` of the protected header of validity timeframe attestations.
+  pub const TYP: &'static str = "timeframe+jwt";
+
+  /// Creates the claims of an attestation by `issuer` that the credential identified by `credential_id` is valid for
+  /// `duration` starting at `start_validity`, or now if unset.
+  pub fn new(issuer: Url, credential_id: Url, start_validity: Option<Timestamp>, duration: Duration) -> Result<Self> {
+    let start_validity_timeframe = start_validity.unwrap_or(Timestamp::now_utc());
+    let end_validity_timeframe = start_validity_timeframe
+      .checked_add(duration)
+      .ok_or(Error::InvalidStatus(
+        "With that granularity, endValidityTimeFrame will turn out not to be in the valid range for RFC 3339"
+          .to_owned(),
+      ))?;
+
+    Ok(Self {
+      iss: issuer,
+      sub: credential_id,
+      start_validity_timeframe,
+      end_validity_timeframe,
+    })
+  }
+
+  /// Returns the issuer of the attestation.
+  pub fn issuer(&self) -> &Url {
+    &self.iss
+  }
+
+  /// Returns the `id` of the credential the attestation is bound to.
+  pub fn credential_id(&self) -> &Url {
+    &self.sub
+  }
+
+  /// Get startValidityTimeframe value.
+  pub fn start_validity_timeframe(&self) -> Timestamp {
+    self.start_validity_timeframe
+  }
+
+  /// Get endValidityTimeframe value.
+  pub fn end_validity_timeframe(&self) -> Timestamp {
+    self.end_validity_timeframe
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn timeframe_attestation_claims_serialization_works() -> anyhow::Result<()> {
+    let claims = TimeframeAttestationClaims::new(
+      Url::parse("did:example:issuer")?,
+      Url::parse("https://example.edu/credentials/3732")?,
+      Some(Timestamp::parse("2024-03-19T13:57:50Z")?),
+      Duration::minutes(1),
+    )?;
+
+    let serialized = serde_json::to_value(&claims)?;
+    assert_eq!(
+      serialized,
+      serde_json::json!({
+        "iss": "did:example:issuer",
+        "sub": "https://example.edu/credentials/3732",
+        "startValidityTimeframe": "2024-03-19T13:57:50Z",
+        "endValidityTimeframe": "2024-03-19T13:58:50Z",
+      })
+    );
+    assert_eq!(
+      serde_json::from_value::<TimeframeAttestationClaims>(serialized)?,
+      claims
+    );
+
+    Ok(())
+  }
+}
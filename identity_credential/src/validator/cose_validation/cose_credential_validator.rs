@@ -0,0 +1,272 @@
+// Copyright 2020-2024 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+use identity_core::convert::FromJson;
+use identity_did::CoreDID;
+use identity_did::DIDUrl;
+use identity_document::document::CoreDocument;
+use identity_document::verifiable::JwsVerificationOptions;
+use identity_verification::jose::cose::CoseSign1;
+use identity_verification::jose::error::Error as JoseError;
+use identity_verification::jwk::Jwk;
+use identity_verification::jws::JwsVerifier;
+
+use super::DecodedCoseCredential;
+use crate::credential::Cose;
+use crate::credential::Credential;
+use crate::credential::VC_COSE_MEDIA_TYPE;
+use crate::validator::CompoundCredentialValidationError;
+use crate::validator::FailFast;
+use crate::validator::JwtCredentialValidationOptions;
+use crate::validator::JwtCredentialValidator;
+use crate::validator::JwtCredentialValidatorUtils;
+use crate::validator::JwtValidationError;
+use crate::validator::SignerContext;
+
+/// A type for decoding and validating [`Credential`]s secured as COSE_Sign1 messages (`application/vc+cose`).
+#[non_exhaustive]
+pub struct CoseCredentialValidator<V: JwsVerifier>(V);
+
+impl<V: JwsVerifier> CoseCredentialValidator<V> {
+  /// Create a new [`CoseCredentialValidator`] that delegates cryptographic signature verification to the given
+  /// `signature_verifier`.
+  pub fn with_signature_verifier(signature_verifier: V) -> Self {
+    Self(signature_verifier)
+  }
+
+  /// Decodes and validates a [`Credential`] secured as a COSE_Sign1 message. A [`DecodedCoseCredential`] is returned
+  /// upon success.
+  ///
+  /// The following properties are validated according to `options`:
+  /// - the issuer's signature on the COSE_Sign1 message,
+  /// - the expiration date,
+  /// - the issuance date,
+  /// - the semantic structure.
+  ///
+  /// # Warning
+  /// The same caveats as for [`JwtCredentialValidator::validate`] apply.
+  ///
+  /// # Errors
+  /// An error is returned whenever a validated condition is not satisfied.
+  pub fn validate<DOC, T>(
+    &self,
+    credential_cose: &Cose,
+    issuer: &DOC,
+    options: &JwtCredentialValidationOptions,
+    fail_fast: FailFast,
+  ) -> Result<DecodedCoseCredential<T>, CompoundCredentialValidationError>
+  where
+    T: ToOwned<Owned = T> + serde::Serialize + serde::de::DeserializeOwned,
+    DOC: AsRef<CoreDocument>,
+  {
+    self.validate_message(credential_cose, None, issuer, options, fail_fast)
+  }
+
+  /// Decodes and validates a [`Credential`] secured as a COSE_Sign1 message whose payload is detached, using the
+  /// given `detached_payload`. A [`DecodedCoseCredential`] is returned upon success.
+  ///
+  /// See [`CoseCredentialValidator::validate`] for the validated properties.
+  ///
+  /// # Errors
+  /// An error is returned whenever a validated condition is not satisfied, including when the payload of the message
+  /// is not detached.
+  pub fn validate_detached<DOC, T>(
+    &self,
+    credential_cose: &Cose,
+    detached_payload: &[u8],
+    issuer: &DOC,
+    options: &JwtCredentialValidationOptions,
+    fail_fast: FailFast,
+  ) -> Result<DecodedCoseCredential<T>, CompoundCredentialValidationError>
+  where
+    T: ToOwned<Owned = T> + serde::Serialize + serde::de::DeserializeOwned,
+    DOC: AsRef<CoreDocument>,
+  {
+    self.validate_message(credential_cose, Some(detached_payload), issuer, options, fail_fast)
+  }
+
+  fn validate_message<DOC, T>(
+    &self,
+    credential_cose: &Cose,
+    detached_payload: Option<&[u8]>,
+    issuer: &DOC,
+    options: &JwtCredentialValidationOptions,
+    fail_fast: FailFast,
+  ) -> Result<DecodedCoseCredential<T>, CompoundCredentialValidationError>
+  where
+    T: ToOwned<Owned = T> + serde::Serialize + serde::de::DeserializeOwned,
+    DOC: AsRef<CoreDocument>,
+  {
+    let issuers: &[CoreDocument] = std::slice::from_ref(issuer.as_ref());
+    let credential_token: DecodedCoseCredential<T> = self
+      .verify_message(
+        credential_cose,
+        detached_payload,
+        issuers,
+        &options.verification_options,
+      )
+      .map_err(|err| CompoundCredentialValidationError {
+        validation_errors: [err].into(),
+      })?;
+
+    JwtCredentialValidator::<V>::validate_credential(&credential_token.credential, issuers, options, fail_fast)
+      .map(|_| credential_token)
+  }
+
+  /// Decode and verify the COSE_Sign1 signature of a [`Credential`] using the DID Document of a trusted issuer.
+  ///
+  /// A [`DecodedCoseCredential`] is returned upon success.
+  ///
+  /// # Errors
+  /// This method immediately returns an error if the `kid` of the message cannot be parsed to a DID Url belonging to
+  /// one of the trusted issuers. Otherwise an attempt to verify the credential's signature will be made and an error
+  /// is returned upon failure.
+  pub fn verify_signature<DOC, T>(
+    &self,
+    credential: &Cose,
+    trusted_issuers: &[DOC],
+    options: &JwsVerificationOptions,
+  ) -> Result<DecodedCoseCredential<T>, JwtValidationError>
+  where
+    T: ToOwned<Owned = T> + serde::Serialize + serde::de::DeserializeOwned,
+    DOC: AsRef<CoreDocument>,
+  {
+    self.verify_message(credential, None, trusted_issuers, options)
+  }
+
+  /// Decode and verify the COSE_Sign1 signature of a [`Credential`] whose payload is detached, using the given
+  /// `detached_payload` and the DID Document of a trusted issuer.
+  ///
+  /// See [`CoseCredentialValidator::verify_signature`].
+  ///
+  /// # Errors
+  /// Besides the errors of [`CoseCredentialValidator::verify_signature`], an error is returned if the payload of the
+  /// message is not detached.
+  pub fn verify_signature_detached<DOC, T>(
+    &self,
+    credential: &Cose,
+    detached_payload: &[u8],
+    trusted_issuers: &[DOC],
+    options: &JwsVerificationOptions,
+  ) -> Result<DecodedCoseCredential<T>, JwtValidationError>
+  where
+    T: ToOwned<Owned = T> + serde::Serialize + serde::de::DeserializeOwned,
+    DOC: AsRef<CoreDocument>,
+  {
+    self.verify_message(credential, Some(detached_payload), trusted_issuers, options)
+  }
+
+  fn verify_message<DOC, T>(
+    &self,
+    credential: &Cose,
+    detached_payload: Option<&[u8]>,
+    trusted_issuers: &[DOC],
+    options: &JwsVerificationOptions,
+  ) -> Result<DecodedCoseCredential<T>, JwtValidationError>
+  where
+    T: ToOwned<Owned = T> + serde::Serialize + serde::de::DeserializeOwned,
+    DOC: AsRef<CoreDocument>,
+  {
+    let message: CoseSign1 = Self::decode(credential, detached_payload, VC_COSE_MEDIA_TYPE)?;
+    let (public_key, method_id) = Self::parse_jwk(&message, trusted_issuers, options, SignerContext::Issuer)?;
+
+    let decoded = message
+      .verify(&self.0, public_key)
+      .map_err(|err| JwtValidationError::Signature {
+        source: err,
+        signer_ctx: SignerContext::Issuer,
+      })?;
+
+    let credential: Credential<T> = Credential::from_json_slice(&decoded.payload)
+      .map_err(|err| JwtValidationError::CredentialStructure(crate::Error::CosePayloadError(err.into())))?;
+
+    // Check that the DID component of the parsed `kid` does indeed correspond to the issuer in the credential.
+    let issuer_id: CoreDID = JwtCredentialValidatorUtils::extract_issuer(&credential)?;
+    if &issuer_id != method_id.did() {
+      return Err(JwtValidationError::IdentifierMismatch {
+        signer_ctx: SignerContext::Issuer,
+      });
+    };
+
+    Ok(DecodedCoseCredential {
+      credential,
+      header: Box::new(decoded.protected),
+    })
+  }
+
+  /// Decode a COSE_Sign1 message whose `typ`, if present, must equal `media_type`, using `detached_payload` as its
+  /// payload if given.
+  pub(crate) fn decode(
+    cose: &Cose,
+    detached_payload: Option<&[u8]>,
+    media_type: &str,
+  ) -> Result<CoseSign1, JwtValidationError> {
+    let message: CoseSign1 = match detached_payload {
+      Some(detached_payload) => CoseSign1::from_slice_detached(cose.as_bytes(), detached_payload),
+      None => CoseSign1::from_slice(cose.as_bytes()),
+    }
+    .map_err(JwtValidationError::CoseDecodingError)?;
+    if matches!(message.protected_header().typ(), Some(typ) if typ != media_type) {
+      return Err(JwtValidationError::CoseDecodingError(JoseError::InvalidParam(
+        "invalid typ value",
+      )));
+    }
+    Ok(message)
+  }
+
+  pub(crate) fn parse_jwk<'i, DOC>(
+    message: &CoseSign1,
+    trusted_signers: &'i [DOC],
+    options: &JwsVerificationOptions,
+    signer_ctx: SignerContext,
+  ) -> Result<(&'i Jwk, DIDUrl), JwtValidationError>
+  where
+    DOC: AsRef<CoreDocument>,
+  {
+    // COSE_Sign1 messages do not carry a nonce, so requiring one can never be satisfied.
+    if options.nonce.is_some() {
+      return Err(JwtValidationError::CoseDecodingError(JoseError::InvalidParam(
+        "invalid nonce value",
+      )));
+    }
+
+    // If no method_url is set, parse the `kid` to a DID Url which should be the identifier
+    // of a verification method in a trusted signer's DID document.
+    let method_id: DIDUrl = match &options.method_id {
+      Some(method_id) => method_id.clone(),
+      None => {
+        let kid: &str = message.kid().and_then(|kid| std::str::from_utf8(kid).ok()).ok_or(
+          JwtValidationError::MethodDataLookupError {
+            source: None,
+            message: "could not extract kid from header",
+            signer_ctx,
+          },
+        )?;
+
+        DIDUrl::parse(kid).map_err(|err| JwtValidationError::MethodDataLookupError {
+          source: Some(err.into()),
+          message: "could not parse kid as a DID Url",
+          signer_ctx,
+        })?
+      }
+    };
+
+    // locate the corresponding signer
+    let signer: &CoreDocument = trusted_signers
+      .iter()
+      .map(AsRef::as_ref)
+      .find(|signer_doc| <CoreDocument>::id(signer_doc) == method_id.did())
+      .ok_or(JwtValidationError::DocumentMismatch(signer_ctx))?;
+
+    // Obtain the public key from the signer's DID document
+    signer
+      .resolve_method(&method_id, options.method_scope)
+      .and_then(|method| method.data().public_key_jwk())
+      .ok_or_else(|| JwtValidationError::MethodDataLookupError {
+        source: None,
+        message: "could not extract JWK from a method identified by kid",
+        signer_ctx,
+      })
+      .map(move |jwk| (jwk, method_id))
+  }
+}
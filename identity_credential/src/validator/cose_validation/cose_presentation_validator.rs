@@ -0,0 +1,146 @@
+// Copyright 2020-2024 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+use std::str::FromStr;
+
+use identity_core::convert::FromJson;
+use identity_did::CoreDID;
+use identity_document::document::CoreDocument;
+use identity_document::verifiable::JwsVerificationOptions;
+use identity_verification::jose::cose::CoseSign1;
+use identity_verification::jws::JwsVerifier;
+
+use super::CoseCredentialValidator;
+use super::DecodedCosePresentation;
+use crate::credential::Cose;
+use crate::credential::VP_COSE_MEDIA_TYPE;
+use crate::presentation::Presentation;
+use crate::validator::CompoundJwtPresentationValidationError;
+use crate::validator::JwtValidationError;
+use crate::validator::SignerContext;
+
+/// Struct for validating [`Presentation`]s secured as COSE_Sign1 messages (`application/vp+cose`).
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub struct CosePresentationValidator<V: JwsVerifier>(V);
+
+impl<V> CosePresentationValidator<V>
+where
+  V: JwsVerifier,
+{
+  /// Creates a new [`CosePresentationValidator`] using a specific [`JwsVerifier`].
+  pub fn with_signature_verifier(signature_verifier: V) -> Self {
+    Self(signature_verifier)
+  }
+
+  /// Validates a [`Presentation`] secured as a COSE_Sign1 message.
+  ///
+  /// The following properties are validated:
+  /// - the COSE_Sign1 message can be decoded into a semantically valid presentation.
+  /// - the holder's signature, using the method identified by the `kid` or by `options`.
+  ///
+  /// # Warning
+  ///
+  /// This method does NOT validate the constituent credentials, which must be validated separately, e.g. with
+  /// [`CoseCredentialValidator`] or [`JwtCredentialValidator`](crate::validator::JwtCredentialValidator).
+  ///
+  /// ## The state of the supplied DID Documents.
+  ///
+  /// The caller must ensure that the DID Document in `holder` is up-to-date.
+  ///
+  /// # Errors
+  ///
+  /// An error is returned whenever a validated condition is not satisfied or when decoding fails.
+  pub fn validate<HDOC, CRED, T>(
+    &self,
+    presentation: &Cose,
+    holder: &HDOC,
+    options: &JwsVerificationOptions,
+  ) -> Result<DecodedCosePresentation<CRED, T>, CompoundJwtPresentationValidationError>
+  where
+    HDOC: AsRef<CoreDocument> + ?Sized,
+    T: ToOwned<Owned = T> + serde::Serialize + serde::de::DeserializeOwned,
+    CRED: ToOwned<Owned = CRED> + serde::Serialize + serde::de::DeserializeOwned + Clone,
+  {
+    self.validate_message(presentation, None, holder, options)
+  }
+
+  /// Validates a [`Presentation`] secured as a COSE_Sign1 message whose payload is detached, using the given
+  /// `detached_payload`.
+  ///
+  /// See [`CosePresentationValidator::validate`] for the validated properties and caveats.
+  ///
+  /// # Errors
+  ///
+  /// An error is returned whenever a validated condition is not satisfied, when decoding fails or when the payload of
+  /// the message is not detached.
+  pub fn validate_detached<HDOC, CRED, T>(
+    &self,
+    presentation: &Cose,
+    detached_payload: &[u8],
+    holder: &HDOC,
+    options: &JwsVerificationOptions,
+  ) -> Result<DecodedCosePresentation<CRED, T>, CompoundJwtPresentationValidationError>
+  where
+    HDOC: AsRef<CoreDocument> + ?Sized,
+    T: ToOwned<Owned = T> + serde::Serialize + serde::de::DeserializeOwned,
+    CRED: ToOwned<Owned = CRED> + serde::Serialize + serde::de::DeserializeOwned + Clone,
+  {
+    self.validate_message(presentation, Some(detached_payload), holder, options)
+  }
+
+  fn validate_message<HDOC, CRED, T>(
+    &self,
+    presentation: &Cose,
+    detached_payload: Option<&[u8]>,
+    holder: &HDOC,
+    options: &JwsVerificationOptions,
+  ) -> Result<DecodedCosePresentation<CRED, T>, CompoundJwtPresentationValidationError>
+  where
+    HDOC: AsRef<CoreDocument> + ?Sized,
+    T: ToOwned<Owned = T> + serde::Serialize + serde::de::DeserializeOwned,
+    CRED: ToOwned<Owned = CRED> + serde::Serialize + serde::de::DeserializeOwned + Clone,
+  {
+    let message: CoseSign1 = CoseCredentialValidator::<V>::decode(presentation, detached_payload, VP_COSE_MEDIA_TYPE)
+      .map_err(CompoundJwtPresentationValidationError::one_presentation_error)?;
+    let (public_key, _) = CoseCredentialValidator::<V>::parse_jwk(
+      &message,
+      std::slice::from_ref(holder.as_ref()),
+      options,
+      SignerContext::Holder,
+    )
+    .map_err(CompoundJwtPresentationValidationError::one_presentation_error)?;
+
+    let decoded = message.verify(&self.0, public_key).map_err(|err| {
+      CompoundJwtPresentationValidationError::one_presentation_error(JwtValidationError::Signature {
+        source: err,
+        signer_ctx: SignerContext::Holder,
+      })
+    })?;
+
+    let presentation: Presentation<CRED, T> = Presentation::from_json_slice(&decoded.payload).map_err(|err| {
+      CompoundJwtPresentationValidationError::one_presentation_error(JwtValidationError::PresentationStructure(
+        crate::Error::CosePayloadError(err.into()),
+      ))
+    })?;
+
+    // Verify that holder document matches holder in presentation.
+    let holder_did: CoreDID = CoreDID::from_str(presentation.holder.as_str()).map_err(|err| {
+      CompoundJwtPresentationValidationError::one_presentation_error(JwtValidationError::SignerUrl {
+        signer_ctx: SignerContext::Holder,
+        source: err.into(),
+      })
+    })?;
+
+    if &holder_did != <CoreDocument>::id(holder.as_ref()) {
+      return Err(CompoundJwtPresentationValidationError::one_presentation_error(
+        JwtValidationError::DocumentMismatch(SignerContext::Holder),
+      ));
+    }
+
+    Ok(DecodedCosePresentation {
+      presentation,
+      header: Box::new(decoded.protected),
+    })
+  }
+}
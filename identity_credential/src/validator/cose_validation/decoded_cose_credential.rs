@@ -0,0 +1,20 @@
+// Copyright 2020-2024 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+use identity_core::common::Object;
+use identity_verification::jose::cose::CoseHeader;
+
+use crate::credential::Credential;
+
+/// Decoded [`Credential`] from a cryptographically verified COSE_Sign1 message.
+///
+/// Note that having an instance of this type only means the COSE_Sign1 message it was constructed from was verified.
+/// It does not imply anything about a potentially present proof property on the credential itself.
+#[non_exhaustive]
+#[derive(Debug, Clone)]
+pub struct DecodedCoseCredential<T = Object> {
+  /// The decoded credential parsed to the [Verifiable Credentials Data model](https://www.w3.org/TR/vc-data-model/).
+  pub credential: Credential<T>,
+  /// The protected header parsed from the COSE_Sign1 message.
+  pub header: Box<CoseHeader>,
+}
@@ -0,0 +1,13 @@
+// Copyright 2020-2024 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+//! Contains functionality for validating credentials and presentations secured as COSE_Sign1 messages.
+mod cose_credential_validator;
+mod cose_presentation_validator;
+mod decoded_cose_credential;
+mod decoded_cose_presentation;
+
+pub use cose_credential_validator::*;
+pub use cose_presentation_validator::*;
+pub use decoded_cose_credential::*;
+pub use decoded_cose_presentation::*;
@@ -0,0 +1,337 @@
+// Copyright 2020-2024 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+use std::str::FromStr;
+
+use identity_core::common::Context;
+use identity_core::common::OneOrMany;
+use identity_core::common::Timestamp;
+use identity_did::CoreDID;
+use identity_did::DIDUrl;
+use identity_document::document::CoreDocument;
+use identity_verification::jose::error::Error as JoseError;
+use identity_verification::jose::jws::JwsAlgorithm;
+use identity_verification::jwk::Jwk;
+use identity_verification::jws::JwsVerifier;
+use identity_verification::jws::VerificationInput;
+
+use super::DataIntegrityVerificationOptions;
+use crate::credential::Credential;
+use crate::credential::Proof;
+use crate::data_integrity::ContextCache;
+use crate::data_integrity::DataIntegrityProof;
+use crate::data_integrity::ProofPurpose;
+use crate::presentation::Presentation;
+use crate::validator::CompoundCredentialValidationError;
+use crate::validator::CompoundJwtPresentationValidationError;
+use crate::validator::FailFast;
+use crate::validator::JwtCredentialValidationOptions;
+use crate::validator::JwtCredentialValidator;
+use crate::validator::JwtCredentialValidatorUtils;
+use crate::validator::JwtValidationError;
+use crate::validator::SignerContext;
+
+/// A type for validating [`Credential`]s and [`Presentation`]s secured with an embedded
+/// [`DataIntegrityProof`].
+///
+/// JSON-LD contexts required by RDFC based cryptosuites are exclusively loaded from the [`ContextCache`] the
+/// validator is created with.
+#[non_exhaustive]
+pub struct DataIntegrityValidator<V: JwsVerifier> {
+  signature_verifier: V,
+  context_cache: ContextCache,
+}
+
+impl<V: JwsVerifier> DataIntegrityValidator<V> {
+  /// Create a new [`DataIntegrityValidator`] that delegates cryptographic signature verification to the given
+  /// `signature_verifier` and resolves JSON-LD contexts from `context_cache`.
+  pub fn with_signature_verifier(signature_verifier: V, context_cache: ContextCache) -> Self {
+    Self {
+      signature_verifier,
+      context_cache,
+    }
+  }
+
+  /// Returns the [`ContextCache`] used by this validator.
+  pub fn context_cache(&self) -> &ContextCache {
+    &self.context_cache
+  }
+
+  /// Validates a [`Credential`] secured with an embedded [`DataIntegrityProof`].
+  ///
+  /// The following properties are validated according to `options`:
+  /// - the issuer's proof, whose `nonce` is matched against the `challenge` of the proof,
+  /// - the expiration date,
+  /// - the issuance date,
+  /// - the semantic structure.
+  ///
+  /// # Warning
+  /// The same caveats as for [`JwtCredentialValidator::validate`] apply.
+  ///
+  /// # Errors
+  /// An error is returned whenever a validated condition is not satisfied.
+  pub fn validate<DOC, T>(
+    &self,
+    credential: &Credential<T>,
+    issuer: &DOC,
+    options: &JwtCredentialValidationOptions,
+    fail_fast: FailFast,
+  ) -> Result<DataIntegrityProof, CompoundCredentialValidationError>
+  where
+    T: ToOwned<Owned = T> + serde::Serialize + serde::de::DeserializeOwned,
+    DOC: AsRef<CoreDocument>,
+  {
+    let issuers: &[CoreDocument] = std::slice::from_ref(issuer.as_ref());
+    let proof: DataIntegrityProof = self
+      .verify_credential_proof(credential, issuers, &(&options.verification_options).into())
+      .map_err(|err| CompoundCredentialValidationError {
+        validation_errors: [err].into(),
+      })?;
+
+    JwtCredentialValidator::<V>::validate_credential(credential, issuers, options, fail_fast).map(|_| proof)
+  }
+
+  /// Verify the embedded [`DataIntegrityProof`] of a [`Credential`] using the DID Document of a trusted issuer.
+  ///
+  /// The verified proof is returned upon success.
+  ///
+  /// # Errors
+  /// This method immediately returns an error if the credential has no proof, if the proof does not meet the
+  /// expectations of `options` or if its `verificationMethod` cannot be found in the trusted issuers' documents.
+  /// Otherwise an attempt to verify the proof will be made and an error is returned upon failure.
+  pub fn verify_credential_proof<DOC, T>(
+    &self,
+    credential: &Credential<T>,
+    trusted_issuers: &[DOC],
+    options: &DataIntegrityVerificationOptions,
+  ) -> Result<DataIntegrityProof, JwtValidationError>
+  where
+    T: ToOwned<Owned = T> + serde::Serialize + serde::de::DeserializeOwned,
+    DOC: AsRef<CoreDocument>,
+  {
+    let (proof, method_id) = self.verify_proof(
+      credential,
+      &credential.context,
+      credential.proof.as_ref(),
+      trusted_issuers,
+      options,
+      ProofPurpose::AssertionMethod,
+      SignerContext::Issuer,
+    )?;
+
+    // Check that the DID component of the verification method does indeed correspond to the issuer in the credential.
+    let issuer_id: CoreDID = JwtCredentialValidatorUtils::extract_issuer(credential)?;
+    if &issuer_id != method_id.did() {
+      return Err(JwtValidationError::IdentifierMismatch {
+        signer_ctx: SignerContext::Issuer,
+      });
+    };
+
+    Ok(proof)
+  }
+
+  /// Validates a [`Presentation`] secured with an embedded [`DataIntegrityProof`] by the holder.
+  ///
+  /// The following properties are validated:
+  /// - the holder's proof, according to `options`,
+  /// - the holder of the presentation matches the `holder` document.
+  ///
+  /// # Warning
+  ///
+  /// This method does NOT validate the constituent credentials, which must be validated separately, e.g. with
+  /// [`DataIntegrityValidator::validate`]. Since the proof of a presentation is not bound to a verifier by default,
+  /// a `challenge` and `domain` should be required through `options`.
+  ///
+  /// # Errors
+  ///
+  /// An error is returned whenever a validated condition is not satisfied.
+  pub fn validate_presentation<HDOC, CRED, T>(
+    &self,
+    presentation: &Presentation<CRED, T>,
+    holder: &HDOC,
+    options: &DataIntegrityVerificationOptions,
+  ) -> Result<DataIntegrityProof, CompoundJwtPresentationValidationError>
+  where
+    HDOC: AsRef<CoreDocument> + ?Sized,
+    T: ToOwned<Owned = T> + serde::Serialize + serde::de::DeserializeOwned,
+    CRED: ToOwned<Owned = CRED> + serde::Serialize + serde::de::DeserializeOwned + Clone,
+  {
+    let (proof, method_id) = self
+      .verify_proof(
+        presentation,
+        &presentation.context,
+        presentation.proof.as_ref(),
+        std::slice::from_ref(holder.as_ref()),
+        options,
+        ProofPurpose::Authentication,
+        SignerContext::Holder,
+      )
+      .map_err(CompoundJwtPresentationValidationError::one_presentation_error)?;
+
+    // Verify that holder document matches holder in presentation.
+    let holder_did: CoreDID = CoreDID::from_str(presentation.holder.as_str()).map_err(|err| {
+      CompoundJwtPresentationValidationError::one_presentation_error(JwtValidationError::SignerUrl {
+        signer_ctx: SignerContext::Holder,
+        source: err.into(),
+      })
+    })?;
+
+    if &holder_did != method_id.did() {
+      return Err(CompoundJwtPresentationValidationError::one_presentation_error(
+        JwtValidationError::IdentifierMismatch {
+          signer_ctx: SignerContext::Holder,
+        },
+      ));
+    }
+
+    Ok(proof)
+  }
+
+  #[allow(clippy::too_many_arguments)]
+  fn verify_proof<S, DOC>(
+    &self,
+    document: &S,
+    document_context: &OneOrMany<Context>,
+    proof: Option<&Proof>,
+    trusted_signers: &[DOC],
+    options: &DataIntegrityVerificationOptions,
+    default_purpose: ProofPurpose,
+    signer_ctx: SignerContext,
+  ) -> Result<(DataIntegrityProof, DIDUrl), JwtValidationError>
+  where
+    S: serde::Serialize,
+    DOC: AsRef<CoreDocument>,
+  {
+    let proof: DataIntegrityProof = proof
+      .ok_or_else(|| proof_error("missing proof"))
+      .and_then(|proof| DataIntegrityProof::try_from(proof).map_err(JwtValidationError::DataIntegrityProofError))?;
+
+    if proof.proof_purpose != options.proof_purpose.unwrap_or(default_purpose) {
+      return Err(proof_error("unexpected proof purpose"));
+    }
+    if options.challenge.is_some() && proof.challenge != options.challenge {
+      return Err(proof_error("invalid challenge"));
+    }
+    if options.domain.is_some() && proof.domain != options.domain {
+      return Err(proof_error("invalid domain"));
+    }
+    let now: Timestamp = options.expires_after.unwrap_or_else(Timestamp::now_utc);
+    if matches!(proof.expires, Some(expires) if expires < now) {
+      return Err(proof_error("the proof has expired"));
+    }
+
+    // A proof context must be a prefix of the document context, so that it cannot change the meaning of the document.
+    if matches!(&proof.context, Some(context) if !document_context.as_slice().starts_with(context.as_slice())) {
+      return Err(proof_error("the proof context does not match the document context"));
+    }
+
+    let method_id: DIDUrl = match &options.method_id {
+      Some(method_id) => method_id.clone(),
+      None => DIDUrl::parse(&proof.verification_method).map_err(|err| JwtValidationError::MethodDataLookupError {
+        source: Some(err.into()),
+        message: "could not parse verificationMethod as a DID Url",
+        signer_ctx,
+      })?,
+    };
+    let public_key: &Jwk = Self::resolve_jwk(&method_id, trusted_signers, options, signer_ctx)?;
+    match proof.cryptosuite.alg() {
+      Some(alg) => self.verify_signature(document, &proof, alg, public_key, signer_ctx)?,
+      None => self.verify_bbs_2023_proof(document, &proof, public_key, options)?,
+    }
+
+    Ok((proof, method_id))
+  }
+
+  fn verify_signature<S: serde::Serialize>(
+    &self,
+    document: &S,
+    proof: &DataIntegrityProof,
+    alg: JwsAlgorithm,
+    public_key: &Jwk,
+    signer_ctx: SignerContext,
+  ) -> Result<(), JwtValidationError> {
+    if matches!(public_key.alg(), Some(key_alg) if key_alg != alg.name()) {
+      return Err(proof_error(
+        "the cryptosuite does not match the algorithm of the verification method",
+      ));
+    }
+
+    let input = VerificationInput {
+      alg,
+      signing_input: proof
+        .hash_data(document, &self.context_cache)
+        .map_err(JwtValidationError::DataIntegrityProofError)?
+        .into_boxed_slice(),
+      decoded_signature: proof
+        .decode_proof_value()
+        .map_err(JwtValidationError::DataIntegrityProofError)?
+        .into_boxed_slice(),
+    };
+    self
+      .signature_verifier
+      .verify(input, public_key)
+      .map_err(|err| JwtValidationError::Signature {
+        source: JoseError::SignatureVerificationError(err),
+        signer_ctx,
+      })
+  }
+
+  #[cfg(feature = "bbs-2023")]
+  fn verify_bbs_2023_proof<S: serde::Serialize>(
+    &self,
+    document: &S,
+    proof: &DataIntegrityProof,
+    public_key: &Jwk,
+    options: &DataIntegrityVerificationOptions,
+  ) -> Result<(), JwtValidationError> {
+    let presentation_header: Vec<u8> =
+      crate::data_integrity::bbs_2023::verify_derived_proof(document, proof, public_key, &self.context_cache)
+        .map_err(JwtValidationError::DataIntegrityProofError)?;
+    if matches!(&options.presentation_header, Some(expected) if expected != &presentation_header) {
+      return Err(proof_error("invalid presentation header"));
+    }
+    Ok(())
+  }
+
+  #[cfg(not(feature = "bbs-2023"))]
+  fn verify_bbs_2023_proof<S: serde::Serialize>(
+    &self,
+    _document: &S,
+    _proof: &DataIntegrityProof,
+    _public_key: &Jwk,
+    _options: &DataIntegrityVerificationOptions,
+  ) -> Result<(), JwtValidationError> {
+    Err(proof_error("the bbs-2023 cryptosuite requires the `bbs-2023` feature"))
+  }
+
+  fn resolve_jwk<'i, DOC>(
+    method_id: &DIDUrl,
+    trusted_signers: &'i [DOC],
+    options: &DataIntegrityVerificationOptions,
+    signer_ctx: SignerContext,
+  ) -> Result<&'i Jwk, JwtValidationError>
+  where
+    DOC: AsRef<CoreDocument>,
+  {
+    // locate the corresponding signer
+    let signer: &CoreDocument = trusted_signers
+      .iter()
+      .map(AsRef::as_ref)
+      .find(|signer_doc| <CoreDocument>::id(signer_doc) == method_id.did())
+      .ok_or(JwtValidationError::DocumentMismatch(signer_ctx))?;
+
+    // Obtain the public key from the signer's DID document
+    signer
+      .resolve_method(method_id, options.method_scope)
+      .and_then(|method| method.data().public_key_jwk())
+      .ok_or(JwtValidationError::MethodDataLookupError {
+        source: None,
+        message: "could not extract JWK from the verification method of the proof",
+        signer_ctx,
+      })
+  }
+}
+
+fn proof_error(message: &'static str) -> JwtValidationError {
+  JwtValidationError::DataIntegrityProofError(crate::Error::DataIntegrityError(message.into()))
+}
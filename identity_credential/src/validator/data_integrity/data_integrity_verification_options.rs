@@ -0,0 +1,108 @@
+// Copyright 2020-2024 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+use identity_core::common::Timestamp;
+use identity_did::DIDUrl;
+use identity_document::verifiable::JwsVerificationOptions;
+use identity_verification::MethodScope;
+
+use crate::data_integrity::ProofPurpose;
+
+/// Options for verifying a [`DataIntegrityProof`](crate::data_integrity::DataIntegrityProof).
+#[non_exhaustive]
+#[derive(Debug, Default, Clone, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DataIntegrityVerificationOptions {
+  /// The expected `challenge` of the proof.
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub challenge: Option<String>,
+
+  /// The expected `domain` of the proof.
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub domain: Option<String>,
+
+  /// The expected `proofPurpose` of the proof.
+  ///
+  /// Defaults to `assertionMethod` for credentials and `authentication` for presentations.
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub proof_purpose: Option<ProofPurpose>,
+
+  /// Verify the signing verification method relationship matches this.
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub method_scope: Option<MethodScope>,
+
+  /// The DID URL of the method whose JWK should be used to verify the proof, overriding its `verificationMethod`.
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub method_id: Option<DIDUrl>,
+
+  /// The point in time at which the proof must not have expired.
+  ///
+  /// Defaults to the current time.
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub expires_after: Option<Timestamp>,
+
+  /// The expected presentation header of `bbs-2023` derived proofs, e.g. a challenge of the verifier.
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub presentation_header: Option<Vec<u8>>,
+}
+
+impl DataIntegrityVerificationOptions {
+  /// Creates a new [`DataIntegrityVerificationOptions`].
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  /// Set the expected value for the `challenge` of the proof.
+  pub fn challenge(mut self, value: impl Into<String>) -> Self {
+    self.challenge = Some(value.into());
+    self
+  }
+
+  /// Set the expected value for the `domain` of the proof.
+  pub fn domain(mut self, value: impl Into<String>) -> Self {
+    self.domain = Some(value.into());
+    self
+  }
+
+  /// Set the expected `proofPurpose` of the proof.
+  pub fn proof_purpose(mut self, value: ProofPurpose) -> Self {
+    self.proof_purpose = Some(value);
+    self
+  }
+
+  /// Set the scope of the verification methods that may be used to verify the proof.
+  pub fn method_scope(mut self, value: MethodScope) -> Self {
+    self.method_scope = Some(value);
+    self
+  }
+
+  /// Set the DID URL of the method whose JWK should be used to verify the proof.
+  pub fn method_id(mut self, value: DIDUrl) -> Self {
+    self.method_id = Some(value);
+    self
+  }
+
+  /// Set the point in time at which the proof must not have expired.
+  pub fn expires_after(mut self, value: Timestamp) -> Self {
+    self.expires_after = Some(value);
+    self
+  }
+
+  /// Set the expected presentation header of `bbs-2023` derived proofs.
+  pub fn presentation_header(mut self, value: impl Into<Vec<u8>>) -> Self {
+    self.presentation_header = Some(value.into());
+    self
+  }
+}
+
+impl From<&JwsVerificationOptions> for DataIntegrityVerificationOptions {
+  /// Maps the `nonce` to the expected `challenge` of the proof.
+  fn from(options: &JwsVerificationOptions) -> Self {
+    Self {
+      challenge: options.nonce.clone(),
+      method_scope: options.method_scope,
+      method_id: options.method_id.clone(),
+      ..Self::default()
+    }
+  }
+}
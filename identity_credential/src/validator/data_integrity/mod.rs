@@ -0,0 +1,9 @@
+// Copyright 2020-2024 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+//! Contains functionality for validating credentials and presentations secured with embedded Data Integrity proofs.
+mod data_integrity_validator;
+mod data_integrity_verification_options;
+
+pub use data_integrity_validator::*;
+pub use data_integrity_verification_options::*;
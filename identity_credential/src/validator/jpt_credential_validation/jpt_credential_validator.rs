@@ -7,12 +7,20 @@ use identity_did::CoreDID;
 use identity_did::DIDUrl;
 use identity_document::document::CoreDocument;
 use identity_document::verifiable::JwpVerificationOptions;
+use identity_verification::jwk::Jwk;
+#[cfg(feature = "jpt-pq")]
+use identity_verification::jws::JwsVerifier;
 use jsonprooftoken::encoding::SerializationType;
 use jsonprooftoken::jpt::claims::JptClaims;
 use jsonprooftoken::jwk::key::Jwk as JwkExt;
+use jsonprooftoken::jwp::issued::JwpIssued;
 use jsonprooftoken::jwp::issued::JwpIssuedDecoder;
 
 use super::DecodedJptCredential;
+#[cfg(feature = "jpt-pq")]
+use crate::credential::jpt_pq;
+#[cfg(feature = "jpt-predicates-experimental")]
+use crate::credential::verify_issued_jwp;
 use crate::credential::Credential;
 use crate::credential::CredentialJwtClaims;
 use crate::credential::Jpt;
@@ -45,10 +53,61 @@ impl JptCredentialValidator {
   where
     T: ToOwned<Owned = T> + serde::Serialize + serde::de::DeserializeOwned,
     DOC: AsRef<CoreDocument>,
+  {
+    Self::validate_with(credential_jpt, issuer, options, fail_fast, |decoded, public_key| {
+      let public_key: JwkExt = public_key
+        .try_into()
+        .map_err(|_| JwtValidationError::MethodDataLookupError {
+          source: None,
+          message: "could not extract JWK from a method identified by kid",
+          signer_ctx: SignerContext::Issuer,
+        })?;
+      #[cfg(feature = "jpt-predicates-experimental")]
+      let verified = verify_issued_jwp(decoded, &public_key);
+      #[cfg(not(feature = "jpt-predicates-experimental"))]
+      let verified = decoded.verify(&public_key);
+      verified.map_err(JwtValidationError::JwpProofVerificationError)
+    })
+  }
+
+  /// Decodes and validates a post-quantum [`Credential`] issued as a JPT secured with salted hashes, verifying the
+  /// signature of the issuer with `issuer_verifier`, e.g. a verifier of ML-DSA signatures.
+  ///
+  /// The same properties as in [`validate`](JptCredentialValidator::validate) are validated.
+  #[cfg(feature = "jpt-pq")]
+  pub fn validate_pqc<DOC, T, V>(
+    credential_jpt: &Jpt,
+    issuer: &DOC,
+    options: &JptCredentialValidationOptions,
+    fail_fast: FailFast,
+    issuer_verifier: &V,
+  ) -> Result<DecodedJptCredential<T>, CompoundCredentialValidationError>
+  where
+    T: ToOwned<Owned = T> + serde::Serialize + serde::de::DeserializeOwned,
+    DOC: AsRef<CoreDocument>,
+    V: JwsVerifier,
+  {
+    Self::validate_with(credential_jpt, issuer, options, fail_fast, |decoded, public_key| {
+      jpt_pq::verify_issued_jwp(decoded, public_key, issuer_verifier)
+        .map_err(|err| JwtValidationError::JwpProofVerificationError(jpt_pq::verification_error(err)))
+    })
+  }
+
+  fn validate_with<DOC, T, F>(
+    credential_jpt: &Jpt,
+    issuer: &DOC,
+    options: &JptCredentialValidationOptions,
+    fail_fast: FailFast,
+    verify: F,
+  ) -> Result<DecodedJptCredential<T>, CompoundCredentialValidationError>
+  where
+    T: ToOwned<Owned = T> + serde::Serialize + serde::de::DeserializeOwned,
+    DOC: AsRef<CoreDocument>,
+    F: FnOnce(&JwpIssuedDecoder, &Jwk) -> Result<JwpIssued, JwtValidationError>,
   {
     // First verify the JWP proof and decode the result into a credential token, then apply all other validations.
     let credential_token =
-      Self::verify_proof(credential_jpt, issuer, &options.verification_options).map_err(|err| {
+      Self::verify_proof(credential_jpt, issuer, &options.verification_options, verify).map_err(|err| {
         CompoundCredentialValidationError {
           validation_errors: [err].into(),
         }
@@ -115,14 +174,16 @@ impl JptCredentialValidator {
   }
 
   /// Proof verification function
-  fn verify_proof<DOC, T>(
+  fn verify_proof<DOC, T, F>(
     credential: &Jpt,
     issuer: &DOC,
     options: &JwpVerificationOptions,
+    verify: F,
   ) -> Result<DecodedJptCredential<T>, JwtValidationError>
   where
     T: ToOwned<Owned = T> + serde::Serialize + serde::de::DeserializeOwned,
     DOC: AsRef<CoreDocument>,
+    F: FnOnce(&JwpIssuedDecoder, &Jwk) -> Result<JwpIssued, JwtValidationError>,
   {
     let decoded = JwpIssuedDecoder::decode(credential.as_str(), SerializationType::COMPACT)
       .map_err(JwtValidationError::JwpDecodingError)?;
@@ -158,17 +219,16 @@ impl JptCredentialValidator {
     }
 
     // Obtain the public key from the issuer's DID document
-    let public_key: JwkExt = issuer
+    let public_key: &Jwk = issuer
       .resolve_method(&method_id, options.method_scope)
       .and_then(|method| method.data().public_key_jwk())
-      .and_then(|k| k.try_into().ok()) //Conversio into jsonprooftoken::Jwk type
       .ok_or_else(|| JwtValidationError::MethodDataLookupError {
         source: None,
         message: "could not extract JWK from a method identified by kid",
         signer_ctx: SignerContext::Issuer,
       })?;
 
-    let credential_token = Self::verify_decoded_jwp(decoded, &public_key)?;
+    let credential_token = Self::decode_credential(verify(&decoded, public_key)?)?;
 
     // Check that the DID component of the parsed `kid` does indeed correspond to the issuer in the credential before
     // returning.
@@ -181,19 +241,11 @@ impl JptCredentialValidator {
     Ok(credential_token)
   }
 
-  /// Verify the decoded issued JWP proof using the given `public_key`.
-  fn verify_decoded_jwp<T>(
-    decoded: JwpIssuedDecoder,
-    public_key: &JwkExt,
-  ) -> Result<DecodedJptCredential<T>, JwtValidationError>
+  /// Decode the credential of an issued JWP whose proof has been verified.
+  fn decode_credential<T>(decoded_jwp: JwpIssued) -> Result<DecodedJptCredential<T>, JwtValidationError>
   where
     T: ToOwned<Owned = T> + serde::Serialize + serde::de::DeserializeOwned,
   {
-    // Verify Jwp proof
-    let decoded_jwp = decoded
-      .verify(public_key)
-      .map_err(JwtValidationError::JwpProofVerificationError)?;
-
     let claims = decoded_jwp.get_claims().ok_or("Claims not present").map_err(|err| {
       JwtValidationError::CredentialStructure(crate::Error::JptClaimsSetDeserializationError(err.into()))
     })?;
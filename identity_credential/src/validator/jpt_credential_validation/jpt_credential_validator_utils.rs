@@ -17,6 +17,7 @@ use jsonprooftoken::jwp::issued::JwpIssuedDecoder;
 
 use crate::credential::CredentialJwtClaims;
 use crate::credential::Jpt;
+use crate::validator::JwtCredentialValidatorUtils;
 use crate::validator::JwtValidationError;
 use crate::validator::SignerContext;
 
@@ -116,15 +117,11 @@ impl JptCredentialValidatorUtils {
     status: RevocationTimeframeStatus,
     validity_timeframe: Option<Timestamp>,
   ) -> ValidationUnitResult {
-    let timeframe = validity_timeframe.unwrap_or(Timestamp::now_utc());
-
-    let check = timeframe >= status.start_validity_timeframe() && timeframe <= status.end_validity_timeframe();
-
-    if !check {
-      Err(JwtValidationError::OutsideTimeframe)
-    } else {
-      Ok(())
-    }
+    JwtCredentialValidatorUtils::check_validity_timeframe(
+      status.start_validity_timeframe(),
+      status.end_validity_timeframe(),
+      validity_timeframe,
+    )
   }
 
   /// Checks whether the credential status has been revoked.
@@ -6,6 +6,10 @@ use identity_core::common::Url;
 use jsonprooftoken::jwp::presented::JwpPresented;
 
 use crate::credential::Credential;
+#[cfg(feature = "jpt-predicates-experimental")]
+use crate::credential::JptPredicate;
+#[cfg(feature = "jpt-predicates-experimental")]
+use crate::credential::JptPseudonym;
 
 /// Decoded [`Credential`] from a cryptographically verified JWP.
 #[non_exhaustive]
@@ -19,4 +23,22 @@ pub struct DecodedJptPresentation<T = Object> {
   pub custom_claims: Option<Object>,
   /// The decoded and verifier Issued JWP, will be used to construct the Presented JWP
   pub decoded_jwp: JwpPresented,
+  /// The predicates proven over concealed claims of the credential.
+  #[cfg(feature = "jpt-predicates-experimental")]
+  pub predicates: Vec<JptPredicate>,
+  /// Whether the presentation proves knowledge of the holder secret the credential is bound to.
+  pub holder_bound: bool,
+  /// The pseudonym of the holder disclosed for the verifier, if any.
+  #[cfg(feature = "jpt-predicates-experimental")]
+  pub pseudonym: Option<JptPseudonym>,
+}
+
+/// What the proof of a Presented JWP proves besides the disclosed claims.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct VerifiedProof {
+  #[cfg(feature = "jpt-predicates-experimental")]
+  pub(crate) predicates: Vec<JptPredicate>,
+  pub(crate) holder_bound: bool,
+  #[cfg(feature = "jpt-predicates-experimental")]
+  pub(crate) pseudonym: Option<JptPseudonym>,
 }
@@ -5,6 +5,9 @@ use identity_document::verifiable::JwpVerificationOptions;
 use serde::Deserialize;
 use serde::Serialize;
 
+#[cfg(feature = "jpt-predicates-experimental")]
+use crate::credential::JptPredicate;
+
 /// Criteria for validating a [`Presentation`](crate::presentation::Presentation).
 #[derive(Debug, Default, Clone, Serialize, Deserialize)]
 #[non_exhaustive]
@@ -17,6 +20,24 @@ pub struct JptPresentationValidationOptions {
   /// Options which affect the verification of the proof on the credential.
   #[serde(default)]
   pub verification_options: JwpVerificationOptions,
+
+  /// The predicates the presentation must prove over concealed claims of the credential.
+  ///
+  /// Each predicate must be proven exactly as given: a proof of `age > 20` does not satisfy a required `age >= 18`.
+  #[cfg(feature = "jpt-predicates-experimental")]
+  #[serde(default)]
+  pub predicates: Vec<JptPredicate>,
+
+  /// Whether the presentation must prove knowledge of the holder secret the credential is bound to.
+  #[serde(default)]
+  pub holder_binding: bool,
+
+  /// The identifier of the verifier, for which the presentation must disclose the pseudonym of the holder.
+  ///
+  /// Setting it implies [`holder_binding`](JptPresentationValidationOptions::holder_binding).
+  #[cfg(feature = "jpt-predicates-experimental")]
+  #[serde(default)]
+  pub verifier_id: Option<String>,
 }
 
 impl JptPresentationValidationOptions {
@@ -37,4 +58,24 @@ impl JptPresentationValidationOptions {
     self.verification_options = options;
     self
   }
+
+  /// Require the presentation to prove `predicate`.
+  #[cfg(feature = "jpt-predicates-experimental")]
+  pub fn predicate(mut self, predicate: JptPredicate) -> Self {
+    self.predicates.push(predicate);
+    self
+  }
+
+  /// Require the presentation to prove knowledge of the holder secret the credential is bound to.
+  pub fn holder_binding(mut self, holder_binding: bool) -> Self {
+    self.holder_binding = holder_binding;
+    self
+  }
+
+  /// Require the presentation to disclose the pseudonym of the holder for the verifier identified by `verifier_id`.
+  #[cfg(feature = "jpt-predicates-experimental")]
+  pub fn verifier_id(mut self, verifier_id: impl Into<String>) -> Self {
+    self.verifier_id = Some(verifier_id.into());
+    self
+  }
 }
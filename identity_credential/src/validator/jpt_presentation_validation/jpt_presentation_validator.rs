@@ -9,11 +9,19 @@ use identity_core::convert::ToJson;
 use identity_did::CoreDID;
 use identity_did::DIDUrl;
 use identity_document::document::CoreDocument;
+use identity_verification::jwk::Jwk;
+#[cfg(feature = "jpt-pq")]
+use identity_verification::jws::JwsVerifier;
 use jsonprooftoken::encoding::SerializationType;
 use jsonprooftoken::jpt::claims::JptClaims;
 use jsonprooftoken::jwk::key::Jwk as JwkExt;
+use jsonprooftoken::jwp::presented::JwpPresented;
 use jsonprooftoken::jwp::presented::JwpPresentedDecoder;
 
+#[cfg(feature = "jpt-pq")]
+use crate::credential::jpt_pq;
+#[cfg(feature = "jpt-predicates-experimental")]
+use crate::credential::verify_presented_jwp;
 use crate::credential::Credential;
 use crate::credential::CredentialJwtClaims;
 use crate::credential::Jpt;
@@ -25,6 +33,7 @@ use crate::validator::SignerContext;
 
 use super::DecodedJptPresentation;
 use super::JptPresentationValidationOptions;
+use super::VerifiedProof;
 
 /// A type for decoding and validating Presented [`Credential`]s in JPT format.
 #[non_exhaustive]
@@ -48,13 +57,80 @@ impl JptPresentationValidator {
   where
     T: ToOwned<Owned = T> + serde::Serialize + serde::de::DeserializeOwned,
     DOC: AsRef<CoreDocument>,
+  {
+    Self::validate_with(presentation_jpt, issuer, options, fail_fast, |decoded, public_key| {
+      let public_key: JwkExt = public_key
+        .try_into()
+        .map_err(|_| JwtValidationError::MethodDataLookupError {
+          source: None,
+          message: "could not extract JWK from a method identified by kid",
+          signer_ctx: SignerContext::Issuer,
+        })?;
+      #[cfg(feature = "jpt-predicates-experimental")]
+      let verified = verify_presented_jwp(decoded, &public_key);
+      #[cfg(not(feature = "jpt-predicates-experimental"))]
+      let verified = decoded
+        .verify(&public_key)
+        .map(|presented| (presented, VerifiedProof::default()));
+      verified.map_err(JwtValidationError::JwpProofVerificationError)
+    })
+  }
+
+  /// Decodes and validates a post-quantum Presented [`Credential`] issued as a JPT secured with salted hashes,
+  /// verifying the signature of the issuer with `issuer_verifier` and that of the holder with `holder_verifier`, e.g.
+  /// verifiers of ML-DSA signatures.
+  ///
+  /// The same properties as in [`validate`](JptPresentationValidator::validate) are validated. Such presentations
+  /// are always bound to the holder, but prove no predicates and disclose no pseudonyms.
+  #[cfg(feature = "jpt-pq")]
+  pub fn validate_pqc<DOC, T, IV, HV>(
+    presentation_jpt: &Jpt,
+    issuer: &DOC,
+    options: &JptPresentationValidationOptions,
+    fail_fast: FailFast,
+    issuer_verifier: &IV,
+    holder_verifier: &HV,
+  ) -> Result<DecodedJptPresentation<T>, CompoundCredentialValidationError>
+  where
+    T: ToOwned<Owned = T> + serde::Serialize + serde::de::DeserializeOwned,
+    DOC: AsRef<CoreDocument>,
+    IV: JwsVerifier,
+    HV: JwsVerifier,
+  {
+    Self::validate_with(presentation_jpt, issuer, options, fail_fast, |decoded, public_key| {
+      let decoded_jwp: JwpPresented =
+        jpt_pq::verify_presented_jwp(decoded, public_key, issuer_verifier, holder_verifier)
+          .map_err(|err| JwtValidationError::JwpProofVerificationError(jpt_pq::verification_error(err)))?;
+      let verified: VerifiedProof = VerifiedProof {
+        #[cfg(feature = "jpt-predicates-experimental")]
+        predicates: Vec::new(),
+        holder_bound: true,
+        #[cfg(feature = "jpt-predicates-experimental")]
+        pseudonym: None,
+      };
+      Ok((decoded_jwp, verified))
+    })
+  }
+
+  fn validate_with<DOC, T, F>(
+    presentation_jpt: &Jpt,
+    issuer: &DOC,
+    options: &JptPresentationValidationOptions,
+    fail_fast: FailFast,
+    verify: F,
+  ) -> Result<DecodedJptPresentation<T>, CompoundCredentialValidationError>
+  where
+    T: ToOwned<Owned = T> + serde::Serialize + serde::de::DeserializeOwned,
+    DOC: AsRef<CoreDocument>,
+    F: FnOnce(&JwpPresentedDecoder, &Jwk) -> Result<(JwpPresented, VerifiedProof), JwtValidationError>,
   {
     // First verify the JWP proof and decode the result into a presented credential token, then apply all other
     // validations.
-    let presented_credential_token =
-      Self::verify_proof(presentation_jpt, issuer, options).map_err(|err| CompoundCredentialValidationError {
+    let presented_credential_token = Self::verify_proof(presentation_jpt, issuer, options, verify).map_err(|err| {
+      CompoundCredentialValidationError {
         validation_errors: [err].into(),
-      })?;
+      }
+    })?;
 
     let credential: &Credential<T> = &presented_credential_token.credential;
 
@@ -88,14 +164,16 @@ impl JptPresentationValidator {
   }
 
   /// Proof verification function
-  fn verify_proof<DOC, T>(
+  fn verify_proof<DOC, T, F>(
     presentation_jpt: &Jpt,
     issuer: &DOC,
     options: &JptPresentationValidationOptions,
+    verify: F,
   ) -> Result<DecodedJptPresentation<T>, JwtValidationError>
   where
     T: ToOwned<Owned = T> + serde::Serialize + serde::de::DeserializeOwned,
     DOC: AsRef<CoreDocument>,
+    F: FnOnce(&JwpPresentedDecoder, &Jwk) -> Result<(JwpPresented, VerifiedProof), JwtValidationError>,
   {
     let decoded: JwpPresentedDecoder =
       JwpPresentedDecoder::decode(presentation_jpt.as_str(), SerializationType::COMPACT)
@@ -140,17 +218,47 @@ impl JptPresentationValidator {
     }
 
     // Obtain the public key from the issuer's DID document
-    let public_key: JwkExt = issuer
+    let public_key: &Jwk = issuer
       .resolve_method(&method_id, options.verification_options.method_scope)
       .and_then(|method| method.data().public_key_jwk())
-      .and_then(|k| k.try_into().ok()) //Conversio into jsonprooftoken::Jwk type
       .ok_or_else(|| JwtValidationError::MethodDataLookupError {
         source: None,
         message: "could not extract JWK from a method identified by kid",
         signer_ctx: SignerContext::Issuer,
       })?;
 
-    let credential_token = Self::verify_decoded_jwp(decoded, &public_key)?;
+    let (decoded_jwp, verified): (JwpPresented, VerifiedProof) = verify(&decoded, public_key)?;
+    let credential_token: DecodedJptPresentation<T> = Self::decode_presentation(decoded_jwp, verified)?;
+
+    // Check that every predicate required by the verifier has been proven.
+    #[cfg(feature = "jpt-predicates-experimental")]
+    if let Some(predicate) = options
+      .predicates
+      .iter()
+      .find(|predicate| !credential_token.predicates.contains(predicate))
+    {
+      return Err(JwtValidationError::UnprovenPredicate(predicate.to_string()));
+    }
+
+    // Check the holder binding and the pseudonym required by the verifier.
+    #[cfg(feature = "jpt-predicates-experimental")]
+    let holder_binding: bool = options.holder_binding || options.verifier_id.is_some();
+    #[cfg(not(feature = "jpt-predicates-experimental"))]
+    let holder_binding: bool = options.holder_binding;
+    if holder_binding && !credential_token.holder_bound {
+      return Err(JwtValidationError::MissingHolderBinding);
+    }
+    #[cfg(feature = "jpt-predicates-experimental")]
+    if let Some(verifier_id) = &options.verifier_id {
+      if credential_token
+        .pseudonym
+        .as_ref()
+        .map(|pseudonym| pseudonym.verifier_id())
+        != Some(verifier_id.as_str())
+      {
+        return Err(JwtValidationError::MissingPseudonym(verifier_id.clone()));
+      }
+    }
 
     // Check that the DID component of the parsed `kid` does indeed correspond to the issuer in the credential before
     // returning.
@@ -163,19 +271,14 @@ impl JptPresentationValidator {
     Ok(credential_token)
   }
 
-  /// Verify the decoded presented JWP proof using the given `public_key`.
-  fn verify_decoded_jwp<T>(
-    decoded: JwpPresentedDecoder,
-    public_key: &JwkExt,
+  /// Decode the credential of a presented JWP whose proof has been verified.
+  fn decode_presentation<T>(
+    decoded_jwp: JwpPresented,
+    verified: VerifiedProof,
   ) -> Result<DecodedJptPresentation<T>, JwtValidationError>
   where
     T: ToOwned<Owned = T> + serde::Serialize + serde::de::DeserializeOwned,
   {
-    // Verify Jwp proof
-    let decoded_jwp = decoded
-      .verify(public_key)
-      .map_err(JwtValidationError::JwpProofVerificationError)?;
-
     let claims = decoded_jwp.get_claims().ok_or("Claims not present").map_err(|err| {
       JwtValidationError::CredentialStructure(crate::Error::JptClaimsSetDeserializationError(err.into()))
     })?;
@@ -221,6 +324,11 @@ impl JptPresentationValidator {
       aud,
       custom_claims,
       decoded_jwp,
+      #[cfg(feature = "jpt-predicates-experimental")]
+      predicates: verified.predicates,
+      holder_bound: verified.holder_bound,
+      #[cfg(feature = "jpt-predicates-experimental")]
+      pseudonym: verified.pseudonym,
     })
   }
 }
@@ -2,6 +2,8 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::credential::Credential;
+use crate::credential::CredentialV2;
+use crate::credential::VcDataModelVersion;
 use identity_core::common::Object;
 use identity_verification::jose::jws::JwsHeader;
 
@@ -19,3 +21,75 @@ pub struct DecodedJwtCredential<T = Object> {
   /// The custom claims parsed from the JWT.
   pub custom_claims: Option<Object>,
 }
+
+/// Decoded [`CredentialV2`] from a cryptographically verified JWS.
+///
+/// Note that having an instance of this type only means the JWS it was constructed from was verified.
+/// It does not imply anything about a potentially present proof property on the credential itself.
+#[non_exhaustive]
+#[derive(Debug, Clone)]
+pub struct DecodedJwtCredentialV2<T = Object> {
+  /// The decoded credential parsed to the [Verifiable Credentials Data Model v2.0](https://www.w3.org/TR/vc-data-model-2.0/).
+  pub credential: CredentialV2<T>,
+  /// The protected header parsed from the JWS.
+  pub header: Box<JwsHeader>,
+  /// The registered JWT claims, such as `iat` or `exp`, parsed from the JWT.
+  pub registered_claims: Option<Object>,
+}
+
+/// A credential decoded from a cryptographically verified JWS, in the version of the VC Data Model selected by its
+/// `@context`.
+#[non_exhaustive]
+#[derive(Debug, Clone)]
+pub enum VersionedDecodedJwtCredential<T = Object> {
+  /// A credential conforming to [VC Data Model v1.1](https://www.w3.org/TR/vc-data-model/).
+  V1_1(DecodedJwtCredential<T>),
+  /// A credential conforming to [VC Data Model v2.0](https://www.w3.org/TR/vc-data-model-2.0/).
+  V2_0(DecodedJwtCredentialV2<T>),
+}
+
+impl<T> VersionedDecodedJwtCredential<T> {
+  /// Returns the version of the VC Data Model the credential conforms to.
+  pub fn version(&self) -> VcDataModelVersion {
+    match self {
+      Self::V1_1(_) => VcDataModelVersion::V1_1,
+      Self::V2_0(_) => VcDataModelVersion::V2_0,
+    }
+  }
+
+  /// Returns the protected header parsed from the JWS.
+  pub fn header(&self) -> &JwsHeader {
+    match self {
+      Self::V1_1(decoded) => &decoded.header,
+      Self::V2_0(decoded) => &decoded.header,
+    }
+  }
+
+  /// Returns the decoded VC Data Model v1.1 credential, if the credential conforms to that version.
+  pub fn into_v1_1(self) -> Option<DecodedJwtCredential<T>> {
+    match self {
+      Self::V1_1(decoded) => Some(decoded),
+      Self::V2_0(_) => None,
+    }
+  }
+
+  /// Returns the decoded VC Data Model v2.0 credential, if the credential conforms to that version.
+  pub fn into_v2_0(self) -> Option<DecodedJwtCredentialV2<T>> {
+    match self {
+      Self::V1_1(_) => None,
+      Self::V2_0(decoded) => Some(decoded),
+    }
+  }
+}
+
+impl<T> From<DecodedJwtCredential<T>> for VersionedDecodedJwtCredential<T> {
+  fn from(decoded: DecodedJwtCredential<T>) -> Self {
+    Self::V1_1(decoded)
+  }
+}
+
+impl<T> From<DecodedJwtCredentialV2<T>> for VersionedDecodedJwtCredential<T> {
+  fn from(decoded: DecodedJwtCredentialV2<T>) -> Self {
+    Self::V2_0(decoded)
+  }
+}
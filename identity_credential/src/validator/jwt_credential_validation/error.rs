@@ -104,8 +104,12 @@ pub enum JwtValidationError {
   /// Indicates that the credential has been suspended.
   #[error("credential has been suspended")]
   Suspended,
+  /// Indicates that the status list credential referenced by a credential could not be fetched or is not valid.
+  #[cfg(feature = "status-list-2021")]
+  #[error("could not resolve the status list credential")]
+  StatusListResolutionError(#[source] Box<dyn std::error::Error + Send + Sync + 'static>),
   /// Indicates that the credential's timeframe interval is not valid
-  #[cfg(feature = "jpt-bbs-plus")]
+  #[cfg(feature = "revocation-bitmap")]
   #[error("timeframe interval not valid")]
   OutsideTimeframe,
   /// Indicates that the JWP representation of an issued credential or presentation could not be decoded.
@@ -116,10 +120,31 @@ pub enum JwtValidationError {
   #[cfg(feature = "jpt-bbs-plus")]
   #[error("could not verify jwp")]
   JwpProofVerificationError(#[source] jsonprooftoken::errors::CustomError),
+  /// Indicates that a predicate required by the verifier is not proven by a JPT presentation.
+  #[cfg(feature = "jpt-predicates-experimental")]
+  #[error("predicate `{0}` is not proven")]
+  UnprovenPredicate(String),
+  /// Indicates that a JPT presentation does not prove knowledge of the holder secret its credential is bound to,
+  /// although the verifier requires it.
+  #[cfg(feature = "jpt-bbs-plus")]
+  #[error("the presentation is not bound to a holder secret")]
+  MissingHolderBinding,
+  /// Indicates that a JPT presentation does not disclose the pseudonym of the holder for the verifier.
+  #[cfg(feature = "jpt-predicates-experimental")]
+  #[error("the presentation does not disclose a pseudonym for verifier `{0}`")]
+  MissingPseudonym(String),
+  /// Indicates that the COSE_Sign1 representation of an issued credential or presentation could not be decoded.
+  #[cfg(feature = "cose")]
+  #[error("could not decode cose")]
+  CoseDecodingError(#[source] identity_verification::jose::error::Error),
+  /// Indicates that the embedded Data Integrity proof of a credential or presentation is missing or invalid.
+  #[cfg(feature = "data-integrity")]
+  #[error("invalid data integrity proof")]
+  DataIntegrityProofError(#[source] crate::Error),
 }
 
 /// Specifies whether an error is related to a credential issuer or the presentation holder.
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy)]
 #[non_exhaustive]
 pub enum SignerContext {
   /// Credential issuer.
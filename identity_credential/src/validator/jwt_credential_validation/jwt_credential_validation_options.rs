@@ -7,6 +7,8 @@ use identity_document::verifiable::JwsVerificationOptions;
 use serde::Deserialize;
 use serde::Serialize;
 
+#[cfg(feature = "status-list-2021")]
+use crate::revocation::status_list_2021::StatusList2021Credential;
 use crate::validator::SubjectHolderRelationship;
 
 /// Options to declare validation criteria for [`Credential`](crate::credential::Credential)s.
@@ -32,6 +34,15 @@ pub struct JwtCredentialValidationOptions {
   #[serde(default)]
   pub status: crate::validator::StatusCheck,
 
+  /// Status list credentials against which `StatusList2021` statuses are checked according to
+  /// [`status`](Self::status).
+  ///
+  /// They must have been validated against the DID Document of their issuer beforehand, e.g. by
+  /// [`StatusListResolver::validation_options`](crate::validator::StatusListResolver::validation_options).
+  #[cfg(feature = "status-list-2021")]
+  #[serde(default, skip_serializing_if = "Vec::is_empty")]
+  pub status_list_credentials: Vec<StatusList2021Credential>,
+
   /// Declares how credential subjects must relate to the presentation holder during validation.
   ///
   /// <https://www.w3.org/TR/vc-data-model/#subject-holder-relationships>
@@ -68,6 +79,13 @@ impl JwtCredentialValidationOptions {
     self
   }
 
+  /// Adds a validated status list credential against which `StatusList2021` statuses are checked.
+  #[cfg(feature = "status-list-2021")]
+  pub fn status_list_credential(mut self, status_list_credential: StatusList2021Credential) -> Self {
+    self.status_list_credentials.push(status_list_credential);
+    self
+  }
+
   /// Declares how credential subjects must relate to the presentation holder during validation.
   ///
   /// <https://www.w3.org/TR/vc-data-model/#subject-holder-relationships>
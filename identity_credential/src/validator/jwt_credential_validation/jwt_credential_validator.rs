@@ -1,6 +1,7 @@
 // Copyright 2020-2023 IOTA Stiftung
 // SPDX-License-Identifier: Apache-2.0
 
+use identity_core::common::Object;
 use identity_core::convert::FromJson;
 use identity_did::CoreDID;
 use identity_did::DIDUrl;
@@ -14,13 +15,18 @@ use identity_verification::jws::JwsVerifier;
 
 use super::CompoundCredentialValidationError;
 use super::DecodedJwtCredential;
+use super::DecodedJwtCredentialV2;
 use super::JwtCredentialValidationOptions;
 use super::JwtCredentialValidatorUtils;
 use super::JwtValidationError;
 use super::SignerContext;
+use super::VersionedDecodedJwtCredential;
 use crate::credential::Credential;
 use crate::credential::CredentialJwtClaims;
+use crate::credential::CredentialV2;
 use crate::credential::Jwt;
+use crate::credential::VcDataModelVersion;
+use crate::credential::VC_JWT_MEDIA_TYPE;
 use crate::validator::FailFast;
 
 /// A type for decoding and validating [`Credential`]s.
@@ -86,6 +92,71 @@ impl<V: JwsVerifier> JwtCredentialValidator<V> {
     )
   }
 
+  /// Decodes and validates a credential issued as a JWT, conforming to either version of the VC Data Model. A
+  /// [`VersionedDecodedJwtCredential`] is returned upon success.
+  ///
+  /// The version of the VC Data Model is selected by the `@context` of the credential:
+  /// - [VC Data Model v1.1](https://www.w3.org/TR/vc-data-model/) credentials are decoded from the `vc` claim into a
+  ///   [`Credential`],
+  /// - [VC Data Model v2.0](https://www.w3.org/TR/vc-data-model-2.0/) credentials are the claims set of a JWT secured
+  ///   in accordance with [VC-JOSE-COSE](https://www.w3.org/TR/vc-jose-cose/) and are decoded into a
+  ///   [`CredentialV2`]. Their JWS must have a `typ` of [`VC_JWT_MEDIA_TYPE`].
+  ///
+  /// The following properties are validated according to `options`:
+  /// - the issuer's signature on the JWS,
+  /// - the expiration date (`expirationDate` or `validUntil`, and the `exp` claim of v2.0 credentials),
+  /// - the issuance date (`issuanceDate` or `validFrom`, and the `nbf` claim of v2.0 credentials),
+  /// - the semantic structure.
+  ///
+  /// # Warning
+  /// The lack of an error returned from this method is in of itself not enough to conclude that the credential can be
+  /// trusted. This section contains more information on additional checks that should be carried out before and after
+  /// calling this method.
+  ///
+  /// ## The state of the issuer's DID Document
+  /// The caller must ensure that `issuer` represents an up-to-date DID Document.
+  ///
+  /// ## Properties that are not validated
+  ///  There are many properties defined in [The Verifiable Credentials Data Model](https://www.w3.org/TR/vc-data-model/) that are **not** validated, such as:
+  /// `proof`, `credentialStatus`, `type`, `credentialSchema`, `refreshService` **and more**.
+  /// These should be manually checked after validation, according to your requirements.
+  ///
+  /// # Errors
+  /// An error is returned whenever a validated condition is not satisfied.
+  pub fn validate_any_version<DOC, T>(
+    &self,
+    credential_jwt: &Jwt,
+    issuer: &DOC,
+    options: &JwtCredentialValidationOptions,
+    fail_fast: FailFast,
+  ) -> Result<VersionedDecodedJwtCredential<T>, CompoundCredentialValidationError>
+  where
+    T: ToOwned<Owned = T> + serde::Serialize + serde::de::DeserializeOwned,
+    DOC: AsRef<CoreDocument>,
+  {
+    let issuers: &[CoreDocument] = std::slice::from_ref(issuer.as_ref());
+    let one_error = |err: JwtValidationError| CompoundCredentialValidationError {
+      validation_errors: [err].into(),
+    };
+
+    match JwtCredentialValidatorUtils::data_model_version(credential_jwt).map_err(one_error)? {
+      VcDataModelVersion::V1_1 => {
+        let credential_token = self
+          .verify_signature(credential_jwt, issuers, &options.verification_options)
+          .map_err(one_error)?;
+        Self::validate_decoded_credential::<CoreDocument, T>(credential_token, issuers, options, fail_fast)
+          .map(VersionedDecodedJwtCredential::V1_1)
+      }
+      VcDataModelVersion::V2_0 => {
+        let credential_token = self
+          .verify_signature_v2(credential_jwt, issuers, &options.verification_options)
+          .map_err(one_error)?;
+        Self::validate_decoded_credential_v2::<CoreDocument, T>(credential_token, issuers, options, fail_fast)
+          .map(VersionedDecodedJwtCredential::V2_0)
+      }
+    }
+  }
+
   /// Decode and verify the JWS signature of a [`Credential`] issued as a JWT using the DID Document of a trusted
   /// issuer.
   ///
@@ -115,6 +186,139 @@ impl<V: JwsVerifier> JwtCredentialValidator<V> {
     Self::verify_signature_with_verifier(&self.0, credential, trusted_issuers, options)
   }
 
+  /// Decode and verify the JWS signature of a [`CredentialV2`] secured as a JWT using the DID Document of a trusted
+  /// issuer.
+  ///
+  /// A [`DecodedJwtCredentialV2`] is returned upon success.
+  ///
+  /// # Errors
+  /// See [`JwtCredentialValidator::verify_signature`]. An error is also returned if the `typ` of the JWS is not
+  /// [`VC_JWT_MEDIA_TYPE`].
+  pub fn verify_signature_v2<DOC, T>(
+    &self,
+    credential: &Jwt,
+    trusted_issuers: &[DOC],
+    options: &JwsVerificationOptions,
+  ) -> Result<DecodedJwtCredentialV2<T>, JwtValidationError>
+  where
+    T: ToOwned<Owned = T> + serde::Serialize + serde::de::DeserializeOwned,
+    DOC: AsRef<CoreDocument>,
+  {
+    let decoded: JwsValidationItem<'_> = Self::decode(credential.as_str())?;
+    Self::check_media_type_v2(&decoded)?;
+    let (public_key, method_id) = Self::parse_jwk(&decoded, trusted_issuers, options)?;
+
+    let decoded_jws: DecodedJws<'_> = Self::verify_signature_raw(decoded, public_key, &self.0)?;
+    Self::decode_credential_v2(decoded_jws, &method_id)
+  }
+
+  /// Parses the claims of a verified JWS into a [`DecodedJwtCredentialV2`], checking that the credential was issued
+  /// by the DID of `method_id`.
+  pub(crate) fn decode_credential_v2<T>(
+    decoded_jws: DecodedJws<'_>,
+    method_id: &DIDUrl,
+  ) -> Result<DecodedJwtCredentialV2<T>, JwtValidationError>
+  where
+    T: ToOwned<Owned = T> + serde::Serialize + serde::de::DeserializeOwned,
+  {
+    let DecodedJws { protected, claims, .. } = decoded_jws;
+    let (credential, registered_claims): (CredentialV2<T>, Option<Object>) =
+      CredentialV2::from_jwt_claims(&claims).map_err(JwtValidationError::CredentialStructure)?;
+
+    // Check that the DID component of the parsed `kid` does indeed correspond to the issuer in the credential.
+    let issuer_id: CoreDID = JwtCredentialValidatorUtils::extract_issuer_v2(&credential)?;
+    if &issuer_id != method_id.did() {
+      return Err(JwtValidationError::IdentifierMismatch {
+        signer_ctx: SignerContext::Issuer,
+      });
+    };
+
+    Ok(DecodedJwtCredentialV2 {
+      credential,
+      header: Box::new(protected),
+      registered_claims,
+    })
+  }
+
+  pub(crate) fn validate_decoded_credential_v2<DOC, T>(
+    credential_token: DecodedJwtCredentialV2<T>,
+    issuers: &[DOC],
+    options: &JwtCredentialValidationOptions,
+    fail_fast: FailFast,
+  ) -> Result<DecodedJwtCredentialV2<T>, CompoundCredentialValidationError>
+  where
+    T: ToOwned<Owned = T> + serde::Serialize + serde::de::DeserializeOwned,
+    DOC: AsRef<CoreDocument>,
+  {
+    let credential: &CredentialV2<T> = &credential_token.credential;
+
+    let valid_until_validation = std::iter::once_with(|| {
+      JwtCredentialValidatorUtils::check_valid_until_on_or_after(
+        credential,
+        options.earliest_expiry_date.unwrap_or_default(),
+      )
+    });
+
+    let valid_from_validation = std::iter::once_with(|| {
+      JwtCredentialValidatorUtils::check_valid_from_on_or_before(
+        credential,
+        options.latest_issuance_date.unwrap_or_default(),
+      )
+    });
+
+    let exp_validation = std::iter::once_with(|| {
+      JwtCredentialValidatorUtils::check_jwt_expires_on_or_after(
+        credential_token.registered_claims.as_ref(),
+        options.earliest_expiry_date.unwrap_or_default(),
+      )
+    });
+
+    let nbf_validation = std::iter::once_with(|| {
+      JwtCredentialValidatorUtils::check_jwt_not_before_on_or_before(
+        credential_token.registered_claims.as_ref(),
+        options.latest_issuance_date.unwrap_or_default(),
+      )
+    });
+
+    let structure_validation = std::iter::once_with(|| JwtCredentialValidatorUtils::check_structure_v2(credential));
+
+    let subject_holder_validation = std::iter::once_with(|| {
+      options
+        .subject_holder_relationship
+        .as_ref()
+        .map(|(holder, relationship)| {
+          JwtCredentialValidatorUtils::check_subject_holder_relationship_v2(credential, holder, *relationship)
+        })
+        .unwrap_or(Ok(()))
+    });
+
+    let validation_units_iter = valid_from_validation
+      .chain(nbf_validation)
+      .chain(valid_until_validation)
+      .chain(exp_validation)
+      .chain(structure_validation)
+      .chain(subject_holder_validation);
+
+    #[cfg(feature = "revocation-bitmap")]
+    let validation_units_iter = {
+      let revocation_validation =
+        std::iter::once_with(|| JwtCredentialValidatorUtils::check_status_v2(credential, issuers, options.status));
+      validation_units_iter.chain(revocation_validation)
+    };
+
+    let validation_units_error_iter = validation_units_iter.filter_map(|result| result.err());
+    let validation_errors: Vec<JwtValidationError> = match fail_fast {
+      FailFast::FirstError => validation_units_error_iter.take(1).collect(),
+      FailFast::AllErrors => validation_units_error_iter.collect(),
+    };
+
+    if validation_errors.is_empty() {
+      Ok(credential_token)
+    } else {
+      Err(CompoundCredentialValidationError { validation_errors })
+    }
+  }
+
   // This method takes a slice of issuer's instead of a single issuer in order to better accommodate presentation
   // validation. It also validates the relationship between a holder and the credential subjects when
   // `relationship_criterion` is Some.
@@ -128,12 +332,26 @@ impl<V: JwsVerifier> JwtCredentialValidator<V> {
     T: ToOwned<Owned = T> + serde::Serialize + serde::de::DeserializeOwned,
     DOC: AsRef<CoreDocument>,
   {
-    let credential: &Credential<T> = &credential_token.credential;
+    Self::validate_credential(&credential_token.credential, issuers, options, fail_fast).map(|_| credential_token)
+  }
+
+  // Runs the validations of `validate_decoded_credential` independently of the format the credential was secured
+  // with.
+  pub(crate) fn validate_credential<DOC, T>(
+    credential: &Credential<T>,
+    issuers: &[DOC],
+    options: &JwtCredentialValidationOptions,
+    fail_fast: FailFast,
+  ) -> Result<(), CompoundCredentialValidationError>
+  where
+    T: ToOwned<Owned = T> + serde::Serialize + serde::de::DeserializeOwned,
+    DOC: AsRef<CoreDocument>,
+  {
     // Run all single concern Credential validations in turn and fail immediately if `fail_fast` is true.
 
     let expiry_date_validation = std::iter::once_with(|| {
       JwtCredentialValidatorUtils::check_expires_on_or_after(
-        &credential_token.credential,
+        credential,
         options.earliest_expiry_date.unwrap_or_default(),
       )
     });
@@ -165,7 +383,7 @@ impl<V: JwsVerifier> JwtCredentialValidator<V> {
     #[cfg(feature = "revocation-bitmap")]
     let validation_units_iter = {
       let revocation_validation =
-        std::iter::once_with(|| JwtCredentialValidatorUtils::check_status(credential, issuers, options.status));
+        std::iter::once_with(|| JwtCredentialValidatorUtils::check_status_with_options(credential, issuers, options));
       validation_units_iter.chain(revocation_validation)
     };
 
@@ -176,7 +394,7 @@ impl<V: JwsVerifier> JwtCredentialValidator<V> {
     };
 
     if validation_errors.is_empty() {
-      Ok(credential_token)
+      Ok(())
     } else {
       Err(CompoundCredentialValidationError { validation_errors })
     }
@@ -274,6 +492,17 @@ impl<V: JwsVerifier> JwtCredentialValidator<V> {
     Ok(credential_token)
   }
 
+  /// Checks that a [`CredentialV2`] is secured with the media type defined by
+  /// [VC-JOSE-COSE](https://www.w3.org/TR/vc-jose-cose/#securing-vcs-with-jose).
+  pub(crate) fn check_media_type_v2(decoded: &JwsValidationItem<'_>) -> Result<(), JwtValidationError> {
+    match decoded.protected_header().and_then(|header| header.typ()) {
+      Some(VC_JWT_MEDIA_TYPE) => Ok(()),
+      _ => Err(JwtValidationError::JwsDecodingError(
+        identity_verification::jose::error::Error::InvalidParam("invalid typ value, expected `vc+jwt`"),
+      )),
+    }
+  }
+
   /// Decode the credential into a [`JwsValidationItem`].
   pub(crate) fn decode(credential_jws: &str) -> Result<JwsValidationItem<'_>, JwtValidationError> {
     let decoder: Decoder = Decoder::new();
@@ -330,11 +559,22 @@ impl<V: JwsVerifier> JwtCredentialValidator<V> {
 
 #[cfg(test)]
 mod tests {
+  use crate::credential::CredentialBuilder;
   use crate::credential::Subject;
+  use crate::validator::test_utils::generate_jwk_document_with_keys;
   use crate::validator::SubjectHolderRelationship;
+  use crypto::signatures::ed25519::SecretKey;
   use identity_core::common::Duration;
   use identity_core::common::Url;
+  use identity_did::DID;
+  use identity_eddsa_verifier::EdDSAJwsVerifier;
+  use identity_verification::jws::CharSet;
+  use identity_verification::jws::CompactJwsEncoder;
+  use identity_verification::jws::CompactJwsEncodingOptions;
+  use identity_verification::jws::JwsAlgorithm;
+  use identity_verification::jws::JwsHeader;
   use once_cell::sync::Lazy;
+  use serde_json::json;
 
   // All tests here are essentially adaptations of the old JwtCredentialValidator tests.
   use super::*;
@@ -367,6 +607,135 @@ mod tests {
   static SIMPLE_CREDENTIAL: Lazy<Credential> =
     Lazy::new(|| Credential::<Object>::from_json(SIMPLE_CREDENTIAL_JSON).unwrap());
 
+  fn sign(payload: &str, typ: Option<&str>, issuer: &CoreDocument, fragment: &str, secret_key: &SecretKey) -> Jwt {
+    let mut header: JwsHeader = JwsHeader::new();
+    header.set_alg(JwsAlgorithm::EdDSA);
+    header.set_kid(issuer.resolve_method(fragment, None).unwrap().id().to_string());
+    if let Some(typ) = typ {
+      header.set_typ(typ);
+    }
+    let encoding_options: CompactJwsEncodingOptions = CompactJwsEncodingOptions::NonDetached {
+      charset_requirements: CharSet::Default,
+    };
+    let encoder: CompactJwsEncoder<'_> =
+      CompactJwsEncoder::new_with_options(payload.as_bytes(), &header, encoding_options).unwrap();
+    let signature: [u8; 64] = secret_key.sign(encoder.signing_input()).to_bytes();
+    Jwt::new(encoder.into_jws(&signature))
+  }
+
+  fn credential_builder(issuer: &CoreDocument) -> CredentialBuilder {
+    CredentialBuilder::default()
+      .id(Url::parse("https://example.edu/credentials/3732").unwrap())
+      .issuer(Url::parse(issuer.id().as_str()).unwrap())
+      .type_("UniversityDegreeCredential")
+      .subject(Subject::with_id(Url::parse("did:example:holder").unwrap()))
+  }
+
+  fn credential_v2_jwt(
+    registered_claims: Option<Object>,
+    typ: Option<&str>,
+    issuer: &CoreDocument,
+    fragment: &str,
+    secret_key: &SecretKey,
+  ) -> Jwt {
+    let credential: CredentialV2 = CredentialV2::from_builder(credential_builder(issuer)).unwrap();
+    let payload: String = credential.serialize_jwt(registered_claims).unwrap();
+    sign(&payload, typ, issuer, fragment, secret_key)
+  }
+
+  fn validate(
+    jwt: &Jwt,
+    issuer: &CoreDocument,
+  ) -> Result<VersionedDecodedJwtCredential, CompoundCredentialValidationError> {
+    JwtCredentialValidator::with_signature_verifier(EdDSAJwsVerifier::default()).validate_any_version(
+      jwt,
+      issuer,
+      &JwtCredentialValidationOptions::default(),
+      FailFast::FirstError,
+    )
+  }
+
+  #[test]
+  fn validate_dispatches_on_data_model_version() {
+    let (issuer, secret_key, fragment) = generate_jwk_document_with_keys();
+
+    let payload: String = credential_builder(&issuer)
+      .build()
+      .unwrap()
+      .serialize_jwt(None)
+      .unwrap();
+    let jwt_v1: Jwt = sign(&payload, None, &issuer, &fragment, &secret_key);
+    assert!(matches!(
+      validate(&jwt_v1, &issuer).unwrap(),
+      VersionedDecodedJwtCredential::V1_1(_)
+    ));
+    let decoded: DecodedJwtCredential = JwtCredentialValidator::with_signature_verifier(EdDSAJwsVerifier::default())
+      .validate(
+        &jwt_v1,
+        &issuer,
+        &JwtCredentialValidationOptions::default(),
+        FailFast::FirstError,
+      )
+      .unwrap();
+    assert_eq!(decoded.credential.issuer.url().as_str(), issuer.id().as_str());
+
+    let jwt_v2: Jwt = credential_v2_jwt(None, Some(VC_JWT_MEDIA_TYPE), &issuer, &fragment, &secret_key);
+    assert!(matches!(
+      validate(&jwt_v2, &issuer).unwrap(),
+      VersionedDecodedJwtCredential::V2_0(_)
+    ));
+  }
+
+  #[test]
+  fn validate_v2_requires_vc_jwt_typ() {
+    let (issuer, secret_key, fragment) = generate_jwk_document_with_keys();
+
+    for typ in [None, Some("JWT")] {
+      let jwt: Jwt = credential_v2_jwt(None, typ, &issuer, &fragment, &secret_key);
+      let err: CompoundCredentialValidationError = validate(&jwt, &issuer).unwrap_err();
+      assert!(matches!(
+        err.validation_errors[0],
+        JwtValidationError::JwsDecodingError(_)
+      ));
+    }
+  }
+
+  #[test]
+  fn validate_v2_rejects_expired_token() {
+    let (issuer, secret_key, fragment) = generate_jwk_document_with_keys();
+    let now: Timestamp = Timestamp::now_utc();
+
+    let expired: Object = Object::from_json_value(json!({
+      "exp": now.checked_sub(Duration::minutes(1)).unwrap().to_unix()
+    }))
+    .unwrap();
+    let jwt: Jwt = credential_v2_jwt(Some(expired), Some(VC_JWT_MEDIA_TYPE), &issuer, &fragment, &secret_key);
+    let err: CompoundCredentialValidationError = validate(&jwt, &issuer).unwrap_err();
+    assert!(matches!(err.validation_errors[0], JwtValidationError::ExpirationDate));
+
+    let not_yet_valid: Object = Object::from_json_value(json!({
+      "nbf": now.checked_add(Duration::minutes(10)).unwrap().to_unix()
+    }))
+    .unwrap();
+    let jwt: Jwt = credential_v2_jwt(
+      Some(not_yet_valid),
+      Some(VC_JWT_MEDIA_TYPE),
+      &issuer,
+      &fragment,
+      &secret_key,
+    );
+    let err: CompoundCredentialValidationError = validate(&jwt, &issuer).unwrap_err();
+    assert!(matches!(err.validation_errors[0], JwtValidationError::IssuanceDate));
+
+    let valid: Object = Object::from_json_value(json!({
+      "exp": now.checked_add(Duration::minutes(10)).unwrap().to_unix(),
+      "nbf": now.checked_sub(Duration::minutes(1)).unwrap().to_unix()
+    }))
+    .unwrap();
+    let jwt: Jwt = credential_v2_jwt(Some(valid), Some(VC_JWT_MEDIA_TYPE), &issuer, &fragment, &secret_key);
+    assert!(validate(&jwt, &issuer).is_ok());
+  }
+
   #[test]
   fn issued_on_or_before() {
     assert!(JwtCredentialValidatorUtils::check_issued_on_or_before(
@@ -3,22 +3,26 @@ use identity_did::CoreDID;
 use identity_did::DIDUrl;
 use identity_document::document::CoreDocument;
 use identity_document::verifiable::JwsVerificationOptions;
+use identity_verification::jwk::CompositeJwk;
 use identity_verification::jwk::Jwk;
 use identity_verification::jws::DecodedJws;
 use identity_verification::jws::Decoder;
 use identity_verification::jws::JwsValidationItem;
 use identity_verification::jws::JwsVerifier;
-use identity_verification::jwk::CompositeJwk;
 
 use super::CompoundCredentialValidationError;
 use super::DecodedJwtCredential;
+use super::DecodedJwtCredentialV2;
 use super::JwtCredentialValidationOptions;
+use super::JwtCredentialValidator;
 use super::JwtCredentialValidatorUtils;
 use super::JwtValidationError;
 use super::SignerContext;
+use super::VersionedDecodedJwtCredential;
 use crate::credential::Credential;
 use crate::credential::CredentialJwtClaims;
 use crate::credential::Jwt;
+use crate::credential::VcDataModelVersion;
 use crate::validator::FailFast;
 
 /// A type for decoding and validating [`Credential`]s.
@@ -84,6 +88,72 @@ impl<TRV: JwsVerifier, PQV: JwsVerifier> JwtCredentialValidatorHybrid<TRV, PQV>
     )
   }
 
+  /// Decodes and validates a credential issued as a JWT, conforming to either version of the VC Data Model. A
+  /// [`VersionedDecodedJwtCredential`] is returned upon success.
+  ///
+  /// The version of the VC Data Model is selected by the `@context` of the credential, see
+  /// [`JwtCredentialValidator::validate_any_version`].
+  ///
+  /// The following properties are validated according to `options`:
+  /// - the issuer's signature on the JWS,
+  /// - the expiration date,
+  /// - the issuance date,
+  /// - the semantic structure.
+  ///
+  /// # Warning
+  /// The lack of an error returned from this method is in of itself not enough to conclude that the credential can be
+  /// trusted. This section contains more information on additional checks that should be carried out before and after
+  /// calling this method.
+  ///
+  /// ## The state of the issuer's DID Document
+  /// The caller must ensure that `issuer` represents an up-to-date DID Document.
+  ///
+  /// ## Properties that are not validated
+  ///  There are many properties defined in [The Verifiable Credentials Data Model](https://www.w3.org/TR/vc-data-model/) that are **not** validated, such as:
+  /// `proof`, `credentialStatus`, `type`, `credentialSchema`, `refreshService` **and more**.
+  /// These should be manually checked after validation, according to your requirements.
+  ///
+  /// # Errors
+  /// An error is returned whenever a validated condition is not satisfied.
+  pub fn validate_any_version<DOC, T>(
+    &self,
+    credential_jwt: &Jwt,
+    issuer: &DOC,
+    options: &JwtCredentialValidationOptions,
+    fail_fast: FailFast,
+  ) -> Result<VersionedDecodedJwtCredential<T>, CompoundCredentialValidationError>
+  where
+    T: ToOwned<Owned = T> + serde::Serialize + serde::de::DeserializeOwned,
+    DOC: AsRef<CoreDocument>,
+  {
+    let issuers: &[CoreDocument] = std::slice::from_ref(issuer.as_ref());
+    let one_error = |err: JwtValidationError| CompoundCredentialValidationError {
+      validation_errors: [err].into(),
+    };
+
+    match JwtCredentialValidatorUtils::data_model_version(credential_jwt).map_err(one_error)? {
+      VcDataModelVersion::V1_1 => {
+        let credential_token = self
+          .verify_signature(credential_jwt, issuers, &options.verification_options)
+          .map_err(one_error)?;
+        Self::validate_decoded_credential::<CoreDocument, T>(credential_token, issuers, options, fail_fast)
+          .map(VersionedDecodedJwtCredential::V1_1)
+      }
+      VcDataModelVersion::V2_0 => {
+        let credential_token = self
+          .verify_signature_v2(credential_jwt, issuers, &options.verification_options)
+          .map_err(one_error)?;
+        JwtCredentialValidator::<TRV>::validate_decoded_credential_v2::<CoreDocument, T>(
+          credential_token,
+          issuers,
+          options,
+          fail_fast,
+        )
+        .map(VersionedDecodedJwtCredential::V2_0)
+      }
+    }
+  }
+
   /// Decode and verify the JWS signature of a [`Credential`] issued as a JWT using the DID Document of a trusted
   /// issuer.
   ///
@@ -113,6 +183,37 @@ impl<TRV: JwsVerifier, PQV: JwsVerifier> JwtCredentialValidatorHybrid<TRV, PQV>
     Self::verify_signature_with_verifiers(&self.0, &self.1, credential, trusted_issuers, options)
   }
 
+  /// Decode and verify the hybrid JWS signature of a [`CredentialV2`] secured as a JWT using the DID Document of a
+  /// trusted issuer.
+  ///
+  /// A [`DecodedJwtCredentialV2`] is returned upon success.
+  ///
+  /// # Errors
+  /// See [`JwtCredentialValidator::verify_signature_v2`].
+  pub fn verify_signature_v2<DOC, T>(
+    &self,
+    credential: &Jwt,
+    trusted_issuers: &[DOC],
+    options: &JwsVerificationOptions,
+  ) -> Result<DecodedJwtCredentialV2<T>, JwtValidationError>
+  where
+    T: ToOwned<Owned = T> + serde::Serialize + serde::de::DeserializeOwned,
+    DOC: AsRef<CoreDocument>,
+  {
+    let decoded: JwsValidationItem<'_> = Self::decode(credential.as_str())?;
+    JwtCredentialValidator::<TRV>::check_media_type_v2(&decoded)?;
+    let (composite, method_id) = Self::parse_composite_pk(&decoded, trusted_issuers, options)?;
+
+    let decoded_jws: DecodedJws<'_> = Self::verify_signature_raw(
+      decoded,
+      composite.traditional_public_key(),
+      composite.pq_public_key(),
+      &self.0,
+      &self.1,
+    )?;
+    JwtCredentialValidator::<TRV>::decode_credential_v2(decoded_jws, &method_id)
+  }
+
   // This method takes a slice of issuer's instead of a single issuer in order to better accommodate presentation
   // validation. It also validates the relationship between a holder and the credential subjects when
   // `relationship_criterion` is Some.
@@ -2,19 +2,26 @@
 // SPDX-License-Identifier: Apache-2.0
 use std::str::FromStr;
 
+use identity_core::common::Context;
 use identity_core::common::Object;
 use identity_core::common::OneOrMany;
 use identity_core::common::Timestamp;
 use identity_core::common::Url;
+use identity_core::common::Value;
 use identity_core::convert::FromJson;
 use identity_did::DID;
 use identity_verification::jws::Decoder;
 
+use super::JwtCredentialValidationOptions;
 use super::JwtValidationError;
 use super::SignerContext;
 use crate::credential::Credential;
 use crate::credential::CredentialJwtClaims;
+use crate::credential::CredentialV2;
+use crate::credential::Issuer;
 use crate::credential::Jwt;
+use crate::credential::Subject;
+use crate::credential::VcDataModelVersion;
 #[cfg(feature = "status-list-2021")]
 use crate::revocation::status_list_2021::StatusList2021Credential;
 use crate::validator::SubjectHolderRelationship;
@@ -52,6 +59,70 @@ impl JwtCredentialValidatorUtils {
       .ok_or(JwtValidationError::IssuanceDate)
   }
 
+  /// Validates the semantic structure of the [`CredentialV2`].
+  ///
+  /// # Warning
+  /// This does not validate against the credential's schema nor the structure of the subject claims.
+  pub fn check_structure_v2<T>(credential: &CredentialV2<T>) -> ValidationUnitResult {
+    credential
+      .check_structure()
+      .map_err(JwtValidationError::CredentialStructure)
+  }
+
+  /// Validate that the [`CredentialV2`] is valid until on or after the specified [`Timestamp`].
+  pub fn check_valid_until_on_or_after<T>(credential: &CredentialV2<T>, timestamp: Timestamp) -> ValidationUnitResult {
+    let valid_until: Option<Timestamp> = credential.valid_until;
+    (valid_until.is_none() || valid_until >= Some(timestamp))
+      .then_some(())
+      .ok_or(JwtValidationError::ExpirationDate)
+  }
+
+  /// Validate that the [`CredentialV2`] is valid from on or before the specified [`Timestamp`].
+  pub fn check_valid_from_on_or_before<T>(credential: &CredentialV2<T>, timestamp: Timestamp) -> ValidationUnitResult {
+    let valid_from: Option<Timestamp> = credential.valid_from;
+    (valid_from.is_none() || valid_from <= Some(timestamp))
+      .then_some(())
+      .ok_or(JwtValidationError::IssuanceDate)
+  }
+
+  /// Validate that the `exp` claim of a [`CredentialV2`] secured as a JWT, if present among its
+  /// `registered_claims`, is on or after the specified [`Timestamp`].
+  pub fn check_jwt_expires_on_or_after(registered_claims: Option<&Object>, timestamp: Timestamp) -> ValidationUnitResult {
+    let exp: Option<Timestamp> = Self::registered_timestamp(registered_claims, "exp")?;
+    (exp.is_none() || exp >= Some(timestamp))
+      .then_some(())
+      .ok_or(JwtValidationError::ExpirationDate)
+  }
+
+  /// Validate that the `nbf` claim of a [`CredentialV2`] secured as a JWT, if present among its
+  /// `registered_claims`, is on or before the specified [`Timestamp`].
+  pub fn check_jwt_not_before_on_or_before(
+    registered_claims: Option<&Object>,
+    timestamp: Timestamp,
+  ) -> ValidationUnitResult {
+    let nbf: Option<Timestamp> = Self::registered_timestamp(registered_claims, "nbf")?;
+    (nbf.is_none() || nbf <= Some(timestamp))
+      .then_some(())
+      .ok_or(JwtValidationError::IssuanceDate)
+  }
+
+  fn registered_timestamp(
+    registered_claims: Option<&Object>,
+    name: &str,
+  ) -> std::result::Result<Option<Timestamp>, JwtValidationError> {
+    registered_claims
+      .and_then(|claims| claims.get(name))
+      .map(|value| {
+        value
+          .as_i64()
+          .and_then(|seconds| Timestamp::from_unix(seconds).ok())
+          .ok_or(JwtValidationError::CredentialStructure(
+            crate::Error::TimestampConversionError,
+          ))
+      })
+      .transpose()
+  }
+
   /// Validate that the relationship between the `holder` and the credential subjects is in accordance with
   /// `relationship`.
   pub fn check_subject_holder_relationship<T>(
@@ -59,7 +130,36 @@ impl JwtCredentialValidatorUtils {
     holder: &Url,
     relationship: SubjectHolderRelationship,
   ) -> ValidationUnitResult {
-    let url_matches: bool = match &credential.credential_subject {
+    Self::check_subjects_holder_relationship(
+      &credential.credential_subject,
+      credential.non_transferable,
+      holder,
+      relationship,
+    )
+  }
+
+  /// Validate that the relationship between the `holder` and the subjects of a [`CredentialV2`] is in accordance
+  /// with `relationship`.
+  pub fn check_subject_holder_relationship_v2<T>(
+    credential: &CredentialV2<T>,
+    holder: &Url,
+    relationship: SubjectHolderRelationship,
+  ) -> ValidationUnitResult {
+    Self::check_subjects_holder_relationship(
+      &credential.credential_subject,
+      credential.non_transferable,
+      holder,
+      relationship,
+    )
+  }
+
+  fn check_subjects_holder_relationship(
+    credential_subject: &OneOrMany<Subject>,
+    non_transferable: Option<bool>,
+    holder: &Url,
+    relationship: SubjectHolderRelationship,
+  ) -> ValidationUnitResult {
+    let url_matches: bool = match credential_subject {
       OneOrMany::One(ref credential_subject) => credential_subject.id.as_ref() == Some(holder),
       OneOrMany::Many(subjects) => {
         // need to check the case where the Many variant holds a vector of exactly one subject
@@ -75,9 +175,7 @@ impl JwtCredentialValidatorUtils {
     Some(relationship)
       .filter(|relationship| match relationship {
         SubjectHolderRelationship::AlwaysSubject => url_matches,
-        SubjectHolderRelationship::SubjectOnNonTransferable => {
-          url_matches || !credential.non_transferable.unwrap_or(false)
-        }
+        SubjectHolderRelationship::SubjectOnNonTransferable => url_matches || !non_transferable.unwrap_or(false),
         SubjectHolderRelationship::Any => true,
       })
       .map(|_| ())
@@ -132,6 +230,82 @@ impl JwtCredentialValidatorUtils {
     credential: &Credential<T>,
     trusted_issuers: &[DOC],
     status_check: crate::validator::StatusCheck,
+  ) -> ValidationUnitResult {
+    Self::check_credential_status(
+      credential.credential_status.as_ref(),
+      &credential.issuer,
+      trusted_issuers,
+      status_check,
+    )
+  }
+
+  /// Checks the status of a credential according to `options`.
+  ///
+  /// `StatusList2021` statuses are checked against the matching
+  /// [`status_list_credentials`](JwtCredentialValidationOptions::status_list_credentials) of `options`, which must be
+  /// issued by the issuer of the credential. Other statuses are checked as in [`Self::check_status`].
+  #[cfg(feature = "revocation-bitmap")]
+  pub(crate) fn check_status_with_options<DOC: AsRef<identity_document::document::CoreDocument>, T>(
+    credential: &Credential<T>,
+    trusted_issuers: &[DOC],
+    options: &JwtCredentialValidationOptions,
+  ) -> ValidationUnitResult {
+    #[cfg(feature = "status-list-2021")]
+    {
+      use crate::revocation::status_list_2021::StatusList2021Entry;
+      use crate::validator::StatusCheck;
+
+      if let Some(status) = credential
+        .credential_status
+        .as_ref()
+        .filter(|status| status.type_ == StatusList2021Entry::TYPE && options.status != StatusCheck::SkipAll)
+      {
+        let entry: StatusList2021Entry = StatusList2021Entry::try_from(status)
+          .map_err(|err| JwtValidationError::InvalidStatus(crate::Error::InvalidStatus(err.to_string())))?;
+        let status_list_credential: Option<&StatusList2021Credential> =
+          options.status_list_credentials.iter().find(|status_list_credential| {
+            status_list_credential.id() == Some(entry.status_list_credential())
+              && status_list_credential.issuer.url() == credential.issuer.url()
+          });
+        return match status_list_credential {
+          Some(status_list_credential) => {
+            Self::check_status_with_status_list_2021(credential, status_list_credential, options.status)
+          }
+          None if options.status == StatusCheck::SkipUnsupported => Ok(()),
+          None => Err(JwtValidationError::InvalidStatus(crate::Error::InvalidStatus(format!(
+            "no status list credential `{}` issued by the credential issuer was provided",
+            entry.status_list_credential()
+          )))),
+        };
+      }
+    }
+
+    Self::check_status(credential, trusted_issuers, options.status)
+  }
+
+  /// Checks whether the status of a [`CredentialV2`] has been revoked.
+  ///
+  /// Only supports `RevocationBitmap2022`.
+  #[cfg(feature = "revocation-bitmap")]
+  pub fn check_status_v2<DOC: AsRef<identity_document::document::CoreDocument>, T>(
+    credential: &CredentialV2<T>,
+    trusted_issuers: &[DOC],
+    status_check: crate::validator::StatusCheck,
+  ) -> ValidationUnitResult {
+    Self::check_credential_status(
+      credential.credential_status.as_ref(),
+      &credential.issuer,
+      trusted_issuers,
+      status_check,
+    )
+  }
+
+  #[cfg(feature = "revocation-bitmap")]
+  fn check_credential_status<DOC: AsRef<identity_document::document::CoreDocument>>(
+    credential_status: Option<&crate::credential::Status>,
+    issuer: &crate::credential::Issuer,
+    trusted_issuers: &[DOC],
+    status_check: crate::validator::StatusCheck,
   ) -> ValidationUnitResult {
     use identity_did::CoreDID;
     use identity_document::document::CoreDocument;
@@ -140,7 +314,7 @@ impl JwtCredentialValidatorUtils {
       return Ok(());
     }
 
-    match &credential.credential_status {
+    match credential_status {
       None => Ok(()),
       Some(status) => {
         // Check status is supported.
@@ -158,7 +332,7 @@ impl JwtCredentialValidatorUtils {
             .map_err(JwtValidationError::InvalidStatus)?;
 
         // Check the credential index against the issuer's DID Document.
-        let issuer_did: CoreDID = Self::extract_issuer(credential)?;
+        let issuer_did: CoreDID = Self::issuer_to_did(issuer)?;
         trusted_issuers
           .iter()
           .find(|issuer| <CoreDocument>::id(issuer.as_ref()) == &issuer_did)
@@ -192,6 +366,212 @@ impl JwtCredentialValidatorUtils {
     }
   }
 
+  /// Check timeframe interval in credentialStatus with `RevocationTimeframeStatus`.
+  ///
+  /// The timeframe of a credential refreshed with a validity timeframe attestation is checked with
+  /// [`check_timeframe_attestation`](JwtCredentialValidatorUtils::check_timeframe_attestation) instead.
+  #[cfg(feature = "revocation-bitmap")]
+  pub fn check_timeframes_with_validity_timeframe_2024<T>(
+    credential: &Credential<T>,
+    validity_timeframe: Option<Timestamp>,
+    status_check: crate::validator::StatusCheck,
+  ) -> ValidationUnitResult {
+    match Self::validity_timeframe_status(credential, status_check)? {
+      None => Ok(()),
+      Some(status) => Self::check_validity_timeframe(
+        status.start_validity_timeframe(),
+        status.end_validity_timeframe(),
+        validity_timeframe,
+      ),
+    }
+  }
+
+  /// Checks whether the credential status has been revoked.
+  ///
+  /// Only supports `RevocationTimeframe2024`.
+  #[cfg(feature = "revocation-bitmap")]
+  pub fn check_revocation_with_validity_timeframe_2024<
+    DOC: AsRef<identity_document::document::CoreDocument> + ?Sized,
+    T,
+  >(
+    credential: &Credential<T>,
+    issuer: &DOC,
+    status_check: crate::validator::StatusCheck,
+  ) -> ValidationUnitResult {
+    let Some(status) = Self::validity_timeframe_status(credential, status_check)? else {
+      return Ok(());
+    };
+    let Some(index) = status.index() else {
+      return Ok(());
+    };
+    let service_url: identity_did::DIDUrl = identity_did::DIDUrl::parse(status.id().as_str()).map_err(|err| {
+      JwtValidationError::InvalidStatus(crate::Error::InvalidStatus(format!(
+        "could not convert status id to DIDUrl; {}",
+        err,
+      )))
+    })?;
+
+    Self::check_revocation_bitmap_status(
+      issuer,
+      crate::credential::RevocationBitmapStatus::new(service_url, index),
+    )
+  }
+
+  /// Verifies the validity timeframe `attestation` with which the `issuer` refreshed the `RevocationTimeframe2024`
+  /// status of the credential, and checks that `validity_timeframe`, or now if unset, falls within the attested
+  /// timeframe. The claims of the attestation are returned upon success.
+  ///
+  /// The attestation must be signed by a method of the issuer and bound to the `id` of the credential.
+  #[cfg(feature = "revocation-bitmap")]
+  pub fn check_timeframe_attestation<DOC, T, V>(
+    credential: &Credential<T>,
+    attestation: &Jwt,
+    issuer: &DOC,
+    signature_verifier: &V,
+    validity_timeframe: Option<Timestamp>,
+  ) -> ValidationUnitResult<crate::revocation::TimeframeAttestationClaims>
+  where
+    DOC: AsRef<identity_document::document::CoreDocument>,
+    V: identity_verification::jws::JwsVerifier,
+  {
+    let decoded = issuer
+      .as_ref()
+      .verify_jws(
+        attestation.as_str(),
+        None,
+        signature_verifier,
+        &identity_document::verifiable::JwsVerificationOptions::default(),
+      )
+      .map_err(Self::attestation_signature_error)?;
+
+    Self::check_timeframe_attestation_claims(credential, issuer, decoded, validity_timeframe)
+  }
+
+  /// Verifies a validity timeframe `attestation` signed with a PQ/T hybrid method of the `issuer`, as in
+  /// [`check_timeframe_attestation`](JwtCredentialValidatorUtils::check_timeframe_attestation).
+  #[cfg(all(feature = "revocation-bitmap", feature = "hybrid"))]
+  pub fn check_timeframe_attestation_hybrid<DOC, T, TRV, PQV>(
+    credential: &Credential<T>,
+    attestation: &Jwt,
+    issuer: &DOC,
+    traditional_verifier: &TRV,
+    pq_verifier: &PQV,
+    validity_timeframe: Option<Timestamp>,
+  ) -> ValidationUnitResult<crate::revocation::TimeframeAttestationClaims>
+  where
+    DOC: AsRef<identity_document::document::CoreDocument>,
+    TRV: identity_verification::jws::JwsVerifier,
+    PQV: identity_verification::jws::JwsVerifier,
+  {
+    let decoded = issuer
+      .as_ref()
+      .verify_jws_hybrid(
+        attestation.as_str(),
+        None,
+        traditional_verifier,
+        pq_verifier,
+        &identity_document::verifiable::JwsVerificationOptions::default(),
+      )
+      .map_err(Self::attestation_signature_error)?;
+
+    Self::check_timeframe_attestation_claims(credential, issuer, decoded, validity_timeframe)
+  }
+
+  #[cfg(feature = "revocation-bitmap")]
+  fn attestation_signature_error(err: identity_document::Error) -> JwtValidationError {
+    match err {
+      identity_document::Error::JwsVerificationError(source) => JwtValidationError::Signature {
+        source,
+        signer_ctx: SignerContext::Issuer,
+      },
+      err => JwtValidationError::MethodDataLookupError {
+        source: Some(err.into()),
+        message: "could not find the method that signed the timeframe attestation",
+        signer_ctx: SignerContext::Issuer,
+      },
+    }
+  }
+
+  #[cfg(feature = "revocation-bitmap")]
+  fn check_timeframe_attestation_claims<DOC, T>(
+    credential: &Credential<T>,
+    issuer: &DOC,
+    decoded: identity_verification::jws::DecodedJws<'_>,
+    validity_timeframe: Option<Timestamp>,
+  ) -> ValidationUnitResult<crate::revocation::TimeframeAttestationClaims>
+  where
+    DOC: AsRef<identity_document::document::CoreDocument>,
+  {
+    use crate::revocation::TimeframeAttestationClaims;
+
+    let invalid = |message: &str| JwtValidationError::InvalidStatus(crate::Error::InvalidStatus(message.to_owned()));
+
+    if Self::validity_timeframe_status(credential, crate::validator::StatusCheck::Strict)?.is_none() {
+      return Err(invalid("the credential has no RevocationTimeframe2024 status"));
+    }
+    if decoded.protected.typ() != Some(TimeframeAttestationClaims::TYP) {
+      return Err(invalid("the attestation is not a validity timeframe attestation"));
+    }
+    let claims: TimeframeAttestationClaims = serde_json::from_slice(&decoded.claims)
+      .map_err(|err| invalid(&format!("invalid validity timeframe attestation; {err}")))?;
+
+    // The attestation must be made by the issuer of the credential for this very credential.
+    if claims.issuer() != credential.issuer.url() || claims.issuer().as_str() != issuer.as_ref().id().as_str() {
+      return Err(JwtValidationError::DocumentMismatch(SignerContext::Issuer));
+    }
+    if credential.id.as_ref() != Some(claims.credential_id()) {
+      return Err(invalid("the attestation is bound to another credential"));
+    }
+
+    Self::check_validity_timeframe(
+      claims.start_validity_timeframe(),
+      claims.end_validity_timeframe(),
+      validity_timeframe,
+    )?;
+    Ok(claims)
+  }
+
+  /// Returns the `RevocationTimeframe2024` status of the credential if set.
+  #[cfg(feature = "revocation-bitmap")]
+  fn validity_timeframe_status<T>(
+    credential: &Credential<T>,
+    status_check: crate::validator::StatusCheck,
+  ) -> ValidationUnitResult<Option<crate::revocation::RevocationTimeframeStatus>> {
+    use crate::revocation::RevocationTimeframeStatus;
+
+    if status_check == crate::validator::StatusCheck::SkipAll {
+      return Ok(None);
+    }
+
+    match &credential.credential_status {
+      None => Ok(None),
+      Some(status) if status.type_ == RevocationTimeframeStatus::TYPE => RevocationTimeframeStatus::try_from(status)
+        .map(Some)
+        .map_err(JwtValidationError::InvalidStatus),
+      Some(_) if status_check == crate::validator::StatusCheck::SkipUnsupported => Ok(None),
+      Some(status) => Err(JwtValidationError::InvalidStatus(crate::Error::InvalidStatus(format!(
+        "unsupported type '{}'",
+        status.type_
+      )))),
+    }
+  }
+
+  /// Checks that `validity_timeframe`, or now if unset, falls within the timeframe from `start` to `end`.
+  #[cfg(feature = "revocation-bitmap")]
+  pub(crate) fn check_validity_timeframe(
+    start: Timestamp,
+    end: Timestamp,
+    validity_timeframe: Option<Timestamp>,
+  ) -> ValidationUnitResult {
+    let timeframe = validity_timeframe.unwrap_or(Timestamp::now_utc());
+
+    if timeframe >= start && timeframe <= end {
+      Ok(())
+    } else {
+      Err(JwtValidationError::OutsideTimeframe)
+    }
+  }
+
   /// Utility for extracting the issuer field of a [`Credential`] as a DID.
   ///
   /// # Errors
@@ -202,7 +582,28 @@ impl JwtCredentialValidatorUtils {
     D: DID,
     <D as FromStr>::Err: std::error::Error + Send + Sync + 'static,
   {
-    D::from_str(credential.issuer.url().as_str()).map_err(|err| JwtValidationError::SignerUrl {
+    Self::issuer_to_did(&credential.issuer)
+  }
+
+  /// Utility for extracting the issuer field of a [`CredentialV2`] as a DID.
+  ///
+  /// # Errors
+  ///
+  /// Fails if the issuer field is not a valid DID.
+  pub fn extract_issuer_v2<D, T>(credential: &CredentialV2<T>) -> std::result::Result<D, JwtValidationError>
+  where
+    D: DID,
+    <D as FromStr>::Err: std::error::Error + Send + Sync + 'static,
+  {
+    Self::issuer_to_did(&credential.issuer)
+  }
+
+  fn issuer_to_did<D>(issuer: &Issuer) -> std::result::Result<D, JwtValidationError>
+  where
+    D: DID,
+    <D as FromStr>::Err: std::error::Error + Send + Sync + 'static,
+  {
+    D::from_str(issuer.url().as_str()).map_err(|err| JwtValidationError::SignerUrl {
       signer_ctx: SignerContext::Issuer,
       source: err.into(),
     })
@@ -232,4 +633,36 @@ impl JwtCredentialValidatorUtils {
       source: err.into(),
     })
   }
+
+  /// Determines the [`VcDataModelVersion`] of a credential in JWT representation without verifying it.
+  ///
+  /// VC Data Model v2.0 credentials are the claims set of the JWT, whereas v1.1 credentials are mapped to the
+  /// `vc` claim.
+  ///
+  /// # Errors
+  ///
+  /// If the JWT decoding fails or the `@context` does not start with a known base context.
+  pub fn data_model_version(credential: &Jwt) -> std::result::Result<VcDataModelVersion, JwtValidationError> {
+    let validation_item = Decoder::new()
+      .decode_compact_serialization(credential.as_str().as_bytes(), None)
+      .map_err(JwtValidationError::JwsDecodingError)?;
+
+    let claims: Object = Object::from_json_slice(&validation_item.claims()).map_err(|err| {
+      JwtValidationError::CredentialStructure(crate::Error::JwtClaimsSetDeserializationError(err.into()))
+    })?;
+    let context: Option<&Value> = claims.get("@context").or_else(|| {
+      claims
+        .get("vc")
+        .and_then(Value::as_object)
+        .and_then(|vc| vc.get("@context"))
+    });
+
+    context
+      .cloned()
+      .and_then(|context| OneOrMany::<Context>::from_json_value(context).ok())
+      .and_then(|context| VcDataModelVersion::from_context(context.iter()))
+      .ok_or(JwtValidationError::CredentialStructure(
+        crate::Error::MissingBaseContext,
+      ))
+  }
 }
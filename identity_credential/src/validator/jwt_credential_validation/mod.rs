@@ -9,6 +9,8 @@ mod jwt_credential_validator;
 #[cfg(feature = "hybrid")]
 mod jwt_credential_validator_hybrid;
 mod jwt_credential_validator_utils;
+#[cfg(feature = "status-list-2021")]
+mod status_list_resolver;
 
 pub use decoded_jwt_credential::*;
 pub use error::*;
@@ -17,3 +19,5 @@ pub use jwt_credential_validator::*;
 #[cfg(feature = "hybrid")]
 pub use jwt_credential_validator_hybrid::*;
 pub use jwt_credential_validator_utils::*;
+#[cfg(feature = "status-list-2021")]
+pub use status_list_resolver::*;
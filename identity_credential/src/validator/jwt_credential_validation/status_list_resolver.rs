@@ -0,0 +1,528 @@
+// Copyright 2020-2024 IOTA Stiftung, Fondazione Links
+// SPDX-License-Identifier: Apache-2.0
+
+use std::collections::HashMap;
+use std::fmt::Debug;
+use std::sync::Mutex;
+
+use async_trait::async_trait;
+use identity_core::common::Duration;
+use identity_core::common::Object;
+use identity_core::common::Timestamp;
+use identity_core::common::Url;
+use identity_core::convert::FromJson;
+use identity_document::document::CoreDocument;
+use identity_verification::jws::Decoder;
+use identity_verification::jws::JwsVerifier;
+
+use super::CompoundCredentialValidationError;
+use super::JwtCredentialValidationOptions;
+use super::JwtCredentialValidator;
+use super::JwtCredentialValidatorUtils;
+use super::JwtValidationError;
+use super::VersionedDecodedJwtCredential;
+use crate::credential::Credential;
+use crate::credential::CredentialJwtClaims;
+use crate::credential::Jwt;
+use crate::credential::VcDataModelVersion;
+use crate::revocation::status_list_2021::StatusList2021Credential;
+use crate::revocation::status_list_2021::StatusList2021Entry;
+use crate::validator::FailFast;
+use crate::validator::StatusCheck;
+
+/// Error returned by a [`StatusListClient`].
+pub type StatusListClientError = Box<dyn std::error::Error + Send + Sync + 'static>;
+
+/// A client used by the [`StatusListResolver`] to fetch the status list credential published at a URL.
+#[async_trait]
+pub trait StatusListClient: Send + Sync {
+  /// Fetches the status list credential published at `url`, returning it in its compact JWT representation.
+  async fn fetch(&self, url: &Url) -> Result<String, StatusListClientError>;
+}
+
+/// A [`StatusListClient`] serving status list credentials from memory instead of fetching them over the network.
+///
+/// Useful in tests and for status lists that are distributed out of band.
+#[derive(Debug, Default)]
+pub struct LocalStatusListClient {
+  status_lists: Mutex<HashMap<Url, String>>,
+}
+
+impl LocalStatusListClient {
+  /// Creates a new, empty [`LocalStatusListClient`].
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  /// Serves `status_list_credential` at `url`, replacing any status list previously served there.
+  pub fn insert(&self, url: Url, status_list_credential: &Jwt) {
+    self
+      .status_lists
+      .lock()
+      .expect("the status list client lock is never poisoned")
+      .insert(url, status_list_credential.as_str().to_owned());
+  }
+}
+
+#[async_trait]
+impl StatusListClient for LocalStatusListClient {
+  async fn fetch(&self, url: &Url) -> Result<String, StatusListClientError> {
+    self
+      .status_lists
+      .lock()
+      .expect("the status list client lock is never poisoned")
+      .get(url)
+      .cloned()
+      .ok_or_else(|| format!("no status list credential is available at `{url}`").into())
+  }
+}
+
+#[cfg(feature = "status-list-2021-fetch")]
+mod __fetch_status_list {
+  use super::StatusListClient;
+  use super::StatusListClientError;
+  use async_trait::async_trait;
+  use futures::StreamExt;
+  use identity_core::common::Url;
+
+  /// The maximum size of a status list credential fetched with [`reqwest::Client`].
+  const MAX_STATUS_LIST_SIZE: usize = 4 * 1_048_576;
+
+  #[async_trait]
+  impl StatusListClient for reqwest::Client {
+    /// Fetches the status list credential via a GET request at `url`.
+    ///
+    /// The maximum size of the status list credential that can be retrieved with this client is 4 MiB.
+    async fn fetch(&self, url: &Url) -> Result<String, StatusListClientError> {
+      let mut stream = self
+        .get(url.to_string())
+        .send()
+        .await?
+        .error_for_status()?
+        .bytes_stream();
+
+      let mut body: Vec<u8> = Vec::new();
+      while let Some(item) = stream.next().await {
+        body.extend(item?);
+        if body.len() > MAX_STATUS_LIST_SIZE {
+          return Err("status list credential can not exceed 4 MiB".into());
+        }
+      }
+      Ok(String::from_utf8(body)?)
+    }
+  }
+}
+
+/// A validator able to verify the JWS of a credential issued as a JWT.
+///
+/// Implemented by [`JwtCredentialValidator`] and, with the `hybrid` feature,
+/// [`JwtCredentialValidatorHybrid`](crate::validator::JwtCredentialValidatorHybrid), so that the
+/// [`StatusListResolver`] supports traditional, PQ and hybrid signatures.
+pub trait JwtCredentialSignatureValidator {
+  /// Decodes and validates a credential issued as a JWT according to `options`.
+  fn validate_credential_jwt<DOC, T>(
+    &self,
+    credential_jwt: &Jwt,
+    issuer: &DOC,
+    options: &JwtCredentialValidationOptions,
+    fail_fast: FailFast,
+  ) -> Result<VersionedDecodedJwtCredential<T>, CompoundCredentialValidationError>
+  where
+    T: ToOwned<Owned = T> + serde::Serialize + serde::de::DeserializeOwned,
+    DOC: AsRef<CoreDocument>;
+}
+
+impl<V: JwsVerifier> JwtCredentialSignatureValidator for JwtCredentialValidator<V> {
+  fn validate_credential_jwt<DOC, T>(
+    &self,
+    credential_jwt: &Jwt,
+    issuer: &DOC,
+    options: &JwtCredentialValidationOptions,
+    fail_fast: FailFast,
+  ) -> Result<VersionedDecodedJwtCredential<T>, CompoundCredentialValidationError>
+  where
+    T: ToOwned<Owned = T> + serde::Serialize + serde::de::DeserializeOwned,
+    DOC: AsRef<CoreDocument>,
+  {
+    self.validate_any_version(credential_jwt, issuer, options, fail_fast)
+  }
+}
+
+#[cfg(feature = "hybrid")]
+impl<TRV: JwsVerifier, PQV: JwsVerifier> JwtCredentialSignatureValidator
+  for crate::validator::JwtCredentialValidatorHybrid<TRV, PQV>
+{
+  fn validate_credential_jwt<DOC, T>(
+    &self,
+    credential_jwt: &Jwt,
+    issuer: &DOC,
+    options: &JwtCredentialValidationOptions,
+    fail_fast: FailFast,
+  ) -> Result<VersionedDecodedJwtCredential<T>, CompoundCredentialValidationError>
+  where
+    T: ToOwned<Owned = T> + serde::Serialize + serde::de::DeserializeOwned,
+    DOC: AsRef<CoreDocument>,
+  {
+    self.validate_any_version(credential_jwt, issuer, options, fail_fast)
+  }
+}
+
+#[derive(Debug)]
+struct CachedStatusList {
+  status_list_credential: StatusList2021Credential,
+  expires_at: Timestamp,
+}
+
+/// Resolves the `StatusList2021Credential`s referenced by the `credentialStatus` of credentials.
+///
+/// Status list credentials are fetched through a [`StatusListClient`], their signature is validated against the DID
+/// Document of the issuer of the credential being checked and they are cached for a configurable time-to-live.
+///
+/// Use [`StatusListResolver::validation_options`] to check the status of a credential when validating it with a
+/// [`JwtCredentialValidator`].
+pub struct StatusListResolver<C, V> {
+  client: C,
+  validator: V,
+  ttl: Duration,
+  cache: Mutex<HashMap<Url, CachedStatusList>>,
+}
+
+impl<C, V> Debug for StatusListResolver<C, V> {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    f.debug_struct("StatusListResolver").finish_non_exhaustive()
+  }
+}
+
+impl<C, V> StatusListResolver<C, V>
+where
+  C: StatusListClient,
+  V: JwtCredentialSignatureValidator,
+{
+  /// Default time-to-live of resolved status list credentials.
+  pub const DEFAULT_TTL_SECONDS: u32 = 300;
+
+  /// Creates a new [`StatusListResolver`] fetching status lists with `client` and validating them with `validator`.
+  pub fn new(client: C, validator: V) -> Self {
+    Self {
+      client,
+      validator,
+      ttl: Duration::seconds(Self::DEFAULT_TTL_SECONDS),
+      cache: Mutex::new(HashMap::new()),
+    }
+  }
+
+  /// Sets for how long a resolved status list credential is reused before being fetched again.
+  pub fn with_ttl(mut self, ttl: Duration) -> Self {
+    self.ttl = ttl;
+    self
+  }
+
+  /// Removes all the cached status list credentials.
+  pub fn clear_cache(&self) {
+    self.lock_cache().clear();
+  }
+
+  /// Fetches the status list credential published at `status_list_url` and validates it against the DID Document of
+  /// `issuer`, unless a previously resolved one is still cached.
+  pub async fn resolve<DOC>(
+    &self,
+    status_list_url: &Url,
+    issuer: &DOC,
+  ) -> Result<StatusList2021Credential, JwtValidationError>
+  where
+    DOC: AsRef<CoreDocument>,
+  {
+    let now: Timestamp = Timestamp::now_utc();
+    if let Some(cached) = self.lock_cache().get(status_list_url) {
+      if cached.expires_at > now {
+        return Ok(cached.status_list_credential.clone());
+      }
+    }
+
+    let jwt: Jwt = self
+      .client
+      .fetch(status_list_url)
+      .await
+      .map(|jwt| Jwt::new(jwt.trim().to_owned()))
+      .map_err(JwtValidationError::StatusListResolutionError)?;
+    let options: JwtCredentialValidationOptions =
+      JwtCredentialValidationOptions::default().status_check(StatusCheck::SkipAll);
+    let credential: Credential = self
+      .validator
+      .validate_credential_jwt(&jwt, issuer, &options, FailFast::FirstError)
+      .map_err(|err| JwtValidationError::StatusListResolutionError(err.into()))?
+      .into_v1_1()
+      .ok_or_else(|| {
+        JwtValidationError::StatusListResolutionError(
+          format!("the status list credential published at `{status_list_url}` is not a VC Data Model v1.1 credential")
+            .into(),
+        )
+      })?
+      .credential;
+    let status_list_credential: StatusList2021Credential = StatusList2021Credential::try_from(credential)
+      .map_err(|err| JwtValidationError::StatusListResolutionError(err.into()))?;
+    if status_list_credential.id() != Some(status_list_url) {
+      return Err(JwtValidationError::StatusListResolutionError(
+        format!("the status list credential published at `{status_list_url}` has a different id").into(),
+      ));
+    }
+
+    if let Some(expires_at) = now.checked_add(self.ttl) {
+      self.lock_cache().insert(
+        status_list_url.clone(),
+        CachedStatusList {
+          status_list_credential: status_list_credential.clone(),
+          expires_at,
+        },
+      );
+    }
+    Ok(status_list_credential)
+  }
+
+  /// Returns a copy of `options` with the status list credential referenced by the `credentialStatus` of
+  /// `credential_jwt` added to [`JwtCredentialValidationOptions::status_list_credentials`], so that its
+  /// `StatusList2021` status is checked when validating the credential with `options`.
+  ///
+  /// The status list credential is resolved with [`StatusListResolver::resolve`] against the DID Document of
+  /// `issuer`. Nothing is resolved if the credential has no `StatusList2021` status or if
+  /// [`JwtCredentialValidationOptions::status`] is [`StatusCheck::SkipAll`].
+  ///
+  /// # Warning
+  ///
+  /// The credential itself is not validated.
+  pub async fn validation_options<DOC>(
+    &self,
+    credential_jwt: &Jwt,
+    issuer: &DOC,
+    options: &JwtCredentialValidationOptions,
+  ) -> Result<JwtCredentialValidationOptions, JwtValidationError>
+  where
+    DOC: AsRef<CoreDocument>,
+  {
+    if options.status == StatusCheck::SkipAll
+      || JwtCredentialValidatorUtils::data_model_version(credential_jwt)? != VcDataModelVersion::V1_1
+    {
+      return Ok(options.clone());
+    }
+
+    let validation_item = Decoder::new()
+      .decode_compact_serialization(credential_jwt.as_str().as_bytes(), None)
+      .map_err(JwtValidationError::JwsDecodingError)?;
+    let credential: Credential = CredentialJwtClaims::<'_, Object>::from_json_slice(&validation_item.claims())
+      .map_err(|err| {
+        JwtValidationError::CredentialStructure(crate::Error::JwtClaimsSetDeserializationError(err.into()))
+      })?
+      .try_into_credential()
+      .map_err(JwtValidationError::CredentialStructure)?;
+
+    let Some(status) = credential
+      .credential_status
+      .as_ref()
+      .filter(|status| status.type_ == StatusList2021Entry::TYPE)
+    else {
+      return Ok(options.clone());
+    };
+    let entry: StatusList2021Entry = StatusList2021Entry::try_from(status)
+      .map_err(|err| JwtValidationError::InvalidStatus(crate::Error::InvalidStatus(err.to_string())))?;
+    let status_list_credential: StatusList2021Credential = self.resolve(entry.status_list_credential(), issuer).await?;
+
+    Ok(options.clone().status_list_credential(status_list_credential))
+  }
+
+  fn lock_cache(&self) -> std::sync::MutexGuard<'_, HashMap<Url, CachedStatusList>> {
+    // The cache is never left in an inconsistent state, so a poisoned lock can be recovered.
+    self.cache.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use std::sync::atomic::AtomicUsize;
+  use std::sync::atomic::Ordering;
+
+  use crypto::signatures::ed25519::SecretKey;
+  use identity_core::common::Object;
+  use identity_did::DID;
+  use identity_eddsa_verifier::EdDSAJwsVerifier;
+  use identity_verification::jws::CharSet;
+  use identity_verification::jws::CompactJwsEncoder;
+  use identity_verification::jws::CompactJwsEncodingOptions;
+  use identity_verification::jws::JwsAlgorithm;
+  use identity_verification::jws::JwsHeader;
+  use identity_verification::MethodData;
+  use identity_verification::VerificationMethod;
+
+  use super::*;
+  use crate::credential::CredentialBuilder;
+  use crate::credential::Issuer;
+  use crate::credential::Subject;
+  use crate::revocation::status_list_2021::StatusList2021;
+  use crate::revocation::status_list_2021::StatusList2021CredentialBuilder;
+  use crate::revocation::status_list_2021::StatusPurpose;
+  use crate::validator::test_utils::generate_jwk_document_with_keys;
+
+  const STATUS_LIST_URL: &str = "https://example.com/status/1";
+
+  #[derive(Default)]
+  struct CountingClient {
+    inner: LocalStatusListClient,
+    fetches: AtomicUsize,
+  }
+
+  #[async_trait]
+  impl StatusListClient for CountingClient {
+    async fn fetch(&self, url: &Url) -> Result<String, StatusListClientError> {
+      self.fetches.fetch_add(1, Ordering::SeqCst);
+      self.inner.fetch(url).await
+    }
+  }
+
+  fn sign(credential: &Credential, document: &CoreDocument, fragment: &str, secret_key: &SecretKey) -> Jwt {
+    let payload: String = credential.serialize_jwt(None).unwrap();
+    let method: &VerificationMethod = document.resolve_method(fragment, None).unwrap();
+    let MethodData::PublicKeyJwk(ref jwk) = method.data() else {
+      panic!("not a jwk");
+    };
+    let alg: JwsAlgorithm = jwk.alg().unwrap_or("").parse().unwrap();
+    let mut header: JwsHeader = JwsHeader::new();
+    header.set_alg(alg);
+    header.set_kid(method.id().to_string());
+    let encoding_options: CompactJwsEncodingOptions = CompactJwsEncodingOptions::NonDetached {
+      charset_requirements: CharSet::Default,
+    };
+    let encoder: CompactJwsEncoder<'_> =
+      CompactJwsEncoder::new_with_options(payload.as_bytes(), &header, encoding_options).unwrap();
+    let signature: [u8; 64] = secret_key.sign(encoder.signing_input()).to_bytes();
+    Jwt::new(encoder.into_jws(&signature))
+  }
+
+  fn status_list_credential(issuer: &CoreDocument, revoked: Option<usize>) -> Credential {
+    let mut status_list: StatusList2021 = StatusList2021::default();
+    if let Some(index) = revoked {
+      status_list.set(index, true).unwrap();
+    }
+    StatusList2021CredentialBuilder::new(status_list)
+      .purpose(StatusPurpose::Revocation)
+      .subject_id(Url::parse(STATUS_LIST_URL).unwrap())
+      .issuer(Issuer::Url(Url::parse(issuer.id().as_str()).unwrap()))
+      .build()
+      .unwrap()
+      .into_inner()
+  }
+
+  fn credential(issuer: &CoreDocument, index: usize) -> Credential {
+    let entry: StatusList2021Entry = StatusList2021Entry::new(
+      Url::parse(STATUS_LIST_URL).unwrap(),
+      StatusPurpose::Revocation,
+      index,
+      None,
+    );
+    CredentialBuilder::default()
+      .id(Url::parse("https://example.edu/credentials/3732").unwrap())
+      .issuer(Url::parse(issuer.id().as_str()).unwrap())
+      .type_("UniversityDegreeCredential")
+      .subject(Subject::with_id(Url::parse("did:example:holder").unwrap()))
+      .status(entry)
+      .build()
+      .unwrap()
+  }
+
+  fn validate(
+    credential_jwt: &Jwt,
+    issuer: &CoreDocument,
+    options: &JwtCredentialValidationOptions,
+  ) -> Result<(), CompoundCredentialValidationError> {
+    JwtCredentialValidator::with_signature_verifier(EdDSAJwsVerifier::default())
+      .validate::<_, Object>(credential_jwt, issuer, options, FailFast::FirstError)
+      .map(|_| ())
+  }
+
+  #[tokio::test]
+  async fn validation_options_check_status_list_2021() {
+    let (issuer, secret_key, fragment) = generate_jwk_document_with_keys();
+    let client: CountingClient = CountingClient::default();
+    let status_list: Jwt = sign(
+      &status_list_credential(&issuer, Some(1)),
+      &issuer,
+      &fragment,
+      &secret_key,
+    );
+    client.inner.insert(Url::parse(STATUS_LIST_URL).unwrap(), &status_list);
+    let resolver = StatusListResolver::new(
+      client,
+      JwtCredentialValidator::with_signature_verifier(EdDSAJwsVerifier::default()),
+    );
+    let options: JwtCredentialValidationOptions = JwtCredentialValidationOptions::default();
+
+    let valid: Jwt = sign(&credential(&issuer, 0), &issuer, &fragment, &secret_key);
+    let valid_options = resolver.validation_options(&valid, &issuer, &options).await.unwrap();
+    validate(&valid, &issuer, &valid_options).unwrap();
+
+    let revoked: Jwt = sign(&credential(&issuer, 1), &issuer, &fragment, &secret_key);
+    let revoked_options = resolver.validation_options(&revoked, &issuer, &options).await.unwrap();
+    let err: CompoundCredentialValidationError = validate(&revoked, &issuer, &revoked_options).unwrap_err();
+    assert!(matches!(err.validation_errors[0], JwtValidationError::Revoked));
+
+    // The status list was fetched only once.
+    assert_eq!(resolver.client.fetches.load(Ordering::SeqCst), 1);
+    resolver.clear_cache();
+    resolver.validation_options(&valid, &issuer, &options).await.unwrap();
+    assert_eq!(resolver.client.fetches.load(Ordering::SeqCst), 2);
+  }
+
+  #[test]
+  fn status_list_2021_requires_a_status_list_credential() {
+    let (issuer, secret_key, fragment) = generate_jwk_document_with_keys();
+    let credential: Jwt = sign(&credential(&issuer, 0), &issuer, &fragment, &secret_key);
+
+    let err: CompoundCredentialValidationError =
+      validate(&credential, &issuer, &JwtCredentialValidationOptions::default()).unwrap_err();
+    assert!(matches!(err.validation_errors[0], JwtValidationError::InvalidStatus(_)));
+
+    let options: JwtCredentialValidationOptions =
+      JwtCredentialValidationOptions::default().status_check(StatusCheck::SkipUnsupported);
+    validate(&credential, &issuer, &options).unwrap();
+  }
+
+  #[tokio::test]
+  async fn status_list_must_be_signed_by_the_credential_issuer() {
+    let (issuer, secret_key, fragment) = generate_jwk_document_with_keys();
+    let (other_issuer, other_secret_key, other_fragment) = generate_jwk_document_with_keys();
+    let client: CountingClient = CountingClient::default();
+    let status_list: Jwt = sign(
+      &status_list_credential(&other_issuer, None),
+      &other_issuer,
+      &other_fragment,
+      &other_secret_key,
+    );
+    client.inner.insert(Url::parse(STATUS_LIST_URL).unwrap(), &status_list);
+    let resolver = StatusListResolver::new(
+      client,
+      JwtCredentialValidator::with_signature_verifier(EdDSAJwsVerifier::default()),
+    );
+
+    let credential: Jwt = sign(&credential(&issuer, 0), &issuer, &fragment, &secret_key);
+    let err: JwtValidationError = resolver
+      .validation_options(&credential, &issuer, &JwtCredentialValidationOptions::default())
+      .await
+      .unwrap_err();
+    assert!(matches!(err, JwtValidationError::StatusListResolutionError(_)));
+
+    // A status list credential of another issuer is not used to check the status.
+    let status_list_credential: StatusList2021Credential =
+      StatusList2021Credential::try_from(status_list_credential(&other_issuer, None)).unwrap();
+    let options: JwtCredentialValidationOptions =
+      JwtCredentialValidationOptions::default().status_list_credential(status_list_credential);
+    let err: CompoundCredentialValidationError = validate(&credential, &issuer, &options).unwrap_err();
+    assert!(matches!(err.validation_errors[0], JwtValidationError::InvalidStatus(_)));
+
+    // Skipping the status check does not fetch anything.
+    let options: JwtCredentialValidationOptions =
+      JwtCredentialValidationOptions::default().status_check(StatusCheck::SkipAll);
+    resolver
+      .validation_options(&credential, &issuer, &options)
+      .await
+      .unwrap();
+    assert_eq!(resolver.client.fetches.load(Ordering::SeqCst), 1);
+  }
+}
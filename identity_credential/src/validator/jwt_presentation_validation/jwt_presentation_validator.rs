@@ -4,7 +4,6 @@
 use identity_core::common::Object;
 use identity_core::common::Timestamp;
 use identity_core::common::Url;
-use identity_core::convert::FromJson;
 use identity_did::CoreDID;
 use identity_document::document::CoreDocument;
 use identity_verification::jws::DecodedJws;
@@ -84,11 +83,9 @@ where
         CompoundJwtPresentationValidationError::one_presentation_error(JwtValidationError::PresentationJwsError(err))
       })?;
 
-    let claims: PresentationJwtClaims<'_, CRED, T> = PresentationJwtClaims::from_json_slice(&decoded_jws.claims)
+    let claims: PresentationJwtClaims<'_, CRED, T> = PresentationJwtClaims::from_jwt_claims(&decoded_jws.claims)
       .map_err(|err| {
-        CompoundJwtPresentationValidationError::one_presentation_error(JwtValidationError::PresentationStructure(
-          crate::Error::JwtClaimsSetDeserializationError(err.into()),
-        ))
+        CompoundJwtPresentationValidationError::one_presentation_error(JwtValidationError::PresentationStructure(err))
       })?;
 
     // Verify that holder document matches holder in presentation.
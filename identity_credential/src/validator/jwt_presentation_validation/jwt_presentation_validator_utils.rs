@@ -2,7 +2,6 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use identity_core::common::Object;
-use identity_core::convert::FromJson;
 use identity_did::DID;
 use identity_verification::jws::Decoder;
 use std::str::FromStr;
@@ -32,9 +31,8 @@ impl JwtPresentationValidatorUtils {
       .map_err(JwtValidationError::JwsDecodingError)?;
 
     let claims: PresentationJwtClaims<'_, identity_core::common::Value, Object> =
-      PresentationJwtClaims::from_json_slice(&validation_item.claims()).map_err(|err| {
-        JwtValidationError::PresentationStructure(crate::Error::JwtClaimsSetDeserializationError(err.into()))
-      })?;
+      PresentationJwtClaims::from_jwt_claims(validation_item.claims())
+        .map_err(JwtValidationError::PresentationStructure)?;
 
     let holder: H = H::from_str(claims.iss.as_str()).map_err(|err| JwtValidationError::SignerUrl {
       signer_ctx: SignerContext::Holder,
@@ -0,0 +1,21 @@
+// Copyright 2020-2024 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+use std::collections::BTreeMap;
+
+use ciborium::Value;
+use identity_verification::jose::cose::CoseHeader;
+
+use crate::mdoc::MobileSecurityObject;
+
+/// The disclosed data elements of an mdoc whose issuer signature and digests were verified.
+#[non_exhaustive]
+#[derive(Debug, Clone)]
+pub struct DecodedMdoc {
+  /// The verified mobile security object.
+  pub mso: MobileSecurityObject,
+  /// The disclosed data elements, indexed by name space and element identifier.
+  pub name_spaces: BTreeMap<String, BTreeMap<String, Value>>,
+  /// The protected header of the issuer's COSE_Sign1 message.
+  pub header: Box<CoseHeader>,
+}
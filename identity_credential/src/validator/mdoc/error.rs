@@ -0,0 +1,50 @@
+// Copyright 2020-2024 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::validator::JwtValidationError;
+
+/// An error associated with validating mdocs.
+#[derive(Debug, thiserror::Error, strum::IntoStaticStr)]
+#[non_exhaustive]
+pub enum MdocValidationError {
+  /// The mdoc or its mobile security object could not be decoded.
+  #[error("could not decode mdoc")]
+  Decoding(#[source] crate::Error),
+
+  /// The issuer's signature could not be verified.
+  #[error("invalid issuer signature")]
+  IssuerSignature(#[source] JwtValidationError),
+
+  /// The mobile security object uses a digest algorithm other than SHA-256.
+  #[error("unsupported digest algorithm `{0}`")]
+  UnsupportedDigestAlgorithm(String),
+
+  /// A disclosed data element does not match the digest signed by the issuer.
+  #[error("the data element {name_space}/{element_identifier} does not match the signed digest")]
+  DigestMismatch {
+    /// The name space of the data element.
+    name_space: String,
+    /// The identifier of the data element.
+    element_identifier: String,
+  },
+
+  /// The document type does not match the one signed by the issuer.
+  #[error("the document type does not match the mobile security object")]
+  DocTypeMismatch,
+
+  /// The mdoc is expired or expires earlier than required.
+  #[error("the mdoc is expired or expires earlier than required")]
+  ExpirationDate,
+
+  /// The mdoc is not yet valid or becomes valid later than required.
+  #[error("the mdoc is not yet valid or becomes valid later than required")]
+  IssuanceDate,
+
+  /// The document does not include a device signature.
+  #[error("the document does not include a device signature")]
+  MissingDeviceSignature,
+
+  /// The device signature could not be verified using the device key of the mobile security object.
+  #[error("invalid device signature")]
+  DeviceSignature(#[source] identity_verification::jose::error::Error),
+}
@@ -0,0 +1,46 @@
+// Copyright 2020-2024 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+use identity_core::common::Timestamp;
+use identity_document::verifiable::JwsVerificationOptions;
+
+/// Criteria for validating an mdoc.
+#[derive(Debug, Default, Clone)]
+#[non_exhaustive]
+pub struct MdocValidationOptions {
+  /// Declares that the mdoc is **not** considered valid if it expires before this [`Timestamp`].
+  /// Uses the current datetime during validation if not set.
+  pub earliest_expiry_date: Option<Timestamp>,
+  /// Declares that the mdoc is **not** considered valid if it becomes valid after this [`Timestamp`].
+  /// Uses the current datetime during validation if not set.
+  pub latest_issuance_date: Option<Timestamp>,
+  /// Options which affect the verification of the issuer's signature.
+  pub verification_options: JwsVerificationOptions,
+}
+
+impl MdocValidationOptions {
+  /// Constructor that sets all options to their defaults.
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  /// Declare that the mdoc is **not** considered valid if it expires before this [`Timestamp`].
+  /// Uses the current datetime during validation if not set.
+  pub fn earliest_expiry_date(mut self, timestamp: Timestamp) -> Self {
+    self.earliest_expiry_date = Some(timestamp);
+    self
+  }
+
+  /// Declare that the mdoc is **not** considered valid if it becomes valid after this [`Timestamp`].
+  /// Uses the current datetime during validation if not set.
+  pub fn latest_issuance_date(mut self, timestamp: Timestamp) -> Self {
+    self.latest_issuance_date = Some(timestamp);
+    self
+  }
+
+  /// Set options which affect the verification of the issuer's signature.
+  pub fn verification_options(mut self, options: JwsVerificationOptions) -> Self {
+    self.verification_options = options;
+    self
+  }
+}
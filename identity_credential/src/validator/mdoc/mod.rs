@@ -0,0 +1,12 @@
+// Copyright 2020-2024 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+mod decoded_mdoc;
+mod error;
+mod mdoc_validation_options;
+mod validator;
+
+pub use decoded_mdoc::*;
+pub use error::*;
+pub use mdoc_validation_options::*;
+pub use validator::*;
@@ -0,0 +1,186 @@
+// Copyright 2020-2024 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+use std::collections::BTreeMap;
+
+use ciborium::Value;
+use identity_core::common::Timestamp;
+use identity_document::document::CoreDocument;
+use identity_verification::jose::cose::CoseSign1;
+use identity_verification::jose::cose::DecodedCoseSign1;
+use identity_verification::jwk::Jwk;
+use identity_verification::jws::JwsVerifier;
+
+use super::DecodedMdoc;
+use super::MdocValidationError;
+use super::MdocValidationOptions;
+use crate::mdoc::IssuerSigned;
+use crate::mdoc::MdocDocument;
+use crate::mdoc::MobileSecurityObject;
+use crate::mdoc::MSO_DIGEST_ALGORITHM;
+use crate::validator::CoseCredentialValidator;
+use crate::validator::JwtValidationError;
+use crate::validator::SignerContext;
+
+/// A type for validating mdocs issued in the format of
+/// [ISO/IEC 18013-5](https://www.iso.org/standard/69084.html).
+#[non_exhaustive]
+pub struct MdocValidator<V: JwsVerifier>(V);
+
+impl<V: JwsVerifier> MdocValidator<V> {
+  /// Create a new [`MdocValidator`] that delegates cryptographic signature verification to the given
+  /// `signature_verifier`.
+  pub fn with_signature_verifier(signature_verifier: V) -> Self {
+    Self(signature_verifier)
+  }
+
+  /// Validates a presented [`MdocDocument`], including the holder's device signature over the given
+  /// `session_transcript`. A [`DecodedMdoc`] containing the disclosed data elements is returned upon success.
+  ///
+  /// In addition to the checks of [`validate_issuer_signed`](Self::validate_issuer_signed), the document type must
+  /// match the one signed by the issuer and the device signature must be valid for the device key contained in the
+  /// mobile security object.
+  ///
+  /// # Errors
+  /// An error is returned whenever a validated condition is not satisfied.
+  pub fn validate_document<DOC>(
+    &self,
+    document: &MdocDocument,
+    issuer: &DOC,
+    session_transcript: &Value,
+    options: &MdocValidationOptions,
+  ) -> Result<DecodedMdoc, MdocValidationError>
+  where
+    DOC: AsRef<CoreDocument>,
+  {
+    let decoded: DecodedMdoc = self.validate_issuer_signed(document.issuer_signed(), issuer, options)?;
+    if decoded.mso.doc_type != document.doc_type() {
+      return Err(MdocValidationError::DocTypeMismatch);
+    }
+    self.verify_device_signature(document, &decoded.mso, session_transcript)?;
+    Ok(decoded)
+  }
+
+  /// Validates the disclosed data elements of an [`IssuerSigned`] structure. A [`DecodedMdoc`] is returned upon
+  /// success.
+  ///
+  /// The following properties are validated according to `options`:
+  /// - the issuer's signature on the mobile security object, whose `kid` must identify a verification method of
+  ///   `issuer`,
+  /// - the validity period,
+  /// - the digest of every disclosed data element.
+  ///
+  /// # Warning
+  /// The holder's device signature is not validated, as [`IssuerSigned`] does not include it. Use
+  /// [`validate_document`](Self::validate_document) to validate presentations.
+  ///
+  /// # Errors
+  /// An error is returned whenever a validated condition is not satisfied.
+  pub fn validate_issuer_signed<DOC>(
+    &self,
+    issuer_signed: &IssuerSigned,
+    issuer: &DOC,
+    options: &MdocValidationOptions,
+  ) -> Result<DecodedMdoc, MdocValidationError>
+  where
+    DOC: AsRef<CoreDocument>,
+  {
+    let message: CoseSign1 = CoseSign1::from_slice(issuer_signed.issuer_auth().as_bytes())
+      .map_err(|err| MdocValidationError::IssuerSignature(JwtValidationError::CoseDecodingError(err)))?;
+    let (public_key, _) = CoseCredentialValidator::<V>::parse_jwk(
+      &message,
+      std::slice::from_ref(issuer.as_ref()),
+      &options.verification_options,
+      SignerContext::Issuer,
+    )
+    .map_err(MdocValidationError::IssuerSignature)?;
+
+    let decoded: DecodedCoseSign1 = message.verify(&self.0, public_key).map_err(|err| {
+      MdocValidationError::IssuerSignature(JwtValidationError::Signature {
+        source: err,
+        signer_ctx: SignerContext::Issuer,
+      })
+    })?;
+    let mso: MobileSecurityObject =
+      MobileSecurityObject::from_slice(&decoded.payload).map_err(MdocValidationError::Decoding)?;
+
+    let now: Timestamp = Timestamp::now_utc();
+    if mso.validity_info.valid_until < options.earliest_expiry_date.unwrap_or(now) {
+      return Err(MdocValidationError::ExpirationDate);
+    }
+    if mso.validity_info.valid_from > options.latest_issuance_date.unwrap_or(now) {
+      return Err(MdocValidationError::IssuanceDate);
+    }
+
+    let name_spaces: BTreeMap<String, BTreeMap<String, Value>> = Self::verify_digests(issuer_signed, &mso)?;
+
+    Ok(DecodedMdoc {
+      mso,
+      name_spaces,
+      header: Box::new(decoded.protected),
+    })
+  }
+
+  /// Verifies the holder's device signature of `document` over the given `session_transcript` using the device key
+  /// of the `mso`.
+  ///
+  /// # Errors
+  /// Fails if the document does not include a device signature or if the signature is invalid.
+  pub fn verify_device_signature(
+    &self,
+    document: &MdocDocument,
+    mso: &MobileSecurityObject,
+    session_transcript: &Value,
+  ) -> Result<(), MdocValidationError> {
+    let device_signature = document
+      .device_signature()
+      .ok_or(MdocValidationError::MissingDeviceSignature)?;
+    let device_authentication: Vec<u8> = document
+      .device_authentication_bytes(session_transcript)
+      .map_err(MdocValidationError::Decoding)?;
+    let device_key: Jwk = Jwk::try_from(&mso.device_key).map_err(MdocValidationError::DeviceSignature)?;
+
+    CoseSign1::from_slice_detached(device_signature.as_bytes(), &device_authentication)
+      .and_then(|message| message.verify(&self.0, &device_key))
+      .map_err(MdocValidationError::DeviceSignature)?;
+    Ok(())
+  }
+
+  fn verify_digests(
+    issuer_signed: &IssuerSigned,
+    mso: &MobileSecurityObject,
+  ) -> Result<BTreeMap<String, BTreeMap<String, Value>>, MdocValidationError> {
+    if mso.digest_algorithm != MSO_DIGEST_ALGORITHM {
+      return Err(MdocValidationError::UnsupportedDigestAlgorithm(
+        mso.digest_algorithm.clone(),
+      ));
+    }
+
+    let mut name_spaces: BTreeMap<String, BTreeMap<String, Value>> = BTreeMap::new();
+    for (name_space, items) in issuer_signed.name_spaces() {
+      for item_bytes in items {
+        let item = item_bytes.item();
+        let mismatch = || MdocValidationError::DigestMismatch {
+          name_space: name_space.clone(),
+          element_identifier: item.element_identifier.clone(),
+        };
+
+        let digest: Vec<u8> = item_bytes.digest().map_err(MdocValidationError::Decoding)?;
+        let expected: &Vec<u8> = mso
+          .value_digests
+          .get(name_space)
+          .and_then(|digests| digests.get(&item.digest_id))
+          .ok_or_else(mismatch)?;
+        if &digest != expected {
+          return Err(mismatch());
+        }
+
+        name_spaces
+          .entry(name_space.clone())
+          .or_default()
+          .insert(item.element_identifier.clone(), item.element_value.clone());
+      }
+    }
+    Ok(name_spaces)
+  }
+}
@@ -5,17 +5,40 @@ use std::fmt::Debug;
 use std::fmt::Display;
 use std::str::FromStr;
 
+use crypto::hashes::sha::Sha256;
+use crypto::hashes::Digest;
+use identity_core::convert::BaseEncoding;
+use identity_core::convert::FromJson;
+use identity_core::convert::ToJson;
+use identity_jose::jwk::CompositeAlgId;
 use identity_jose::jwk::CompositeJwk;
-use identity_jose::jwu::decode_b64_json;
+use identity_jose::jwk::Jwk;
+use identity_jose::jwk::JwkType;
+use identity_jose::jwu;
 
+use crate::did_peer::SHA256_MULTIHASH_PREFIX;
 use crate::CoreDID;
 use crate::Error;
 use crate::DID;
 
+/// The multibase prefix of short form method-specific ids. Long form ids always start with `e`, the base64url
+/// encoding of a JSON object.
+const SHORT_FORM_PREFIX: char = 'z';
+/// The length of Ed25519 public keys.
+const ED25519_PUBLIC_KEY_LENGTH: usize = 32;
+/// The length of ML-DSA-44 public keys.
+const ML_DSA_44_PUBLIC_KEY_LENGTH: usize = 1312;
+/// The length of ML-DSA-65 public keys.
+const ML_DSA_65_PUBLIC_KEY_LENGTH: usize = 1952;
+
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, serde::Deserialize, serde::Serialize)]
 #[repr(transparent)]
 #[serde(into = "CoreDID", try_from = "CoreDID")]
 /// A type representing a `did:compositejwk` DID.
+///
+/// The long form encodes the JSON of its [`CompositeJwk`], while the short form only encodes the base58btc multibase
+/// SHA2-256 multihash of its canonical JSON, and its key must be obtained from elsewhere. Long forms created by this
+/// library encode the canonical JSON, but those encoding the key in any other member order are accepted as well.
 pub struct DIDCompositeJwk(CoreDID);
 
 impl DIDCompositeJwk {
@@ -27,10 +50,152 @@ impl DIDCompositeJwk {
     s.parse()
   }
 
-  /// Returns the JWK encoded inside this did:jwk.
+  /// Creates the long form [`DIDCompositeJwk`] of `composite_jwk`.
+  ///
+  /// The key is encoded with the JSON Canonicalization Scheme, so identical keys always yield identical DIDs.
+  ///
+  /// # Errors
+  ///
+  /// Fails if the component keys are not public keys of the types and algorithms identified by the `algId`.
+  pub fn new(composite_jwk: &CompositeJwk) -> Result<Self, Error> {
+    validate_composite_jwk(composite_jwk)?;
+    let json: Vec<u8> = composite_jwk
+      .to_jcs()
+      .map_err(|_| Error::Other("invalid did:compositejwk key"))?;
+    Self::parse(&format!("did:{}:{}", Self::METHOD, jwu::encode_b64(json)))
+  }
+
+  /// Creates the short form [`DIDCompositeJwk`] of `composite_jwk`.
+  ///
+  /// See [`DIDCompositeJwk::new`] for the accepted keys.
+  pub fn new_short_form(composite_jwk: &CompositeJwk) -> Result<Self, Error> {
+    Self::new(composite_jwk).and_then(|did| did.short_form())
+  }
+
+  /// Returns `true` if this is a short form did:compositejwk, which does not encode its key.
+  pub fn is_short_form(&self) -> bool {
+    self.method_id().starts_with(SHORT_FORM_PREFIX)
+  }
+
+  /// Returns the short form of this did:compositejwk, which is the same for every encoding of its key.
+  ///
+  /// # Errors
+  ///
+  /// Fails if the key cannot be canonicalized.
+  pub fn short_form(&self) -> Result<Self, Error> {
+    if self.is_short_form() {
+      return Ok(self.clone());
+    }
+    let json: Vec<u8> = self
+      .try_composite_jwk()?
+      .to_jcs()
+      .map_err(|_| Error::Other("invalid did:compositejwk key"))?;
+    let mut multihash: Vec<u8> = SHA256_MULTIHASH_PREFIX.to_vec();
+    multihash.extend_from_slice(&Sha256::digest(&json));
+
+    Self::parse(&format!(
+      "did:{}:{}",
+      Self::METHOD,
+      BaseEncoding::encode_multibase(&multihash, None)
+    ))
+  }
+
+  /// Returns the composite JWK encoded inside this did:compositejwk.
+  ///
+  /// # Panics
+  ///
+  /// Panics for short form DIDs, see [`DIDCompositeJwk::try_composite_jwk`].
+  #[deprecated(since = "1.3.1", note = "use `try_composite_jwk` instead")]
   pub fn composite_jwk(&self) -> CompositeJwk {
-    decode_b64_json(self.method_id()).expect("did:compositejwk encodes a valid compositeJwk")
+    self.try_composite_jwk().expect("did:compositejwk is not in short form")
   }
+
+  /// Returns the composite JWK encoded inside this did:compositejwk.
+  ///
+  /// # Errors
+  ///
+  /// Fails for short form DIDs, whose key must be obtained from elsewhere and checked with
+  /// [`DIDCompositeJwk::verify_composite_jwk`].
+  pub fn try_composite_jwk(&self) -> Result<CompositeJwk, Error> {
+    if self.is_short_form() {
+      return Err(Error::Other("a short form did:compositejwk does not encode its key"));
+    }
+    decode_composite_jwk(self.method_id())
+  }
+
+  /// Checks that `composite_jwk` is the key identified by this did:compositejwk, in either form.
+  pub fn verify_composite_jwk(&self, composite_jwk: &CompositeJwk) -> Result<(), Error> {
+    let matches: bool = if self.is_short_form() {
+      &Self::new_short_form(composite_jwk)? == self
+    } else {
+      &self.try_composite_jwk()? == composite_jwk
+    };
+    if matches {
+      Ok(())
+    } else {
+      Err(Error::Other("the composite JWK does not match the did:compositejwk"))
+    }
+  }
+}
+
+/// Decodes the key of a long form method-specific id, in any member order.
+fn decode_composite_jwk(method_id: &str) -> Result<CompositeJwk, Error> {
+  let json: Vec<u8> = jwu::decode_b64(method_id).map_err(|_| Error::InvalidMethodId)?;
+  let composite_jwk: CompositeJwk = CompositeJwk::from_json_slice(&json).map_err(|_| Error::InvalidMethodId)?;
+  validate_composite_jwk(&composite_jwk)?;
+  Ok(composite_jwk)
+}
+
+/// Checks the method-specific id of a did:compositejwk in either form.
+fn validate_method_id(method_id: &str) -> Result<(), Error> {
+  if !method_id.starts_with(SHORT_FORM_PREFIX) {
+    return decode_composite_jwk(method_id).map(|_| ());
+  }
+  let multihash: Vec<u8> = BaseEncoding::decode_multibase(method_id).map_err(|_| Error::InvalidMethodId)?;
+  if multihash.len() != SHA256_MULTIHASH_PREFIX.len() + 32 || !multihash.starts_with(&SHA256_MULTIHASH_PREFIX) {
+    return Err(Error::InvalidMethodId);
+  }
+  Ok(())
+}
+
+/// Checks that the component keys of `composite_jwk` are public keys of the types and algorithms of its `algId`.
+fn validate_composite_jwk(composite_jwk: &CompositeJwk) -> Result<(), Error> {
+  let (pq_alg, pq_public_key_length): (&str, usize) = match composite_jwk.alg_id() {
+    CompositeAlgId::IdMldsa44Ed25519Sha512 => ("ML-DSA-44", ML_DSA_44_PUBLIC_KEY_LENGTH),
+    CompositeAlgId::IdMldsa65Ed25519Sha512 => ("ML-DSA-65", ML_DSA_65_PUBLIC_KEY_LENGTH),
+  };
+
+  let traditional: &Jwk = composite_jwk.traditional_public_key();
+  let okp = traditional
+    .try_okp_params()
+    .map_err(|_| Error::Other("the traditional key of a did:compositejwk must be an OKP key"))?;
+  if okp.crv != "Ed25519" || matches!(traditional.alg(), Some(alg) if alg != "EdDSA") {
+    return Err(Error::Other(
+      "the traditional key does not match the did:compositejwk algId",
+    ));
+  }
+  if !traditional.is_public() || decoded_length(&okp.x) != Some(ED25519_PUBLIC_KEY_LENGTH) {
+    return Err(Error::Other("invalid traditional public key in did:compositejwk"));
+  }
+
+  let pq: &Jwk = composite_jwk.pq_public_key();
+  if pq.kty() != JwkType::MLDSA || matches!(pq.alg(), Some(alg) if alg != pq_alg) {
+    return Err(Error::Other(
+      "the post-quantum key does not match the did:compositejwk algId",
+    ));
+  }
+  let pq_params = pq
+    .try_pq_params()
+    .map_err(|_| Error::Other("invalid post-quantum public key in did:compositejwk"))?;
+  if !pq.is_public() || decoded_length(&pq_params.public) != Some(pq_public_key_length) {
+    return Err(Error::Other("invalid post-quantum public key in did:compositejwk"));
+  }
+
+  Ok(())
+}
+
+fn decoded_length(data: &str) -> Option<usize> {
+  jwu::decode_b64(data).ok().map(|bytes| bytes.len())
 }
 
 impl AsRef<CoreDID> for DIDCompositeJwk {
@@ -77,8 +242,88 @@ impl TryFrom<CoreDID> for DIDCompositeJwk {
     let Self::METHOD = value.method() else {
       return Err(Error::InvalidMethodName);
     };
-    decode_b64_json::<CompositeJwk>(value.method_id())
+    validate_method_id(value.method_id())
       .map(|_| Self(value))
       .map_err(|_| Error::InvalidMethodId)
   }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+  use identity_jose::jwk::JwkParams;
+  use identity_jose::jwk::JwkParamsOkp;
+
+  use super::*;
+
+  fn composite_jwk(alg_id: CompositeAlgId, pq_public_key_length: usize) -> CompositeJwk {
+    let mut okp: JwkParamsOkp = JwkParamsOkp::new();
+    okp.crv = "Ed25519".to_owned();
+    okp.x = jwu::encode_b64([1; ED25519_PUBLIC_KEY_LENGTH]);
+    let mut traditional: Jwk = Jwk::from_params(okp);
+    traditional.set_alg("EdDSA");
+
+    let mut params: JwkParams = JwkParams::new(JwkType::MLDSA);
+    if let JwkParams::MLDSA(params) = &mut params {
+      params.public = jwu::encode_b64(vec![2; pq_public_key_length]);
+    }
+    let mut pq: Jwk = Jwk::from_params(params);
+    pq.set_alg("ML-DSA-44");
+
+    CompositeJwk::new(alg_id, traditional, pq)
+  }
+
+  #[test]
+  fn test_canonical_encoding() {
+    let key: CompositeJwk = composite_jwk(CompositeAlgId::IdMldsa44Ed25519Sha512, ML_DSA_44_PUBLIC_KEY_LENGTH);
+    let did: DIDCompositeJwk = DIDCompositeJwk::new(&key).unwrap();
+    assert_eq!(did.try_composite_jwk().unwrap(), key);
+    assert_eq!(DIDCompositeJwk::new(&did.try_composite_jwk().unwrap()).unwrap(), did);
+
+    // The serde member order is not canonical, but such legacy DIDs identify the same key.
+    let non_canonical: DIDCompositeJwk = DIDCompositeJwk::parse(&format!(
+      "did:compositejwk:{}",
+      jwu::encode_b64(key.to_json_vec().unwrap())
+    ))
+    .unwrap();
+    assert_ne!(non_canonical, did);
+    assert_eq!(non_canonical.try_composite_jwk().unwrap(), key);
+    assert!(non_canonical.verify_composite_jwk(&key).is_ok());
+    assert_eq!(non_canonical.short_form().unwrap(), did.short_form().unwrap());
+  }
+
+  #[test]
+  fn test_short_form() {
+    let key: CompositeJwk = composite_jwk(CompositeAlgId::IdMldsa44Ed25519Sha512, ML_DSA_44_PUBLIC_KEY_LENGTH);
+    let did: DIDCompositeJwk = DIDCompositeJwk::new(&key).unwrap();
+    let short_form: DIDCompositeJwk = DIDCompositeJwk::new_short_form(&key).unwrap();
+    assert!(!did.is_short_form());
+    assert!(short_form.is_short_form());
+    assert_eq!(did.short_form().unwrap(), short_form);
+    assert_eq!(DIDCompositeJwk::parse(&short_form.to_string()).unwrap(), short_form);
+    assert!(short_form.to_string().len() < 100);
+    assert!(short_form.try_composite_jwk().is_err());
+
+    assert!(did.verify_composite_jwk(&key).is_ok());
+    assert!(short_form.verify_composite_jwk(&key).is_ok());
+    let other: CompositeJwk = composite_jwk(CompositeAlgId::IdMldsa44Ed25519Sha512, ML_DSA_44_PUBLIC_KEY_LENGTH - 1);
+    assert!(short_form.verify_composite_jwk(&other).is_err());
+  }
+
+  #[test]
+  fn test_invalid() {
+    // The post-quantum component does not match the algId.
+    let key: CompositeJwk = composite_jwk(CompositeAlgId::IdMldsa65Ed25519Sha512, ML_DSA_65_PUBLIC_KEY_LENGTH);
+    assert!(DIDCompositeJwk::new(&key).is_err());
+    // The post-quantum public key has the wrong length.
+    let key: CompositeJwk = composite_jwk(CompositeAlgId::IdMldsa44Ed25519Sha512, ML_DSA_65_PUBLIC_KEY_LENGTH);
+    assert!(DIDCompositeJwk::new(&key).is_err());
+
+    for did in [
+      "did:compositejwk:eyJ9",
+      "did:compositejwk:zQmd8CpeFPci817KDsbSAKWcXAE2mjvCQSasRewvbSF54",
+      "did:jwk:eyJrdHkiOiJPS1AiLCJjcnYiOiJYMjU1MTkiLCJ1c2UiOiJlbmMiLCJ4IjoiM3A3YmZYdDl3YlRUVzJIQzdPUTFOei1EUThoYmVHZE5yZngtRkctSUswOCJ9",
+    ] {
+      assert!(DIDCompositeJwk::parse(did).is_err(), "{did}");
+    }
+  }
+}
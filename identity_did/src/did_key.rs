@@ -0,0 +1,597 @@
+// Copyright 2020-2024 IOTA Stiftung, Fondazione Links
+// SPDX-License-Identifier: Apache-2.0
+
+use std::fmt::Debug;
+use std::fmt::Display;
+use std::str::FromStr;
+
+#[cfg(feature = "did-key-validation")]
+use bls12_381_plus::G2Affine;
+use identity_core::convert::BaseEncoding;
+use identity_jose::jwk::BlsCurve;
+use identity_jose::jwk::EcCurve;
+use identity_jose::jwk::EcxCurve;
+use identity_jose::jwk::EdCurve;
+use identity_jose::jwk::Jwk;
+use identity_jose::jwk::JwkParams;
+use identity_jose::jwk::JwkParamsEc;
+use identity_jose::jwk::JwkParamsOkp;
+use identity_jose::jwk::JwkType;
+use identity_jose::jws::JwsAlgorithm;
+use identity_jose::jwu;
+#[cfg(feature = "did-key-validation")]
+use p256::elliptic_curve::sec1::ToEncodedPoint;
+
+use crate::multicodec::decode_varint;
+use crate::multicodec::encode_varint;
+use crate::CoreDID;
+use crate::Error;
+use crate::DID;
+
+/// The length of a compressed BLS12-381 G2 point.
+const BLS12381G2_COMPRESSED_LENGTH: usize = 96;
+
+/// The type of a public key encoded in a `did:key` DID.
+///
+/// The ML-DSA and SLH-DSA multicodec codes are still provisional, so DIDs of these types may not be understood by
+/// other `did:key` implementations. P-256, secp256k1 and BLS12-381 G2 keys require the `did-key-validation` feature,
+/// without it DIDs of these types are rejected.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+#[allow(non_camel_case_types)]
+pub enum DIDKeyType {
+  /// An Ed25519 public key.
+  Ed25519,
+  /// An X25519 public key, used for key agreement only.
+  X25519,
+  /// A compressed P-256 public key.
+  P256,
+  /// A compressed secp256k1 public key.
+  Secp256k1,
+  /// A compressed BLS12-381 G2 public key.
+  Bls12381G2,
+  /// An ML-DSA-44 public key.
+  MlDsa44,
+  /// An ML-DSA-65 public key.
+  MlDsa65,
+  /// An ML-DSA-87 public key.
+  MlDsa87,
+  /// An SLH-DSA-SHA2-128s public key.
+  SlhDsaSha2_128s,
+  /// An SLH-DSA-SHAKE-128s public key.
+  SlhDsaShake_128s,
+  /// An SLH-DSA-SHA2-128f public key.
+  SlhDsaSha2_128f,
+  /// An SLH-DSA-SHAKE-128f public key.
+  SlhDsaShake_128f,
+  /// An SLH-DSA-SHA2-192s public key.
+  SlhDsaSha2_192s,
+  /// An SLH-DSA-SHAKE-192s public key.
+  SlhDsaShake_192s,
+  /// An SLH-DSA-SHA2-192f public key.
+  SlhDsaSha2_192f,
+  /// An SLH-DSA-SHAKE-192f public key.
+  SlhDsaShake_192f,
+  /// An SLH-DSA-SHA2-256s public key.
+  SlhDsaSha2_256s,
+  /// An SLH-DSA-SHAKE-256s public key.
+  SlhDsaShake_256s,
+  /// An SLH-DSA-SHA2-256f public key.
+  SlhDsaSha2_256f,
+  /// An SLH-DSA-SHAKE-256f public key.
+  SlhDsaShake_256f,
+}
+
+impl DIDKeyType {
+  const ALL: [Self; 20] = [
+    Self::Ed25519,
+    Self::X25519,
+    Self::P256,
+    Self::Secp256k1,
+    Self::Bls12381G2,
+    Self::MlDsa44,
+    Self::MlDsa65,
+    Self::MlDsa87,
+    Self::SlhDsaSha2_128s,
+    Self::SlhDsaShake_128s,
+    Self::SlhDsaSha2_128f,
+    Self::SlhDsaShake_128f,
+    Self::SlhDsaSha2_192s,
+    Self::SlhDsaShake_192s,
+    Self::SlhDsaSha2_192f,
+    Self::SlhDsaShake_192f,
+    Self::SlhDsaSha2_256s,
+    Self::SlhDsaShake_256s,
+    Self::SlhDsaSha2_256f,
+    Self::SlhDsaShake_256f,
+  ];
+
+  /// Returns the multicodec code of the public key type.
+  pub const fn multicodec(self) -> u64 {
+    match self {
+      Self::Ed25519 => 0xed,
+      Self::X25519 => 0xec,
+      Self::P256 => 0x1200,
+      Self::Secp256k1 => 0xe7,
+      Self::Bls12381G2 => 0xeb,
+      Self::MlDsa44 => 0x1210,
+      Self::MlDsa65 => 0x1211,
+      Self::MlDsa87 => 0x1212,
+      Self::SlhDsaSha2_128s => 0x1220,
+      Self::SlhDsaShake_128s => 0x1221,
+      Self::SlhDsaSha2_128f => 0x1222,
+      Self::SlhDsaShake_128f => 0x1223,
+      Self::SlhDsaSha2_192s => 0x1224,
+      Self::SlhDsaShake_192s => 0x1225,
+      Self::SlhDsaSha2_192f => 0x1226,
+      Self::SlhDsaShake_192f => 0x1227,
+      Self::SlhDsaSha2_256s => 0x1228,
+      Self::SlhDsaShake_256s => 0x1229,
+      Self::SlhDsaSha2_256f => 0x122a,
+      Self::SlhDsaShake_256f => 0x122b,
+    }
+  }
+
+  /// Returns the length of the encoded public key.
+  pub const fn public_key_length(self) -> usize {
+    match self {
+      Self::Ed25519 | Self::X25519 => 32,
+      Self::P256 | Self::Secp256k1 => 33,
+      Self::Bls12381G2 => BLS12381G2_COMPRESSED_LENGTH,
+      Self::MlDsa44 => 1312,
+      Self::MlDsa65 => 1952,
+      Self::MlDsa87 => 2592,
+      Self::SlhDsaSha2_128s | Self::SlhDsaShake_128s | Self::SlhDsaSha2_128f | Self::SlhDsaShake_128f => 32,
+      Self::SlhDsaSha2_192s | Self::SlhDsaShake_192s | Self::SlhDsaSha2_192f | Self::SlhDsaShake_192f => 48,
+      Self::SlhDsaSha2_256s | Self::SlhDsaShake_256s | Self::SlhDsaSha2_256f | Self::SlhDsaShake_256f => 64,
+    }
+  }
+
+  /// Returns the JWS algorithm used to sign with keys of this type, if it is implied by the type.
+  ///
+  /// X25519 keys cannot sign and BLS12-381 G2 keys are used with several proof algorithms.
+  pub fn alg(self) -> Option<JwsAlgorithm> {
+    match self {
+      Self::Ed25519 => Some(JwsAlgorithm::EdDSA),
+      Self::X25519 | Self::Bls12381G2 => None,
+      Self::P256 => Some(JwsAlgorithm::ES256),
+      Self::Secp256k1 => Some(JwsAlgorithm::ES256K),
+      Self::MlDsa44 => Some(JwsAlgorithm::ML_DSA_44),
+      Self::MlDsa65 => Some(JwsAlgorithm::ML_DSA_65),
+      Self::MlDsa87 => Some(JwsAlgorithm::ML_DSA_87),
+      Self::SlhDsaSha2_128s => Some(JwsAlgorithm::SLH_DSA_SHA2_128s),
+      Self::SlhDsaShake_128s => Some(JwsAlgorithm::SLH_DSA_SHAKE_128s),
+      Self::SlhDsaSha2_128f => Some(JwsAlgorithm::SLH_DSA_SHA2_128f),
+      Self::SlhDsaShake_128f => Some(JwsAlgorithm::SLH_DSA_SHAKE_128f),
+      Self::SlhDsaSha2_192s => Some(JwsAlgorithm::SLH_DSA_SHA2_192s),
+      Self::SlhDsaShake_192s => Some(JwsAlgorithm::SLH_DSA_SHAKE_192s),
+      Self::SlhDsaSha2_192f => Some(JwsAlgorithm::SLH_DSA_SHA2_192f),
+      Self::SlhDsaShake_192f => Some(JwsAlgorithm::SLH_DSA_SHAKE_192f),
+      Self::SlhDsaSha2_256s => Some(JwsAlgorithm::SLH_DSA_SHA2_256s),
+      Self::SlhDsaShake_256s => Some(JwsAlgorithm::SLH_DSA_SHAKE_256s),
+      Self::SlhDsaSha2_256f => Some(JwsAlgorithm::SLH_DSA_SHA2_256f),
+      Self::SlhDsaShake_256f => Some(JwsAlgorithm::SLH_DSA_SHAKE_256f),
+    }
+  }
+
+  /// Returns `true` if keys of this type are used for key agreement rather than signing.
+  pub const fn is_key_agreement(self) -> bool {
+    matches!(self, Self::X25519)
+  }
+
+  /// Returns the JWK key type of post-quantum keys.
+  const fn pq_jwk_type(self) -> Option<JwkType> {
+    match self {
+      Self::MlDsa44 | Self::MlDsa65 | Self::MlDsa87 => Some(JwkType::MLDSA),
+      Self::Ed25519 | Self::X25519 | Self::P256 | Self::Secp256k1 | Self::Bls12381G2 => None,
+      _ => Some(JwkType::SLHDSA),
+    }
+  }
+
+  fn from_multicodec(code: u64) -> Option<Self> {
+    Self::ALL.into_iter().find(|key_type| key_type.multicodec() == code)
+  }
+
+  fn from_alg(alg: &JwsAlgorithm) -> Option<Self> {
+    Self::ALL
+      .into_iter()
+      .find(|key_type| key_type.alg().as_ref() == Some(alg))
+  }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, serde::Deserialize, serde::Serialize)]
+#[repr(transparent)]
+#[serde(into = "CoreDID", try_from = "CoreDID")]
+/// A type representing a `did:key` DID.
+///
+/// The method-specific id is the base58btc multibase encoding of a multicodec prefixed public key, see
+/// [`DIDKeyType`] for the supported key types.
+pub struct DIDKey(CoreDID);
+
+impl DIDKey {
+  /// [`DIDKey`]'s method.
+  pub const METHOD: &'static str = "key";
+
+  /// Tries to parse a [`DIDKey`] from a string.
+  pub fn parse(s: &str) -> Result<Self, Error> {
+    s.parse()
+  }
+
+  /// Creates a [`DIDKey`] from the encoded public key of the given type.
+  ///
+  /// Elliptic curve keys must be given in compressed form.
+  pub fn new(key_type: DIDKeyType, public_key: &[u8]) -> Result<Self, Error> {
+    let mut bytes: Vec<u8> = encode_varint(key_type.multicodec());
+    bytes.extend_from_slice(public_key);
+    Self::parse(&format!(
+      "did:{}:{}",
+      Self::METHOD,
+      BaseEncoding::encode_multibase(&bytes, None)
+    ))
+  }
+
+  /// Creates a [`DIDKey`] from a public JWK of one of the [`DIDKeyType`]s.
+  ///
+  /// The `alg` of ML-DSA and SLH-DSA keys is required to determine their parameter set.
+  pub fn from_jwk(jwk: &Jwk) -> Result<Self, Error> {
+    if !jwk.is_public() {
+      return Err(Error::Other("did:key requires a public key"));
+    }
+    let (key_type, public_key) = match jwk.params() {
+      JwkParams::Okp(params) => match (params.try_ed_curve(), params.try_ecx_curve()) {
+        (Ok(EdCurve::Ed25519), _) => (DIDKeyType::Ed25519, decode(&params.x)?),
+        (_, Ok(EcxCurve::X25519)) => (DIDKeyType::X25519, decode(&params.x)?),
+        _ => return Err(Error::Other("unsupported did:key curve")),
+      },
+      JwkParams::Ec(params) => {
+        let key_type: DIDKeyType = match (params.try_ec_curve(), params.try_bls_curve()) {
+          (Ok(EcCurve::P256), _) => DIDKeyType::P256,
+          (Ok(EcCurve::Secp256K1), _) => DIDKeyType::Secp256k1,
+          (_, Ok(BlsCurve::BLS12381G2)) => DIDKeyType::Bls12381G2,
+          _ => return Err(Error::Other("unsupported did:key curve")),
+        };
+        let public_key: Vec<u8> = compress_point(key_type, &decode(&params.x)?, &decode(&params.y)?)
+          .ok_or(Error::Other("invalid elliptic curve point"))?;
+        (key_type, public_key)
+      }
+      JwkParams::MLDSA(params) | JwkParams::SLHDSA(params) => {
+        let key_type: DIDKeyType = jwk
+          .alg()
+          .and_then(|alg| JwsAlgorithm::from_str(alg).ok())
+          .and_then(|alg| DIDKeyType::from_alg(&alg))
+          .filter(|key_type| key_type.pq_jwk_type() == Some(jwk.kty()))
+          .ok_or(Error::Other("post-quantum JWK without a supported alg"))?;
+        (key_type, decode(&params.public)?)
+      }
+      _ => return Err(Error::Other("unsupported did:key key type")),
+    };
+
+    Self::new(key_type, &public_key)
+  }
+
+  /// Returns the type of the public key encoded inside this did:key.
+  pub fn key_type(&self) -> DIDKeyType {
+    self.decode().0
+  }
+
+  /// Returns the public key encoded inside this did:key, with elliptic curve points in compressed form.
+  pub fn public_key(&self) -> Vec<u8> {
+    self.decode().1
+  }
+
+  /// Returns the public key encoded inside this did:key as a JWK.
+  ///
+  /// The `alg` of the JWK is set if it is implied by the [`DIDKeyType`].
+  pub fn jwk(&self) -> Jwk {
+    let (key_type, public_key) = self.decode();
+    let params: JwkParams = match key_type {
+      DIDKeyType::Ed25519 | DIDKeyType::X25519 => {
+        let mut params: JwkParamsOkp = JwkParamsOkp::new();
+        params.crv = match key_type {
+          DIDKeyType::Ed25519 => EdCurve::Ed25519.name(),
+          _ => EcxCurve::X25519.name(),
+        }
+        .to_owned();
+        params.x = jwu::encode_b64(public_key);
+        params.into()
+      }
+      DIDKeyType::P256 | DIDKeyType::Secp256k1 | DIDKeyType::Bls12381G2 => {
+        let (x, y) = decompress_point(key_type, &public_key).expect("did:key encodes a valid point");
+        let mut params: JwkParamsEc = JwkParamsEc::new();
+        params.crv = match key_type {
+          DIDKeyType::P256 => EcCurve::P256.name(),
+          DIDKeyType::Secp256k1 => EcCurve::Secp256K1.name(),
+          _ => BlsCurve::BLS12381G2.name(),
+        }
+        .to_owned();
+        params.x = jwu::encode_b64(x);
+        params.y = jwu::encode_b64(y);
+        params.into()
+      }
+      key_type => {
+        let kty: JwkType = key_type
+          .pq_jwk_type()
+          .expect("the remaining key types are post-quantum");
+        let mut params: JwkParams = JwkParams::new(kty);
+        if let JwkParams::MLDSA(params) | JwkParams::SLHDSA(params) = &mut params {
+          params.public = jwu::encode_b64(public_key);
+        }
+        params
+      }
+    };
+
+    let mut jwk: Jwk = Jwk::from_params(params);
+    if let Some(alg) = key_type.alg() {
+      jwk.set_alg(alg.name());
+    }
+    jwk
+  }
+
+  fn decode(&self) -> (DIDKeyType, Vec<u8>) {
+    decode_method_id(self.method_id()).expect("did:key encodes a valid public key")
+  }
+}
+
+/// Decodes and validates the method-specific id of a `did:key`.
+fn decode_method_id(method_id: &str) -> Option<(DIDKeyType, Vec<u8>)> {
+  if !method_id.starts_with('z') {
+    return None;
+  }
+  let bytes: Vec<u8> = BaseEncoding::decode_multibase(method_id).ok()?;
+  let (code, public_key) = decode_varint(&bytes)?;
+  let key_type: DIDKeyType = DIDKeyType::from_multicodec(code)?;
+  if public_key.len() != key_type.public_key_length() {
+    return None;
+  }
+  if matches!(
+    key_type,
+    DIDKeyType::P256 | DIDKeyType::Secp256k1 | DIDKeyType::Bls12381G2
+  ) {
+    decompress_point(key_type, public_key)?;
+  }
+  Some((key_type, public_key.to_vec()))
+}
+
+fn decode(value: &str) -> Result<Vec<u8>, Error> {
+  jwu::decode_b64(value).map_err(|_| Error::Other("invalid JWK encoding"))
+}
+
+/// Returns the affine coordinates of a compressed elliptic curve point, failing if it is not on the curve.
+#[cfg(feature = "did-key-validation")]
+fn decompress_point(key_type: DIDKeyType, point: &[u8]) -> Option<(Vec<u8>, Vec<u8>)> {
+  // `from_sec1_bytes` also accepts other SEC1 encodings depending on the enabled curve features, so the compressed
+  // tag is checked explicitly.
+  if matches!(key_type, DIDKeyType::P256 | DIDKeyType::Secp256k1) && !matches!(point.first(), Some(0x02 | 0x03)) {
+    return None;
+  }
+  let uncompressed: Vec<u8> = match key_type {
+    DIDKeyType::P256 => p256::PublicKey::from_sec1_bytes(point)
+      .ok()?
+      .to_encoded_point(false)
+      .as_bytes()[1..]
+      .to_vec(),
+    DIDKeyType::Secp256k1 => k256::PublicKey::from_sec1_bytes(point)
+      .ok()?
+      .to_encoded_point(false)
+      .as_bytes()[1..]
+      .to_vec(),
+    DIDKeyType::Bls12381G2 => {
+      let point: G2Affine = Option::from(G2Affine::from_compressed(point.try_into().ok()?))?;
+      if bool::from(point.is_identity()) {
+        return None;
+      }
+      point.to_uncompressed().to_vec()
+    }
+    _ => return None,
+  };
+  let (x, y) = uncompressed.split_at(uncompressed.len() / 2);
+  Some((x.to_vec(), y.to_vec()))
+}
+
+/// Returns the compressed encoding of the elliptic curve point with the affine coordinates `x` and `y`.
+#[cfg(feature = "did-key-validation")]
+fn compress_point(key_type: DIDKeyType, x: &[u8], y: &[u8]) -> Option<Vec<u8>> {
+  let uncompressed: Vec<u8> = [x, y].concat();
+  match key_type {
+    DIDKeyType::P256 | DIDKeyType::Secp256k1 => {
+      let mut sec1: Vec<u8> = vec![0x04];
+      sec1.extend_from_slice(&uncompressed);
+      match key_type {
+        DIDKeyType::P256 => p256::PublicKey::from_sec1_bytes(&sec1)
+          .ok()
+          .map(|key| key.to_encoded_point(true).as_bytes().to_vec()),
+        _ => k256::PublicKey::from_sec1_bytes(&sec1)
+          .ok()
+          .map(|key| key.to_encoded_point(true).as_bytes().to_vec()),
+      }
+    }
+    DIDKeyType::Bls12381G2 => {
+      let point: G2Affine = Option::from(G2Affine::from_uncompressed(uncompressed.as_slice().try_into().ok()?))?;
+      Some(point.to_compressed().to_vec())
+    }
+    _ => None,
+  }
+}
+
+/// Elliptic curve points cannot be validated without the `did-key-validation` feature.
+#[cfg(not(feature = "did-key-validation"))]
+fn decompress_point(_key_type: DIDKeyType, _point: &[u8]) -> Option<(Vec<u8>, Vec<u8>)> {
+  None
+}
+
+#[cfg(not(feature = "did-key-validation"))]
+fn compress_point(_key_type: DIDKeyType, _x: &[u8], _y: &[u8]) -> Option<Vec<u8>> {
+  None
+}
+
+impl AsRef<CoreDID> for DIDKey {
+  fn as_ref(&self) -> &CoreDID {
+    &self.0
+  }
+}
+
+impl From<DIDKey> for CoreDID {
+  fn from(value: DIDKey) -> Self {
+    value.0
+  }
+}
+
+impl<'a> TryFrom<&'a str> for DIDKey {
+  type Error = Error;
+  fn try_from(value: &'a str) -> Result<Self, Self::Error> {
+    value.parse()
+  }
+}
+
+impl Display for DIDKey {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    write!(f, "{}", self.0)
+  }
+}
+
+impl FromStr for DIDKey {
+  type Err = Error;
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    s.parse::<CoreDID>().and_then(TryFrom::try_from)
+  }
+}
+
+impl From<DIDKey> for String {
+  fn from(value: DIDKey) -> Self {
+    value.to_string()
+  }
+}
+
+impl TryFrom<CoreDID> for DIDKey {
+  type Error = Error;
+  fn try_from(value: CoreDID) -> Result<Self, Self::Error> {
+    let Self::METHOD = value.method() else {
+      return Err(Error::InvalidMethodName);
+    };
+    decode_method_id(value.method_id())
+      .map(|_| Self(value))
+      .ok_or(Error::InvalidMethodId)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use identity_core::convert::FromJson;
+
+  use super::*;
+
+  #[test]
+  fn test_jwk() {
+    // Test vectors of the did:key specification.
+    for (did, key_type, jwk) in [
+      (
+        "did:key:z6MkiTBz1ymuepAQ4HEHYSF1H8quG5GLVVQR3djdX3mDooWp",
+        DIDKeyType::Ed25519,
+        serde_json::json!({
+          "kty": "OKP", "crv": "Ed25519", "alg": "EdDSA", "x": "O2onvM62pC1io6jQKm8Nc2UyFXcd4kOmOsBIoYtZ2ik"
+        }),
+      ),
+      (
+        "did:key:z6LSeu9HkTHSfLLeUs2nnzUSNedgDUevfNQgQjQC23ZCit6F",
+        DIDKeyType::X25519,
+        serde_json::json!({
+          "kty": "OKP", "crv": "X25519", "x": "L-V9o0fNYkMVKNqsX7spBzD_9oSvxM_C7ZCZX1jLO3Q"
+        }),
+      ),
+    ] {
+      let did: DIDKey = DIDKey::parse(did).unwrap();
+      let jwk: Jwk = Jwk::from_json_value(jwk).unwrap();
+      assert_eq!(did.key_type(), key_type);
+      assert_eq!(did.jwk(), jwk);
+      assert_eq!(DIDKey::from_jwk(&jwk).unwrap(), did);
+    }
+  }
+
+  #[cfg(feature = "did-key-validation")]
+  #[test]
+  fn test_jwk_ec() {
+    // Test vectors of the did:key specification.
+    for (did, key_type, jwk) in [
+      (
+        "did:key:zDnaerDaTF5BXEavCrfRZEk316dpbLsfPDZ3WJ5hRTPFU2169",
+        DIDKeyType::P256,
+        serde_json::json!({
+          "kty": "EC", "crv": "P-256", "alg": "ES256",
+          "x": "fyNYMN0976ci7xqiSdag3buk-ZCwgXU4kz9XNkBlNUI", "y": "hW2ojTNfH7Jbi8--CJUo3OCbH3y5n91g-IMA9MLMbTU"
+        }),
+      ),
+      (
+        "did:key:zQ3shokFTS3brHcDQrn82RUDfCZESWL1ZdCEJwekUDPQiYBme",
+        DIDKeyType::Secp256k1,
+        serde_json::json!({
+          "kty": "EC", "crv": "secp256k1", "alg": "ES256K",
+          "x": "h0wVx_2iDlOcblulc8E5iEw1EYh5n1RYtLQfeSTyNc0", "y": "O2EATIGbu6DezKFptj5scAIRntgfecanVNXxat1rnwE"
+        }),
+      ),
+    ] {
+      let did: DIDKey = DIDKey::parse(did).unwrap();
+      let jwk: Jwk = Jwk::from_json_value(jwk).unwrap();
+      assert_eq!(did.key_type(), key_type);
+      assert_eq!(did.jwk(), jwk);
+      assert_eq!(DIDKey::from_jwk(&jwk).unwrap(), did);
+    }
+  }
+
+  #[cfg(feature = "did-key-validation")]
+  #[test]
+  fn test_bls12381g2() {
+    let did: DIDKey = DIDKey::parse(
+      "did:key:zUC7K4ndUaGZgV7Cp2yJy6JtMoUHY6u7tkcSYUvPrEidqBmLCTLmi6d5WvwnUqejscAkERJ3bfjEiSYtdPkRSE8kSa11hFBr4sTgnbZ95SJj19PN2jdvJjyzpSZgxkyyxNnBNnY",
+    )
+    .unwrap();
+    assert_eq!(did.key_type(), DIDKeyType::Bls12381G2);
+    assert_eq!(did.jwk().alg(), None);
+    assert_eq!(DIDKey::from_jwk(&did.jwk()).unwrap(), did);
+  }
+
+  #[cfg(not(feature = "did-key-validation"))]
+  #[test]
+  fn test_ec_requires_validation() {
+    assert!("did:key:zDnaerDaTF5BXEavCrfRZEk316dpbLsfPDZ3WJ5hRTPFU2169"
+      .parse::<DIDKey>()
+      .is_err());
+    assert!("did:key:zQ3shokFTS3brHcDQrn82RUDfCZESWL1ZdCEJwekUDPQiYBme"
+      .parse::<DIDKey>()
+      .is_err());
+  }
+
+  #[test]
+  fn test_post_quantum() {
+    for key_type in [DIDKeyType::MlDsa44, DIDKeyType::SlhDsaShake_256f] {
+      let did: DIDKey = DIDKey::new(key_type, &vec![7; key_type.public_key_length()]).unwrap();
+      assert_eq!(did.key_type(), key_type);
+      assert_eq!(did.public_key(), vec![7; key_type.public_key_length()]);
+      let jwk: Jwk = did.jwk();
+      assert_eq!(jwk.alg(), key_type.alg().map(JwsAlgorithm::name));
+      assert_eq!(DIDKey::from_jwk(&jwk).unwrap(), did);
+    }
+  }
+
+  #[test]
+  fn test_invalid() {
+    assert!("did:jwk:z6MkiTBz1ymuepAQ4HEHYSF1H8quG5GLVVQR3djdX3mDooWp"
+      .parse::<DIDKey>()
+      .is_err());
+    // Not base58btc.
+    assert!("did:key:u7QE7aie8zrakLWKjqNAqbw1zZTIVdx3iQ6Y6wEihi1naKQ"
+      .parse::<DIDKey>()
+      .is_err());
+    // Truncated key.
+    assert!("did:key:z6MkiTBz1ymuepAQ4HEHYSF1H8quG5GLVVQR3djdX3mDooW"
+      .parse::<DIDKey>()
+      .is_err());
+    // Wrong key length.
+    assert!(DIDKey::new(DIDKeyType::Ed25519, &[0; 31]).is_err());
+    // Not a point on the curve.
+    let mut point = [0xff; 33];
+    point[0] = 0x02;
+    assert!(DIDKey::new(DIDKeyType::P256, &point).is_err());
+    assert!(DIDKey::new(DIDKeyType::P256, &[0x05; 33]).is_err());
+    assert!(DIDKey::new(DIDKeyType::Bls12381G2, &[0; 96]).is_err());
+  }
+}
@@ -0,0 +1,593 @@
+// Copyright 2020-2024 IOTA Stiftung, Fondazione Links
+// SPDX-License-Identifier: Apache-2.0
+
+use std::fmt::Debug;
+use std::fmt::Display;
+use std::str::FromStr;
+
+use crypto::hashes::sha::Sha256;
+use crypto::hashes::Digest;
+use identity_core::common::Object;
+use identity_core::common::Value;
+use identity_core::convert::BaseEncoding;
+use identity_core::convert::FromJson;
+use identity_core::convert::ToJson;
+use identity_jose::jwu;
+
+use crate::multicodec::decode_varint;
+use crate::multicodec::encode_varint;
+use crate::CoreDID;
+use crate::DIDKey;
+use crate::Error;
+use crate::DID;
+
+/// The multicodec code of JSON.
+const JSON_MULTICODEC: u64 = 0x0200;
+/// The multihash code of SHA2-256 followed by the digest length.
+pub(crate) const SHA256_MULTIHASH_PREFIX: [u8; 2] = [0x12, 0x20];
+/// The purpose code of services encoded in `did:peer:2` DIDs.
+const SERVICE_CODE: char = 'S';
+/// The abbreviations of service members encoded in `did:peer:2` DIDs.
+const SERVICE_ABBREVIATIONS: [(&str, &str); 4] = [
+  ("type", "t"),
+  ("serviceEndpoint", "s"),
+  ("routingKeys", "r"),
+  ("accept", "a"),
+];
+/// The abbreviations of service types encoded in `did:peer:2` DIDs.
+const SERVICE_TYPE_ABBREVIATIONS: [(&str, &str); 1] = [("DIDCommMessaging", "dm")];
+
+/// The algorithm used to generate a `did:peer` DID, indicated by the first character of its method-specific id.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum PeerNumalgo {
+  /// Numalgo 0, encoding a single inception key like `did:key`.
+  InceptionKey,
+  /// Numalgo 2, encoding multiple keys and services.
+  MultipleInceptionKeys,
+  /// Numalgo 4, encoding the hash of an input document, followed by the document itself in the long form.
+  ShortFormAndLongForm,
+}
+
+impl PeerNumalgo {
+  /// Returns the character identifying the algorithm.
+  pub const fn code(self) -> char {
+    match self {
+      Self::InceptionKey => '0',
+      Self::MultipleInceptionKeys => '2',
+      Self::ShortFormAndLongForm => '4',
+    }
+  }
+
+  fn from_code(code: char) -> Option<Self> {
+    [
+      Self::InceptionKey,
+      Self::MultipleInceptionKeys,
+      Self::ShortFormAndLongForm,
+    ]
+    .into_iter()
+    .find(|numalgo| numalgo.code() == code)
+  }
+}
+
+/// The verification relationship of a key encoded in a `did:peer:2` DID.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum PeerPurpose {
+  /// An assertion method, encoded as `A`.
+  Assertion,
+  /// A key agreement key, encoded as `E`.
+  Encryption,
+  /// An authentication method, encoded as `V`.
+  Verification,
+  /// A capability invocation method, encoded as `I`.
+  CapabilityInvocation,
+  /// A capability delegation method, encoded as `D`.
+  CapabilityDelegation,
+}
+
+impl PeerPurpose {
+  /// Returns the character encoding the purpose.
+  pub const fn code(self) -> char {
+    match self {
+      Self::Assertion => 'A',
+      Self::Encryption => 'E',
+      Self::Verification => 'V',
+      Self::CapabilityInvocation => 'I',
+      Self::CapabilityDelegation => 'D',
+    }
+  }
+
+  fn from_code(code: char) -> Option<Self> {
+    [
+      Self::Assertion,
+      Self::Encryption,
+      Self::Verification,
+      Self::CapabilityInvocation,
+      Self::CapabilityDelegation,
+    ]
+    .into_iter()
+    .find(|purpose| purpose.code() == code)
+  }
+}
+
+/// The decoded method-specific id of a `did:peer`.
+enum PeerContent {
+  InceptionKey(DIDKey),
+  MultipleInceptionKeys {
+    keys: Vec<(PeerPurpose, DIDKey)>,
+    services: Vec<Object>,
+  },
+  ShortFormAndLongForm {
+    hash: String,
+    input_document: Option<Object>,
+  },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, serde::Deserialize, serde::Serialize)]
+#[repr(transparent)]
+#[serde(into = "CoreDID", try_from = "CoreDID")]
+/// A type representing a `did:peer` DID of numalgo 0, 2 or 4.
+pub struct DIDPeer(CoreDID);
+
+impl DIDPeer {
+  /// [`DIDPeer`]'s method.
+  pub const METHOD: &'static str = "peer";
+
+  /// Tries to parse a [`DIDPeer`] from a string.
+  pub fn parse(s: &str) -> Result<Self, Error> {
+    s.parse()
+  }
+
+  /// Creates a numalgo 0 [`DIDPeer`] encoding the public key of `key`.
+  pub fn new_numalgo0(key: &DIDKey) -> Result<Self, Error> {
+    Self::parse(&format!(
+      "did:{}:{}{}",
+      Self::METHOD,
+      PeerNumalgo::InceptionKey.code(),
+      key.method_id()
+    ))
+  }
+
+  /// Creates a numalgo 2 [`DIDPeer`] encoding the public keys of `keys` with their purposes, followed by `services`.
+  ///
+  /// Services are given with their full member names, which are abbreviated in the DID. Services without an `id` are
+  /// assigned `#service`, `#service-1` and so on by their position when decoded.
+  pub fn new_numalgo2<K, S>(keys: K, services: S) -> Result<Self, Error>
+  where
+    K: IntoIterator<Item = (PeerPurpose, DIDKey)>,
+    S: IntoIterator<Item = Object>,
+  {
+    let mut method_id: String = PeerNumalgo::MultipleInceptionKeys.code().to_string();
+    for (purpose, key) in keys {
+      method_id.push('.');
+      method_id.push(purpose.code());
+      method_id.push_str(key.method_id());
+    }
+    for service in services {
+      let service: Value = rename_service_members(Value::Object(service.into_iter().collect()), true);
+      let json: Vec<u8> = service
+        .to_json_vec()
+        .map_err(|_| Error::Other("invalid did:peer service"))?;
+      method_id.push('.');
+      method_id.push(SERVICE_CODE);
+      method_id.push_str(&jwu::encode_b64(json));
+    }
+
+    Self::parse(&format!("did:{}:{method_id}", Self::METHOD))
+  }
+
+  /// Creates the long form of a numalgo 4 [`DIDPeer`] encoding `input_document`.
+  ///
+  /// The input document must not have an `id` and refers to the DID through relative references, e.g. `#key-1`. It is
+  /// encoded with the JSON Canonicalization Scheme, so equal input documents yield the same DID.
+  pub fn new_numalgo4(input_document: &Object) -> Result<Self, Error> {
+    if input_document.contains_key("id") {
+      return Err(Error::Other("the input document of a did:peer:4 must not have an id"));
+    }
+    let mut bytes: Vec<u8> = encode_varint(JSON_MULTICODEC);
+    bytes.extend(
+      input_document
+        .to_jcs()
+        .map_err(|_| Error::Other("invalid did:peer input document"))?,
+    );
+    let encoded_document: String = BaseEncoding::encode_multibase(&bytes, None);
+
+    Self::parse(&format!(
+      "did:{}:{}{}:{encoded_document}",
+      Self::METHOD,
+      PeerNumalgo::ShortFormAndLongForm.code(),
+      hash_document(&encoded_document)
+    ))
+  }
+
+  /// Returns the algorithm used to generate this did:peer.
+  ///
+  /// # Errors
+  ///
+  /// Fails if the method-specific id does not start with a supported numalgo.
+  pub fn numalgo(&self) -> Result<PeerNumalgo, Error> {
+    self
+      .method_id()
+      .chars()
+      .next()
+      .and_then(PeerNumalgo::from_code)
+      .ok_or(Error::InvalidMethodId)
+  }
+
+  /// Returns the key encoded inside a numalgo 0 did:peer, or `None` for other numalgos.
+  ///
+  /// # Errors
+  ///
+  /// Fails if the method-specific id cannot be decoded.
+  pub fn inception_key(&self) -> Result<Option<DIDKey>, Error> {
+    match self.decode()? {
+      PeerContent::InceptionKey(key) => Ok(Some(key)),
+      _ => Ok(None),
+    }
+  }
+
+  /// Returns the keys encoded inside a numalgo 2 did:peer with their purposes, in the order they are encoded.
+  ///
+  /// The verification methods of the keys are identified by `#key-1`, `#key-2` and so on, in the same order. Other
+  /// numalgos encode no keys this way.
+  ///
+  /// # Errors
+  ///
+  /// Fails if the method-specific id cannot be decoded.
+  pub fn keys(&self) -> Result<Vec<(PeerPurpose, DIDKey)>, Error> {
+    match self.decode()? {
+      PeerContent::MultipleInceptionKeys { keys, .. } => Ok(keys),
+      _ => Ok(Vec::new()),
+    }
+  }
+
+  /// Returns the services encoded inside a numalgo 2 did:peer, with expanded member names and relative ids.
+  ///
+  /// # Errors
+  ///
+  /// Fails if the method-specific id cannot be decoded.
+  pub fn services(&self) -> Result<Vec<Object>, Error> {
+    let PeerContent::MultipleInceptionKeys { services, .. } = self.decode()? else {
+      return Ok(Vec::new());
+    };
+    let services: Vec<Object> = services
+      .into_iter()
+      .enumerate()
+      .map(|(index, mut service)| {
+        if !service.contains_key("id") {
+          let id: String = match index {
+            0 => "#service".to_owned(),
+            index => format!("#service-{index}"),
+          };
+          service.insert("id".to_owned(), Value::String(id));
+        }
+        service
+      })
+      .collect();
+    Ok(services)
+  }
+
+  /// Returns the input document encoded inside the long form of a numalgo 4 did:peer, or `None` for the short form
+  /// and other numalgos.
+  ///
+  /// # Errors
+  ///
+  /// Fails if the method-specific id cannot be decoded.
+  pub fn input_document(&self) -> Result<Option<Object>, Error> {
+    match self.decode()? {
+      PeerContent::ShortFormAndLongForm { input_document, .. } => Ok(input_document),
+      _ => Ok(None),
+    }
+  }
+
+  /// Returns `true` if this is the short form of a numalgo 4 did:peer, which does not encode the input document.
+  pub fn is_short_form(&self) -> bool {
+    matches!(
+      self.decode(),
+      Ok(PeerContent::ShortFormAndLongForm {
+        input_document: None,
+        ..
+      })
+    )
+  }
+
+  /// Returns the short form of a numalgo 4 did:peer.
+  ///
+  /// # Errors
+  ///
+  /// Fails if this is not a numalgo 4 did:peer or its method-specific id cannot be decoded.
+  pub fn short_form(&self) -> Result<DIDPeer, Error> {
+    let PeerContent::ShortFormAndLongForm { hash, .. } = self.decode()? else {
+      return Err(Error::Other("only a did:peer:4 has a short form"));
+    };
+    Self::parse(&format!(
+      "did:{}:{}{hash}",
+      Self::METHOD,
+      PeerNumalgo::ShortFormAndLongForm.code()
+    ))
+  }
+
+  fn decode(&self) -> Result<PeerContent, Error> {
+    decode_method_id(self.method_id()).ok_or(Error::InvalidMethodId)
+  }
+}
+
+/// Decodes and validates the method-specific id of a `did:peer`.
+fn decode_method_id(method_id: &str) -> Option<PeerContent> {
+  let mut chars = method_id.chars();
+  let numalgo: PeerNumalgo = chars.next().and_then(PeerNumalgo::from_code)?;
+  let content: &str = chars.as_str();
+
+  match numalgo {
+    PeerNumalgo::InceptionKey => DIDKey::parse(&format!("did:{}:{content}", DIDKey::METHOD))
+      .ok()
+      .map(PeerContent::InceptionKey),
+    PeerNumalgo::MultipleInceptionKeys => {
+      let mut keys: Vec<(PeerPurpose, DIDKey)> = Vec::new();
+      let mut services: Vec<Object> = Vec::new();
+      for element in content.strip_prefix('.')?.split('.') {
+        let mut chars = element.chars();
+        let code: char = chars.next()?;
+        if code == SERVICE_CODE {
+          let json: Vec<u8> = jwu::decode_b64(chars.as_str()).ok()?;
+          let Value::Object(service) = rename_service_members(Value::from_json_slice(&json).ok()?, false) else {
+            return None;
+          };
+          services.push(service.into_iter().collect());
+        } else {
+          let purpose: PeerPurpose = PeerPurpose::from_code(code)?;
+          let key: DIDKey = DIDKey::parse(&format!("did:{}:{}", DIDKey::METHOD, chars.as_str())).ok()?;
+          keys.push((purpose, key));
+        }
+      }
+      Some(PeerContent::MultipleInceptionKeys { keys, services })
+    }
+    PeerNumalgo::ShortFormAndLongForm => {
+      let (hash, encoded_document) = match content.split_once(':') {
+        Some((hash, encoded_document)) => (hash, Some(encoded_document)),
+        None => (content, None),
+      };
+      let multihash: Vec<u8> = hash
+        .starts_with('z')
+        .then(|| BaseEncoding::decode_multibase(hash).ok())
+        .flatten()?;
+      if multihash.len() != SHA256_MULTIHASH_PREFIX.len() + 32 || !multihash.starts_with(&SHA256_MULTIHASH_PREFIX) {
+        return None;
+      }
+
+      let input_document: Option<Object> = match encoded_document {
+        Some(encoded_document) => {
+          if hash_document(encoded_document) != hash || !encoded_document.starts_with('z') {
+            return None;
+          }
+          let bytes: Vec<u8> = BaseEncoding::decode_multibase(encoded_document).ok()?;
+          let (JSON_MULTICODEC, json) = decode_varint(&bytes)? else {
+            return None;
+          };
+          Some(Object::from_json_slice(json).ok()?)
+        }
+        None => None,
+      };
+      Some(PeerContent::ShortFormAndLongForm {
+        hash: hash.to_owned(),
+        input_document,
+      })
+    }
+  }
+}
+
+/// Returns the base58btc multibase encoded SHA2-256 multihash of the encoded input document of a `did:peer:4`.
+fn hash_document(encoded_document: &str) -> String {
+  let mut multihash: Vec<u8> = SHA256_MULTIHASH_PREFIX.to_vec();
+  multihash.extend_from_slice(&Sha256::digest(encoded_document.as_bytes()));
+  BaseEncoding::encode_multibase(&multihash, None)
+}
+
+/// Abbreviates the member names and types of a service, or expands them if `abbreviate` is `false`.
+fn rename_service_members(value: Value, abbreviate: bool) -> Value {
+  let type_member: &str = if abbreviate { "t" } else { "type" };
+  match value {
+    Value::Object(members) => Value::Object(
+      members
+        .into_iter()
+        .map(|(name, value)| {
+          let name: String = rename(&SERVICE_ABBREVIATIONS, name, abbreviate);
+          let value: Value = match value {
+            Value::String(type_) if name == type_member => {
+              Value::String(rename(&SERVICE_TYPE_ABBREVIATIONS, type_, abbreviate))
+            }
+            value => rename_service_members(value, abbreviate),
+          };
+          (name, value)
+        })
+        .collect(),
+    ),
+    Value::Array(values) => Value::Array(
+      values
+        .into_iter()
+        .map(|value| rename_service_members(value, abbreviate))
+        .collect(),
+    ),
+    value => value,
+  }
+}
+
+fn rename(abbreviations: &[(&str, &str)], value: String, abbreviate: bool) -> String {
+  abbreviations
+    .iter()
+    .map(|&(name, abbreviation)| {
+      if abbreviate {
+        (name, abbreviation)
+      } else {
+        (abbreviation, name)
+      }
+    })
+    .find(|(from, _)| *from == value)
+    .map_or(value, |(_, to)| to.to_owned())
+}
+
+impl AsRef<CoreDID> for DIDPeer {
+  fn as_ref(&self) -> &CoreDID {
+    &self.0
+  }
+}
+
+impl From<DIDPeer> for CoreDID {
+  fn from(value: DIDPeer) -> Self {
+    value.0
+  }
+}
+
+impl<'a> TryFrom<&'a str> for DIDPeer {
+  type Error = Error;
+  fn try_from(value: &'a str) -> Result<Self, Self::Error> {
+    value.parse()
+  }
+}
+
+impl Display for DIDPeer {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    write!(f, "{}", self.0)
+  }
+}
+
+impl FromStr for DIDPeer {
+  type Err = Error;
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    s.parse::<CoreDID>().and_then(TryFrom::try_from)
+  }
+}
+
+impl From<DIDPeer> for String {
+  fn from(value: DIDPeer) -> Self {
+    value.to_string()
+  }
+}
+
+impl TryFrom<CoreDID> for DIDPeer {
+  type Error = Error;
+  fn try_from(value: CoreDID) -> Result<Self, Self::Error> {
+    let Self::METHOD = value.method() else {
+      return Err(Error::InvalidMethodName);
+    };
+    decode_method_id(value.method_id())
+      .map(|_| Self(value))
+      .ok_or(Error::InvalidMethodId)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  const ED25519_KEY: &str = "z6MkiTBz1ymuepAQ4HEHYSF1H8quG5GLVVQR3djdX3mDooWp";
+  const X25519_KEY: &str = "z6LSeu9HkTHSfLLeUs2nnzUSNedgDUevfNQgQjQC23ZCit6F";
+  /// The abbreviated DIDComm service of the did:peer specification examples.
+  const SERVICE: &str = "eyJ0IjoiZG0iLCJzIjoiaHR0cHM6Ly9leGFtcGxlLmNvbS9lbmRwb2ludCIsInIiOlsiZGlkOmV4YW1wbGU6c29tZW1lZGlhdG9yI3NvbWVrZXkiXSwiYSI6WyJkaWRjb21tL3YyIiwiZGlkY29tbS9haXAyO2Vudj1yZmM1ODciXX0";
+
+  fn did_key(key: &str) -> DIDKey {
+    DIDKey::parse(&format!("did:key:{key}")).unwrap()
+  }
+
+  #[test]
+  fn test_numalgo0() {
+    let did: DIDPeer = DIDPeer::parse(&format!("did:peer:0{ED25519_KEY}")).unwrap();
+    assert_eq!(did.numalgo().unwrap(), PeerNumalgo::InceptionKey);
+    assert_eq!(did.inception_key().unwrap(), Some(did_key(ED25519_KEY)));
+    assert_eq!(DIDPeer::new_numalgo0(&did_key(ED25519_KEY)).unwrap(), did);
+    assert!(did.keys().unwrap().is_empty());
+  }
+
+  #[test]
+  fn test_numalgo2() {
+    let did: DIDPeer = DIDPeer::parse(&format!("did:peer:2.E{X25519_KEY}.V{ED25519_KEY}.S{SERVICE}")).unwrap();
+    assert_eq!(did.numalgo().unwrap(), PeerNumalgo::MultipleInceptionKeys);
+    assert_eq!(
+      did.keys().unwrap(),
+      vec![
+        (PeerPurpose::Encryption, did_key(X25519_KEY)),
+        (PeerPurpose::Verification, did_key(ED25519_KEY))
+      ]
+    );
+    let service: Object = Object::from_json_value(serde_json::json!({
+      "id": "#service",
+      "type": "DIDCommMessaging",
+      "serviceEndpoint": "https://example.com/endpoint",
+      "routingKeys": ["did:example:somemediator#somekey"],
+      "accept": ["didcomm/v2", "didcomm/aip2;env=rfc587"]
+    }))
+    .unwrap();
+    assert_eq!(did.services().unwrap(), vec![service.clone()]);
+
+    let mut services: Vec<Object> = vec![service.clone(), service];
+    services[0].remove("id");
+    services[1].insert("id".to_owned(), Value::String("#didcomm".to_owned()));
+    let encoded: DIDPeer = DIDPeer::new_numalgo2(did.keys().unwrap(), services.clone()).unwrap();
+    services[0].insert("id".to_owned(), Value::String("#service".to_owned()));
+    assert_eq!(encoded.keys().unwrap(), did.keys().unwrap());
+    assert_eq!(encoded.services().unwrap(), services);
+  }
+
+  #[test]
+  fn test_numalgo4() {
+    let input_document: Object = Object::from_json_value(serde_json::json!({
+      "verificationMethod": [{
+        "id": "#key-1",
+        "type": "JsonWebKey2020",
+        "publicKeyJwk": did_key(ED25519_KEY).jwk()
+      }],
+      "authentication": ["#key-1"]
+    }))
+    .unwrap();
+
+    let did: DIDPeer = DIDPeer::new_numalgo4(&input_document).unwrap();
+    assert_eq!(did.numalgo().unwrap(), PeerNumalgo::ShortFormAndLongForm);
+    assert!(!did.is_short_form());
+    assert_eq!(did.input_document().unwrap(), Some(input_document.clone()));
+
+    let short_form: DIDPeer = did.short_form().unwrap();
+    assert!(short_form.is_short_form());
+    assert!(short_form.input_document().unwrap().is_none());
+    assert!(did.as_ref().as_str().starts_with(short_form.as_ref().as_str()));
+    assert_eq!(short_form.short_form().unwrap(), short_form);
+    assert!(DIDPeer::parse(&format!("did:peer:0{ED25519_KEY}"))
+      .unwrap()
+      .short_form()
+      .is_err());
+    assert!(
+      DIDPeer::parse("did:peer:4zQmd8CpeFPci817KDsbSAKWcXAE2mjvCQSasRewvbSF54Bd")
+        .unwrap()
+        .is_short_form()
+    );
+
+    // The hash binds the encoded document.
+    let (_, encoded_document) = did.method_id().split_once(':').unwrap();
+    let mut other_document: Object = input_document.clone();
+    other_document.insert("alsoKnownAs".to_owned(), Value::Array(Vec::new()));
+    let other: DIDPeer = DIDPeer::new_numalgo4(&other_document).unwrap();
+    let (other_hash, _) = other.method_id().split_once(':').unwrap();
+    assert!(DIDPeer::parse(&format!("did:peer:{other_hash}:{encoded_document}")).is_err());
+
+    let mut with_id: Object = input_document;
+    with_id.insert("id".to_owned(), Value::String("did:example:123".to_owned()));
+    assert!(DIDPeer::new_numalgo4(&with_id).is_err());
+  }
+
+  #[test]
+  fn test_invalid() {
+    for did in [
+      format!("did:key:{ED25519_KEY}"),
+      format!("did:peer:1{ED25519_KEY}"),
+      format!("did:peer:0{}", &ED25519_KEY[1..]),
+      "did:peer:2".to_owned(),
+      format!("did:peer:2.X{ED25519_KEY}"),
+      format!("did:peer:2.V{ED25519_KEY}..S{SERVICE}"),
+      "did:peer:2.SW10".to_owned(),
+      "did:peer:4zQmd8CpeFPci817KDsbSAKWcXAE2mjvCQSasRewvbSF54".to_owned(),
+      "did:peer:4zQmd8CpeFPci817KDsbSAKWcXAE2mjvCQSasRewvbSF54Bd:z123".to_owned(),
+    ] {
+      assert!(did.parse::<DIDPeer>().is_err(), "{did}");
+    }
+  }
+}
@@ -11,6 +11,13 @@ use crate::Error as DIDError;
 /// Alias for a `Result` with the error type [`DIDError`].
 type Result<T> = std::result::Result<T, DIDError>;
 
+/// The percent-encoded colon, which separates the domain from the port in the method id.
+const ENCODED_COLON: &str = "%3A";
+/// The directory containing the DID document of a DID without path.
+const WELL_KNOWN: &str = ".well-known";
+/// The name of the DID document file.
+const DOCUMENT_FILE: &str = "did.json";
+
 /// A DID conforming to the Web DID method specification.
 ///
 /// This is a thin wrapper around the [`DID`][`CoreDID`] type from the
@@ -27,28 +34,45 @@ impl WebDID {
   /// The IOTA DID method name (`"iota"`).
   pub const METHOD: &'static str = "web";
 
-  /// Create a new valid Web DID.
+  /// Creates the [`WebDID`] whose document is published at `url`.
+  ///
+  /// `url` is either the URL of the `did.json` document or the URL of the directory containing it, e.g.
+  /// `https://example.com:8443/users/alice/did.json` and `https://example.com:8443/users/alice` both result in
+  /// `did:web:example.com%3A8443:users:alice`. A document located under `/.well-known` results in a DID without
+  /// path, e.g. `did:web:example.com`.
+  ///
+  /// # Errors
+  ///
+  /// Returns `Err` if `url` is not a valid URL, its host is not a domain name or it has a query or fragment.
   pub fn new(url: &str) -> Result<Self> {
     let parsed_url: Url = Url::parse(url).map_err(|_| Error::Other("Not a valid Url"))?;
-    
-    // Extract the domain and path
-    if let Some(domain) = parsed_url.domain() {
-
-      let port = parsed_url.port().map_or(String::new(), |p| format!("%3a{}", p));
-
-      let path = parsed_url.path_segments().map_or(String::new(), |p| {
-        format!("{}{}", ":", p.into_iter().collect::<Vec<&str>>().join(":"))
-      });
+    if parsed_url.query().is_some() || parsed_url.fragment().is_some() || !parsed_url.username().is_empty() {
+      return Err(Error::InvalidMethodId);
+    }
+    let domain: &str = parsed_url.domain().ok_or(Error::InvalidMethodId)?;
 
-      let did_web = format!("did:{}:{}{}{}", Self::METHOD, domain, port, path);
-      println!("DID Web: {}", did_web);
-      let core_did = CoreDID::parse(did_web).map_err(|_| Error::Other("Cannot convert to CoreDID"))?;
-      println!("{}",core_did);
-      Ok(Self(core_did))
+    let mut method_id: String = domain.to_owned();
+    if let Some(port) = parsed_url.port() {
+      method_id.push_str(&format!("{ENCODED_COLON}{port}"));
+    }
 
-    } else {
-        return Err(Error::InvalidMethodId);
+    let mut segments: Vec<&str> = parsed_url
+      .path_segments()
+      .map(|segments| segments.filter(|segment| !segment.is_empty()).collect())
+      .unwrap_or_default();
+    if segments.last() == Some(&DOCUMENT_FILE) {
+      segments.pop();
+    }
+    if segments == [WELL_KNOWN] {
+      segments.pop();
+    }
+    for segment in segments {
+      // Colons separate the segments of the method id, so they are kept percent-encoded within a segment.
+      method_id.push(':');
+      method_id.push_str(&segment.replace(':', ENCODED_COLON));
     }
+
+    CoreDID::parse(format!("did:{}:{}", Self::METHOD, method_id)).and_then(Self::try_from_core)
   }
 
   /// Parses an [`WebDID`] from the given `input`.
@@ -57,16 +81,19 @@ impl WebDID {
   ///
   /// Returns `Err` if the input does not conform to the [`WebDID`] specification.
   pub fn parse(input: impl AsRef<str>) -> Result<Self> {
-    CoreDID::parse(input.as_ref().to_lowercase()).and_then(Self::try_from_core)
+    CoreDID::parse(input.as_ref()).and_then(Self::try_from_core)
   }
 
-  /// Converts a [`WebDID`] to a [`Url`]
+  /// Returns the HTTPS [`Url`] the DID document is published at.
+  ///
+  /// A DID without path is located at `https://<domain>[:<port>]/.well-known/did.json`, otherwise the path segments
+  /// are followed by `did.json`, e.g. `did:web:example.com%3A8443:users:alice` is located at
+  /// `https://example.com:8443/users/alice/did.json`.
   pub fn to_url(&self) -> Url {
     //This is safe because if i have constructed a WebDID I already know it is valid
     WebDID::check_validity(self).unwrap()
   }
 
-
   /// Converts a [`CoreDID`] to a [`WebDID`].
   ///
   /// # Errors
@@ -99,6 +126,20 @@ impl WebDID {
     Self::check_validity(did).is_ok()
   }
 
+  /// Returns the [`Url`] of the document of `did` like [`WebDID::check_validity`], optionally accepting an IP
+  /// address as host.
+  ///
+  /// The `did:web` method specification does not allow IP addresses, which may however be used for testing or in
+  /// closed networks. Internationalized domain names are converted to their ASCII (punycode) form.
+  ///
+  /// # Errors
+  ///
+  /// Returns `Err` if the input is not a syntactically valid [`WebDID`], apart from its host being an IP address if
+  /// `allow_ip_host` is `true`.
+  pub fn document_url<D: DID>(did: &D, allow_ip_host: bool) -> Result<Url> {
+    Self::check_method(did).and_then(|_| Self::method_id_url(did.method_id(), allow_ip_host))
+  }
+
 
   // ===========================================================================
   // Helpers
@@ -115,61 +156,44 @@ impl WebDID {
       .ok_or(DIDError::InvalidMethodName)
   }
 
-  /// Checks if the given `DID` has a valid [`IotaDID`] `method_id`.
+  /// Checks if the given `DID` has a valid [`WebDID`] `method_id`.
   ///
   /// # Errors
   ///
-  /// Returns `Err` if the input does not have a [`IotaDID`] compliant method id.
+  /// Returns the [`Url`] of the DID document or `Err` if the input does not have a [`WebDID`] compliant method id.
   fn check_method_id<D: DID>(did: &D) -> Result<Url> {
-    let (domain, port, path) = Self::denormalized_components(did.method_id());
-
-    let port = port.map(|p| u16::from_str(&p))
-    .map_or(Ok(None), |r| r.map(Some)
-    .map_err(|_| Error::InvalidMethodId))?;
-
-    let mut url = Url::parse(&format!("https://{}", domain))
-    .map_err(|_| Error::InvalidMethodId)?;
-
-    url.set_port(port).map_err(|_| Error::InvalidMethodId)?;
-
-    path.and_then(|p| Some(url.set_path(&p)));
+    Self::method_id_url(did.method_id(), false)
+  }
 
-    url.domain().ok_or(Error::InvalidMethodId)?;
+  /// Computes the [`Url`] of the document from the method id of a [`WebDID`].
+  fn method_id_url(method_id: &str, allow_ip_host: bool) -> Result<Url> {
+    let mut components = method_id.split(':');
+    // The port is separated from the domain by a percent-encoded colon, e.g. `example.com%3A8443`.
+    let authority: String = components
+      .next()
+      .unwrap_or_default()
+      .replace(ENCODED_COLON, ":")
+      .replace(&ENCODED_COLON.to_lowercase(), ":");
+    let segments: Vec<&str> = components.collect();
+    if segments
+      .iter()
+      .any(|segment| segment.is_empty() || *segment == "." || *segment == "..")
+    {
+      return Err(Error::InvalidMethodId);
+    }
 
-    Ok(url)
-  }
+    let mut url: Url = Url::parse(format!("https://{authority}/")).map_err(|_| Error::InvalidMethodId)?;
+    if (url.domain().is_none() && !allow_ip_host) || url.path() != "/" || !url.username().is_empty() {
+      return Err(Error::InvalidMethodId);
+    }
 
-  // did:web:cybersecurity-links.github.io%3A3000:did-web-server:.well-known:did.json
-
-  /// cybersecurity-links.github.io%3A3000:did-web-server:.well-known:did.json -> https:://cybersecurity-links.github.io:3000/did-web-server/.well-known/did.json
-  #[inline(always)]
-  fn denormalized_components(input: &str) -> (String, Option<String>, Option<String>) {
-
-    match input.find("%3a") {
-      Some(i) => {
-        let (domain, tail) = input.split_at(i);
-        match tail.find(":") {
-          Some(i) => {
-            let (port, path) = tail.split_at(i);
-            (domain.to_owned(), Some(port[3..].to_owned()), Some(path.replace(":", "/")))
-          },
-          None => (domain.to_owned(), Some(tail[3..].to_owned()), None),
-        }
-      
-      },
-      None => {
-        match input.find(":") {
-          Some(i) => {
-            let (domain, path) = input.split_at(i);
-            (domain.to_owned(), None, Some(path.replace(":", "/")))
-          },
-          None => (input.to_owned(), None, None),
-        }
-      }
+    if segments.is_empty() {
+      url.set_path(&format!("/{WELL_KNOWN}/{DOCUMENT_FILE}"));
+    } else {
+      url.set_path(&format!("/{}/{DOCUMENT_FILE}", segments.join("/")));
     }
-      
+    Ok(url)
   }
-
 }
 
 impl From<WebDID> for CoreDID {
@@ -227,4 +251,93 @@ impl AsRef<CoreDID> for WebDID {
   fn as_ref(&self) -> &CoreDID {
     &self.0
   }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_document_url() {
+    for (did, url) in [
+      ("did:web:example.com", "https://example.com/.well-known/did.json"),
+      (
+        "did:web:example.com%3A8443",
+        "https://example.com:8443/.well-known/did.json",
+      ),
+      (
+        "did:web:example.com%3a8443",
+        "https://example.com:8443/.well-known/did.json",
+      ),
+      (
+        "did:web:w3c-ccg.github.io:user:Alice",
+        "https://w3c-ccg.github.io/user/Alice/did.json",
+      ),
+      (
+        "did:web:example.com%3A8443:users:alice",
+        "https://example.com:8443/users/alice/did.json",
+      ),
+    ] {
+      assert_eq!(WebDID::parse(did).unwrap().to_url().as_str(), url);
+    }
+  }
+
+  #[test]
+  fn test_new() {
+    for (url, did) in [
+      ("https://example.com/.well-known/did.json", "did:web:example.com"),
+      ("https://example.com", "did:web:example.com"),
+      ("https://localhost:4443/.well-known/", "did:web:localhost%3A4443"),
+      (
+        "https://example.com:8443/users/alice/did.json",
+        "did:web:example.com%3A8443:users:alice",
+      ),
+      (
+        "https://example.com:8443/users/alice/",
+        "did:web:example.com%3A8443:users:alice",
+      ),
+      ("https://example.com/a:b", "did:web:example.com:a%3Ab"),
+    ] {
+      let web_did: WebDID = WebDID::new(url).unwrap();
+      assert_eq!(web_did.as_str(), did);
+      assert_eq!(WebDID::new(web_did.to_url().as_str()).unwrap(), web_did);
+    }
+  }
+
+  #[test]
+  fn test_invalid() {
+    for did in [
+      "did:web:127.0.0.1",
+      "did:web:example.com::alice",
+      "did:web:example.com:alice:",
+      "did:web:example.com:..",
+      "did:web:example.com%3Aport",
+      "did:iota:example.com",
+    ] {
+      assert!(WebDID::parse(did).is_err(), "{did}");
+    }
+    for url in [
+      "not a url",
+      "https://127.0.0.1/did.json",
+      "https://example.com/did.json?version=1",
+    ] {
+      assert!(WebDID::new(url).is_err(), "{url}");
+    }
+  }
+
+  #[test]
+  fn test_document_url_hosts() {
+    let did: CoreDID = CoreDID::parse("did:web:127.0.0.1%3A8080:alice").unwrap();
+    assert!(WebDID::document_url(&did, false).is_err());
+    assert_eq!(
+      WebDID::document_url(&did, true).unwrap().as_str(),
+      "https://127.0.0.1:8080/alice/did.json"
+    );
+
+    let did: CoreDID = CoreDID::parse("did:web:B%C3%BCcher.example").unwrap();
+    assert_eq!(
+      WebDID::document_url(&did, false).unwrap().as_str(),
+      "https://xn--bcher-kva.example/.well-known/did.json"
+    );
+  }
+}
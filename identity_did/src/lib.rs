@@ -19,10 +19,13 @@
 #[allow(clippy::module_inception)]
 mod did;
 mod did_jwk;
+mod did_key;
+mod did_peer;
 mod did_url;
 mod error;
 mod did_web;
 mod did_compositejwk;
+pub mod multicodec;
 
 pub use crate::did_url::DIDUrl;
 pub use crate::did_url::RelativeDIDUrl;
@@ -30,6 +33,8 @@ pub use ::did_url_parser::DID as BaseDIDUrl;
 pub use did::CoreDID;
 pub use did::DID;
 pub use did_jwk::*;
+pub use did_key::*;
+pub use did_peer::*;
 pub use error::Error;
 pub use did_web::*;
 pub use did_compositejwk::*;
\ No newline at end of file
@@ -0,0 +1,57 @@
+// Copyright 2020-2024 IOTA Stiftung, Fondazione Links
+// SPDX-License-Identifier: Apache-2.0
+
+//! Unsigned varints used to prefix multicodec encoded values, see
+//! [unsigned-varint](https://github.com/multiformats/unsigned-varint).
+
+/// Encodes `value` as an unsigned varint.
+pub fn encode_varint(mut value: u64) -> Vec<u8> {
+  let mut bytes: Vec<u8> = Vec::new();
+  loop {
+    let byte: u8 = (value & 0x7f) as u8;
+    value >>= 7;
+    if value == 0 {
+      bytes.push(byte);
+      return bytes;
+    }
+    bytes.push(byte | 0x80);
+  }
+}
+
+/// Decodes an unsigned varint of at most 9 bytes, returning the value and the remaining bytes.
+///
+/// Returns `None` if `bytes` does not start with a minimally encoded varint.
+pub fn decode_varint(bytes: &[u8]) -> Option<(u64, &[u8])> {
+  let mut value: u64 = 0;
+  for (index, byte) in bytes.iter().enumerate().take(9) {
+    value |= u64::from(byte & 0x7f) << (7 * index);
+    if byte & 0x80 == 0 {
+      // Reject non-minimal encodings.
+      if index > 0 && *byte == 0 {
+        return None;
+      }
+      return Some((value, &bytes[index + 1..]));
+    }
+  }
+  None
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_varint() {
+    for (code, encoded) in [
+      (0xed, vec![0xed, 0x01]),
+      (0x1200, vec![0x80, 0x24]),
+      (0x1210, vec![0x90, 0x24]),
+      (0x30_0001, vec![0x81, 0x80, 0xc0, 0x01]),
+    ] {
+      assert_eq!(encode_varint(code), encoded);
+      assert_eq!(decode_varint(&encoded), Some((code, [].as_slice())));
+    }
+    assert_eq!(decode_varint(&[0xed, 0x00]), None);
+    assert_eq!(decode_varint(&[0xed]), None);
+  }
+}
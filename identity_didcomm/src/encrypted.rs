@@ -0,0 +1,350 @@
+// Copyright 2020-2024 IOTA Stiftung, Fondazione Links
+// SPDX-License-Identifier: Apache-2.0
+
+use crypto::hashes::sha::SHA256;
+use crypto::hashes::sha::SHA256_LEN;
+use crypto::keys::x25519;
+use identity_core::common::Url;
+use identity_core::convert::ToJson;
+use identity_document::document::CoreDocument;
+use identity_resolver::Resolver;
+use identity_verification::jose::jwk::JwkType;
+use identity_verification::jwu::encode_b64;
+
+use crate::jwe::concat_kdf;
+use crate::jwe::encrypt_content;
+use crate::jwe::generate_cek;
+use crate::jwe::wrap_key;
+use crate::jwe::EncryptedContent;
+use crate::key_agreement::agreement_key;
+use crate::key_agreement::agreement_keys;
+use crate::key_agreement::check_x25519_shared_secret;
+use crate::key_agreement::did_of;
+use crate::key_agreement::encode_x25519_jwk;
+use crate::key_agreement::resolve_document;
+use crate::key_agreement::x25519_public_key;
+use crate::key_agreement::AgreementKey;
+use crate::routing::wrap_in_forward;
+use crate::ContentEncryptionAlgorithm;
+use crate::DidCommService;
+use crate::Error;
+use crate::Jwe;
+use crate::JweHeader;
+use crate::JweRecipient;
+use crate::JweRecipientHeader;
+use crate::KeyAgreementStorage;
+use crate::KeyManagementAlgorithm;
+use crate::Message;
+use crate::Result;
+use crate::SignedMessage;
+use crate::ENCRYPTED_TYP;
+
+/// Options of [`Message::pack_encrypted`] and [`SignedMessage::pack_encrypted`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct EncryptOptions {
+  /// The key management algorithm.
+  pub alg: KeyManagementAlgorithm,
+  /// The content encryption algorithm.
+  pub enc: ContentEncryptionAlgorithm,
+  /// The id of the `keyAgreement` method of the sender, for authenticated encryption.
+  pub sender_kid: Option<String>,
+  /// Whether to wrap the message in `forward` envelopes for the mediators of the recipient.
+  pub forward: bool,
+}
+
+impl EncryptOptions {
+  /// Anonymous encryption (anoncrypt): the recipient cannot tell who sent the message.
+  pub fn anoncrypt() -> Self {
+    Self {
+      alg: KeyManagementAlgorithm::EcdhEsA256Kw,
+      enc: ContentEncryptionAlgorithm::A256CbcHs512,
+      sender_kid: None,
+      forward: true,
+    }
+  }
+
+  /// Authenticated encryption (authcrypt) with the X25519 `keyAgreement` method `sender_kid` of the sender.
+  pub fn authcrypt(sender_kid: impl Into<String>) -> Self {
+    Self {
+      alg: KeyManagementAlgorithm::Ecdh1PuA256Kw,
+      enc: ContentEncryptionAlgorithm::A256CbcHs512,
+      sender_kid: Some(sender_kid.into()),
+      forward: true,
+    }
+  }
+
+  /// Experimental hybrid anonymous encryption, combining X25519 with the Kyber768 `keyAgreement` method of the
+  /// recipient.
+  #[cfg(feature = "kyber")]
+  pub fn hybrid_anoncrypt() -> Self {
+    Self {
+      alg: KeyManagementAlgorithm::EcdhEsKyber768A256Kw,
+      enc: ContentEncryptionAlgorithm::A256Gcm,
+      sender_kid: None,
+      forward: true,
+    }
+  }
+
+  /// Sets the content encryption algorithm.
+  pub fn content_encryption(mut self, enc: ContentEncryptionAlgorithm) -> Self {
+    self.enc = enc;
+    self
+  }
+
+  /// Sets whether to wrap the message in `forward` envelopes for the mediators of the recipient.
+  pub fn forward(mut self, forward: bool) -> Self {
+    self.forward = forward;
+    self
+  }
+}
+
+/// A message packed for its recipient.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct PackedMessage {
+  /// The packed message, to be sent as it is.
+  pub message: String,
+  /// The endpoint of the `DIDCommMessaging` service the message must be sent to, if the recipient has one.
+  pub service_endpoint: Option<Url>,
+  /// The ids of the keys of the recipient the message is encrypted for.
+  pub to_kids: Vec<String>,
+  /// The id of the key of the sender, for authenticated encryption.
+  pub from_kid: Option<String>,
+  /// Whether the message is wrapped in `forward` envelopes for the mediators of the recipient.
+  pub forwarded: bool,
+}
+
+impl Message {
+  /// Encrypts the message for the `keyAgreement` keys of the recipient `to`, resolved with `resolver`.
+  ///
+  /// `to` is either a DID among the recipients of the message, in which case the message is encrypted for all its
+  /// X25519 `keyAgreement` keys, or the DID URL of one of these keys. For authenticated encryption the private key of
+  /// the sender is used from `storage`, and the sender must be the `from` of the message.
+  pub async fn pack_encrypted<DOC, S>(
+    &self,
+    to: &str,
+    options: &EncryptOptions,
+    resolver: &Resolver<DOC>,
+    storage: &S,
+  ) -> Result<PackedMessage>
+  where
+    DOC: AsRef<CoreDocument> + 'static,
+    S: KeyAgreementStorage + ?Sized,
+  {
+    let to_did: String = did_of(to)?;
+    if !self.to.iter().any(|recipient| recipient == &to_did) {
+      return Err(Error::InvalidMessage(format!(
+        "`{to_did}` is not a recipient of the message"
+      )));
+    }
+    if let Some(sender_kid) = &options.sender_kid {
+      if self.from.as_deref() != Some(did_of(sender_kid)?.as_str()) {
+        return Err(Error::SenderMismatch(format!(
+          "the message is not sent by `{sender_kid}`"
+        )));
+      }
+    }
+    pack_payload(self.to_json_string()?.as_bytes(), to, options, resolver, storage).await
+  }
+}
+
+impl SignedMessage {
+  /// Encrypts the signed message for the `keyAgreement` keys of the recipient `to`, resolved with `resolver`.
+  ///
+  /// See [`Message::pack_encrypted`].
+  pub async fn pack_encrypted<DOC, S>(
+    &self,
+    to: &str,
+    options: &EncryptOptions,
+    resolver: &Resolver<DOC>,
+    storage: &S,
+  ) -> Result<PackedMessage>
+  where
+    DOC: AsRef<CoreDocument> + 'static,
+    S: KeyAgreementStorage + ?Sized,
+  {
+    pack_payload(self.to_json_string()?.as_bytes(), to, options, resolver, storage).await
+  }
+}
+
+/// Encrypts `payload` for `to` and wraps it for the mediators of the recipient.
+async fn pack_payload<DOC, S>(
+  payload: &[u8],
+  to: &str,
+  options: &EncryptOptions,
+  resolver: &Resolver<DOC>,
+  storage: &S,
+) -> Result<PackedMessage>
+where
+  DOC: AsRef<CoreDocument> + 'static,
+  S: KeyAgreementStorage + ?Sized,
+{
+  let to_did: String = did_of(to)?;
+  let document: DOC = resolve_document(resolver, &to_did).await?;
+  let keys: Vec<AgreementKey> = agreement_keys(document.as_ref(), to, JwkType::Okp)?;
+
+  let sender: Option<AgreementKey> = match (&options.sender_kid, options.alg.is_authenticated()) {
+    (Some(sender_kid), true) => {
+      let sender_document: DOC = resolve_document(resolver, &did_of(sender_kid)?).await?;
+      Some(agreement_key(sender_document.as_ref(), sender_kid, JwkType::Okp)?)
+    }
+    (None, false) => None,
+    (Some(_), false) => {
+      return Err(Error::InvalidMessage(format!(
+        "{} does not authenticate the sender",
+        options.alg
+      )))
+    }
+    (None, true) => return Err(Error::InvalidMessage(format!("{} requires a sender key", options.alg))),
+  };
+  if sender.is_some() && options.enc != ContentEncryptionAlgorithm::A256CbcHs512 {
+    return Err(Error::UnsupportedAlgorithm(format!(
+      "{} with {}",
+      options.alg, options.enc
+    )));
+  }
+
+  let mut recipients: Vec<RecipientKey> = Vec::with_capacity(keys.len());
+  for key in keys {
+    let static_secret: Option<Vec<u8>> = match &sender {
+      Some(sender) => Some(check_x25519_shared_secret(
+        storage.diffie_hellman(&sender.jwk, &key.jwk).await?,
+      )?),
+      None => None,
+    };
+    #[cfg(feature = "kyber")]
+    let kem_key: Option<AgreementKey> = match options.alg {
+      #[cfg(feature = "kyber")]
+      KeyManagementAlgorithm::EcdhEsKyber768A256Kw => {
+        Some(agreement_keys(document.as_ref(), &to_did, JwkType::KYBER)?.remove(0))
+      }
+      _ => None,
+    };
+    recipients.push(RecipientKey {
+      key,
+      #[cfg(feature = "kyber")]
+      kem_key,
+      static_secret,
+    });
+  }
+
+  let jwe: Jwe = encrypt_jwe(
+    payload,
+    &recipients,
+    options.alg,
+    options.enc,
+    sender.as_ref().map(|sender| sender.kid.as_str()),
+  )?;
+  let packed: PackedMessage = PackedMessage {
+    message: jwe
+      .to_json()
+      .map_err(|err| Error::Encryption(format!("could not serialize the JWE: {err}")))?,
+    service_endpoint: None,
+    to_kids: recipients.into_iter().map(|recipient| recipient.key.kid).collect(),
+    from_kid: sender.map(|sender| sender.kid),
+    forwarded: false,
+  };
+
+  match DidCommService::from_document(document.as_ref())? {
+    Some(service) if options.forward => wrap_in_forward(packed, &to_did, &service, resolver).await,
+    Some(service) => Ok(PackedMessage {
+      service_endpoint: Some(service.uri),
+      ..packed
+    }),
+    None => Ok(packed),
+  }
+}
+
+/// A key of a recipient of a [`Jwe`].
+pub(crate) struct RecipientKey {
+  /// The X25519 key of the recipient.
+  pub(crate) key: AgreementKey,
+  /// The Kyber key of the recipient, for hybrid encryption.
+  #[cfg(feature = "kyber")]
+  pub(crate) kem_key: Option<AgreementKey>,
+  /// The shared secret of the static keys of the sender and recipient, for authenticated encryption.
+  pub(crate) static_secret: Option<Vec<u8>>,
+}
+
+/// Returns the `apv` of a JWE for the recipient keys `kids`.
+pub(crate) fn apv<'a>(kids: impl Iterator<Item = &'a str>) -> [u8; SHA256_LEN] {
+  let mut kids: Vec<&str> = kids.collect();
+  kids.sort_unstable();
+  let mut digest: [u8; SHA256_LEN] = [0; SHA256_LEN];
+  SHA256(kids.join(".").as_bytes(), &mut digest);
+  digest
+}
+
+/// Encrypts `payload` for `recipients`.
+pub(crate) fn encrypt_jwe(
+  payload: &[u8],
+  recipients: &[RecipientKey],
+  alg: KeyManagementAlgorithm,
+  enc: ContentEncryptionAlgorithm,
+  sender_kid: Option<&str>,
+) -> Result<Jwe> {
+  let ephemeral_key: x25519::SecretKey =
+    x25519::SecretKey::generate().map_err(|err| Error::Encryption(err.to_string()))?;
+  let apu: Option<Vec<u8>> = sender_kid.map(|kid| kid.as_bytes().to_vec());
+  let apv: [u8; SHA256_LEN] = apv(recipients.iter().map(|recipient| recipient.key.kid.as_str()));
+
+  let header: JweHeader = JweHeader {
+    typ: Some(ENCRYPTED_TYP.to_owned()),
+    alg,
+    enc,
+    skid: sender_kid.map(ToOwned::to_owned),
+    apu: apu.as_ref().map(encode_b64),
+    apv: encode_b64(apv),
+    epk: encode_x25519_jwk(&ephemeral_key.public_key()),
+  };
+  let protected: String = encode_b64(
+    header
+      .to_json_vec()
+      .map_err(|err| Error::Encryption(format!("could not serialize the JWE header: {err}")))?,
+  );
+
+  let cek: Vec<u8> = generate_cek(enc)?;
+  let content: EncryptedContent = encrypt_content(enc, &cek, protected.as_bytes(), payload)?;
+  // ECDH-1PU binds the key wrapping keys to the authentication tag of the content.
+  let cc_tag: &[u8] = if alg.is_authenticated() { &content.tag } else { &[] };
+
+  let recipients: Vec<JweRecipient> = recipients
+    .iter()
+    .map(|recipient| {
+      let public_key: x25519::PublicKey = x25519_public_key(&recipient.key.jwk)?;
+      let mut z: Vec<u8> = check_x25519_shared_secret(ephemeral_key.diffie_hellman(&public_key).as_bytes().to_vec())?;
+      if let Some(static_secret) = &recipient.static_secret {
+        z.extend_from_slice(static_secret);
+      }
+
+      #[cfg_attr(not(feature = "kyber"), allow(unused_mut))]
+      let mut header: JweRecipientHeader = JweRecipientHeader {
+        kid: recipient.key.kid.clone(),
+        pq_kid: None,
+        ek: None,
+      };
+      #[cfg(feature = "kyber")]
+      if let Some(kem_key) = &recipient.kem_key {
+        let (ciphertext, shared_secret) = crate::jwe::kyber::encapsulate(&kem_key.jwk)?;
+        z.extend_from_slice(&shared_secret);
+        header.pq_kid = Some(kem_key.kid.clone());
+        header.ek = Some(encode_b64(ciphertext));
+      }
+
+      let kek = concat_kdf(&z, alg, apu.as_deref().unwrap_or_default(), &apv, cc_tag);
+      Ok(JweRecipient {
+        header,
+        encrypted_key: encode_b64(wrap_key(&kek, &cek)?),
+      })
+    })
+    .collect::<Result<_>>()?;
+
+  Ok(Jwe {
+    protected,
+    recipients,
+    iv: encode_b64(content.iv),
+    ciphertext: encode_b64(content.ciphertext),
+    tag: encode_b64(content.tag),
+  })
+}
@@ -0,0 +1,52 @@
+// Copyright 2020-2024 IOTA Stiftung, Fondazione Links
+// SPDX-License-Identifier: Apache-2.0
+
+/// Alias for a `Result` with the error type [`Error`].
+pub type Result<T, E = Error> = core::result::Result<T, E>;
+
+/// Errors that can occur when packing or unpacking DIDComm messages.
+#[derive(Debug, thiserror::Error, strum::IntoStaticStr)]
+#[non_exhaustive]
+pub enum Error {
+  /// Caused by a message, JWS or JWE that is malformed or violates the DIDComm specification.
+  #[error("invalid DIDComm message: {0}")]
+  InvalidMessage(String),
+  /// Caused by a message whose `expires_time` has passed.
+  #[error("the DIDComm message has expired")]
+  MessageExpired,
+  /// Caused by a failure to resolve the DID document of a sender, recipient or mediator.
+  #[error("could not resolve the DID document of `{did}`")]
+  Resolution {
+    /// The DID that could not be resolved.
+    did: String,
+    /// The error returned by the resolver.
+    source: identity_resolver::Error,
+  },
+  /// Caused by a DID document without a key suitable for the requested operation.
+  #[error("no suitable key found: {0}")]
+  KeyNotFound(String),
+  /// Caused by a JWE or key using an algorithm that is not supported.
+  #[error("unsupported algorithm `{0}`")]
+  UnsupportedAlgorithm(String),
+  /// Caused by a failure to encrypt a message.
+  #[error("encryption failed: {0}")]
+  Encryption(String),
+  /// Caused by a failure to decrypt a message, e.g. because it was not encrypted for any key in storage.
+  #[error("decryption failed: {0}")]
+  Decryption(String),
+  /// Caused by a failure to sign a message with the key storage.
+  #[error("signing the message failed")]
+  Signing(#[source] identity_storage::JwkStorageDocumentError),
+  /// Caused by a signature that cannot be verified with the key of the signer.
+  #[error("the signature of the message could not be verified")]
+  SignatureVerification(#[source] identity_document::Error),
+  /// Caused by a signer or authenticated sender that does not match the `from` of the plaintext message.
+  #[error("the sender of the message does not match its `from`: {0}")]
+  SenderMismatch(String),
+  /// Caused by a failure of the [`KeyAgreementStorage`](crate::KeyAgreementStorage).
+  #[error("key agreement storage operation failed: {0}")]
+  KeyStorage(String),
+  /// Caused by a message that cannot be routed to its recipient.
+  #[error("routing failed: {0}")]
+  Routing(String),
+}
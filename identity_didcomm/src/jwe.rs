@@ -0,0 +1,344 @@
+// Copyright 2020-2024 IOTA Stiftung, Fondazione Links
+// SPDX-License-Identifier: Apache-2.0
+
+use core::num::NonZeroUsize;
+
+use crypto::ciphers::aes_cbc::Aes256CbcHmac512;
+use crypto::ciphers::aes_gcm::Aes256Gcm;
+use crypto::ciphers::aes_kw::Aes256Kw;
+use crypto::ciphers::traits::Aead;
+use crypto::hashes::sha::SHA256;
+use crypto::hashes::sha::SHA256_LEN;
+use identity_verification::jose::jwk::Jwk;
+use serde::Deserialize;
+use serde::Serialize;
+
+use crate::Error;
+use crate::Result;
+
+/// The length in bytes of the key encryption keys derived for `A256KW`.
+const KEK_LENGTH: usize = 32;
+/// The length in bytes of the integrity check value AES key wrap prepends to the wrapped key.
+const KEY_WRAP_OVERHEAD: usize = 8;
+
+/// The JWE key management algorithms used to encrypt the content encryption key for each recipient.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[non_exhaustive]
+pub enum KeyManagementAlgorithm {
+  /// Anonymous encryption: ephemeral-static X25519 key agreement with the key wrapped by `A256KW`.
+  #[serde(rename = "ECDH-ES+A256KW")]
+  EcdhEsA256Kw,
+  /// Authenticated encryption: ephemeral-static and static-static X25519 key agreement with the key wrapped by
+  /// `A256KW`, see [ECDH-1PU](https://datatracker.ietf.org/doc/html/draft-madden-jose-ecdh-1pu-04).
+  #[serde(rename = "ECDH-1PU+A256KW")]
+  Ecdh1PuA256Kw,
+  /// Experimental hybrid anonymous encryption: the shared secrets of an ephemeral-static X25519 key agreement and of
+  /// a Kyber768 encapsulation are combined to derive the key wrapping key.
+  #[cfg(feature = "kyber")]
+  #[serde(rename = "ECDH-ES+Kyber768+A256KW")]
+  EcdhEsKyber768A256Kw,
+}
+
+impl KeyManagementAlgorithm {
+  /// Returns the JWE `alg` of this algorithm.
+  pub const fn name(self) -> &'static str {
+    match self {
+      Self::EcdhEsA256Kw => "ECDH-ES+A256KW",
+      Self::Ecdh1PuA256Kw => "ECDH-1PU+A256KW",
+      #[cfg(feature = "kyber")]
+      Self::EcdhEsKyber768A256Kw => "ECDH-ES+Kyber768+A256KW",
+    }
+  }
+
+  /// Returns whether the algorithm authenticates the sender.
+  pub const fn is_authenticated(self) -> bool {
+    matches!(self, Self::Ecdh1PuA256Kw)
+  }
+}
+
+impl std::fmt::Display for KeyManagementAlgorithm {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    f.write_str(self.name())
+  }
+}
+
+/// The JWE content encryption algorithms.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[non_exhaustive]
+pub enum ContentEncryptionAlgorithm {
+  /// AES-256-CBC with HMAC-SHA-512, required for authenticated encryption.
+  #[serde(rename = "A256CBC-HS512")]
+  A256CbcHs512,
+  /// AES-256-GCM.
+  #[serde(rename = "A256GCM")]
+  A256Gcm,
+}
+
+impl ContentEncryptionAlgorithm {
+  /// Returns the JWE `enc` of this algorithm.
+  pub const fn name(self) -> &'static str {
+    match self {
+      Self::A256CbcHs512 => "A256CBC-HS512",
+      Self::A256Gcm => "A256GCM",
+    }
+  }
+}
+
+impl std::fmt::Display for ContentEncryptionAlgorithm {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    f.write_str(self.name())
+  }
+}
+
+/// The protected header shared by all recipients of a [`Jwe`].
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct JweHeader {
+  /// The media type of the encrypted message.
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub typ: Option<String>,
+  /// The key management algorithm.
+  pub alg: KeyManagementAlgorithm,
+  /// The content encryption algorithm.
+  pub enc: ContentEncryptionAlgorithm,
+  /// The id of the key of the sender, for authenticated encryption.
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub skid: Option<String>,
+  /// The base64url-encoded id of the key of the sender, for authenticated encryption.
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub apu: Option<String>,
+  /// The base64url-encoded SHA-256 digest of the sorted ids of the recipient keys.
+  pub apv: String,
+  /// The ephemeral public key of the sender.
+  pub epk: Jwk,
+}
+
+/// The unprotected header of a recipient of a [`Jwe`].
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct JweRecipientHeader {
+  /// The id of the X25519 key of the recipient.
+  pub kid: String,
+  /// The id of the Kyber key of the recipient, for hybrid encryption.
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub pq_kid: Option<String>,
+  /// The base64url-encoded Kyber ciphertext encapsulating the shared secret, for hybrid encryption.
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub ek: Option<String>,
+}
+
+/// A recipient of a [`Jwe`].
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct JweRecipient {
+  /// The unprotected header of the recipient.
+  pub header: JweRecipientHeader,
+  /// The base64url-encoded content encryption key, wrapped for the recipient.
+  pub encrypted_key: String,
+}
+
+/// An encrypted DIDComm message in the [General JWE JSON Serialization](https://www.rfc-editor.org/rfc/rfc7516#section-7.2.1).
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Jwe {
+  /// The base64url-encoded [`JweHeader`].
+  pub protected: String,
+  /// The recipients of the message.
+  pub recipients: Vec<JweRecipient>,
+  /// The base64url-encoded initialization vector.
+  pub iv: String,
+  /// The base64url-encoded ciphertext.
+  pub ciphertext: String,
+  /// The base64url-encoded authentication tag.
+  pub tag: String,
+}
+
+/// Derives a key wrapping key from the shared secret `z` with the
+/// [Concat KDF](https://www.rfc-editor.org/rfc/rfc7518#section-4.6.2).
+///
+/// `cc_tag` is the authentication tag of the content, included in the derivation of ECDH-1PU keys and empty
+/// otherwise.
+pub(crate) fn concat_kdf(
+  z: &[u8],
+  alg: KeyManagementAlgorithm,
+  apu: &[u8],
+  apv: &[u8],
+  cc_tag: &[u8],
+) -> [u8; KEK_LENGTH] {
+  fn length_prefixed(input: &mut Vec<u8>, data: &[u8]) {
+    input.extend_from_slice(&(data.len() as u32).to_be_bytes());
+    input.extend_from_slice(data);
+  }
+
+  // A single round produces the 256 bits of an A256KW key.
+  let mut input: Vec<u8> = 1u32.to_be_bytes().to_vec();
+  input.extend_from_slice(z);
+  length_prefixed(&mut input, alg.name().as_bytes());
+  length_prefixed(&mut input, apu);
+  length_prefixed(&mut input, apv);
+  input.extend_from_slice(&((KEK_LENGTH * 8) as u32).to_be_bytes());
+  if !cc_tag.is_empty() {
+    length_prefixed(&mut input, cc_tag);
+  }
+
+  let mut kek: [u8; SHA256_LEN] = [0; SHA256_LEN];
+  SHA256(&input, &mut kek);
+  kek
+}
+
+/// Wraps the content encryption key `cek` with `kek` using `A256KW`.
+pub(crate) fn wrap_key(kek: &[u8], cek: &[u8]) -> Result<Vec<u8>> {
+  let mut wrapped: Vec<u8> = vec![0; cek.len() + KEY_WRAP_OVERHEAD];
+  Aes256Kw::new(kek)
+    .wrap_key(cek, &mut wrapped)
+    .map_err(|err| Error::Encryption(format!("key wrapping failed: {err}")))?;
+  Ok(wrapped)
+}
+
+/// Unwraps a content encryption key wrapped with `kek` using `A256KW`.
+pub(crate) fn unwrap_key(kek: &[u8], wrapped: &[u8]) -> Result<Vec<u8>> {
+  if wrapped.len() <= KEY_WRAP_OVERHEAD {
+    return Err(Error::Decryption("invalid encrypted key".to_owned()));
+  }
+  let mut cek: Vec<u8> = vec![0; wrapped.len() - KEY_WRAP_OVERHEAD];
+  Aes256Kw::new(kek)
+    .unwrap_key(wrapped, &mut cek)
+    .map_err(|_| Error::Decryption("the encrypted key could not be unwrapped".to_owned()))?;
+  Ok(cek)
+}
+
+/// Generates a random content encryption key for `enc`.
+pub(crate) fn generate_cek(enc: ContentEncryptionAlgorithm) -> Result<Vec<u8>> {
+  let length: usize = match enc {
+    ContentEncryptionAlgorithm::A256CbcHs512 => Aes256CbcHmac512::KEY_LENGTH,
+    ContentEncryptionAlgorithm::A256Gcm => Aes256Gcm::KEY_LENGTH,
+  };
+  let mut cek: Vec<u8> = vec![0; length];
+  crypto::utils::rand::fill(&mut cek).map_err(|err| Error::Encryption(err.to_string()))?;
+  Ok(cek)
+}
+
+/// The output of the content encryption.
+pub(crate) struct EncryptedContent {
+  pub(crate) iv: Vec<u8>,
+  pub(crate) ciphertext: Vec<u8>,
+  pub(crate) tag: Vec<u8>,
+}
+
+/// Encrypts `plaintext` with `cek`, authenticating `aad`.
+pub(crate) fn encrypt_content(
+  enc: ContentEncryptionAlgorithm,
+  cek: &[u8],
+  aad: &[u8],
+  plaintext: &[u8],
+) -> Result<EncryptedContent> {
+  fn encrypt<A: Aead>(cek: &[u8], aad: &[u8], plaintext: &[u8]) -> Result<EncryptedContent> {
+    let mut iv: Vec<u8> = vec![0; A::NONCE_LENGTH];
+    crypto::utils::rand::fill(&mut iv).map_err(|err| Error::Encryption(err.to_string()))?;
+    let mut ciphertext: Vec<u8> = vec![0; plaintext.len() + A::padsize(plaintext).map_or(0, NonZeroUsize::get)];
+    let mut tag: Vec<u8> = vec![0; A::TAG_LENGTH];
+    let length: usize = A::try_encrypt(cek, &iv, aad, plaintext, &mut ciphertext, &mut tag)
+      .map_err(|err| Error::Encryption(err.to_string()))?;
+    ciphertext.truncate(length);
+    Ok(EncryptedContent { iv, ciphertext, tag })
+  }
+
+  match enc {
+    ContentEncryptionAlgorithm::A256CbcHs512 => encrypt::<Aes256CbcHmac512>(cek, aad, plaintext),
+    ContentEncryptionAlgorithm::A256Gcm => encrypt::<Aes256Gcm>(cek, aad, plaintext),
+  }
+}
+
+/// Decrypts the content encrypted with `cek`, checking the authenticity of the content and of `aad`.
+pub(crate) fn decrypt_content(
+  enc: ContentEncryptionAlgorithm,
+  cek: &[u8],
+  aad: &[u8],
+  content: &EncryptedContent,
+) -> Result<Vec<u8>> {
+  fn decrypt<A: Aead>(cek: &[u8], aad: &[u8], content: &EncryptedContent) -> Result<Vec<u8>> {
+    if cek.len() != A::KEY_LENGTH || content.iv.len() != A::NONCE_LENGTH || content.tag.len() != A::TAG_LENGTH {
+      return Err(Error::Decryption("invalid key, iv or tag length".to_owned()));
+    }
+    let mut plaintext: Vec<u8> = vec![0; content.ciphertext.len()];
+    let length: usize = A::try_decrypt(cek, &content.iv, aad, &mut plaintext, &content.ciphertext, &content.tag)
+      .map_err(|_| Error::Decryption("the content could not be authenticated".to_owned()))?;
+    plaintext.truncate(length);
+    Ok(plaintext)
+  }
+
+  match enc {
+    ContentEncryptionAlgorithm::A256CbcHs512 => decrypt::<Aes256CbcHmac512>(cek, aad, content),
+    ContentEncryptionAlgorithm::A256Gcm => decrypt::<Aes256Gcm>(cek, aad, content),
+  }
+}
+
+/// Kyber768 key generation and encapsulation.
+///
+/// NOTE: this is the round 3 Kyber768 submission, not the standardized ML-KEM-768 (FIPS 203), which the bundled
+/// liboqs does not provide. The two are not interoperable.
+#[cfg(feature = "kyber")]
+pub(crate) mod kyber {
+  use identity_verification::jose::jwk::Jwk;
+  use identity_verification::jose::jwk::JwkParams;
+  use identity_verification::jose::jwk::JwkParamsPQ;
+  use identity_verification::jwu::decode_b64;
+  use identity_verification::jwu::encode_b64;
+  use oqs::kem::Algorithm;
+  use oqs::kem::Kem;
+
+  use crate::Error;
+  use crate::Result;
+  use crate::KYBER_768;
+
+  fn kem() -> Result<Kem> {
+    oqs::init();
+    Kem::new(Algorithm::Kyber768).map_err(|err| Error::UnsupportedAlgorithm(format!("{KYBER_768}: {err}")))
+  }
+
+  /// Generates a key pair, returning the public JWK and the private key.
+  pub(crate) fn generate() -> Result<(Jwk, Vec<u8>)> {
+    let (public_key, secret_key) = kem()?
+      .keypair()
+      .map_err(|err| Error::KeyStorage(format!("key generation failed: {err}")))?;
+    let mut jwk: Jwk = Jwk::from_params(JwkParams::KYBER(JwkParamsPQ {
+      public: encode_b64(public_key.into_vec()),
+      private: None,
+    }));
+    jwk.set_alg(KYBER_768);
+    Ok((jwk, secret_key.into_vec()))
+  }
+
+  /// Encapsulates a fresh shared secret for `public_key`, returning the ciphertext and the shared secret.
+  pub(crate) fn encapsulate(public_key: &Jwk) -> Result<(Vec<u8>, Vec<u8>)> {
+    if public_key.alg() != Some(KYBER_768) {
+      return Err(Error::UnsupportedAlgorithm(
+        public_key.alg().unwrap_or_default().to_owned(),
+      ));
+    }
+    let public: Vec<u8> = public_key
+      .try_pq_params()
+      .ok()
+      .and_then(|params| decode_b64(params.public.as_str()).ok())
+      .ok_or_else(|| Error::InvalidMessage("invalid Kyber key".to_owned()))?;
+    let kem: Kem = kem()?;
+    let public = kem
+      .public_key_from_bytes(&public)
+      .ok_or_else(|| Error::InvalidMessage("invalid Kyber key".to_owned()))?;
+    let (ciphertext, shared_secret) = kem
+      .encapsulate(public)
+      .map_err(|err| Error::Encryption(format!("encapsulation failed: {err}")))?;
+    Ok((ciphertext.into_vec(), shared_secret.into_vec()))
+  }
+
+  /// Decapsulates the shared secret of `ciphertext` with `secret_key`.
+  pub(crate) fn decapsulate(secret_key: &[u8], ciphertext: &[u8]) -> Result<Vec<u8>> {
+    let kem: Kem = kem()?;
+    let secret_key = kem
+      .secret_key_from_bytes(secret_key)
+      .ok_or_else(|| Error::KeyStorage("invalid Kyber key".to_owned()))?;
+    let ciphertext = kem
+      .ciphertext_from_bytes(ciphertext)
+      .ok_or_else(|| Error::Decryption("invalid Kyber ciphertext".to_owned()))?;
+    kem
+      .decapsulate(secret_key, ciphertext)
+      .map(|shared_secret| shared_secret.into_vec())
+      .map_err(|err| Error::Decryption(format!("decapsulation failed: {err}")))
+  }
+}
@@ -0,0 +1,301 @@
+// Copyright 2020-2024 IOTA Stiftung, Fondazione Links
+// SPDX-License-Identifier: Apache-2.0
+
+use async_trait::async_trait;
+use crypto::keys::x25519;
+use identity_did::CoreDID;
+use identity_did::DIDUrl;
+use identity_document::document::CoreDocument;
+use identity_resolver::Resolver;
+use identity_verification::jose::jwk::EcxCurve;
+use identity_verification::jose::jwk::Jwk;
+use identity_verification::jose::jwk::JwkParamsOkp;
+use identity_verification::jose::jwk::JwkType;
+use identity_verification::jwu::decode_b64;
+use identity_verification::jwu::encode_b64;
+
+use crate::Error;
+use crate::Result;
+
+/// The `alg` of Kyber768 key agreement keys.
+pub const KYBER_768: &str = "Kyber768";
+
+/// The types of keys a [`KeyAgreementStorage`] can generate.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum KeyAgreementKeyType {
+  /// X25519 keys, used for ECDH-ES and ECDH-1PU key agreement.
+  X25519,
+  /// Kyber768 keys, used by the experimental hybrid encryption profile.
+  #[cfg(feature = "kyber")]
+  Kyber768,
+}
+
+#[cfg(not(feature = "send-sync-storage"))]
+mod storage_sub_trait {
+  pub trait StorageSendSyncMaybe {}
+  impl<S: super::KeyAgreementStorage> StorageSendSyncMaybe for S {}
+}
+
+#[cfg(feature = "send-sync-storage")]
+mod storage_sub_trait {
+  pub trait StorageSendSyncMaybe: Send + Sync {}
+  impl<S: Send + Sync + super::KeyAgreementStorage> StorageSendSyncMaybe for S {}
+}
+
+/// Secure storage for the private keys of the `keyAgreement` methods of DID documents.
+///
+/// Keys are identified by their public JWK: the private key material never leaves the storage, which only exposes
+/// the key agreement operations needed to decrypt and authenticate DIDComm messages.
+#[cfg_attr(not(feature = "send-sync-storage"), async_trait(?Send))]
+#[cfg_attr(feature = "send-sync-storage", async_trait)]
+pub trait KeyAgreementStorage: storage_sub_trait::StorageSendSyncMaybe {
+  /// Generates a new key of type `key_type` and returns its public JWK.
+  ///
+  /// The returned JWK can be added to a DID document as a `keyAgreement` method.
+  async fn generate(&self, key_type: KeyAgreementKeyType) -> Result<Jwk>;
+
+  /// Returns `true` if the private key of `public_key` exists in storage, `false` otherwise.
+  async fn exists(&self, public_key: &Jwk) -> Result<bool>;
+
+  /// Associates the private key of `public_key` with `kid`, the id of the `keyAgreement` method it was published as.
+  ///
+  /// Only messages encrypted for the methods inserted this way are decrypted: other recipients of a message are
+  /// skipped without resolving their DID documents.
+  async fn insert_kid(&self, kid: &str, public_key: &Jwk) -> Result<()>;
+
+  /// Returns `true` if a private key is associated with the `keyAgreement` method `kid`, `false` otherwise.
+  async fn exists_kid(&self, kid: &str) -> Result<bool>;
+
+  /// Computes the X25519 shared secret between the private key of `public_key` and `peer_key`.
+  async fn diffie_hellman(&self, public_key: &Jwk, peer_key: &Jwk) -> Result<Vec<u8>>;
+
+  /// Decapsulates the shared secret encapsulated in `ciphertext` with the private KEM key of `public_key`.
+  async fn decapsulate(&self, public_key: &Jwk, ciphertext: &[u8]) -> Result<Vec<u8>>;
+}
+
+/// A `keyAgreement` method of a DID document.
+#[derive(Clone, Debug)]
+pub(crate) struct AgreementKey {
+  /// The id of the method, used as the `kid` of JWE recipients.
+  pub(crate) kid: String,
+  /// The public key of the method.
+  pub(crate) jwk: Jwk,
+}
+
+/// Resolves the DID document of `did` with `resolver`.
+pub(crate) async fn resolve_document<DOC>(resolver: &Resolver<DOC>, did: &str) -> Result<DOC>
+where
+  DOC: AsRef<CoreDocument> + 'static,
+{
+  let did: CoreDID = CoreDID::parse(did).map_err(|err| Error::InvalidMessage(format!("invalid DID `{did}`: {err}")))?;
+  resolver.resolve(&did).await.map_err(|source| Error::Resolution {
+    did: did.to_string(),
+    source,
+  })
+}
+
+/// Returns the DID of `did_or_kid`, which is either a DID or the DID URL of a method.
+pub(crate) fn did_of(did_or_kid: &str) -> Result<String> {
+  match DIDUrl::parse(did_or_kid) {
+    Ok(url) => Ok(url.did().to_string()),
+    Err(err) => Err(Error::InvalidMessage(format!("invalid DID `{did_or_kid}`: {err}"))),
+  }
+}
+
+/// Returns the `keyAgreement` methods of `document` of the given key type, restricted to the method identified by
+/// `did_or_kid` if it is a DID URL with a fragment.
+pub(crate) fn agreement_keys(document: &CoreDocument, did_or_kid: &str, kty: JwkType) -> Result<Vec<AgreementKey>> {
+  let kid: Option<DIDUrl> = DIDUrl::parse(did_or_kid).ok().filter(|url| url.fragment().is_some());
+
+  let keys: Vec<AgreementKey> = document
+    .key_agreement()
+    .iter()
+    .filter_map(|method_ref| document.resolve_method_ref(method_ref))
+    .filter(|method| kid.as_ref().map_or(true, |kid| method.id() == kid))
+    .filter_map(|method| {
+      let jwk: &Jwk = method.data().public_key_jwk()?;
+      let matches: bool = match kty {
+        JwkType::Okp => jwk
+          .try_okp_params()
+          .and_then(JwkParamsOkp::try_ecx_curve)
+          .map_or(false, |curve| curve == EcxCurve::X25519),
+        kty => jwk.kty() == kty,
+      };
+      matches.then(|| AgreementKey {
+        kid: method.id().to_string(),
+        jwk: jwk.clone(),
+      })
+    })
+    .collect();
+
+  if keys.is_empty() {
+    let key_type: &str = match kty {
+      JwkType::Okp => "X25519",
+      kty => kty.name(),
+    };
+    return Err(Error::KeyNotFound(format!(
+      "`{did_or_kid}` has no {key_type} keyAgreement method"
+    )));
+  }
+  Ok(keys)
+}
+
+/// Returns the `keyAgreement` method `kid` of `document`.
+pub(crate) fn agreement_key(document: &CoreDocument, kid: &str, kty: JwkType) -> Result<AgreementKey> {
+  if DIDUrl::parse(kid)
+    .ok()
+    .and_then(|url| url.fragment().map(ToOwned::to_owned))
+    .is_none()
+  {
+    return Err(Error::InvalidMessage(format!("`{kid}` is not the DID URL of a key")));
+  }
+  agreement_keys(document, kid, kty).map(|mut keys| keys.remove(0))
+}
+
+/// Decodes the X25519 public key of `jwk`.
+pub(crate) fn x25519_public_key(jwk: &Jwk) -> Result<x25519::PublicKey> {
+  let params: &JwkParamsOkp = jwk
+    .try_okp_params()
+    .map_err(|_| Error::UnsupportedAlgorithm(jwk.kty().to_string()))?;
+  if params.try_ecx_curve().ok() != Some(EcxCurve::X25519) {
+    return Err(Error::UnsupportedAlgorithm(params.crv.clone()));
+  }
+  let x: Vec<u8> =
+    decode_b64(params.x.as_str()).map_err(|err| Error::InvalidMessage(format!("invalid X25519 key: {err}")))?;
+  x25519::PublicKey::try_from_slice(&x).map_err(|err| Error::InvalidMessage(format!("invalid X25519 key: {err}")))
+}
+
+/// Rejects the all-zero output of an X25519 key agreement with a low order public key, see
+/// [RFC 7748 section 6.1](https://www.rfc-editor.org/rfc/rfc7748#section-6.1).
+pub(crate) fn check_x25519_shared_secret(shared_secret: Vec<u8>) -> Result<Vec<u8>> {
+  if shared_secret.iter().fold(0, |acc, byte| acc | byte) == 0 {
+    return Err(Error::InvalidMessage(
+      "the X25519 shared secret is all-zero: the key has low order".to_owned(),
+    ));
+  }
+  Ok(shared_secret)
+}
+
+/// Encodes an X25519 public key as a JWK.
+pub(crate) fn encode_x25519_jwk(public_key: &x25519::PublicKey) -> Jwk {
+  let mut params: JwkParamsOkp = JwkParamsOkp::new();
+  params.crv = EcxCurve::X25519.name().to_owned();
+  params.x = encode_b64(public_key.as_slice());
+  Jwk::from_params(params)
+}
+
+#[cfg(feature = "memstore")]
+mod memstore {
+  use std::collections::HashMap;
+
+  use async_trait::async_trait;
+  use crypto::keys::x25519;
+  use identity_verification::jose::jwk::Jwk;
+  use tokio::sync::RwLock;
+
+  use super::encode_x25519_jwk;
+  use super::x25519_public_key;
+  use super::KeyAgreementKeyType;
+  use super::KeyAgreementStorage;
+  use crate::Error;
+  use crate::Result;
+
+  enum PrivateKey {
+    X25519(x25519::SecretKey),
+    #[cfg(feature = "kyber")]
+    Kyber768(Vec<u8>),
+  }
+
+  /// An insecure, in-memory [`KeyAgreementStorage`] implementation that serves as an example and may be used in
+  /// tests.
+  #[derive(Default)]
+  pub struct KeyAgreementMemStore {
+    keys: RwLock<HashMap<String, PrivateKey>>,
+    kids: RwLock<HashMap<String, String>>,
+  }
+
+  impl KeyAgreementMemStore {
+    /// Creates a new, empty [`KeyAgreementMemStore`].
+    pub fn new() -> Self {
+      Self::default()
+    }
+
+    /// Returns the number of keys in the store.
+    pub async fn count(&self) -> usize {
+      self.keys.read().await.len()
+    }
+  }
+
+  impl std::fmt::Debug for KeyAgreementMemStore {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+      f.debug_struct("KeyAgreementMemStore").finish_non_exhaustive()
+    }
+  }
+
+  #[cfg_attr(not(feature = "send-sync-storage"), async_trait(?Send))]
+  #[cfg_attr(feature = "send-sync-storage", async_trait)]
+  impl KeyAgreementStorage for KeyAgreementMemStore {
+    async fn generate(&self, key_type: KeyAgreementKeyType) -> Result<Jwk> {
+      let (mut jwk, private_key): (Jwk, PrivateKey) = match key_type {
+        KeyAgreementKeyType::X25519 => {
+          let secret_key: x25519::SecretKey =
+            x25519::SecretKey::generate().map_err(|err| Error::KeyStorage(err.to_string()))?;
+          (
+            encode_x25519_jwk(&secret_key.public_key()),
+            PrivateKey::X25519(secret_key),
+          )
+        }
+        #[cfg(feature = "kyber")]
+        KeyAgreementKeyType::Kyber768 => {
+          let (public_key, secret_key) = crate::jwe::kyber::generate()?;
+          (public_key, PrivateKey::Kyber768(secret_key))
+        }
+      };
+      let kid: String = jwk.thumbprint_sha256_b64();
+      jwk.set_kid(kid.clone());
+      self.keys.write().await.insert(kid, private_key);
+      Ok(jwk)
+    }
+
+    async fn exists(&self, public_key: &Jwk) -> Result<bool> {
+      Ok(self.keys.read().await.contains_key(&public_key.thumbprint_sha256_b64()))
+    }
+
+    async fn insert_kid(&self, kid: &str, public_key: &Jwk) -> Result<()> {
+      let thumbprint: String = public_key.thumbprint_sha256_b64();
+      if !self.keys.read().await.contains_key(&thumbprint) {
+        return Err(Error::KeyStorage("key not found".to_owned()));
+      }
+      self.kids.write().await.insert(kid.to_owned(), thumbprint);
+      Ok(())
+    }
+
+    async fn exists_kid(&self, kid: &str) -> Result<bool> {
+      Ok(self.kids.read().await.contains_key(kid))
+    }
+
+    async fn diffie_hellman(&self, public_key: &Jwk, peer_key: &Jwk) -> Result<Vec<u8>> {
+      let peer_key: x25519::PublicKey = x25519_public_key(peer_key)?;
+      match self.keys.read().await.get(&public_key.thumbprint_sha256_b64()) {
+        Some(PrivateKey::X25519(secret_key)) => Ok(secret_key.diffie_hellman(&peer_key).as_bytes().to_vec()),
+        #[cfg(feature = "kyber")]
+        Some(_) => Err(Error::KeyStorage("the key is not an X25519 key".to_owned())),
+        None => Err(Error::KeyStorage("key not found".to_owned())),
+      }
+    }
+
+    #[cfg_attr(not(feature = "kyber"), allow(unused_variables))]
+    async fn decapsulate(&self, public_key: &Jwk, ciphertext: &[u8]) -> Result<Vec<u8>> {
+      match self.keys.read().await.get(&public_key.thumbprint_sha256_b64()) {
+        #[cfg(feature = "kyber")]
+        Some(PrivateKey::Kyber768(secret_key)) => crate::jwe::kyber::decapsulate(secret_key, ciphertext),
+        Some(_) => Err(Error::KeyStorage("the key is not a KEM key".to_owned())),
+        None => Err(Error::KeyStorage("key not found".to_owned())),
+      }
+    }
+  }
+}
+
+#[cfg(feature = "memstore")]
+pub use memstore::KeyAgreementMemStore;
@@ -0,0 +1,35 @@
+// Copyright 2020-2024 IOTA Stiftung, Fondazione Links
+// SPDX-License-Identifier: Apache-2.0
+
+#![forbid(unsafe_code)]
+#![doc = include_str!("./../README.md")]
+#![warn(
+  rust_2018_idioms,
+  unreachable_pub,
+  missing_docs,
+  rustdoc::missing_crate_level_docs,
+  rustdoc::broken_intra_doc_links,
+  rustdoc::private_intra_doc_links,
+  rustdoc::private_doc_tests,
+  clippy::missing_safety_doc
+)]
+
+mod encrypted;
+mod error;
+mod jwe;
+mod key_agreement;
+mod message;
+mod routing;
+mod signed;
+#[cfg(test)]
+mod tests;
+mod unpack;
+
+pub use self::encrypted::*;
+pub use self::error::*;
+pub use self::jwe::*;
+pub use self::key_agreement::*;
+pub use self::message::*;
+pub use self::routing::*;
+pub use self::signed::*;
+pub use self::unpack::*;
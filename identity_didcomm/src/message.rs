@@ -0,0 +1,227 @@
+// Copyright 2020-2024 IOTA Stiftung, Fondazione Links
+// SPDX-License-Identifier: Apache-2.0
+
+use identity_core::common::Object;
+use identity_core::common::Timestamp;
+use identity_core::common::Url;
+use identity_core::convert::FromJson;
+use identity_core::convert::ToJson;
+use identity_verification::jwu::encode_b64;
+use serde::Deserialize;
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::Error;
+use crate::Result;
+
+/// The media type of plaintext DIDComm messages.
+pub const PLAINTEXT_TYP: &str = "application/didcomm-plain+json";
+/// The media type of signed DIDComm messages.
+pub const SIGNED_TYP: &str = "application/didcomm-signed+json";
+/// The media type of encrypted DIDComm messages.
+pub const ENCRYPTED_TYP: &str = "application/didcomm-encrypted+json";
+
+/// A plaintext [DIDComm message](https://identity.foundation/didcomm-messaging/spec/v2.1/#plaintext-message-structure).
+///
+/// Plaintext messages are rarely sent as they are: they are signed with [`Message::pack_signed`] or encrypted for
+/// their recipients with [`Message::pack_encrypted`].
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Message {
+  /// Uniquely identifies the message among the messages of its sender.
+  pub id: String,
+  /// The media type of the message, [`PLAINTEXT_TYP`].
+  #[serde(default = "plaintext_typ")]
+  pub typ: String,
+  /// The URI of the protocol message type, e.g. `https://didcomm.org/trust-ping/2.0/ping`.
+  #[serde(rename = "type")]
+  pub type_: String,
+  /// The DID of the sender.
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub from: Option<String>,
+  /// The DIDs of the recipients.
+  #[serde(default, skip_serializing_if = "Vec::is_empty")]
+  pub to: Vec<String>,
+  /// The id of the thread the message belongs to.
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub thid: Option<String>,
+  /// The id of the parent thread.
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub pthid: Option<String>,
+  /// When the message was created, in seconds since the Unix epoch.
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub created_time: Option<i64>,
+  /// When the message expires, in seconds since the Unix epoch.
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub expires_time: Option<i64>,
+  /// The content of the message, defined by its type.
+  pub body: Value,
+  /// The attachments of the message.
+  #[serde(default, skip_serializing_if = "Vec::is_empty")]
+  pub attachments: Vec<Attachment>,
+  /// Additional headers of the message.
+  #[serde(flatten)]
+  pub headers: Object,
+}
+
+impl Message {
+  /// Creates a new [`Message`] of type `type_` identified by `id`.
+  pub fn new(id: impl Into<String>, type_: impl Into<String>, body: Value) -> Self {
+    Self {
+      id: id.into(),
+      typ: PLAINTEXT_TYP.to_owned(),
+      type_: type_.into(),
+      from: None,
+      to: Vec::new(),
+      thid: None,
+      pthid: None,
+      created_time: Some(Timestamp::now_utc().to_unix()),
+      expires_time: None,
+      body,
+      attachments: Vec::new(),
+      headers: Object::new(),
+    }
+  }
+
+  /// Sets the DID of the sender.
+  pub fn with_from(mut self, from: impl Into<String>) -> Self {
+    self.from = Some(from.into());
+    self
+  }
+
+  /// Adds the DID of a recipient.
+  pub fn with_to(mut self, to: impl Into<String>) -> Self {
+    self.to.push(to.into());
+    self
+  }
+
+  /// Sets the id of the thread the message belongs to.
+  pub fn with_thid(mut self, thid: impl Into<String>) -> Self {
+    self.thid = Some(thid.into());
+    self
+  }
+
+  /// Sets the id of the parent thread.
+  pub fn with_pthid(mut self, pthid: impl Into<String>) -> Self {
+    self.pthid = Some(pthid.into());
+    self
+  }
+
+  /// Sets when the message expires.
+  pub fn with_expires_time(mut self, expires_time: Timestamp) -> Self {
+    self.expires_time = Some(expires_time.to_unix());
+    self
+  }
+
+  /// Adds an attachment.
+  pub fn with_attachment(mut self, attachment: Attachment) -> Self {
+    self.attachments.push(attachment);
+    self
+  }
+
+  /// Encodes the message as JSON.
+  pub fn to_json_string(&self) -> Result<String> {
+    self
+      .to_json()
+      .map_err(|err| Error::InvalidMessage(format!("could not serialize the message: {err}")))
+  }
+
+  /// Decodes a plaintext message from JSON, checking its media type.
+  pub fn from_plaintext(json: &[u8]) -> Result<Self> {
+    let message: Self = <Self as FromJson>::from_json_slice(json)
+      .map_err(|err| Error::InvalidMessage(format!("invalid plaintext message: {err}")))?;
+    if message.typ != PLAINTEXT_TYP {
+      return Err(Error::InvalidMessage(format!(
+        "unexpected media type `{}`",
+        message.typ
+      )));
+    }
+    Ok(message)
+  }
+
+  /// Checks that the message has not expired.
+  pub fn check_expiration(&self) -> Result<()> {
+    match self.expires_time {
+      Some(expires_time) if expires_time < Timestamp::now_utc().to_unix() => Err(Error::MessageExpired),
+      _ => Ok(()),
+    }
+  }
+}
+
+fn plaintext_typ() -> String {
+  PLAINTEXT_TYP.to_owned()
+}
+
+/// Generates a random message id.
+pub(crate) fn random_id() -> Result<String> {
+  let mut id: [u8; 16] = [0; 16];
+  crypto::utils::rand::fill(&mut id).map_err(|err| Error::InvalidMessage(err.to_string()))?;
+  Ok(encode_b64(id))
+}
+
+/// An [attachment](https://identity.foundation/didcomm-messaging/spec/v2.1/#attachments) of a [`Message`].
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Attachment {
+  /// Identifies the attachment within the message.
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub id: Option<String>,
+  /// The media type of the attached content.
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub media_type: Option<String>,
+  /// The attached content.
+  pub data: AttachmentData,
+}
+
+impl Attachment {
+  /// Creates an attachment embedding `json`.
+  pub fn json(json: Value) -> Self {
+    Self {
+      id: None,
+      media_type: None,
+      data: AttachmentData {
+        json: Some(json),
+        ..AttachmentData::default()
+      },
+    }
+  }
+
+  /// Creates an attachment embedding base64url-encoded content.
+  pub fn base64(base64: impl Into<String>) -> Self {
+    Self {
+      id: None,
+      media_type: None,
+      data: AttachmentData {
+        base64: Some(base64.into()),
+        ..AttachmentData::default()
+      },
+    }
+  }
+
+  /// Sets the id of the attachment.
+  pub fn with_id(mut self, id: impl Into<String>) -> Self {
+    self.id = Some(id.into());
+    self
+  }
+
+  /// Sets the media type of the attached content.
+  pub fn with_media_type(mut self, media_type: impl Into<String>) -> Self {
+    self.media_type = Some(media_type.into());
+    self
+  }
+}
+
+/// The content of an [`Attachment`], embedded as JSON or base64url, or referenced by links.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct AttachmentData {
+  /// Embedded JSON content.
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub json: Option<Value>,
+  /// Embedded base64url-encoded content.
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub base64: Option<String>,
+  /// Links to the content.
+  #[serde(default, skip_serializing_if = "Vec::is_empty")]
+  pub links: Vec<Url>,
+  /// The multihash of the linked content.
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub hash: Option<String>,
+}
@@ -0,0 +1,236 @@
+// Copyright 2020-2024 IOTA Stiftung, Fondazione Links
+// SPDX-License-Identifier: Apache-2.0
+
+use identity_core::common::Object;
+use identity_core::common::Url;
+use identity_core::convert::ToJson;
+use identity_did::DIDUrl;
+use identity_document::document::CoreDocument;
+use identity_document::service::Service;
+use identity_document::service::ServiceEndpoint;
+use identity_resolver::Resolver;
+use identity_verification::jose::jwk::JwkType;
+use serde_json::Value;
+
+use crate::encrypted::encrypt_jwe;
+use crate::encrypted::RecipientKey;
+use crate::key_agreement::agreement_keys;
+use crate::key_agreement::did_of;
+use crate::key_agreement::resolve_document;
+use crate::message::random_id;
+use crate::Attachment;
+use crate::ContentEncryptionAlgorithm;
+use crate::Error;
+use crate::Jwe;
+use crate::KeyManagementAlgorithm;
+use crate::Message;
+use crate::PackedMessage;
+use crate::Result;
+
+/// The type of [forward](https://identity.foundation/didcomm-messaging/spec/v2.1/#messages) messages.
+pub const FORWARD_TYPE: &str = "https://didcomm.org/routing/2.0/forward";
+/// The type of the DID document services of DIDComm v2 endpoints.
+pub const DIDCOMM_MESSAGING_SERVICE: &str = "DIDCommMessaging";
+
+/// The content of a `forward` message, asking a mediator to deliver an encrypted message to `next`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Forward {
+  /// The DID or key id of the next hop.
+  pub next: String,
+  /// The encrypted message to deliver.
+  pub forwarded_message: Value,
+}
+
+impl Forward {
+  /// Creates a new [`Forward`].
+  pub fn new(next: impl Into<String>, forwarded_message: Value) -> Self {
+    Self {
+      next: next.into(),
+      forwarded_message,
+    }
+  }
+
+  /// Creates the `forward` message identified by `id`.
+  pub fn to_message(&self, id: impl Into<String>) -> Message {
+    Message::new(id, FORWARD_TYPE, serde_json::json!({ "next": self.next }))
+      .with_attachment(Attachment::json(self.forwarded_message.clone()))
+  }
+
+  /// Extracts the [`Forward`] of a `forward` message.
+  pub fn from_message(message: &Message) -> Result<Self> {
+    if message.type_ != FORWARD_TYPE {
+      return Err(Error::Routing(format!("`{}` is not a forward message", message.type_)));
+    }
+    let next: String = message
+      .body
+      .get("next")
+      .and_then(Value::as_str)
+      .map(ToOwned::to_owned)
+      .ok_or_else(|| Error::Routing("the forward message has no `next`".to_owned()))?;
+    let forwarded_message: Value = match message.attachments.as_slice() {
+      [attachment] => attachment
+        .data
+        .json
+        .clone()
+        .ok_or_else(|| Error::Routing("the forwarded message is not embedded as JSON".to_owned()))?,
+      _ => {
+        return Err(Error::Routing(
+          "a forward message must have exactly one attachment".to_owned(),
+        ))
+      }
+    };
+    Ok(Self {
+      next,
+      forwarded_message,
+    })
+  }
+
+  /// Encodes the forwarded message as JSON, to be delivered to `next`.
+  pub fn forwarded_message_string(&self) -> Result<String> {
+    self
+      .forwarded_message
+      .to_json()
+      .map_err(|err| Error::Routing(format!("could not serialize the forwarded message: {err}")))
+  }
+}
+
+/// A [`DIDCommMessaging`](https://identity.foundation/didcomm-messaging/spec/v2.1/#did-document-service-endpoint)
+/// service of a DID document.
+///
+/// The `routingKeys` and `accept` of the service are read from its properties.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DidCommService {
+  /// The endpoint messages are sent to.
+  pub uri: Url,
+  /// The ids of the `keyAgreement` methods of the mediators, from the outermost to the innermost.
+  pub routing_keys: Vec<String>,
+  /// The media types accepted by the endpoint.
+  pub accept: Vec<String>,
+}
+
+impl DidCommService {
+  /// Creates a new [`DidCommService`] without mediators.
+  pub fn new(uri: Url) -> Self {
+    Self {
+      uri,
+      routing_keys: Vec::new(),
+      accept: vec!["didcomm/v2".to_owned()],
+    }
+  }
+
+  /// Sets the ids of the `keyAgreement` methods of the mediators.
+  pub fn with_routing_keys(mut self, routing_keys: Vec<String>) -> Self {
+    self.routing_keys = routing_keys;
+    self
+  }
+
+  /// Returns the first `DIDCommMessaging` service of `document`, if any.
+  pub fn from_document(document: &CoreDocument) -> Result<Option<Self>> {
+    let Some(service) = document
+      .service()
+      .iter()
+      .find(|service| service.type_().contains(&DIDCOMM_MESSAGING_SERVICE.to_owned()))
+    else {
+      return Ok(None);
+    };
+
+    let uri: Url = match service.service_endpoint() {
+      ServiceEndpoint::One(uri) => uri.clone(),
+      ServiceEndpoint::Set(uris) => uris
+        .head()
+        .cloned()
+        .ok_or_else(|| Error::Routing(format!("`{}` has no endpoint", service.id())))?,
+      ServiceEndpoint::Map(_) => {
+        return Err(Error::Routing(format!(
+          "`{}` has an unsupported endpoint",
+          service.id()
+        )))
+      }
+    };
+    let strings = |property: &str| -> Result<Vec<String>> {
+      match service.properties().get(property) {
+        None => Ok(Vec::new()),
+        Some(Value::Array(values)) => values
+          .iter()
+          .map(|value| {
+            value
+              .as_str()
+              .map(ToOwned::to_owned)
+              .ok_or_else(|| Error::Routing(format!("invalid `{property}` in `{}`", service.id())))
+          })
+          .collect(),
+        Some(_) => Err(Error::Routing(format!("invalid `{property}` in `{}`", service.id()))),
+      }
+    };
+
+    Ok(Some(Self {
+      uri,
+      routing_keys: strings("routingKeys")?,
+      accept: strings("accept")?,
+    }))
+  }
+
+  /// Creates the [`Service`] identified by `id` for a DID document.
+  pub fn into_service(self, id: DIDUrl) -> Result<Service> {
+    let mut properties: Object = Object::new();
+    if !self.routing_keys.is_empty() {
+      properties.insert("routingKeys".to_owned(), self.routing_keys.into());
+    }
+    if !self.accept.is_empty() {
+      properties.insert("accept".to_owned(), self.accept.into());
+    }
+    Service::builder(properties)
+      .id(id)
+      .type_(DIDCOMM_MESSAGING_SERVICE)
+      .service_endpoint(self.uri)
+      .build()
+      .map_err(|err| Error::Routing(format!("invalid service: {err}")))
+  }
+}
+
+/// Wraps `packed`, encrypted for `to`, in anonymously encrypted `forward` messages for the mediators of `service`.
+pub(crate) async fn wrap_in_forward<DOC>(
+  mut packed: PackedMessage,
+  to: &str,
+  service: &DidCommService,
+  resolver: &Resolver<DOC>,
+) -> Result<PackedMessage>
+where
+  DOC: AsRef<CoreDocument> + 'static,
+{
+  let mut next: String = to.to_owned();
+  for routing_key in service.routing_keys.iter().rev() {
+    let mediator: String = did_of(routing_key)?;
+    let document: DOC = resolve_document(resolver, &mediator).await?;
+    let recipients: Vec<RecipientKey> = agreement_keys(document.as_ref(), routing_key, JwkType::Okp)?
+      .into_iter()
+      .map(|key| RecipientKey {
+        key,
+        #[cfg(feature = "kyber")]
+        kem_key: None,
+        static_secret: None,
+      })
+      .collect();
+
+    let forwarded_message: Value = serde_json::from_str(&packed.message)
+      .map_err(|err| Error::Routing(format!("invalid forwarded message: {err}")))?;
+    let forward: Message = Forward::new(next, forwarded_message)
+      .to_message(random_id()?)
+      .with_to(mediator);
+    let jwe: Jwe = encrypt_jwe(
+      forward.to_json_string()?.as_bytes(),
+      &recipients,
+      KeyManagementAlgorithm::EcdhEsA256Kw,
+      ContentEncryptionAlgorithm::A256CbcHs512,
+      None,
+    )?;
+
+    packed.message = jwe
+      .to_json()
+      .map_err(|err| Error::Encryption(format!("could not serialize the JWE: {err}")))?;
+    packed.forwarded = true;
+    next = routing_key.clone();
+  }
+  packed.service_endpoint = Some(service.uri.clone());
+  Ok(packed)
+}
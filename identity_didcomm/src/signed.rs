@@ -0,0 +1,194 @@
+// Copyright 2020-2024 IOTA Stiftung, Fondazione Links
+// SPDX-License-Identifier: Apache-2.0
+
+use identity_core::convert::FromJson;
+use identity_core::convert::ToJson;
+use identity_did::DIDUrl;
+use identity_did::DID;
+use identity_document::document::CoreDocument;
+use identity_document::verifiable::JwsVerificationOptions;
+use identity_resolver::Resolver;
+use identity_storage::JwkDocumentExt;
+use identity_storage::JwkStorage;
+use identity_storage::JwsSignatureOptions;
+use identity_storage::KeyIdStorage;
+use identity_storage::Storage;
+use identity_verification::jose::jws::DecodedJws;
+use identity_verification::jose::jws::Decoder;
+use identity_verification::jose::jws::JwsVerifier;
+use identity_verification::jwu::decode_b64;
+use identity_verification::MethodScope;
+use serde::Deserialize;
+use serde::Serialize;
+
+use crate::key_agreement::did_of;
+use crate::key_agreement::resolve_document;
+use crate::Error;
+use crate::Message;
+use crate::Result;
+use crate::SIGNED_TYP;
+
+/// A signed DIDComm message in the [General JWS JSON Serialization](https://www.rfc-editor.org/rfc/rfc7515#section-7.2.1).
+///
+/// Signed messages provide non-repudiation: any party can verify that the message was signed by an
+/// `authentication` key of its sender.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SignedMessage {
+  /// The base64url-encoded plaintext message.
+  pub payload: String,
+  /// The signatures over the payload.
+  pub signatures: Vec<JwsSignature>,
+}
+
+/// A signature of a [`SignedMessage`].
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct JwsSignature {
+  /// The base64url-encoded protected header.
+  pub protected: String,
+  /// The base64url-encoded signature.
+  pub signature: String,
+  /// The unprotected header, carrying the `kid` of the signing key.
+  pub header: JwsSignatureHeader,
+}
+
+/// The unprotected header of a [`JwsSignature`].
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct JwsSignatureHeader {
+  /// The id of the verification method of the signer.
+  pub kid: String,
+}
+
+impl Message {
+  /// Signs the message with the key of the verification method `fragment` of `document`, held in `storage`.
+  ///
+  /// The method must be an `authentication` method for recipients to accept the signature, and the `from` of the
+  /// message, if set, must be the DID of `document`.
+  pub async fn pack_signed<K, I>(
+    &self,
+    document: &CoreDocument,
+    storage: &Storage<K, I>,
+    fragment: &str,
+  ) -> Result<SignedMessage>
+  where
+    K: JwkStorage,
+    I: KeyIdStorage,
+  {
+    if self
+      .from
+      .as_deref()
+      .map_or(false, |from| from != document.id().as_str())
+    {
+      return Err(Error::SenderMismatch(format!(
+        "the message is not sent by `{}`",
+        document.id()
+      )));
+    }
+    let kid: String = document
+      .resolve_method(fragment, None)
+      .map(|method| method.id().to_string())
+      .ok_or(Error::Signing(
+        identity_storage::JwkStorageDocumentError::MethodNotFound,
+      ))?;
+
+    let jws: String = document
+      .create_jws(
+        storage,
+        fragment,
+        self.to_json_string()?.as_bytes(),
+        &JwsSignatureOptions::new().typ(SIGNED_TYP).kid(kid.clone()),
+      )
+      .await
+      .map_err(Error::Signing)?
+      .into();
+
+    let mut segments = jws.split('.');
+    match (segments.next(), segments.next(), segments.next()) {
+      (Some(protected), Some(payload), Some(signature)) => Ok(SignedMessage {
+        payload: payload.to_owned(),
+        signatures: vec![JwsSignature {
+          protected: protected.to_owned(),
+          signature: signature.to_owned(),
+          header: JwsSignatureHeader { kid },
+        }],
+      }),
+      _ => Err(Error::InvalidMessage("the storage produced a malformed JWS".to_owned())),
+    }
+  }
+}
+
+impl SignedMessage {
+  /// Encodes the signed message as JSON.
+  pub fn to_json_string(&self) -> Result<String> {
+    self
+      .to_json()
+      .map_err(|err| Error::InvalidMessage(format!("could not serialize the signed message: {err}")))
+  }
+
+  /// Decodes a signed message from JSON.
+  pub fn from_json_str(json: &str) -> Result<Self> {
+    <Self as FromJson>::from_json(json).map_err(|err| Error::InvalidMessage(format!("invalid signed message: {err}")))
+  }
+
+  /// Verifies every signature of the message with the `authentication` method of its signer, resolved with
+  /// `resolver`, and returns the plaintext message with the id of the method of the first signer.
+  ///
+  /// Every signer must be the sender of the message.
+  pub async fn verify<DOC, V>(&self, resolver: &Resolver<DOC>, signature_verifier: &V) -> Result<(Message, String)>
+  where
+    DOC: AsRef<CoreDocument> + 'static,
+    V: JwsVerifier,
+  {
+    if self.signatures.is_empty() {
+      return Err(Error::InvalidMessage("the signed message has no signature".to_owned()));
+    }
+
+    let mut signers: Vec<String> = Vec::with_capacity(self.signatures.len());
+    for signature in &self.signatures {
+      let jws: String = format!("{}.{}.{}", signature.protected, self.payload, signature.signature);
+      let kid: String = Decoder::new()
+        .decode_compact_serialization(jws.as_bytes(), None)
+        .map_err(|err| Error::InvalidMessage(format!("invalid signature: {err}")))?
+        .kid()
+        .map(ToOwned::to_owned)
+        .ok_or_else(|| Error::InvalidMessage("the signature has no `kid`".to_owned()))?;
+      if kid != signature.header.kid {
+        return Err(Error::InvalidMessage(
+          "the protected and unprotected `kid` of the signature differ".to_owned(),
+        ));
+      }
+      let method_id: DIDUrl =
+        DIDUrl::parse(&kid).map_err(|err| Error::InvalidMessage(format!("invalid `kid` `{kid}`: {err}")))?;
+
+      let document: DOC = resolve_document(resolver, &did_of(&kid)?).await?;
+      let decoded: DecodedJws<'_> = document
+        .as_ref()
+        .verify_jws(
+          &jws,
+          None,
+          signature_verifier,
+          &JwsVerificationOptions::new()
+            .method_id(method_id)
+            .method_scope(MethodScope::authentication()),
+        )
+        .map_err(Error::SignatureVerification)?;
+      if decoded.protected.typ().map_or(false, |typ| typ != SIGNED_TYP) {
+        return Err(Error::InvalidMessage(format!(
+          "unexpected media type `{}`",
+          decoded.protected.typ().unwrap_or_default()
+        )));
+      }
+      signers.push(kid);
+    }
+
+    let payload: Vec<u8> =
+      decode_b64(&self.payload).map_err(|err| Error::InvalidMessage(format!("invalid payload: {err}")))?;
+    let message: Message = Message::from_plaintext(&payload)?;
+    for signer in &signers {
+      if message.from.as_deref() != Some(did_of(signer)?.as_str()) {
+        return Err(Error::SenderMismatch(format!("the message is signed by `{signer}`")));
+      }
+    }
+
+    Ok((message, signers.swap_remove(0)))
+  }
+}
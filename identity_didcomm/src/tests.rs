@@ -0,0 +1,681 @@
+// Copyright 2020-2024 IOTA Stiftung, Fondazione Links
+// SPDX-License-Identifier: Apache-2.0
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use identity_core::common::Duration;
+use identity_core::common::Object;
+use identity_core::common::Timestamp;
+use identity_core::common::Url;
+use identity_core::convert::FromJson;
+use identity_core::convert::ToJson;
+use identity_did::CoreDID;
+use identity_did::DID;
+use identity_did::DIDUrl;
+use identity_document::document::CoreDocument;
+use identity_eddsa_verifier::EdDSAJwsVerifier;
+use identity_resolver::Resolver;
+use identity_storage::JwkDocumentExt;
+use identity_storage::JwkMemStore;
+use identity_storage::KeyIdMemstore;
+use identity_storage::Storage;
+use identity_verification::jws::JwsAlgorithm;
+use identity_verification::jwu::decode_b64;
+use identity_verification::jwu::encode_b64;
+use identity_verification::MethodScope;
+use identity_verification::VerificationMethod;
+use serde_json::json;
+
+use crate::*;
+
+const PING_TYPE: &str = "https://didcomm.org/trust-ping/2.0/ping";
+
+/// A DID document with its signing and key agreement keys.
+struct Party {
+  document: CoreDocument,
+  storage: Storage<JwkMemStore, KeyIdMemstore>,
+  agreement_storage: KeyAgreementMemStore,
+  fragment: String,
+  agreement_kid: String,
+}
+
+impl Party {
+  async fn new(name: &str) -> Self {
+    let mut document: CoreDocument = CoreDocument::builder(Object::new())
+      .id(CoreDID::parse(format!("did:example:{name}")).unwrap())
+      .build()
+      .unwrap();
+    let storage: Storage<JwkMemStore, KeyIdMemstore> = Storage::new(JwkMemStore::new(), KeyIdMemstore::new());
+    let fragment: String = document
+      .generate_method(
+        &storage,
+        JwkMemStore::ED25519_KEY_TYPE,
+        JwsAlgorithm::EdDSA,
+        Some("sign-0"),
+        MethodScope::authentication(),
+      )
+      .await
+      .unwrap();
+
+    let agreement_storage: KeyAgreementMemStore = KeyAgreementMemStore::new();
+    let jwk = agreement_storage.generate(KeyAgreementKeyType::X25519).await.unwrap();
+    let method: VerificationMethod =
+      VerificationMethod::new_from_jwk(document.id().clone(), jwk.clone(), Some("key-agreement-0")).unwrap();
+    let agreement_kid: String = method.id().to_string();
+    agreement_storage.insert_kid(&agreement_kid, &jwk).await.unwrap();
+    document.insert_method(method, MethodScope::key_agreement()).unwrap();
+
+    Self {
+      document,
+      storage,
+      agreement_storage,
+      fragment,
+      agreement_kid,
+    }
+  }
+
+  fn did(&self) -> String {
+    self.document.id().to_string()
+  }
+}
+
+fn resolver(parties: &[&Party]) -> Resolver<CoreDocument> {
+  let documents: Arc<HashMap<String, CoreDocument>> = Arc::new(
+    parties
+      .iter()
+      .map(|party| (party.did(), party.document.clone()))
+      .collect(),
+  );
+  let mut resolver: Resolver<CoreDocument> = Resolver::new();
+  resolver.attach_handler("example".to_owned(), move |did: CoreDID| {
+    let documents = documents.clone();
+    async move {
+      documents
+        .get(did.as_str())
+        .cloned()
+        .ok_or_else(|| std::io::Error::new(std::io::ErrorKind::NotFound, did.to_string()))
+    }
+  });
+  resolver
+}
+
+fn ping(from: &Party, to: &Party) -> Message {
+  Message::new("1234567890", PING_TYPE, json!({ "response_requested": true }))
+    .with_from(from.did())
+    .with_to(to.did())
+}
+
+/// A mediator holding the forwarded messages of its clients until they fetch them.
+struct InMemoryMediator {
+  party: Party,
+  mailboxes: HashMap<String, Vec<String>>,
+}
+
+impl InMemoryMediator {
+  async fn receive(&mut self, packed: &str, resolver: &Resolver<CoreDocument>) -> Result<()> {
+    let unpacked: UnpackedMessage = Message::unpack(
+      packed,
+      resolver,
+      &self.party.agreement_storage,
+      &EdDSAJwsVerifier::default(),
+    )
+    .await?;
+    let forward: Forward = Forward::from_message(&unpacked.message)?;
+    self
+      .mailboxes
+      .entry(forward.next.clone())
+      .or_default()
+      .push(forward.forwarded_message_string()?);
+    Ok(())
+  }
+
+  fn fetch(&mut self, did: &str) -> Vec<String> {
+    self.mailboxes.remove(did).unwrap_or_default()
+  }
+}
+
+#[tokio::test]
+async fn plaintext_round_trip() {
+  let alice: Party = Party::new("alice").await;
+  let bob: Party = Party::new("bob").await;
+  let resolver = resolver(&[&alice, &bob]);
+
+  let message: Message = ping(&alice, &bob).with_thid("thread-1");
+  let unpacked: UnpackedMessage = Message::unpack(
+    &message.to_json_string().unwrap(),
+    &resolver,
+    &bob.agreement_storage,
+    &EdDSAJwsVerifier::default(),
+  )
+  .await
+  .unwrap();
+
+  assert_eq!(unpacked.message, message);
+  assert_eq!(unpacked.metadata, UnpackMetadata::default());
+}
+
+#[tokio::test]
+async fn signed_message_round_trip() {
+  let alice: Party = Party::new("alice").await;
+  let bob: Party = Party::new("bob").await;
+  let resolver = resolver(&[&alice, &bob]);
+
+  let message: Message = ping(&alice, &bob);
+  let signed: SignedMessage = message
+    .pack_signed(&alice.document, &alice.storage, &alice.fragment)
+    .await
+    .unwrap();
+  let unpacked: UnpackedMessage = Message::unpack(
+    &signed.to_json_string().unwrap(),
+    &resolver,
+    &bob.agreement_storage,
+    &EdDSAJwsVerifier::default(),
+  )
+  .await
+  .unwrap();
+
+  assert_eq!(unpacked.message, message);
+  assert!(unpacked.metadata.non_repudiation);
+  assert!(unpacked.metadata.authenticated);
+  assert!(!unpacked.metadata.encrypted);
+  assert_eq!(
+    unpacked.metadata.sign_from,
+    alice
+      .document
+      .resolve_method(&alice.fragment, None)
+      .map(|method| method.id().to_string())
+  );
+}
+
+#[tokio::test]
+async fn signing_requires_the_sender_key() {
+  let alice: Party = Party::new("alice").await;
+  let bob: Party = Party::new("bob").await;
+
+  let err = ping(&bob, &alice)
+    .pack_signed(&alice.document, &alice.storage, &alice.fragment)
+    .await
+    .unwrap_err();
+  assert!(matches!(err, Error::SenderMismatch(_)));
+}
+
+#[tokio::test]
+async fn tampered_signed_message_is_rejected() {
+  let alice: Party = Party::new("alice").await;
+  let bob: Party = Party::new("bob").await;
+  let resolver = resolver(&[&alice, &bob]);
+
+  let mut signed: SignedMessage = ping(&alice, &bob)
+    .pack_signed(&alice.document, &alice.storage, &alice.fragment)
+    .await
+    .unwrap();
+  signed.payload = encode_b64(ping(&alice, &bob).with_thid("forged").to_json_string().unwrap());
+
+  let err = signed
+    .verify(&resolver, &EdDSAJwsVerifier::default())
+    .await
+    .unwrap_err();
+  assert!(matches!(err, Error::SignatureVerification(_)));
+}
+
+#[tokio::test]
+async fn anoncrypt_round_trip() {
+  let alice: Party = Party::new("alice").await;
+  let bob: Party = Party::new("bob").await;
+  let resolver = resolver(&[&alice, &bob]);
+
+  let message: Message = ping(&alice, &bob);
+  let packed: PackedMessage = message
+    .pack_encrypted(
+      &bob.did(),
+      &EncryptOptions::anoncrypt(),
+      &resolver,
+      &alice.agreement_storage,
+    )
+    .await
+    .unwrap();
+  assert_eq!(packed.to_kids, vec![bob.agreement_kid.clone()]);
+  assert_eq!(packed.from_kid, None);
+  assert_eq!(packed.service_endpoint, None);
+
+  let unpacked: UnpackedMessage = Message::unpack(
+    &packed.message,
+    &resolver,
+    &bob.agreement_storage,
+    &EdDSAJwsVerifier::default(),
+  )
+  .await
+  .unwrap();
+  assert_eq!(unpacked.message, message);
+  assert!(unpacked.metadata.encrypted);
+  assert!(unpacked.metadata.anonymous_sender);
+  assert!(!unpacked.metadata.authenticated);
+  assert_eq!(
+    unpacked.metadata.key_management_alg,
+    Some(KeyManagementAlgorithm::EcdhEsA256Kw)
+  );
+  assert_eq!(unpacked.metadata.encrypted_to_kid, Some(bob.agreement_kid.clone()));
+}
+
+#[tokio::test]
+async fn anoncrypt_with_aes_gcm() {
+  let alice: Party = Party::new("alice").await;
+  let bob: Party = Party::new("bob").await;
+  let resolver = resolver(&[&alice, &bob]);
+
+  let message: Message = ping(&alice, &bob);
+  let options: EncryptOptions = EncryptOptions::anoncrypt().content_encryption(ContentEncryptionAlgorithm::A256Gcm);
+  let packed: PackedMessage = message
+    .pack_encrypted(&bob.did(), &options, &resolver, &alice.agreement_storage)
+    .await
+    .unwrap();
+
+  let unpacked: UnpackedMessage = Message::unpack(
+    &packed.message,
+    &resolver,
+    &bob.agreement_storage,
+    &EdDSAJwsVerifier::default(),
+  )
+  .await
+  .unwrap();
+  assert_eq!(unpacked.message, message);
+}
+
+#[tokio::test]
+async fn authcrypt_round_trip() {
+  let alice: Party = Party::new("alice").await;
+  let bob: Party = Party::new("bob").await;
+  let resolver = resolver(&[&alice, &bob]);
+
+  let message: Message = ping(&alice, &bob);
+  let packed: PackedMessage = message
+    .pack_encrypted(
+      &bob.agreement_kid,
+      &EncryptOptions::authcrypt(&alice.agreement_kid),
+      &resolver,
+      &alice.agreement_storage,
+    )
+    .await
+    .unwrap();
+  assert_eq!(packed.from_kid, Some(alice.agreement_kid.clone()));
+
+  let unpacked: UnpackedMessage = Message::unpack(
+    &packed.message,
+    &resolver,
+    &bob.agreement_storage,
+    &EdDSAJwsVerifier::default(),
+  )
+  .await
+  .unwrap();
+  assert_eq!(unpacked.message, message);
+  assert!(unpacked.metadata.authenticated);
+  assert!(!unpacked.metadata.anonymous_sender);
+  assert!(!unpacked.metadata.non_repudiation);
+  assert_eq!(unpacked.metadata.encrypted_from_kid, Some(alice.agreement_kid.clone()));
+}
+
+#[tokio::test]
+async fn authcrypt_requires_the_sender_key() {
+  let alice: Party = Party::new("alice").await;
+  let bob: Party = Party::new("bob").await;
+  let carol: Party = Party::new("carol").await;
+  let resolver = resolver(&[&alice, &bob, &carol]);
+
+  let err = ping(&carol, &bob)
+    .pack_encrypted(
+      &bob.did(),
+      &EncryptOptions::authcrypt(&alice.agreement_kid),
+      &resolver,
+      &alice.agreement_storage,
+    )
+    .await
+    .unwrap_err();
+  assert!(matches!(err, Error::SenderMismatch(_)));
+
+  // Carol cannot authenticate as Alice without her private key.
+  let err = ping(&alice, &bob)
+    .pack_encrypted(
+      &bob.did(),
+      &EncryptOptions::authcrypt(&alice.agreement_kid),
+      &resolver,
+      &carol.agreement_storage,
+    )
+    .await
+    .unwrap_err();
+  assert!(matches!(err, Error::KeyStorage(_)));
+}
+
+#[tokio::test]
+async fn anoncrypted_signed_message_round_trip() {
+  let alice: Party = Party::new("alice").await;
+  let bob: Party = Party::new("bob").await;
+  let resolver = resolver(&[&alice, &bob]);
+
+  let message: Message = ping(&alice, &bob);
+  let packed: PackedMessage = message
+    .pack_signed(&alice.document, &alice.storage, &alice.fragment)
+    .await
+    .unwrap()
+    .pack_encrypted(
+      &bob.did(),
+      &EncryptOptions::anoncrypt(),
+      &resolver,
+      &alice.agreement_storage,
+    )
+    .await
+    .unwrap();
+
+  let unpacked: UnpackedMessage = Message::unpack(
+    &packed.message,
+    &resolver,
+    &bob.agreement_storage,
+    &EdDSAJwsVerifier::default(),
+  )
+  .await
+  .unwrap();
+  assert_eq!(unpacked.message, message);
+  assert!(unpacked.metadata.encrypted);
+  assert!(unpacked.metadata.non_repudiation);
+  assert!(unpacked.metadata.authenticated);
+}
+
+#[tokio::test]
+async fn tampered_encrypted_message_is_rejected() {
+  let alice: Party = Party::new("alice").await;
+  let bob: Party = Party::new("bob").await;
+  let resolver = resolver(&[&alice, &bob]);
+
+  let packed: PackedMessage = ping(&alice, &bob)
+    .pack_encrypted(
+      &bob.did(),
+      &EncryptOptions::authcrypt(&alice.agreement_kid),
+      &resolver,
+      &alice.agreement_storage,
+    )
+    .await
+    .unwrap();
+  let jwe: Jwe = Jwe::from_json(&packed.message).unwrap();
+
+  let mut tampered: Jwe = jwe.clone();
+  tampered.ciphertext = encode_b64(b"forged ciphertext of the right size..");
+  let err = Message::unpack(
+    &tampered.to_json().unwrap(),
+    &resolver,
+    &bob.agreement_storage,
+    &EdDSAJwsVerifier::default(),
+  )
+  .await
+  .unwrap_err();
+  assert!(matches!(err, Error::Decryption(_)));
+
+  // Redirecting a recipient to another key is caught by the `apv`, which binds the header to the recipients.
+  let mut tampered: Jwe = jwe;
+  tampered.recipients[0].header.kid = alice.agreement_kid.clone();
+  let err = Message::unpack(
+    &tampered.to_json().unwrap(),
+    &resolver,
+    &alice.agreement_storage,
+    &EdDSAJwsVerifier::default(),
+  )
+  .await
+  .unwrap_err();
+  assert!(matches!(err, Error::InvalidMessage(_)));
+}
+
+#[tokio::test]
+async fn message_for_another_recipient_cannot_be_decrypted() {
+  let alice: Party = Party::new("alice").await;
+  let bob: Party = Party::new("bob").await;
+  let carol: Party = Party::new("carol").await;
+  let resolver = resolver(&[&alice, &bob, &carol]);
+
+  let message: Message = ping(&alice, &bob);
+  let err = message
+    .pack_encrypted(
+      &carol.did(),
+      &EncryptOptions::anoncrypt(),
+      &resolver,
+      &alice.agreement_storage,
+    )
+    .await
+    .unwrap_err();
+  assert!(matches!(err, Error::InvalidMessage(_)));
+
+  let packed: PackedMessage = message
+    .pack_encrypted(
+      &bob.did(),
+      &EncryptOptions::anoncrypt(),
+      &resolver,
+      &alice.agreement_storage,
+    )
+    .await
+    .unwrap();
+  let err = Message::unpack(
+    &packed.message,
+    &resolver,
+    &carol.agreement_storage,
+    &EdDSAJwsVerifier::default(),
+  )
+  .await
+  .unwrap_err();
+  assert!(matches!(err, Error::Decryption(_)));
+}
+
+#[tokio::test]
+async fn recipients_unknown_to_storage_are_not_resolved() {
+  let alice: Party = Party::new("alice").await;
+  let bob: Party = Party::new("bob").await;
+  let carol: Party = Party::new("carol").await;
+
+  let packed: PackedMessage = ping(&alice, &bob)
+    .pack_encrypted(
+      &bob.did(),
+      &EncryptOptions::anoncrypt(),
+      &resolver(&[&alice, &bob]),
+      &alice.agreement_storage,
+    )
+    .await
+    .unwrap();
+
+  // Carol cannot resolve the DID of Bob, but the message is simply not for her.
+  let err = Message::unpack(
+    &packed.message,
+    &resolver(&[&carol]),
+    &carol.agreement_storage,
+    &EdDSAJwsVerifier::default(),
+  )
+  .await
+  .unwrap_err();
+  assert!(matches!(err, Error::Decryption(_)));
+}
+
+#[tokio::test]
+async fn low_order_ephemeral_key_is_rejected() {
+  let alice: Party = Party::new("alice").await;
+  let bob: Party = Party::new("bob").await;
+  let resolver = resolver(&[&alice, &bob]);
+
+  let packed: PackedMessage = ping(&alice, &bob)
+    .pack_encrypted(
+      &bob.did(),
+      &EncryptOptions::anoncrypt(),
+      &resolver,
+      &alice.agreement_storage,
+    )
+    .await
+    .unwrap();
+  let mut jwe: Jwe = Jwe::from_json(&packed.message).unwrap();
+  let mut header: serde_json::Value = serde_json::from_slice(&decode_b64(&jwe.protected).unwrap()).unwrap();
+  header["epk"]["x"] = encode_b64([0; 32]).into();
+  jwe.protected = encode_b64(serde_json::to_vec(&header).unwrap());
+
+  let err = Message::unpack(
+    &jwe.to_json().unwrap(),
+    &resolver,
+    &bob.agreement_storage,
+    &EdDSAJwsVerifier::default(),
+  )
+  .await
+  .unwrap_err();
+  assert!(matches!(err, Error::InvalidMessage(ref message) if message.contains("low order")));
+}
+
+#[tokio::test]
+async fn expired_message_is_rejected() {
+  let alice: Party = Party::new("alice").await;
+  let bob: Party = Party::new("bob").await;
+  let resolver = resolver(&[&alice, &bob]);
+
+  let expires_time: Timestamp = Timestamp::now_utc().checked_sub(Duration::minutes(1)).unwrap();
+  let packed: PackedMessage = ping(&alice, &bob)
+    .with_expires_time(expires_time)
+    .pack_encrypted(
+      &bob.did(),
+      &EncryptOptions::anoncrypt(),
+      &resolver,
+      &alice.agreement_storage,
+    )
+    .await
+    .unwrap();
+
+  let err = Message::unpack(
+    &packed.message,
+    &resolver,
+    &bob.agreement_storage,
+    &EdDSAJwsVerifier::default(),
+  )
+  .await
+  .unwrap_err();
+  assert!(matches!(err, Error::MessageExpired));
+}
+
+#[tokio::test]
+async fn forward_through_mediator() {
+  let alice: Party = Party::new("alice").await;
+  let mut bob: Party = Party::new("bob").await;
+  let mediator: Party = Party::new("mediator").await;
+
+  let endpoint: Url = Url::parse("https://mediator.example.com/didcomm").unwrap();
+  let service = DidCommService::new(endpoint.clone())
+    .with_routing_keys(vec![mediator.agreement_kid.clone()])
+    .into_service(DIDUrl::parse(format!("{}#didcomm", bob.did())).unwrap())
+    .unwrap();
+  bob.document.insert_service(service).unwrap();
+  let resolver = resolver(&[&alice, &bob, &mediator]);
+
+  let message: Message = ping(&alice, &bob);
+  let packed: PackedMessage = message
+    .pack_encrypted(
+      &bob.did(),
+      &EncryptOptions::authcrypt(&alice.agreement_kid),
+      &resolver,
+      &alice.agreement_storage,
+    )
+    .await
+    .unwrap();
+  assert!(packed.forwarded);
+  assert_eq!(packed.service_endpoint, Some(endpoint));
+
+  // Bob cannot read the forward envelope, only the mediator can.
+  assert!(Message::unpack(
+    &packed.message,
+    &resolver,
+    &bob.agreement_storage,
+    &EdDSAJwsVerifier::default()
+  )
+  .await
+  .is_err());
+
+  let mut mediator: InMemoryMediator = InMemoryMediator {
+    party: mediator,
+    mailboxes: HashMap::new(),
+  };
+  mediator.receive(&packed.message, &resolver).await.unwrap();
+  let delivered: Vec<String> = mediator.fetch(&bob.did());
+  assert_eq!(delivered.len(), 1);
+
+  let unpacked: UnpackedMessage = Message::unpack(
+    &delivered[0],
+    &resolver,
+    &bob.agreement_storage,
+    &EdDSAJwsVerifier::default(),
+  )
+  .await
+  .unwrap();
+  assert_eq!(unpacked.message, message);
+  assert_eq!(unpacked.metadata.encrypted_from_kid, Some(alice.agreement_kid.clone()));
+
+  // Without forwarding, the message is encrypted for Bob only but still addressed to the service endpoint.
+  let packed: PackedMessage = message
+    .pack_encrypted(
+      &bob.did(),
+      &EncryptOptions::anoncrypt().forward(false),
+      &resolver,
+      &alice.agreement_storage,
+    )
+    .await
+    .unwrap();
+  assert!(!packed.forwarded);
+  assert!(packed.service_endpoint.is_some());
+  assert!(mediator.receive(&packed.message, &resolver).await.is_err());
+}
+
+#[tokio::test]
+async fn forward_message_round_trip() {
+  let forward: Forward = Forward::new("did:example:bob", json!({ "ciphertext": "abc" }));
+  let message: Message = forward.to_message("forward-1");
+  assert_eq!(message.type_, FORWARD_TYPE);
+  assert_eq!(Forward::from_message(&message).unwrap(), forward);
+
+  let err = Forward::from_message(&Message::new("1", PING_TYPE, json!({}))).unwrap_err();
+  assert!(matches!(err, Error::Routing(_)));
+}
+
+#[cfg(feature = "kyber")]
+#[tokio::test]
+async fn hybrid_anoncrypt_round_trip() {
+  let alice: Party = Party::new("alice").await;
+  let mut bob: Party = Party::new("bob").await;
+
+  let jwk = bob
+    .agreement_storage
+    .generate(KeyAgreementKeyType::Kyber768)
+    .await
+    .unwrap();
+  let method: VerificationMethod =
+    VerificationMethod::new_from_jwk(bob.document.id().clone(), jwk, Some("key-agreement-pq")).unwrap();
+  let pq_kid: String = method.id().to_string();
+  bob
+    .document
+    .insert_method(method, MethodScope::key_agreement())
+    .unwrap();
+  let resolver = resolver(&[&alice, &bob]);
+
+  let message: Message = ping(&alice, &bob);
+  let packed: PackedMessage = message
+    .pack_encrypted(
+      &bob.did(),
+      &EncryptOptions::hybrid_anoncrypt(),
+      &resolver,
+      &alice.agreement_storage,
+    )
+    .await
+    .unwrap();
+  let jwe: Jwe = Jwe::from_json(&packed.message).unwrap();
+  assert_eq!(jwe.recipients[0].header.pq_kid, Some(pq_kid));
+
+  let unpacked: UnpackedMessage = Message::unpack(
+    &packed.message,
+    &resolver,
+    &bob.agreement_storage,
+    &EdDSAJwsVerifier::default(),
+  )
+  .await
+  .unwrap();
+  assert_eq!(unpacked.message, message);
+  assert_eq!(
+    unpacked.metadata.key_management_alg,
+    Some(KeyManagementAlgorithm::EcdhEsKyber768A256Kw)
+  );
+}
@@ -0,0 +1,290 @@
+// Copyright 2020-2024 IOTA Stiftung, Fondazione Links
+// SPDX-License-Identifier: Apache-2.0
+
+use identity_core::convert::FromJson;
+use identity_document::document::CoreDocument;
+use identity_resolver::Resolver;
+use identity_verification::jose::jwk::JwkType;
+use identity_verification::jose::jws::JwsVerifier;
+use identity_verification::jwu::decode_b64;
+use identity_verification::jwu::encode_b64;
+use serde_json::Value;
+
+use crate::encrypted::apv;
+use crate::jwe::concat_kdf;
+use crate::jwe::decrypt_content;
+use crate::jwe::unwrap_key;
+use crate::jwe::EncryptedContent;
+use crate::key_agreement::agreement_key;
+use crate::key_agreement::check_x25519_shared_secret;
+use crate::key_agreement::did_of;
+use crate::key_agreement::resolve_document;
+use crate::key_agreement::AgreementKey;
+use crate::ContentEncryptionAlgorithm;
+use crate::Error;
+use crate::Jwe;
+use crate::JweHeader;
+use crate::JweRecipient;
+use crate::KeyAgreementStorage;
+use crate::KeyManagementAlgorithm;
+use crate::Message;
+use crate::Result;
+use crate::SignedMessage;
+use crate::ENCRYPTED_TYP;
+
+/// The maximum number of nested JWE envelopes, e.g. an anoncrypted authcrypted message.
+const MAX_ENCRYPTION_LAYERS: usize = 2;
+
+/// How an unpacked message was protected.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct UnpackMetadata {
+  /// Whether the message was encrypted.
+  pub encrypted: bool,
+  /// Whether the sender was authenticated, by authenticated encryption or by a signature.
+  pub authenticated: bool,
+  /// Whether the message was signed, so that its origin can be proven to third parties.
+  pub non_repudiation: bool,
+  /// Whether the message was encrypted without authenticating its sender.
+  pub anonymous_sender: bool,
+  /// The key management algorithm of the innermost encryption layer.
+  pub key_management_alg: Option<KeyManagementAlgorithm>,
+  /// The id of the key of the sender, for authenticated encryption.
+  pub encrypted_from_kid: Option<String>,
+  /// The id of the key of the recipient the message was decrypted with.
+  pub encrypted_to_kid: Option<String>,
+  /// The id of the method the message was signed with.
+  pub sign_from: Option<String>,
+}
+
+/// A message unpacked by [`Message::unpack`].
+#[derive(Clone, Debug, PartialEq)]
+#[non_exhaustive]
+pub struct UnpackedMessage {
+  /// The plaintext message.
+  pub message: Message,
+  /// How the message was protected.
+  pub metadata: UnpackMetadata,
+}
+
+impl Message {
+  /// Unpacks a plaintext, signed or encrypted message.
+  ///
+  /// Encrypted messages are decrypted with the first of their recipient keys held in `storage`, and the keys of
+  /// signers and authenticated senders are resolved with `resolver`. The sender authenticated by each layer must be
+  /// the `from` of the plaintext message, and the recipient key must belong to one of its `to`.
+  pub async fn unpack<DOC, S, V>(
+    packed: &str,
+    resolver: &Resolver<DOC>,
+    storage: &S,
+    signature_verifier: &V,
+  ) -> Result<UnpackedMessage>
+  where
+    DOC: AsRef<CoreDocument> + 'static,
+    S: KeyAgreementStorage + ?Sized,
+    V: JwsVerifier,
+  {
+    let mut metadata: UnpackMetadata = UnpackMetadata::default();
+    let mut payload: Vec<u8> = packed.as_bytes().to_vec();
+    let mut layers: usize = 0;
+
+    let message: Message = loop {
+      let value: Value =
+        serde_json::from_slice(&payload).map_err(|err| Error::InvalidMessage(format!("invalid JSON: {err}")))?;
+      if value.get("ciphertext").is_some() {
+        layers += 1;
+        if layers > MAX_ENCRYPTION_LAYERS {
+          return Err(Error::InvalidMessage("too many encryption layers".to_owned()));
+        }
+        let jwe: Jwe =
+          Jwe::from_json_value(value).map_err(|err| Error::InvalidMessage(format!("invalid JWE: {err}")))?;
+        let decrypted: Decrypted = decrypt_jwe(&jwe, resolver, storage).await?;
+
+        metadata.encrypted = true;
+        metadata.key_management_alg = Some(decrypted.alg);
+        metadata.encrypted_to_kid = Some(decrypted.to_kid);
+        if decrypted.from_kid.is_some() {
+          metadata.authenticated = true;
+          metadata.encrypted_from_kid = decrypted.from_kid;
+        }
+        payload = decrypted.plaintext;
+      } else if value.get("signatures").is_some() {
+        let signed: SignedMessage =
+          SignedMessage::from_json_value(value).map_err(|err| Error::InvalidMessage(format!("invalid JWS: {err}")))?;
+        let (message, kid) = signed.verify(resolver, signature_verifier).await?;
+        metadata.authenticated = true;
+        metadata.non_repudiation = true;
+        metadata.sign_from = Some(kid);
+        break message;
+      } else {
+        break Message::from_plaintext(&payload)?;
+      }
+    };
+
+    metadata.anonymous_sender = metadata.encrypted && metadata.encrypted_from_kid.is_none();
+    if let Some(from_kid) = &metadata.encrypted_from_kid {
+      if message.from.as_deref() != Some(did_of(from_kid)?.as_str()) {
+        return Err(Error::SenderMismatch(format!(
+          "the message is encrypted by `{from_kid}`"
+        )));
+      }
+    }
+    if let Some(to_kid) = &metadata.encrypted_to_kid {
+      let to_did: String = did_of(to_kid)?;
+      if !message.to.is_empty() && !message.to.contains(&to_did) {
+        return Err(Error::InvalidMessage(format!(
+          "the message is encrypted for `{to_did}`, which is not one of its recipients"
+        )));
+      }
+    }
+    message.check_expiration()?;
+
+    Ok(UnpackedMessage { message, metadata })
+  }
+}
+
+/// The result of decrypting a [`Jwe`].
+struct Decrypted {
+  plaintext: Vec<u8>,
+  alg: KeyManagementAlgorithm,
+  from_kid: Option<String>,
+  to_kid: String,
+}
+
+/// Decrypts `jwe` with the first of its recipient keys held in `storage`.
+async fn decrypt_jwe<DOC, S>(jwe: &Jwe, resolver: &Resolver<DOC>, storage: &S) -> Result<Decrypted>
+where
+  DOC: AsRef<CoreDocument> + 'static,
+  S: KeyAgreementStorage + ?Sized,
+{
+  let decode = |name: &str, value: &str| -> Result<Vec<u8>> {
+    decode_b64(value).map_err(|err| Error::InvalidMessage(format!("invalid JWE `{name}`: {err}")))
+  };
+
+  let header: JweHeader = JweHeader::from_json_slice(&decode("protected", &jwe.protected)?)
+    .map_err(|err| Error::InvalidMessage(format!("invalid JWE header: {err}")))?;
+  if header.typ.as_deref().map_or(false, |typ| typ != ENCRYPTED_TYP) {
+    return Err(Error::InvalidMessage(format!(
+      "unexpected media type `{}`",
+      header.typ.unwrap_or_default()
+    )));
+  }
+
+  // The `apv` binds the header to the full set of recipients, so that none of them can be removed or replaced.
+  let apv_digest = apv(jwe.recipients.iter().map(|recipient| recipient.header.kid.as_str()));
+  if header.apv != encode_b64(apv_digest) {
+    return Err(Error::InvalidMessage(
+      "the `apv` does not match the recipients".to_owned(),
+    ));
+  }
+
+  let (sender, apu): (Option<AgreementKey>, Vec<u8>) = match (header.alg.is_authenticated(), &header.skid) {
+    (true, Some(skid)) => {
+      if header.enc != ContentEncryptionAlgorithm::A256CbcHs512 {
+        return Err(Error::UnsupportedAlgorithm(format!(
+          "{} with {}",
+          header.alg, header.enc
+        )));
+      }
+      let apu: Vec<u8> = decode("apu", header.apu.as_deref().unwrap_or_default())?;
+      if apu != skid.as_bytes() {
+        return Err(Error::InvalidMessage("the `apu` does not match the `skid`".to_owned()));
+      }
+      let document: DOC = resolve_document(resolver, &did_of(skid)?).await?;
+      (Some(agreement_key(document.as_ref(), skid, JwkType::Okp)?), apu)
+    }
+    (true, None) => return Err(Error::InvalidMessage(format!("{} requires a `skid`", header.alg))),
+    (false, Some(_)) => {
+      return Err(Error::InvalidMessage(format!(
+        "{} does not authenticate the sender",
+        header.alg
+      )))
+    }
+    (false, None) => (None, Vec::new()),
+  };
+
+  let content: EncryptedContent = EncryptedContent {
+    iv: decode("iv", &jwe.iv)?,
+    ciphertext: decode("ciphertext", &jwe.ciphertext)?,
+    tag: decode("tag", &jwe.tag)?,
+  };
+  let cc_tag: &[u8] = if header.alg.is_authenticated() {
+    &content.tag
+  } else {
+    &[]
+  };
+
+  for recipient in &jwe.recipients {
+    #[cfg_attr(not(feature = "kyber"), allow(unused_variables))]
+    let Some((key, document)) = recipient_key(recipient, resolver, storage).await?
+    else {
+      continue;
+    };
+
+    let mut z: Vec<u8> = check_x25519_shared_secret(storage.diffie_hellman(&key.jwk, &header.epk).await?)?;
+    if let Some(sender) = &sender {
+      z.extend(check_x25519_shared_secret(
+        storage.diffie_hellman(&key.jwk, &sender.jwk).await?,
+      )?);
+    }
+    #[cfg(feature = "kyber")]
+    if header.alg == KeyManagementAlgorithm::EcdhEsKyber768A256Kw {
+      let (Some(pq_kid), Some(ek)) = (&recipient.header.pq_kid, &recipient.header.ek) else {
+        return Err(Error::InvalidMessage(
+          "the recipient has no `pq_kid` or `ek`".to_owned(),
+        ));
+      };
+      let kem_key: AgreementKey = agreement_key(document.as_ref(), pq_kid, JwkType::KYBER)?;
+      z.extend(storage.decapsulate(&kem_key.jwk, &decode("ek", ek)?).await?);
+    }
+
+    let kek = concat_kdf(&z, header.alg, &apu, &apv_digest, cc_tag);
+    let cek: Vec<u8> = unwrap_key(&kek, &decode("encrypted_key", &recipient.encrypted_key)?)?;
+    let plaintext: Vec<u8> = decrypt_content(header.enc, &cek, jwe.protected.as_bytes(), &content)?;
+
+    return Ok(Decrypted {
+      plaintext,
+      alg: header.alg,
+      from_kid: header.skid,
+      to_kid: key.kid,
+    });
+  }
+
+  Err(Error::Decryption(
+    "the message is not encrypted for any key in storage".to_owned(),
+  ))
+}
+
+/// Returns the key of `recipient` with the DID document it belongs to, if its private key is held in `storage`.
+///
+/// Recipients whose `kid` is unknown to `storage` are skipped before their DID document is resolved, and a recipient
+/// whose DID document cannot be resolved is not considered to be held in `storage`.
+async fn recipient_key<DOC, S>(
+  recipient: &JweRecipient,
+  resolver: &Resolver<DOC>,
+  storage: &S,
+) -> Result<Option<(AgreementKey, DOC)>>
+where
+  DOC: AsRef<CoreDocument> + 'static,
+  S: KeyAgreementStorage + ?Sized,
+{
+  let kid: &str = &recipient.header.kid;
+  if !storage.exists_kid(kid).await? {
+    return Ok(None);
+  }
+  let document: DOC = match resolve_document(resolver, &did_of(kid)?).await {
+    Ok(document) => document,
+    Err(Error::Resolution { .. }) => return Ok(None),
+    Err(err) => return Err(err),
+  };
+  let key: AgreementKey = match agreement_key(document.as_ref(), kid, JwkType::Okp) {
+    Ok(key) => key,
+    Err(Error::KeyNotFound(_)) => return Ok(None),
+    Err(err) => return Err(err),
+  };
+  if storage.exists(&key.jwk).await? {
+    Ok(Some((key, document)))
+  } else {
+    Ok(None)
+  }
+}
@@ -9,7 +9,13 @@ use std::convert::Infallible;
 
 use identity_did::DIDCompositeJwk;
 use identity_did::DIDJwk;
+use identity_did::DIDKey;
+use identity_did::DIDPeer;
+use identity_did::PeerNumalgo;
+use identity_did::PeerPurpose;
+use identity_did::DID as _;
 use identity_did::WebDID;
+use identity_verification::jose::jwk::CompositeJwk;
 use identity_verification::jose::jwk::Jwk;
 use identity_verification::jose::jws::DecodedJws;
 use identity_verification::jose::jws::Decoder;
@@ -20,7 +26,9 @@ use identity_core::common::Object;
 use identity_core::common::OneOrSet;
 use identity_core::common::OrderedSet;
 use identity_core::common::Url;
+use identity_core::common::Value;
 use identity_core::convert::FmtJson;
+use identity_core::convert::FromJson as _;
 use serde::Serializer;
 
 use crate::document::DocumentBuilder;
@@ -1052,12 +1060,204 @@ impl CoreDocument {
   }
 }
 
+impl CoreDocument {
+  /// Creates a [`CoreDocument`] from a did:key DID.
+  ///
+  /// The key is referenced from the key agreement relationship for X25519 keys and from all verification
+  /// relationships except key agreement otherwise.
+  pub fn expand_did_key(did_key: DIDKey) -> Result<Self, Error> {
+    let key_agreement: bool = did_key.key_type().is_key_agreement();
+    let verification_method = VerificationMethod::try_from(did_key.clone()).map_err(Error::InvalidKeyMaterial)?;
+    let verification_method_id = verification_method.id().clone();
+
+    let builder = DocumentBuilder::default()
+      .id(did_key.into())
+      .verification_method(verification_method);
+    if key_agreement {
+      builder.key_agreement(verification_method_id).build()
+    } else {
+      builder
+        .assertion_method(verification_method_id.clone())
+        .authentication(verification_method_id.clone())
+        .capability_invocation(verification_method_id.clone())
+        .capability_delegation(verification_method_id)
+        .build()
+    }
+  }
+}
+
+/// The members of a did:peer:4 input document that hold relative references to the DID.
+const PEER_REFERENCE_MEMBERS: [&str; 8] = [
+  "id",
+  "controller",
+  "verificationMethod",
+  "authentication",
+  "assertionMethod",
+  "keyAgreement",
+  "capabilityInvocation",
+  "capabilityDelegation",
+];
+
+impl CoreDocument {
+  /// Creates a [`CoreDocument`] from a did:peer DID.
+  ///
+  /// Numalgo 0 DIDs are expanded like did:key. Numalgo 2 DIDs are expanded to a verification method `#key-N` for the
+  /// N-th encoded key, referenced from the relationship of its purpose, followed by the encoded services. The long
+  /// form of numalgo 4 DIDs is expanded to its contextualized input document, while the short form cannot be
+  /// expanded on its own.
+  pub fn expand_did_peer(did_peer: DIDPeer) -> Result<Self, Error> {
+    let did: CoreDID = did_peer.clone().into();
+    let invalid_did = |_: identity_did::Error| Error::InvalidDocument("invalid did:peer method-specific id", None);
+    match did_peer.numalgo().map_err(invalid_did)? {
+      PeerNumalgo::InceptionKey => {
+        let did_key: DIDKey = did_peer
+          .inception_key()
+          .map_err(invalid_did)?
+          .ok_or(Error::InvalidDocument(
+            "numalgo 0 did:peer without an inception key",
+            None,
+          ))?;
+        let verification_method = VerificationMethod::new_from_jwk(did.clone(), did_key.jwk(), Some(did_key.method_id()))
+          .map_err(Error::InvalidKeyMaterial)?;
+        let verification_method_id = verification_method.id().clone();
+
+        let builder = DocumentBuilder::default().id(did).verification_method(verification_method);
+        if did_key.key_type().is_key_agreement() {
+          builder.key_agreement(verification_method_id).build()
+        } else {
+          builder
+            .assertion_method(verification_method_id.clone())
+            .authentication(verification_method_id.clone())
+            .capability_invocation(verification_method_id.clone())
+            .capability_delegation(verification_method_id)
+            .build()
+        }
+      }
+      PeerNumalgo::MultipleInceptionKeys => {
+        let mut builder = DocumentBuilder::default().id(did.clone());
+        for (index, (purpose, did_key)) in did_peer.keys().map_err(invalid_did)?.into_iter().enumerate() {
+          let fragment: String = format!("key-{}", index + 1);
+          let verification_method = VerificationMethod::new_from_jwk(did.clone(), did_key.jwk(), Some(&fragment))
+            .map_err(Error::InvalidKeyMaterial)?;
+          let verification_method_id = verification_method.id().clone();
+
+          builder = builder.verification_method(verification_method);
+          builder = match purpose {
+            PeerPurpose::Assertion => builder.assertion_method(verification_method_id),
+            PeerPurpose::Encryption => builder.key_agreement(verification_method_id),
+            PeerPurpose::Verification => builder.authentication(verification_method_id),
+            PeerPurpose::CapabilityInvocation => builder.capability_invocation(verification_method_id),
+            PeerPurpose::CapabilityDelegation => builder.capability_delegation(verification_method_id),
+            _ => return Err(Error::InvalidDocument("unsupported did:peer key purpose", None)),
+          };
+        }
+        for service in did_peer.services().map_err(invalid_did)? {
+          builder = builder.service(expand_peer_service(&did, service)?);
+        }
+        builder.build()
+      }
+      PeerNumalgo::ShortFormAndLongForm => {
+        let mut document: Object = did_peer
+          .input_document()
+          .map_err(invalid_did)?
+          .ok_or(Error::InvalidDocument(
+            "the short form of a did:peer:4 cannot be expanded without its input document",
+            None,
+          ))?;
+        let short_form: DIDPeer = did_peer.short_form().map_err(invalid_did)?;
+
+        for (name, value) in document.iter_mut() {
+          contextualize_peer_value(did.as_str(), name, value);
+        }
+        document.insert("id".to_owned(), Value::String(did.to_string()));
+        match document.get_mut("alsoKnownAs") {
+          Some(Value::Array(also_known_as)) => also_known_as.push(Value::String(short_form.to_string())),
+          _ => {
+            document.insert(
+              "alsoKnownAs".to_owned(),
+              Value::Array(vec![Value::String(short_form.to_string())]),
+            );
+          }
+        }
+
+        CoreDocument::from_json_value(Value::Object(document.into_iter().collect()))
+          .map_err(|err| Error::InvalidDocument("invalid did:peer:4 input document", Some(err)))
+      }
+      _ => Err(Error::InvalidDocument("unsupported did:peer numalgo", None)),
+    }
+  }
+}
+
+/// Builds a [`Service`] of a did:peer:2 DID, making its id absolute and flattening DIDComm endpoint objects into the
+/// URI endpoint and the service properties.
+fn expand_peer_service(did: &CoreDID, mut service: Object) -> Result<Service, Error> {
+  if let Some(Value::String(id)) = service.get_mut("id") {
+    if id.starts_with('#') {
+      *id = format!("{did}{id}");
+    }
+  }
+  if let Some(Value::Object(endpoint)) = service.get("serviceEndpoint") {
+    if let Some(uri) = endpoint.get("uri").cloned() {
+      let endpoint = endpoint.clone();
+      service.insert("serviceEndpoint".to_owned(), uri);
+      for (name, value) in endpoint.into_iter().filter(|(name, _)| name != "uri") {
+        service.entry(name).or_insert(value);
+      }
+    }
+  }
+
+  Service::from_json_value(Value::Object(service.into_iter().collect()))
+    .map_err(|_| Error::InvalidService("invalid did:peer service"))
+}
+
+/// Makes the relative references of a did:peer:4 input document member absolute and sets the controller of the
+/// verification methods it contains.
+fn contextualize_peer_value(did: &str, name: &str, value: &mut Value) {
+  if !PEER_REFERENCE_MEMBERS.contains(&name) {
+    return;
+  }
+  match value {
+    Value::String(reference) if reference.starts_with('#') => *reference = format!("{did}{reference}"),
+    Value::Array(values) => values
+      .iter_mut()
+      .for_each(|value| contextualize_peer_value(did, name, value)),
+    Value::Object(members) => {
+      if !members.contains_key("controller") {
+        members.insert("controller".to_owned(), Value::String(did.to_owned()));
+      }
+      for (name, value) in members.iter_mut() {
+        contextualize_peer_value(did, name, value);
+      }
+    }
+    _ => {}
+  }
+}
 
 //TODO: expand_composite_jwk
 impl CoreDocument {
-  /// Creates a [`CoreDocument`] from a did:jwk DID.
+  /// Creates a [`CoreDocument`] from a long form did:compositejwk DID.
+  ///
+  /// Short form DIDs do not encode their key, see [`CoreDocument::expand_did_compositejwk_with_key`].
   pub fn expand_did_compositejwk(did_compositejwk: DIDCompositeJwk) -> Result<Self, Error> {
-    let verification_method = VerificationMethod::try_from(did_compositejwk.clone()).map_err(Error::InvalidKeyMaterial)?;
+    let composite_jwk = did_compositejwk.try_composite_jwk().map_err(|_| {
+      Error::InvalidDocument(
+        "a short form did:compositejwk cannot be expanded without its key",
+        None,
+      )
+    })?;
+    Self::expand_did_compositejwk_with_key(did_compositejwk, composite_jwk)
+  }
+
+  /// Creates a [`CoreDocument`] from a did:compositejwk DID in either form and the composite key it identifies.
+  pub fn expand_did_compositejwk_with_key(
+    did_compositejwk: DIDCompositeJwk,
+    composite_jwk: CompositeJwk,
+  ) -> Result<Self, Error> {
+    did_compositejwk
+      .verify_composite_jwk(&composite_jwk)
+      .map_err(|_| Error::InvalidDocument("the composite key does not match the did:compositejwk", None))?;
+    let verification_method = VerificationMethod::new_from_compositejwk(did_compositejwk.clone(), composite_jwk, Some("0"))
+      .map_err(Error::InvalidKeyMaterial)?;
     let verification_method_id = verification_method.id().clone();
 
     DocumentBuilder::default()
@@ -1074,6 +1274,9 @@ impl CoreDocument {
 //TODO: Web - impl CoreDocument (WebDID)
 /// DID web
 impl CoreDocument {
+  /// Creates an empty document for the `did:web` DID whose document is published at `url`.
+  ///
+  /// See [`WebDID::new`] for the accepted URLs.
   pub fn new_from_url(url: &str) -> Result<Self, Error>{
     let id = WebDID::new(url).map_err(|_| Error::InvalidDocument("Invalid DID Web", None))?;
     let document: CoreDocument = CoreDocument::builder(Object::default())
@@ -1812,4 +2015,89 @@ mod tests {
 
     assert_eq!(CoreDocument::expand_did_jwk(did_jwk).unwrap(), target_doc);
   }
+
+  #[test]
+  fn test_did_key_expansion() {
+    let did = "did:key:z6MkiTBz1ymuepAQ4HEHYSF1H8quG5GLVVQR3djdX3mDooWp";
+    let method_id = format!("{did}#z6MkiTBz1ymuepAQ4HEHYSF1H8quG5GLVVQR3djdX3mDooWp");
+    let target_doc: CoreDocument = serde_json::from_value(serde_json::json!({
+      "id": did,
+      "verificationMethod": [
+        {
+          "id": method_id,
+          "type": "JsonWebKey2020",
+          "controller": did,
+          "publicKeyJwk": {
+            "kty": "OKP",
+            "crv": "Ed25519",
+            "alg": "EdDSA",
+            "x": "O2onvM62pC1io6jQKm8Nc2UyFXcd4kOmOsBIoYtZ2ik"
+          }
+        }
+      ],
+      "assertionMethod": [method_id],
+      "authentication": [method_id],
+      "capabilityInvocation": [method_id],
+      "capabilityDelegation": [method_id]
+    }))
+    .unwrap();
+    assert_eq!(
+      CoreDocument::expand_did_key(DIDKey::parse(did).unwrap()).unwrap(),
+      target_doc
+    );
+
+    // X25519 keys are only used for key agreement.
+    let did_key = DIDKey::parse("did:key:z6LSeu9HkTHSfLLeUs2nnzUSNedgDUevfNQgQjQC23ZCit6F").unwrap();
+    let document = CoreDocument::expand_did_key(did_key).unwrap();
+    assert_eq!(document.key_agreement().len(), 1);
+    assert!(document.authentication().is_empty());
+    assert!(document.assertion_method().is_empty());
+  }
+
+  #[test]
+  fn test_did_peer_expansion() {
+    let did = "did:peer:2.Ez6LSeu9HkTHSfLLeUs2nnzUSNedgDUevfNQgQjQC23ZCit6F.Vz6MkiTBz1ymuepAQ4HEHYSF1H8quG5GLVVQR3djdX3mDooWp.SeyJ0IjoiZG0iLCJzIjp7InVyaSI6Imh0dHBzOi8vZXhhbXBsZS5jb20vZW5kcG9pbnQiLCJhY2NlcHQiOlsiZGlkY29tbS92MiJdfX0";
+    let document = CoreDocument::expand_did_peer(DIDPeer::parse(did).unwrap()).unwrap();
+    let key_agreement = format!("{did}#key-1");
+    let authentication = format!("{did}#key-2");
+    assert_eq!(document.methods(None).len(), 2);
+    assert_eq!(document.key_agreement().head().unwrap().id().to_string(), key_agreement);
+    assert_eq!(document.authentication().head().unwrap().id().to_string(), authentication);
+    assert!(document.assertion_method().is_empty());
+
+    // DIDComm endpoint objects are flattened into the endpoint URI and the service properties.
+    let service: &Service = document.service().head().unwrap();
+    assert_eq!(service.id().to_string(), format!("{did}#service"));
+    assert_eq!(service.type_().as_slice(), ["DIDCommMessaging"]);
+    assert_eq!(
+      service.service_endpoint(),
+      &crate::service::ServiceEndpoint::One(Url::parse("https://example.com/endpoint").unwrap())
+    );
+    assert_eq!(service.properties()["accept"], serde_json::json!(["didcomm/v2"]));
+
+    let input_document: Object = Object::from_json_value(serde_json::json!({
+      "verificationMethod": [{
+        "id": "#key-1",
+        "type": "JsonWebKey2020",
+        "publicKeyJwk": {
+          "kty": "OKP",
+          "crv": "Ed25519",
+          "x": "O2onvM62pC1io6jQKm8Nc2UyFXcd4kOmOsBIoYtZ2ik"
+        }
+      }],
+      "authentication": ["#key-1"]
+    }))
+    .unwrap();
+    let did_peer = DIDPeer::new_numalgo4(&input_document).unwrap();
+    let short_form = did_peer.short_form().unwrap();
+    let document = CoreDocument::expand_did_peer(did_peer.clone()).unwrap();
+    let method_id = format!("{did_peer}#key-1");
+    assert_eq!(document.id().as_str(), did_peer.as_ref().as_str());
+    assert_eq!(document.also_known_as().head().unwrap().as_str(), short_form.to_string());
+    assert_eq!(document.methods(None)[0].id().to_string(), method_id);
+    assert_eq!(document.methods(None)[0].controller().as_str(), did_peer.as_ref().as_str());
+    assert_eq!(document.authentication().head().unwrap().id().to_string(), method_id);
+
+    assert!(CoreDocument::expand_did_peer(short_form).is_err());
+  }
 }
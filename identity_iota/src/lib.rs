@@ -34,10 +34,20 @@ pub mod credential {
   //! [Specification](https://www.w3.org/TR/vc-data-model/)
 
   pub use identity_credential::credential::*;
+  #[cfg(feature = "data-integrity")]
+  pub use identity_credential::data_integrity::*;
   #[cfg(feature = "domain-linkage")]
   pub use identity_credential::domain_linkage::*;
   pub use identity_credential::error::*;
+  #[cfg(feature = "mdoc")]
+  pub use identity_credential::mdoc::*;
+  #[cfg(feature = "openid4vci")]
+  pub use identity_credential::openid4vci;
+  #[cfg(feature = "openid4vp")]
+  pub use identity_credential::openid4vp;
   pub use identity_credential::presentation::*;
+  #[cfg(feature = "presentation-exchange")]
+  pub use identity_credential::presentation_exchange;
   #[cfg(feature = "revocation-bitmap")]
   pub use identity_credential::revocation::*;
   pub use identity_credential::validator::*;
@@ -98,6 +108,22 @@ pub mod resolver {
   pub use identity_resolver::*;
 }
 
+#[cfg(feature = "didcomm")]
+#[cfg_attr(docsrs, doc(cfg(feature = "didcomm")))]
+pub mod didcomm {
+  //! DIDComm v2 messaging
+
+  pub use identity_didcomm::*;
+}
+
+#[cfg(feature = "webvh")]
+#[cfg_attr(docsrs, doc(cfg(feature = "webvh")))]
+pub mod webvh {
+  //! The did:webvh method
+
+  pub use identity_webvh::*;
+}
+
 pub mod verification {
   //! Types for verifiable data
   pub use identity_verification::*;
@@ -105,6 +131,14 @@ pub mod verification {
 
 pub mod storage {
   //! Storage traits.
+  /// CredentialRegistry types and functionalities.
+  pub mod credential_registry {
+    pub use identity_storage::credential_registry::*;
+  }
+  /// did:web publishing types and functionalities.
+  pub mod did_web {
+    pub use identity_storage::did_web::*;
+  }
   /// KeyIdStorage types and functionalities.
   pub mod key_id_storage {
     pub use identity_storage::key_id_storage::*;
@@ -118,6 +152,8 @@ pub mod storage {
   pub mod storage {
     pub use identity_storage::storage::*;
   }
+  pub use identity_storage::credential_registry::*;
+  pub use identity_storage::did_web::*;
   pub use identity_storage::key_id_storage::*;
   pub use identity_storage::key_storage::*;
   pub use identity_storage::storage::*;
@@ -128,3 +164,9 @@ pub mod sd_jwt_payload {
   //! Expose the selective disclosure crate.
   pub use identity_credential::sd_jwt_payload::*;
 }
+
+#[cfg(feature = "mdoc")]
+pub mod ciborium {
+  //! Expose the CBOR crate used to represent mdoc data elements.
+  pub use identity_credential::ciborium::*;
+}
@@ -0,0 +1,32 @@
+// Copyright 2020-2024 IOTA Stiftung, Fondazione Links
+// SPDX-License-Identifier: Apache-2.0
+
+use identity_core::common::Timestamp;
+
+use crate::block::output::AliasOutput;
+use crate::block::output::OutputId;
+use crate::IotaDocument;
+
+/// An Alias Output, which may have been spent, along with its place in the history of its alias.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AliasOutputRecord {
+  /// The Alias Output.
+  pub output: AliasOutput,
+  /// The timestamp of the milestone that booked the output.
+  pub booked: Timestamp,
+  /// The id of the Alias Output transitioned into this output, or `None` if this output created the alias.
+  pub previous: Option<OutputId>,
+}
+
+/// A version of a DID document, stored in the state metadata of an Alias Output.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct IotaDocumentVersion {
+  /// The id of the version, which is the state index of the Alias Output.
+  pub version_id: u32,
+  /// The id of the earliest Alias Output storing this version.
+  pub output_id: OutputId,
+  /// The timestamp of the milestone from which this version is the latest one.
+  pub valid_from: Timestamp,
+  /// The DID document.
+  pub document: IotaDocument,
+}
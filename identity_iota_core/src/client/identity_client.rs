@@ -4,6 +4,8 @@
 #[cfg(feature = "test")]
 use iota_sdk::client::Client;
 
+use identity_core::common::Timestamp;
+
 use crate::block::address::Address;
 use crate::block::output::feature::SenderFeature;
 use crate::block::output::unlock_condition::GovernorAddressUnlockCondition;
@@ -12,15 +14,21 @@ use crate::block::output::AliasId;
 use crate::block::output::AliasOutput;
 use crate::block::output::AliasOutputBuilder;
 use crate::block::output::Feature;
+use crate::block::output::Output;
 use crate::block::output::OutputId;
+use crate::block::output::Rent;
 use crate::block::output::RentStructure;
 use crate::block::output::UnlockCondition;
 use crate::block::protocol::ProtocolParameters;
+use crate::state_metadata::check_state_metadata_length;
+use crate::AliasOutputRecord;
 use crate::Error;
 use crate::IotaDID;
 use crate::IotaDocument;
+use crate::IotaDocumentVersion;
 use crate::NetworkName;
 use crate::Result;
+use crate::StateMetadataEncoding;
 
 /// Helper functions necessary for the [`IotaIdentityClientExt`] trait.
 #[cfg_attr(feature = "send-sync-client-ext", async_trait::async_trait)]
@@ -30,6 +38,14 @@ pub trait IotaIdentityClient {
   async fn get_alias_output(&self, alias_id: AliasId) -> Result<(OutputId, AliasOutput)>;
   /// Get the protocol parameters of the node we are trying to connect to.
   async fn get_protocol_parameters(&self) -> Result<ProtocolParameters>;
+  /// Resolve the Alias Output with the given `output_id`, which may have been spent.
+  ///
+  /// Required to resolve the history of DID documents, the default implementation returns an error.
+  async fn get_alias_output_record(&self, output_id: OutputId) -> Result<AliasOutputRecord> {
+    Err(Error::DIDHistoryError(format!(
+      "the client cannot resolve the spent alias output {output_id}"
+    )))
+  }
 }
 
 /// An extension trait that provides helper functions for publication
@@ -59,6 +75,33 @@ pub trait IotaIdentityClientExt: IotaIdentityClient {
     document: IotaDocument,
     rent_structure: Option<RentStructure>,
   ) -> Result<AliasOutput> {
+    self
+      .new_did_output_with_encoding(address, document, rent_structure, StateMetadataEncoding::default())
+      .await
+  }
+
+  /// Create a DID with a new Alias Output containing the given `document` packed with `encoding`.
+  ///
+  /// Compact encodings reduce the storage deposit of documents with large keys, such as post-quantum
+  /// or composite verification methods. See [`IotaIdentityClientExt::new_did_output`].
+  ///
+  /// NOTE: This does *not* publish the Alias Output.
+  ///
+  /// # Errors
+  ///
+  /// - [`Error::StateMetadataTooLarge`] when the packed document does not fit in the state metadata.
+  /// - [`Error::DIDUpdateError`] when retrieving the `RentStructure` fails.
+  /// - [`Error::AliasOutputBuildError`] when building the Alias Output fails.
+  async fn new_did_output_with_encoding(
+    &self,
+    address: Address,
+    document: IotaDocument,
+    rent_structure: Option<RentStructure>,
+    encoding: StateMetadataEncoding,
+  ) -> Result<AliasOutput> {
+    let state_metadata: Vec<u8> = document.pack_with_encoding(encoding)?;
+    check_state_metadata_length(&state_metadata)?;
+
     let rent_structure: RentStructure = if let Some(rent) = rent_structure {
       rent
     } else {
@@ -68,7 +111,7 @@ pub trait IotaIdentityClientExt: IotaIdentityClient {
     AliasOutputBuilder::new_with_minimum_storage_deposit(rent_structure, AliasId::null())
       .with_state_index(0)
       .with_foundry_counter(0)
-      .with_state_metadata(document.pack()?)
+      .with_state_metadata(state_metadata)
       .add_feature(Feature::Sender(SenderFeature::new(address)))
       .add_unlock_condition(UnlockCondition::StateControllerAddress(
         StateControllerAddressUnlockCondition::new(address),
@@ -90,12 +133,34 @@ pub trait IotaIdentityClientExt: IotaIdentityClient {
   ///
   /// Returns `Err` when failing to resolve the DID contained in `document`.
   async fn update_did_output(&self, document: IotaDocument) -> Result<AliasOutput> {
+    self
+      .update_did_output_with_encoding(document, StateMetadataEncoding::default())
+      .await
+  }
+
+  /// Fetches the associated Alias Output and updates it with `document` packed with `encoding` in its state
+  /// metadata. The storage deposit on the output is left unchanged, use
+  /// [`IotaIdentityClientExt::check_storage_deposit`] to check whether it must be increased.
+  ///
+  /// NOTE: This does *not* publish the updated Alias Output.
+  ///
+  /// # Errors
+  ///
+  /// - [`Error::StateMetadataTooLarge`] when the packed document does not fit in the state metadata.
+  /// - Returns `Err` when failing to resolve the DID contained in `document`.
+  async fn update_did_output_with_encoding(
+    &self,
+    document: IotaDocument,
+    encoding: StateMetadataEncoding,
+  ) -> Result<AliasOutput> {
     let id: AliasId = AliasId::from(document.id());
+    let state_metadata: Vec<u8> = document.pack_with_encoding(encoding)?;
+    check_state_metadata_length(&state_metadata)?;
     let (_, alias_output) = self.get_alias_output(id).await?;
 
     let mut alias_output_builder: AliasOutputBuilder = AliasOutputBuilder::from(&alias_output)
       .with_state_index(alias_output.state_index() + 1)
-      .with_state_metadata(document.pack()?);
+      .with_state_metadata(state_metadata);
 
     if alias_output.alias_id().is_null() {
       alias_output_builder = alias_output_builder.with_alias_id(id);
@@ -104,6 +169,36 @@ pub trait IotaIdentityClientExt: IotaIdentityClient {
     alias_output_builder.finish().map_err(Error::AliasOutputBuildError)
   }
 
+  /// Checks that the amount of `alias_output` covers its minimum storage deposit according to the given
+  /// `rent_structure`, which will be fetched from the node if not provided, and returns the minimum storage deposit.
+  ///
+  /// Should be called before publishing an updated DID output, since a larger document requires a larger deposit.
+  ///
+  /// # Errors
+  ///
+  /// - [`Error::InsufficientStorageDeposit`] when the amount of the output is below its minimum storage deposit.
+  /// - [`Error::DIDUpdateError`] when retrieving the `RentStructure` fails.
+  async fn check_storage_deposit(
+    &self,
+    alias_output: &AliasOutput,
+    rent_structure: Option<RentStructure>,
+  ) -> Result<u64> {
+    let rent_structure: RentStructure = if let Some(rent) = rent_structure {
+      rent
+    } else {
+      self.get_rent_structure().await?
+    };
+
+    let required: u64 = Output::Alias(alias_output.clone()).rent_cost(&rent_structure);
+    if alias_output.amount() < required {
+      return Err(Error::InsufficientStorageDeposit {
+        required,
+        available: alias_output.amount(),
+      });
+    }
+    Ok(required)
+  }
+
   /// Removes the DID document from the state metadata of its Alias Output,
   /// effectively deactivating it. The storage deposit on the output is left unchanged,
   /// and should be reallocated manually.
@@ -146,6 +241,79 @@ pub trait IotaIdentityClientExt: IotaIdentityClient {
     IotaDocument::unpack_from_output(did, &alias_output, true)
   }
 
+  /// Resolves the versions of the DID document of `did`, from the oldest to the latest.
+  ///
+  /// Every state transition of the Alias Output creates a new version, identified by the state index of the output.
+  /// Deactivated versions are included as empty, deactivated documents.
+  ///
+  /// # Errors
+  ///
+  /// - [`NetworkMismatch`](Error::NetworkMismatch) if the network of the DID and client differ.
+  /// - [`Error::DIDHistoryError`] if a previous Alias Output cannot be resolved.
+  async fn resolve_did_history(&self, did: &IotaDID) -> Result<Vec<IotaDocumentVersion>> {
+    validate_network(self, did).await?;
+
+    let (mut output_id, _) = self.get_alias_output(AliasId::from(did)).await?;
+    let mut versions: Vec<IotaDocumentVersion> = Vec::new();
+    loop {
+      let record: AliasOutputRecord = self.get_alias_output_record(output_id).await?;
+      let version = IotaDocumentVersion {
+        version_id: record.output.state_index(),
+        output_id,
+        valid_from: record.booked,
+        document: IotaDocument::unpack_from_output(did, &record.output, true)?,
+      };
+
+      // Governance transitions keep the state index and document, only the earliest output of a version is kept.
+      if versions
+        .last()
+        .map_or(false, |later| later.version_id == version.version_id)
+      {
+        versions.pop();
+      }
+      versions.push(version);
+
+      match record.previous {
+        Some(previous) => output_id = previous,
+        None => break,
+      }
+    }
+    versions.reverse();
+
+    Ok(versions)
+  }
+
+  /// Resolves the version of the DID document of `did` identified by `version_id`.
+  ///
+  /// # Errors
+  ///
+  /// - [`Error::DIDHistoryError`] if the version does not exist or the history cannot be resolved.
+  async fn resolve_did_version(&self, did: &IotaDID, version_id: u32) -> Result<IotaDocument> {
+    self
+      .resolve_did_history(did)
+      .await?
+      .into_iter()
+      .find(|version| version.version_id == version_id)
+      .map(|version| version.document)
+      .ok_or_else(|| Error::DIDHistoryError(format!("{did} has no version {version_id}")))
+  }
+
+  /// Resolves the version of the DID document of `did` that was the latest one at `time`.
+  ///
+  /// # Errors
+  ///
+  /// - [`Error::DIDHistoryError`] if `did` was created after `time` or the history cannot be resolved.
+  async fn resolve_did_at(&self, did: &IotaDID, time: Timestamp) -> Result<IotaDocument> {
+    self
+      .resolve_did_history(did)
+      .await?
+      .into_iter()
+      .take_while(|version| version.valid_from <= time)
+      .last()
+      .map(|version| version.document)
+      .ok_or_else(|| Error::DIDHistoryError(format!("{did} did not exist at {time}")))
+  }
+
   /// Fetches the [`AliasOutput`] associated with the given DID.
   ///
   /// # Errors
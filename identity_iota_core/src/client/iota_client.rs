@@ -3,22 +3,29 @@
 
 use std::ops::Deref;
 
+use identity_core::common::Timestamp;
 use iota_sdk::client::api::input_selection::Burn;
 use iota_sdk::client::secret::SecretManager;
 use iota_sdk::client::Client;
 use iota_sdk::types::block::protocol::ProtocolParameters;
 
 use crate::block::address::Address;
+use crate::block::input::Input;
 use crate::block::output::unlock_condition::AddressUnlockCondition;
 use crate::block::output::AliasId;
 use crate::block::output::AliasOutput;
 use crate::block::output::BasicOutputBuilder;
 use crate::block::output::Output;
 use crate::block::output::OutputId;
+use crate::block::output::OutputWithMetadata;
 use crate::block::output::UnlockCondition;
+use crate::block::payload::transaction::TransactionEssence;
+use crate::block::payload::transaction::TransactionId;
+use crate::block::payload::Payload;
 use crate::block::Block;
 use crate::client::identity_client::validate_network;
 use crate::error::Result;
+use crate::AliasOutputRecord;
 use crate::Error;
 use crate::IotaDID;
 use crate::IotaDocument;
@@ -137,6 +144,63 @@ impl IotaIdentityClient for Client {
       Err(Error::NotAnAliasOutput(output_id))
     }
   }
+
+  async fn get_alias_output_record(&self, output_id: OutputId) -> Result<AliasOutputRecord> {
+    let output: OutputWithMetadata = self.get_output(&output_id).await.map_err(Error::DIDResolutionError)?;
+    let booked: Timestamp = Timestamp::from_unix(output.metadata().milestone_timestamp_booked().into())
+      .map_err(|err| Error::DIDHistoryError(format!("invalid milestone timestamp: {err}")))?;
+    let transaction_id: TransactionId = *output.metadata().transaction_id();
+    let Output::Alias(alias_output) = output.into_output() else {
+      return Err(Error::NotAnAliasOutput(output_id));
+    };
+
+    // An output with a null alias id created the alias, otherwise it transitioned an input of its transaction.
+    let previous: Option<OutputId> = if alias_output.alias_id().is_null() {
+      None
+    } else {
+      Some(previous_alias_output_id(self, transaction_id, alias_output.alias_id()).await?)
+    };
+
+    Ok(AliasOutputRecord {
+      output: alias_output,
+      booked,
+      previous,
+    })
+  }
+}
+
+/// Returns the id of the Alias Output with `alias_id` consumed by the transaction with `transaction_id`.
+async fn previous_alias_output_id(client: &Client, transaction_id: TransactionId, alias_id: &AliasId) -> Result<OutputId> {
+  let block: Block = client
+    .get_included_block(&transaction_id)
+    .await
+    .map_err(Error::DIDResolutionError)?;
+  let Some(Payload::Transaction(transaction)) = block.payload() else {
+    return Err(Error::DIDHistoryError(format!(
+      "the block including transaction {transaction_id} has no transaction payload"
+    )));
+  };
+  let TransactionEssence::Regular(essence) = transaction.essence();
+
+  for input in essence.inputs() {
+    let Input::Utxo(input) = input else {
+      continue;
+    };
+    let output: Output = client
+      .get_output(input.output_id())
+      .await
+      .map_err(Error::DIDResolutionError)?
+      .into_output();
+    if let Output::Alias(alias_output) = output {
+      if alias_output.alias_id().or_from_output_id(input.output_id()) == *alias_id {
+        return Ok(*input.output_id());
+      }
+    }
+  }
+
+  Err(Error::DIDHistoryError(format!(
+    "transaction {transaction_id} does not consume an alias output of {alias_id}"
+  )))
 }
 
 /// Publishes an `alias_output`.
@@ -0,0 +1,693 @@
+// Copyright 2020-2024 IOTA Stiftung, Fondazione Links
+// SPDX-License-Identifier: Apache-2.0
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::sync::MutexGuard;
+
+use identity_core::common::Duration;
+use identity_core::common::Timestamp;
+use iota_sdk::client::secret::SecretManager;
+
+use crate::block::address::Address;
+use crate::block::output::unlock_condition::AddressUnlockCondition;
+use crate::block::output::AliasId;
+use crate::block::output::AliasOutput;
+use crate::block::output::BasicOutputBuilder;
+use crate::block::output::Output;
+use crate::block::output::OutputId;
+use crate::block::output::Rent;
+use crate::block::output::RentStructure;
+use crate::block::output::UnlockCondition;
+use crate::block::payload::transaction::TransactionId;
+use crate::block::protocol::ProtocolParameters;
+use crate::client::identity_client::validate_network;
+use crate::state_metadata::check_state_metadata_length;
+use crate::AliasOutputRecord;
+use crate::Error;
+use crate::IotaClientExt;
+use crate::IotaDID;
+use crate::IotaDocument;
+use crate::IotaIdentityClient;
+#[cfg(feature = "test")]
+use crate::IotaIdentityClientExt;
+use crate::NetworkName;
+use crate::Result;
+
+/// The token supply of a [`MockLedger`] created with [`MockLedger::new`].
+const TOKEN_SUPPLY: u64 = 1_813_620_509_061_365;
+
+/// A milestone issued by a [`MockLedger`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MockMilestone {
+  /// The index of the milestone.
+  pub index: u32,
+  /// The time at which the milestone was issued.
+  pub timestamp: Timestamp,
+}
+
+/// An Alias Output booked on a [`MockLedger`].
+#[derive(Clone, Debug)]
+struct BookedOutput {
+  output_id: OutputId,
+  output: AliasOutput,
+  milestone: MockMilestone,
+  previous: Option<OutputId>,
+}
+
+impl BookedOutput {
+  fn to_record(&self) -> AliasOutputRecord {
+    AliasOutputRecord {
+      output: self.output.clone(),
+      booked: self.milestone.timestamp,
+      previous: self.previous,
+    }
+  }
+}
+
+#[derive(Debug, Default)]
+struct LedgerState {
+  /// The unspent Alias Outputs, by alias id.
+  aliases: HashMap<AliasId, BookedOutput>,
+  /// The spent Alias Outputs, by output id.
+  spent: HashMap<OutputId, BookedOutput>,
+  /// The tokens of the Basic Outputs created by destroying Alias Outputs, by address.
+  balances: HashMap<Address, u64>,
+  /// The latest issued milestone.
+  milestone: Option<MockMilestone>,
+}
+
+impl LedgerState {
+  /// Returns the milestone following the latest one, without issuing it.
+  fn next_milestone(&self) -> MockMilestone {
+    let now: Timestamp = Timestamp::now_utc();
+    match self.milestone {
+      Some(previous) => MockMilestone {
+        index: previous.index + 1,
+        // Milestone timestamps strictly increase, even if issued within the same second.
+        timestamp: if now > previous.timestamp {
+          now
+        } else {
+          previous
+            .timestamp
+            .checked_add(Duration::seconds(1))
+            .expect("the timestamp of the previous milestone is not the maximum timestamp")
+        },
+      },
+      None => MockMilestone {
+        index: 1,
+        timestamp: now,
+      },
+    }
+  }
+}
+
+impl MockMilestone {
+  /// Returns the id of the only transaction confirmed by this milestone.
+  fn transaction_id(&self) -> TransactionId {
+    let mut transaction_id: [u8; TransactionId::LENGTH] = [0; TransactionId::LENGTH];
+    transaction_id[..4].copy_from_slice(&self.index.to_be_bytes());
+    TransactionId::new(transaction_id)
+  }
+}
+
+/// An in-memory stand-in for an IOTA ledger, implementing [`IotaIdentityClient`] and [`IotaClientExt`].
+///
+/// Publishing an Alias Output books it in the ledger state and issues a new milestone confirming it. The ledger
+/// enforces the rules of the network relevant to DID documents:
+/// - new Alias Outputs start with a null alias id and a state index of `0`,
+/// - a state transition increments the state index and leaves the unlock conditions unchanged,
+/// - a governance transition keeps the state index, state metadata and amount unchanged,
+/// - the state metadata does not exceed [`AliasOutput::STATE_METADATA_LENGTH_MAX`],
+/// - the amount of an output covers its minimum storage deposit and does not exceed the token supply.
+///
+/// Signatures are not checked, so any [`SecretManager`] can be passed to [`IotaClientExt`] methods, e.g.
+/// [`SecretManager::Placeholder`].
+///
+/// Clones share the same ledger state.
+///
+/// This type is intended exclusively for testing.
+#[derive(Clone, Debug)]
+pub struct MockLedger {
+  protocol_parameters: ProtocolParameters,
+  state: Arc<Mutex<LedgerState>>,
+}
+
+impl MockLedger {
+  /// Creates an empty ledger for the network with the given name, with the default [`RentStructure`].
+  pub fn new(network: &NetworkName) -> Self {
+    let protocol_parameters: ProtocolParameters = ProtocolParameters::new(
+      2,
+      network.as_ref().to_owned(),
+      network.as_ref(),
+      0,
+      15,
+      RentStructure::default(),
+      TOKEN_SUPPLY,
+    )
+    .expect("network names are valid human-readable parts");
+    Self::with_protocol_parameters(protocol_parameters)
+  }
+
+  /// Creates an empty ledger with the given [`ProtocolParameters`].
+  ///
+  /// The Bech32 human-readable part of the parameters is the network name of the DIDs the ledger holds.
+  pub fn with_protocol_parameters(protocol_parameters: ProtocolParameters) -> Self {
+    Self {
+      protocol_parameters,
+      state: Arc::new(Mutex::new(LedgerState::default())),
+    }
+  }
+
+  /// Returns the latest milestone, if any transaction was confirmed.
+  pub fn latest_milestone(&self) -> Option<MockMilestone> {
+    self.state().milestone
+  }
+
+  /// Returns the milestone that confirmed the unspent Alias Output with the given `alias_id`, if any.
+  pub fn output_milestone(&self, alias_id: &AliasId) -> Option<MockMilestone> {
+    self.state().aliases.get(alias_id).map(|booked| booked.milestone)
+  }
+
+  /// Returns the amount of tokens sent to `address` by destroying Alias Outputs.
+  pub fn balance(&self, address: &Address) -> u64 {
+    self.state().balances.get(address).copied().unwrap_or_default()
+  }
+
+  fn state(&self) -> MutexGuard<'_, LedgerState> {
+    // The state is never left inconsistent by a panic, so the poison can be ignored.
+    self.state.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+  }
+
+  /// Checks that `alias_output` can be booked on top of `current`, the unspent output with the same alias id.
+  fn validate_transition(&self, alias_output: &AliasOutput, current: Option<&BookedOutput>) -> Result<()> {
+    match current.map(|booked| &booked.output) {
+      None if alias_output.alias_id().is_null() => {
+        if alias_output.state_index() != 0 || alias_output.foundry_counter() != 0 {
+          return Err(Error::DIDUpdateError(
+            "publish_did_output: a new alias output must have a state index and foundry counter of 0",
+            None,
+          ));
+        }
+      }
+      None => {
+        return Err(Error::DIDUpdateError(
+          "publish_did_output: the alias output to transition does not exist",
+          None,
+        ))
+      }
+      Some(current) if alias_output.state_index() == current.state_index() + 1 => {
+        if alias_output.unlock_conditions() != current.unlock_conditions() {
+          return Err(Error::DIDUpdateError(
+            "publish_did_output: a state transition cannot change the unlock conditions",
+            None,
+          ));
+        }
+      }
+      Some(current) if alias_output.state_index() == current.state_index() => {
+        if alias_output.state_metadata() != current.state_metadata() || alias_output.amount() != current.amount() {
+          return Err(Error::DIDUpdateError(
+            "publish_did_output: a governance transition cannot change the state metadata or amount",
+            None,
+          ));
+        }
+      }
+      Some(_) => {
+        return Err(Error::DIDUpdateError(
+          "publish_did_output: the state index must be incremented by a state transition or kept by a governance \
+           transition",
+          None,
+        ))
+      }
+    }
+
+    if let Some(current) = current {
+      if alias_output.immutable_features() != current.output.immutable_features() {
+        return Err(Error::DIDUpdateError(
+          "publish_did_output: the immutable features of an alias output cannot change",
+          None,
+        ));
+      }
+    }
+
+    check_state_metadata_length(alias_output.state_metadata())?;
+
+    let required: u64 = Output::Alias(alias_output.clone()).rent_cost(self.protocol_parameters.rent_structure());
+    if alias_output.amount() < required {
+      return Err(Error::InsufficientStorageDeposit {
+        required,
+        available: alias_output.amount(),
+      });
+    }
+    if alias_output.amount() > self.protocol_parameters.token_supply() {
+      return Err(Error::DIDUpdateError(
+        "publish_did_output: the amount exceeds the token supply",
+        None,
+      ));
+    }
+
+    Ok(())
+  }
+}
+
+#[cfg_attr(feature = "send-sync-client-ext", async_trait::async_trait)]
+#[cfg_attr(not(feature = "send-sync-client-ext"), async_trait::async_trait(?Send))]
+impl IotaIdentityClient for MockLedger {
+  async fn get_alias_output(&self, alias_id: AliasId) -> Result<(OutputId, AliasOutput)> {
+    self
+      .state()
+      .aliases
+      .get(&alias_id)
+      .map(|booked| (booked.output_id, booked.output.clone()))
+      .ok_or_else(|| Error::DIDResolutionError(iota_sdk::client::Error::NoOutput(alias_id.to_string())))
+  }
+
+  async fn get_protocol_parameters(&self) -> Result<ProtocolParameters> {
+    Ok(self.protocol_parameters.clone())
+  }
+
+  async fn get_alias_output_record(&self, output_id: OutputId) -> Result<AliasOutputRecord> {
+    let state: MutexGuard<'_, LedgerState> = self.state();
+    state
+      .spent
+      .get(&output_id)
+      .or_else(|| state.aliases.values().find(|booked| booked.output_id == output_id))
+      .map(BookedOutput::to_record)
+      .ok_or_else(|| Error::DIDResolutionError(iota_sdk::client::Error::NoOutput(output_id.to_string())))
+  }
+}
+
+#[cfg(feature = "test")]
+impl IotaIdentityClientExt for MockLedger {}
+
+#[cfg_attr(feature = "send-sync-client-ext", async_trait::async_trait)]
+#[cfg_attr(not(feature = "send-sync-client-ext"), async_trait::async_trait(?Send))]
+impl IotaClientExt for MockLedger {
+  async fn publish_did_output(
+    &self,
+    _secret_manager: &SecretManager,
+    alias_output: AliasOutput,
+  ) -> Result<IotaDocument> {
+    let network: NetworkName = NetworkName::try_from(self.protocol_parameters.bech32_hrp().to_string())?;
+    let mut state: MutexGuard<'_, LedgerState> = self.state();
+
+    let current: Option<&BookedOutput> = state.aliases.get(alias_output.alias_id());
+    self.validate_transition(&alias_output, current)?;
+
+    let milestone: MockMilestone = state.next_milestone();
+    let output_id: OutputId =
+      OutputId::new(milestone.transaction_id(), 0).map_err(|err| Error::OutputIdConversionError(err.to_string()))?;
+    let alias_id: AliasId = alias_output.alias_id().or_from_output_id(&output_id);
+    // Extract the document before booking the output, so that invalid documents leave the ledger unchanged.
+    let document: IotaDocument =
+      IotaDocument::unpack_from_output(&IotaDID::new(&alias_id, &network), &alias_output, true)?;
+
+    let previous: Option<OutputId> = current.map(|booked| booked.output_id);
+    state.milestone = Some(milestone);
+    let booked = BookedOutput {
+      output_id,
+      output: alias_output,
+      milestone,
+      previous,
+    };
+    if let Some(spent) = state.aliases.insert(alias_id, booked) {
+      state.spent.insert(spent.output_id, spent);
+    }
+
+    Ok(document)
+  }
+
+  async fn delete_did_output(&self, _secret_manager: &SecretManager, address: Address, did: &IotaDID) -> Result<()> {
+    validate_network(self, did).await?;
+
+    let alias_id: AliasId = AliasId::from(did);
+    let (_, alias_output) = self.get_alias_output(alias_id).await?;
+
+    // Validate the Basic Output receiving the tokens of the destroyed Alias Output.
+    BasicOutputBuilder::new_with_amount(alias_output.amount())
+      .with_native_tokens(alias_output.native_tokens().clone())
+      .add_unlock_condition(UnlockCondition::Address(AddressUnlockCondition::new(address)))
+      .finish_output(self.protocol_parameters.token_supply())
+      .map_err(Error::BasicOutputBuildError)?;
+
+    let mut state: MutexGuard<'_, LedgerState> = self.state();
+    let Some(spent) = state.aliases.remove(&alias_id) else {
+      return Err(Error::DIDResolutionError(iota_sdk::client::Error::NoOutput(
+        alias_id.to_string(),
+      )));
+    };
+    state.spent.insert(spent.output_id, spent);
+    state.milestone = Some(state.next_milestone());
+    *state.balances.entry(address).or_default() += alias_output.amount();
+
+    Ok(())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use identity_verification::MethodScope;
+
+  use crate::block::address::Ed25519Address;
+  use crate::block::output::AliasOutputBuilder;
+  use crate::test_utils::generate_composite_method;
+  use crate::test_utils::generate_method;
+  use crate::IotaDocumentVersion;
+  use crate::IotaIdentityClientExt;
+  use crate::StateMetadataEncoding;
+
+  use super::*;
+
+  fn network() -> NetworkName {
+    NetworkName::try_from("tst").unwrap()
+  }
+
+  fn address() -> Address {
+    Address::Ed25519(Ed25519Address::new([7; Ed25519Address::LENGTH]))
+  }
+
+  async fn publish_new(ledger: &MockLedger, mut document: IotaDocument) -> IotaDocument {
+    let method = generate_method(document.id(), "#key-1");
+    document.insert_method(method, MethodScope::VerificationMethod).unwrap();
+    let output: AliasOutput = ledger.new_did_output(address(), document, None).await.unwrap();
+    ledger
+      .publish_did_output(&SecretManager::Placeholder, output)
+      .await
+      .unwrap()
+  }
+
+  /// Updates the output of `document`, increasing its amount to cover the storage deposit of the larger document.
+  async fn update_output(ledger: &MockLedger, document: IotaDocument) -> AliasOutput {
+    let output: AliasOutput = ledger.update_did_output(document).await.unwrap();
+    let required: u64 = match ledger.check_storage_deposit(&output, None).await {
+      Ok(required) => required,
+      Err(Error::InsufficientStorageDeposit { required, .. }) => required,
+      Err(err) => panic!("unexpected error: {err}"),
+    };
+    AliasOutputBuilder::from(&output)
+      .with_amount(output.amount().max(required))
+      .finish()
+      .unwrap()
+  }
+
+  #[tokio::test]
+  async fn test_create_update_deactivate_delete() {
+    let ledger: MockLedger = MockLedger::new(&network());
+    assert_eq!(ledger.latest_milestone(), None);
+
+    // Create.
+    let document: IotaDocument = publish_new(&ledger, IotaDocument::new(&network())).await;
+    let did: IotaDID = document.id().clone();
+    assert!(!did.is_placeholder());
+    assert_eq!(ledger.resolve_did(&did).await.unwrap(), document);
+    let created: MockMilestone = ledger.output_milestone(&AliasId::from(&did)).unwrap();
+    assert_eq!(created.index, 1);
+    assert_eq!(ledger.resolve_did_output(&did).await.unwrap().state_index(), 0);
+
+    // Update.
+    let mut updated: IotaDocument = document.clone();
+    updated
+      .insert_method(generate_method(&did, "#key-2"), MethodScope::authentication())
+      .unwrap();
+    let output: AliasOutput = update_output(&ledger, updated.clone()).await;
+    let published: IotaDocument = ledger
+      .publish_did_output(&SecretManager::Placeholder, output)
+      .await
+      .unwrap();
+    assert_eq!(published.core_document(), updated.core_document());
+    assert_eq!(ledger.resolve_did(&did).await.unwrap(), published);
+    assert_eq!(ledger.resolve_did_output(&did).await.unwrap().state_index(), 1);
+    let milestone: MockMilestone = ledger.output_milestone(&AliasId::from(&did)).unwrap();
+    assert_eq!(milestone.index, 2);
+    assert!(milestone.timestamp > created.timestamp);
+
+    // Deactivate.
+    let output: AliasOutput = ledger.deactivate_did_output(&did).await.unwrap();
+    ledger
+      .publish_did_output(&SecretManager::Placeholder, output)
+      .await
+      .unwrap();
+    let deactivated: IotaDocument = ledger.resolve_did(&did).await.unwrap();
+    assert_eq!(deactivated.metadata.deactivated, Some(true));
+    assert!(deactivated.methods(None).is_empty());
+
+    // Reactivate.
+    let output: AliasOutput = update_output(&ledger, updated.clone()).await;
+    ledger
+      .publish_did_output(&SecretManager::Placeholder, output)
+      .await
+      .unwrap();
+    assert_eq!(
+      ledger.resolve_did(&did).await.unwrap().core_document(),
+      updated.core_document()
+    );
+    assert_eq!(ledger.resolve_did_output(&did).await.unwrap().state_index(), 3);
+
+    // Delete.
+    let amount: u64 = ledger.resolve_did_output(&did).await.unwrap().amount();
+    ledger
+      .delete_did_output(&SecretManager::Placeholder, address(), &did)
+      .await
+      .unwrap();
+    assert_eq!(ledger.balance(&address()), amount);
+    assert!(matches!(
+      ledger.resolve_did(&did).await.unwrap_err(),
+      Error::DIDResolutionError(_)
+    ));
+    assert!(ledger.update_did_output(updated).await.is_err());
+    assert_eq!(ledger.latest_milestone().unwrap().index, 5);
+  }
+
+  #[tokio::test]
+  async fn test_history() {
+    let ledger: MockLedger = MockLedger::new(&network());
+    let created: IotaDocument = publish_new(&ledger, IotaDocument::new(&network())).await;
+    let did: IotaDID = created.id().clone();
+
+    let mut updated: IotaDocument = created.clone();
+    updated
+      .insert_method(generate_method(&did, "#key-2"), MethodScope::authentication())
+      .unwrap();
+    let output: AliasOutput = update_output(&ledger, updated.clone()).await;
+    ledger
+      .publish_did_output(&SecretManager::Placeholder, output)
+      .await
+      .unwrap();
+
+    // A governance transition does not create a new version.
+    let current: AliasOutput = ledger.resolve_did_output(&did).await.unwrap();
+    let governor: Address = Address::Ed25519(Ed25519Address::new([8; Ed25519Address::LENGTH]));
+    let output: AliasOutput = AliasOutputBuilder::from(&current)
+      .replace_unlock_condition(UnlockCondition::GovernorAddress(
+        crate::block::output::unlock_condition::GovernorAddressUnlockCondition::new(governor),
+      ))
+      .finish()
+      .unwrap();
+    ledger
+      .publish_did_output(&SecretManager::Placeholder, output)
+      .await
+      .unwrap();
+
+    let output: AliasOutput = ledger.deactivate_did_output(&did).await.unwrap();
+    ledger
+      .publish_did_output(&SecretManager::Placeholder, output)
+      .await
+      .unwrap();
+
+    let history: Vec<IotaDocumentVersion> = ledger.resolve_did_history(&did).await.unwrap();
+    assert_eq!(
+      history.iter().map(|version| version.version_id).collect::<Vec<_>>(),
+      [0, 1, 2]
+    );
+    assert_eq!(history[0].document, created);
+    assert_eq!(history[1].document.core_document(), updated.core_document());
+    assert_eq!(history[2].document.metadata.deactivated, Some(true));
+    assert!(history
+      .windows(2)
+      .all(|versions| versions[0].valid_from < versions[1].valid_from));
+
+    assert_eq!(ledger.resolve_did_version(&did, 0).await.unwrap(), created);
+    assert!(ledger.resolve_did_version(&did, 3).await.is_err());
+
+    // Point-in-time resolution.
+    assert_eq!(
+      ledger.resolve_did_at(&did, history[0].valid_from).await.unwrap(),
+      created
+    );
+    let before_deactivation: Timestamp = history[2].valid_from.checked_sub(Duration::seconds(1)).unwrap();
+    assert_eq!(
+      ledger
+        .resolve_did_at(&did, before_deactivation)
+        .await
+        .unwrap()
+        .core_document(),
+      updated.core_document()
+    );
+    let before_creation: Timestamp = history[0].valid_from.checked_sub(Duration::seconds(1)).unwrap();
+    assert!(ledger.resolve_did_at(&did, before_creation).await.is_err());
+  }
+
+  #[tokio::test]
+  async fn test_transition_rules() {
+    let ledger: MockLedger = MockLedger::new(&network());
+    let document: IotaDocument = publish_new(&ledger, IotaDocument::new(&network())).await;
+    let did: IotaDID = document.id().clone();
+    let current: AliasOutput = ledger.resolve_did_output(&did).await.unwrap();
+
+    // INVALID: replaying the current output as a state transition.
+    let replay: AliasOutput = AliasOutputBuilder::from(&current)
+      .with_alias_id(AliasId::from(&did))
+      .with_state_metadata(Vec::new())
+      .finish()
+      .unwrap();
+    assert!(ledger
+      .publish_did_output(&SecretManager::Placeholder, replay)
+      .await
+      .is_err());
+
+    // INVALID: skipping state indices.
+    let skipped: AliasOutput = AliasOutputBuilder::from(&current)
+      .with_alias_id(AliasId::from(&did))
+      .with_state_index(current.state_index() + 2)
+      .finish()
+      .unwrap();
+    assert!(ledger
+      .publish_did_output(&SecretManager::Placeholder, skipped)
+      .await
+      .is_err());
+
+    // INVALID: transitioning an unknown alias.
+    let unknown: AliasOutput = AliasOutputBuilder::from(&current)
+      .with_alias_id(AliasId::new([1; AliasId::LENGTH]))
+      .with_state_index(1)
+      .finish()
+      .unwrap();
+    assert!(ledger
+      .publish_did_output(&SecretManager::Placeholder, unknown)
+      .await
+      .is_err());
+
+    // INVALID: a new output below its minimum storage deposit.
+    let output: AliasOutput = ledger
+      .new_did_output(address(), IotaDocument::new(&network()), None)
+      .await
+      .unwrap();
+    let output: AliasOutput = AliasOutputBuilder::from(&output)
+      .with_amount(output.amount() - 1)
+      .finish()
+      .unwrap();
+    assert!(matches!(
+      ledger
+        .publish_did_output(&SecretManager::Placeholder, output)
+        .await
+        .unwrap_err(),
+      Error::InsufficientStorageDeposit { .. }
+    ));
+
+    // INVALID: resolving a DID of another network.
+    let other: IotaDID = IotaDID::new(&AliasId::from(&did), &NetworkName::try_from("smr").unwrap());
+    assert!(matches!(
+      ledger.resolve_did(&other).await.unwrap_err(),
+      Error::NetworkMismatch { .. }
+    ));
+
+    // Rejected transactions leave the ledger unchanged.
+    assert_eq!(ledger.resolve_did_output(&did).await.unwrap(), current);
+    assert_eq!(ledger.latest_milestone().unwrap().index, 1);
+  }
+
+  #[tokio::test]
+  async fn test_storage_deposit() {
+    let ledger: MockLedger = MockLedger::new(&network());
+    let document: IotaDocument = publish_new(&ledger, IotaDocument::new(&network())).await;
+    let did: IotaDID = document.id().clone();
+
+    // Post-quantum keys increase the size of the document beyond the deposit of the output.
+    let mut updated: IotaDocument = document.clone();
+    for index in 0..2 {
+      updated
+        .insert_method(
+          generate_composite_method(&did, &format!("#composite-{index}")),
+          MethodScope::assertion_method(),
+        )
+        .unwrap();
+    }
+    let output: AliasOutput = ledger.update_did_output(updated.clone()).await.unwrap();
+    let required: u64 = match ledger
+      .publish_did_output(&SecretManager::Placeholder, output.clone())
+      .await
+      .unwrap_err()
+    {
+      Error::InsufficientStorageDeposit { required, available } => {
+        assert_eq!(available, output.amount());
+        required
+      }
+      err => panic!("unexpected error: {err}"),
+    };
+    assert!(matches!(
+      ledger.check_storage_deposit(&output, None).await.unwrap_err(),
+      Error::InsufficientStorageDeposit { required: checked, .. } if checked == required
+    ));
+
+    // Increasing the amount allows publishing the update.
+    let output: AliasOutput = AliasOutputBuilder::from(&output)
+      .with_amount(required)
+      .finish()
+      .unwrap();
+    let published: IotaDocument = ledger
+      .publish_did_output(&SecretManager::Placeholder, output)
+      .await
+      .unwrap();
+    assert_eq!(published.core_document(), updated.core_document());
+
+    // Compact encodings require a smaller deposit.
+    let output: AliasOutput = ledger
+      .update_did_output_with_encoding(updated.clone(), StateMetadataEncoding::JsonBrotli)
+      .await
+      .unwrap();
+    assert!(ledger.check_storage_deposit(&output, None).await.unwrap() < required);
+    ledger
+      .publish_did_output(&SecretManager::Placeholder, output)
+      .await
+      .unwrap();
+    assert_eq!(
+      ledger.resolve_did(&did).await.unwrap().core_document(),
+      updated.core_document()
+    );
+  }
+
+  #[tokio::test]
+  async fn test_pq_document_cycle() {
+    let ledger: MockLedger = MockLedger::new(&network());
+
+    let mut document: IotaDocument = IotaDocument::new(&network());
+    document
+      .insert_method(
+        generate_composite_method(document.id(), "#composite"),
+        MethodScope::assertion_method(),
+      )
+      .unwrap();
+    let output: AliasOutput = ledger
+      .new_did_output_with_encoding(address(), document, None, StateMetadataEncoding::Cbor)
+      .await
+      .unwrap();
+    let published: IotaDocument = ledger
+      .publish_did_output(&SecretManager::Placeholder, output)
+      .await
+      .unwrap();
+    let did: IotaDID = published.id().clone();
+
+    let resolved: IotaDocument = ledger.resolve_did(&did).await.unwrap();
+    assert_eq!(resolved, published);
+    assert!(resolved
+      .expand_composite_method("#composite", Some(MethodScope::assertion_method()))
+      .is_ok());
+
+    ledger
+      .delete_did_output(&SecretManager::Placeholder, address(), &did)
+      .await
+      .unwrap();
+    assert!(ledger.resolve_did(&did).await.is_err());
+  }
+}
@@ -1,12 +1,21 @@
 // Copyright 2020-2022 IOTA Stiftung
 // SPDX-License-Identifier: Apache-2.0
 
+pub use history::AliasOutputRecord;
+pub use history::IotaDocumentVersion;
 pub use identity_client::IotaIdentityClient;
 pub use identity_client::IotaIdentityClientExt;
 
 #[cfg(feature = "iota-client")]
 pub use self::iota_client::IotaClientExt;
+#[cfg(feature = "mock-ledger")]
+pub use self::mock_ledger::MockLedger;
+#[cfg(feature = "mock-ledger")]
+pub use self::mock_ledger::MockMilestone;
 
+mod history;
 mod identity_client;
 #[cfg(feature = "iota-client")]
 mod iota_client;
+#[cfg(feature = "mock-ledger")]
+mod mock_ledger;
@@ -8,6 +8,7 @@ use identity_credential::credential::Jws;
 use identity_did::CoreDID;
 use identity_did::DIDUrl;
 use identity_document::verifiable::JwsVerificationOptions;
+use identity_verification::jose::jwk::CompositeJwk;
 use identity_verification::jose::jws::DecodedJws;
 use identity_verification::jose::jws::JwsVerifier;
 use serde::Deserialize;
@@ -354,6 +355,53 @@ impl IotaDocument {
     self.document.resolve_method(method_query, scope)
   }
 
+  /// Constructs a verification method from the post-quantum/traditional hybrid `key` and adds it to the
+  /// document in the given [`MethodScope`], returning the id of the new method.
+  ///
+  /// If `fragment` is `None`, the fragment is derived from the `kid`s of the component keys.
+  ///
+  /// # Errors
+  ///
+  /// Returns an error if either component key contains private key material, if no fragment can be determined
+  /// or if a method with the same fragment already exists.
+  pub fn insert_composite_method(
+    &mut self,
+    key: CompositeJwk,
+    fragment: Option<&str>,
+    scope: MethodScope,
+  ) -> Result<DIDUrl> {
+    if !key.traditional_public_key().is_public() || !key.pq_public_key().is_public() {
+      return Err(Error::InvalidDoc(identity_document::Error::InvalidKeyMaterial(
+        identity_verification::Error::PrivateKeyMaterialExposed,
+      )));
+    }
+
+    let method: VerificationMethod = VerificationMethod::new_from_compositejwk(self.id().clone(), key, fragment)
+      .map_err(|err| Error::InvalidDoc(identity_document::Error::InvalidKeyMaterial(err)))?;
+    let method_id: DIDUrl = method.id().clone();
+    self.insert_method(method, scope)?;
+
+    Ok(method_id)
+  }
+
+  /// Expands the composite method matching the provided `method_query` and the verification relationship
+  /// specified by `scope` into its [`CompositeJwk`], which holds the traditional and post-quantum public keys.
+  ///
+  /// # Errors
+  ///
+  /// Returns an error if no such method exists or if it does not hold a composite public key.
+  pub fn expand_composite_method<'query, Q>(&self, method_query: Q, scope: Option<MethodScope>) -> Result<&CompositeJwk>
+  where
+    Q: Into<DIDUrlQuery<'query>>,
+  {
+    self
+      .resolve_method(method_query, scope)
+      .ok_or(Error::InvalidDoc(identity_document::Error::MethodNotFound))?
+      .data()
+      .try_composite_public_key()
+      .map_err(|err| Error::InvalidDoc(identity_document::Error::InvalidKeyMaterial(err)))
+  }
+
   // ===========================================================================
   // Signatures
   // ===========================================================================
@@ -378,6 +426,35 @@ impl IotaDocument {
       .map_err(Error::JwsVerificationError)
   }
 
+  /// Decodes and verifies the provided post-quantum/traditional hybrid JWS according to the passed
+  /// [`JwsVerificationOptions`], verifying the traditional signature with `traditional_verifier` and the
+  /// post-quantum signature with `pq_verifier`.
+  ///
+  /// Regardless of which options are passed the following conditions must be met in order for a verification attempt to
+  /// take place.
+  /// - The JWS must be encoded according to the JWS compact serialization.
+  /// - The `kid` value in the protected header must be an identifier of a composite verification method in this DID
+  ///   document.
+  pub fn verify_jws_hybrid<'jws, TRV: JwsVerifier, PQV: JwsVerifier>(
+    &self,
+    jws: &'jws Jws,
+    detached_payload: Option<&'jws [u8]>,
+    traditional_verifier: &TRV,
+    pq_verifier: &PQV,
+    options: &JwsVerificationOptions,
+  ) -> Result<DecodedJws<'jws>> {
+    self
+      .core_document()
+      .verify_jws_hybrid(
+        jws.as_str(),
+        detached_payload,
+        traditional_verifier,
+        pq_verifier,
+        options,
+      )
+      .map_err(Error::JwsVerificationError)
+  }
+
   // ===========================================================================
   // Packing
   // ===========================================================================
@@ -605,7 +682,25 @@ mod tests {
   use crate::block::output::AliasOutputBuilder;
   use crate::block::output::UnlockCondition;
 
+  use crypto::hashes::sha::Sha512;
+  use crypto::hashes::Digest;
+  use crypto::signatures::ed25519::SecretKey;
+  use identity_eddsa_verifier::EdDSAJwsVerifier;
+  use identity_verification::jwk::CompositeAlgId;
+  use identity_verification::jwk::EdCurve;
+  use identity_verification::jwk::Jwk;
+  use identity_verification::jwk::JwkParams;
+  use identity_verification::jwk::JwkParamsOkp;
+  use identity_verification::jws::CompactJwsEncoder;
+  use identity_verification::jws::JwsAlgorithm;
+  use identity_verification::jws::JwsHeader;
+  use identity_verification::jws::SignatureVerificationError;
+  use identity_verification::jws::SignatureVerificationErrorKind;
+  use identity_verification::jws::VerificationInput;
+  use identity_verification::jwu;
+
   use super::*;
+  use crate::test_utils::generate_composite_method;
   use crate::test_utils::generate_method;
 
   fn valid_did() -> IotaDID {
@@ -667,6 +762,198 @@ mod tests {
     assert_eq!(methods.next(), None);
   }
 
+  #[test]
+  fn test_composite_methods() {
+    let mut document: IotaDocument = IotaDocument::new_with_id(valid_did());
+    let key: CompositeJwk = generate_composite_method(document.id(), "#composite")
+      .data()
+      .try_composite_public_key()
+      .unwrap()
+      .clone();
+
+    let method_id: DIDUrl = document
+      .insert_composite_method(key.clone(), Some("composite"), MethodScope::assertion_method())
+      .unwrap();
+    assert_eq!(method_id.fragment().unwrap(), "composite");
+    assert_eq!(
+      document
+        .expand_composite_method(&method_id, Some(MethodScope::assertion_method()))
+        .unwrap(),
+      &key
+    );
+
+    // INVALID: duplicate fragment.
+    assert!(document
+      .insert_composite_method(key, Some("composite"), MethodScope::VerificationMethod)
+      .is_err());
+
+    // INVALID: the method does not hold a composite key.
+    document
+      .insert_method(
+        generate_method(document.id(), "#key-1"),
+        MethodScope::VerificationMethod,
+      )
+      .unwrap();
+    assert!(document.expand_composite_method("#key-1", None).is_err());
+    assert!(document.expand_composite_method("#missing", None).is_err());
+  }
+
+  /// Stands in for an ML-DSA-65 verifier, which is not available in this crate: a signature is valid if it is the
+  /// SHA-512 digest of the public key followed by the signing input.
+  struct MockMlDsaVerifier;
+
+  fn mock_ml_dsa_sign(public_key: &Jwk, signing_input: &[u8]) -> Vec<u8> {
+    let JwkParams::MLDSA(params) = public_key.params() else {
+      panic!("not an ML-DSA key");
+    };
+    let mut message: Vec<u8> = jwu::decode_b64(&params.public).unwrap();
+    message.extend_from_slice(signing_input);
+    Sha512::digest(&message).to_vec()
+  }
+
+  impl JwsVerifier for MockMlDsaVerifier {
+    fn verify(
+      &self,
+      input: VerificationInput,
+      public_key: &Jwk,
+    ) -> std::result::Result<(), SignatureVerificationError> {
+      if input.alg != JwsAlgorithm::ML_DSA_65 {
+        return Err(SignatureVerificationErrorKind::UnsupportedAlg.into());
+      }
+      if *input.decoded_signature != *mock_ml_dsa_sign(public_key, &input.signing_input) {
+        return Err(SignatureVerificationErrorKind::InvalidSignature.into());
+      }
+      Ok(())
+    }
+  }
+
+  /// Signs `payload` with the `id-MLDSA65-Ed25519-SHA512` method `fragment` of `document`, using `ed25519_key` for
+  /// the traditional signature and the mock ML-DSA scheme for the post-quantum one.
+  fn sign_hybrid(document: &IotaDocument, fragment: &str, ed25519_key: &SecretKey, payload: &[u8]) -> Jws {
+    let key: &CompositeJwk = document.expand_composite_method(fragment, None).unwrap();
+    let mut header: JwsHeader = JwsHeader::new();
+    header.set_alg(JwsAlgorithm::IdMldsa65Ed25519Sha512);
+    header.set_kid(document.id().to_url().join(fragment).unwrap().to_string());
+    let encoder: CompactJwsEncoder<'_> = CompactJwsEncoder::new(payload, &header).unwrap();
+
+    // The components sign the DER-encoded OID of the composite algorithm followed by the digest of the input.
+    let mut message: Vec<u8> = vec![
+      0x06, 0x0B, 0x60, 0x86, 0x48, 0x01, 0x86, 0xFA, 0x6B, 0x50, 0x08, 0x01, 0x0A,
+    ];
+    message.extend_from_slice(&Sha512::digest(encoder.signing_input()));
+    let mut signature: Vec<u8> = ed25519_key.sign(&message).to_bytes().to_vec();
+    signature.extend(mock_ml_dsa_sign(key.pq_public_key(), &message));
+    Jws::new(encoder.into_jws(&signature))
+  }
+
+  /// Returns a document with the composite method `#composite`, whose traditional key is `ed25519_key`.
+  fn generate_hybrid_document(ed25519_key: &SecretKey) -> IotaDocument {
+    let mut document: IotaDocument = IotaDocument::new_with_id(valid_did());
+    let placeholder: CompositeJwk = generate_composite_method(document.id(), "#composite")
+      .data()
+      .try_composite_public_key()
+      .unwrap()
+      .clone();
+    let mut params: JwkParamsOkp = JwkParamsOkp::new();
+    params.crv = EdCurve::Ed25519.name().to_owned();
+    params.x = jwu::encode_b64(ed25519_key.public_key().as_slice());
+    let mut traditional: Jwk = Jwk::from_params(params);
+    traditional.set_alg(JwsAlgorithm::EdDSA.name());
+    let key: CompositeJwk = CompositeJwk::new(
+      CompositeAlgId::IdMldsa65Ed25519Sha512,
+      traditional,
+      placeholder.pq_public_key().clone(),
+    );
+    document
+      .insert_composite_method(key, Some("composite"), MethodScope::assertion_method())
+      .unwrap();
+    document
+  }
+
+  #[test]
+  fn test_verify_jws_hybrid() {
+    let ed25519_key: SecretKey = SecretKey::generate().unwrap();
+    let document: IotaDocument = generate_hybrid_document(&ed25519_key);
+    let options: JwsVerificationOptions = JwsVerificationOptions::default();
+    let payload: &[u8] = b"{\"claim\":true}";
+
+    let jws: Jws = sign_hybrid(&document, "#composite", &ed25519_key, payload);
+    let decoded: DecodedJws<'_> = document
+      .verify_jws_hybrid(&jws, None, &EdDSAJwsVerifier::default(), &MockMlDsaVerifier, &options)
+      .unwrap();
+    assert_eq!(decoded.claims.as_ref(), payload);
+
+    // The method must be referenced from the requested relationship.
+    assert!(document
+      .verify_jws_hybrid(
+        &jws,
+        None,
+        &EdDSAJwsVerifier::default(),
+        &MockMlDsaVerifier,
+        &JwsVerificationOptions::default().method_scope(MethodScope::authentication()),
+      )
+      .is_err());
+  }
+
+  #[test]
+  fn test_verify_jws_hybrid_requires_both_signatures() {
+    let ed25519_key: SecretKey = SecretKey::generate().unwrap();
+    let document: IotaDocument = generate_hybrid_document(&ed25519_key);
+    let options: JwsVerificationOptions = JwsVerificationOptions::default();
+    let jws: Jws = sign_hybrid(&document, "#composite", &ed25519_key, b"{}");
+    let (signing_input, signature) = jws.as_str().rsplit_once('.').unwrap();
+    let signature: Vec<u8> = jwu::decode_b64(signature).unwrap();
+    let ed25519_signature_length: usize = crypto::signatures::ed25519::Signature::LENGTH;
+
+    // INVALID: only the post-quantum signature is valid.
+    let mut forged: Vec<u8> = signature.clone();
+    forged[0] ^= 1;
+    let forged: Jws = Jws::new(format!("{signing_input}.{}", jwu::encode_b64(forged)));
+    assert!(document
+      .verify_jws_hybrid(
+        &forged,
+        None,
+        &EdDSAJwsVerifier::default(),
+        &MockMlDsaVerifier,
+        &options
+      )
+      .is_err());
+
+    // INVALID: only the traditional signature is valid.
+    let mut forged: Vec<u8> = signature.clone();
+    forged[ed25519_signature_length] ^= 1;
+    let forged: Jws = Jws::new(format!("{signing_input}.{}", jwu::encode_b64(forged)));
+    assert!(document
+      .verify_jws_hybrid(
+        &forged,
+        None,
+        &EdDSAJwsVerifier::default(),
+        &MockMlDsaVerifier,
+        &options
+      )
+      .is_err());
+
+    // INVALID: the traditional signature of another key.
+    let other: Jws = sign_hybrid(&document, "#composite", &SecretKey::generate().unwrap(), b"{}");
+    assert!(document
+      .verify_jws_hybrid(&other, None, &EdDSAJwsVerifier::default(), &MockMlDsaVerifier, &options)
+      .is_err());
+
+    // INVALID: the method does not hold a composite key.
+    let mut document: IotaDocument = document;
+    document
+      .insert_method(
+        generate_method(document.id(), "#key-1"),
+        MethodScope::assertion_method(),
+      )
+      .unwrap();
+    let options: JwsVerificationOptions =
+      JwsVerificationOptions::default().method_id(document.id().to_url().join("#key-1").unwrap());
+    assert!(document
+      .verify_jws_hybrid(&jws, None, &EdDSAJwsVerifier::default(), &MockMlDsaVerifier, &options)
+      .is_err());
+  }
+
   #[test]
   fn test_services() {
     // VALID: add one service.
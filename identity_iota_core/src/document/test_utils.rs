@@ -3,9 +3,13 @@
 
 use crypto::signatures::ed25519::PublicKey;
 use crypto::signatures::ed25519::SecretKey;
+use identity_verification::jwk::CompositeAlgId;
+use identity_verification::jwk::CompositeJwk;
 use identity_verification::jwk::EdCurve;
 use identity_verification::jwk::Jwk;
+use identity_verification::jwk::JwkParams;
 use identity_verification::jwk::JwkParamsOkp;
+use identity_verification::jwk::JwkParamsPQ;
 use identity_verification::jws::JwsAlgorithm;
 use identity_verification::jwu;
 use identity_verification::VerificationMethod;
@@ -19,6 +23,25 @@ pub(crate) fn generate_method(controller: &IotaDID, fragment: &str) -> Verificat
   VerificationMethod::new_from_jwk(controller.to_owned(), jwk, Some(fragment)).unwrap()
 }
 
+/// Length in bytes of ML-DSA-65 public keys.
+const ML_DSA_65_PUBLIC_KEY_LENGTH: usize = 1952;
+
+/// Generates an `id-MLDSA65-Ed25519-SHA512` composite method. The ML-DSA public key is a fixed placeholder and only
+/// suitable to test encodings.
+pub(crate) fn generate_composite_method(controller: &IotaDID, fragment: &str) -> VerificationMethod {
+  let traditional: PublicKey = SecretKey::generate().unwrap().public_key();
+  let traditional_jwk: Jwk = encode_public_ed25519_jwk(traditional.as_ref());
+
+  let pq_public_key: Vec<u8> = vec![0x42; ML_DSA_65_PUBLIC_KEY_LENGTH];
+  let mut params: JwkParamsPQ = JwkParamsPQ::new();
+  params.public = jwu::encode_b64(pq_public_key);
+  let mut pq_jwk: Jwk = Jwk::from_params(JwkParams::MLDSA(params));
+  pq_jwk.set_alg(JwsAlgorithm::ML_DSA_65.name());
+
+  let composite: CompositeJwk = CompositeJwk::new(CompositeAlgId::IdMldsa65Ed25519Sha512, traditional_jwk, pq_jwk);
+  VerificationMethod::new_from_compositejwk(controller.to_owned(), composite, Some(fragment)).unwrap()
+}
+
 fn encode_public_ed25519_jwk(public_key: &[u8]) -> Jwk {
   let x = jwu::encode_b64(public_key);
   let mut params = JwkParamsOkp::new();
@@ -51,6 +51,23 @@ pub enum Error {
   /// Caused by an attempt to read state metadata that does not adhere to the IOTA DID method specification.
   #[error("invalid state metadata {0}")]
   InvalidStateMetadata(&'static str),
+  /// Caused by a packed DID document that exceeds the maximum length of the state metadata of an Alias Output.
+  #[error("the packed document is {size} bytes long, exceeding the maximum state metadata length of {max} bytes")]
+  StateMetadataTooLarge {
+    /// The length in bytes of the packed document.
+    size: usize,
+    /// The maximum length in bytes of the state metadata.
+    max: usize,
+  },
+  #[cfg(feature = "client")]
+  /// Caused by an Alias Output whose amount does not cover its minimum storage deposit.
+  #[error("the output holds {available} tokens but requires a storage deposit of {required}")]
+  InsufficientStorageDeposit {
+    /// The minimum storage deposit of the output.
+    required: u64,
+    /// The amount of the output.
+    available: u64,
+  },
   #[cfg(feature = "revocation-bitmap")]
   /// Caused by a failure during (un)revocation of credentials.
   #[error("credential revocation error")]
@@ -73,4 +90,7 @@ pub enum Error {
   /// Caused by an error during JSON Web Signature verification.
   #[error("jws signature verification failed")]
   JwsVerificationError(#[source] identity_document::Error),
+  /// Caused by a failure to resolve the history of a DID document.
+  #[error("DID history resolution failed: {0}")]
+  DIDHistoryError(String),
 }
@@ -1,6 +1,9 @@
 // Copyright 2020-2023 IOTA Stiftung
 // SPDX-License-Identifier: Apache-2.0
 
+use std::io::Read;
+use std::io::Write;
+
 use identity_core::convert::FromJson;
 use identity_core::convert::ToJson;
 use identity_did::CoreDID;
@@ -23,6 +26,21 @@ pub(crate) static PLACEHOLDER_DID: Lazy<CoreDID> = Lazy::new(|| CoreDID::parse("
 /// Magic bytes used to mark DID documents.
 const DID_MARKER: &[u8] = b"DID";
 
+/// The maximum length in bytes of the state metadata of an Alias Output.
+pub const MAX_STATE_METADATA_LENGTH: usize = 8192;
+
+/// The maximum length in bytes of a decompressed document, guarding against decompression bombs.
+const MAX_DECOMPRESSED_LENGTH: u64 = 1 << 20;
+
+/// The Brotli quality used to compress documents.
+const BROTLI_QUALITY: u32 = 11;
+
+/// The base 2 logarithm of the Brotli window size used to compress documents.
+const BROTLI_WINDOW_SIZE: u32 = 22;
+
+/// The size of the buffers used by the Brotli encoder and decoder.
+const BROTLI_BUFFER_SIZE: usize = 4096;
+
 /// Intermediate representation of the DID document as it is contained in the state metadata of
 /// an Alias Output.
 ///
@@ -84,6 +102,17 @@ impl StateMetadataDocument {
       StateMetadataEncoding::Json => self
         .to_json_vec()
         .map_err(|err| Error::SerializationError("failed to serialize document to JSON", Some(err)))?,
+      StateMetadataEncoding::JsonBrotli => brotli_compress(
+        &self
+          .to_json_vec()
+          .map_err(|err| Error::SerializationError("failed to serialize document to JSON", Some(err)))?,
+      )?,
+      StateMetadataEncoding::Cbor => {
+        let mut buffer: Vec<u8> = Vec::new();
+        ciborium::into_writer(&self, &mut buffer)
+          .map_err(|_| Error::SerializationError("failed to serialize document to CBOR", None))?;
+        buffer
+      }
     };
 
     // Prepend flags and length.
@@ -160,10 +189,53 @@ impl StateMetadataDocument {
           Some(err),
         )
       }),
+      StateMetadataEncoding::JsonBrotli => {
+        StateMetadataDocument::from_json_slice(&brotli_decompress(data)?).map_err(|err| {
+          Error::SerializationError(
+            "state metadata decoding: failed to deserialize compressed JSON document",
+            Some(err),
+          )
+        })
+      }
+      StateMetadataEncoding::Cbor => ciborium::from_reader(data)
+        .map_err(|_| Error::SerializationError("state metadata decoding: failed to deserialize CBOR document", None)),
     }
   }
 }
 
+/// Checks that `packed` fits in the state metadata of an Alias Output.
+pub(crate) fn check_state_metadata_length(packed: &[u8]) -> Result<()> {
+  if packed.len() > MAX_STATE_METADATA_LENGTH {
+    return Err(Error::StateMetadataTooLarge {
+      size: packed.len(),
+      max: MAX_STATE_METADATA_LENGTH,
+    });
+  }
+  Ok(())
+}
+
+/// Compresses `data` with Brotli.
+fn brotli_compress(data: &[u8]) -> Result<Vec<u8>> {
+  let mut writer = brotli::CompressorWriter::new(Vec::new(), BROTLI_BUFFER_SIZE, BROTLI_QUALITY, BROTLI_WINDOW_SIZE);
+  writer
+    .write_all(data)
+    .map_err(|_| Error::SerializationError("failed to compress document", None))?;
+  Ok(writer.into_inner())
+}
+
+/// Decompresses Brotli-compressed `data`, rejecting documents larger than [`MAX_DECOMPRESSED_LENGTH`].
+fn brotli_decompress(data: &[u8]) -> Result<Vec<u8>> {
+  let mut decompressed: Vec<u8> = Vec::new();
+  brotli::Decompressor::new(data, BROTLI_BUFFER_SIZE)
+    .take(MAX_DECOMPRESSED_LENGTH + 1)
+    .read_to_end(&mut decompressed)
+    .map_err(|_| Error::InvalidStateMetadata("invalid compressed document"))?;
+  if decompressed.len() as u64 > MAX_DECOMPRESSED_LENGTH {
+    return Err(Error::InvalidStateMetadata("decompressed document too large"));
+  }
+  Ok(decompressed)
+}
+
 /// Prepends the message flags and marker magic bytes to the data in the following order:
 /// `[marker, version, encoding, data length, data]`.
 fn add_flags_to_message(
@@ -217,9 +289,12 @@ mod tests {
   use identity_did::DID;
   use identity_verification::MethodScope;
 
+  use crate::state_metadata::check_state_metadata_length;
   use crate::state_metadata::document::DID_MARKER;
   use crate::state_metadata::PLACEHOLDER_DID;
+  use crate::test_utils::generate_composite_method;
   use crate::test_utils::generate_method;
+  use crate::Error;
   use crate::IotaDID;
   use crate::IotaDocument;
   use crate::StateMetadataDocument;
@@ -534,4 +609,106 @@ mod tests {
       unpacked_doc.document.properties()
     );
   }
+
+  fn test_composite_document(methods: usize) -> TestSetup {
+    let TestSetup {
+      mut document,
+      did_self,
+      did_foreign,
+    } = test_document();
+    for index in 0..methods {
+      document
+        .insert_method(
+          generate_composite_method(&did_self, &format!("composite-{index}")),
+          MethodScope::VerificationMethod,
+        )
+        .unwrap();
+    }
+    TestSetup {
+      document,
+      did_self,
+      did_foreign,
+    }
+  }
+
+  const ENCODINGS: [StateMetadataEncoding; 3] = [
+    StateMetadataEncoding::Json,
+    StateMetadataEncoding::JsonBrotli,
+    StateMetadataEncoding::Cbor,
+  ];
+
+  #[test]
+  fn test_packing_roundtrip_encodings() {
+    let TestSetup { document, did_self, .. } = test_composite_document(1);
+
+    for encoding in ENCODINGS {
+      let packed: Vec<u8> = StateMetadataDocument::from(document.clone()).pack(encoding).unwrap();
+      assert_eq!(&packed[0..3], DID_MARKER);
+      assert_eq!(packed[3], StateMetadataVersion::V1 as u8);
+      assert_eq!(packed[4], encoding as u8);
+
+      let unpacked: IotaDocument = StateMetadataDocument::unpack(&packed)
+        .unwrap()
+        .into_iota_document(&did_self)
+        .unwrap();
+      assert_eq!(unpacked, document, "{encoding:?}");
+      assert!(unpacked
+        .resolve_method("#composite-0", None)
+        .unwrap()
+        .data()
+        .composite_public_key()
+        .is_some());
+    }
+  }
+
+  #[test]
+  fn test_compact_encodings_fit_large_documents() {
+    let TestSetup { document, .. } = test_composite_document(3);
+
+    let json: Vec<u8> = StateMetadataDocument::from(document.clone())
+      .pack(StateMetadataEncoding::Json)
+      .unwrap();
+    let brotli: Vec<u8> = StateMetadataDocument::from(document.clone())
+      .pack(StateMetadataEncoding::JsonBrotli)
+      .unwrap();
+    let cbor: Vec<u8> = StateMetadataDocument::from(document)
+      .pack(StateMetadataEncoding::Cbor)
+      .unwrap();
+    assert!(brotli.len() < json.len());
+    assert!(cbor.len() < json.len());
+
+    // The JSON document exceeds the state metadata of an Alias Output, the compressed one does not.
+    assert!(matches!(
+      check_state_metadata_length(&json),
+      Err(Error::StateMetadataTooLarge { .. })
+    ));
+    assert!(check_state_metadata_length(&brotli).is_ok());
+  }
+
+  #[test]
+  fn test_unpack_invalid_encoded_data() {
+    let TestSetup { document, .. } = test_document();
+
+    for encoding in [StateMetadataEncoding::JsonBrotli, StateMetadataEncoding::Cbor] {
+      let mut packed: Vec<u8> = StateMetadataDocument::from(document.clone()).pack(encoding).unwrap();
+      let length: usize = packed.len();
+      packed[length / 2] ^= 0xff;
+      packed[length - 1] ^= 0xff;
+      assert!(StateMetadataDocument::unpack(&packed).is_err(), "{encoding:?}");
+    }
+
+    // INVALID: JSON data flagged as compressed.
+    let mut packed: Vec<u8> = StateMetadataDocument::from(document.clone())
+      .pack(StateMetadataEncoding::Json)
+      .unwrap();
+    packed[4] = StateMetadataEncoding::JsonBrotli as u8;
+    assert!(StateMetadataDocument::unpack(&packed).is_err());
+
+    // INVALID: unknown encoding.
+    packed[4] = u8::MAX;
+    assert!(matches!(
+      StateMetadataDocument::unpack(&packed),
+      Err(Error::InvalidStateMetadata(_))
+    ));
+  }
 }
@@ -12,6 +12,13 @@ pub enum StateMetadataEncoding {
   /// State Metadata encoded as JSON.
   #[default]
   Json = 0,
+  /// State Metadata encoded as JSON and compressed with Brotli.
+  ///
+  /// Reduces the storage deposit of documents with large keys, e.g. post-quantum or composite verification methods,
+  /// at the cost of human readability on the ledger.
+  JsonBrotli = 1,
+  /// State Metadata encoded as CBOR.
+  Cbor = 2,
 }
 
 impl TryFrom<u8> for StateMetadataEncoding {
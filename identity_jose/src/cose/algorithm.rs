@@ -0,0 +1,139 @@
+// Copyright 2020-2024 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+use core::fmt::Display;
+use core::fmt::Formatter;
+
+use crate::error::Error;
+use crate::error::Result;
+use crate::jws::JwsAlgorithm;
+
+/// Supported algorithms for the COSE `alg` header parameter.
+///
+/// Every variant has a [`JwsAlgorithm`] counterpart, which allows signatures to be verified with any
+/// [`JwsVerifier`](crate::jws::JwsVerifier).
+///
+/// [More Info](https://www.iana.org/assignments/cose/cose.xhtml#algorithms)
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
+#[allow(non_camel_case_types)]
+pub enum CoseAlgorithm {
+  /// ECDSA using P-256 and SHA-256
+  ES256,
+  /// ECDSA using P-384 and SHA-384
+  ES384,
+  /// ECDSA using P-521 and SHA-512
+  ES512,
+  /// ECDSA using secp256k1 curve and SHA-256
+  ES256K,
+  /// EdDSA signature algorithms
+  EdDSA,
+  /// COSE Algorithm for ML-DSA-44
+  /// [More Info](https://datatracker.ietf.org/doc/html/draft-ietf-cose-dilithium#name-the-ml-dsa-algorithm-family)
+  ML_DSA_44,
+  /// COSE Algorithm for ML-DSA-65
+  /// [More Info](https://datatracker.ietf.org/doc/html/draft-ietf-cose-dilithium#name-the-ml-dsa-algorithm-family)
+  ML_DSA_65,
+  /// COSE Algorithm for ML-DSA-87
+  /// [More Info](https://datatracker.ietf.org/doc/html/draft-ietf-cose-dilithium#name-the-ml-dsa-algorithm-family)
+  ML_DSA_87,
+}
+
+impl CoseAlgorithm {
+  /// A slice of all supported [`CoseAlgorithm`]s.
+  pub const ALL: &'static [Self] = &[
+    Self::ES256,
+    Self::ES384,
+    Self::ES512,
+    Self::ES256K,
+    Self::EdDSA,
+    Self::ML_DSA_44,
+    Self::ML_DSA_65,
+    Self::ML_DSA_87,
+  ];
+
+  /// Returns the integer identifier of the algorithm.
+  pub const fn id(self) -> i64 {
+    match self {
+      Self::ES256 => -7,
+      Self::ES384 => -35,
+      Self::ES512 => -36,
+      Self::ES256K => -47,
+      Self::EdDSA => -8,
+      Self::ML_DSA_44 => -48,
+      Self::ML_DSA_65 => -49,
+      Self::ML_DSA_87 => -50,
+    }
+  }
+
+  /// Returns the algorithm with the given integer identifier, if supported.
+  pub fn from_id(id: i64) -> Option<Self> {
+    Self::ALL.iter().copied().find(|alg| alg.id() == id)
+  }
+
+  /// Returns the [`JwsAlgorithm`] using the same signature scheme.
+  pub const fn to_jws_algorithm(self) -> JwsAlgorithm {
+    match self {
+      Self::ES256 => JwsAlgorithm::ES256,
+      Self::ES384 => JwsAlgorithm::ES384,
+      Self::ES512 => JwsAlgorithm::ES512,
+      Self::ES256K => JwsAlgorithm::ES256K,
+      Self::EdDSA => JwsAlgorithm::EdDSA,
+      Self::ML_DSA_44 => JwsAlgorithm::ML_DSA_44,
+      Self::ML_DSA_65 => JwsAlgorithm::ML_DSA_65,
+      Self::ML_DSA_87 => JwsAlgorithm::ML_DSA_87,
+    }
+  }
+
+  /// Returns the name of the algorithm, which equals the name of the corresponding [`JwsAlgorithm`].
+  pub const fn name(self) -> &'static str {
+    match self {
+      Self::ES256 => "ES256",
+      Self::ES384 => "ES384",
+      Self::ES512 => "ES512",
+      Self::ES256K => "ES256K",
+      Self::EdDSA => "EdDSA",
+      Self::ML_DSA_44 => "ML-DSA-44",
+      Self::ML_DSA_65 => "ML-DSA-65",
+      Self::ML_DSA_87 => "ML-DSA-87",
+    }
+  }
+}
+
+impl TryFrom<JwsAlgorithm> for CoseAlgorithm {
+  type Error = Error;
+
+  fn try_from(value: JwsAlgorithm) -> Result<Self, Self::Error> {
+    Self::ALL
+      .iter()
+      .copied()
+      .find(|alg| alg.to_jws_algorithm() == value)
+      .ok_or(Error::InvalidParam("unsupported COSE algorithm"))
+  }
+}
+
+impl From<CoseAlgorithm> for JwsAlgorithm {
+  fn from(value: CoseAlgorithm) -> Self {
+    value.to_jws_algorithm()
+  }
+}
+
+impl Display for CoseAlgorithm {
+  fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+    f.write_str(self.name())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn roundtrip() {
+    for alg in CoseAlgorithm::ALL {
+      assert_eq!(CoseAlgorithm::from_id(alg.id()), Some(*alg));
+      assert_eq!(CoseAlgorithm::try_from(alg.to_jws_algorithm()).unwrap(), *alg);
+    }
+    assert_eq!(CoseAlgorithm::from_id(-48), Some(CoseAlgorithm::ML_DSA_44));
+    assert!(CoseAlgorithm::try_from(JwsAlgorithm::HS256).is_err());
+  }
+}
@@ -0,0 +1,43 @@
+// Copyright 2020-2024 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+use ciborium::value::Integer;
+use ciborium::Value;
+
+use crate::error::Error;
+use crate::error::Result;
+
+pub(super) fn to_vec(value: &Value) -> Result<Vec<u8>> {
+  let mut bytes: Vec<u8> = Vec::new();
+  ciborium::ser::into_writer(value, &mut bytes).map_err(|err| Error::InvalidCbor(err.into()))?;
+  Ok(bytes)
+}
+
+pub(super) fn from_slice(bytes: &[u8]) -> Result<Value> {
+  ciborium::de::from_reader(bytes).map_err(|err| Error::InvalidCbor(err.into()))
+}
+
+pub(super) fn label(label: i64) -> Value {
+  Value::Integer(label.into())
+}
+
+pub(super) fn as_i64(value: &Value) -> Option<i64> {
+  value.as_integer().and_then(|int: Integer| i64::try_from(int).ok())
+}
+
+/// Converts a CBOR map into `(label, value)` pairs, rejecting non-integer and duplicate labels.
+pub(super) fn into_labeled_map(value: Value, name: &'static str) -> Result<Vec<(i64, Value)>> {
+  let Value::Map(entries) = value else {
+    return Err(Error::InvalidContent(name));
+  };
+
+  let mut labeled: Vec<(i64, Value)> = Vec::with_capacity(entries.len());
+  for (key, value) in entries {
+    let key: i64 = as_i64(&key).ok_or(Error::InvalidContent(name))?;
+    if labeled.iter().any(|(existing, _)| *existing == key) {
+      return Err(Error::InvalidContent(name));
+    }
+    labeled.push((key, value));
+  }
+  Ok(labeled)
+}
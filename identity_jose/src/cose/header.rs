@@ -0,0 +1,162 @@
+// Copyright 2020-2024 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+use ciborium::Value;
+
+use super::cbor;
+use super::CoseAlgorithm;
+use crate::error::Error;
+use crate::error::Result;
+
+const LABEL_ALG: i64 = 1;
+const LABEL_CONTENT_TYPE: i64 = 3;
+const LABEL_KID: i64 = 4;
+const LABEL_TYP: i64 = 16;
+
+/// COSE Header, either the protected or the unprotected bucket of a COSE message.
+///
+/// Only the common header parameters relevant for signed credentials are supported, unknown parameters are
+/// ignored when decoding.
+///
+/// [More Info](https://www.rfc-editor.org/rfc/rfc9052#section-3.1)
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct CoseHeader {
+  /// Algorithm.
+  ///
+  /// [More Info](https://www.rfc-editor.org/rfc/rfc9052#section-3.1)
+  alg: Option<CoseAlgorithm>,
+  /// Content Type.
+  ///
+  /// [More Info](https://www.rfc-editor.org/rfc/rfc9052#section-3.1)
+  content_type: Option<String>,
+  /// Key Identifier.
+  ///
+  /// [More Info](https://www.rfc-editor.org/rfc/rfc9052#section-3.1)
+  kid: Option<Vec<u8>>,
+  /// Type of the complete COSE object.
+  ///
+  /// [More Info](https://www.rfc-editor.org/rfc/rfc9596)
+  typ: Option<String>,
+}
+
+impl CoseHeader {
+  /// Create a new empty `CoseHeader`.
+  pub const fn new() -> Self {
+    Self {
+      alg: None,
+      content_type: None,
+      kid: None,
+      typ: None,
+    }
+  }
+
+  /// Returns the value for the algorithm claim (alg).
+  pub fn alg(&self) -> Option<CoseAlgorithm> {
+    self.alg
+  }
+
+  /// Sets a value for the algorithm claim (alg).
+  pub fn set_alg(&mut self, value: impl Into<CoseAlgorithm>) {
+    self.alg = Some(value.into());
+  }
+
+  /// Returns the value of the content type claim (content type).
+  pub fn content_type(&self) -> Option<&str> {
+    self.content_type.as_deref()
+  }
+
+  /// Sets a value for the content type claim (content type).
+  pub fn set_content_type(&mut self, value: impl Into<String>) {
+    self.content_type = Some(value.into());
+  }
+
+  /// Returns the value of the key identifier claim (kid).
+  pub fn kid(&self) -> Option<&[u8]> {
+    self.kid.as_deref()
+  }
+
+  /// Sets a value for the key identifier claim (kid).
+  pub fn set_kid(&mut self, value: impl Into<Vec<u8>>) {
+    self.kid = Some(value.into());
+  }
+
+  /// Returns the value of the type claim (typ).
+  pub fn typ(&self) -> Option<&str> {
+    self.typ.as_deref()
+  }
+
+  /// Sets a value for the type claim (typ).
+  pub fn set_typ(&mut self, value: impl Into<String>) {
+    self.typ = Some(value.into());
+  }
+
+  /// Returns `true` if no header parameter is set.
+  pub fn is_empty(&self) -> bool {
+    self == &Self::new()
+  }
+
+  /// Returns `true` if `self` and `other` have no header parameter in common.
+  pub fn is_disjoint(&self, other: &CoseHeader) -> bool {
+    !((self.alg.is_some() && other.alg.is_some())
+      || (self.content_type.is_some() && other.content_type.is_some())
+      || (self.kid.is_some() && other.kid.is_some())
+      || (self.typ.is_some() && other.typ.is_some()))
+  }
+
+  pub(super) fn to_cbor_value(&self) -> Value {
+    let mut entries: Vec<(Value, Value)> = Vec::new();
+    if let Some(alg) = self.alg {
+      entries.push((cbor::label(LABEL_ALG), cbor::label(alg.id())));
+    }
+    if let Some(content_type) = &self.content_type {
+      entries.push((cbor::label(LABEL_CONTENT_TYPE), Value::Text(content_type.clone())));
+    }
+    if let Some(kid) = &self.kid {
+      entries.push((cbor::label(LABEL_KID), Value::Bytes(kid.clone())));
+    }
+    if let Some(typ) = &self.typ {
+      entries.push((cbor::label(LABEL_TYP), Value::Text(typ.clone())));
+    }
+    Value::Map(entries)
+  }
+
+  pub(super) fn from_cbor_value(value: Value) -> Result<Self> {
+    let mut header: Self = Self::new();
+    for (label, value) in cbor::into_labeled_map(value, "invalid COSE header")? {
+      match (label, value) {
+        (LABEL_ALG, value) => {
+          let alg: CoseAlgorithm = cbor::as_i64(&value)
+            .and_then(CoseAlgorithm::from_id)
+            .ok_or(Error::InvalidParam("unsupported COSE algorithm"))?;
+          header.alg = Some(alg);
+        }
+        (LABEL_CONTENT_TYPE, Value::Text(content_type)) => header.content_type = Some(content_type),
+        (LABEL_KID, Value::Bytes(kid)) => header.kid = Some(kid),
+        (LABEL_TYP, Value::Text(typ)) => header.typ = Some(typ),
+        (LABEL_CONTENT_TYPE | LABEL_KID | LABEL_TYP, _) => {
+          return Err(Error::InvalidContent("invalid COSE header parameter"));
+        }
+        _ => {}
+      }
+    }
+    Ok(header)
+  }
+
+  /// Encodes the header as a serialized CBOR map, as used for the protected bucket. An empty header is encoded as an
+  /// empty byte string.
+  pub(super) fn to_protected_bytes(&self) -> Result<Vec<u8>> {
+    if self.is_empty() {
+      Ok(Vec::new())
+    } else {
+      cbor::to_vec(&self.to_cbor_value())
+    }
+  }
+
+  pub(super) fn from_protected_bytes(bytes: &[u8]) -> Result<Self> {
+    if bytes.is_empty() {
+      Ok(Self::new())
+    } else {
+      Self::from_cbor_value(cbor::from_slice(bytes)?)
+    }
+  }
+}
@@ -0,0 +1,329 @@
+// Copyright 2020-2024 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+use ciborium::Value;
+
+use super::cbor;
+use super::CoseAlgorithm;
+use crate::error::Error;
+use crate::error::Result;
+use crate::jwk::Jwk;
+use crate::jwk::JwkParams;
+use crate::jwk::JwkParamsEc;
+use crate::jwk::JwkParamsOkp;
+use crate::jwk::JwkParamsPQ;
+use crate::jws::JwsAlgorithm;
+use crate::jwu::decode_b64;
+use crate::jwu::encode_b64;
+
+const LABEL_KTY: i64 = 1;
+const LABEL_KID: i64 = 2;
+const LABEL_ALG: i64 = 3;
+const LABEL_CRV: i64 = -1;
+const LABEL_X: i64 = -2;
+const LABEL_Y: i64 = -3;
+const LABEL_D: i64 = -4;
+const LABEL_PUB: i64 = -1;
+const LABEL_PRIV: i64 = -2;
+
+const KTY_OKP: i64 = 1;
+const KTY_EC2: i64 = 2;
+const KTY_AKP: i64 = 7;
+
+/// Registered COSE elliptic curves, in the same order as their identifiers starting at 1.
+///
+/// [More Info](https://www.iana.org/assignments/cose/cose.xhtml#elliptic-curves)
+const CURVES: [&str; 8] = [
+  "P-256",
+  "P-384",
+  "P-521",
+  "X25519",
+  "X448",
+  "Ed25519",
+  "Ed448",
+  "secp256k1",
+];
+
+fn curve_id(name: &str) -> Result<i64> {
+  CURVES
+    .iter()
+    .position(|curve| *curve == name)
+    .map(|index| index as i64 + 1)
+    .ok_or(Error::KeyError("unsupported COSE curve"))
+}
+
+fn curve_name(id: i64) -> Result<&'static str> {
+  usize::try_from(id - 1)
+    .ok()
+    .and_then(|index| CURVES.get(index))
+    .copied()
+    .ok_or(Error::KeyError("unsupported COSE curve"))
+}
+
+/// Key type specific parameters of a [`CoseKey`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum CoseKeyParams {
+  /// Octet Key Pair parameters.
+  ///
+  /// [More Info](https://www.rfc-editor.org/rfc/rfc9053#section-7.2)
+  Okp {
+    /// The curve identifier.
+    crv: i64,
+    /// The public key.
+    x: Vec<u8>,
+    /// The private key.
+    d: Option<Vec<u8>>,
+  },
+  /// Double coordinate elliptic curve parameters.
+  ///
+  /// [More Info](https://www.rfc-editor.org/rfc/rfc9053#section-7.1.1)
+  Ec2 {
+    /// The curve identifier.
+    crv: i64,
+    /// The x-coordinate.
+    x: Vec<u8>,
+    /// The y-coordinate.
+    y: Vec<u8>,
+    /// The private key.
+    d: Option<Vec<u8>>,
+  },
+  /// Algorithm Key Pair parameters, used by the ML-DSA algorithm family. The parameter set is identified by the
+  /// `alg` of the key.
+  ///
+  /// [More Info](https://datatracker.ietf.org/doc/html/draft-ietf-cose-dilithium#name-the-ml-dsa-key-type)
+  Akp {
+    /// The public key.
+    public: Vec<u8>,
+    /// The private key.
+    private: Option<Vec<u8>>,
+  },
+}
+
+/// COSE Key.
+///
+/// [More Info](https://www.rfc-editor.org/rfc/rfc9052#section-7)
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CoseKey {
+  kid: Option<Vec<u8>>,
+  alg: Option<CoseAlgorithm>,
+  params: CoseKeyParams,
+}
+
+impl CoseKey {
+  /// Creates a new [`CoseKey`] from the given `params`.
+  pub fn from_params(params: CoseKeyParams) -> Self {
+    Self {
+      kid: None,
+      alg: None,
+      params,
+    }
+  }
+
+  /// Returns the key identifier.
+  pub fn kid(&self) -> Option<&[u8]> {
+    self.kid.as_deref()
+  }
+
+  /// Sets the key identifier.
+  pub fn set_kid(&mut self, value: impl Into<Vec<u8>>) {
+    self.kid = Some(value.into());
+  }
+
+  /// Returns the algorithm the key is intended to be used with.
+  pub fn alg(&self) -> Option<CoseAlgorithm> {
+    self.alg
+  }
+
+  /// Sets the algorithm the key is intended to be used with.
+  pub fn set_alg(&mut self, value: CoseAlgorithm) {
+    self.alg = Some(value);
+  }
+
+  /// Returns the key type specific parameters.
+  pub fn params(&self) -> &CoseKeyParams {
+    &self.params
+  }
+
+  /// Returns `true` if the key does not contain private key material.
+  pub fn is_public(&self) -> bool {
+    match &self.params {
+      CoseKeyParams::Okp { d, .. } | CoseKeyParams::Ec2 { d, .. } => d.is_none(),
+      CoseKeyParams::Akp { private, .. } => private.is_none(),
+    }
+  }
+
+  /// Encodes the key as a CBOR map.
+  pub fn to_vec(&self) -> Result<Vec<u8>> {
+    let bytes = |value: &Vec<u8>| Value::Bytes(value.clone());
+
+    let mut entries: Vec<(Value, Value)> = Vec::new();
+    let kty: i64 = match &self.params {
+      CoseKeyParams::Okp { .. } => KTY_OKP,
+      CoseKeyParams::Ec2 { .. } => KTY_EC2,
+      CoseKeyParams::Akp { .. } => KTY_AKP,
+    };
+    entries.push((cbor::label(LABEL_KTY), cbor::label(kty)));
+    if let Some(kid) = &self.kid {
+      entries.push((cbor::label(LABEL_KID), bytes(kid)));
+    }
+    if let Some(alg) = self.alg {
+      entries.push((cbor::label(LABEL_ALG), cbor::label(alg.id())));
+    }
+
+    match &self.params {
+      CoseKeyParams::Okp { crv, x, d } => {
+        entries.push((cbor::label(LABEL_CRV), cbor::label(*crv)));
+        entries.push((cbor::label(LABEL_X), bytes(x)));
+        if let Some(d) = d {
+          entries.push((cbor::label(LABEL_D), bytes(d)));
+        }
+      }
+      CoseKeyParams::Ec2 { crv, x, y, d } => {
+        entries.push((cbor::label(LABEL_CRV), cbor::label(*crv)));
+        entries.push((cbor::label(LABEL_X), bytes(x)));
+        entries.push((cbor::label(LABEL_Y), bytes(y)));
+        if let Some(d) = d {
+          entries.push((cbor::label(LABEL_D), bytes(d)));
+        }
+      }
+      CoseKeyParams::Akp { public, private } => {
+        entries.push((cbor::label(LABEL_PUB), bytes(public)));
+        if let Some(private) = private {
+          entries.push((cbor::label(LABEL_PRIV), bytes(private)));
+        }
+      }
+    }
+
+    cbor::to_vec(&Value::Map(entries))
+  }
+
+  /// Decodes a key from a CBOR map.
+  pub fn from_slice(bytes: &[u8]) -> Result<Self> {
+    let entries: Vec<(i64, Value)> = cbor::into_labeled_map(cbor::from_slice(bytes)?, "invalid COSE key")?;
+    let get = |label: i64| entries.iter().find(|(key, _)| *key == label).map(|(_, value)| value);
+    let get_int = |label: i64| get(label).map(|value| cbor::as_i64(value).ok_or(Error::KeyError("COSE key")));
+    let get_bytes = |label: i64| {
+      get(label).map(|value| match value {
+        Value::Bytes(bytes) => Ok(bytes.clone()),
+        _ => Err(Error::KeyError("COSE key")),
+      })
+    };
+
+    let kid: Option<Vec<u8>> = get_bytes(LABEL_KID).transpose()?;
+    let alg: Option<CoseAlgorithm> = get_int(LABEL_ALG)
+      .transpose()?
+      .map(|id| CoseAlgorithm::from_id(id).ok_or(Error::InvalidParam("unsupported COSE algorithm")))
+      .transpose()?;
+    let required = |value: Option<Result<Vec<u8>>>| value.unwrap_or(Err(Error::KeyError("COSE key")));
+
+    let params: CoseKeyParams = match get_int(LABEL_KTY).transpose()? {
+      Some(KTY_OKP) => CoseKeyParams::Okp {
+        crv: get_int(LABEL_CRV).unwrap_or(Err(Error::KeyError("COSE key")))?,
+        x: required(get_bytes(LABEL_X))?,
+        d: get_bytes(LABEL_D).transpose()?,
+      },
+      Some(KTY_EC2) => CoseKeyParams::Ec2 {
+        crv: get_int(LABEL_CRV).unwrap_or(Err(Error::KeyError("COSE key")))?,
+        x: required(get_bytes(LABEL_X))?,
+        y: required(get_bytes(LABEL_Y))?,
+        d: get_bytes(LABEL_D).transpose()?,
+      },
+      Some(KTY_AKP) => CoseKeyParams::Akp {
+        public: required(get_bytes(LABEL_PUB))?,
+        private: get_bytes(LABEL_PRIV).transpose()?,
+      },
+      _ => return Err(Error::KeyError("unsupported COSE key type")),
+    };
+
+    Ok(Self { kid, alg, params })
+  }
+}
+
+impl TryFrom<&Jwk> for CoseKey {
+  type Error = Error;
+
+  /// Converts an `OKP`, `EC` or `ML-DSA` [`Jwk`] into a [`CoseKey`], including private key material if present.
+  fn try_from(jwk: &Jwk) -> Result<Self> {
+    let decode_opt = |value: Option<&String>| value.map(decode_b64).transpose();
+
+    let alg: Option<CoseAlgorithm> = jwk
+      .alg()
+      .map(|alg| {
+        alg
+          .parse::<JwsAlgorithm>()
+          .map_err(|_| Error::JwsAlgorithmParsingError)
+          .and_then(CoseAlgorithm::try_from)
+      })
+      .transpose()?;
+
+    let params: CoseKeyParams = match jwk.params() {
+      JwkParams::Okp(JwkParamsOkp { crv, x, d }) => CoseKeyParams::Okp {
+        crv: curve_id(crv)?,
+        x: decode_b64(x)?,
+        d: decode_opt(d.as_ref())?,
+      },
+      JwkParams::Ec(JwkParamsEc { crv, x, y, d }) => CoseKeyParams::Ec2 {
+        crv: curve_id(crv)?,
+        x: decode_b64(x)?,
+        y: decode_b64(y)?,
+        d: decode_opt(d.as_ref())?,
+      },
+      JwkParams::MLDSA(JwkParamsPQ { public, private }) => {
+        if alg.is_none() {
+          return Err(Error::KeyError("ML-DSA keys require an alg"));
+        }
+        CoseKeyParams::Akp {
+          public: decode_b64(public)?,
+          private: decode_opt(private.as_ref())?,
+        }
+      }
+      _ => return Err(Error::KeyError("unsupported COSE key type")),
+    };
+
+    Ok(Self {
+      kid: jwk.kid().map(|kid| kid.as_bytes().to_vec()),
+      alg,
+      params,
+    })
+  }
+}
+
+impl TryFrom<&CoseKey> for Jwk {
+  type Error = Error;
+
+  /// Converts a [`CoseKey`] into a [`Jwk`], including private key material if present.
+  fn try_from(key: &CoseKey) -> Result<Self> {
+    let params: JwkParams = match &key.params {
+      CoseKeyParams::Okp { crv, x, d } => JwkParams::Okp(JwkParamsOkp {
+        crv: curve_name(*crv)?.to_owned(),
+        x: encode_b64(x),
+        d: d.as_ref().map(encode_b64),
+      }),
+      CoseKeyParams::Ec2 { crv, x, y, d } => JwkParams::Ec(JwkParamsEc {
+        crv: curve_name(*crv)?.to_owned(),
+        x: encode_b64(x),
+        y: encode_b64(y),
+        d: d.as_ref().map(encode_b64),
+      }),
+      CoseKeyParams::Akp { public, private } => match key.alg {
+        Some(CoseAlgorithm::ML_DSA_44 | CoseAlgorithm::ML_DSA_65 | CoseAlgorithm::ML_DSA_87) => {
+          JwkParams::MLDSA(JwkParamsPQ {
+            public: encode_b64(public),
+            private: private.as_ref().map(encode_b64),
+          })
+        }
+        _ => return Err(Error::KeyError("unsupported COSE key type")),
+      },
+    };
+
+    let mut jwk: Jwk = Jwk::from_params(params);
+    if let Some(alg) = key.alg {
+      jwk.set_alg(alg.name());
+    }
+    if let Some(kid) = key.kid() {
+      jwk.set_kid(core::str::from_utf8(kid).map_err(Error::InvalidUtf8)?);
+    }
+    Ok(jwk)
+  }
+}
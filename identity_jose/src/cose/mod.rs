@@ -0,0 +1,15 @@
+// Copyright 2020-2024 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+//! CBOR Object Signing and Encryption ([COSE](https://www.rfc-editor.org/rfc/rfc9052))
+
+mod algorithm;
+mod cbor;
+mod header;
+mod key;
+mod sign1;
+
+pub use self::algorithm::*;
+pub use self::header::*;
+pub use self::key::*;
+pub use self::sign1::*;
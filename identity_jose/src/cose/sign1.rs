@@ -0,0 +1,258 @@
+// Copyright 2020-2024 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+use ciborium::Value;
+
+use super::cbor;
+use super::CoseAlgorithm;
+use super::CoseHeader;
+use crate::error::Error;
+use crate::error::Result;
+use crate::jwk::Jwk;
+use crate::jws::JwsVerifier;
+use crate::jws::VerificationInput;
+
+/// The CBOR tag of a COSE_Sign1 message.
+///
+/// [More Info](https://www.rfc-editor.org/rfc/rfc9052#section-2)
+pub const COSE_SIGN1_TAG: u64 = 18;
+
+const SIGNATURE1_CONTEXT: &str = "Signature1";
+
+/// Computes the `Sig_structure` of a COSE_Sign1 message without external additional authenticated data.
+///
+/// [More Info](https://www.rfc-editor.org/rfc/rfc9052#section-4.4)
+fn sig_structure(protected_bytes: &[u8], payload: &[u8]) -> Result<Vec<u8>> {
+  cbor::to_vec(&Value::Array(vec![
+    Value::Text(SIGNATURE1_CONTEXT.to_owned()),
+    Value::Bytes(protected_bytes.to_vec()),
+    Value::Bytes(Vec::new()),
+    Value::Bytes(payload.to_vec()),
+  ]))
+}
+
+/// Encoder for COSE_Sign1 messages with an embedded payload.
+///
+/// Usage: create the encoder, sign the [`signing_input`](Self::signing_input) with the key corresponding to the
+/// `alg` in the protected header and pass the signature to [`into_cose`](Self::into_cose).
+#[derive(Debug, Clone)]
+pub struct CoseSign1Encoder<'payload> {
+  protected_bytes: Vec<u8>,
+  unprotected: CoseHeader,
+  payload: &'payload [u8],
+  signing_input: Box<[u8]>,
+}
+
+impl<'payload> CoseSign1Encoder<'payload> {
+  /// Creates a new encoder for the given `payload` with only a `protected` header.
+  ///
+  /// # Errors
+  /// Fails if the `protected` header does not contain an `alg`.
+  pub fn new(payload: &'payload [u8], protected: &CoseHeader) -> Result<Self> {
+    Self::new_with_unprotected(payload, protected, &CoseHeader::new())
+  }
+
+  /// Creates a new encoder for the given `payload` with a `protected` and an `unprotected` header.
+  ///
+  /// # Errors
+  /// Fails if the `protected` header does not contain an `alg` or if both headers share a parameter.
+  pub fn new_with_unprotected(
+    payload: &'payload [u8],
+    protected: &CoseHeader,
+    unprotected: &CoseHeader,
+  ) -> Result<Self> {
+    if protected.alg().is_none() {
+      return Err(Error::ProtectedHeaderWithoutAlg);
+    }
+    if !protected.is_disjoint(unprotected) {
+      return Err(Error::InvalidContent(
+        "protected and unprotected headers are not disjoint",
+      ));
+    }
+
+    let protected_bytes: Vec<u8> = protected.to_protected_bytes()?;
+    let signing_input: Box<[u8]> = sig_structure(&protected_bytes, payload)?.into_boxed_slice();
+
+    Ok(Self {
+      protected_bytes,
+      unprotected: unprotected.clone(),
+      payload,
+      signing_input,
+    })
+  }
+
+  /// The data to be signed.
+  pub fn signing_input(&self) -> &[u8] {
+    &self.signing_input
+  }
+
+  /// Encodes the tagged COSE_Sign1 message with the given `signature`.
+  pub fn into_cose(self, signature: &[u8]) -> Result<Vec<u8>> {
+    let payload: Value = Value::Bytes(self.payload.to_vec());
+    self.encode(payload, signature)
+  }
+
+  /// Encodes the tagged COSE_Sign1 message with the given `signature` and a detached payload.
+  ///
+  /// [More Info](https://www.rfc-editor.org/rfc/rfc9052#section-2)
+  pub fn into_detached_cose(self, signature: &[u8]) -> Result<Vec<u8>> {
+    self.encode(Value::Null, signature)
+  }
+
+  fn encode(self, payload: Value, signature: &[u8]) -> Result<Vec<u8>> {
+    cbor::to_vec(&Value::Tag(
+      COSE_SIGN1_TAG,
+      Box::new(Value::Array(vec![
+        Value::Bytes(self.protected_bytes),
+        self.unprotected.to_cbor_value(),
+        payload,
+        Value::Bytes(signature.to_vec()),
+      ])),
+    ))
+  }
+}
+
+/// A decoded COSE_Sign1 message whose signature has not been verified yet.
+///
+/// [More Info](https://www.rfc-editor.org/rfc/rfc9052#section-4.2)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CoseSign1 {
+  protected: CoseHeader,
+  protected_bytes: Vec<u8>,
+  unprotected: CoseHeader,
+  payload: Vec<u8>,
+  signature: Vec<u8>,
+}
+
+impl CoseSign1 {
+  /// Decodes a tagged or untagged COSE_Sign1 message.
+  ///
+  /// # Errors
+  /// Fails if `bytes` is not a well-formed COSE_Sign1 message or if the payload is detached.
+  pub fn from_slice(bytes: &[u8]) -> Result<Self> {
+    Self::decode(bytes, None)
+  }
+
+  /// Decodes a tagged or untagged COSE_Sign1 message whose payload is detached, using the given `detached_payload`.
+  ///
+  /// # Errors
+  /// Fails if `bytes` is not a well-formed COSE_Sign1 message or if the payload is not detached.
+  pub fn from_slice_detached(bytes: &[u8], detached_payload: &[u8]) -> Result<Self> {
+    Self::decode(bytes, Some(detached_payload))
+  }
+
+  fn decode(bytes: &[u8], detached_payload: Option<&[u8]>) -> Result<Self> {
+    let value: Value = match cbor::from_slice(bytes)? {
+      Value::Tag(COSE_SIGN1_TAG, value) => *value,
+      Value::Tag(..) => return Err(Error::InvalidContent("unexpected CBOR tag")),
+      value => value,
+    };
+
+    let Value::Array(items) = value else {
+      return Err(Error::InvalidContent("expected a COSE_Sign1 array"));
+    };
+    let [protected, unprotected, payload, signature]: [Value; 4] = items
+      .try_into()
+      .map_err(|_| Error::InvalidContent("expected a COSE_Sign1 array of four items"))?;
+
+    let (Value::Bytes(protected_bytes), Value::Bytes(signature)) = (protected, signature) else {
+      return Err(Error::InvalidContent("invalid COSE_Sign1 structure"));
+    };
+    let payload: Vec<u8> = match (payload, detached_payload) {
+      (Value::Bytes(payload), None) => payload,
+      (Value::Null, Some(detached_payload)) => detached_payload.to_vec(),
+      (Value::Null, None) => return Err(Error::InvalidContent("unexpected detached payload")),
+      (Value::Bytes(_), Some(_)) => return Err(Error::InvalidContent("expected a detached payload")),
+      _ => return Err(Error::InvalidContent("invalid COSE_Sign1 payload")),
+    };
+
+    let protected: CoseHeader = CoseHeader::from_protected_bytes(&protected_bytes)?;
+    let unprotected: CoseHeader = CoseHeader::from_cbor_value(unprotected)?;
+    if !protected.is_disjoint(&unprotected) {
+      return Err(Error::InvalidContent(
+        "protected and unprotected headers are not disjoint",
+      ));
+    }
+
+    Ok(Self {
+      protected,
+      protected_bytes,
+      unprotected,
+      payload,
+      signature,
+    })
+  }
+
+  /// Returns the protected header.
+  pub fn protected_header(&self) -> &CoseHeader {
+    &self.protected
+  }
+
+  /// Returns the unprotected header.
+  pub fn unprotected_header(&self) -> &CoseHeader {
+    &self.unprotected
+  }
+
+  /// Returns the `alg` of the protected header.
+  pub fn alg(&self) -> Option<CoseAlgorithm> {
+    self.protected.alg()
+  }
+
+  /// Returns the `kid`, preferring the protected over the unprotected header.
+  pub fn kid(&self) -> Option<&[u8]> {
+    self.protected.kid().or_else(|| self.unprotected.kid())
+  }
+
+  /// Returns the payload.
+  pub fn payload(&self) -> &[u8] {
+    &self.payload
+  }
+
+  /// Returns the signature.
+  pub fn signature(&self) -> &[u8] {
+    &self.signature
+  }
+
+  /// Verifies the signature using the given `verifier` and `public_key`.
+  ///
+  /// The `alg` of the protected header is mapped to the corresponding [`JwsAlgorithm`](crate::jws::JwsAlgorithm),
+  /// so that any [`JwsVerifier`] implementation can be used.
+  ///
+  /// # Errors
+  /// Fails if the protected header does not contain an `alg`, if the `alg` of `public_key` is set but does not match
+  /// or if the signature is invalid.
+  pub fn verify<T>(self, verifier: &T, public_key: &Jwk) -> Result<DecodedCoseSign1>
+  where
+    T: JwsVerifier,
+  {
+    let alg: CoseAlgorithm = self.protected.alg().ok_or(Error::ProtectedHeaderWithoutAlg)?;
+    public_key.check_alg(alg.name())?;
+
+    let input = VerificationInput {
+      alg: alg.to_jws_algorithm(),
+      signing_input: sig_structure(&self.protected_bytes, &self.payload)?.into_boxed_slice(),
+      decoded_signature: self.signature.into_boxed_slice(),
+    };
+    verifier
+      .verify(input, public_key)
+      .map_err(Error::SignatureVerificationError)?;
+
+    Ok(DecodedCoseSign1 {
+      protected: self.protected,
+      unprotected: self.unprotected,
+      payload: self.payload,
+    })
+  }
+}
+
+/// A cryptographically verified COSE_Sign1 message.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct DecodedCoseSign1 {
+  /// The protected header.
+  pub protected: CoseHeader,
+  /// The unprotected header.
+  pub unprotected: CoseHeader,
+  /// The verified payload.
+  pub payload: Vec<u8>,
+}
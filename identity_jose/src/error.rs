@@ -49,4 +49,7 @@ pub enum Error {
   /// Caused by a missing `alg` claim in the protected header.
   #[error("missing alg in protected header")]
   ProtectedHeaderWithoutAlg,
+  /// Caused by invalid CBOR serialization or deserialization.
+  #[error("invalid cbor")]
+  InvalidCbor(#[source] Box<dyn std::error::Error + Send + Sync + 'static>),
 }
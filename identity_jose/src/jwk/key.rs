@@ -341,22 +341,26 @@ impl Jwk {
     }
   }
 
-  /// Returns the [`JwkParamsPQ`] in this JWK if it is of type `ML-DSA` or `SLH-DSA`.
+  /// Returns the [`JwkParamsPQ`] in this JWK if it is of a post-quantum key type.
   pub fn try_pq_params(&self) -> Result<&JwkParamsPQ> {
     match self.params() {
       JwkParams::MLDSA(params) => Ok(params),
       JwkParams::SLHDSA(params) => Ok(params),
       JwkParams::FALCON(params) => Ok(params),
+      #[cfg(feature = "kyber")]
+      JwkParams::KYBER(params) => Ok(params),
       _ => Err(Error::KeyError("PQ")),
     }
   }
 
-  /// Returns a mutable reference to the [`JwkParamsPQ`] in this JWK if it is of type `ML-DSA` or `SLH-DSA`.
+  /// Returns a mutable reference to the [`JwkParamsPQ`] in this JWK if it is of a post-quantum key type.
   pub fn try_pq_params_mut(&mut self) -> Result<&mut JwkParamsPQ> {
     match self.params_mut() {
       JwkParams::MLDSA(params) => Ok(params),
       JwkParams::SLHDSA(params) => Ok(params),
       JwkParams::FALCON(params) => Ok(params),
+      #[cfg(feature = "kyber")]
+      JwkParams::KYBER(params) => Ok(params),
       _ => Err(Error::KeyError("PQ")),
     }
   }
@@ -419,6 +423,10 @@ impl Jwk {
       JwkParams::FALCON(JwkParamsPQ { public, .. }) => {
         format!(r#"{{"kty":"{kty}","pub":"{public}"}}"#)
       }
+      #[cfg(feature = "kyber")]
+      JwkParams::KYBER(JwkParamsPQ { public, .. }) => {
+        format!(r#"{{"kty":"{kty}","pub":"{public}"}}"#)
+      }
     }
   }
 
@@ -474,6 +482,8 @@ impl Jwk {
       JwkParams::MLDSA(params) => params.is_private(), //TODO: PQ - is_private Jwk method
       JwkParams::SLHDSA(params) => params.is_private(),
       JwkParams::FALCON(params) => params.is_private(),
+      #[cfg(feature = "kyber")]
+      JwkParams::KYBER(params) => params.is_private(),
     }
   }
 
@@ -36,6 +36,9 @@ pub enum JwkParams {
   SLHDSA(JwkParamsPQ),
   /// FALCON parameters
   FALCON(JwkParamsPQ),
+  /// Kyber (round 3) parameters
+  #[cfg(feature = "kyber")]
+  KYBER(JwkParamsPQ),
 }
 
 impl JwkParams {
@@ -49,6 +52,8 @@ impl JwkParams {
       JwkType::MLDSA => Self::MLDSA(JwkParamsPQ::new()),
       JwkType::SLHDSA => Self::SLHDSA(JwkParamsPQ::new()),
       JwkType::FALCON => Self::FALCON(JwkParamsPQ::new()),
+      #[cfg(feature = "kyber")]
+      JwkType::KYBER => Self::KYBER(JwkParamsPQ::new()),
     }
   }
 
@@ -62,6 +67,8 @@ impl JwkParams {
       Self::MLDSA(_) => JwkType::MLDSA,
       Self::SLHDSA(_) => JwkType::SLHDSA,
       Self::FALCON(_) => JwkType::FALCON,
+      #[cfg(feature = "kyber")]
+      Self::KYBER(_) => JwkType::KYBER,
     }
   }
 
@@ -77,6 +84,8 @@ impl JwkParams {
       Self::MLDSA(inner) => Some(Self::MLDSA(inner.to_public())),
       Self::SLHDSA(inner) => Some(Self::SLHDSA(inner.to_public())),
       Self::FALCON(inner) => Some(Self::FALCON(inner.to_public())),
+      #[cfg(feature = "kyber")]
+      Self::KYBER(inner) => Some(Self::KYBER(inner.to_public())),
     }
   }
 
@@ -90,6 +99,8 @@ impl JwkParams {
       Self::MLDSA(value) => value.is_public(),
       Self::SLHDSA(value) => value.is_public(),
       Self::FALCON(value) => value.is_public(),
+      #[cfg(feature = "kyber")]
+      Self::KYBER(value) => value.is_public(),
     }
   }
 }
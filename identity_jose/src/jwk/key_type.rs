@@ -35,6 +35,10 @@ pub enum JwkType {
 
   ///Falcon
   FALCON,
+  /// JSON Web Key Type for the round 3 Kyber key encapsulation mechanism, which is not interoperable with ML-KEM
+  /// (FIPS 203).
+  #[cfg(feature = "kyber")]
+  KYBER,
 }
 
 impl JwkType {
@@ -48,6 +52,8 @@ impl JwkType {
       Self::MLDSA => "ML-DSA",
       Self::SLHDSA => "SLH-DSA",
       Self::FALCON => "FALCON",
+      #[cfg(feature = "kyber")]
+      Self::KYBER => "KYBER",
     }
   }
 }
@@ -56,6 +56,12 @@ impl JwsVerifier for Box<dyn JwsVerifier> {
   }
 }
 
+impl<T: JwsVerifier + ?Sized> JwsVerifier for &T {
+  fn verify(&self, input: VerificationInput, public_key: &Jwk) -> Result<(), SignatureVerificationError> {
+    (**self).verify(input, public_key)
+  }
+}
+
 // =================================================================================================================
 // Implementation
 // ================================================================================================================
@@ -15,6 +15,8 @@
   clippy::missing_safety_doc
 )]
 
+#[cfg(feature = "cose")]
+pub mod cose;
 pub mod error;
 pub mod jose;
 pub mod jwk;
@@ -0,0 +1,146 @@
+// Copyright 2020-2024 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+use crypto::signatures::ed25519::SecretKey;
+
+use crate::cose::CoseAlgorithm;
+use crate::cose::CoseHeader;
+use crate::cose::CoseKey;
+use crate::cose::CoseSign1;
+use crate::cose::CoseSign1Encoder;
+use crate::cose::DecodedCoseSign1;
+use crate::error::Error;
+use crate::jwk::Jwk;
+use crate::jwk::JwkParams;
+use crate::jwk::JwkParamsEc;
+use crate::jwk::JwkParamsOkp;
+use crate::jwk::JwkParamsPQ;
+use crate::jwk::JwkType;
+use crate::jws::JwsAlgorithm;
+use crate::jws::JwsVerifierFn;
+use crate::jws::VerificationInput;
+use crate::tests::ed25519;
+
+fn public_key_jwk(secret_key: &SecretKey) -> Jwk {
+  let mut jwk = Jwk::new(JwkType::Okp);
+  jwk.set_alg(JwsAlgorithm::EdDSA.name());
+  jwk
+    .set_params(JwkParamsOkp {
+      crv: "Ed25519".into(),
+      x: crate::jwu::encode_b64(secret_key.public_key().as_slice()),
+      d: None,
+    })
+    .unwrap();
+  jwk
+}
+
+fn sign(payload: &[u8], protected: &CoseHeader, unprotected: &CoseHeader, secret_key: &SecretKey) -> Vec<u8> {
+  let encoder: CoseSign1Encoder<'_> = CoseSign1Encoder::new_with_unprotected(payload, protected, unprotected).unwrap();
+  let signature = secret_key.sign(encoder.signing_input()).to_bytes();
+  encoder.into_cose(&signature).unwrap()
+}
+
+#[test]
+fn test_cose_sign1_roundtrip() {
+  let secret_key = SecretKey::generate().unwrap();
+  let kid: &[u8] = b"did:iota:0x123#signing-key";
+
+  let mut protected = CoseHeader::new();
+  protected.set_alg(CoseAlgorithm::EdDSA);
+  protected.set_typ("application/vc+cose");
+  let mut unprotected = CoseHeader::new();
+  unprotected.set_kid(kid);
+
+  let payload: &[u8] = br#"{"hello":"world"}"#;
+  let cose: Vec<u8> = sign(payload, &protected, &unprotected, &secret_key);
+
+  let decoded: CoseSign1 = CoseSign1::from_slice(&cose).unwrap();
+  assert_eq!(decoded.protected_header(), &protected);
+  assert_eq!(decoded.kid(), Some(kid));
+  assert_eq!(decoded.payload(), payload);
+
+  let verifier = JwsVerifierFn::from(|input: VerificationInput, key: &Jwk| {
+    if input.alg != JwsAlgorithm::EdDSA {
+      panic!("invalid algorithm");
+    }
+    ed25519::verify(input, key)
+  });
+  let verified: DecodedCoseSign1 = decoded.clone().verify(&verifier, &public_key_jwk(&secret_key)).unwrap();
+  assert_eq!(verified.payload, payload);
+
+  let other_key = SecretKey::generate().unwrap();
+  assert!(matches!(
+    decoded.verify(&verifier, &public_key_jwk(&other_key)).unwrap_err(),
+    Error::SignatureVerificationError(_)
+  ));
+}
+
+#[test]
+fn test_cose_sign1_detached_payload() {
+  let secret_key = SecretKey::generate().unwrap();
+  let mut protected = CoseHeader::new();
+  protected.set_alg(CoseAlgorithm::EdDSA);
+
+  let payload: &[u8] = b"detached";
+  let encoder: CoseSign1Encoder<'_> = CoseSign1Encoder::new(payload, &protected).unwrap();
+  let signature = secret_key.sign(encoder.signing_input()).to_bytes();
+  let cose: Vec<u8> = encoder.into_detached_cose(&signature).unwrap();
+
+  assert!(matches!(
+    CoseSign1::from_slice(&cose).unwrap_err(),
+    Error::InvalidContent(_)
+  ));
+  let verifier = JwsVerifierFn::from(ed25519::verify);
+  let public_key: Jwk = public_key_jwk(&secret_key);
+  let decoded: CoseSign1 = CoseSign1::from_slice_detached(&cose, payload).unwrap();
+  assert_eq!(decoded.verify(&verifier, &public_key).unwrap().payload, payload);
+  assert!(CoseSign1::from_slice_detached(&cose, b"other")
+    .unwrap()
+    .verify(&verifier, &public_key)
+    .is_err());
+}
+
+#[test]
+fn test_cose_sign1_invalid_headers() {
+  let mut unprotected = CoseHeader::new();
+  unprotected.set_alg(CoseAlgorithm::EdDSA);
+  assert!(matches!(
+    CoseSign1Encoder::new(b"payload", &CoseHeader::new()).unwrap_err(),
+    Error::ProtectedHeaderWithoutAlg
+  ));
+  assert!(matches!(
+    CoseSign1Encoder::new_with_unprotected(b"payload", &unprotected, &unprotected).unwrap_err(),
+    Error::InvalidContent(_)
+  ));
+}
+
+#[test]
+fn test_cose_key_from_jwk() {
+  let secret_key = SecretKey::generate().unwrap();
+  let okp: Jwk = public_key_jwk(&secret_key);
+  let cose_key: CoseKey = CoseKey::try_from(&okp).unwrap();
+  assert_eq!(cose_key.alg(), Some(CoseAlgorithm::EdDSA));
+  assert_eq!(CoseKey::from_slice(&cose_key.to_vec().unwrap()).unwrap(), cose_key);
+  assert_eq!(Jwk::try_from(&cose_key).unwrap(), okp);
+
+  let mut ec = Jwk::new(JwkType::Ec);
+  ec.set_params(JwkParamsEc {
+    crv: "P-256".into(),
+    x: "gI0GAILBdu7T53akrFmMyGcsF3n5dO7MmwNBHKW5SV0".into(),
+    y: "SLW_xSffzlPWrHEVI30DHM_4egVwt3NQqeUD7nMFpps".into(),
+    d: None,
+  })
+  .unwrap();
+  let cose_key: CoseKey = CoseKey::try_from(&ec).unwrap();
+  assert_eq!(Jwk::try_from(&cose_key).unwrap(), ec);
+
+  let mut ml_dsa = Jwk::from_params(JwkParams::MLDSA(JwkParamsPQ {
+    public: crate::jwu::encode_b64([7u8; 1312]),
+    private: None,
+  }));
+  ml_dsa.set_alg(JwsAlgorithm::ML_DSA_44.name());
+  let cose_key: CoseKey = CoseKey::try_from(&ml_dsa).unwrap();
+  assert_eq!(cose_key.alg(), Some(CoseAlgorithm::ML_DSA_44));
+  assert_eq!(CoseKey::from_slice(&cose_key.to_vec().unwrap()).unwrap(), cose_key);
+  assert_eq!(Jwk::try_from(&cose_key).unwrap(), ml_dsa);
+}
@@ -1,6 +1,8 @@
 // Copyright 2020-2023 IOTA Stiftung
 // SPDX-License-Identifier: Apache-2.0
 
+#[cfg(feature = "cose")]
+mod cose;
 mod ed25519;
 mod es256;
 mod hs256;
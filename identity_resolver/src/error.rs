@@ -73,5 +73,24 @@ pub enum ErrorCause {
   UnsupportedNetwork(String),
   /// Resolved DID is different from the DID value inside the DID Document  
   #[error("resolved DID different from the DID Document id")]
-  DidNotMatching
+  DidNotMatching,
+  /// Caused by attempting to resolve a specific version of a DID document through a handler that does not support
+  /// versioning.
+  #[error("did resolution failed: the DID method \"{method}\" does not support resolving document versions")]
+  UnsupportedVersioning {
+    /// The method whose handler does not support versioning.
+    method: String,
+  },
+  /// Caused by malformed `versionId` or `versionTime` DID URL parameters.
+  #[error("invalid version query: {0}")]
+  InvalidVersionQuery(String),
+  /// The requested version of a DID document does not exist.
+  #[error("no DID document version matching {0}")]
+  VersionNotFound(String),
+  /// Caused by adding a document version that does not follow the latest one.
+  #[error("invalid DID document version: {0}")]
+  InvalidDocumentVersion(String),
+  /// The key of a short form `did:compositejwk` is not known to the attached store.
+  #[error("no composite key for {0}")]
+  CompositeJwkNotFound(String),
 }
@@ -9,6 +9,8 @@ use crate::ErrorCause;
 use crate::Result;
 use std::pin::Pin;
 
+use super::VersionQuery;
+
 /// Internal trait used by the resolver to apply the command pattern.
 ///
 /// The resolver is generic over the type of command which enables  
@@ -16,7 +18,11 @@ use std::pin::Pin;
 pub trait Command<'a, T>: std::fmt::Debug + private::Sealed {
   type Output: Future<Output = T> + 'a;
 
-  fn apply(&self, input: &'a str) -> Self::Output;
+  /// Resolves the DID `input`, selecting the document version matching `version` if given.
+  fn apply(&self, input: &'a str, version: Option<VersionQuery>) -> Self::Output;
+
+  /// Whether the handler can resolve specific document versions.
+  fn supports_versions(&self) -> bool;
 }
 
 mod private {
@@ -40,18 +46,26 @@ impl<DOC: 'static> std::fmt::Debug for SingleThreadedCommand<DOC> {
 }
 
 /// Internal representation of a thread safe handler.
-type SendSyncCallback<DOC> =
-  Box<dyn for<'r> Fn(&'r str) -> Pin<Box<dyn Future<Output = Result<DOC>> + 'r + Send>> + Send + Sync>;
+type SendSyncCallback<DOC> = Box<
+  dyn for<'r> Fn(&'r str, Option<VersionQuery>) -> Pin<Box<dyn Future<Output = Result<DOC>> + 'r + Send>>
+    + Send
+    + Sync,
+>;
 
 /// Wrapper around a thread safe callback.
 pub struct SendSyncCommand<DOC: 'static> {
   fun: SendSyncCallback<DOC>,
+  versioned: bool,
 }
 
 impl<'a, DOC: 'static> Command<'a, Result<DOC>> for SendSyncCommand<DOC> {
   type Output = Pin<Box<dyn Future<Output = Result<DOC>> + 'a + Send>>;
-  fn apply(&self, input: &'a str) -> Self::Output {
-    (self.fun)(input)
+  fn apply(&self, input: &'a str, version: Option<VersionQuery>) -> Self::Output {
+    (self.fun)(input, version)
+  }
+
+  fn supports_versions(&self) -> bool {
+    self.versioned
   }
 }
 
@@ -71,7 +85,23 @@ impl<DOC: 'static> SendSyncCommand<DOC> {
     E: Into<Box<dyn std::error::Error + Send + Sync + 'static>>,
     DIDERR: Into<Box<dyn std::error::Error + Send + Sync + 'static>>,
   {
-    let fun: SendSyncCallback<DOC> = Box::new(move |input: &str| {
+    Self {
+      versioned: false,
+      ..Self::new_versioned(move |did: D, _: Option<VersionQuery>| handler(did))
+    }
+  }
+
+  /// Converts a handler that additionally receives the requested document version to a command.
+  pub(super) fn new_versioned<D, F, Fut, DOCUMENT, E, DIDERR>(handler: F) -> Self
+  where
+    D: DID + Send + for<'r> TryFrom<&'r str, Error = DIDERR> + 'static,
+    DOCUMENT: 'static + Into<DOC>,
+    F: Fn(D, Option<VersionQuery>) -> Fut + 'static + Clone + Send + Sync,
+    Fut: Future<Output = std::result::Result<DOCUMENT, E>> + Send,
+    E: Into<Box<dyn std::error::Error + Send + Sync + 'static>>,
+    DIDERR: Into<Box<dyn std::error::Error + Send + Sync + 'static>>,
+  {
+    let fun: SendSyncCallback<DOC> = Box::new(move |input: &str, version: Option<VersionQuery>| {
       let handler_clone: F = handler.clone();
       let did_parse_attempt = D::try_from(input)
         .map_err(|error| ErrorCause::DIDParsingError { source: error.into() })
@@ -79,7 +109,7 @@ impl<DOC: 'static> SendSyncCommand<DOC> {
 
       Box::pin(async move {
         let did: D = did_parse_attempt?;
-        handler_clone(did)
+        handler_clone(did, version)
           .await
           .map(Into::into)
           .map_err(|error| ErrorCause::HandlerError { source: error.into() })
@@ -87,7 +117,7 @@ impl<DOC: 'static> SendSyncCommand<DOC> {
       })
     });
 
-    Self { fun }
+    Self { fun, versioned: true }
   }
 }
 
@@ -97,16 +127,21 @@ impl<DOC: 'static> SendSyncCommand<DOC> {
 
 /// Internal representation of a single threaded handler.
 pub(super) type SingleThreadedCallback<DOC> =
-  Box<dyn for<'r> Fn(&'r str) -> Pin<Box<dyn Future<Output = Result<DOC>> + 'r>>>;
+  Box<dyn for<'r> Fn(&'r str, Option<VersionQuery>) -> Pin<Box<dyn Future<Output = Result<DOC>> + 'r>>>;
 
 /// Wrapper around a single threaded callback.
 pub struct SingleThreadedCommand<DOC> {
   fun: SingleThreadedCallback<DOC>,
+  versioned: bool,
 }
 impl<'a, DOC: 'static> Command<'a, Result<DOC>> for SingleThreadedCommand<DOC> {
   type Output = Pin<Box<dyn Future<Output = Result<DOC>> + 'a>>;
-  fn apply(&self, input: &'a str) -> Self::Output {
-    (self.fun)(input)
+  fn apply(&self, input: &'a str, version: Option<VersionQuery>) -> Self::Output {
+    (self.fun)(input, version)
+  }
+
+  fn supports_versions(&self) -> bool {
+    self.versioned
   }
 }
 
@@ -121,7 +156,24 @@ impl<DOC: 'static> SingleThreadedCommand<DOC> {
     E: Into<Box<dyn std::error::Error + Send + Sync + 'static>>,
     DIDERR: Into<Box<dyn std::error::Error + Send + Sync + 'static>>,
   {
-    let fun: SingleThreadedCallback<DOC> = Box::new(move |input: &str| {
+    Self {
+      versioned: false,
+      ..Self::new_versioned(move |did: D, _: Option<VersionQuery>| handler(did))
+    }
+  }
+
+  /// Equivalent to [`SendSyncCommand::new_versioned`](SendSyncCommand::new_versioned()), but with less `Send` +
+  /// `Sync` bounds.
+  pub(super) fn new_versioned<D, F, Fut, DOCUMENT, E, DIDERR>(handler: F) -> Self
+  where
+    D: DID + for<'r> TryFrom<&'r str, Error = DIDERR> + 'static,
+    DOCUMENT: 'static + Into<DOC>,
+    F: Fn(D, Option<VersionQuery>) -> Fut + 'static + Clone,
+    Fut: Future<Output = std::result::Result<DOCUMENT, E>>,
+    E: Into<Box<dyn std::error::Error + Send + Sync + 'static>>,
+    DIDERR: Into<Box<dyn std::error::Error + Send + Sync + 'static>>,
+  {
+    let fun: SingleThreadedCallback<DOC> = Box::new(move |input: &str, version: Option<VersionQuery>| {
       let handler_clone: F = handler.clone();
       let did_parse_attempt = D::try_from(input)
         .map_err(|error| ErrorCause::DIDParsingError { source: error.into() })
@@ -129,7 +181,7 @@ impl<DOC: 'static> SingleThreadedCommand<DOC> {
 
       Box::pin(async move {
         let did: D = did_parse_attempt?;
-        handler_clone(did)
+        handler_clone(did, version)
           .await
           .map(Into::into)
           .map_err(|error| ErrorCause::HandlerError { source: error.into() })
@@ -137,6 +189,6 @@ impl<DOC: 'static> SingleThreadedCommand<DOC> {
       })
     });
 
-    Self { fun }
+    Self { fun, versioned: true }
   }
 }
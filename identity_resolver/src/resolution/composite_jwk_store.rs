@@ -0,0 +1,85 @@
+// Copyright 2020-2024 IOTA Stiftung, Fondazione Links
+// SPDX-License-Identifier: Apache-2.0
+
+use std::collections::HashMap;
+use std::sync::RwLock;
+
+use async_trait::async_trait;
+use identity_did::DIDCompositeJwk;
+use identity_document::document::CoreDocument;
+use identity_verification::jwk::CompositeJwk;
+
+use crate::Error;
+use crate::ErrorCause;
+
+/// Storage of the keys of short form `did:compositejwk` DIDs, which only encode the hash of their key.
+///
+/// See [`Resolver::attach_did_compositejwk_handler_with_store`](crate::Resolver::attach_did_compositejwk_handler_with_store).
+#[async_trait]
+pub trait CompositeJwkStore: Send + Sync {
+  /// Returns the key of the short form `did`, or `None` if it is unknown.
+  ///
+  /// The returned key is checked against the hash encoded in `did` before it is used.
+  async fn composite_jwk(
+    &self,
+    did: &DIDCompositeJwk,
+  ) -> std::result::Result<Option<CompositeJwk>, Box<dyn std::error::Error + Send + Sync + 'static>>;
+}
+
+/// An in-memory [`CompositeJwkStore`].
+#[derive(Debug, Default)]
+pub struct CompositeJwkMemStore {
+  keys: RwLock<HashMap<DIDCompositeJwk, CompositeJwk>>,
+}
+
+impl CompositeJwkMemStore {
+  /// Creates an empty store.
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  /// Adds `composite_jwk` to the store and returns the short form DID identifying it.
+  ///
+  /// # Errors
+  ///
+  /// Fails if `composite_jwk` cannot be encoded as a `did:compositejwk`, see [`DIDCompositeJwk::new`].
+  pub fn insert(&self, composite_jwk: CompositeJwk) -> std::result::Result<DIDCompositeJwk, identity_did::Error> {
+    let did: DIDCompositeJwk = DIDCompositeJwk::new_short_form(&composite_jwk)?;
+    self
+      .keys
+      .write()
+      .expect("composite JWK store lock poisoned")
+      .insert(did.clone(), composite_jwk);
+    Ok(did)
+  }
+}
+
+#[async_trait]
+impl CompositeJwkStore for CompositeJwkMemStore {
+  async fn composite_jwk(
+    &self,
+    did: &DIDCompositeJwk,
+  ) -> std::result::Result<Option<CompositeJwk>, Box<dyn std::error::Error + Send + Sync + 'static>> {
+    let keys = self.keys.read().expect("composite JWK store lock poisoned");
+    Ok(keys.get(did).cloned())
+  }
+}
+
+/// Expands `did`, obtaining the key of short form DIDs from `store`.
+pub(super) async fn resolve_did_compositejwk<S>(
+  store: &S,
+  did: DIDCompositeJwk,
+) -> std::result::Result<CoreDocument, Box<dyn std::error::Error + Send + Sync + 'static>>
+where
+  S: CompositeJwkStore + ?Sized,
+{
+  if !did.is_short_form() {
+    return Ok(CoreDocument::expand_did_compositejwk(did)?);
+  }
+
+  let composite_jwk: CompositeJwk = store
+    .composite_jwk(&did)
+    .await?
+    .ok_or_else(|| Error::new(ErrorCause::CompositeJwkNotFound(did.to_string())))?;
+  Ok(CoreDocument::expand_did_compositejwk_with_key(did, composite_jwk)?)
+}
@@ -2,13 +2,30 @@
 // SPDX-License-Identifier: Apache-2.0
 
 mod commands;
+#[cfg(feature = "composite-jwk-store")]
+mod composite_jwk_store;
 mod resolver;
 #[cfg(test)]
 mod tests;
+mod version;
+mod web;
 
 use self::commands::SingleThreadedCommand;
 use identity_document::document::CoreDocument;
 
+#[cfg(feature = "composite-jwk-store")]
+pub use composite_jwk_store::CompositeJwkMemStore;
+#[cfg(feature = "composite-jwk-store")]
+pub use composite_jwk_store::CompositeJwkStore;
 pub use resolver::Resolver;
+pub use version::DocumentVersion;
+pub use version::DocumentVersionMemStore;
+pub use version::DocumentVersionStore;
+pub use version::VersionQuery;
+pub use version::VERSION_ID_PARAM;
+pub use version::VERSION_TIME_PARAM;
+pub use web::WebRedirectPolicy;
+pub use web::WebResolutionError;
+pub use web::WebResolutionOptions;
 /// Alias for a [`Resolver`] that is not [`Send`] + [`Sync`].
 pub type SingleThreadedResolver<DOC = CoreDocument> = Resolver<DOC, SingleThreadedCommand<DOC>>;
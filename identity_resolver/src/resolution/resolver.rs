@@ -4,10 +4,16 @@
 use core::future::Future;
 use futures::stream::FuturesUnordered;
 use futures::TryStreamExt;
+use identity_core::common::Timestamp;
+use identity_did::CoreDID;
 use identity_did::DIDCompositeJwk;
 use identity_did::DIDJwk;
+use identity_did::DIDKey;
+use identity_did::DIDPeer;
+use identity_did::DIDUrl;
 use identity_did::DID;
 use std::collections::HashSet;
+use std::sync::Arc;
 
 use identity_document::document::CoreDocument;
 use std::collections::HashMap;
@@ -20,6 +26,13 @@ use crate::Result;
 use super::commands::Command;
 use super::commands::SendSyncCommand;
 use super::commands::SingleThreadedCommand;
+#[cfg(feature = "composite-jwk-store")]
+use super::composite_jwk_store::resolve_did_compositejwk;
+use super::version::resolve_from_store;
+#[cfg(feature = "composite-jwk-store")]
+use super::CompositeJwkStore;
+use super::DocumentVersionStore;
+use super::VersionQuery;
 
 /// Convenience type for resolving DID documents from different DID methods.   
 ///
@@ -92,16 +105,57 @@ where
   /// }
   /// ```
   pub async fn resolve<D: DID>(&self, did: &D) -> Result<DOC> {
+    self.delegate(did.method())?.apply(did.as_str(), None).await
+  }
+
+  /// Fetches the version of the DID Document of the given DID selected by `version`.
+  ///
+  /// # Errors
+  ///
+  /// Errors if the resolver has not been configured to handle the method corresponding to the given DID, if the
+  /// handler of that method does not support versioning or the resolution process itself fails.
+  pub async fn resolve_version<D: DID>(&self, did: &D, version: &VersionQuery) -> Result<DOC> {
     let method: &str = did.method();
-    let delegate: &M = self
+    let delegate: &M = self.delegate(method)?;
+    if !delegate.supports_versions() {
+      return Err(Error::new(ErrorCause::UnsupportedVersioning {
+        method: method.to_owned(),
+      }));
+    }
+
+    delegate.apply(did.as_str(), Some(version.clone())).await
+  }
+
+  /// Fetches the version of the DID Document of the given DID that was valid at `time`.
+  ///
+  /// Equivalent to [`Self::resolve_version`](Self::resolve_version()) with [`VersionQuery::Time`].
+  pub async fn resolve_at<D: DID>(&self, did: &D, time: Timestamp) -> Result<DOC> {
+    self.resolve_version(did, &VersionQuery::Time(time)).await
+  }
+
+  /// Fetches the DID Document of the DID of `did_url`, honouring its `versionId` or `versionTime` parameter.
+  ///
+  /// Without either parameter the latest version is fetched, as with [`Self::resolve`](Self::resolve()).
+  ///
+  /// # Errors
+  ///
+  /// Errors if the version parameters are malformed or under the same conditions as
+  /// [`Self::resolve_version`](Self::resolve_version()).
+  pub async fn resolve_did_url(&self, did_url: &DIDUrl) -> Result<DOC> {
+    match VersionQuery::from_did_url(did_url)? {
+      Some(version) => self.resolve_version(did_url.did(), &version).await,
+      None => self.resolve(did_url.did()).await,
+    }
+  }
+
+  fn delegate(&self, method: &str) -> Result<&M> {
+    self
       .command_map
       .get(method)
       .ok_or_else(|| ErrorCause::UnsupportedMethodError {
         method: method.to_owned(),
       })
-      .map_err(Error::new)?;
-
-    delegate.apply(did.as_str()).await
+      .map_err(Error::new)
   }
 
   /// Concurrently fetches the DID Documents of the multiple given DIDs.
@@ -188,6 +242,42 @@ impl<DOC: 'static> Resolver<DOC, SendSyncCommand<DOC>> {
     let command = SendSyncCommand::new(handler);
     self.command_map.insert(method, command);
   }
+
+  /// Attach a new handler responsible for resolving DIDs of the given DID method, including specific versions of
+  /// their documents.
+  ///
+  /// Equivalent to [`Self::attach_handler`](Self::attach_handler()), except that the `handler` additionally receives
+  /// the [`VersionQuery`] passed to [`Self::resolve_version`](Self::resolve_version()), or `None` when the latest
+  /// version is requested.
+  pub fn attach_versioned_handler<D, F, Fut, DOCUMENT, E, DIDERR>(&mut self, method: String, handler: F)
+  where
+    D: DID + Send + for<'r> TryFrom<&'r str, Error = DIDERR> + 'static,
+    DOCUMENT: 'static + Into<DOC>,
+    F: Fn(D, Option<VersionQuery>) -> Fut + 'static + Clone + Send + Sync,
+    Fut: Future<Output = std::result::Result<DOCUMENT, E>> + Send,
+    E: Into<Box<dyn std::error::Error + Send + Sync + 'static>>,
+    DIDERR: Into<Box<dyn std::error::Error + Send + Sync + 'static>>,
+  {
+    let command = SendSyncCommand::new_versioned(handler);
+    self.command_map.insert(method, command);
+  }
+
+  /// Attaches a handler resolving DIDs of the given DID method, and their document versions, from `store`.
+  ///
+  /// Intended for methods that do not record the history of their documents, such as `did:web` and `did:jwk`.
+  pub fn attach_versioned_store<S, DOCUMENT>(&mut self, method: String, store: S)
+  where
+    S: DocumentVersionStore<DOCUMENT> + 'static,
+    DOCUMENT: 'static + Send + Into<DOC>,
+  {
+    let store: Arc<S> = Arc::new(store);
+    let handler = move |did: CoreDID, version: Option<VersionQuery>| {
+      let future_store = store.clone();
+      async move { resolve_from_store(future_store.as_ref(), did, version).await }
+    };
+
+    self.attach_versioned_handler(method, handler);
+  }
 }
 
 impl<DOC: 'static> Resolver<DOC, SingleThreadedCommand<DOC>> {
@@ -247,6 +337,42 @@ impl<DOC: 'static> Resolver<DOC, SingleThreadedCommand<DOC>> {
     let command = SingleThreadedCommand::new(handler);
     self.command_map.insert(method, command);
   }
+
+  /// Attach a new handler responsible for resolving DIDs of the given DID method, including specific versions of
+  /// their documents.
+  ///
+  /// Equivalent to [`Self::attach_handler`](Self::attach_handler()), except that the `handler` additionally receives
+  /// the [`VersionQuery`] passed to [`Self::resolve_version`](Self::resolve_version()), or `None` when the latest
+  /// version is requested.
+  pub fn attach_versioned_handler<D, F, Fut, DOCUMENT, E, DIDERR>(&mut self, method: String, handler: F)
+  where
+    D: DID + for<'r> TryFrom<&'r str, Error = DIDERR> + 'static,
+    DOCUMENT: 'static + Into<DOC>,
+    F: Fn(D, Option<VersionQuery>) -> Fut + 'static + Clone,
+    Fut: Future<Output = std::result::Result<DOCUMENT, E>>,
+    E: Into<Box<dyn std::error::Error + Send + Sync + 'static>>,
+    DIDERR: Into<Box<dyn std::error::Error + Send + Sync + 'static>>,
+  {
+    let command = SingleThreadedCommand::new_versioned(handler);
+    self.command_map.insert(method, command);
+  }
+
+  /// Attaches a handler resolving DIDs of the given DID method, and their document versions, from `store`.
+  ///
+  /// Intended for methods that do not record the history of their documents, such as `did:web` and `did:jwk`.
+  pub fn attach_versioned_store<S, DOCUMENT>(&mut self, method: String, store: S)
+  where
+    S: DocumentVersionStore<DOCUMENT> + 'static,
+    DOCUMENT: 'static + Send + Into<DOC>,
+  {
+    let store: Arc<S> = Arc::new(store);
+    let handler = move |did: CoreDID, version: Option<VersionQuery>| {
+      let future_store = store.clone();
+      async move { resolve_from_store(future_store.as_ref(), did, version).await }
+    };
+
+    self.attach_versioned_handler(method, handler);
+  }
 }
 
 impl<DOC: From<CoreDocument> + 'static> Resolver<DOC, SingleThreadedCommand<DOC>> {
@@ -266,19 +392,87 @@ impl<DOC: From<CoreDocument> + 'static> Resolver<DOC, SendSyncCommand<DOC>> {
 }
 
 impl<DOC: From<CoreDocument> + 'static> Resolver<DOC, SingleThreadedCommand<DOC>> {
-  /// Attaches a handler capable of resolving `did:compositejwk` DIDs.
+  /// Attaches a handler capable of resolving long form `did:compositejwk` DIDs.
   pub fn attach_did_compositejwk_handler(&mut self) {
-    let handler = |did_compositejwk: DIDCompositeJwk| async move { CoreDocument::expand_did_compositejwk(did_compositejwk) };
+    let handler =
+      |did_compositejwk: DIDCompositeJwk| async move { CoreDocument::expand_did_compositejwk(did_compositejwk) };
+    self.attach_handler(DIDCompositeJwk::METHOD.to_string(), handler)
+  }
+
+  /// Attaches a handler capable of resolving `did:compositejwk` DIDs in either form, obtaining the keys of short
+  /// form DIDs from `store`.
+  #[cfg(feature = "composite-jwk-store")]
+  pub fn attach_did_compositejwk_handler_with_store<S>(&mut self, store: S)
+  where
+    S: CompositeJwkStore + 'static,
+  {
+    let store: Arc<S> = Arc::new(store);
+    let handler = move |did_compositejwk: DIDCompositeJwk| {
+      let future_store = store.clone();
+      async move { resolve_did_compositejwk(future_store.as_ref(), did_compositejwk).await }
+    };
     self.attach_handler(DIDCompositeJwk::METHOD.to_string(), handler)
   }
 }
 
 impl<DOC: From<CoreDocument> + 'static> Resolver<DOC, SendSyncCommand<DOC>> {
-  /// Attaches a handler capable of resolving `did:compositejwk` DIDs.
+  /// Attaches a handler capable of resolving long form `did:compositejwk` DIDs.
   pub fn attach_did_compositejwk_handler(&mut self) {
-    let handler = |did_compositejwk: DIDCompositeJwk| async move { CoreDocument::expand_did_compositejwk(did_compositejwk) };
+    let handler =
+      |did_compositejwk: DIDCompositeJwk| async move { CoreDocument::expand_did_compositejwk(did_compositejwk) };
     self.attach_handler(DIDCompositeJwk::METHOD.to_string(), handler)
   }
+
+  /// Attaches a handler capable of resolving `did:compositejwk` DIDs in either form, obtaining the keys of short
+  /// form DIDs from `store`.
+  #[cfg(feature = "composite-jwk-store")]
+  pub fn attach_did_compositejwk_handler_with_store<S>(&mut self, store: S)
+  where
+    S: CompositeJwkStore + 'static,
+  {
+    let store: Arc<S> = Arc::new(store);
+    let handler = move |did_compositejwk: DIDCompositeJwk| {
+      let future_store = store.clone();
+      async move { resolve_did_compositejwk(future_store.as_ref(), did_compositejwk).await }
+    };
+    self.attach_handler(DIDCompositeJwk::METHOD.to_string(), handler)
+  }
+}
+
+impl<DOC: From<CoreDocument> + 'static> Resolver<DOC, SingleThreadedCommand<DOC>> {
+  /// Attaches a handler capable of resolving `did:key` DIDs.
+  pub fn attach_did_key_handler(&mut self) {
+    let handler = |did_key: DIDKey| async move { CoreDocument::expand_did_key(did_key) };
+    self.attach_handler(DIDKey::METHOD.to_string(), handler)
+  }
+}
+
+impl<DOC: From<CoreDocument> + 'static> Resolver<DOC, SendSyncCommand<DOC>> {
+  /// Attaches a handler capable of resolving `did:key` DIDs.
+  pub fn attach_did_key_handler(&mut self) {
+    let handler = |did_key: DIDKey| async move { CoreDocument::expand_did_key(did_key) };
+    self.attach_handler(DIDKey::METHOD.to_string(), handler)
+  }
+}
+
+impl<DOC: From<CoreDocument> + 'static> Resolver<DOC, SingleThreadedCommand<DOC>> {
+  /// Attaches a handler capable of resolving `did:peer` DIDs.
+  ///
+  /// The short form of `did:peer:4` DIDs cannot be resolved, as their documents are only encoded in the long form.
+  pub fn attach_did_peer_handler(&mut self) {
+    let handler = |did_peer: DIDPeer| async move { CoreDocument::expand_did_peer(did_peer) };
+    self.attach_handler(DIDPeer::METHOD.to_string(), handler)
+  }
+}
+
+impl<DOC: From<CoreDocument> + 'static> Resolver<DOC, SendSyncCommand<DOC>> {
+  /// Attaches a handler capable of resolving `did:peer` DIDs.
+  ///
+  /// The short form of `did:peer:4` DIDs cannot be resolved, as their documents are only encoded in the long form.
+  pub fn attach_did_peer_handler(&mut self) {
+    let handler = |did_peer: DIDPeer| async move { CoreDocument::expand_did_peer(did_peer) };
+    self.attach_handler(DIDPeer::METHOD.to_string(), handler)
+  }
 }
 
 #[cfg(feature = "iota")]
@@ -286,6 +480,8 @@ mod iota_handler {
   use crate::ErrorCause;
 
   use super::Resolver;
+  use crate::Error;
+  use crate::VersionQuery;
   use identity_document::document::CoreDocument;
   use identity_iota_core::IotaDID;
   use identity_iota_core::IotaDocument;
@@ -293,25 +489,52 @@ mod iota_handler {
   use std::collections::HashMap;
   use std::sync::Arc;
 
+  /// Resolves the version of the document of `did` selected by `version`, whose ids are the state indices of the
+  /// Alias Output.
+  async fn resolve_iota_version<CLI>(
+    client: &CLI,
+    did: &IotaDID,
+    version: Option<VersionQuery>,
+  ) -> std::result::Result<IotaDocument, Box<dyn std::error::Error + Send + Sync + 'static>>
+  where
+    CLI: IotaIdentityClientExt + Send + Sync,
+  {
+    let document: IotaDocument = match version {
+      None => client.resolve_did(did).await?,
+      Some(VersionQuery::Id(version_id)) => {
+        let state_index: u32 = version_id.parse().map_err(|_| {
+          Error::new(ErrorCause::InvalidVersionQuery(format!(
+            "\"{version_id}\" is not the state index of an Alias Output"
+          )))
+        })?;
+        client.resolve_did_version(did, state_index).await?
+      }
+      Some(VersionQuery::Time(time)) => client.resolve_did_at(did, time).await?,
+    };
+    Ok(document)
+  }
+
   impl<DOC> Resolver<DOC>
   where
     DOC: From<IotaDocument> + AsRef<CoreDocument> + 'static,
   {
     /// Convenience method for attaching a new handler responsible for resolving IOTA DIDs.
     ///
-    /// See also [`attach_handler`](Self::attach_handler).
+    /// The handler supports resolving document versions, whose ids are the state indices of the Alias Output.
+    ///
+    /// See also [`attach_versioned_handler`](Self::attach_versioned_handler).
     pub fn attach_iota_handler<CLI>(&mut self, client: CLI)
     where
       CLI: IotaIdentityClientExt + Send + Sync + 'static,
     {
       let arc_client: Arc<CLI> = Arc::new(client);
 
-      let handler = move |did: IotaDID| {
+      let handler = move |did: IotaDID, version: Option<VersionQuery>| {
         let future_client = arc_client.clone();
-        async move { future_client.resolve_did(&did).await }
+        async move { resolve_iota_version(future_client.as_ref(), &did, version).await }
       };
 
-      self.attach_handler(IotaDID::METHOD.to_owned(), handler);
+      self.attach_versioned_handler(IotaDID::METHOD.to_owned(), handler);
     }
 
     /// Convenience method for attaching multiple handlers responsible for resolving IOTA DIDs
@@ -331,7 +554,7 @@ mod iota_handler {
     /// ```
     ///
     /// # See Also
-    /// - [`attach_handler`](Self::attach_handler).
+    /// - [`attach_versioned_handler`](Self::attach_versioned_handler).
     ///
     /// # Note
     ///
@@ -346,7 +569,7 @@ mod iota_handler {
     {
       let arc_clients = Arc::new(clients.into_iter().collect::<HashMap<&'static str, CLI>>());
 
-      let handler = move |did: IotaDID| {
+      let handler = move |did: IotaDID, version: Option<VersionQuery>| {
         let future_client = arc_clients.clone();
         async move {
           let did_network = did.network_str();
@@ -356,28 +579,29 @@ mod iota_handler {
               .ok_or(crate::Error::new(ErrorCause::UnsupportedNetwork(
                 did_network.to_string(),
               )))?;
-          client
-            .resolve_did(&did)
+          resolve_iota_version(client, &did, version)
             .await
-            .map_err(|err| crate::Error::new(ErrorCause::HandlerError { source: Box::new(err) }))
+            .map_err(|source| crate::Error::new(ErrorCause::HandlerError { source }))
         }
       };
 
-      self.attach_handler(IotaDID::METHOD.to_owned(), handler);
+      self.attach_versioned_handler(IotaDID::METHOD.to_owned(), handler);
     }
   }
 }
 
-
 mod web_handler {
-  use crate::ErrorCause;
   use super::Resolver;
-  use identity_did::DID;
-  use identity_document::document::CoreDocument;
-  use identity_did::WebDID;
+  use crate::resolution::web::resolve_web_did;
   use crate::Error;
+  use crate::ErrorCause;
   use crate::Result;
-
+  use crate::WebResolutionOptions;
+  use identity_did::CoreDID;
+  use identity_did::WebDID;
+  use identity_did::DID;
+  use identity_document::document::CoreDocument;
+  use std::sync::Arc;
 
   impl<DOC> Resolver<DOC>
   where
@@ -386,26 +610,25 @@ mod web_handler {
     /// Convenience method for attaching a new handler responsible for resolving Web DIDs.
     ///
     /// See also [`attach_handler`](Self::attach_handler).
-    pub fn attach_web_handler(&mut self, client: reqwest::Client) -> Result<(), Error>
-    {
+    pub fn attach_web_handler(&mut self, client: reqwest::Client) -> Result<(), Error> {
       let handler = move |did: WebDID| {
         let future_client = client.clone();
         async move {
-          future_client.get(did.to_url().as_ref())
+          future_client
+            .get(did.to_url().as_ref())
             .send()
             .await
             .map_err(|e| Error::new(ErrorCause::HandlerError { source: Box::new(e) }))?
             .json::<CoreDocument>()
             .await
             .map_err(|e| Error::new(ErrorCause::HandlerError { source: Box::new(e) }))
-            .and_then(|d| 
+            .and_then(|d| {
               if d.id().as_str() == did.as_str() {
                 Ok(d)
               } else {
                 Err(Error::new(ErrorCause::DidNotMatching))
               }
-            )
-
+            })
         }
       };
 
@@ -413,9 +636,182 @@ mod web_handler {
       Ok(())
     }
 
+    /// Attaches a handler resolving Web DIDs with the security checks configured by `options`.
+    ///
+    /// Unlike [`attach_web_handler`](Self::attach_web_handler), the handler by default only fetches documents over
+    /// HTTPS from domain names, validates every redirect, limits the size of the response, checks its content type
+    /// and times out, see [`WebResolutionOptions`]. Failures are reported as [`ErrorCause::HandlerError`] with a
+    /// [`WebResolutionError`](crate::WebResolutionError) source.
+    ///
+    /// The client of the handler is built from `client_builder` with redirects disabled, as they are followed by the
+    /// handler itself. Settings weakening TLS, such as accepting invalid certificates, must not be applied to it.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the client cannot be built.
+    pub fn attach_web_handler_with_options(
+      &mut self,
+      client_builder: reqwest::ClientBuilder,
+      options: WebResolutionOptions,
+    ) -> Result<(), Error> {
+      self.attach_web_handler_with_scheme(client_builder, "https", options)
+    }
+
+    /// Attaches the hardened `did:web` handler fetching documents with the given URL scheme, which allows testing
+    /// against a plain HTTP server.
+    pub(crate) fn attach_web_handler_with_scheme(
+      &mut self,
+      client_builder: reqwest::ClientBuilder,
+      scheme: &'static str,
+      options: WebResolutionOptions,
+    ) -> Result<(), Error> {
+      let client: reqwest::Client = client_builder
+        .redirect(reqwest::redirect::Policy::none())
+        .build()
+        .map_err(|e| Error::new(ErrorCause::HandlerError { source: Box::new(e) }))?;
+      let options: Arc<WebResolutionOptions> = Arc::new(options);
+
+      let handler = move |did: CoreDID| {
+        let future_client = client.clone();
+        let future_options = options.clone();
+        async move { resolve_web_did(&future_client, scheme, &did, &future_options).await }
+      };
+
+      self.attach_handler(WebDID::METHOD.to_owned(), handler);
+      Ok(())
+    }
   }
 }
 
+#[cfg(feature = "webvh")]
+mod webvh_handler {
+  use super::super::web::read_body;
+  use super::super::web::request_error;
+  use super::Resolver;
+  use crate::DocumentVersion;
+  use crate::Error;
+  use crate::ErrorCause;
+  use crate::VersionQuery;
+  use crate::WebResolutionError;
+  use identity_document::document::CoreDocument;
+  use identity_verification::jws::JwsVerifier;
+  use identity_webvh::DidLog;
+  use identity_webvh::LogVersion;
+  use identity_webvh::WebVhDID;
+  use reqwest::Response;
+  use std::sync::Arc;
+  use std::time::Duration;
+
+  /// The maximum size of a `did.jsonl` log in bytes.
+  const MAX_LOG_SIZE: usize = 10 * 1024 * 1024;
+  /// The timeout of fetching a `did.jsonl` log, including reading the response.
+  const LOG_TIMEOUT: Duration = Duration::from_secs(30);
+
+  /// Fetches and verifies the log of `did` and returns the version of its document selected by `version`.
+  async fn resolve_webvh_version<TRV, PQV>(
+    client: &reqwest::Client,
+    scheme: &str,
+    did: &WebVhDID,
+    version: Option<VersionQuery>,
+    traditional_verifier: &TRV,
+    pq_verifier: &PQV,
+  ) -> std::result::Result<CoreDocument, Box<dyn std::error::Error + Send + Sync + 'static>>
+  where
+    TRV: JwsVerifier,
+    PQV: JwsVerifier,
+  {
+    let mut url = did.log_url();
+    url
+      .set_scheme(scheme)
+      .map_err(|_| format!("unsupported scheme \"{scheme}\""))?;
+    let response: Response = client
+      .get(url.as_str())
+      .timeout(LOG_TIMEOUT)
+      .send()
+      .await
+      .map_err(|err| request_error(err, &url))?;
+    if !response.status().is_success() {
+      return Err(WebResolutionError::HttpStatus(response.status().as_u16()).into());
+    }
+    let jsonl: String = String::from_utf8(read_body(response, &url, MAX_LOG_SIZE).await?)?;
+    let versions: Vec<DocumentVersion> = DidLog::from_jsonl(&jsonl)?
+      .verify(did, traditional_verifier, pq_verifier)?
+      .into_iter()
+      .map(
+        |LogVersion {
+           version_id,
+           version_time,
+           document,
+           ..
+         }| DocumentVersion {
+          version_id,
+          valid_from: version_time,
+          document,
+        },
+      )
+      .collect();
+
+    let selected: Option<DocumentVersion> = match &version {
+      Some(query) => query.select(&versions).cloned(),
+      None => versions.last().cloned(),
+    };
+    selected.map(|version| version.document).ok_or_else(|| {
+      let selector: String = version.map(|query| format!("?{query}")).unwrap_or_default();
+      Error::new(ErrorCause::VersionNotFound(format!("{did}{selector}"))).into()
+    })
+  }
+
+  impl<DOC> Resolver<DOC>
+  where
+    DOC: From<CoreDocument> + AsRef<CoreDocument> + 'static,
+  {
+    /// Convenience method for attaching a new handler responsible for resolving `did:webvh` DIDs.
+    ///
+    /// The handler fetches the `did.jsonl` log of the DID over HTTPS and verifies it before returning the latest
+    /// document. Ed25519 signatures of log entries are verified with `traditional_verifier` and ML-DSA signatures with
+    /// `pq_verifier`. Document versions are selected from the verified log by their `versionId` or `versionTime`.
+    /// Logs larger than 10 MiB are rejected, and fetching a log times out after 30 seconds.
+    ///
+    /// See also [`attach_versioned_handler`](Self::attach_versioned_handler).
+    pub fn attach_webvh_handler<TRV, PQV>(
+      &mut self,
+      client: reqwest::Client,
+      traditional_verifier: TRV,
+      pq_verifier: PQV,
+    ) where
+      TRV: JwsVerifier + Send + Sync + 'static,
+      PQV: JwsVerifier + Send + Sync + 'static,
+    {
+      self.attach_webvh_handler_with_scheme(client, "https", traditional_verifier, pq_verifier)
+    }
+
+    /// Attaches the `did:webvh` handler fetching logs with the given URL scheme, which allows testing against a
+    /// plain HTTP server.
+    pub(crate) fn attach_webvh_handler_with_scheme<TRV, PQV>(
+      &mut self,
+      client: reqwest::Client,
+      scheme: &'static str,
+      traditional_verifier: TRV,
+      pq_verifier: PQV,
+    ) where
+      TRV: JwsVerifier + Send + Sync + 'static,
+      PQV: JwsVerifier + Send + Sync + 'static,
+    {
+      let verifiers: Arc<(TRV, PQV)> = Arc::new((traditional_verifier, pq_verifier));
+
+      let handler = move |did: WebVhDID, version: Option<VersionQuery>| {
+        let future_client = client.clone();
+        let future_verifiers = verifiers.clone();
+        async move {
+          let (traditional_verifier, pq_verifier) = future_verifiers.as_ref();
+          resolve_webvh_version(&future_client, scheme, &did, version, traditional_verifier, pq_verifier).await
+        }
+      };
+
+      self.attach_versioned_handler(WebVhDID::METHOD.to_owned(), handler);
+    }
+  }
+}
 
 impl<CMD, DOC> Default for Resolver<DOC, CMD>
 where
@@ -508,4 +904,94 @@ mod tests {
     let doc = resolver.resolve(&did_jwk).await.unwrap();
     assert_eq!(doc.id(), did_jwk.as_ref());
   }
+
+  #[tokio::test]
+  async fn test_did_key_resolution() {
+    let mut resolver = Resolver::<CoreDocument>::new();
+    resolver.attach_did_key_handler();
+
+    let did_key = "did:key:zDnaerDaTF5BXEavCrfRZEk316dpbLsfPDZ3WJ5hRTPFU2169"
+      .parse::<DIDKey>()
+      .unwrap();
+
+    let doc = resolver.resolve(&did_key).await.unwrap();
+    assert_eq!(doc.id(), did_key.as_ref());
+    assert_eq!(
+      doc.methods(None)[0].data().try_public_key_jwk().unwrap(),
+      &did_key.jwk()
+    );
+  }
+
+  #[tokio::test]
+  async fn test_did_peer_resolution() {
+    let mut resolver = Resolver::<CoreDocument>::new();
+    resolver.attach_did_peer_handler();
+
+    let did_peer =
+      "did:peer:2.Ez6LSeu9HkTHSfLLeUs2nnzUSNedgDUevfNQgQjQC23ZCit6F.Vz6MkiTBz1ymuepAQ4HEHYSF1H8quG5GLVVQR3djdX3mDooWp"
+        .parse::<DIDPeer>()
+        .unwrap();
+
+    let doc = resolver.resolve(&did_peer).await.unwrap();
+    assert_eq!(doc.id(), did_peer.as_ref());
+    assert_eq!(doc.key_agreement().len(), 1);
+    assert_eq!(doc.authentication().len(), 1);
+  }
+
+  #[cfg(feature = "composite-jwk-store")]
+  #[tokio::test]
+  async fn test_did_compositejwk_short_form_resolution() {
+    use identity_verification::jwk::CompositeAlgId;
+    use identity_verification::jwk::CompositeJwk;
+    use identity_verification::jwk::Jwk;
+    use identity_verification::jwk::JwkParams;
+    use identity_verification::jwk::JwkParamsOkp;
+    use identity_verification::jwk::JwkType;
+    use identity_verification::jwu;
+
+    use crate::CompositeJwkMemStore;
+
+    let mut okp = JwkParamsOkp::new();
+    okp.crv = "Ed25519".to_owned();
+    okp.x = jwu::encode_b64([1; 32]);
+    let mut params = JwkParams::new(JwkType::MLDSA);
+    if let JwkParams::MLDSA(params) = &mut params {
+      params.public = jwu::encode_b64(vec![2; 1312]);
+    }
+    let composite_jwk = CompositeJwk::new(
+      CompositeAlgId::IdMldsa44Ed25519Sha512,
+      Jwk::from_params(okp),
+      Jwk::from_params(params),
+    );
+
+    let store = CompositeJwkMemStore::new();
+    let short_form: DIDCompositeJwk = store.insert(composite_jwk.clone()).unwrap();
+    let long_form: DIDCompositeJwk = DIDCompositeJwk::new(&composite_jwk).unwrap();
+    let unknown: DIDCompositeJwk =
+      DIDCompositeJwk::parse("did:compositejwk:zQmd8CpeFPci817KDsbSAKWcXAE2mjvCQSasRewvbSF54Bd").unwrap();
+
+    // Short form DIDs cannot be resolved without a store.
+    let mut resolver = Resolver::<CoreDocument>::new();
+    resolver.attach_did_compositejwk_handler();
+    assert!(resolver.resolve(&short_form).await.is_err());
+
+    resolver.attach_did_compositejwk_handler_with_store(store);
+    let doc = resolver.resolve(&short_form).await.unwrap();
+    assert_eq!(doc.id(), short_form.as_ref());
+    assert_eq!(
+      doc.methods(None)[0].data().try_composite_public_key().unwrap(),
+      &composite_jwk
+    );
+    let doc = resolver.resolve(&long_form).await.unwrap();
+    assert_eq!(doc.id(), long_form.as_ref());
+
+    let err = resolver.resolve(&unknown).await.unwrap_err().into_error_cause();
+    let ErrorCause::HandlerError { source } = err else {
+      panic!("unexpected error cause");
+    };
+    assert!(matches!(
+      source.downcast_ref::<Error>().map(Error::error_cause),
+      Some(ErrorCause::CompositeJwkNotFound(_))
+    ));
+  }
 }
@@ -4,3 +4,7 @@
 use super::resolver::*;
 mod resolution;
 mod send_sync;
+mod versioning;
+mod web;
+#[cfg(feature = "webvh")]
+mod webvh;
@@ -0,0 +1,183 @@
+// Copyright 2020-2024 IOTA Stiftung, Fondazione Links
+// SPDX-License-Identifier: Apache-2.0
+
+use identity_core::common::Duration;
+use identity_core::common::Timestamp;
+use identity_core::common::Url;
+use identity_did::CoreDID;
+use identity_did::DIDUrl;
+use identity_did::DID;
+use identity_document::document::CoreDocument;
+use identity_document::document::DocumentBuilder;
+use identity_iota_core::block::address::Address;
+use identity_iota_core::block::address::Ed25519Address;
+use identity_iota_core::block::output::AliasId;
+use identity_iota_core::block::output::AliasOutput;
+use identity_iota_core::IotaClientExt;
+use identity_iota_core::IotaDID;
+use identity_iota_core::IotaDocument;
+use identity_iota_core::IotaIdentityClientExt;
+use identity_iota_core::MockLedger;
+use identity_iota_core::NetworkName;
+use iota_sdk::client::secret::SecretManager;
+
+use crate::DocumentVersionMemStore;
+use crate::ErrorCause;
+use crate::Resolver;
+use crate::SingleThreadedResolver;
+use crate::VersionQuery;
+
+fn document(did: &CoreDID, alias: &str) -> CoreDocument {
+  DocumentBuilder::default()
+    .id(did.clone())
+    .also_known_as(Url::parse(alias).unwrap())
+    .build()
+    .unwrap()
+}
+
+fn timestamp(seconds: i64) -> Timestamp {
+  Timestamp::from_unix(seconds).unwrap()
+}
+
+#[test]
+fn version_query_from_did_url() {
+  let url = |query: &str| DIDUrl::parse(format!("did:example:123{query}")).unwrap();
+
+  assert_eq!(VersionQuery::from_did_url(&url("")).unwrap(), None);
+  assert_eq!(VersionQuery::from_did_url(&url("?service=foo")).unwrap(), None);
+  assert_eq!(
+    VersionQuery::from_did_url(&url("?versionId=2")).unwrap(),
+    Some(VersionQuery::Id("2".to_owned()))
+  );
+  assert_eq!(
+    VersionQuery::from_did_url(&url("?versionTime=2023-01-01T00:00:00Z#key-1")).unwrap(),
+    Some(VersionQuery::Time(Timestamp::parse("2023-01-01T00:00:00Z").unwrap()))
+  );
+
+  for invalid in ["?versionTime=yesterday", "?versionId=1&versionTime=2023-01-01T00:00:00Z", "?versionId=1&versionId=2"] {
+    assert!(matches!(
+      VersionQuery::from_did_url(&url(invalid)).unwrap_err().into_error_cause(),
+      ErrorCause::InvalidVersionQuery(_)
+    ));
+  }
+}
+
+#[test]
+fn mem_store_rejects_out_of_order_versions() {
+  let did: CoreDID = CoreDID::parse("did:web:example.com").unwrap();
+  let store: DocumentVersionMemStore = DocumentVersionMemStore::new();
+
+  assert_eq!(store.publish(document(&did, "https://a.example"), timestamp(10)).unwrap(), "1");
+  assert!(matches!(
+    store
+      .publish(document(&did, "https://b.example"), timestamp(10))
+      .unwrap_err()
+      .into_error_cause(),
+    ErrorCause::InvalidDocumentVersion(_)
+  ));
+  assert_eq!(store.publish(document(&did, "https://b.example"), timestamp(20)).unwrap(), "2");
+}
+
+#[tokio::test]
+async fn versioned_store_resolution() {
+  let did: CoreDID = CoreDID::parse("did:web:example.com").unwrap();
+  let first: CoreDocument = document(&did, "https://a.example");
+  let second: CoreDocument = document(&did, "https://b.example");
+
+  let store: DocumentVersionMemStore = DocumentVersionMemStore::new();
+  store.publish(first.clone(), timestamp(10)).unwrap();
+  store.publish(second.clone(), timestamp(20)).unwrap();
+
+  let mut resolver: Resolver = Resolver::new();
+  resolver.attach_versioned_store("web".to_owned(), store);
+
+  assert_eq!(resolver.resolve(&did).await.unwrap(), second);
+  assert_eq!(
+    resolver
+      .resolve_version(&did, &VersionQuery::Id("1".to_owned()))
+      .await
+      .unwrap(),
+    first
+  );
+  assert_eq!(resolver.resolve_at(&did, timestamp(19)).await.unwrap(), first);
+  assert_eq!(resolver.resolve_at(&did, timestamp(20)).await.unwrap(), second);
+
+  let url: DIDUrl = did.clone().join("?versionTime=1970-01-01T00:00:15Z").unwrap();
+  assert_eq!(resolver.resolve_did_url(&url).await.unwrap(), first);
+  let url: DIDUrl = did.clone().join("#key-1").unwrap();
+  assert_eq!(resolver.resolve_did_url(&url).await.unwrap(), second);
+
+  for query in [VersionQuery::Id("3".to_owned()), VersionQuery::Time(timestamp(9))] {
+    let ErrorCause::HandlerError { source } = resolver.resolve_version(&did, &query).await.unwrap_err().into_error_cause()
+    else {
+      unreachable!()
+    };
+    let cause: &ErrorCause = source.downcast_ref::<crate::Error>().unwrap().error_cause();
+    assert!(matches!(cause, ErrorCause::VersionNotFound(_)));
+  }
+}
+
+#[tokio::test]
+async fn unversioned_handler_rejects_version_queries() {
+  async fn handler(did: CoreDID) -> std::result::Result<CoreDocument, std::io::Error> {
+    Ok(document(&did, "https://a.example"))
+  }
+
+  let did: CoreDID = CoreDID::parse("did:foo:1234").unwrap();
+  let mut resolver: SingleThreadedResolver = SingleThreadedResolver::new();
+  resolver.attach_handler("foo".to_owned(), handler);
+
+  assert!(resolver.resolve(&did).await.is_ok());
+  let url: DIDUrl = did.clone().join("?versionId=1").unwrap();
+  assert!(matches!(
+    resolver.resolve_did_url(&url).await.unwrap_err().into_error_cause(),
+    ErrorCause::UnsupportedVersioning { method } if method == "foo"
+  ));
+}
+
+#[tokio::test]
+async fn iota_version_resolution() {
+  let network: NetworkName = NetworkName::try_from("tst").unwrap();
+  let address: Address = Address::Ed25519(Ed25519Address::new([1; Ed25519Address::LENGTH]));
+  let ledger: MockLedger = MockLedger::new(&network);
+
+  let output: AliasOutput = ledger
+    .new_did_output(address, IotaDocument::new(&network), None)
+    .await
+    .unwrap();
+  let created: IotaDocument = ledger
+    .publish_did_output(&SecretManager::Placeholder, output)
+    .await
+    .unwrap();
+  let did: IotaDID = created.id().clone();
+
+  let mut updated: IotaDocument = created.clone();
+  updated
+    .also_known_as_mut()
+    .append(Url::parse("https://a.example").unwrap());
+  let output: AliasOutput = ledger.update_did_output(updated).await.unwrap();
+  let updated: IotaDocument = ledger
+    .publish_did_output(&SecretManager::Placeholder, output)
+    .await
+    .unwrap();
+  let updated_at: Timestamp = ledger.output_milestone(&AliasId::from(&did)).unwrap().timestamp;
+
+  let mut resolver: Resolver<IotaDocument> = Resolver::new();
+  resolver.attach_iota_handler(ledger);
+
+  assert_eq!(resolver.resolve(&did).await.unwrap(), updated);
+  let url: DIDUrl = did.to_url().join("?versionId=0").unwrap();
+  assert_eq!(resolver.resolve_did_url(&url).await.unwrap(), created);
+  let before_update: Timestamp = updated_at.checked_sub(Duration::seconds(1)).unwrap();
+  assert_eq!(resolver.resolve_at(&did, before_update).await.unwrap(), created);
+  assert_eq!(resolver.resolve_at(&did, updated_at).await.unwrap(), updated);
+
+  let url: DIDUrl = did.to_url().join("?versionId=latest").unwrap();
+  let ErrorCause::HandlerError { source } = resolver.resolve_did_url(&url).await.unwrap_err().into_error_cause() else {
+    unreachable!()
+  };
+  assert!(matches!(
+    source.downcast_ref::<crate::Error>().unwrap().error_cause(),
+    ErrorCause::InvalidVersionQuery(_)
+  ));
+}
@@ -0,0 +1,283 @@
+// Copyright 2020-2024 IOTA Stiftung, Fondazione Links
+// SPDX-License-Identifier: Apache-2.0
+
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::Duration;
+
+use identity_core::common::Object;
+use identity_core::convert::ToJson;
+use identity_did::CoreDID;
+use identity_document::document::CoreDocument;
+use tokio::io::AsyncReadExt;
+use tokio::io::AsyncWriteExt;
+use tokio::net::TcpListener;
+
+use crate::ErrorCause;
+use crate::Resolver;
+use crate::WebRedirectPolicy;
+use crate::WebResolutionError;
+use crate::WebResolutionOptions;
+
+const HOST: &str = "alice.example";
+
+/// A response of the HTTP stand-in, sent after `delay`.
+struct Route {
+  path: String,
+  response: String,
+  delay: Duration,
+}
+
+impl Route {
+  fn new(path: &str, status: &str, headers: &[(&str, &str)], body: &str) -> Self {
+    let headers: String = headers
+      .iter()
+      .map(|(name, value)| format!("{name}: {value}\r\n"))
+      .collect();
+    Self {
+      path: path.to_owned(),
+      response: format!(
+        "HTTP/1.1 {status}\r\n{headers}content-length: {}\r\nconnection: close\r\n\r\n{body}",
+        body.len()
+      ),
+      delay: Duration::ZERO,
+    }
+  }
+
+  fn document(path: &str, content_type: &str, document: &CoreDocument) -> Self {
+    Self::new(
+      path,
+      "200 OK",
+      &[("content-type", content_type)],
+      &document.to_json().unwrap(),
+    )
+  }
+
+  fn redirect(path: &str, location: &str) -> Self {
+    Self::new(path, "302 Found", &[("location", location)], "")
+  }
+
+  fn delayed(mut self, delay: Duration) -> Self {
+    self.delay = delay;
+    self
+  }
+}
+
+/// Serves `routes` over plain HTTP, standing in for the web server hosting `did:web` documents.
+fn serve(listener: TcpListener, routes: Vec<Route>) {
+  let routes: Arc<Vec<Route>> = Arc::new(routes);
+  tokio::spawn(async move {
+    loop {
+      let (mut stream, _) = listener.accept().await.unwrap();
+      let routes: Arc<Vec<Route>> = routes.clone();
+      tokio::spawn(async move {
+        let mut request: Vec<u8> = Vec::new();
+        let mut buffer = [0; 1024];
+        while !request.ends_with(b"\r\n\r\n") {
+          let read: usize = stream.read(&mut buffer).await.unwrap();
+          if read == 0 {
+            break;
+          }
+          request.extend_from_slice(&buffer[..read]);
+        }
+
+        let not_found: Route = Route::new("", "404 Not Found", &[], "");
+        let route: &Route = routes
+          .iter()
+          .find(|route| request.starts_with(format!("GET {} ", route.path).as_bytes()))
+          .unwrap_or(&not_found);
+        tokio::time::sleep(route.delay).await;
+        let _ = stream.write_all(route.response.as_bytes()).await;
+      });
+    }
+  });
+}
+
+fn document(did: &CoreDID) -> CoreDocument {
+  CoreDocument::builder(Object::new()).id(did.clone()).build().unwrap()
+}
+
+/// The options for testing against the plain HTTP stand-in.
+fn http_options() -> WebResolutionOptions {
+  WebResolutionOptions::new().with_https_only(false)
+}
+
+/// Returns a resolver resolving `hosts` to `address`.
+fn resolver(address: SocketAddr, hosts: &[&str], options: WebResolutionOptions) -> Resolver {
+  let client_builder: reqwest::ClientBuilder = hosts.iter().fold(reqwest::Client::builder(), |builder, host| {
+    builder.resolve(host, address)
+  });
+  let mut resolver: Resolver = Resolver::new();
+  resolver
+    .attach_web_handler_with_scheme(client_builder, "http", options)
+    .unwrap();
+  resolver
+}
+
+async fn resolution_error(resolver: &Resolver, did: &CoreDID) -> WebResolutionError {
+  let ErrorCause::HandlerError { source } = resolver.resolve(did).await.unwrap_err().into_error_cause() else {
+    unreachable!()
+  };
+  *source.downcast::<WebResolutionError>().unwrap()
+}
+
+#[tokio::test]
+async fn resolves_conforming_documents() {
+  let listener: TcpListener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+  let address: SocketAddr = listener.local_addr().unwrap();
+  let port: u16 = address.port();
+  let alice: CoreDID = CoreDID::parse(format!("did:web:{HOST}%3A{port}:users:alice")).unwrap();
+  let root: CoreDID = CoreDID::parse(format!("did:web:{HOST}%3A{port}")).unwrap();
+  let moved: CoreDID = CoreDID::parse(format!("did:web:{HOST}%3A{port}:users:moved")).unwrap();
+  let ip: CoreDID = CoreDID::parse(format!("did:web:127.0.0.1%3A{port}:users:ip")).unwrap();
+  // Internationalized domain names are fetched from their punycode form.
+  let idn: CoreDID = CoreDID::parse(format!("did:web:b%C3%BCcher.example%3A{port}:users:idn")).unwrap();
+
+  serve(
+    listener,
+    vec![
+      Route::document("/users/alice/did.json", "application/did+json", &document(&alice)),
+      Route::document(
+        "/.well-known/did.json",
+        "application/json; charset=utf-8",
+        &document(&root),
+      ),
+      Route::redirect("/users/moved/did.json", "/archive/moved/did.json"),
+      Route::document("/archive/moved/did.json", "application/did+ld+json", &document(&moved)),
+      Route::document("/users/ip/did.json", "application/did+json", &document(&ip)),
+      Route::document("/users/idn/did.json", "application/did+json", &document(&idn)),
+    ],
+  );
+
+  let options: WebResolutionOptions = http_options().with_redirect_policy(WebRedirectPolicy::SameOrigin { max: 1 });
+  let resolver: Resolver = resolver(address, &[HOST, "xn--bcher-kva.example"], options.clone());
+  assert_eq!(resolver.resolve(&alice).await.unwrap(), document(&alice));
+  assert_eq!(resolver.resolve(&root).await.unwrap(), document(&root));
+  assert_eq!(resolver.resolve(&moved).await.unwrap(), document(&moved));
+  assert_eq!(resolver.resolve(&idn).await.unwrap(), document(&idn));
+
+  // IP addresses must be allowed explicitly.
+  assert!(matches!(
+    resolution_error(&resolver, &ip).await,
+    WebResolutionError::IpHostNotAllowed(_)
+  ));
+  let resolver: Resolver = self::resolver(address, &[], options.with_ip_hosts(true));
+  assert_eq!(resolver.resolve(&ip).await.unwrap(), document(&ip));
+}
+
+#[tokio::test]
+async fn rejects_nonconforming_responses() {
+  let listener: TcpListener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+  let address: SocketAddr = listener.local_addr().unwrap();
+  let did = |path: &str| CoreDID::parse(format!("did:web:{HOST}%3A{}:{path}", address.port())).unwrap();
+
+  serve(
+    listener,
+    vec![
+      Route::document("/html/did.json", "text/html", &document(&did("html"))),
+      Route::new(
+        "/untyped/did.json",
+        "200 OK",
+        &[],
+        &document(&did("untyped")).to_json().unwrap(),
+      ),
+      Route::document("/large/did.json", "application/did+json", &document(&did("large"))),
+      Route::document("/other/did.json", "application/did+json", &document(&did("alice"))),
+      Route::new(
+        "/invalid/did.json",
+        "200 OK",
+        &[("content-type", "application/json")],
+        "{}",
+      ),
+      Route::document("/slow/did.json", "application/did+json", &document(&did("slow")))
+        .delayed(Duration::from_secs(2)),
+      Route::redirect("/moved/did.json", "/moved-again/did.json"),
+      Route::redirect("/moved-again/did.json", "/archive/did.json"),
+      Route::redirect("/cross/did.json", "http://mallory.example/did.json"),
+    ],
+  );
+
+  let options: WebResolutionOptions = http_options().with_timeout(Duration::from_millis(200));
+  let resolver: Resolver = resolver(address, &[HOST], options.clone());
+  assert!(matches!(
+    resolution_error(&resolver, &did("html")).await,
+    WebResolutionError::UnexpectedContentType(Some(content_type)) if content_type == "text/html"
+  ));
+  assert!(matches!(
+    resolution_error(&resolver, &did("untyped")).await,
+    WebResolutionError::UnexpectedContentType(None)
+  ));
+  assert!(matches!(
+    resolution_error(&resolver, &did("missing")).await,
+    WebResolutionError::HttpStatus(404)
+  ));
+  assert!(matches!(
+    resolution_error(&resolver, &did("other")).await,
+    WebResolutionError::DocumentIdMismatch { .. }
+  ));
+  assert!(matches!(
+    resolution_error(&resolver, &did("invalid")).await,
+    WebResolutionError::InvalidDocument(_)
+  ));
+  assert!(matches!(
+    resolution_error(&resolver, &did("slow")).await,
+    WebResolutionError::Timeout(_)
+  ));
+  assert!(matches!(
+    resolution_error(&resolver, &did("moved")).await,
+    WebResolutionError::RedirectNotAllowed(_)
+  ));
+  assert!(matches!(
+    resolution_error(&resolver, &did("..")).await,
+    WebResolutionError::InvalidDID(_)
+  ));
+
+  let small: Resolver = self::resolver(address, &[HOST], options.clone().with_max_response_size(10));
+  assert!(matches!(
+    resolution_error(&small, &did("large")).await,
+    WebResolutionError::ResponseTooLarge(10)
+  ));
+
+  let same_origin: Resolver = self::resolver(
+    address,
+    &[HOST],
+    options
+      .clone()
+      .with_redirect_policy(WebRedirectPolicy::SameOrigin { max: 1 }),
+  );
+  assert!(matches!(
+    resolution_error(&same_origin, &did("moved")).await,
+    WebResolutionError::TooManyRedirects(1)
+  ));
+  assert!(matches!(
+    resolution_error(&same_origin, &did("cross")).await,
+    WebResolutionError::RedirectNotAllowed(_)
+  ));
+  let any: Resolver = self::resolver(
+    address,
+    &[HOST],
+    options.clone().with_redirect_policy(WebRedirectPolicy::Any { max: 2 }),
+  );
+  assert!(matches!(
+    resolution_error(&any, &did("moved")).await,
+    WebResolutionError::HttpStatus(404)
+  ));
+
+  // Plain HTTP is only accepted when explicitly allowed.
+  let https_only: Resolver = self::resolver(address, &[HOST], options.with_https_only(true));
+  assert!(matches!(
+    resolution_error(&https_only, &did("html")).await,
+    WebResolutionError::InsecureUrl(_)
+  ));
+
+  // The default options reject IP addresses.
+  let mut strict: Resolver = Resolver::new();
+  strict
+    .attach_web_handler_with_options(reqwest::Client::builder(), WebResolutionOptions::default())
+    .unwrap();
+  let ip: CoreDID = CoreDID::parse(format!("did:web:127.0.0.1%3A{}", address.port())).unwrap();
+  assert!(matches!(
+    resolution_error(&strict, &ip).await,
+    WebResolutionError::IpHostNotAllowed(_)
+  ));
+}
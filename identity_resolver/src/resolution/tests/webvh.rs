@@ -0,0 +1,191 @@
+// Copyright 2020-2024 IOTA Stiftung, Fondazione Links
+// SPDX-License-Identifier: Apache-2.0
+
+use std::net::SocketAddr;
+
+use identity_core::common::Object;
+use identity_core::common::Timestamp;
+use identity_core::common::Url;
+use identity_did::DIDUrl;
+use identity_did::DID;
+use identity_document::document::CoreDocument;
+use identity_eddsa_verifier::EdDSAJwsVerifier;
+use identity_pqc_verifier::PQCJwsVerifier;
+use identity_storage::JwkMemStore;
+use identity_storage::KeyIdMemstore;
+use identity_storage::Storage;
+use identity_webvh::DidLog;
+use identity_webvh::LogParameters;
+use identity_webvh::StorageLogSigner;
+use identity_webvh::UpdateKeyType;
+use identity_webvh::WebVhDID;
+use tokio::io::AsyncReadExt;
+use tokio::io::AsyncWriteExt;
+use tokio::net::TcpListener;
+
+use crate::ErrorCause;
+use crate::Resolver;
+use crate::VersionQuery;
+
+const HOST: &str = "alice.example";
+
+fn timestamp(seconds: i64) -> Timestamp {
+  Timestamp::from_unix(1_700_000_000 + seconds).unwrap()
+}
+
+/// Serves `body` at `path` over plain HTTP, standing in for the web server hosting a `did.jsonl` log.
+fn serve(listener: TcpListener, path: String, body: String) {
+  tokio::spawn(async move {
+    loop {
+      let (mut stream, _) = listener.accept().await.unwrap();
+      let mut request: Vec<u8> = Vec::new();
+      let mut buffer = [0; 1024];
+      while !request.ends_with(b"\r\n\r\n") {
+        let read: usize = stream.read(&mut buffer).await.unwrap();
+        if read == 0 {
+          break;
+        }
+        request.extend_from_slice(&buffer[..read]);
+      }
+
+      let response: String = if request.starts_with(format!("GET {path} ").as_bytes()) {
+        format!(
+          "HTTP/1.1 200 OK\r\ncontent-type: text/jsonl\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{body}",
+          body.len()
+        )
+      } else {
+        "HTTP/1.1 404 Not Found\r\ncontent-length: 0\r\nconnection: close\r\n\r\n".to_owned()
+      };
+      stream.write_all(response.as_bytes()).await.unwrap();
+    }
+  });
+}
+
+/// Returns a resolver fetching logs over plain HTTP, resolving [`HOST`] to `address`.
+fn resolver(address: SocketAddr) -> Resolver {
+  let client: reqwest::Client = reqwest::Client::builder().resolve(HOST, address).build().unwrap();
+  let mut resolver: Resolver = Resolver::new();
+  resolver.attach_webvh_handler_with_scheme(client, "http", EdDSAJwsVerifier::default(), PQCJwsVerifier::default());
+  resolver
+}
+
+#[tokio::test]
+async fn webvh_resolution() {
+  let listener: TcpListener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+  let address: SocketAddr = listener.local_addr().unwrap();
+  let location: String = format!("{HOST}%3A{}:dids:alice", address.port());
+
+  let storage = Storage::new(JwkMemStore::new(), KeyIdMemstore::new());
+  let first_key = StorageLogSigner::generate(&storage, UpdateKeyType::Ed25519)
+    .await
+    .unwrap();
+  let second_key = StorageLogSigner::generate(&storage, UpdateKeyType::MlDsa44Ed25519)
+    .await
+    .unwrap();
+
+  let document: CoreDocument = CoreDocument::builder(Object::new())
+    .id(WebVhDID::placeholder(&location).unwrap())
+    .build()
+    .unwrap();
+  let mut log: DidLog = DidLog::create(
+    document,
+    LogParameters::new()
+      .with_update_keys([first_key.update_key().clone()])
+      .with_next_key_hashes([second_key.update_key().hash()]),
+    timestamp(0),
+    &first_key,
+  )
+  .await
+  .unwrap();
+  let first: CoreDocument = log.latest().unwrap().state().clone();
+  let first_version_id: String = log.latest().unwrap().version_id().to_owned();
+
+  let mut second: CoreDocument = first.clone();
+  second
+    .also_known_as_mut()
+    .append(Url::parse("https://alice.example").unwrap());
+  log
+    .update(
+      second.clone(),
+      LogParameters::new().with_update_keys([second_key.update_key().clone()]),
+      timestamp(10),
+      &second_key,
+    )
+    .await
+    .unwrap();
+
+  let did: WebVhDID = WebVhDID::try_from_core(first.id().clone()).unwrap();
+  assert_eq!(did.log_url().path(), "/dids/alice/did.jsonl");
+  serve(listener, did.log_url().path().to_owned(), log.to_jsonl().unwrap());
+  let resolver: Resolver = resolver(address);
+
+  assert_eq!(resolver.resolve(&did).await.unwrap(), second);
+  assert_eq!(
+    resolver
+      .resolve_version(&did, &VersionQuery::Id(first_version_id.clone()))
+      .await
+      .unwrap(),
+    first
+  );
+  assert_eq!(resolver.resolve_at(&did, timestamp(9)).await.unwrap(), first);
+  let url: DIDUrl = did.to_url().join(format!("?versionId={first_version_id}")).unwrap();
+  assert_eq!(resolver.resolve_did_url(&url).await.unwrap(), first);
+
+  let ErrorCause::HandlerError { source } = resolver
+    .resolve_at(&did, timestamp(-1))
+    .await
+    .unwrap_err()
+    .into_error_cause()
+  else {
+    unreachable!()
+  };
+  assert!(matches!(
+    source.downcast_ref::<crate::Error>().unwrap().error_cause(),
+    ErrorCause::VersionNotFound(_)
+  ));
+
+  // A DID with another SCID at the same location does not match the log.
+  let other: WebVhDID = WebVhDID::new("QmOther", &location).unwrap();
+  assert!(matches!(
+    resolver.resolve(&other).await.unwrap_err().into_error_cause(),
+    ErrorCause::HandlerError { .. }
+  ));
+}
+
+#[tokio::test]
+async fn webvh_resolution_rejects_tampered_logs() {
+  let listener: TcpListener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+  let address: SocketAddr = listener.local_addr().unwrap();
+  let location: String = format!("{HOST}%3A{}", address.port());
+
+  let storage = Storage::new(JwkMemStore::new(), KeyIdMemstore::new());
+  let signer = StorageLogSigner::generate(&storage, UpdateKeyType::MlDsa44)
+    .await
+    .unwrap();
+  let document: CoreDocument = CoreDocument::builder(Object::new())
+    .id(WebVhDID::placeholder(&location).unwrap())
+    .build()
+    .unwrap();
+  let log: DidLog = DidLog::create(
+    document,
+    LogParameters::new().with_update_keys([signer.update_key().clone()]),
+    timestamp(0),
+    &signer,
+  )
+  .await
+  .unwrap();
+  let did: WebVhDID = WebVhDID::try_from_core(log.latest().unwrap().state().id().clone()).unwrap();
+  assert_eq!(did.log_url().path(), "/.well-known/did.jsonl");
+
+  let tampered: String = log.to_jsonl().unwrap().replace(
+    "\"state\":{",
+    "\"state\":{\"alsoKnownAs\":[\"https://mallory.example/\"],",
+  );
+  serve(listener, did.log_url().path().to_owned(), tampered);
+
+  let ErrorCause::HandlerError { source } = resolver(address).resolve(&did).await.unwrap_err().into_error_cause()
+  else {
+    unreachable!()
+  };
+  assert!(source.downcast_ref::<identity_webvh::Error>().is_some());
+}
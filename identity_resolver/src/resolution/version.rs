@@ -0,0 +1,206 @@
+// Copyright 2020-2024 IOTA Stiftung, Fondazione Links
+// SPDX-License-Identifier: Apache-2.0
+
+use std::collections::HashMap;
+use std::sync::RwLock;
+
+use async_trait::async_trait;
+use identity_core::common::Timestamp;
+use identity_did::CoreDID;
+use identity_did::DIDUrl;
+use identity_document::document::CoreDocument;
+
+use crate::Error;
+use crate::ErrorCause;
+use crate::Result;
+
+/// Name of the DID URL parameter selecting a document version by its id.
+pub const VERSION_ID_PARAM: &str = "versionId";
+/// Name of the DID URL parameter selecting the document version valid at a given time.
+pub const VERSION_TIME_PARAM: &str = "versionTime";
+
+/// Selects a specific version of a DID document, as expressed by the `versionId` and `versionTime`
+/// [DID parameters](https://www.w3.org/TR/did-core/#did-parameters).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum VersionQuery {
+  /// The version with the given id.
+  Id(String),
+  /// The latest version whose validity started at or before the given time.
+  Time(Timestamp),
+}
+
+impl VersionQuery {
+  /// Extracts the version query from the `versionId` or `versionTime` parameter of `did_url`.
+  ///
+  /// Returns `None` if neither parameter is present.
+  ///
+  /// # Errors
+  ///
+  /// Fails if both parameters are present, if either is repeated or if `versionTime` is not a valid timestamp.
+  pub fn from_did_url(did_url: &DIDUrl) -> Result<Option<Self>> {
+    let mut query: Option<Self> = None;
+    for (key, value) in did_url.query_pairs() {
+      let parsed: Self = match key.as_ref() {
+        VERSION_ID_PARAM => Self::Id(value.into_owned()),
+        VERSION_TIME_PARAM => Timestamp::parse(&value).map(Self::Time).map_err(|_| {
+          Error::new(ErrorCause::InvalidVersionQuery(format!(
+            "invalid {VERSION_TIME_PARAM} \"{value}\""
+          )))
+        })?,
+        _ => continue,
+      };
+      if query.replace(parsed).is_some() {
+        return Err(Error::new(ErrorCause::InvalidVersionQuery(format!(
+          "at most one of {VERSION_ID_PARAM} and {VERSION_TIME_PARAM} may be given"
+        ))));
+      }
+    }
+    Ok(query)
+  }
+
+  /// Returns the version in `versions` selected by this query.
+  ///
+  /// `versions` must be ordered from oldest to newest.
+  pub fn select<'v, DOC>(&self, versions: &'v [DocumentVersion<DOC>]) -> Option<&'v DocumentVersion<DOC>> {
+    self.position(versions).map(|index| &versions[index])
+  }
+
+  fn position<DOC>(&self, versions: &[DocumentVersion<DOC>]) -> Option<usize> {
+    match self {
+      Self::Id(version_id) => versions.iter().position(|version| &version.version_id == version_id),
+      Self::Time(time) => versions
+        .iter()
+        .take_while(|version| version.valid_from <= *time)
+        .count()
+        .checked_sub(1),
+    }
+  }
+}
+
+impl std::fmt::Display for VersionQuery {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    match self {
+      Self::Id(version_id) => write!(f, "{VERSION_ID_PARAM}={version_id}"),
+      Self::Time(time) => write!(f, "{VERSION_TIME_PARAM}={time}"),
+    }
+  }
+}
+
+/// A version of a DID document.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DocumentVersion<DOC = CoreDocument> {
+  /// The id of the version.
+  pub version_id: String,
+  /// The time from which this version is the latest one.
+  pub valid_from: Timestamp,
+  /// The DID document.
+  pub document: DOC,
+}
+
+/// Storage of the version history of DID documents whose method does not record it, such as `did:web` and
+/// `did:jwk`.
+///
+/// See [`Resolver::attach_versioned_store`](crate::Resolver::attach_versioned_store).
+#[async_trait]
+pub trait DocumentVersionStore<DOC>: Send + Sync {
+  /// Returns all versions of the document of `did`, ordered from oldest to newest.
+  ///
+  /// An unknown DID has no versions.
+  async fn document_versions(
+    &self,
+    did: &CoreDID,
+  ) -> std::result::Result<Vec<DocumentVersion<DOC>>, Box<dyn std::error::Error + Send + Sync + 'static>>;
+}
+
+/// An in-memory [`DocumentVersionStore`].
+///
+/// Version ids are assigned sequentially per DID, starting at `1`.
+#[derive(Debug)]
+pub struct DocumentVersionMemStore<DOC = CoreDocument> {
+  versions: RwLock<HashMap<CoreDID, Vec<DocumentVersion<DOC>>>>,
+}
+
+impl<DOC> DocumentVersionMemStore<DOC>
+where
+  DOC: AsRef<CoreDocument>,
+{
+  /// Creates an empty store.
+  pub fn new() -> Self {
+    Self {
+      versions: RwLock::new(HashMap::new()),
+    }
+  }
+
+  /// Adds `document` as the latest version of the document of its DID, valid from `valid_from`.
+  ///
+  /// Returns the id of the new version.
+  ///
+  /// # Errors
+  ///
+  /// Fails if `valid_from` is not later than the start of the current latest version.
+  pub fn publish(&self, document: DOC, valid_from: Timestamp) -> Result<String> {
+    let mut versions = self.versions.write().expect("version store lock poisoned");
+    let history: &mut Vec<DocumentVersion<DOC>> = versions.entry(document.as_ref().id().clone()).or_default();
+    if let Some(latest) = history.last() {
+      if valid_from <= latest.valid_from {
+        return Err(Error::new(ErrorCause::InvalidDocumentVersion(format!(
+          "version valid from {valid_from} does not follow version {} valid from {}",
+          latest.version_id, latest.valid_from
+        ))));
+      }
+    }
+
+    let version_id: String = (history.len() + 1).to_string();
+    history.push(DocumentVersion {
+      version_id: version_id.clone(),
+      valid_from,
+      document,
+    });
+    Ok(version_id)
+  }
+}
+
+impl<DOC> Default for DocumentVersionMemStore<DOC>
+where
+  DOC: AsRef<CoreDocument>,
+{
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
+#[async_trait]
+impl<DOC> DocumentVersionStore<DOC> for DocumentVersionMemStore<DOC>
+where
+  DOC: Clone + Send + Sync,
+{
+  async fn document_versions(
+    &self,
+    did: &CoreDID,
+  ) -> std::result::Result<Vec<DocumentVersion<DOC>>, Box<dyn std::error::Error + Send + Sync + 'static>> {
+    let versions = self.versions.read().expect("version store lock poisoned");
+    Ok(versions.get(did).cloned().unwrap_or_default())
+  }
+}
+
+/// Resolves the version of the document of `did` selected by `version`, or the latest one if `version` is `None`.
+pub(super) async fn resolve_from_store<DOC, S>(
+  store: &S,
+  did: CoreDID,
+  version: Option<VersionQuery>,
+) -> std::result::Result<DOC, Box<dyn std::error::Error + Send + Sync + 'static>>
+where
+  S: DocumentVersionStore<DOC> + ?Sized,
+{
+  let mut versions: Vec<DocumentVersion<DOC>> = store.document_versions(&did).await?;
+  let index: Option<usize> = match &version {
+    Some(query) => query.position(&versions),
+    None => versions.len().checked_sub(1),
+  };
+  let index: usize = index.ok_or_else(|| {
+    let selector: String = version.map(|query| format!("?{query}")).unwrap_or_default();
+    Error::new(ErrorCause::VersionNotFound(format!("{did}{selector}")))
+  })?;
+
+  Ok(versions.swap_remove(index).document)
+}
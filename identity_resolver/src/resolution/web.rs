@@ -0,0 +1,339 @@
+// Copyright 2020-2024 IOTA Stiftung, Fondazione Links
+// SPDX-License-Identifier: Apache-2.0
+
+use std::time::Duration;
+
+use identity_core::common::Url;
+use identity_core::convert::FromJson;
+use identity_did::CoreDID;
+use identity_did::WebDID;
+use identity_document::document::CoreDocument;
+use reqwest::header::HeaderValue;
+use reqwest::header::ACCEPT;
+use reqwest::header::CONTENT_TYPE;
+use reqwest::header::LOCATION;
+use reqwest::Response;
+use reqwest::StatusCode;
+
+/// The media types accepted for `did:web` documents by default.
+const DEFAULT_CONTENT_TYPES: [&str; 4] = [
+  "application/did+json",
+  "application/did+ld+json",
+  "application/json",
+  "application/ld+json",
+];
+
+/// Policy for following HTTP redirects while resolving `did:web` DIDs.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum WebRedirectPolicy {
+  /// Redirects are rejected.
+  None,
+  /// At most `max` redirects to the same scheme, host and port are followed.
+  SameOrigin {
+    /// The maximum number of redirects.
+    max: usize,
+  },
+  /// At most `max` redirects to any location allowed by the [`WebResolutionOptions`] are followed.
+  Any {
+    /// The maximum number of redirects.
+    max: usize,
+  },
+}
+
+/// Options of the hardened `did:web` resolution, see
+/// [`Resolver::attach_web_handler_with_options`](crate::Resolver::attach_web_handler_with_options).
+///
+/// The default options only fetch documents over HTTPS from domain names, reject redirects, accept responses of at
+/// most 1 MiB with a JSON content type and time out after 10 seconds.
+#[derive(Clone, Debug)]
+pub struct WebResolutionOptions {
+  pub(crate) https_only: bool,
+  pub(crate) allow_ip_hosts: bool,
+  pub(crate) redirect_policy: WebRedirectPolicy,
+  pub(crate) max_response_size: usize,
+  pub(crate) timeout: Duration,
+  pub(crate) content_types: Vec<String>,
+}
+
+impl WebResolutionOptions {
+  /// Creates the default [`WebResolutionOptions`].
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  /// Sets whether documents may only be fetched over HTTPS, as required by the `did:web` method specification.
+  ///
+  /// Disabling this is only meant for testing against local servers.
+  pub fn with_https_only(mut self, https_only: bool) -> Self {
+    self.https_only = https_only;
+    self
+  }
+
+  /// Sets whether DIDs and redirects may refer to IP addresses instead of domain names.
+  pub fn with_ip_hosts(mut self, allow_ip_hosts: bool) -> Self {
+    self.allow_ip_hosts = allow_ip_hosts;
+    self
+  }
+
+  /// Sets the policy for following redirects.
+  pub fn with_redirect_policy(mut self, redirect_policy: WebRedirectPolicy) -> Self {
+    self.redirect_policy = redirect_policy;
+    self
+  }
+
+  /// Sets the maximum size of a document in bytes.
+  pub fn with_max_response_size(mut self, max_response_size: usize) -> Self {
+    self.max_response_size = max_response_size;
+    self
+  }
+
+  /// Sets the timeout of each request, including reading the response.
+  pub fn with_timeout(mut self, timeout: Duration) -> Self {
+    self.timeout = timeout;
+    self
+  }
+
+  /// Sets the accepted media types of documents, e.g. `application/did+json`.
+  ///
+  /// Parameters of the `Content-Type` header, such as the charset, are ignored.
+  pub fn with_content_types<I, S>(mut self, content_types: I) -> Self
+  where
+    I: IntoIterator<Item = S>,
+    S: Into<String>,
+  {
+    self.content_types = content_types
+      .into_iter()
+      .map(|content_type| content_type.into().to_ascii_lowercase())
+      .collect();
+    self
+  }
+
+  /// Returns whether documents may only be fetched over HTTPS.
+  pub fn https_only(&self) -> bool {
+    self.https_only
+  }
+
+  /// Returns whether DIDs and redirects may refer to IP addresses.
+  pub fn ip_hosts(&self) -> bool {
+    self.allow_ip_hosts
+  }
+
+  /// Returns the policy for following redirects.
+  pub fn redirect_policy(&self) -> WebRedirectPolicy {
+    self.redirect_policy
+  }
+
+  /// Returns the maximum size of a document in bytes.
+  pub fn max_response_size(&self) -> usize {
+    self.max_response_size
+  }
+
+  /// Returns the timeout of each request.
+  pub fn timeout(&self) -> Duration {
+    self.timeout
+  }
+
+  /// Returns the accepted media types of documents.
+  pub fn content_types(&self) -> &[String] {
+    &self.content_types
+  }
+}
+
+impl Default for WebResolutionOptions {
+  fn default() -> Self {
+    Self {
+      https_only: true,
+      allow_ip_hosts: false,
+      redirect_policy: WebRedirectPolicy::None,
+      max_response_size: 1024 * 1024,
+      timeout: Duration::from_secs(10),
+      content_types: DEFAULT_CONTENT_TYPES
+        .iter()
+        .map(|&content_type| content_type.to_owned())
+        .collect(),
+    }
+  }
+}
+
+/// Cause of a failed hardened `did:web` resolution, returned as the source of
+/// [`ErrorCause::HandlerError`](crate::ErrorCause::HandlerError).
+#[derive(Debug, thiserror::Error, strum::IntoStaticStr)]
+#[non_exhaustive]
+pub enum WebResolutionError {
+  /// The DID is not a valid `did:web` DID.
+  #[error("invalid did:web DID")]
+  InvalidDID(#[source] identity_did::Error),
+  /// The document or a redirect is not located at an HTTPS URL.
+  #[error("refusing to fetch {0} over an insecure connection")]
+  InsecureUrl(Url),
+  /// The host of the DID or of a redirect is an IP address, which is not allowed.
+  #[error("the host of {0} is an IP address")]
+  IpHostNotAllowed(Url),
+  /// The server redirected to a location not allowed by the redirect policy.
+  #[error("redirect to {0} not allowed")]
+  RedirectNotAllowed(String),
+  /// The server redirected more often than allowed by the redirect policy.
+  #[error("more than {0} redirects")]
+  TooManyRedirects(usize),
+  /// The server responded with an unsuccessful status code.
+  #[error("unexpected HTTP status {0}")]
+  HttpStatus(u16),
+  /// The `Content-Type` of the response is missing or not accepted.
+  #[error("unexpected content type {0:?}")]
+  UnexpectedContentType(Option<String>),
+  /// The response is larger than allowed.
+  #[error("the response exceeds the maximum size of {0} bytes")]
+  ResponseTooLarge(usize),
+  /// The request timed out.
+  #[error("the request to {0} timed out")]
+  Timeout(Url),
+  /// The request failed, e.g. because the connection could not be established.
+  #[error("the request failed")]
+  Request(#[source] reqwest::Error),
+  /// The response is not a valid DID document.
+  #[error("invalid DID document")]
+  InvalidDocument(#[source] identity_core::Error),
+  /// The `id` of the document is not the resolved DID.
+  #[error("the id of the document is {actual}, expected {expected}")]
+  DocumentIdMismatch {
+    /// The resolved DID.
+    expected: CoreDID,
+    /// The `id` of the fetched document.
+    actual: CoreDID,
+  },
+}
+
+/// Fetches the document of `did` with the URL `scheme` following the policies of `options`.
+///
+/// `client` must not follow redirects on its own, which are validated and followed here.
+pub(crate) async fn resolve_web_did(
+  client: &reqwest::Client,
+  scheme: &str,
+  did: &CoreDID,
+  options: &WebResolutionOptions,
+) -> Result<CoreDocument, WebResolutionError> {
+  // IP addresses are rejected by `check_url` to report them as such.
+  let mut url: Url = WebDID::document_url(did, true).map_err(WebResolutionError::InvalidDID)?;
+  if url.set_scheme(scheme).is_err() {
+    return Err(WebResolutionError::InsecureUrl(url));
+  }
+  let mut redirects: usize = 0;
+  let response: Response = loop {
+    check_url(&url, options)?;
+    let response: Response = client
+      .get(url.as_str())
+      .header(ACCEPT, accept_header(options))
+      .timeout(options.timeout)
+      .send()
+      .await
+      .map_err(|err| request_error(err, &url))?;
+    if !response.status().is_redirection() {
+      break response;
+    }
+
+    let location: String = response
+      .headers()
+      .get(LOCATION)
+      .and_then(|location| location.to_str().ok())
+      .ok_or_else(|| WebResolutionError::HttpStatus(response.status().as_u16()))?
+      .to_owned();
+    let max: usize = match options.redirect_policy {
+      WebRedirectPolicy::None => return Err(WebResolutionError::RedirectNotAllowed(location)),
+      WebRedirectPolicy::SameOrigin { max } | WebRedirectPolicy::Any { max } => max,
+    };
+    if redirects == max {
+      return Err(WebResolutionError::TooManyRedirects(max));
+    }
+    let target: Url = url
+      .join(&location)
+      .map_err(|_| WebResolutionError::RedirectNotAllowed(location.clone()))?;
+    if matches!(options.redirect_policy, WebRedirectPolicy::SameOrigin { .. }) && target.origin() != url.origin() {
+      return Err(WebResolutionError::RedirectNotAllowed(location));
+    }
+    redirects += 1;
+    url = target;
+  };
+
+  if response.status() != StatusCode::OK {
+    return Err(WebResolutionError::HttpStatus(response.status().as_u16()));
+  }
+  check_content_type(&response, options)?;
+  let body: Vec<u8> = read_body(response, &url, options.max_response_size).await?;
+
+  let document: CoreDocument = CoreDocument::from_json_slice(&body).map_err(WebResolutionError::InvalidDocument)?;
+  if document.id() != did {
+    return Err(WebResolutionError::DocumentIdMismatch {
+      expected: did.clone(),
+      actual: document.id().clone(),
+    });
+  }
+  Ok(document)
+}
+
+/// Checks the scheme and host of a URL the document is fetched from.
+fn check_url(url: &Url, options: &WebResolutionOptions) -> Result<(), WebResolutionError> {
+  match url.scheme() {
+    "https" => (),
+    "http" if !options.https_only => (),
+    _ => return Err(WebResolutionError::InsecureUrl(url.clone())),
+  }
+  if url.domain().is_none() && !options.allow_ip_hosts {
+    return Err(WebResolutionError::IpHostNotAllowed(url.clone()));
+  }
+  Ok(())
+}
+
+fn check_content_type(response: &Response, options: &WebResolutionOptions) -> Result<(), WebResolutionError> {
+  let content_type: Option<String> = response
+    .headers()
+    .get(CONTENT_TYPE)
+    .and_then(|content_type| content_type.to_str().ok())
+    .map(|content_type| {
+      content_type
+        .split(';')
+        .next()
+        .unwrap_or_default()
+        .trim()
+        .to_ascii_lowercase()
+    });
+  match content_type {
+    Some(content_type) if options.content_types.contains(&content_type) => Ok(()),
+    content_type => Err(WebResolutionError::UnexpectedContentType(content_type)),
+  }
+}
+
+/// Reads the body of `response`, failing as soon as it exceeds `max_size` bytes.
+pub(super) async fn read_body(
+  mut response: Response,
+  url: &Url,
+  max_size: usize,
+) -> Result<Vec<u8>, WebResolutionError> {
+  if response
+    .content_length()
+    .map_or(false, |length| length > max_size as u64)
+  {
+    return Err(WebResolutionError::ResponseTooLarge(max_size));
+  }
+  let mut body: Vec<u8> = Vec::new();
+  while let Some(chunk) = response.chunk().await.map_err(|err| request_error(err, url))? {
+    if body.len() + chunk.len() > max_size {
+      return Err(WebResolutionError::ResponseTooLarge(max_size));
+    }
+    body.extend_from_slice(&chunk);
+  }
+  Ok(body)
+}
+
+fn accept_header(options: &WebResolutionOptions) -> HeaderValue {
+  HeaderValue::from_str(&options.content_types.join(", "))
+    .unwrap_or_else(|_| HeaderValue::from_static(DEFAULT_CONTENT_TYPES[0]))
+}
+
+pub(super) fn request_error(err: reqwest::Error, url: &Url) -> WebResolutionError {
+  if err.is_timeout() {
+    WebResolutionError::Timeout(url.clone())
+  } else {
+    WebResolutionError::Request(err)
+  }
+}
@@ -0,0 +1,98 @@
+// Copyright 2020-2024 IOTA Stiftung, Fondazione Links
+// SPDX-License-Identifier: Apache-2.0
+
+use identity_core::common::Url;
+#[cfg(feature = "status-list-2021")]
+use identity_credential::revocation::status_list_2021::StatusPurpose;
+use identity_did::DIDUrl;
+use serde::Deserialize;
+use serde::Serialize;
+
+/// The lifecycle state of a credential recorded in a
+/// [`CredentialRegistryStorage`](crate::credential_registry::CredentialRegistryStorage).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CredentialState {
+  /// The credential is valid.
+  Active,
+  /// The credential is temporarily invalid and may be reinstated.
+  Suspended,
+  /// The credential is permanently invalid.
+  Revoked,
+}
+
+/// The status list mechanism used to publish the state of the credentials mapped to a [`StatusListRecord`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type")]
+#[non_exhaustive]
+pub enum StatusListKind {
+  /// A `RevocationBitmap2022` embedded in the service `service_id` of the issuer's DID document.
+  ///
+  /// Both revoked and suspended credentials are marked in the bitmap.
+  #[serde(rename_all = "camelCase")]
+  RevocationBitmap2022 {
+    /// The identifier of the `RevocationBitmap2022` service.
+    service_id: DIDUrl,
+  },
+  /// A `StatusList2021Credential` published at `status_list_credential`.
+  ///
+  /// The `purpose` of the list determines whether its credentials may be revoked or suspended.
+  #[cfg(feature = "status-list-2021")]
+  #[serde(rename_all = "camelCase")]
+  StatusList2021 {
+    /// The URL the `StatusList2021Credential` is published at.
+    status_list_credential: Url,
+    /// The purpose of the status list.
+    status_purpose: StatusPurpose,
+    /// The number of entries of the status list.
+    num_entries: usize,
+  },
+}
+
+impl StatusListKind {
+  /// Returns the number of indices that can be allocated in a status list of this kind.
+  pub fn capacity(&self) -> u64 {
+    match self {
+      Self::RevocationBitmap2022 { .. } => u64::from(u32::MAX) + 1,
+      #[cfg(feature = "status-list-2021")]
+      Self::StatusList2021 { num_entries, .. } => (*num_entries as u64).min(u64::from(u32::MAX) + 1),
+    }
+  }
+}
+
+/// A status list managed by the issuer.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StatusListRecord {
+  /// The identifier of the status list within the registry.
+  pub id: String,
+  /// The mechanism used to publish the status list.
+  pub kind: StatusListKind,
+  /// The next index to be allocated.
+  pub next_index: u64,
+}
+
+impl StatusListRecord {
+  /// Creates a new [`StatusListRecord`] with no allocated indices.
+  pub fn new(id: impl Into<String>, kind: StatusListKind) -> Self {
+    Self {
+      id: id.into(),
+      kind,
+      next_index: 0,
+    }
+  }
+}
+
+/// An issued credential and the status list entry assigned to it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CredentialRecord {
+  /// The `id` of the issued credential.
+  pub credential_id: Url,
+  /// The identifier of the status list the credential is mapped to.
+  pub status_list_id: String,
+  /// The index of the credential in the status list.
+  pub index: u32,
+  /// The current state of the credential.
+  pub state: CredentialState,
+}
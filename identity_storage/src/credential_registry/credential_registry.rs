@@ -0,0 +1,272 @@
+// Copyright 2020-2024 IOTA Stiftung, Fondazione Links
+// SPDX-License-Identifier: Apache-2.0
+
+use identity_core::common::Url;
+use identity_credential::credential::Credential;
+#[cfg(feature = "status-list-2021")]
+use identity_credential::credential::Issuer;
+use identity_credential::credential::RevocationBitmapStatus;
+use identity_credential::credential::Status;
+#[cfg(feature = "status-list-2021")]
+use identity_credential::revocation::status_list_2021::StatusList2021;
+#[cfg(feature = "status-list-2021")]
+use identity_credential::revocation::status_list_2021::StatusList2021Credential;
+#[cfg(feature = "status-list-2021")]
+use identity_credential::revocation::status_list_2021::StatusList2021CredentialBuilder;
+#[cfg(feature = "status-list-2021")]
+use identity_credential::revocation::status_list_2021::StatusList2021Entry;
+#[cfg(feature = "status-list-2021")]
+use identity_credential::revocation::status_list_2021::StatusPurpose;
+use identity_credential::revocation::RevocationBitmap;
+use identity_did::DIDUrl;
+use identity_document::service::Service;
+
+use super::credential_record::CredentialRecord;
+use super::credential_record::CredentialState;
+use super::credential_record::StatusListKind;
+use super::credential_record::StatusListRecord;
+use super::credential_registry_error::CredentialRegistryError;
+use super::credential_registry_error::CredentialRegistryErrorKind;
+use super::credential_registry_storage::CredentialRegistryResult;
+use super::credential_registry_storage::CredentialRegistryStorage;
+
+/// Issuer-side service managing the lifecycle of issued credentials.
+///
+/// The registry allocates status list indices to newly issued credentials, records them in a
+/// [`CredentialRegistryStorage`] and changes their state by credential id. The published form of a
+/// status list is computed from the recorded states on demand, see
+/// [`CredentialRegistry::revocation_bitmap_service`] and `CredentialRegistry::status_list_2021_credential`.
+#[derive(Debug)]
+pub struct CredentialRegistry<S> {
+  storage: S,
+}
+
+impl<S: CredentialRegistryStorage> CredentialRegistry<S> {
+  /// Creates a new [`CredentialRegistry`] backed by `storage`.
+  pub fn new(storage: S) -> Self {
+    Self { storage }
+  }
+
+  /// Returns a reference to the underlying [`CredentialRegistryStorage`].
+  pub fn storage(&self) -> &S {
+    &self.storage
+  }
+
+  /// Registers a new, empty status list identified by `status_list_id`.
+  pub async fn create_status_list(
+    &self,
+    status_list_id: impl Into<String>,
+    kind: StatusListKind,
+  ) -> CredentialRegistryResult<StatusListRecord> {
+    #[cfg(feature = "status-list-2021")]
+    if let StatusListKind::StatusList2021 { num_entries, .. } = &kind {
+      // Reject sizes that cannot be encoded before anything is recorded.
+      StatusList2021::new(*num_entries).map_err(encoding_error)?;
+    }
+
+    let status_list: StatusListRecord = StatusListRecord::new(status_list_id, kind);
+    self.storage.insert_status_list(status_list.clone()).await?;
+    Ok(status_list)
+  }
+
+  /// Allocates an index of the status list identified by `status_list_id` to `credential`,
+  /// sets the `credentialStatus` of `credential` accordingly and records it as [`CredentialState::Active`].
+  ///
+  /// The credential must have an `id`, which is used to refer to it in subsequent state changes.
+  /// The credential must be signed only after calling this method.
+  pub async fn issue(
+    &self,
+    status_list_id: &str,
+    credential: &mut Credential,
+  ) -> CredentialRegistryResult<CredentialRecord> {
+    let credential_id: Url = credential.id.clone().ok_or_else(|| {
+      CredentialRegistryError::new(CredentialRegistryErrorKind::InvalidCredential)
+        .with_custom_message("the credential does not have an `id`")
+    })?;
+    match self.storage.get_credential(&credential_id).await {
+      Ok(_) => {
+        return Err(CredentialRegistryError::new(
+          CredentialRegistryErrorKind::CredentialAlreadyExists,
+        ))
+      }
+      Err(err) if matches!(err.kind(), CredentialRegistryErrorKind::CredentialNotFound) => (),
+      Err(err) => return Err(err),
+    }
+
+    let status_list: StatusListRecord = self.storage.get_status_list(status_list_id).await?;
+    let index: u32 = self.storage.allocate_index(status_list_id).await?;
+    let record: CredentialRecord = CredentialRecord {
+      credential_id,
+      status_list_id: status_list.id,
+      index,
+      state: CredentialState::Active,
+    };
+    self.storage.insert_credential(record.clone()).await?;
+
+    credential.credential_status = Some(credential_status(&status_list.kind, index));
+    Ok(record)
+  }
+
+  /// Returns the current state of the credential identified by `credential_id`.
+  pub async fn state(&self, credential_id: &Url) -> CredentialRegistryResult<CredentialState> {
+    Ok(self.storage.get_credential(credential_id).await?.state)
+  }
+
+  /// Permanently revokes the credential identified by `credential_id`.
+  ///
+  /// Both active and suspended credentials can be revoked.
+  pub async fn revoke(&self, credential_id: &Url) -> CredentialRegistryResult<CredentialRecord> {
+    self
+      .transition(credential_id, CredentialState::Revoked, |state| {
+        matches!(state, CredentialState::Active | CredentialState::Suspended)
+      })
+      .await
+  }
+
+  /// Suspends the active credential identified by `credential_id`.
+  pub async fn suspend(&self, credential_id: &Url) -> CredentialRegistryResult<CredentialRecord> {
+    self
+      .transition(credential_id, CredentialState::Suspended, |state| {
+        state == CredentialState::Active
+      })
+      .await
+  }
+
+  /// Reinstates the suspended credential identified by `credential_id`.
+  pub async fn unsuspend(&self, credential_id: &Url) -> CredentialRegistryResult<CredentialRecord> {
+    self
+      .transition(credential_id, CredentialState::Active, |state| {
+        state == CredentialState::Suspended
+      })
+      .await
+  }
+
+  /// Returns the [`RevocationBitmap`] of the `RevocationBitmap2022` status list identified by `status_list_id`,
+  /// with every revoked or suspended credential marked.
+  pub async fn revocation_bitmap(&self, status_list_id: &str) -> CredentialRegistryResult<RevocationBitmap> {
+    let status_list: StatusListRecord = self.storage.get_status_list(status_list_id).await?;
+    if !matches!(status_list.kind, StatusListKind::RevocationBitmap2022 { .. }) {
+      return Err(unsupported_kind(&status_list.id));
+    }
+
+    let mut bitmap: RevocationBitmap = RevocationBitmap::new();
+    for credential in self.storage.credentials(status_list_id).await? {
+      if credential.state != CredentialState::Active {
+        bitmap.revoke(credential.index);
+      }
+    }
+    Ok(bitmap)
+  }
+
+  /// Returns the `RevocationBitmap2022` [`Service`] of the status list identified by `status_list_id`,
+  /// ready to replace the corresponding service of the issuer's DID document.
+  pub async fn revocation_bitmap_service(&self, status_list_id: &str) -> CredentialRegistryResult<Service> {
+    let status_list: StatusListRecord = self.storage.get_status_list(status_list_id).await?;
+    let service_id: DIDUrl = match status_list.kind {
+      StatusListKind::RevocationBitmap2022 { service_id } => service_id,
+      #[allow(unreachable_patterns)]
+      _ => return Err(unsupported_kind(&status_list.id)),
+    };
+
+    self
+      .revocation_bitmap(status_list_id)
+      .await?
+      .to_service(service_id)
+      .map_err(encoding_error)
+  }
+
+  /// Returns the unsigned [`StatusList2021Credential`] of the `StatusList2021` status list identified by
+  /// `status_list_id`, issued by `issuer` and with every credential that is not active marked.
+  ///
+  /// The returned credential must be signed by the issuer before being published.
+  #[cfg(feature = "status-list-2021")]
+  pub async fn status_list_2021_credential(
+    &self,
+    status_list_id: &str,
+    issuer: Issuer,
+  ) -> CredentialRegistryResult<StatusList2021Credential> {
+    let status_list: StatusListRecord = self.storage.get_status_list(status_list_id).await?;
+    let StatusListKind::StatusList2021 {
+      status_list_credential,
+      status_purpose,
+      num_entries,
+    } = status_list.kind
+    else {
+      return Err(unsupported_kind(&status_list.id));
+    };
+
+    let mut list: StatusList2021 = StatusList2021::new(num_entries).map_err(encoding_error)?;
+    for credential in self.storage.credentials(status_list_id).await? {
+      if credential.state != CredentialState::Active {
+        list.set(credential.index as usize, true).map_err(encoding_error)?;
+      }
+    }
+
+    StatusList2021CredentialBuilder::new(list)
+      .purpose(status_purpose)
+      .subject_id(status_list_credential)
+      .issuer(issuer)
+      .build()
+      .map_err(encoding_error)
+  }
+
+  async fn transition(
+    &self,
+    credential_id: &Url,
+    target: CredentialState,
+    is_allowed_from: impl FnOnce(CredentialState) -> bool,
+  ) -> CredentialRegistryResult<CredentialRecord> {
+    let mut record: CredentialRecord = self.storage.get_credential(credential_id).await?;
+    if !is_allowed_from(record.state) {
+      return Err(
+        CredentialRegistryError::new(CredentialRegistryErrorKind::InvalidStateTransition)
+          .with_custom_message(format!("cannot go from {:?} to {target:?}", record.state)),
+      );
+    }
+
+    #[cfg(feature = "status-list-2021")]
+    if let StatusListKind::StatusList2021 { status_purpose, .. } =
+      self.storage.get_status_list(&record.status_list_id).await?.kind
+    {
+      let expressible: bool = match status_purpose {
+        StatusPurpose::Revocation => {
+          matches!(record.state, CredentialState::Active) && target == CredentialState::Revoked
+        }
+        StatusPurpose::Suspension => target != CredentialState::Revoked,
+      };
+      if !expressible {
+        return Err(
+          CredentialRegistryError::new(CredentialRegistryErrorKind::UnsupportedStatusPurpose)
+            .with_custom_message(format!("a {status_purpose} status list cannot express {target:?}")),
+        );
+      }
+    }
+
+    self.storage.set_credential_state(credential_id, target).await?;
+    record.state = target;
+    Ok(record)
+  }
+}
+
+fn credential_status(kind: &StatusListKind, index: u32) -> Status {
+  match kind {
+    StatusListKind::RevocationBitmap2022 { service_id } => {
+      RevocationBitmapStatus::new(service_id.clone(), index).into()
+    }
+    #[cfg(feature = "status-list-2021")]
+    StatusListKind::StatusList2021 {
+      status_list_credential,
+      status_purpose,
+      ..
+    } => StatusList2021Entry::new(status_list_credential.clone(), *status_purpose, index as usize, None).into(),
+  }
+}
+
+fn unsupported_kind(status_list_id: &str) -> CredentialRegistryError {
+  CredentialRegistryError::new(CredentialRegistryErrorKind::StatusListEncoding)
+    .with_custom_message(format!("status list `{status_list_id}` is of a different kind"))
+}
+
+fn encoding_error(err: impl std::error::Error + Send + Sync + 'static) -> CredentialRegistryError {
+  CredentialRegistryError::new(CredentialRegistryErrorKind::StatusListEncoding).with_source(err)
+}
@@ -0,0 +1,96 @@
+// Copyright 2020-2024 IOTA Stiftung, Fondazione Links
+// SPDX-License-Identifier: Apache-2.0
+
+use std::fmt::Display;
+
+use identity_core::common::SingleStructError;
+
+/// Error type for credential registry operations.
+pub type CredentialRegistryError = SingleStructError<CredentialRegistryErrorKind>;
+
+/// The cause of the failed credential registry operation.
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub enum CredentialRegistryErrorKind {
+  /// Indicates that the registry is not able to find the requested status list.
+  StatusListNotFound,
+
+  /// Indicates that a status list with the same identifier already exists in the registry.
+  StatusListAlreadyExists,
+
+  /// Indicates that all the indices of a status list have already been allocated.
+  StatusListExhausted,
+
+  /// Indicates that the registry is not able to find the requested credential.
+  CredentialNotFound,
+
+  /// Indicates that a credential with the same identifier has already been recorded.
+  CredentialAlreadyExists,
+
+  /// Indicates that the credential cannot be recorded, e.g. because it does not have an `id`.
+  InvalidCredential,
+
+  /// Indicates that the requested change of state is not allowed from the current state of the credential,
+  /// e.g. when unsuspending a revoked credential.
+  InvalidStateTransition,
+
+  /// Indicates that the status list the credential is mapped to cannot express the requested state,
+  /// e.g. when suspending a credential mapped to a `StatusList2021` used for revocation.
+  UnsupportedStatusPurpose,
+
+  /// Indicates a failure to produce the status list or the service to be published.
+  StatusListEncoding,
+
+  /// Indicates that the storage is unavailable for an unpredictable amount of time.
+  Unavailable,
+
+  /// Indicates an unsuccessful I/O operation that may be retried, such as a temporary connection failure or timeouts.
+  ///
+  /// Returning this error signals to the caller that the operation may be retried with a chance of success.
+  /// It is at the caller's discretion whether to retry or not, and how often.
+  RetryableIOFailure,
+
+  /// Indicates a failure to serialize or deserialize.
+  SerializationError,
+
+  /// Indicates that something went wrong, but it is unclear whether the reason matches any of the other variants.
+  ///
+  /// When using this variant one may want to attach additional context to the corresponding
+  /// [`CredentialRegistryError`]. See
+  /// [`CredentialRegistryError::with_custom_message`](CredentialRegistryError::with_custom_message()) and
+  /// [`CredentialRegistryError::with_source`](CredentialRegistryError::with_source()).
+  Unspecified,
+}
+
+impl CredentialRegistryErrorKind {
+  /// Returns the string representation of the error.
+  pub const fn as_str(&self) -> &str {
+    match self {
+      Self::StatusListNotFound => "status list not found in the credential registry",
+      Self::StatusListAlreadyExists => "status list already exists in the credential registry",
+      Self::StatusListExhausted => "all the indices of the status list have been allocated",
+      Self::CredentialNotFound => "credential not found in the credential registry",
+      Self::CredentialAlreadyExists => "credential already exists in the credential registry",
+      Self::InvalidCredential => "the credential cannot be recorded in the credential registry",
+      Self::InvalidStateTransition => "the credential cannot transition to the requested state",
+      Self::UnsupportedStatusPurpose => "the status list of the credential cannot express the requested state",
+      Self::StatusListEncoding => "the status list could not be encoded",
+      Self::Unavailable => "credential registry storage unavailable",
+      Self::RetryableIOFailure => "credential registry storage was unsuccessful because of an I/O failure",
+      Self::SerializationError => "(de)serialization error",
+      Self::Unspecified => "credential registry operation failed",
+    }
+  }
+}
+
+impl AsRef<str> for CredentialRegistryErrorKind {
+  fn as_ref(&self) -> &str {
+    self.as_str()
+  }
+}
+
+impl Display for CredentialRegistryErrorKind {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    write!(f, "{}", self.as_str())
+  }
+}
@@ -0,0 +1,62 @@
+// Copyright 2020-2024 IOTA Stiftung, Fondazione Links
+// SPDX-License-Identifier: Apache-2.0
+
+use async_trait::async_trait;
+use identity_core::common::Url;
+
+use super::credential_record::CredentialRecord;
+use super::credential_record::CredentialState;
+use super::credential_record::StatusListRecord;
+use super::credential_registry_error::CredentialRegistryError;
+
+/// Result of credential registry operations.
+pub type CredentialRegistryResult<T> = Result<T, CredentialRegistryError>;
+
+/// Persistent storage for the status lists of an issuer and the credentials mapped to them.
+#[cfg_attr(not(feature = "send-sync-storage"), async_trait(?Send))]
+#[cfg_attr(feature = "send-sync-storage", async_trait)]
+pub trait CredentialRegistryStorage: storage_sub_trait::StorageSendSyncMaybe {
+  /// Insert a new [`StatusListRecord`].
+  ///
+  /// If a status list with the same id already exists in the storage an error must be returned
+  /// immediately without altering the state of the storage.
+  async fn insert_status_list(&self, status_list: StatusListRecord) -> CredentialRegistryResult<()>;
+
+  /// Obtain the [`StatusListRecord`] identified by `status_list_id`.
+  async fn get_status_list(&self, status_list_id: &str) -> CredentialRegistryResult<StatusListRecord>;
+
+  /// Reserve the next free index of the status list identified by `status_list_id`.
+  ///
+  /// Implementations must never return the same index twice for the same status list and must
+  /// return an error once the capacity of the status list has been reached.
+  async fn allocate_index(&self, status_list_id: &str) -> CredentialRegistryResult<u32>;
+
+  /// Insert a new [`CredentialRecord`].
+  ///
+  /// If a credential with the same id already exists in the storage an error must be returned
+  /// immediately without altering the state of the storage.
+  async fn insert_credential(&self, credential: CredentialRecord) -> CredentialRegistryResult<()>;
+
+  /// Obtain the [`CredentialRecord`] of the credential identified by `credential_id`.
+  async fn get_credential(&self, credential_id: &Url) -> CredentialRegistryResult<CredentialRecord>;
+
+  /// Set the state of the credential identified by `credential_id`.
+  ///
+  /// If the credential is not found in storage, an error must be returned.
+  async fn set_credential_state(&self, credential_id: &Url, state: CredentialState) -> CredentialRegistryResult<()>;
+
+  /// Obtain all the [`CredentialRecord`]s mapped to the status list identified by `status_list_id`.
+  async fn credentials(&self, status_list_id: &str) -> CredentialRegistryResult<Vec<CredentialRecord>>;
+}
+
+#[cfg(not(feature = "send-sync-storage"))]
+mod storage_sub_trait {
+  pub trait StorageSendSyncMaybe {}
+  impl<S: super::CredentialRegistryStorage> StorageSendSyncMaybe for S {}
+}
+
+#[cfg(feature = "send-sync-storage")]
+mod storage_sub_trait {
+  pub trait StorageSendSyncMaybe: Send + Sync {}
+  impl<S: Send + Sync + super::CredentialRegistryStorage> StorageSendSyncMaybe for S {}
+}
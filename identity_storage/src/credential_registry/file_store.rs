@@ -0,0 +1,118 @@
+// Copyright 2020-2024 IOTA Stiftung, Fondazione Links
+// SPDX-License-Identifier: Apache-2.0
+
+use std::ffi::OsString;
+use std::io::ErrorKind;
+use std::path::Path;
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+use async_trait::async_trait;
+use identity_core::common::Url;
+
+use super::credential_record::CredentialRecord;
+use super::credential_record::CredentialState;
+use super::credential_record::StatusListRecord;
+use super::credential_registry_error::CredentialRegistryError;
+use super::credential_registry_error::CredentialRegistryErrorKind;
+use super::credential_registry_storage::CredentialRegistryResult;
+use super::credential_registry_storage::CredentialRegistryStorage;
+use super::registry_state::RegistryState;
+
+/// A [`CredentialRegistryStorage`] implementation persisting the registry as a JSON file.
+///
+/// Every operation reads the file, applies the change and atomically replaces the file,
+/// so the registry survives restarts of the issuer. The file is created on the first write.
+///
+/// Concurrent access is only synchronized within the same `CredentialRegistryFileStore` instance.
+#[derive(Debug)]
+pub struct CredentialRegistryFileStore {
+  path: PathBuf,
+  lock: Mutex<()>,
+}
+
+impl CredentialRegistryFileStore {
+  /// Creates a new `CredentialRegistryFileStore` persisting the registry at `path`.
+  pub fn new(path: impl Into<PathBuf>) -> Self {
+    Self {
+      path: path.into(),
+      lock: Mutex::new(()),
+    }
+  }
+
+  /// Returns the path of the file the registry is persisted at.
+  pub fn path(&self) -> &Path {
+    &self.path
+  }
+
+  fn read<T>(&self, f: impl FnOnce(&RegistryState) -> CredentialRegistryResult<T>) -> CredentialRegistryResult<T> {
+    let _guard = self.lock()?;
+    f(&self.load()?)
+  }
+
+  fn write<T>(&self, f: impl FnOnce(&mut RegistryState) -> CredentialRegistryResult<T>) -> CredentialRegistryResult<T> {
+    let _guard = self.lock()?;
+    let mut state: RegistryState = self.load()?;
+    let output: T = f(&mut state)?;
+    self.save(&state)?;
+    Ok(output)
+  }
+
+  fn lock(&self) -> CredentialRegistryResult<std::sync::MutexGuard<'_, ()>> {
+    self.lock.lock().map_err(|_| {
+      CredentialRegistryError::new(CredentialRegistryErrorKind::Unavailable)
+        .with_custom_message("the credential registry lock is poisoned")
+    })
+  }
+
+  fn load(&self) -> CredentialRegistryResult<RegistryState> {
+    match std::fs::read(&self.path) {
+      Ok(bytes) => serde_json::from_slice(&bytes)
+        .map_err(|err| CredentialRegistryError::new(CredentialRegistryErrorKind::SerializationError).with_source(err)),
+      Err(err) if err.kind() == ErrorKind::NotFound => Ok(RegistryState::default()),
+      Err(err) => Err(CredentialRegistryError::new(CredentialRegistryErrorKind::RetryableIOFailure).with_source(err)),
+    }
+  }
+
+  fn save(&self, state: &RegistryState) -> CredentialRegistryResult<()> {
+    let bytes: Vec<u8> = serde_json::to_vec_pretty(state)
+      .map_err(|err| CredentialRegistryError::new(CredentialRegistryErrorKind::SerializationError).with_source(err))?;
+    let mut tmp_path: OsString = self.path.clone().into_os_string();
+    tmp_path.push(".tmp");
+    std::fs::write(&tmp_path, bytes)
+      .and_then(|_| std::fs::rename(&tmp_path, &self.path))
+      .map_err(|err| CredentialRegistryError::new(CredentialRegistryErrorKind::RetryableIOFailure).with_source(err))
+  }
+}
+
+#[cfg_attr(not(feature = "send-sync-storage"), async_trait(?Send))]
+#[cfg_attr(feature = "send-sync-storage", async_trait)]
+impl CredentialRegistryStorage for CredentialRegistryFileStore {
+  async fn insert_status_list(&self, status_list: StatusListRecord) -> CredentialRegistryResult<()> {
+    self.write(|state| state.insert_status_list(status_list))
+  }
+
+  async fn get_status_list(&self, status_list_id: &str) -> CredentialRegistryResult<StatusListRecord> {
+    self.read(|state| state.get_status_list(status_list_id))
+  }
+
+  async fn allocate_index(&self, status_list_id: &str) -> CredentialRegistryResult<u32> {
+    self.write(|state| state.allocate_index(status_list_id))
+  }
+
+  async fn insert_credential(&self, credential: CredentialRecord) -> CredentialRegistryResult<()> {
+    self.write(|state| state.insert_credential(credential))
+  }
+
+  async fn get_credential(&self, credential_id: &Url) -> CredentialRegistryResult<CredentialRecord> {
+    self.read(|state| state.get_credential(credential_id))
+  }
+
+  async fn set_credential_state(&self, credential_id: &Url, state: CredentialState) -> CredentialRegistryResult<()> {
+    self.write(|registry| registry.set_credential_state(credential_id, state))
+  }
+
+  async fn credentials(&self, status_list_id: &str) -> CredentialRegistryResult<Vec<CredentialRecord>> {
+    self.read(|state| state.credentials(status_list_id))
+  }
+}
@@ -0,0 +1,66 @@
+// Copyright 2020-2024 IOTA Stiftung, Fondazione Links
+// SPDX-License-Identifier: Apache-2.0
+
+use async_trait::async_trait;
+use identity_core::common::Url;
+
+use super::credential_record::CredentialRecord;
+use super::credential_record::CredentialState;
+use super::credential_record::StatusListRecord;
+use super::credential_registry_storage::CredentialRegistryResult;
+use super::credential_registry_storage::CredentialRegistryStorage;
+use super::registry_state::RegistryState;
+use crate::key_storage::shared::Shared;
+
+/// An in-memory [`CredentialRegistryStorage`] implementation that serves as an example and may be used in tests.
+#[derive(Debug)]
+pub struct CredentialRegistryMemstore {
+  state: Shared<RegistryState>,
+}
+
+impl CredentialRegistryMemstore {
+  /// Creates a new, empty `CredentialRegistryMemstore` instance.
+  pub fn new() -> Self {
+    Self {
+      state: Shared::new(RegistryState::default()),
+    }
+  }
+}
+
+impl Default for CredentialRegistryMemstore {
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
+#[cfg_attr(not(feature = "send-sync-storage"), async_trait(?Send))]
+#[cfg_attr(feature = "send-sync-storage", async_trait)]
+impl CredentialRegistryStorage for CredentialRegistryMemstore {
+  async fn insert_status_list(&self, status_list: StatusListRecord) -> CredentialRegistryResult<()> {
+    self.state.write().await.insert_status_list(status_list)
+  }
+
+  async fn get_status_list(&self, status_list_id: &str) -> CredentialRegistryResult<StatusListRecord> {
+    self.state.read().await.get_status_list(status_list_id)
+  }
+
+  async fn allocate_index(&self, status_list_id: &str) -> CredentialRegistryResult<u32> {
+    self.state.write().await.allocate_index(status_list_id)
+  }
+
+  async fn insert_credential(&self, credential: CredentialRecord) -> CredentialRegistryResult<()> {
+    self.state.write().await.insert_credential(credential)
+  }
+
+  async fn get_credential(&self, credential_id: &Url) -> CredentialRegistryResult<CredentialRecord> {
+    self.state.read().await.get_credential(credential_id)
+  }
+
+  async fn set_credential_state(&self, credential_id: &Url, state: CredentialState) -> CredentialRegistryResult<()> {
+    self.state.write().await.set_credential_state(credential_id, state)
+  }
+
+  async fn credentials(&self, status_list_id: &str) -> CredentialRegistryResult<Vec<CredentialRecord>> {
+    self.state.read().await.credentials(status_list_id)
+  }
+}
@@ -0,0 +1,33 @@
+// Copyright 2020-2024 IOTA Stiftung, Fondazione Links
+// SPDX-License-Identifier: Apache-2.0
+
+//! A Credential Registry keeps track of the credentials issued by an issuer
+//! and of the status list entries assigned to them.
+//!
+//! This module provides the [`CredentialRegistryStorage`] trait, which persists
+//! [`StatusListRecord`]s and [`CredentialRecord`]s, together with the
+//! [`CredentialRegistry`] service that allocates status list indices, changes the
+//! lifecycle state of issued credentials and produces the updated
+//! `RevocationBitmap2022` service or `StatusList2021Credential` ready to be published.
+
+mod credential_record;
+#[allow(clippy::module_inception)]
+mod credential_registry;
+mod credential_registry_error;
+mod credential_registry_storage;
+mod file_store;
+mod registry_state;
+
+#[cfg(feature = "memstore")]
+mod memstore;
+
+#[cfg(all(test, feature = "memstore"))]
+mod tests;
+
+pub use credential_record::*;
+pub use credential_registry::*;
+pub use credential_registry_error::*;
+pub use credential_registry_storage::*;
+pub use file_store::*;
+#[cfg(feature = "memstore")]
+pub use memstore::*;
@@ -0,0 +1,112 @@
+// Copyright 2020-2024 IOTA Stiftung, Fondazione Links
+// SPDX-License-Identifier: Apache-2.0
+
+use std::collections::BTreeMap;
+
+use identity_core::common::Url;
+use serde::Deserialize;
+use serde::Serialize;
+
+use super::credential_record::CredentialRecord;
+use super::credential_record::CredentialState;
+use super::credential_record::StatusListRecord;
+use super::credential_registry_error::CredentialRegistryError;
+use super::credential_registry_error::CredentialRegistryErrorKind;
+use super::credential_registry_storage::CredentialRegistryResult;
+
+/// The content of a credential registry, shared by the in-memory and file-backed storages.
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct RegistryState {
+  status_lists: BTreeMap<String, StatusListRecord>,
+  credentials: BTreeMap<String, CredentialRecord>,
+}
+
+impl RegistryState {
+  pub(crate) fn insert_status_list(&mut self, status_list: StatusListRecord) -> CredentialRegistryResult<()> {
+    if self.status_lists.contains_key(&status_list.id) {
+      return Err(CredentialRegistryError::new(
+        CredentialRegistryErrorKind::StatusListAlreadyExists,
+      ));
+    }
+    self.status_lists.insert(status_list.id.clone(), status_list);
+    Ok(())
+  }
+
+  pub(crate) fn get_status_list(&self, status_list_id: &str) -> CredentialRegistryResult<StatusListRecord> {
+    self
+      .status_lists
+      .get(status_list_id)
+      .cloned()
+      .ok_or_else(|| CredentialRegistryError::new(CredentialRegistryErrorKind::StatusListNotFound))
+  }
+
+  pub(crate) fn allocate_index(&mut self, status_list_id: &str) -> CredentialRegistryResult<u32> {
+    let status_list: &mut StatusListRecord = self
+      .status_lists
+      .get_mut(status_list_id)
+      .ok_or_else(|| CredentialRegistryError::new(CredentialRegistryErrorKind::StatusListNotFound))?;
+    if status_list.next_index >= status_list.kind.capacity() {
+      return Err(CredentialRegistryError::new(
+        CredentialRegistryErrorKind::StatusListExhausted,
+      ));
+    }
+    let index: u32 = u32::try_from(status_list.next_index)
+      .map_err(|_| CredentialRegistryError::new(CredentialRegistryErrorKind::StatusListExhausted))?;
+    status_list.next_index += 1;
+    Ok(index)
+  }
+
+  pub(crate) fn insert_credential(&mut self, credential: CredentialRecord) -> CredentialRegistryResult<()> {
+    if !self.status_lists.contains_key(&credential.status_list_id) {
+      return Err(CredentialRegistryError::new(
+        CredentialRegistryErrorKind::StatusListNotFound,
+      ));
+    }
+    let key: String = credential.credential_id.to_string();
+    if self.credentials.contains_key(&key) {
+      return Err(CredentialRegistryError::new(
+        CredentialRegistryErrorKind::CredentialAlreadyExists,
+      ));
+    }
+    self.credentials.insert(key, credential);
+    Ok(())
+  }
+
+  pub(crate) fn get_credential(&self, credential_id: &Url) -> CredentialRegistryResult<CredentialRecord> {
+    self
+      .credentials
+      .get(credential_id.as_str())
+      .cloned()
+      .ok_or_else(|| CredentialRegistryError::new(CredentialRegistryErrorKind::CredentialNotFound))
+  }
+
+  pub(crate) fn set_credential_state(
+    &mut self,
+    credential_id: &Url,
+    state: CredentialState,
+  ) -> CredentialRegistryResult<()> {
+    self
+      .credentials
+      .get_mut(credential_id.as_str())
+      .ok_or_else(|| CredentialRegistryError::new(CredentialRegistryErrorKind::CredentialNotFound))?
+      .state = state;
+    Ok(())
+  }
+
+  pub(crate) fn credentials(&self, status_list_id: &str) -> CredentialRegistryResult<Vec<CredentialRecord>> {
+    if !self.status_lists.contains_key(status_list_id) {
+      return Err(CredentialRegistryError::new(
+        CredentialRegistryErrorKind::StatusListNotFound,
+      ));
+    }
+    Ok(
+      self
+        .credentials
+        .values()
+        .filter(|credential| credential.status_list_id == status_list_id)
+        .cloned()
+        .collect(),
+    )
+  }
+}
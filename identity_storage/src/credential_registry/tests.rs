@@ -0,0 +1,195 @@
+// Copyright 2020-2024 IOTA Stiftung, Fondazione Links
+// SPDX-License-Identifier: Apache-2.0
+
+use identity_core::common::Url;
+use identity_core::convert::FromJson;
+use identity_credential::credential::Credential;
+use identity_credential::credential::CredentialBuilder;
+use identity_credential::credential::RevocationBitmapStatus;
+use identity_credential::credential::Subject;
+use identity_credential::revocation::RevocationBitmap;
+use identity_did::DIDUrl;
+use identity_document::service::Service;
+use serde_json::json;
+
+use super::*;
+
+fn credential(id: Option<&str>) -> Credential {
+  let mut builder = CredentialBuilder::default()
+    .issuer(Url::parse("did:example:issuer").unwrap())
+    .type_("UniversityDegreeCredential")
+    .subject(Subject::from_json_value(json!({ "id": "did:example:holder", "degree": "BSc" })).unwrap());
+  if let Some(id) = id {
+    builder = builder.id(Url::parse(id).unwrap());
+  }
+  builder.build().unwrap()
+}
+
+fn bitmap_kind() -> StatusListKind {
+  StatusListKind::RevocationBitmap2022 {
+    service_id: DIDUrl::parse("did:example:issuer#revocation").unwrap(),
+  }
+}
+
+async fn bitmap_from_registry<S: CredentialRegistryStorage>(registry: &CredentialRegistry<S>) -> RevocationBitmap {
+  let service: Service = registry.revocation_bitmap_service("revocation").await.unwrap();
+  RevocationBitmap::try_from(&service).unwrap()
+}
+
+#[tokio::test]
+async fn revocation_bitmap_lifecycle() {
+  let registry = CredentialRegistry::new(CredentialRegistryMemstore::new());
+  registry.create_status_list("revocation", bitmap_kind()).await.unwrap();
+
+  let mut first: Credential = credential(Some("https://example.edu/credentials/1"));
+  let mut second: Credential = credential(Some("https://example.edu/credentials/2"));
+  let first_record: CredentialRecord = registry.issue("revocation", &mut first).await.unwrap();
+  let second_record: CredentialRecord = registry.issue("revocation", &mut second).await.unwrap();
+  assert_eq!(first_record.index, 0);
+  assert_eq!(second_record.index, 1);
+
+  let status: RevocationBitmapStatus =
+    RevocationBitmapStatus::try_from(second.credential_status.clone().unwrap()).unwrap();
+  assert_eq!(status.index().unwrap(), 1);
+  assert_eq!(status.id().unwrap().fragment(), Some("revocation"));
+
+  let first_id: Url = first.id.clone().unwrap();
+  let second_id: Url = second.id.clone().unwrap();
+  registry.revoke(&first_id).await.unwrap();
+  registry.suspend(&second_id).await.unwrap();
+  assert_eq!(registry.state(&first_id).await.unwrap(), CredentialState::Revoked);
+  assert_eq!(registry.state(&second_id).await.unwrap(), CredentialState::Suspended);
+
+  let bitmap: RevocationBitmap = bitmap_from_registry(&registry).await;
+  assert!(bitmap.is_revoked(0));
+  assert!(bitmap.is_revoked(1));
+
+  registry.unsuspend(&second_id).await.unwrap();
+  let bitmap: RevocationBitmap = bitmap_from_registry(&registry).await;
+  assert!(bitmap.is_revoked(0));
+  assert!(!bitmap.is_revoked(1));
+
+  // A revoked credential cannot be reinstated.
+  let err: CredentialRegistryError = registry.unsuspend(&first_id).await.unwrap_err();
+  assert!(matches!(
+    err.kind(),
+    CredentialRegistryErrorKind::InvalidStateTransition
+  ));
+  let err: CredentialRegistryError = registry.suspend(&first_id).await.unwrap_err();
+  assert!(matches!(
+    err.kind(),
+    CredentialRegistryErrorKind::InvalidStateTransition
+  ));
+}
+
+#[tokio::test]
+async fn issue_rejects_invalid_credentials() {
+  let registry = CredentialRegistry::new(CredentialRegistryMemstore::new());
+  registry.create_status_list("revocation", bitmap_kind()).await.unwrap();
+
+  let err: CredentialRegistryError = registry.issue("revocation", &mut credential(None)).await.unwrap_err();
+  assert!(matches!(err.kind(), CredentialRegistryErrorKind::InvalidCredential));
+
+  let mut issued: Credential = credential(Some("https://example.edu/credentials/1"));
+  registry.issue("revocation", &mut issued).await.unwrap();
+  let err: CredentialRegistryError = registry.issue("revocation", &mut issued).await.unwrap_err();
+  assert!(matches!(
+    err.kind(),
+    CredentialRegistryErrorKind::CredentialAlreadyExists
+  ));
+
+  let mut unknown_list: Credential = credential(Some("https://example.edu/credentials/2"));
+  let err: CredentialRegistryError = registry.issue("unknown", &mut unknown_list).await.unwrap_err();
+  assert!(matches!(err.kind(), CredentialRegistryErrorKind::StatusListNotFound));
+  assert!(unknown_list.credential_status.is_none());
+}
+
+#[tokio::test]
+async fn file_store_persists_registry() {
+  let path = std::env::temp_dir().join(format!("identity_credential_registry_{}.json", std::process::id()));
+  let _ = std::fs::remove_file(&path);
+
+  let mut first: Credential = credential(Some("https://example.edu/credentials/1"));
+  {
+    let registry = CredentialRegistry::new(CredentialRegistryFileStore::new(&path));
+    registry.create_status_list("revocation", bitmap_kind()).await.unwrap();
+    registry.issue("revocation", &mut first).await.unwrap();
+  }
+
+  let registry = CredentialRegistry::new(CredentialRegistryFileStore::new(&path));
+  let mut second: Credential = credential(Some("https://example.edu/credentials/2"));
+  assert_eq!(registry.issue("revocation", &mut second).await.unwrap().index, 1);
+  registry.revoke(first.id.as_ref().unwrap()).await.unwrap();
+
+  let bitmap: RevocationBitmap = bitmap_from_registry(&registry).await;
+  assert!(bitmap.is_revoked(0));
+  assert!(!bitmap.is_revoked(1));
+
+  std::fs::remove_file(&path).unwrap();
+}
+
+#[cfg(feature = "status-list-2021")]
+#[tokio::test]
+async fn status_list_2021_lifecycle() {
+  use identity_credential::credential::Issuer;
+  use identity_credential::revocation::status_list_2021::CredentialStatus;
+  use identity_credential::revocation::status_list_2021::StatusList2021Credential;
+  use identity_credential::revocation::status_list_2021::StatusList2021Entry;
+  use identity_credential::revocation::status_list_2021::StatusPurpose;
+
+  let registry = CredentialRegistry::new(CredentialRegistryMemstore::new());
+  let status_list_url: Url = Url::parse("https://example.edu/status/1").unwrap();
+  for (id, status_purpose) in [
+    ("revocation", StatusPurpose::Revocation),
+    ("suspension", StatusPurpose::Suspension),
+  ] {
+    let kind: StatusListKind = StatusListKind::StatusList2021 {
+      status_list_credential: status_list_url.join(id).unwrap(),
+      status_purpose,
+      num_entries: 131_072,
+    };
+    registry.create_status_list(id, kind).await.unwrap();
+  }
+
+  let mut revocable: Credential = credential(Some("https://example.edu/credentials/1"));
+  let mut suspendable: Credential = credential(Some("https://example.edu/credentials/2"));
+  registry.issue("revocation", &mut revocable).await.unwrap();
+  registry.issue("suspension", &mut suspendable).await.unwrap();
+  let entry: StatusList2021Entry =
+    StatusList2021Entry::try_from(revocable.credential_status.as_ref().unwrap()).unwrap();
+  assert_eq!(entry.index(), 0);
+
+  let revocable_id: Url = revocable.id.clone().unwrap();
+  let suspendable_id: Url = suspendable.id.clone().unwrap();
+  let err: CredentialRegistryError = registry.suspend(&revocable_id).await.unwrap_err();
+  assert!(matches!(
+    err.kind(),
+    CredentialRegistryErrorKind::UnsupportedStatusPurpose
+  ));
+  let err: CredentialRegistryError = registry.revoke(&suspendable_id).await.unwrap_err();
+  assert!(matches!(
+    err.kind(),
+    CredentialRegistryErrorKind::UnsupportedStatusPurpose
+  ));
+
+  registry.revoke(&revocable_id).await.unwrap();
+  registry.suspend(&suspendable_id).await.unwrap();
+
+  let issuer: Issuer = Issuer::Url(Url::parse("did:example:issuer").unwrap());
+  let revocation_list: StatusList2021Credential = registry
+    .status_list_2021_credential("revocation", issuer.clone())
+    .await
+    .unwrap();
+  assert_eq!(revocation_list.entry(0).unwrap(), CredentialStatus::Revoked);
+  assert_eq!(revocation_list.purpose(), StatusPurpose::Revocation);
+
+  registry.unsuspend(&suspendable_id).await.unwrap();
+  let suspension_list: StatusList2021Credential = registry
+    .status_list_2021_credential("suspension", issuer)
+    .await
+    .unwrap();
+  assert_eq!(suspension_list.entry(0).unwrap(), CredentialStatus::Valid);
+
+  let err: CredentialRegistryError = registry.revocation_bitmap_service("revocation").await.unwrap_err();
+  assert!(matches!(err.kind(), CredentialRegistryErrorKind::StatusListEncoding));
+}
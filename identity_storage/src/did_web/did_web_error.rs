@@ -0,0 +1,79 @@
+// Copyright 2020-2024 IOTA Stiftung, Fondazione Links
+// SPDX-License-Identifier: Apache-2.0
+
+use std::fmt::Display;
+
+use identity_core::common::SingleStructError;
+
+/// Error type for `did:web` publishing operations.
+pub type DidWebError = SingleStructError<DidWebErrorKind>;
+
+/// The cause of the failed `did:web` publishing operation.
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub enum DidWebErrorKind {
+  /// Indicates that the `id` of the document is not a valid `did:web` DID.
+  InvalidDID,
+
+  /// Indicates that the `id` of the document does not match the URL it is published at.
+  DocumentMismatch,
+
+  /// Indicates that no document is published at the requested location.
+  DocumentNotFound,
+
+  /// Indicates that a document is already published at the requested location.
+  DocumentAlreadyExists,
+
+  /// Indicates that the publisher cannot write to the location of the document,
+  /// e.g. because its path cannot be mapped to a file.
+  UnsupportedLocation,
+
+  /// Indicates that the publisher is unavailable for an unpredictable amount of time.
+  Unavailable,
+
+  /// Indicates an unsuccessful I/O operation that may be retried, such as a temporary connection failure or timeouts.
+  ///
+  /// Returning this error signals to the caller that the operation may be retried with a chance of success.
+  /// It is at the caller's discretion whether to retry or not, and how often.
+  RetryableIOFailure,
+
+  /// Indicates a failure to serialize or deserialize.
+  SerializationError,
+
+  /// Indicates that something went wrong, but it is unclear whether the reason matches any of the other variants.
+  ///
+  /// When using this variant one may want to attach additional context to the corresponding
+  /// [`DidWebError`]. See
+  /// [`DidWebError::with_custom_message`](DidWebError::with_custom_message()) and
+  /// [`DidWebError::with_source`](DidWebError::with_source()).
+  Unspecified,
+}
+
+impl DidWebErrorKind {
+  /// Returns the string representation of the error.
+  pub const fn as_str(&self) -> &str {
+    match self {
+      Self::InvalidDID => "the document id is not a valid did:web DID",
+      Self::DocumentMismatch => "the document id does not match the URL of the document",
+      Self::DocumentNotFound => "no did:web document is published at the requested location",
+      Self::DocumentAlreadyExists => "a did:web document is already published at the requested location",
+      Self::UnsupportedLocation => "the publisher cannot write to the location of the document",
+      Self::Unavailable => "did:web publisher unavailable",
+      Self::RetryableIOFailure => "did:web publishing was unsuccessful because of an I/O failure",
+      Self::SerializationError => "(de)serialization error",
+      Self::Unspecified => "did:web publishing operation failed",
+    }
+  }
+}
+
+impl AsRef<str> for DidWebErrorKind {
+  fn as_ref(&self) -> &str {
+    self.as_str()
+  }
+}
+
+impl Display for DidWebErrorKind {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    write!(f, "{}", self.as_str())
+  }
+}
@@ -0,0 +1,126 @@
+// Copyright 2020-2024 IOTA Stiftung, Fondazione Links
+// SPDX-License-Identifier: Apache-2.0
+
+use identity_core::common::Url;
+use identity_did::WebDID;
+use identity_document::document::CoreDocument;
+
+use super::did_web_error::DidWebError;
+use super::did_web_error::DidWebErrorKind;
+use super::did_web_publisher::DidWebPublisher;
+use super::did_web_publisher::DidWebResult;
+
+/// Creates, updates and removes `did:web` documents published through a [`DidWebPublisher`].
+///
+/// The location of a document is derived from its `id`, see [`WebDID::to_url`], so a document is always
+/// published where resolvers look for it.
+#[derive(Debug)]
+pub struct DidWebManager<P> {
+  publisher: P,
+}
+
+impl<P: DidWebPublisher> DidWebManager<P> {
+  /// Creates a new [`DidWebManager`] publishing documents through `publisher`.
+  pub fn new(publisher: P) -> Self {
+    Self { publisher }
+  }
+
+  /// Returns a reference to the underlying [`DidWebPublisher`].
+  pub fn publisher(&self) -> &P {
+    &self.publisher
+  }
+
+  /// Publishes the new `document` and returns the URL it is published at.
+  ///
+  /// # Errors
+  ///
+  /// Fails with [`DidWebErrorKind::InvalidDID`] if the `id` of `document` is not a valid `did:web` DID and with
+  /// [`DidWebErrorKind::DocumentAlreadyExists`] if a document is already published at its location.
+  pub async fn create(&self, document: &CoreDocument) -> DidWebResult<Url> {
+    let url: Url = document_url(document)?;
+    match self.publisher.fetch(&url).await {
+      Ok(_) => return Err(DidWebError::new(DidWebErrorKind::DocumentAlreadyExists)),
+      Err(err) if matches!(err.kind(), DidWebErrorKind::DocumentNotFound) => (),
+      Err(err) => return Err(err),
+    }
+    self.publisher.publish(&url, document).await?;
+    Ok(url)
+  }
+
+  /// Replaces the published document with the same `id` by `document` and returns the URL it is published at.
+  ///
+  /// # Errors
+  ///
+  /// Fails with [`DidWebErrorKind::InvalidDID`] if the `id` of `document` is not a valid `did:web` DID and with
+  /// [`DidWebErrorKind::DocumentNotFound`] if no document is published at its location.
+  pub async fn update(&self, document: &CoreDocument) -> DidWebResult<Url> {
+    let url: Url = document_url(document)?;
+    self.publisher.fetch(&url).await?;
+    self.publisher.publish(&url, document).await?;
+    Ok(url)
+  }
+
+  /// Publishes `document`, replacing the document published at its location if any, and returns the URL it is
+  /// published at.
+  ///
+  /// # Errors
+  ///
+  /// Fails with [`DidWebErrorKind::InvalidDID`] if the `id` of `document` is not a valid `did:web` DID.
+  pub async fn publish(&self, document: &CoreDocument) -> DidWebResult<Url> {
+    let url: Url = document_url(document)?;
+    self.publisher.publish(&url, document).await?;
+    Ok(url)
+  }
+
+  /// Returns the document published for `did`.
+  ///
+  /// # Errors
+  ///
+  /// Fails with [`DidWebErrorKind::DocumentNotFound`] if no document is published for `did` and with
+  /// [`DidWebErrorKind::DocumentMismatch`] if the `id` of the published document is not `did`.
+  pub async fn fetch(&self, did: &WebDID) -> DidWebResult<CoreDocument> {
+    let url: Url = did.to_url();
+    let document: CoreDocument = self.publisher.fetch(&url).await?;
+    validate_document_url(&document, &url)?;
+    Ok(document)
+  }
+
+  /// Removes the document published for `did`.
+  ///
+  /// # Errors
+  ///
+  /// Fails with [`DidWebErrorKind::DocumentNotFound`] if no document is published for `did`.
+  pub async fn remove(&self, did: &WebDID) -> DidWebResult<()> {
+    self.publisher.unpublish(&did.to_url()).await
+  }
+}
+
+/// Validates that the `id` of `document` is the `did:web` DID whose document is located at `url`.
+///
+/// Returns the `id` of `document` as a [`WebDID`].
+///
+/// # Errors
+///
+/// Fails with [`DidWebErrorKind::InvalidDID`] if the `id` of `document` is not a valid `did:web` DID and with
+/// [`DidWebErrorKind::DocumentMismatch`] if it is located at another URL.
+pub fn validate_document_url(document: &CoreDocument, url: &Url) -> DidWebResult<WebDID> {
+  let did: WebDID = web_did(document)?;
+  if did.to_url() != *url {
+    return Err(
+      DidWebError::new(DidWebErrorKind::DocumentMismatch).with_custom_message(format!(
+        "the document of {did} is located at {}, not at {url}",
+        did.to_url()
+      )),
+    );
+  }
+  Ok(did)
+}
+
+fn web_did(document: &CoreDocument) -> DidWebResult<WebDID> {
+  WebDID::try_from_core(document.id().clone())
+    .map_err(|err| DidWebError::new(DidWebErrorKind::InvalidDID).with_source(err))
+}
+
+fn document_url(document: &CoreDocument) -> DidWebResult<Url> {
+  web_did(document).map(|did| did.to_url())
+}
@@ -0,0 +1,47 @@
+// Copyright 2020-2024 IOTA Stiftung, Fondazione Links
+// SPDX-License-Identifier: Apache-2.0
+
+use async_trait::async_trait;
+use identity_core::common::Url;
+use identity_document::document::CoreDocument;
+
+use super::did_web_error::DidWebError;
+
+/// Result of `did:web` publishing operations.
+pub type DidWebResult<T> = Result<T, DidWebError>;
+
+/// Writes `did:web` documents to the location they are served from.
+///
+/// Documents are addressed by the HTTPS URL they are resolved from, see
+/// [`WebDID::to_url`](identity_did::WebDID::to_url). Implementations are not required to validate the documents,
+/// which is done by the [`DidWebManager`](crate::DidWebManager).
+#[cfg_attr(not(feature = "send-sync-storage"), async_trait(?Send))]
+#[cfg_attr(feature = "send-sync-storage", async_trait)]
+pub trait DidWebPublisher: storage_sub_trait::StorageSendSyncMaybe {
+  /// Publish `document` at `url`, replacing the document currently published there, if any.
+  async fn publish(&self, url: &Url, document: &CoreDocument) -> DidWebResult<()>;
+
+  /// Obtain the document published at `url`.
+  ///
+  /// If no document is published at `url`, an error of kind
+  /// [`DocumentNotFound`](crate::DidWebErrorKind::DocumentNotFound) must be returned.
+  async fn fetch(&self, url: &Url) -> DidWebResult<CoreDocument>;
+
+  /// Remove the document published at `url`.
+  ///
+  /// If no document is published at `url`, an error of kind
+  /// [`DocumentNotFound`](crate::DidWebErrorKind::DocumentNotFound) must be returned.
+  async fn unpublish(&self, url: &Url) -> DidWebResult<()>;
+}
+
+#[cfg(not(feature = "send-sync-storage"))]
+mod storage_sub_trait {
+  pub trait StorageSendSyncMaybe {}
+  impl<S: super::DidWebPublisher> StorageSendSyncMaybe for S {}
+}
+
+#[cfg(feature = "send-sync-storage")]
+mod storage_sub_trait {
+  pub trait StorageSendSyncMaybe: Send + Sync {}
+  impl<S: Send + Sync + super::DidWebPublisher> StorageSendSyncMaybe for S {}
+}
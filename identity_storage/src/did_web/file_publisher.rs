@@ -0,0 +1,102 @@
+// Copyright 2020-2024 IOTA Stiftung, Fondazione Links
+// SPDX-License-Identifier: Apache-2.0
+
+use std::ffi::OsString;
+use std::io::ErrorKind;
+use std::path::Path;
+use std::path::PathBuf;
+
+use async_trait::async_trait;
+use identity_core::common::Url;
+use identity_document::document::CoreDocument;
+
+use super::did_web_error::DidWebError;
+use super::did_web_error::DidWebErrorKind;
+use super::did_web_publisher::DidWebPublisher;
+use super::did_web_publisher::DidWebResult;
+
+/// A [`DidWebPublisher`] writing documents into the directory served by a web server.
+///
+/// The path of the document URL is mapped to a file relative to the root directory, e.g. the document of
+/// `did:web:example.com:users:alice` located at `https://example.com/users/alice/did.json` is written to
+/// `<root>/users/alice/did.json`. The host of the URL is ignored, so a publisher serves a single origin.
+///
+/// Documents are written to a temporary file which atomically replaces the published one.
+#[derive(Debug, Clone)]
+pub struct DidWebFilePublisher {
+  root: PathBuf,
+}
+
+impl DidWebFilePublisher {
+  /// Creates a new `DidWebFilePublisher` writing documents into the directory `root`.
+  pub fn new(root: impl Into<PathBuf>) -> Self {
+    Self { root: root.into() }
+  }
+
+  /// Returns the root directory documents are written into.
+  pub fn root(&self) -> &Path {
+    &self.root
+  }
+
+  /// Returns the path of the file the document located at `url` is written to.
+  ///
+  /// # Errors
+  ///
+  /// Fails with [`DidWebErrorKind::UnsupportedLocation`] if a segment of the path of `url` is percent-encoded or
+  /// cannot be mapped to a file name.
+  pub fn document_path(&self, url: &Url) -> DidWebResult<PathBuf> {
+    let segments: Vec<&str> = url.path_segments().map(Iterator::collect).unwrap_or_default();
+    if segments.last().map_or(true, |file| file.is_empty()) {
+      return Err(unsupported_location(url));
+    }
+
+    let mut path: PathBuf = self.root.clone();
+    for segment in segments {
+      if segment.is_empty() || segment == "." || segment == ".." || segment.contains(&['%', '\\'][..]) {
+        return Err(unsupported_location(url));
+      }
+      path.push(segment);
+    }
+    Ok(path)
+  }
+}
+
+#[cfg_attr(not(feature = "send-sync-storage"), async_trait(?Send))]
+#[cfg_attr(feature = "send-sync-storage", async_trait)]
+impl DidWebPublisher for DidWebFilePublisher {
+  async fn publish(&self, url: &Url, document: &CoreDocument) -> DidWebResult<()> {
+    let path: PathBuf = self.document_path(url)?;
+    let bytes: Vec<u8> = serde_json::to_vec_pretty(document)
+      .map_err(|err| DidWebError::new(DidWebErrorKind::SerializationError).with_source(err))?;
+
+    let mut tmp_path: OsString = path.clone().into_os_string();
+    tmp_path.push(".tmp");
+    path
+      .parent()
+      .map_or(Ok(()), std::fs::create_dir_all)
+      .and_then(|_| std::fs::write(&tmp_path, bytes))
+      .and_then(|_| std::fs::rename(&tmp_path, &path))
+      .map_err(io_error)
+  }
+
+  async fn fetch(&self, url: &Url) -> DidWebResult<CoreDocument> {
+    let bytes: Vec<u8> = std::fs::read(self.document_path(url)?).map_err(io_error)?;
+    serde_json::from_slice(&bytes).map_err(|err| DidWebError::new(DidWebErrorKind::SerializationError).with_source(err))
+  }
+
+  async fn unpublish(&self, url: &Url) -> DidWebResult<()> {
+    std::fs::remove_file(self.document_path(url)?).map_err(io_error)
+  }
+}
+
+fn unsupported_location(url: &Url) -> DidWebError {
+  DidWebError::new(DidWebErrorKind::UnsupportedLocation).with_custom_message(format!("cannot map {url} to a file"))
+}
+
+fn io_error(err: std::io::Error) -> DidWebError {
+  if err.kind() == ErrorKind::NotFound {
+    DidWebError::new(DidWebErrorKind::DocumentNotFound)
+  } else {
+    DidWebError::new(DidWebErrorKind::RetryableIOFailure).with_source(err)
+  }
+}
@@ -0,0 +1,64 @@
+// Copyright 2020-2024 IOTA Stiftung, Fondazione Links
+// SPDX-License-Identifier: Apache-2.0
+
+use std::collections::HashMap;
+
+use async_trait::async_trait;
+use identity_core::common::Url;
+use identity_document::document::CoreDocument;
+
+use super::did_web_error::DidWebError;
+use super::did_web_error::DidWebErrorKind;
+use super::did_web_publisher::DidWebPublisher;
+use super::did_web_publisher::DidWebResult;
+use crate::key_storage::shared::Shared;
+
+/// An in-memory [`DidWebPublisher`] implementation that serves as an example and may be used in tests.
+#[derive(Debug)]
+pub struct DidWebMemPublisher {
+  documents: Shared<HashMap<Url, CoreDocument>>,
+}
+
+impl DidWebMemPublisher {
+  /// Creates a new, empty `DidWebMemPublisher` instance.
+  pub fn new() -> Self {
+    Self {
+      documents: Shared::new(HashMap::new()),
+    }
+  }
+}
+
+impl Default for DidWebMemPublisher {
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
+#[cfg_attr(not(feature = "send-sync-storage"), async_trait(?Send))]
+#[cfg_attr(feature = "send-sync-storage", async_trait)]
+impl DidWebPublisher for DidWebMemPublisher {
+  async fn publish(&self, url: &Url, document: &CoreDocument) -> DidWebResult<()> {
+    self.documents.write().await.insert(url.clone(), document.clone());
+    Ok(())
+  }
+
+  async fn fetch(&self, url: &Url) -> DidWebResult<CoreDocument> {
+    self
+      .documents
+      .read()
+      .await
+      .get(url)
+      .cloned()
+      .ok_or_else(|| DidWebError::new(DidWebErrorKind::DocumentNotFound))
+  }
+
+  async fn unpublish(&self, url: &Url) -> DidWebResult<()> {
+    self
+      .documents
+      .write()
+      .await
+      .remove(url)
+      .map(|_| ())
+      .ok_or_else(|| DidWebError::new(DidWebErrorKind::DocumentNotFound))
+  }
+}
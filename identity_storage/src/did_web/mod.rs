@@ -0,0 +1,26 @@
+// Copyright 2020-2024 IOTA Stiftung, Fondazione Links
+// SPDX-License-Identifier: Apache-2.0
+
+//! Tooling to create, publish and update `did:web` documents.
+//!
+//! The [`DidWebManager`] computes the location of the document of a [`WebDID`](identity_did::WebDID),
+//! validates that the `id` of the document matches that location and writes the document through a
+//! [`DidWebPublisher`], such as the [`DidWebFilePublisher`] writing into the directory served by a web server.
+
+mod did_web_error;
+mod did_web_manager;
+mod did_web_publisher;
+mod file_publisher;
+
+#[cfg(feature = "memstore")]
+mod memstore;
+
+#[cfg(all(test, feature = "memstore"))]
+mod tests;
+
+pub use did_web_error::*;
+pub use did_web_manager::*;
+pub use did_web_publisher::*;
+pub use file_publisher::*;
+#[cfg(feature = "memstore")]
+pub use memstore::*;
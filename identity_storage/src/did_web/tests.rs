@@ -0,0 +1,111 @@
+// Copyright 2020-2024 IOTA Stiftung, Fondazione Links
+// SPDX-License-Identifier: Apache-2.0
+
+use identity_core::common::Object;
+use identity_core::common::Url;
+use identity_did::CoreDID;
+use identity_did::WebDID;
+use identity_document::document::CoreDocument;
+
+use super::*;
+
+fn document(url: &str) -> CoreDocument {
+  CoreDocument::new_from_url(url).unwrap()
+}
+
+#[tokio::test]
+async fn document_lifecycle() {
+  let manager = DidWebManager::new(DidWebMemPublisher::new());
+  let mut document: CoreDocument = document("https://example.com:8443/users/alice");
+  let did: WebDID = WebDID::try_from_core(document.id().clone()).unwrap();
+
+  assert!(matches!(
+    manager.fetch(&did).await.unwrap_err().kind(),
+    DidWebErrorKind::DocumentNotFound
+  ));
+  assert!(matches!(
+    manager.update(&document).await.unwrap_err().kind(),
+    DidWebErrorKind::DocumentNotFound
+  ));
+
+  let url: Url = manager.create(&document).await.unwrap();
+  assert_eq!(url.as_str(), "https://example.com:8443/users/alice/did.json");
+  assert_eq!(manager.fetch(&did).await.unwrap(), document);
+  assert!(matches!(
+    manager.create(&document).await.unwrap_err().kind(),
+    DidWebErrorKind::DocumentAlreadyExists
+  ));
+
+  document
+    .also_known_as_mut()
+    .append(Url::parse("https://alice.example").unwrap());
+  assert_eq!(manager.update(&document).await.unwrap(), url);
+  assert_eq!(manager.fetch(&did).await.unwrap(), document);
+
+  manager.remove(&did).await.unwrap();
+  assert!(matches!(
+    manager.remove(&did).await.unwrap_err().kind(),
+    DidWebErrorKind::DocumentNotFound
+  ));
+}
+
+#[tokio::test]
+async fn documents_must_match_their_location() {
+  let publisher = DidWebMemPublisher::new();
+  let alice: CoreDocument = document("https://example.com/users/alice/did.json");
+  let bob: CoreDocument = document("https://example.com/users/bob/did.json");
+  let bob_url: Url = Url::parse("https://example.com/users/bob/did.json").unwrap();
+
+  assert!(validate_document_url(&bob, &bob_url).is_ok());
+  assert!(matches!(
+    validate_document_url(&alice, &bob_url).unwrap_err().kind(),
+    DidWebErrorKind::DocumentMismatch
+  ));
+
+  // A document published at the location of another DID is rejected.
+  publisher.publish(&bob_url, &alice).await.unwrap();
+  let manager = DidWebManager::new(publisher);
+  let bob_did: WebDID = WebDID::try_from_core(bob.id().clone()).unwrap();
+  assert!(matches!(
+    manager.fetch(&bob_did).await.unwrap_err().kind(),
+    DidWebErrorKind::DocumentMismatch
+  ));
+
+  let not_web: CoreDocument = CoreDocument::builder(Object::new())
+    .id(CoreDID::parse("did:example:alice").unwrap())
+    .build()
+    .unwrap();
+  assert!(matches!(
+    manager.publish(&not_web).await.unwrap_err().kind(),
+    DidWebErrorKind::InvalidDID
+  ));
+}
+
+#[tokio::test]
+async fn file_publisher_writes_served_paths() {
+  let root = std::env::temp_dir().join(format!("identity_did_web_{}", std::process::id()));
+  let manager = DidWebManager::new(DidWebFilePublisher::new(&root));
+
+  let root_document: CoreDocument = document("https://example.com/.well-known/did.json");
+  let path_document: CoreDocument = document("https://example.com:8443/users/alice");
+  manager.create(&root_document).await.unwrap();
+  manager.create(&path_document).await.unwrap();
+  assert!(root.join(".well-known").join("did.json").is_file());
+  assert!(root.join("users").join("alice").join("did.json").is_file());
+
+  let did: WebDID = WebDID::try_from_core(path_document.id().clone()).unwrap();
+  assert_eq!(manager.fetch(&did).await.unwrap(), path_document);
+  manager.remove(&did).await.unwrap();
+  assert!(matches!(
+    manager.fetch(&did).await.unwrap_err().kind(),
+    DidWebErrorKind::DocumentNotFound
+  ));
+
+  let encoded: Url = Url::parse("https://example.com/users/a%20b/did.json").unwrap();
+  assert!(matches!(
+    manager.publisher().document_path(&encoded).unwrap_err().kind(),
+    DidWebErrorKind::UnsupportedLocation
+  ));
+
+  std::fs::remove_dir_all(&root).unwrap();
+}
@@ -2,6 +2,16 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use anyhow::Context;
+#[cfg(feature = "jpt-predicates-experimental")]
+use bls12_381_plus::group::Curve;
+#[cfg(feature = "jpt-predicates-experimental")]
+use bls12_381_plus::G1Affine;
+#[cfg(feature = "jpt-predicates-experimental")]
+use bls12_381_plus::G1Projective;
+#[cfg(feature = "jpt-predicates-experimental")]
+use bls12_381_plus::Scalar;
+#[cfg(feature = "jpt-predicates-experimental")]
+use identity_credential::credential::JptHolderProofRequest;
 use identity_verification::jose::jwk::Jwk;
 use identity_verification::jose::jwu;
 use identity_verification::jwk::BlsCurve;
@@ -10,11 +20,19 @@ use jsonprooftoken::jpa::algs::ProofAlgorithm;
 use zkryptium::bbsplus::ciphersuites::BbsCiphersuite;
 use zkryptium::bbsplus::ciphersuites::Bls12381Sha256;
 use zkryptium::bbsplus::ciphersuites::Bls12381Shake256;
+#[cfg(feature = "jpt-predicates-experimental")]
+use zkryptium::bbsplus::generators::Generators;
 use zkryptium::bbsplus::keys::BBSplusPublicKey;
 use zkryptium::bbsplus::keys::BBSplusSecretKey;
+#[cfg(feature = "jpt-predicates-experimental")]
+use zkryptium::bbsplus::signature::BBSplusSignature;
 use zkryptium::keys::pair::KeyPair;
 use zkryptium::schemes::algorithms::BBSplus;
 use zkryptium::schemes::generics::Signature;
+#[cfg(feature = "jpt-predicates-experimental")]
+use zkryptium::utils::util::bbsplus_utils::hash_to_scalar;
+#[cfg(feature = "jpt-predicates-experimental")]
+use zkryptium::utils::util::bbsplus_utils::i2osp;
 
 use crate::key_storage::KeyStorageError;
 use crate::key_storage::KeyStorageErrorKind;
@@ -141,6 +159,134 @@ pub fn sign_bbs(
   })
 }
 
+#[cfg(feature = "jpt-predicates-experimental")]
+fn _sign_bbs_scalars<S>(
+  messages: &[Scalar],
+  holder_commitment: Option<&G1Projective>,
+  sk: &BBSplusSecretKey,
+  pk: &BBSplusPublicKey,
+  header: &[u8],
+) -> Result<Vec<u8>, zkryptium::errors::Error>
+where
+  S: BbsCiphersuite,
+{
+  let generators: Generators = Generators::create::<S>(messages.len() + 1, Some(S::API_ID));
+  let q1: G1Projective = generators.values[0];
+  let h: &[G1Projective] = &generators.values[1..];
+
+  let mut domain_input: Vec<u8> = pk.to_bytes().to_vec();
+  domain_input.extend(i2osp(messages.len(), 8));
+  domain_input.extend(q1.to_affine().to_compressed());
+  h.iter()
+    .for_each(|point| domain_input.extend(point.to_affine().to_compressed()));
+  domain_input.extend(S::API_ID);
+  domain_input.extend(i2osp(header.len(), 8));
+  domain_input.extend(header);
+  let dst: Vec<u8> = [S::API_ID, S::H2S].concat();
+  let domain: Scalar = hash_to_scalar::<S>(&domain_input, &dst)?;
+
+  let mut e_input: Vec<u8> = sk.0.to_be_bytes().to_vec();
+  e_input.extend(domain.to_be_bytes());
+  messages
+    .iter()
+    .for_each(|message| e_input.extend(message.to_be_bytes()));
+  if let Some(commitment) = holder_commitment {
+    e_input.extend(commitment.to_affine().to_compressed());
+  }
+  let e: Scalar = hash_to_scalar::<S>(&e_input, &dst)?;
+
+  // The commitment to the holder secret is added as is, its generator being known only to the holder.
+  let b: G1Projective = messages
+    .iter()
+    .zip(h)
+    .fold(generators.g1_base_point + q1 * domain, |b, (message, generator)| {
+      b + generator * message
+    })
+    + holder_commitment.copied().unwrap_or_default();
+  let sk_e_inv: Scalar = Option::from((sk.0 + e).invert()).ok_or(
+    zkryptium::errors::Error::SignatureGenerationError("invalid secret key".to_owned()),
+  )?;
+
+  Ok(BBSplusSignature { A: b * sk_e_inv, e }.to_bytes().to_vec())
+}
+
+/// Signs `messages` that are already encoded as scalars, as done for the predicate claims of JPTs, and `header`
+/// using the given keys.
+#[cfg(feature = "jpt-predicates-experimental")]
+pub fn sign_bbs_scalars(
+  alg: ProofAlgorithm,
+  messages: &[[u8; 32]],
+  sk: &BBSplusSecretKey,
+  pk: &BBSplusPublicKey,
+  header: &[u8],
+) -> KeyStorageResult<Vec<u8>> {
+  _sign_bbs_scalars_with(alg, messages, None, sk, pk, header)
+}
+
+/// Signs `messages` that are already encoded as scalars, and `header`, together with the compressed commitment to the
+/// secret of a holder, as done for the JPTs bound to that secret.
+#[cfg(feature = "jpt-predicates-experimental")]
+pub fn sign_bbs_blind(
+  alg: ProofAlgorithm,
+  messages: &[[u8; 32]],
+  holder_commitment: &[u8],
+  sk: &BBSplusSecretKey,
+  pk: &BBSplusPublicKey,
+  header: &[u8],
+) -> KeyStorageResult<Vec<u8>> {
+  let holder_commitment: G1Projective = holder_commitment
+    .try_into()
+    .ok()
+    .and_then(|bytes: &[u8; 48]| Option::<G1Affine>::from(G1Affine::from_compressed(bytes)))
+    .map(G1Projective::from)
+    .ok_or_else(|| {
+      KeyStorageError::new(KeyStorageErrorKind::Unspecified).with_custom_message("invalid holder commitment".to_owned())
+    })?;
+  _sign_bbs_scalars_with(alg, messages, Some(&holder_commitment), sk, pk, header)
+}
+
+#[cfg(feature = "jpt-predicates-experimental")]
+fn _sign_bbs_scalars_with(
+  alg: ProofAlgorithm,
+  messages: &[[u8; 32]],
+  holder_commitment: Option<&G1Projective>,
+  sk: &BBSplusSecretKey,
+  pk: &BBSplusPublicKey,
+  header: &[u8],
+) -> KeyStorageResult<Vec<u8>> {
+  let messages: Vec<Scalar> = messages
+    .iter()
+    .map(|bytes| Option::from(Scalar::from_be_bytes(bytes)))
+    .collect::<Option<_>>()
+    .ok_or_else(|| {
+      KeyStorageError::new(KeyStorageErrorKind::Unspecified).with_custom_message("invalid scalar message".to_owned())
+    })?;
+  match alg {
+    ProofAlgorithm::BLS12381_SHA256 => {
+      _sign_bbs_scalars::<Bls12381Sha256>(&messages, holder_commitment, sk, pk, header)
+    }
+    ProofAlgorithm::BLS12381_SHAKE256 => {
+      _sign_bbs_scalars::<Bls12381Shake256>(&messages, holder_commitment, sk, pk, header)
+    }
+    _ => return Err(KeyStorageErrorKind::UnsupportedProofAlgorithm.into()),
+  }
+  .map_err(|e| {
+    KeyStorageError::new(KeyStorageErrorKind::Unspecified)
+      .with_source(e)
+      .with_custom_message("signature failed".to_owned())
+  })
+}
+
+/// Answers `request` with a proof of knowledge of `sk`, the secret of a holder of JPTs.
+#[cfg(feature = "jpt-predicates-experimental")]
+pub fn prove_bbs_holder_secret(request: &JptHolderProofRequest, sk: &BBSplusSecretKey) -> KeyStorageResult<Vec<u8>> {
+  request.prove(&sk.to_bytes()).map_err(|e| {
+    KeyStorageError::new(KeyStorageErrorKind::Unspecified)
+      .with_source(e)
+      .with_custom_message("holder secret proof failed".to_owned())
+  })
+}
+
 fn _update_bbs_signature<S>(
   sig: &[u8; 80],
   sk: &BBSplusSecretKey,
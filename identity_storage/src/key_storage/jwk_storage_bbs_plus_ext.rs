@@ -2,12 +2,16 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use async_trait::async_trait;
+#[cfg(feature = "jpt-predicates-experimental")]
+use identity_credential::credential::JptHolderProofRequest;
 use identity_verification::jwk::Jwk;
 use jsonprooftoken::jpa::algs::ProofAlgorithm;
 
 use crate::JwkGenOutput;
 use crate::JwkStorage;
 use crate::KeyId;
+#[cfg(feature = "jpt-predicates-experimental")]
+use crate::KeyStorageErrorKind;
 use crate::KeyStorageResult;
 use crate::KeyType;
 use crate::ProofUpdateCtx;
@@ -29,6 +33,58 @@ pub trait JwkStorageBbsPlusExt: JwkStorage {
     public_key: &Jwk,
   ) -> KeyStorageResult<Vec<u8>>;
 
+  /// Sign the provided `messages`, already encoded as BBS scalars, and `header` using the private key identified by
+  /// `key_id`. Used to issue JPTs whose integer claims can be the subject of predicate proofs.
+  ///
+  /// Storages that do not support scalar messages can rely on the default implementation, which returns
+  /// [`KeyStorageErrorKind::UnsupportedProofAlgorithm`].
+  #[cfg(feature = "jpt-predicates-experimental")]
+  async fn sign_bbs_scalars(
+    &self,
+    key_id: &KeyId,
+    messages: &[[u8; 32]],
+    header: &[u8],
+    public_key: &Jwk,
+  ) -> KeyStorageResult<Vec<u8>> {
+    let _ = (key_id, messages, header, public_key);
+    Err(KeyStorageErrorKind::UnsupportedProofAlgorithm.into())
+  }
+
+  /// Sign the provided `messages`, already encoded as BBS scalars, and `header` together with the compressed
+  /// `holder_commitment` to the secret of a holder, using the private key identified by `key_id`. Used to issue JPTs
+  /// bound to the secret of their holder.
+  ///
+  /// Storages that do not support holder binding can rely on the default implementation, which returns
+  /// [`KeyStorageErrorKind::UnsupportedProofAlgorithm`].
+  #[cfg(feature = "jpt-predicates-experimental")]
+  async fn sign_bbs_blind(
+    &self,
+    key_id: &KeyId,
+    messages: &[[u8; 32]],
+    holder_commitment: &[u8],
+    header: &[u8],
+    public_key: &Jwk,
+  ) -> KeyStorageResult<Vec<u8>> {
+    let _ = (key_id, messages, holder_commitment, header, public_key);
+    Err(KeyStorageErrorKind::UnsupportedProofAlgorithm.into())
+  }
+
+  /// Answers `request` with a proof of knowledge of the private key identified by `key_id`, used as the secret of the
+  /// holder of JPTs, without revealing it.
+  ///
+  /// Storages that do not support holder binding can rely on the default implementation, which returns
+  /// [`KeyStorageErrorKind::UnsupportedProofAlgorithm`].
+  #[cfg(feature = "jpt-predicates-experimental")]
+  async fn prove_bbs_holder_secret(
+    &self,
+    key_id: &KeyId,
+    public_key: &Jwk,
+    request: &JptHolderProofRequest,
+  ) -> KeyStorageResult<Vec<u8>> {
+    let _ = (key_id, public_key, request);
+    Err(KeyStorageErrorKind::UnsupportedProofAlgorithm.into())
+  }
+
   /// Update proof functionality for timeframe revocation mechanism
   async fn update_signature(
     &self,
@@ -29,7 +29,6 @@ use super::KeyType;
 
 use crate::key_storage::JwkStorage;
 
-
 /// The map from key ids to JWKs.
 type JwkKeyStore = HashMap<KeyId, Jwk>;
 
@@ -319,7 +318,6 @@ fn check_key_alg_compatibility(key_type: MemStoreKeyType, alg: &JwsAlgorithm) ->
 
 #[cfg(feature = "pqc-liboqs")]
 mod pqc_liboqs {
-  use std::str::FromStr;
   use async_trait::async_trait;
   use crypto::signatures::ed25519::SecretKey;
   use identity_verification::jose::jwk::Jwk;
@@ -329,6 +327,7 @@ mod pqc_liboqs {
   use identity_verification::jwu;
   use oqs::sig::Algorithm;
   use oqs::sig::Sig;
+  use std::str::FromStr;
   use tokio::sync::RwLockReadGuard;
   use tokio::sync::RwLockWriteGuard;
 
@@ -558,7 +557,13 @@ mod bbs_plus_impl {
   use crate::key_storage::bls::encode_bls_jwk;
   use crate::key_storage::bls::expand_bls_jwk;
   use crate::key_storage::bls::generate_bbs_keypair;
+  #[cfg(feature = "jpt-predicates-experimental")]
+  use crate::key_storage::bls::prove_bbs_holder_secret;
   use crate::key_storage::bls::sign_bbs;
+  #[cfg(feature = "jpt-predicates-experimental")]
+  use crate::key_storage::bls::sign_bbs_blind;
+  #[cfg(feature = "jpt-predicates-experimental")]
+  use crate::key_storage::bls::sign_bbs_scalars;
   use crate::key_storage::bls::update_bbs_signature;
   use crate::JwkGenOutput;
   use crate::JwkMemStore;
@@ -570,6 +575,8 @@ mod bbs_plus_impl {
   use crate::KeyType;
   use crate::ProofUpdateCtx;
   use async_trait::async_trait;
+  #[cfg(feature = "jpt-predicates-experimental")]
+  use identity_credential::credential::JptHolderProofRequest;
   use identity_verification::jwk::BlsCurve;
   use identity_verification::jwk::Jwk;
   use jsonprooftoken::jpa::algs::ProofAlgorithm;
@@ -632,6 +639,112 @@ mod bbs_plus_impl {
       sign_bbs(alg, data, &sk.expect("jwk is private"), &pk, header)
     }
 
+    #[cfg(feature = "jpt-predicates-experimental")]
+    async fn sign_bbs_scalars(
+      &self,
+      key_id: &KeyId,
+      messages: &[[u8; 32]],
+      header: &[u8],
+      public_key: &Jwk,
+    ) -> KeyStorageResult<Vec<u8>> {
+      let jwk_store = self.jwk_store.read().await;
+
+      // Extract the required alg from the given public key
+      let alg = public_key
+        .alg()
+        .and_then(|alg_str| ProofAlgorithm::from_str(alg_str).ok())
+        .ok_or(KeyStorageErrorKind::UnsupportedProofAlgorithm)?;
+
+      // Check the provided JWK represents a BLS12381G2 key.
+      if !public_key
+        .try_ec_params()
+        .map(|ec| ec.crv == BlsCurve::BLS12381G2.to_string())
+        .unwrap_or(false)
+      {
+        return Err(
+          KeyStorageError::new(KeyStorageErrorKind::UnsupportedKeyType)
+            .with_custom_message(format!("expected a key from the {} curve", BlsCurve::BLS12381G2)),
+        );
+      }
+
+      // Obtain the corresponding private key.
+      let jwk: &Jwk = jwk_store.get(key_id).ok_or(KeyStorageErrorKind::KeyNotFound)?;
+      let (sk, pk) = expand_bls_jwk(jwk)?;
+
+      sign_bbs_scalars(alg, messages, &sk.expect("jwk is private"), &pk, header)
+    }
+
+    #[cfg(feature = "jpt-predicates-experimental")]
+    async fn sign_bbs_blind(
+      &self,
+      key_id: &KeyId,
+      messages: &[[u8; 32]],
+      holder_commitment: &[u8],
+      header: &[u8],
+      public_key: &Jwk,
+    ) -> KeyStorageResult<Vec<u8>> {
+      let jwk_store = self.jwk_store.read().await;
+
+      // Extract the required alg from the given public key
+      let alg = public_key
+        .alg()
+        .and_then(|alg_str| ProofAlgorithm::from_str(alg_str).ok())
+        .ok_or(KeyStorageErrorKind::UnsupportedProofAlgorithm)?;
+
+      // Check the provided JWK represents a BLS12381G2 key.
+      if !public_key
+        .try_ec_params()
+        .map(|ec| ec.crv == BlsCurve::BLS12381G2.to_string())
+        .unwrap_or(false)
+      {
+        return Err(
+          KeyStorageError::new(KeyStorageErrorKind::UnsupportedKeyType)
+            .with_custom_message(format!("expected a key from the {} curve", BlsCurve::BLS12381G2)),
+        );
+      }
+
+      // Obtain the corresponding private key.
+      let jwk: &Jwk = jwk_store.get(key_id).ok_or(KeyStorageErrorKind::KeyNotFound)?;
+      let (sk, pk) = expand_bls_jwk(jwk)?;
+
+      sign_bbs_blind(
+        alg,
+        messages,
+        holder_commitment,
+        &sk.expect("jwk is private"),
+        &pk,
+        header,
+      )
+    }
+
+    #[cfg(feature = "jpt-predicates-experimental")]
+    async fn prove_bbs_holder_secret(
+      &self,
+      key_id: &KeyId,
+      public_key: &Jwk,
+      request: &JptHolderProofRequest,
+    ) -> KeyStorageResult<Vec<u8>> {
+      let jwk_store = self.jwk_store.read().await;
+
+      // Check the provided JWK represents a BLS12381G2 key.
+      if !public_key
+        .try_ec_params()
+        .map(|ec| ec.crv == BlsCurve::BLS12381G2.to_string())
+        .unwrap_or(false)
+      {
+        return Err(
+          KeyStorageError::new(KeyStorageErrorKind::UnsupportedKeyType)
+            .with_custom_message(format!("expected a key from the {} curve", BlsCurve::BLS12381G2)),
+        );
+      }
+
+      // Obtain the corresponding private key.
+      let jwk: &Jwk = jwk_store.get(key_id).ok_or(KeyStorageErrorKind::KeyNotFound)?;
+      let sk = expand_bls_jwk(jwk)?.0.expect("jwk is private");
+
+      prove_bbs_holder_secret(request, &sk)
+    }
+
     async fn update_signature(
       &self,
       key_id: &KeyId,
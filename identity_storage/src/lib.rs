@@ -14,10 +14,14 @@
   clippy::missing_safety_doc
 )]
 
+pub mod credential_registry;
+pub mod did_web;
 pub mod key_id_storage;
 pub mod key_storage;
 pub mod storage;
 
+pub use credential_registry::*;
+pub use did_web::*;
 pub use key_id_storage::*;
 pub use key_storage::public_modules::*;
 pub use storage::*;
@@ -0,0 +1,525 @@
+// Copyright 2020-2024 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+use super::JwkStorageDocumentError as Error;
+use super::Storage;
+use super::StorageResult;
+
+use crate::key_id_storage::KeyIdStorage;
+use crate::key_id_storage::MethodDigest;
+use crate::key_storage::JwkStorage;
+#[cfg(feature = "pqc")]
+use crate::key_storage::JwkStoragePQ;
+use crate::key_storage::KeyId;
+
+use async_trait::async_trait;
+use identity_core::convert::ToJson;
+use identity_credential::credential::Cose;
+use identity_credential::credential::Credential;
+use identity_credential::credential::VC_CONTENT_TYPE;
+use identity_credential::credential::VC_COSE_MEDIA_TYPE;
+use identity_credential::credential::VP_CONTENT_TYPE;
+use identity_credential::credential::VP_COSE_MEDIA_TYPE;
+use identity_credential::presentation::Presentation;
+use identity_document::document::CoreDocument;
+use identity_verification::jose::cose::CoseAlgorithm;
+use identity_verification::jose::cose::CoseHeader;
+use identity_verification::jose::cose::CoseSign1Encoder;
+use identity_verification::jose::jws::JwsAlgorithm;
+use identity_verification::jwk::Jwk;
+use identity_verification::MethodData;
+use identity_verification::VerificationMethod;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+/// Options for creating COSE_Sign1 messages.
+#[non_exhaustive]
+#[derive(Debug, Default, Clone, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CoseSignatureOptions {
+  /// The Type (typ) header parameter.
+  ///
+  /// [More Info](https://www.rfc-editor.org/rfc/rfc9596).
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub typ: Option<String>,
+
+  /// The Content Type header parameter.
+  ///
+  /// [More Info](https://www.rfc-editor.org/rfc/rfc9052#section-3.1).
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub content_type: Option<String>,
+
+  /// The kid to set in the protected header.
+  ///
+  /// If unset, the kid will be the id of the method used to produce the signature, encoded as UTF-8.
+  ///
+  /// [More Info](https://www.rfc-editor.org/rfc/rfc9052#section-3.1).
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub kid: Option<String>,
+
+  /// Whether the payload is detached from the message, in which case it has to be provided to the verifier
+  /// separately, e.g. with
+  /// [`CoseCredentialValidator::validate_detached`](identity_credential::validator::CoseCredentialValidator::validate_detached).
+  ///
+  /// [More Info](https://www.rfc-editor.org/rfc/rfc9052#section-2).
+  #[serde(default)]
+  pub detached_payload: bool,
+}
+
+impl CoseSignatureOptions {
+  /// Creates a new [`CoseSignatureOptions`].
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  /// Replace the value of the `typ` field.
+  pub fn typ(mut self, value: impl Into<String>) -> Self {
+    self.typ = Some(value.into());
+    self
+  }
+
+  /// Replace the value of the `content_type` field.
+  pub fn content_type(mut self, value: impl Into<String>) -> Self {
+    self.content_type = Some(value.into());
+    self
+  }
+
+  /// Replace the value of the `kid` field.
+  pub fn kid(mut self, value: impl Into<String>) -> Self {
+    self.kid = Some(value.into());
+    self
+  }
+
+  /// Replace the value of the `detached_payload` field.
+  pub fn detached_payload(mut self, value: bool) -> Self {
+    self.detached_payload = value;
+    self
+  }
+}
+
+/// Extension trait for creating COSE_Sign1 messages with the keys of a DID document.
+///
+/// The methods mirror the JWT based ones of [`JwkDocumentExt`](super::JwkDocumentExt): the key material is looked up
+/// the same way and signing is delegated to the [`JwkStorage`] (resp. [`JwkStoragePQ`] for the `_pqc` variants)
+/// backing the given `storage`.
+#[cfg_attr(not(feature = "send-sync-storage"), async_trait(?Send))]
+#[cfg_attr(feature = "send-sync-storage", async_trait)]
+pub trait CoseDocumentExt {
+  /// Produces a COSE_Sign1 message over the given `payload`.
+  ///
+  /// The `alg` in the protected header is derived from the `alg` of the public key of the method identified by
+  /// `fragment`. Unless the `kid` is explicitly set in the options, the `kid` is the `id` of that method.
+  async fn create_cose<K, I>(
+    &self,
+    storage: &Storage<K, I>,
+    fragment: &str,
+    payload: &[u8],
+    options: &CoseSignatureOptions,
+  ) -> StorageResult<Cose>
+  where
+    K: JwkStorage,
+    I: KeyIdStorage;
+
+  /// Produces a COSE_Sign1 message whose payload is the given `credential`
+  /// in accordance with [VC-JOSE-COSE](https://www.w3.org/TR/vc-jose-cose/#securing-with-cose).
+  ///
+  /// Unless set in the options, the `typ` is [`VC_COSE_MEDIA_TYPE`] and the content type is [`VC_CONTENT_TYPE`].
+  async fn create_credential_cose<K, I, T>(
+    &self,
+    credential: &Credential<T>,
+    storage: &Storage<K, I>,
+    fragment: &str,
+    options: &CoseSignatureOptions,
+  ) -> StorageResult<Cose>
+  where
+    K: JwkStorage,
+    I: KeyIdStorage,
+    T: ToOwned<Owned = T> + Serialize + DeserializeOwned + Sync;
+
+  /// Produces a COSE_Sign1 message whose payload is the given `presentation`
+  /// in accordance with [VC-JOSE-COSE](https://www.w3.org/TR/vc-jose-cose/#securing-with-cose).
+  ///
+  /// Unless set in the options, the `typ` is [`VP_COSE_MEDIA_TYPE`] and the content type is [`VP_CONTENT_TYPE`].
+  async fn create_presentation_cose<K, I, CRED, T>(
+    &self,
+    presentation: &Presentation<CRED, T>,
+    storage: &Storage<K, I>,
+    fragment: &str,
+    options: &CoseSignatureOptions,
+  ) -> StorageResult<Cose>
+  where
+    K: JwkStorage,
+    I: KeyIdStorage,
+    T: ToOwned<Owned = T> + Serialize + DeserializeOwned + Sync,
+    CRED: ToOwned<Owned = CRED> + Serialize + DeserializeOwned + Clone + Sync;
+
+  /// Produces a COSE_Sign1 message over the given `payload` using a PQC algorithm.
+  ///
+  /// See [`CoseDocumentExt::create_cose`].
+  #[cfg(feature = "pqc")]
+  async fn create_cose_pqc<K, I>(
+    &self,
+    storage: &Storage<K, I>,
+    fragment: &str,
+    payload: &[u8],
+    options: &CoseSignatureOptions,
+  ) -> StorageResult<Cose>
+  where
+    K: JwkStoragePQ,
+    I: KeyIdStorage;
+
+  /// Produces a COSE_Sign1 message whose payload is the given `credential` using a PQC algorithm.
+  ///
+  /// See [`CoseDocumentExt::create_credential_cose`].
+  #[cfg(feature = "pqc")]
+  async fn create_credential_cose_pqc<K, I, T>(
+    &self,
+    credential: &Credential<T>,
+    storage: &Storage<K, I>,
+    fragment: &str,
+    options: &CoseSignatureOptions,
+  ) -> StorageResult<Cose>
+  where
+    K: JwkStoragePQ,
+    I: KeyIdStorage,
+    T: ToOwned<Owned = T> + Serialize + DeserializeOwned + Sync;
+
+  /// Produces a COSE_Sign1 message whose payload is the given `presentation` using a PQC algorithm.
+  ///
+  /// See [`CoseDocumentExt::create_presentation_cose`].
+  #[cfg(feature = "pqc")]
+  async fn create_presentation_cose_pqc<K, I, CRED, T>(
+    &self,
+    presentation: &Presentation<CRED, T>,
+    storage: &Storage<K, I>,
+    fragment: &str,
+    options: &CoseSignatureOptions,
+  ) -> StorageResult<Cose>
+  where
+    K: JwkStoragePQ,
+    I: KeyIdStorage,
+    T: ToOwned<Owned = T> + Serialize + DeserializeOwned + Sync,
+    CRED: ToOwned<Owned = CRED> + Serialize + DeserializeOwned + Clone + Sync;
+}
+
+// ====================================================================================================================
+// Helpers
+// ====================================================================================================================
+
+/// Resolves the method identified by `fragment` and builds the protected header for it.
+fn prepare_header<'a>(
+  document: &'a CoreDocument,
+  fragment: &str,
+  options: &CoseSignatureOptions,
+) -> StorageResult<(&'a VerificationMethod, &'a Jwk, CoseHeader)> {
+  let method: &VerificationMethod = document.resolve_method(fragment, None).ok_or(Error::MethodNotFound)?;
+  let MethodData::PublicKeyJwk(ref jwk) = method.data() else {
+    return Err(Error::NotPublicKeyJwk);
+  };
+
+  // Extract the algorithm, which must have a COSE counterpart.
+  let alg: CoseAlgorithm = jwk
+    .alg()
+    .unwrap_or("")
+    .parse::<JwsAlgorithm>()
+    .ok()
+    .and_then(|alg| CoseAlgorithm::try_from(alg).ok())
+    .ok_or(Error::InvalidJwsAlgorithm)?;
+
+  let mut header: CoseHeader = CoseHeader::new();
+  header.set_alg(alg);
+  match &options.kid {
+    Some(kid) => header.set_kid(kid.as_bytes()),
+    None => header.set_kid(method.id().to_string().into_bytes()),
+  }
+  if let Some(typ) = &options.typ {
+    header.set_typ(typ.clone());
+  }
+  if let Some(content_type) = &options.content_type {
+    header.set_content_type(content_type.clone());
+  }
+
+  Ok((method, jwk, header))
+}
+
+/// Returns the options with the `typ` and content type set to the given defaults, unless already set.
+fn with_defaults(options: &CoseSignatureOptions, typ: &str, content_type: &str) -> CoseSignatureOptions {
+  let mut options: CoseSignatureOptions = options.clone();
+  options.typ.get_or_insert_with(|| typ.to_owned());
+  options.content_type.get_or_insert_with(|| content_type.to_owned());
+  options
+}
+
+/// Encodes the signed message, detaching the payload if requested in the options.
+fn encode(encoder: CoseSign1Encoder<'_>, signature: &[u8], options: &CoseSignatureOptions) -> StorageResult<Cose> {
+  if options.detached_payload {
+    encoder.into_detached_cose(signature)
+  } else {
+    encoder.into_cose(signature)
+  }
+  .map(Cose::new)
+  .map_err(|err| Error::EncodingError(err.into()))
+}
+
+fn encode_payload<S: Serialize>(value: &S) -> StorageResult<Vec<u8>> {
+  value
+    .to_json_vec()
+    .map_err(|err| Error::ClaimsSerializationError(identity_credential::Error::CosePayloadError(err.into())))
+}
+
+async fn key_id<I: KeyIdStorage>(method: &VerificationMethod, key_id_storage: &I) -> StorageResult<KeyId> {
+  // Get the key identifier corresponding to the given method from the KeyId storage.
+  let method_digest: MethodDigest = MethodDigest::new(method).map_err(Error::MethodDigestConstructionError)?;
+  <I as KeyIdStorage>::get_key_id(key_id_storage, &method_digest)
+    .await
+    .map_err(Error::KeyIdStorageError)
+}
+
+// ====================================================================================================================
+// CoreDocument
+// ====================================================================================================================
+
+#[cfg_attr(not(feature = "send-sync-storage"), async_trait(?Send))]
+#[cfg_attr(feature = "send-sync-storage", async_trait)]
+impl CoseDocumentExt for CoreDocument {
+  async fn create_cose<K, I>(
+    &self,
+    storage: &Storage<K, I>,
+    fragment: &str,
+    payload: &[u8],
+    options: &CoseSignatureOptions,
+  ) -> StorageResult<Cose>
+  where
+    K: JwkStorage,
+    I: KeyIdStorage,
+  {
+    let (method, jwk, header) = prepare_header(self, fragment, options)?;
+    let key_id: KeyId = key_id(method, storage.key_id_storage()).await?;
+
+    let encoder: CoseSign1Encoder<'_> =
+      CoseSign1Encoder::new(payload, &header).map_err(|err| Error::EncodingError(err.into()))?;
+    let signature = <K as JwkStorage>::sign(storage.key_storage(), &key_id, encoder.signing_input(), jwk)
+      .await
+      .map_err(Error::KeyStorageError)?;
+    encode(encoder, &signature, options)
+  }
+
+  async fn create_credential_cose<K, I, T>(
+    &self,
+    credential: &Credential<T>,
+    storage: &Storage<K, I>,
+    fragment: &str,
+    options: &CoseSignatureOptions,
+  ) -> StorageResult<Cose>
+  where
+    K: JwkStorage,
+    I: KeyIdStorage,
+    T: ToOwned<Owned = T> + Serialize + DeserializeOwned + Sync,
+  {
+    let payload: Vec<u8> = encode_payload(credential)?;
+    let options: CoseSignatureOptions = with_defaults(options, VC_COSE_MEDIA_TYPE, VC_CONTENT_TYPE);
+    self.create_cose(storage, fragment, &payload, &options).await
+  }
+
+  async fn create_presentation_cose<K, I, CRED, T>(
+    &self,
+    presentation: &Presentation<CRED, T>,
+    storage: &Storage<K, I>,
+    fragment: &str,
+    options: &CoseSignatureOptions,
+  ) -> StorageResult<Cose>
+  where
+    K: JwkStorage,
+    I: KeyIdStorage,
+    T: ToOwned<Owned = T> + Serialize + DeserializeOwned + Sync,
+    CRED: ToOwned<Owned = CRED> + Serialize + DeserializeOwned + Clone + Sync,
+  {
+    let payload: Vec<u8> = encode_payload(presentation)?;
+    let options: CoseSignatureOptions = with_defaults(options, VP_COSE_MEDIA_TYPE, VP_CONTENT_TYPE);
+    self.create_cose(storage, fragment, &payload, &options).await
+  }
+
+  #[cfg(feature = "pqc")]
+  async fn create_cose_pqc<K, I>(
+    &self,
+    storage: &Storage<K, I>,
+    fragment: &str,
+    payload: &[u8],
+    options: &CoseSignatureOptions,
+  ) -> StorageResult<Cose>
+  where
+    K: JwkStoragePQ,
+    I: KeyIdStorage,
+  {
+    let (method, jwk, header) = prepare_header(self, fragment, options)?;
+    let key_id: KeyId = key_id(method, storage.key_id_storage()).await?;
+
+    let encoder: CoseSign1Encoder<'_> =
+      CoseSign1Encoder::new(payload, &header).map_err(|err| Error::EncodingError(err.into()))?;
+    let signature = <K as JwkStoragePQ>::pq_sign(storage.key_storage(), &key_id, encoder.signing_input(), jwk)
+      .await
+      .map_err(Error::KeyStorageError)?;
+    encode(encoder, &signature, options)
+  }
+
+  #[cfg(feature = "pqc")]
+  async fn create_credential_cose_pqc<K, I, T>(
+    &self,
+    credential: &Credential<T>,
+    storage: &Storage<K, I>,
+    fragment: &str,
+    options: &CoseSignatureOptions,
+  ) -> StorageResult<Cose>
+  where
+    K: JwkStoragePQ,
+    I: KeyIdStorage,
+    T: ToOwned<Owned = T> + Serialize + DeserializeOwned + Sync,
+  {
+    let payload: Vec<u8> = encode_payload(credential)?;
+    let options: CoseSignatureOptions = with_defaults(options, VC_COSE_MEDIA_TYPE, VC_CONTENT_TYPE);
+    self.create_cose_pqc(storage, fragment, &payload, &options).await
+  }
+
+  #[cfg(feature = "pqc")]
+  async fn create_presentation_cose_pqc<K, I, CRED, T>(
+    &self,
+    presentation: &Presentation<CRED, T>,
+    storage: &Storage<K, I>,
+    fragment: &str,
+    options: &CoseSignatureOptions,
+  ) -> StorageResult<Cose>
+  where
+    K: JwkStoragePQ,
+    I: KeyIdStorage,
+    T: ToOwned<Owned = T> + Serialize + DeserializeOwned + Sync,
+    CRED: ToOwned<Owned = CRED> + Serialize + DeserializeOwned + Clone + Sync,
+  {
+    let payload: Vec<u8> = encode_payload(presentation)?;
+    let options: CoseSignatureOptions = with_defaults(options, VP_COSE_MEDIA_TYPE, VP_CONTENT_TYPE);
+    self.create_cose_pqc(storage, fragment, &payload, &options).await
+  }
+}
+
+// ====================================================================================================================
+// IotaDocument
+// ====================================================================================================================
+#[cfg(feature = "iota-document")]
+mod iota_document {
+  use super::*;
+  use identity_iota_core::IotaDocument;
+
+  #[cfg_attr(not(feature = "send-sync-storage"), async_trait(?Send))]
+  #[cfg_attr(feature = "send-sync-storage", async_trait)]
+  impl CoseDocumentExt for IotaDocument {
+    async fn create_cose<K, I>(
+      &self,
+      storage: &Storage<K, I>,
+      fragment: &str,
+      payload: &[u8],
+      options: &CoseSignatureOptions,
+    ) -> StorageResult<Cose>
+    where
+      K: JwkStorage,
+      I: KeyIdStorage,
+    {
+      self
+        .core_document()
+        .create_cose(storage, fragment, payload, options)
+        .await
+    }
+
+    async fn create_credential_cose<K, I, T>(
+      &self,
+      credential: &Credential<T>,
+      storage: &Storage<K, I>,
+      fragment: &str,
+      options: &CoseSignatureOptions,
+    ) -> StorageResult<Cose>
+    where
+      K: JwkStorage,
+      I: KeyIdStorage,
+      T: ToOwned<Owned = T> + Serialize + DeserializeOwned + Sync,
+    {
+      self
+        .core_document()
+        .create_credential_cose(credential, storage, fragment, options)
+        .await
+    }
+
+    async fn create_presentation_cose<K, I, CRED, T>(
+      &self,
+      presentation: &Presentation<CRED, T>,
+      storage: &Storage<K, I>,
+      fragment: &str,
+      options: &CoseSignatureOptions,
+    ) -> StorageResult<Cose>
+    where
+      K: JwkStorage,
+      I: KeyIdStorage,
+      T: ToOwned<Owned = T> + Serialize + DeserializeOwned + Sync,
+      CRED: ToOwned<Owned = CRED> + Serialize + DeserializeOwned + Clone + Sync,
+    {
+      self
+        .core_document()
+        .create_presentation_cose(presentation, storage, fragment, options)
+        .await
+    }
+
+    #[cfg(feature = "pqc")]
+    async fn create_cose_pqc<K, I>(
+      &self,
+      storage: &Storage<K, I>,
+      fragment: &str,
+      payload: &[u8],
+      options: &CoseSignatureOptions,
+    ) -> StorageResult<Cose>
+    where
+      K: JwkStoragePQ,
+      I: KeyIdStorage,
+    {
+      self
+        .core_document()
+        .create_cose_pqc(storage, fragment, payload, options)
+        .await
+    }
+
+    #[cfg(feature = "pqc")]
+    async fn create_credential_cose_pqc<K, I, T>(
+      &self,
+      credential: &Credential<T>,
+      storage: &Storage<K, I>,
+      fragment: &str,
+      options: &CoseSignatureOptions,
+    ) -> StorageResult<Cose>
+    where
+      K: JwkStoragePQ,
+      I: KeyIdStorage,
+      T: ToOwned<Owned = T> + Serialize + DeserializeOwned + Sync,
+    {
+      self
+        .core_document()
+        .create_credential_cose_pqc(credential, storage, fragment, options)
+        .await
+    }
+
+    #[cfg(feature = "pqc")]
+    async fn create_presentation_cose_pqc<K, I, CRED, T>(
+      &self,
+      presentation: &Presentation<CRED, T>,
+      storage: &Storage<K, I>,
+      fragment: &str,
+      options: &CoseSignatureOptions,
+    ) -> StorageResult<Cose>
+    where
+      K: JwkStoragePQ,
+      I: KeyIdStorage,
+      T: ToOwned<Owned = T> + Serialize + DeserializeOwned + Sync,
+      CRED: ToOwned<Owned = CRED> + Serialize + DeserializeOwned + Clone + Sync,
+    {
+      self
+        .core_document()
+        .create_presentation_cose_pqc(presentation, storage, fragment, options)
+        .await
+    }
+  }
+}
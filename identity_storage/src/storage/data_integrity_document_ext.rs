@@ -0,0 +1,615 @@
+// Copyright 2020-2024 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+use super::JwkStorageDocumentError as Error;
+use super::Storage;
+use super::StorageResult;
+
+use crate::key_id_storage::KeyIdStorage;
+use crate::key_id_storage::MethodDigest;
+use crate::key_storage::JwkStorage;
+#[cfg(feature = "bbs-2023")]
+use crate::key_storage::JwkStorageBbsPlusExt;
+#[cfg(feature = "pqc")]
+use crate::key_storage::JwkStoragePQ;
+use crate::key_storage::KeyId;
+
+use async_trait::async_trait;
+use identity_core::common::Context;
+use identity_core::common::OneOrMany;
+use identity_core::common::Timestamp;
+use identity_credential::credential::Credential;
+use identity_credential::credential::Proof;
+#[cfg(feature = "bbs-2023")]
+use identity_credential::data_integrity::Bbs2023SigningInput;
+use identity_credential::data_integrity::ContextCache;
+use identity_credential::data_integrity::Cryptosuite;
+use identity_credential::data_integrity::DataIntegrityProof;
+use identity_credential::data_integrity::ProofPurpose;
+use identity_credential::presentation::Presentation;
+use identity_document::document::CoreDocument;
+use identity_verification::jose::jws::JwsAlgorithm;
+use identity_verification::jwk::Jwk;
+use identity_verification::MethodData;
+#[cfg(feature = "bbs-2023")]
+use jsonprooftoken::jpa::algs::ProofAlgorithm;
+use identity_verification::VerificationMethod;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+/// Options for creating Data Integrity proofs.
+#[non_exhaustive]
+#[derive(Debug, Default, Clone, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DataIntegrityProofOptions {
+  /// The cryptosuite used to create the proof.
+  ///
+  /// If unset, the cryptosuite is derived from the `alg` of the signing method, preferring JCS based cryptosuites.
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub cryptosuite: Option<Cryptosuite>,
+
+  /// The purpose of the proof.
+  ///
+  /// If unset, `assertionMethod` is used for credentials and `authentication` for presentations.
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub proof_purpose: Option<ProofPurpose>,
+
+  /// The creation date of the proof.
+  ///
+  /// If unset, the current time is used, except for `bbs-2023` proofs which omit it so that it cannot be used to
+  /// correlate their holder.
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub created: Option<Timestamp>,
+
+  /// The expiration date of the proof.
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub expires: Option<Timestamp>,
+
+  /// A challenge provided by the verifier.
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub challenge: Option<String>,
+
+  /// The security domain in which the proof is meant to be used.
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub domain: Option<String>,
+
+  /// A value making the proof unique.
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub nonce: Option<String>,
+
+  /// The JSON pointers of the statements that are disclosed in every proof derived from a `bbs-2023` proof.
+  #[serde(default, skip_serializing_if = "Vec::is_empty")]
+  pub mandatory_pointers: Vec<String>,
+
+  /// The JSON-LD contexts of the secured document, required by RDFC based cryptosuites.
+  #[serde(skip)]
+  pub context_cache: ContextCache,
+}
+
+impl DataIntegrityProofOptions {
+  /// Creates a new [`DataIntegrityProofOptions`].
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  /// Replace the value of the `cryptosuite` field.
+  pub fn cryptosuite(mut self, value: Cryptosuite) -> Self {
+    self.cryptosuite = Some(value);
+    self
+  }
+
+  /// Replace the value of the `proof_purpose` field.
+  pub fn proof_purpose(mut self, value: ProofPurpose) -> Self {
+    self.proof_purpose = Some(value);
+    self
+  }
+
+  /// Replace the value of the `created` field.
+  pub fn created(mut self, value: Timestamp) -> Self {
+    self.created = Some(value);
+    self
+  }
+
+  /// Replace the value of the `expires` field.
+  pub fn expires(mut self, value: Timestamp) -> Self {
+    self.expires = Some(value);
+    self
+  }
+
+  /// Replace the value of the `challenge` field.
+  pub fn challenge(mut self, value: impl Into<String>) -> Self {
+    self.challenge = Some(value.into());
+    self
+  }
+
+  /// Replace the value of the `domain` field.
+  pub fn domain(mut self, value: impl Into<String>) -> Self {
+    self.domain = Some(value.into());
+    self
+  }
+
+  /// Replace the value of the `nonce` field.
+  pub fn nonce(mut self, value: impl Into<String>) -> Self {
+    self.nonce = Some(value.into());
+    self
+  }
+
+  /// Replace the value of the `mandatory_pointers` field.
+  pub fn mandatory_pointers(mut self, value: Vec<String>) -> Self {
+    self.mandatory_pointers = value;
+    self
+  }
+
+  /// Replace the value of the `context_cache` field.
+  pub fn context_cache(mut self, value: ContextCache) -> Self {
+    self.context_cache = value;
+    self
+  }
+}
+
+/// Extension trait for creating Data Integrity proofs with the keys of a DID document.
+///
+/// The returned [`Proof`] is meant to be embedded in the secured credential (resp. presentation) with `set_proof`.
+/// Signing is delegated to the [`JwkStorage`] (resp. [`JwkStoragePQ`] for the `_pqc` variants and
+/// `JwkStorageBbsPlusExt` for `bbs-2023`) backing the given `storage`.
+#[cfg_attr(not(feature = "send-sync-storage"), async_trait(?Send))]
+#[cfg_attr(feature = "send-sync-storage", async_trait)]
+pub trait DataIntegrityDocumentExt {
+  /// Produces a [`DataIntegrityProof`] over the given `credential` with the method identified by `fragment`.
+  ///
+  /// Any existing proof of the credential is not covered by the new proof.
+  async fn create_credential_data_integrity_proof<K, I, T>(
+    &self,
+    credential: &Credential<T>,
+    storage: &Storage<K, I>,
+    fragment: &str,
+    options: &DataIntegrityProofOptions,
+  ) -> StorageResult<Proof>
+  where
+    K: JwkStorage,
+    I: KeyIdStorage,
+    T: ToOwned<Owned = T> + Serialize + DeserializeOwned + Sync;
+
+  /// Produces a [`DataIntegrityProof`] over the given `presentation` with the method identified by `fragment`.
+  ///
+  /// Any existing proof of the presentation is not covered by the new proof.
+  async fn create_presentation_data_integrity_proof<K, I, CRED, T>(
+    &self,
+    presentation: &Presentation<CRED, T>,
+    storage: &Storage<K, I>,
+    fragment: &str,
+    options: &DataIntegrityProofOptions,
+  ) -> StorageResult<Proof>
+  where
+    K: JwkStorage,
+    I: KeyIdStorage,
+    T: ToOwned<Owned = T> + Serialize + DeserializeOwned + Sync,
+    CRED: ToOwned<Owned = CRED> + Serialize + DeserializeOwned + Clone + Sync;
+
+  /// Produces a `bbs-2023` base proof over the given `credential` with the BLS12-381 G2 method identified by
+  /// `fragment`, e.g. created with `generate_method_jwp` and the `BLS12381-SHA256` algorithm.
+  ///
+  /// The holder derives selectively disclosed credentials from the secured credential with
+  /// [`derive_bbs_2023_credential`](identity_credential::data_integrity::derive_bbs_2023_credential). The statements
+  /// selected by the `mandatory_pointers` of `options` are disclosed in every derived credential.
+  #[cfg(feature = "bbs-2023")]
+  async fn create_credential_bbs_2023_proof<K, I, T>(
+    &self,
+    credential: &Credential<T>,
+    storage: &Storage<K, I>,
+    fragment: &str,
+    options: &DataIntegrityProofOptions,
+  ) -> StorageResult<Proof>
+  where
+    K: JwkStorageBbsPlusExt,
+    I: KeyIdStorage,
+    T: ToOwned<Owned = T> + Serialize + DeserializeOwned + Sync;
+
+  /// Produces a [`DataIntegrityProof`] over the given `credential` using a PQC algorithm.
+  ///
+  /// See [`DataIntegrityDocumentExt::create_credential_data_integrity_proof`].
+  #[cfg(feature = "pqc")]
+  async fn create_credential_data_integrity_proof_pqc<K, I, T>(
+    &self,
+    credential: &Credential<T>,
+    storage: &Storage<K, I>,
+    fragment: &str,
+    options: &DataIntegrityProofOptions,
+  ) -> StorageResult<Proof>
+  where
+    K: JwkStoragePQ,
+    I: KeyIdStorage,
+    T: ToOwned<Owned = T> + Serialize + DeserializeOwned + Sync;
+
+  /// Produces a [`DataIntegrityProof`] over the given `presentation` using a PQC algorithm.
+  ///
+  /// See [`DataIntegrityDocumentExt::create_presentation_data_integrity_proof`].
+  #[cfg(feature = "pqc")]
+  async fn create_presentation_data_integrity_proof_pqc<K, I, CRED, T>(
+    &self,
+    presentation: &Presentation<CRED, T>,
+    storage: &Storage<K, I>,
+    fragment: &str,
+    options: &DataIntegrityProofOptions,
+  ) -> StorageResult<Proof>
+  where
+    K: JwkStoragePQ,
+    I: KeyIdStorage,
+    T: ToOwned<Owned = T> + Serialize + DeserializeOwned + Sync,
+    CRED: ToOwned<Owned = CRED> + Serialize + DeserializeOwned + Clone + Sync;
+}
+
+// ====================================================================================================================
+// Helpers
+// ====================================================================================================================
+
+/// An unsigned proof together with the data to sign and the key to sign it with.
+struct PreparedProof<'a> {
+  method: &'a VerificationMethod,
+  jwk: &'a Jwk,
+  proof: DataIntegrityProof,
+  hash_data: Vec<u8>,
+}
+
+/// Resolves the method identified by `fragment` and computes the data to be signed for `document`.
+fn prepare_proof<'a, S: Serialize>(
+  signer: &'a CoreDocument,
+  fragment: &str,
+  document: &S,
+  document_context: &OneOrMany<Context>,
+  default_purpose: ProofPurpose,
+  options: &DataIntegrityProofOptions,
+) -> StorageResult<PreparedProof<'a>> {
+  let method: &VerificationMethod = signer.resolve_method(fragment, None).ok_or(Error::MethodNotFound)?;
+  let MethodData::PublicKeyJwk(ref jwk) = method.data() else {
+    return Err(Error::NotPublicKeyJwk);
+  };
+
+  // The cryptosuite must be compatible with the algorithm of the key.
+  let alg: JwsAlgorithm = jwk
+    .alg()
+    .unwrap_or("")
+    .parse()
+    .map_err(|_| Error::InvalidJwsAlgorithm)?;
+  let cryptosuite: Cryptosuite = options
+    .cryptosuite
+    .or_else(|| Cryptosuite::from_alg(alg))
+    .filter(|cryptosuite| cryptosuite.alg() == Some(alg))
+    .ok_or(Error::InvalidJwsAlgorithm)?;
+
+  let mut proof = DataIntegrityProof::new(
+    cryptosuite,
+    method.id().to_string(),
+    options.proof_purpose.unwrap_or(default_purpose),
+  );
+  if !cryptosuite.is_rdfc() {
+    proof.context = Some(document_context.clone());
+  }
+  proof.created = Some(options.created.unwrap_or_else(Timestamp::now_utc));
+  proof.expires = options.expires;
+  proof.challenge.clone_from(&options.challenge);
+  proof.domain.clone_from(&options.domain);
+  proof.nonce.clone_from(&options.nonce);
+
+  let hash_data: Vec<u8> = proof
+    .hash_data(document, &options.context_cache)
+    .map_err(Error::ClaimsSerializationError)?;
+
+  Ok(PreparedProof {
+    method,
+    jwk,
+    proof,
+    hash_data,
+  })
+}
+
+fn finish_proof(mut proof: DataIntegrityProof, signature: &[u8]) -> StorageResult<Proof> {
+  proof.set_proof_value(signature);
+  Proof::try_from(proof).map_err(Error::ClaimsSerializationError)
+}
+
+async fn key_id<I: KeyIdStorage>(method: &VerificationMethod, key_id_storage: &I) -> StorageResult<KeyId> {
+  // Get the key identifier corresponding to the given method from the KeyId storage.
+  let method_digest: MethodDigest = MethodDigest::new(method).map_err(Error::MethodDigestConstructionError)?;
+  <I as KeyIdStorage>::get_key_id(key_id_storage, &method_digest)
+    .await
+    .map_err(Error::KeyIdStorageError)
+}
+
+async fn sign<K, I>(storage: &Storage<K, I>, prepared: PreparedProof<'_>) -> StorageResult<Proof>
+where
+  K: JwkStorage,
+  I: KeyIdStorage,
+{
+  let key_id: KeyId = key_id(prepared.method, storage.key_id_storage()).await?;
+  let signature: Vec<u8> = <K as JwkStorage>::sign(storage.key_storage(), &key_id, &prepared.hash_data, prepared.jwk)
+    .await
+    .map_err(Error::KeyStorageError)?;
+  finish_proof(prepared.proof, &signature)
+}
+
+#[cfg(feature = "pqc")]
+async fn sign_pqc<K, I>(storage: &Storage<K, I>, prepared: PreparedProof<'_>) -> StorageResult<Proof>
+where
+  K: JwkStoragePQ,
+  I: KeyIdStorage,
+{
+  let key_id: KeyId = key_id(prepared.method, storage.key_id_storage()).await?;
+  let signature: Vec<u8> =
+    <K as JwkStoragePQ>::pq_sign(storage.key_storage(), &key_id, &prepared.hash_data, prepared.jwk)
+      .await
+      .map_err(Error::KeyStorageError)?;
+  finish_proof(prepared.proof, &signature)
+}
+
+// ====================================================================================================================
+// CoreDocument
+// ====================================================================================================================
+
+#[cfg_attr(not(feature = "send-sync-storage"), async_trait(?Send))]
+#[cfg_attr(feature = "send-sync-storage", async_trait)]
+impl DataIntegrityDocumentExt for CoreDocument {
+  async fn create_credential_data_integrity_proof<K, I, T>(
+    &self,
+    credential: &Credential<T>,
+    storage: &Storage<K, I>,
+    fragment: &str,
+    options: &DataIntegrityProofOptions,
+  ) -> StorageResult<Proof>
+  where
+    K: JwkStorage,
+    I: KeyIdStorage,
+    T: ToOwned<Owned = T> + Serialize + DeserializeOwned + Sync,
+  {
+    let prepared: PreparedProof<'_> = prepare_proof(
+      self,
+      fragment,
+      credential,
+      &credential.context,
+      ProofPurpose::AssertionMethod,
+      options,
+    )?;
+    sign(storage, prepared).await
+  }
+
+  async fn create_presentation_data_integrity_proof<K, I, CRED, T>(
+    &self,
+    presentation: &Presentation<CRED, T>,
+    storage: &Storage<K, I>,
+    fragment: &str,
+    options: &DataIntegrityProofOptions,
+  ) -> StorageResult<Proof>
+  where
+    K: JwkStorage,
+    I: KeyIdStorage,
+    T: ToOwned<Owned = T> + Serialize + DeserializeOwned + Sync,
+    CRED: ToOwned<Owned = CRED> + Serialize + DeserializeOwned + Clone + Sync,
+  {
+    let prepared: PreparedProof<'_> = prepare_proof(
+      self,
+      fragment,
+      presentation,
+      &presentation.context,
+      ProofPurpose::Authentication,
+      options,
+    )?;
+    sign(storage, prepared).await
+  }
+
+  #[cfg(feature = "bbs-2023")]
+  async fn create_credential_bbs_2023_proof<K, I, T>(
+    &self,
+    credential: &Credential<T>,
+    storage: &Storage<K, I>,
+    fragment: &str,
+    options: &DataIntegrityProofOptions,
+  ) -> StorageResult<Proof>
+  where
+    K: JwkStorageBbsPlusExt,
+    I: KeyIdStorage,
+    T: ToOwned<Owned = T> + Serialize + DeserializeOwned + Sync,
+  {
+    let method: &VerificationMethod = self.resolve_method(fragment, None).ok_or(Error::MethodNotFound)?;
+    let MethodData::PublicKeyJwk(ref jwk) = method.data() else {
+      return Err(Error::NotPublicKeyJwk);
+    };
+    // bbs-2023 is only defined for the BLS12381-SHA256 ciphersuite.
+    if jwk.alg() != Some(ProofAlgorithm::BLS12381_SHA256.to_string().as_str())
+      || options.cryptosuite.map_or(false, |cryptosuite| cryptosuite != Cryptosuite::Bbs2023)
+    {
+      return Err(Error::InvalidJwpAlgorithm);
+    }
+
+    let mut proof = DataIntegrityProof::new(
+      Cryptosuite::Bbs2023,
+      method.id().to_string(),
+      options.proof_purpose.unwrap_or(ProofPurpose::AssertionMethod),
+    );
+    proof.created = options.created;
+    proof.expires = options.expires;
+    proof.challenge.clone_from(&options.challenge);
+    proof.domain.clone_from(&options.domain);
+    proof.nonce.clone_from(&options.nonce);
+
+    let input: Bbs2023SigningInput = Bbs2023SigningInput::new(
+      credential,
+      proof,
+      options.mandatory_pointers.clone(),
+      &options.context_cache,
+    )
+    .map_err(Error::ClaimsSerializationError)?;
+
+    let key_id: KeyId = key_id(method, storage.key_id_storage()).await?;
+    let signature: Vec<u8> = <K as JwkStorageBbsPlusExt>::sign_bbs(
+      storage.key_storage(),
+      &key_id,
+      input.bbs_messages(),
+      input.bbs_header(),
+      jwk,
+    )
+    .await
+    .map_err(Error::KeyStorageError)?;
+
+    input
+      .into_proof(&signature, jwk)
+      .and_then(Proof::try_from)
+      .map_err(Error::ClaimsSerializationError)
+  }
+
+  #[cfg(feature = "pqc")]
+  async fn create_credential_data_integrity_proof_pqc<K, I, T>(
+    &self,
+    credential: &Credential<T>,
+    storage: &Storage<K, I>,
+    fragment: &str,
+    options: &DataIntegrityProofOptions,
+  ) -> StorageResult<Proof>
+  where
+    K: JwkStoragePQ,
+    I: KeyIdStorage,
+    T: ToOwned<Owned = T> + Serialize + DeserializeOwned + Sync,
+  {
+    let prepared: PreparedProof<'_> = prepare_proof(
+      self,
+      fragment,
+      credential,
+      &credential.context,
+      ProofPurpose::AssertionMethod,
+      options,
+    )?;
+    sign_pqc(storage, prepared).await
+  }
+
+  #[cfg(feature = "pqc")]
+  async fn create_presentation_data_integrity_proof_pqc<K, I, CRED, T>(
+    &self,
+    presentation: &Presentation<CRED, T>,
+    storage: &Storage<K, I>,
+    fragment: &str,
+    options: &DataIntegrityProofOptions,
+  ) -> StorageResult<Proof>
+  where
+    K: JwkStoragePQ,
+    I: KeyIdStorage,
+    T: ToOwned<Owned = T> + Serialize + DeserializeOwned + Sync,
+    CRED: ToOwned<Owned = CRED> + Serialize + DeserializeOwned + Clone + Sync,
+  {
+    let prepared: PreparedProof<'_> = prepare_proof(
+      self,
+      fragment,
+      presentation,
+      &presentation.context,
+      ProofPurpose::Authentication,
+      options,
+    )?;
+    sign_pqc(storage, prepared).await
+  }
+}
+
+// ====================================================================================================================
+// IotaDocument
+// ====================================================================================================================
+#[cfg(feature = "iota-document")]
+mod iota_document {
+  use super::*;
+  use identity_iota_core::IotaDocument;
+
+  #[cfg_attr(not(feature = "send-sync-storage"), async_trait(?Send))]
+  #[cfg_attr(feature = "send-sync-storage", async_trait)]
+  impl DataIntegrityDocumentExt for IotaDocument {
+    async fn create_credential_data_integrity_proof<K, I, T>(
+      &self,
+      credential: &Credential<T>,
+      storage: &Storage<K, I>,
+      fragment: &str,
+      options: &DataIntegrityProofOptions,
+    ) -> StorageResult<Proof>
+    where
+      K: JwkStorage,
+      I: KeyIdStorage,
+      T: ToOwned<Owned = T> + Serialize + DeserializeOwned + Sync,
+    {
+      self
+        .core_document()
+        .create_credential_data_integrity_proof(credential, storage, fragment, options)
+        .await
+    }
+
+    async fn create_presentation_data_integrity_proof<K, I, CRED, T>(
+      &self,
+      presentation: &Presentation<CRED, T>,
+      storage: &Storage<K, I>,
+      fragment: &str,
+      options: &DataIntegrityProofOptions,
+    ) -> StorageResult<Proof>
+    where
+      K: JwkStorage,
+      I: KeyIdStorage,
+      T: ToOwned<Owned = T> + Serialize + DeserializeOwned + Sync,
+      CRED: ToOwned<Owned = CRED> + Serialize + DeserializeOwned + Clone + Sync,
+    {
+      self
+        .core_document()
+        .create_presentation_data_integrity_proof(presentation, storage, fragment, options)
+        .await
+    }
+
+    #[cfg(feature = "bbs-2023")]
+    async fn create_credential_bbs_2023_proof<K, I, T>(
+      &self,
+      credential: &Credential<T>,
+      storage: &Storage<K, I>,
+      fragment: &str,
+      options: &DataIntegrityProofOptions,
+    ) -> StorageResult<Proof>
+    where
+      K: JwkStorageBbsPlusExt,
+      I: KeyIdStorage,
+      T: ToOwned<Owned = T> + Serialize + DeserializeOwned + Sync,
+    {
+      self
+        .core_document()
+        .create_credential_bbs_2023_proof(credential, storage, fragment, options)
+        .await
+    }
+
+    #[cfg(feature = "pqc")]
+    async fn create_credential_data_integrity_proof_pqc<K, I, T>(
+      &self,
+      credential: &Credential<T>,
+      storage: &Storage<K, I>,
+      fragment: &str,
+      options: &DataIntegrityProofOptions,
+    ) -> StorageResult<Proof>
+    where
+      K: JwkStoragePQ,
+      I: KeyIdStorage,
+      T: ToOwned<Owned = T> + Serialize + DeserializeOwned + Sync,
+    {
+      self
+        .core_document()
+        .create_credential_data_integrity_proof_pqc(credential, storage, fragment, options)
+        .await
+    }
+
+    #[cfg(feature = "pqc")]
+    async fn create_presentation_data_integrity_proof_pqc<K, I, CRED, T>(
+      &self,
+      presentation: &Presentation<CRED, T>,
+      storage: &Storage<K, I>,
+      fragment: &str,
+      options: &DataIntegrityProofOptions,
+    ) -> StorageResult<Proof>
+    where
+      K: JwkStoragePQ,
+      I: KeyIdStorage,
+      T: ToOwned<Owned = T> + Serialize + DeserializeOwned + Sync,
+      CRED: ToOwned<Owned = CRED> + Serialize + DeserializeOwned + Clone + Sync,
+    {
+      self
+        .core_document()
+        .create_presentation_data_integrity_proof_pqc(presentation, storage, fragment, options)
+        .await
+    }
+  }
+}
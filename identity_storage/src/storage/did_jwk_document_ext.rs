@@ -225,10 +225,7 @@ impl DidJwkDocumentExt for CoreDocument {
   
         let composite_pk = CompositeJwk::new(alg, t_jwk, pq_jwk);
 
-        let b64 = encode_b64_json(&composite_pk)
-          .map_err(|err| Error::EncodingError(Box::new(err)))?;
-        
-        let did = DIDCompositeJwk::parse(&("did:compositejwk:".to_string() + &b64))
+        let did = DIDCompositeJwk::new(&composite_pk)
           .map_err(|err| Error::EncodingError(Box::new(err)))?;
 
         let document = CoreDocument::expand_did_compositejwk(did)
@@ -0,0 +1,124 @@
+// Copyright 2020-2024 IOTA Stiftung, Fondazione Links
+// SPDX-License-Identifier: Apache-2.0
+
+use async_trait::async_trait;
+use identity_did::DIDKey;
+use identity_did::DID;
+use identity_document::document::CoreDocument;
+use identity_verification::jwk::Jwk;
+use identity_verification::jws::JwsAlgorithm;
+use identity_verification::VerificationMethod;
+
+use super::JwkStorageDocumentError as Error;
+use crate::try_undo_key_generation;
+use crate::JwkGenOutput;
+use crate::JwkStorage;
+#[cfg(feature = "pqc")]
+use crate::JwkStoragePQ;
+use crate::KeyId;
+use crate::KeyIdStorage;
+use crate::KeyType;
+use crate::MethodDigest;
+use crate::Storage;
+use crate::StorageResult;
+
+/// Extension trait for creating `did:key` DIDs whose keys are held in a [`Storage`].
+#[cfg_attr(not(feature = "send-sync-storage"), async_trait(?Send))]
+#[cfg_attr(feature = "send-sync-storage", async_trait)]
+pub trait DidKeyDocumentExt {
+  /// Generates a new key in the given `storage` and returns the expanded document of the `did:key` DID encoding its
+  /// public key, together with the fragment of its verification method.
+  ///
+  /// The generated key must be of one of the [`DIDKeyType`](identity_did::DIDKeyType)s.
+  async fn new_did_key<K, I>(
+    storage: &Storage<K, I>,
+    key_type: KeyType,
+    alg: JwsAlgorithm,
+  ) -> StorageResult<(CoreDocument, String)>
+  where
+    K: JwkStorage,
+    I: KeyIdStorage;
+
+  /// Generates a new post-quantum key in the given `storage` and returns the expanded document of the `did:key` DID
+  /// encoding its public key, together with the fragment of its verification method.
+  ///
+  /// Only ML-DSA and SLH-DSA keys can be encoded as `did:key`.
+  #[cfg(feature = "pqc")]
+  async fn new_did_key_pqc<K, I>(
+    storage: &Storage<K, I>,
+    key_type: KeyType,
+    alg: JwsAlgorithm,
+  ) -> StorageResult<(CoreDocument, String)>
+  where
+    K: JwkStoragePQ,
+    I: KeyIdStorage;
+}
+
+#[cfg_attr(not(feature = "send-sync-storage"), async_trait(?Send))]
+#[cfg_attr(feature = "send-sync-storage", async_trait)]
+impl DidKeyDocumentExt for CoreDocument {
+  async fn new_did_key<K, I>(
+    storage: &Storage<K, I>,
+    key_type: KeyType,
+    alg: JwsAlgorithm,
+  ) -> StorageResult<(CoreDocument, String)>
+  where
+    K: JwkStorage,
+    I: KeyIdStorage,
+  {
+    let JwkGenOutput { key_id, jwk } = K::generate(storage.key_storage(), key_type, alg)
+      .await
+      .map_err(Error::KeyStorageError)?;
+
+    match expand_did_key(storage, &key_id, &jwk).await {
+      Ok(output) => Ok(output),
+      Err(err) => Err(try_undo_key_generation(storage, &key_id, err).await),
+    }
+  }
+
+  #[cfg(feature = "pqc")]
+  async fn new_did_key_pqc<K, I>(
+    storage: &Storage<K, I>,
+    key_type: KeyType,
+    alg: JwsAlgorithm,
+  ) -> StorageResult<(CoreDocument, String)>
+  where
+    K: JwkStoragePQ,
+    I: KeyIdStorage,
+  {
+    let JwkGenOutput { key_id, jwk } = K::generate_pq_key(storage.key_storage(), key_type, alg)
+      .await
+      .map_err(Error::KeyStorageError)?;
+
+    match expand_did_key(storage, &key_id, &jwk).await {
+      Ok(output) => Ok(output),
+      Err(err) => Err(try_undo_key_generation(storage, &key_id, err).await),
+    }
+  }
+}
+
+/// Expands the `did:key` DID of the generated `jwk` and associates its verification method with `key_id`.
+async fn expand_did_key<K, I>(
+  storage: &Storage<K, I>,
+  key_id: &KeyId,
+  jwk: &Jwk,
+) -> StorageResult<(CoreDocument, String)>
+where
+  K: JwkStorage,
+  I: KeyIdStorage,
+{
+  let did: DIDKey = DIDKey::from_jwk(jwk).map_err(|err| Error::EncodingError(Box::new(err)))?;
+  let fragment: String = did.method_id().to_owned();
+  let document: CoreDocument = CoreDocument::expand_did_key(did).map_err(|err| Error::EncodingError(Box::new(err)))?;
+
+  let method: &VerificationMethod = document
+    .resolve_method(fragment.as_str(), None)
+    .ok_or(identity_verification::Error::MissingIdFragment)
+    .map_err(Error::VerificationMethodConstructionError)?;
+  let method_digest: MethodDigest = MethodDigest::new(method).map_err(Error::MethodDigestConstructionError)?;
+  I::insert_key_id(storage.key_id_storage(), method_digest, key_id.clone())
+    .await
+    .map_err(Error::KeyIdStorageError)?;
+
+  Ok((document, fragment))
+}
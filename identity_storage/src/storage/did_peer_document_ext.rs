@@ -0,0 +1,407 @@
+// Copyright 2020-2024 IOTA Stiftung, Fondazione Links
+// SPDX-License-Identifier: Apache-2.0
+
+use async_trait::async_trait;
+use identity_core::common::Object;
+use identity_core::common::Value;
+use identity_core::convert::ToJson;
+use identity_did::CoreDID;
+use identity_did::DIDKey;
+use identity_did::DIDPeer;
+use identity_did::PeerNumalgo;
+use identity_did::PeerPurpose;
+use identity_did::DID;
+use identity_document::document::CoreDocument;
+#[cfg(feature = "hybrid")]
+use identity_verification::jwk::CompositeAlgId;
+#[cfg(feature = "hybrid")]
+use identity_verification::jwk::CompositeJwk;
+use identity_verification::jwk::Jwk;
+use identity_verification::jws::JwsAlgorithm;
+use identity_verification::VerificationMethod;
+
+use super::JwkStorageDocumentError as Error;
+use crate::try_undo_key_generation;
+use crate::JwkGenOutput;
+use crate::JwkStorage;
+#[cfg(feature = "pqc")]
+use crate::JwkStoragePQ;
+use crate::KeyId;
+use crate::KeyIdStorage;
+use crate::KeyType;
+use crate::MethodDigest;
+use crate::Storage;
+use crate::StorageResult;
+
+/// The DID used to build the verification method of a `did:peer:4` input document before it is made relative.
+const PLACEHOLDER_DID: &str = "did:example:peer";
+/// The fragment of the verification method of `did:peer:4` input documents.
+const INPUT_DOCUMENT_FRAGMENT: &str = "key-1";
+/// The purposes of composite keys, which cannot be used for key agreement.
+#[cfg(feature = "hybrid")]
+const SIGNING_PURPOSES: [PeerPurpose; 4] = [
+  PeerPurpose::Assertion,
+  PeerPurpose::Verification,
+  PeerPurpose::CapabilityInvocation,
+  PeerPurpose::CapabilityDelegation,
+];
+
+/// Extension trait for creating `did:peer` DIDs whose keys are held in a [`Storage`].
+#[cfg_attr(not(feature = "send-sync-storage"), async_trait(?Send))]
+#[cfg_attr(feature = "send-sync-storage", async_trait)]
+pub trait DidPeerDocumentExt {
+  /// Generates a new key in the given `storage` and returns the expanded document of a `did:peer` DID of the given
+  /// `numalgo` encoding its public key for the given `purposes` and `services`, together with the fragment of its
+  /// first verification method.
+  ///
+  /// Numalgo 0 and 2 require the generated key to be of one of the [`DIDKeyType`](identity_did::DIDKeyType)s.
+  /// Numalgo 2 encodes the key once per purpose, as the verification methods `#key-1`, `#key-2` and so on, while
+  /// numalgo 4 references its single verification method from the relationship of each purpose. Numalgo 0 is
+  /// expanded like `did:key`, so it only accepts the purposes derived from the key type and cannot encode services.
+  /// Key agreement keys can only be used for [`PeerPurpose::Encryption`], other keys for every other purpose.
+  /// Services are given with their full member names and relative ids.
+  async fn new_did_peer<K, I>(
+    storage: &Storage<K, I>,
+    numalgo: PeerNumalgo,
+    key_type: KeyType,
+    alg: JwsAlgorithm,
+    purposes: &[PeerPurpose],
+    services: &[Object],
+  ) -> StorageResult<(CoreDocument, String)>
+  where
+    K: JwkStorage,
+    I: KeyIdStorage;
+
+  /// Generates a new post-quantum key in the given `storage` and returns the expanded document of a `did:peer` DID of
+  /// the given `numalgo` encoding its public key and `services`, together with the fragment of its verification
+  /// method.
+  ///
+  /// See [`DidPeerDocumentExt::new_did_peer`] for the restrictions of each numalgo.
+  #[cfg(feature = "pqc")]
+  async fn new_did_peer_pqc<K, I>(
+    storage: &Storage<K, I>,
+    numalgo: PeerNumalgo,
+    key_type: KeyType,
+    alg: JwsAlgorithm,
+    purposes: &[PeerPurpose],
+    services: &[Object],
+  ) -> StorageResult<(CoreDocument, String)>
+  where
+    K: JwkStoragePQ,
+    I: KeyIdStorage;
+
+  /// Generates a new composite key in the given `storage` and returns the expanded document of a `did:peer:4` DID
+  /// encoding its public key and `services`, together with the fragment of its verification method.
+  ///
+  /// Composite keys have no multicodec, so they can only be encoded in the input document of numalgo 4. The key is
+  /// referenced from every verification relationship except key agreement.
+  #[cfg(feature = "hybrid")]
+  async fn new_did_peer_composite<K, I>(
+    storage: &Storage<K, I>,
+    alg: CompositeAlgId,
+    services: &[Object],
+  ) -> StorageResult<(CoreDocument, String)>
+  where
+    K: JwkStorage + JwkStoragePQ,
+    I: KeyIdStorage;
+}
+
+#[cfg_attr(not(feature = "send-sync-storage"), async_trait(?Send))]
+#[cfg_attr(feature = "send-sync-storage", async_trait)]
+impl DidPeerDocumentExt for CoreDocument {
+  async fn new_did_peer<K, I>(
+    storage: &Storage<K, I>,
+    numalgo: PeerNumalgo,
+    key_type: KeyType,
+    alg: JwsAlgorithm,
+    purposes: &[PeerPurpose],
+    services: &[Object],
+  ) -> StorageResult<(CoreDocument, String)>
+  where
+    K: JwkStorage,
+    I: KeyIdStorage,
+  {
+    check_input(numalgo, purposes, services)?;
+    let JwkGenOutput { key_id, jwk } = K::generate(storage.key_storage(), key_type, alg)
+      .await
+      .map_err(Error::KeyStorageError)?;
+
+    let output = match encode_did_peer(numalgo, &jwk, purposes, services) {
+      Ok((did, fragments)) => expand_did_peer(storage, &key_id, did, fragments).await,
+      Err(err) => Err(err),
+    };
+    match output {
+      Ok(output) => Ok(output),
+      Err(err) => Err(try_undo_key_generation(storage, &key_id, err).await),
+    }
+  }
+
+  #[cfg(feature = "pqc")]
+  async fn new_did_peer_pqc<K, I>(
+    storage: &Storage<K, I>,
+    numalgo: PeerNumalgo,
+    key_type: KeyType,
+    alg: JwsAlgorithm,
+    purposes: &[PeerPurpose],
+    services: &[Object],
+  ) -> StorageResult<(CoreDocument, String)>
+  where
+    K: JwkStoragePQ,
+    I: KeyIdStorage,
+  {
+    check_input(numalgo, purposes, services)?;
+    let JwkGenOutput { key_id, jwk } = K::generate_pq_key(storage.key_storage(), key_type, alg)
+      .await
+      .map_err(Error::KeyStorageError)?;
+
+    let output = match encode_did_peer(numalgo, &jwk, purposes, services) {
+      Ok((did, fragments)) => expand_did_peer(storage, &key_id, did, fragments).await,
+      Err(err) => Err(err),
+    };
+    match output {
+      Ok(output) => Ok(output),
+      Err(err) => Err(try_undo_key_generation(storage, &key_id, err).await),
+    }
+  }
+
+  #[cfg(feature = "hybrid")]
+  async fn new_did_peer_composite<K, I>(
+    storage: &Storage<K, I>,
+    alg: CompositeAlgId,
+    services: &[Object],
+  ) -> StorageResult<(CoreDocument, String)>
+  where
+    K: JwkStorage + JwkStoragePQ,
+    I: KeyIdStorage,
+  {
+    let (pq_key_type, pq_alg, trad_key_type, trad_alg) = match alg {
+      CompositeAlgId::IdMldsa44Ed25519Sha512 => (
+        KeyType::from_static_str("ML-DSA"),
+        JwsAlgorithm::ML_DSA_44,
+        KeyType::from_static_str("Ed25519"),
+        JwsAlgorithm::EdDSA,
+      ),
+      CompositeAlgId::IdMldsa65Ed25519Sha512 => (
+        KeyType::from_static_str("ML-DSA"),
+        JwsAlgorithm::ML_DSA_65,
+        KeyType::from_static_str("Ed25519"),
+        JwsAlgorithm::EdDSA,
+      ),
+    };
+
+    let JwkGenOutput {
+      key_id: t_key_id,
+      jwk: t_jwk,
+    } = K::generate(storage.key_storage(), trad_key_type, trad_alg)
+      .await
+      .map_err(Error::KeyStorageError)?;
+
+    let JwkGenOutput {
+      key_id: pq_key_id,
+      jwk: pq_jwk,
+    } = match K::generate_pq_key(storage.key_storage(), pq_key_type, pq_alg)
+      .await
+      .map_err(Error::KeyStorageError)
+    {
+      Ok(output) => output,
+      Err(err) => return Err(try_undo_key_generation(storage, &t_key_id, err).await),
+    };
+
+    let key_id = KeyId::new(format!("{}~{}", t_key_id.as_str(), pq_key_id.as_str()));
+    let composite_pk = CompositeJwk::new(alg, t_jwk, pq_jwk);
+
+    let output = match encode_composite_did_peer(composite_pk, services) {
+      Ok(did) => expand_did_peer(storage, &key_id, did, vec![INPUT_DOCUMENT_FRAGMENT.to_owned()]).await,
+      Err(err) => Err(err),
+    };
+    match output {
+      Ok(output) => Ok(output),
+      Err(err) => {
+        let err = try_undo_key_generation(storage, &t_key_id, err).await;
+        Err(try_undo_key_generation(storage, &pq_key_id, err).await)
+      }
+    }
+  }
+}
+
+/// Rejects purposes and services the numalgo cannot encode, before any key is generated.
+fn check_input(numalgo: PeerNumalgo, purposes: &[PeerPurpose], services: &[Object]) -> StorageResult<()> {
+  let encoding_error = |message: &'static str| Error::EncodingError(Box::new(identity_did::Error::Other(message)));
+  if numalgo == PeerNumalgo::InceptionKey && !services.is_empty() {
+    return Err(encoding_error("did:peer:0 cannot encode services"));
+  }
+  if purposes.is_empty() {
+    return Err(encoding_error("a did:peer key requires at least one purpose"));
+  }
+  if purposes
+    .iter()
+    .enumerate()
+    .any(|(index, purpose)| purposes[..index].contains(purpose))
+  {
+    return Err(encoding_error("duplicate did:peer key purpose"));
+  }
+  Ok(())
+}
+
+/// Checks that `purposes` can be used with a key agreement key, or with a signing key otherwise.
+fn check_purposes(key_agreement: bool, purposes: &[PeerPurpose]) -> StorageResult<()> {
+  if purposes
+    .iter()
+    .any(|purpose| (*purpose == PeerPurpose::Encryption) != key_agreement)
+  {
+    return Err(Error::EncodingError(Box::new(identity_did::Error::Other(
+      "the did:peer key purposes do not match the key type",
+    ))));
+  }
+  Ok(())
+}
+
+/// Encodes the generated `jwk` for `purposes` and `services` as a `did:peer` of the given `numalgo`, returning it
+/// together with the fragments of the verification methods of the key.
+fn encode_did_peer(
+  numalgo: PeerNumalgo,
+  jwk: &Jwk,
+  purposes: &[PeerPurpose],
+  services: &[Object],
+) -> StorageResult<(DIDPeer, Vec<String>)> {
+  let encoding_error = |err: identity_did::Error| Error::EncodingError(Box::new(err));
+  let did_key: DIDKey = DIDKey::from_jwk(jwk).map_err(encoding_error)?;
+  check_purposes(did_key.key_type().is_key_agreement(), purposes)?;
+
+  match numalgo {
+    PeerNumalgo::InceptionKey => {
+      // did:key expansion references signing keys from every signing relationship.
+      if !did_key.key_type().is_key_agreement() && purposes.len() != 4 {
+        return Err(encoding_error(identity_did::Error::Other(
+          "a did:peer:0 signing key is used for every signing purpose",
+        )));
+      }
+      let fragment: String = did_key.method_id().to_owned();
+      let did: DIDPeer = DIDPeer::new_numalgo0(&did_key).map_err(encoding_error)?;
+      Ok((did, vec![fragment]))
+    }
+    PeerNumalgo::MultipleInceptionKeys => {
+      let did: DIDPeer = DIDPeer::new_numalgo2(
+        purposes.iter().map(|purpose| (*purpose, did_key.clone())),
+        services.iter().cloned(),
+      )
+      .map_err(encoding_error)?;
+      let fragments: Vec<String> = (1..=purposes.len()).map(|index| format!("key-{index}")).collect();
+      Ok((did, fragments))
+    }
+    PeerNumalgo::ShortFormAndLongForm => {
+      let method: VerificationMethod =
+        VerificationMethod::new_from_jwk(placeholder_did(), did_key.jwk(), Some(INPUT_DOCUMENT_FRAGMENT))
+          .map_err(Error::VerificationMethodConstructionError)?;
+      let input_document: Object = input_document(method, purposes, services)?;
+      let did: DIDPeer = DIDPeer::new_numalgo4(&input_document).map_err(encoding_error)?;
+      Ok((did, vec![INPUT_DOCUMENT_FRAGMENT.to_owned()]))
+    }
+    _ => Err(encoding_error(identity_did::Error::Other(
+      "unsupported did:peer numalgo",
+    ))),
+  }
+}
+
+/// Encodes the generated composite public key and `services` as a `did:peer:4`.
+#[cfg(feature = "hybrid")]
+fn encode_composite_did_peer(composite_pk: CompositeJwk, services: &[Object]) -> StorageResult<DIDPeer> {
+  let method: VerificationMethod =
+    VerificationMethod::new_from_compositejwk(placeholder_did(), composite_pk, Some(INPUT_DOCUMENT_FRAGMENT))
+      .map_err(Error::VerificationMethodConstructionError)?;
+  let input_document: Object = input_document(method, &SIGNING_PURPOSES, services)?;
+  DIDPeer::new_numalgo4(&input_document).map_err(|err| Error::EncodingError(Box::new(err)))
+}
+
+fn placeholder_did() -> CoreDID {
+  CoreDID::parse(PLACEHOLDER_DID).expect("the placeholder DID is valid")
+}
+
+/// Builds the `did:peer:4` input document of a single verification `method` referenced from the relationships of
+/// `purposes` and `services`, referring to the DID through relative references only.
+fn input_document(method: VerificationMethod, purposes: &[PeerPurpose], services: &[Object]) -> StorageResult<Object> {
+  let Value::Object(mut method) = method
+    .to_json_value()
+    .map_err(|err| Error::EncodingError(Box::new(err)))?
+  else {
+    unreachable!("verification methods serialize to JSON objects");
+  };
+  let method_id: String = format!("#{INPUT_DOCUMENT_FRAGMENT}");
+  method.insert("id".to_owned(), Value::String(method_id.clone()));
+  method.remove("controller");
+
+  let mut input_document: Object = Object::new();
+  input_document.insert(
+    "verificationMethod".to_owned(),
+    Value::Array(vec![Value::Object(method)]),
+  );
+  for purpose in purposes {
+    let relationship: &str = match purpose {
+      PeerPurpose::Assertion => "assertionMethod",
+      PeerPurpose::Encryption => "keyAgreement",
+      PeerPurpose::Verification => "authentication",
+      PeerPurpose::CapabilityInvocation => "capabilityInvocation",
+      PeerPurpose::CapabilityDelegation => "capabilityDelegation",
+      _ => {
+        return Err(Error::EncodingError(Box::new(identity_did::Error::Other(
+          "unsupported did:peer key purpose",
+        ))))
+      }
+    };
+    input_document.insert(
+      relationship.to_owned(),
+      Value::Array(vec![Value::String(method_id.clone())]),
+    );
+  }
+  if !services.is_empty() {
+    let services: Vec<Value> = services
+      .iter()
+      .enumerate()
+      .map(|(index, service)| {
+        let mut service: Object = service.clone();
+        if !service.contains_key("id") {
+          let id: String = match index {
+            0 => "#service".to_owned(),
+            index => format!("#service-{index}"),
+          };
+          service.insert("id".to_owned(), Value::String(id));
+        }
+        Value::Object(service.into_iter().collect())
+      })
+      .collect();
+    input_document.insert("service".to_owned(), Value::Array(services));
+  }
+
+  Ok(input_document)
+}
+
+/// Expands the generated `did` and associates the verification methods of the given `fragments` with `key_id`.
+async fn expand_did_peer<K, I>(
+  storage: &Storage<K, I>,
+  key_id: &KeyId,
+  did: DIDPeer,
+  fragments: Vec<String>,
+) -> StorageResult<(CoreDocument, String)>
+where
+  K: JwkStorage,
+  I: KeyIdStorage,
+{
+  let document: CoreDocument = CoreDocument::expand_did_peer(did).map_err(|err| Error::EncodingError(Box::new(err)))?;
+
+  for fragment in fragments.iter() {
+    let method: &VerificationMethod = document
+      .resolve_method(fragment.as_str(), None)
+      .ok_or(identity_verification::Error::MissingIdFragment)
+      .map_err(Error::VerificationMethodConstructionError)?;
+    let method_digest: MethodDigest = MethodDigest::new(method).map_err(Error::MethodDigestConstructionError)?;
+    I::insert_key_id(storage.key_id_storage(), method_digest, key_id.clone())
+      .await
+      .map_err(Error::KeyIdStorageError)?;
+  }
+
+  let fragment: String = fragments
+    .into_iter()
+    .next()
+    .expect("did:peer encodes at least one method");
+  Ok((document, fragment))
+}
@@ -36,6 +36,18 @@ pub enum JwkStorageDocumentError {
   /// Cannot cunstruct a valid Jwp (issued or presented form)
   #[error("Not able to construct a valid Jwp")]
   JwpBuildingError,
+  /// Caused by predicates over the claims of a JPT that cannot be proven.
+  #[cfg(feature = "jpt-predicates-experimental")]
+  #[error("could not prove the requested predicates")]
+  JptPredicateError(#[source] identity_credential::Error),
+  /// Caused by a JPT bound to a holder secret that cannot be issued or presented.
+  #[cfg(feature = "jpt-predicates-experimental")]
+  #[error("could not bind the JPT to the holder secret")]
+  JptHolderBindingError(#[source] identity_credential::Error),
+  /// Caused by a post-quantum JPT that cannot be issued or presented.
+  #[cfg(feature = "jpt-pq")]
+  #[error("could not secure the post-quantum JPT")]
+  JptPqcError(#[source] identity_credential::Error),
   /// Credential's proof update internal error
   #[error("Credential's proof internal error")]
   ProofUpdateError(String),
@@ -17,8 +17,10 @@ use async_trait::async_trait;
 use crypto::hashes::Digest;
 use identity_core::common::Object;
 use identity_credential::credential::Credential;
+use identity_credential::credential::CredentialV2;
 use identity_credential::credential::Jws;
 use identity_credential::credential::Jwt;
+use identity_credential::credential::VC_JWT_MEDIA_TYPE;
 use identity_credential::presentation::JwtPresentationOptions;
 use identity_credential::presentation::Presentation;
 use identity_did::DIDUrl;
@@ -176,6 +178,24 @@ pub trait JwkDocumentExtHybrid {
     I: KeyIdStorage,
     T: ToOwned<Owned = T> + Serialize + DeserializeOwned + Sync;
 
+  /// Create a PQ/T hybrid Verifiable Credential conforming to the VC Data Model v2.0, secured in accordance with
+  /// [VC-JOSE-COSE](https://www.w3.org/TR/vc-jose-cose/#securing-vcs-with-jose).
+  ///
+  /// Unless the `typ` is explicitly set in the options, the `typ` in the protected header is
+  /// [`VC_JWT_MEDIA_TYPE`].
+  async fn create_credential_jwt_v2_hybrid<K, I, T>(
+    &self,
+    credential: &CredentialV2<T>,
+    storage: &Storage<K, I>,
+    fragment: &str,
+    options: &JwsSignatureOptions,
+    custom_claims: Option<Object>,
+  ) -> StorageResult<Jwt>
+  where
+    K: JwkStorage + JwkStoragePQ,
+    I: KeyIdStorage,
+    T: ToOwned<Owned = T> + Serialize + DeserializeOwned + Sync;
+
   /// Create a PQ/T hybrid Verifiable Presentation.
   async fn create_presentation_jwt_hybrid<K, I, CRED, T>(
     &self,
@@ -381,6 +401,45 @@ impl JwkDocumentExtHybrid for CoreDocument {
       .map(|jws| Jwt::new(jws.into()))
   }
 
+  async fn create_credential_jwt_v2_hybrid<K, I, T>(
+    &self,
+    credential: &CredentialV2<T>,
+    storage: &Storage<K, I>,
+    fragment: &str,
+    options: &JwsSignatureOptions,
+    custom_claims: Option<Object>,
+  ) -> StorageResult<Jwt>
+  where
+    K: JwkStorage + JwkStoragePQ,
+    I: KeyIdStorage,
+    T: ToOwned<Owned = T> + Serialize + DeserializeOwned + Sync,
+  {
+    if options.detached_payload {
+      return Err(Error::EncodingError(Box::<dyn std::error::Error + Send + Sync>::from(
+        "cannot use detached payload for credential signing",
+      )));
+    }
+
+    if !options.b64.unwrap_or(true) {
+      // JWTs should not have `b64` set per https://datatracker.ietf.org/doc/html/rfc7797#section-7.
+      return Err(Error::EncodingError(Box::<dyn std::error::Error + Send + Sync>::from(
+        "cannot use `b64 = false` with JWTs",
+      )));
+    }
+
+    let payload = credential
+      .serialize_jwt(custom_claims)
+      .map_err(Error::ClaimsSerializationError)?;
+    let options: JwsSignatureOptions = match options.typ {
+      Some(_) => options.clone(),
+      None => options.clone().typ(VC_JWT_MEDIA_TYPE),
+    };
+    self
+      .create_jws(storage, fragment, payload.as_bytes(), &options)
+      .await
+      .map(|jws| Jwt::new(jws.into()))
+  }
+
   async fn create_presentation_jwt_hybrid<K, I, CRED, T>(
     &self,
     presentation: &Presentation<CRED, T>,
@@ -483,6 +542,25 @@ mod iota_document {
         .await
     }
 
+    async fn create_credential_jwt_v2_hybrid<K, I, T>(
+      &self,
+      credential: &CredentialV2<T>,
+      storage: &Storage<K, I>,
+      fragment: &str,
+      options: &JwsSignatureOptions,
+      custom_claims: Option<Object>,
+    ) -> StorageResult<Jwt>
+    where
+      K: JwkStorage + JwkStoragePQ,
+      I: KeyIdStorage,
+      T: ToOwned<Owned = T> + Serialize + DeserializeOwned + Sync,
+    {
+      self
+        .core_document()
+        .create_credential_jwt_v2_hybrid(credential, storage, fragment, options, custom_claims)
+        .await
+    }
+
     async fn create_presentation_jwt_hybrid<K, I, CRED, T>(
       &self,
       presentation: &Presentation<CRED, T>,
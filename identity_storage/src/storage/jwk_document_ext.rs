@@ -17,10 +17,14 @@ use crate::key_storage::KeyType;
 use async_trait::async_trait;
 use identity_core::common::Object;
 use identity_credential::credential::Credential;
+use identity_credential::credential::CredentialV2;
 use identity_credential::credential::Jws;
 use identity_credential::credential::Jwt;
+use identity_credential::credential::VcDataModelVersion;
+use identity_credential::credential::VC_JWT_MEDIA_TYPE;
 use identity_credential::presentation::JwtPresentationOptions;
 use identity_credential::presentation::Presentation;
+use identity_credential::presentation::VP_JWT_MEDIA_TYPE;
 use identity_did::DIDUrl;
 use identity_document::document::CoreDocument;
 use identity_verification::jose::jws::CompactJwsEncoder;
@@ -116,8 +120,31 @@ pub trait JwkDocumentExt: private::Sealed {
     I: KeyIdStorage,
     T: ToOwned<Owned = T> + Serialize + DeserializeOwned + Sync;
 
+  /// Produces a JWT where the payload is the given `credential`
+  /// in accordance with [VC-JOSE-COSE](https://www.w3.org/TR/vc-jose-cose/#securing-vcs-with-jose).
+  ///
+  /// Unless the `typ` is explicitly set in the options, the `typ` in the protected header is
+  /// [`VC_JWT_MEDIA_TYPE`]. The remaining header parameters are set as in
+  /// [`JwkDocumentExt::create_credential_jwt`].
+  ///
+  /// The `custom_claims` can be used to set additional claims on the resulting JWT.
+  async fn create_credential_jwt_v2<K, I, T>(
+    &self,
+    credential: &CredentialV2<T>,
+    storage: &Storage<K, I>,
+    fragment: &str,
+    options: &JwsSignatureOptions,
+    custom_claims: Option<Object>,
+  ) -> StorageResult<Jwt>
+  where
+    K: JwkStorage,
+    I: KeyIdStorage,
+    T: ToOwned<Owned = T> + Serialize + DeserializeOwned + Sync;
+
   /// Produces a JWT where the payload is produced from the given `presentation`
-  /// in accordance with [VC Data Model v1.1](https://www.w3.org/TR/vc-data-model/#json-web-token).
+  /// in accordance with [VC Data Model v1.1](https://www.w3.org/TR/vc-data-model/#json-web-token), or
+  /// [VC-JOSE-COSE](https://www.w3.org/TR/vc-jose-cose/#securing-vps-with-jose) for VC Data Model v2.0
+  /// presentations. In the latter case the `typ` defaults to [`VP_JWT_MEDIA_TYPE`].
   ///
   /// Unless the `kid` is explicitly set in the options, the `kid` in the protected header is the `id`
   /// of the method identified by `fragment` and the JWS signature will be produced by the corresponding
@@ -472,6 +499,45 @@ impl JwkDocumentExt for CoreDocument {
       .map(|jws| Jwt::new(jws.into()))
   }
 
+  async fn create_credential_jwt_v2<K, I, T>(
+    &self,
+    credential: &CredentialV2<T>,
+    storage: &Storage<K, I>,
+    fragment: &str,
+    options: &JwsSignatureOptions,
+    custom_claims: Option<Object>,
+  ) -> StorageResult<Jwt>
+  where
+    K: JwkStorage,
+    I: KeyIdStorage,
+    T: ToOwned<Owned = T> + Serialize + DeserializeOwned + Sync,
+  {
+    if options.detached_payload {
+      return Err(Error::EncodingError(Box::<dyn std::error::Error + Send + Sync>::from(
+        "cannot use detached payload for credential signing",
+      )));
+    }
+
+    if !options.b64.unwrap_or(true) {
+      // JWTs should not have `b64` set per https://datatracker.ietf.org/doc/html/rfc7797#section-7.
+      return Err(Error::EncodingError(Box::<dyn std::error::Error + Send + Sync>::from(
+        "cannot use `b64 = false` with JWTs",
+      )));
+    }
+
+    let payload = credential
+      .serialize_jwt(custom_claims)
+      .map_err(Error::ClaimsSerializationError)?;
+    let options: JwsSignatureOptions = match options.typ {
+      Some(_) => options.clone(),
+      None => options.clone().typ(VC_JWT_MEDIA_TYPE),
+    };
+    self
+      .create_jws(storage, fragment, payload.as_bytes(), &options)
+      .await
+      .map(|jws| Jwt::new(jws.into()))
+  }
+
   async fn create_presentation_jwt<K, I, CRED, T>(
     &self,
     presentation: &Presentation<CRED, T>,
@@ -501,8 +567,12 @@ impl JwkDocumentExt for CoreDocument {
     let payload = presentation
       .serialize_jwt(jwt_options)
       .map_err(Error::ClaimsSerializationError)?;
+    let jws_options: JwsSignatureOptions = match presentation.data_model_version() {
+      Some(VcDataModelVersion::V2_0) if jws_options.typ.is_none() => jws_options.clone().typ(VP_JWT_MEDIA_TYPE),
+      _ => jws_options.clone(),
+    };
     self
-      .create_jws(storage, fragment, payload.as_bytes(), jws_options)
+      .create_jws(storage, fragment, payload.as_bytes(), &jws_options)
       .await
       .map(|jws| Jwt::new(jws.into()))
   }
@@ -607,6 +677,26 @@ mod iota_document {
         .create_credential_jwt(credential, storage, fragment, options, custom_claims)
         .await
     }
+
+    async fn create_credential_jwt_v2<K, I, T>(
+      &self,
+      credential: &CredentialV2<T>,
+      storage: &Storage<K, I>,
+      fragment: &str,
+      options: &JwsSignatureOptions,
+      custom_claims: Option<Object>,
+    ) -> StorageResult<Jwt>
+    where
+      K: JwkStorage,
+      I: KeyIdStorage,
+      T: ToOwned<Owned = T> + Serialize + DeserializeOwned + Sync,
+    {
+      self
+        .core_document()
+        .create_credential_jwt_v2(credential, storage, fragment, options, custom_claims)
+        .await
+    }
+
     async fn create_presentation_jwt<K, I, CRED, T>(
       &self,
       presentation: &Presentation<CRED, T>,
@@ -6,6 +6,8 @@ use crate::key_id_storage::MethodDigest;
 use crate::try_undo_key_generation;
 use crate::JwkGenOutput;
 use crate::JwkStorageBbsPlusExt;
+#[cfg(feature = "jpt-pq")]
+use crate::JwkStoragePQ;
 use crate::KeyIdStorage;
 use crate::KeyType;
 use crate::Storage;
@@ -13,13 +15,31 @@ use crate::StorageResult;
 use async_trait::async_trait;
 use identity_core::common::Object;
 use identity_core::convert::ToJson;
+#[cfg(feature = "jpt-pq")]
+use identity_credential::credential::pqc_issuer_protected_header;
 use identity_credential::credential::Credential;
 use identity_credential::credential::Jpt;
+#[cfg(feature = "jpt-predicates-experimental")]
+use identity_credential::credential::JptHolderBoundPresentation;
+#[cfg(feature = "jpt-predicates-experimental")]
+use identity_credential::credential::JptHolderCommitment;
+#[cfg(feature = "jpt-predicates-experimental")]
+use identity_credential::credential::JptHolderProofRequest;
+#[cfg(feature = "jpt-pq")]
+use identity_credential::credential::JptPqcSigningInput;
+#[cfg(feature = "jpt-predicates-experimental")]
+use identity_credential::credential::JptSigningInput;
 use identity_credential::credential::JwpCredentialOptions;
+#[cfg(feature = "jpt-pq")]
+use identity_credential::credential::JPT_PQC_ALGORITHM;
 use identity_credential::presentation::JwpPresentationOptions;
 use identity_credential::presentation::SelectiveDisclosurePresentation;
 use identity_did::DIDUrl;
 use identity_document::document::CoreDocument;
+#[cfg(feature = "jpt-pq")]
+use identity_verification::jose::jwk::Jwk as PqcJwk;
+#[cfg(feature = "jpt-pq")]
+use identity_verification::jws::JwsAlgorithm;
 use identity_verification::MethodData;
 use identity_verification::MethodScope;
 use identity_verification::VerificationMethod;
@@ -30,6 +50,8 @@ use jsonprooftoken::jwk::key::Jwk;
 use jsonprooftoken::jwp::header::IssuerProtectedHeader;
 use jsonprooftoken::jwp::header::PresentationProtectedHeader;
 use jsonprooftoken::jwp::issued::JwpIssuedBuilder;
+#[cfg(feature = "jpt-predicates-experimental")]
+use jsonprooftoken::jwp::presented::JwpPresented;
 use serde::de::DeserializeOwned;
 use serde::Serialize;
 
@@ -94,6 +116,75 @@ pub trait JwpDocumentExt {
     method_id: &str,
     options: &JwpPresentationOptions,
   ) -> StorageResult<Jpt>;
+
+  /// Commits to the holder secret stored with the BBS+ method identified by `fragment`, proving knowledge of the
+  /// secret bound to the `nonce` chosen by the issuer.
+  ///
+  /// The issuer binds the credential to the secret with
+  /// [`JwpCredentialOptions::holder_commitment`].
+  #[cfg(feature = "jpt-predicates-experimental")]
+  async fn create_jpt_holder_commitment<K, I>(
+    &self,
+    storage: &Storage<K, I>,
+    fragment: &str,
+    nonce: &str,
+  ) -> StorageResult<JptHolderCommitment>
+  where
+    K: JwkStorageBbsPlusExt,
+    I: KeyIdStorage;
+
+  /// Produces a JPT where the payload contains the Selective Disclosed attributes of a `credential` bound to a holder
+  /// secret, proving knowledge of the secret stored with the `holder_method`, and disclosing the pseudonym of the
+  /// holder for [`JwpPresentationOptions::verifier_id`] if set.
+  #[cfg(feature = "jpt-predicates-experimental")]
+  async fn create_holder_bound_presentation_jpt<K, I>(
+    &self,
+    presentation: &mut SelectiveDisclosurePresentation,
+    method_id: &str,
+    storage: &Storage<K, I>,
+    holder_method: &VerificationMethod,
+    options: &JwpPresentationOptions,
+  ) -> StorageResult<Jpt>
+  where
+    K: JwkStorageBbsPlusExt,
+    I: KeyIdStorage;
+
+  /// Produces a post-quantum JPT where the payload is produced from the given `credential`. The claims are committed
+  /// to with salted hashes, signed with the PQC method identified by `fragment`, e.g. ML-DSA, and bound to the
+  /// `holder_key`, which signs the presentations of the credential.
+  ///
+  /// Predicates and holder secrets set in the `options` require BBS+ and are not supported.
+  #[cfg(feature = "jpt-pq")]
+  async fn create_credential_jpt_pqc<K, I, T>(
+    &self,
+    credential: &Credential<T>,
+    storage: &Storage<K, I>,
+    fragment: &str,
+    holder_key: &PqcJwk,
+    options: &JwpCredentialOptions,
+    custom_claims: Option<Object>,
+  ) -> StorageResult<Jpt>
+  where
+    K: JwkStoragePQ,
+    I: KeyIdStorage,
+    T: ToOwned<Owned = T> + Serialize + DeserializeOwned + Sync;
+
+  /// Produces a post-quantum JPT where the payload contains the Selective Disclosed attributes of a `credential`
+  /// issued with [`create_credential_jpt_pqc`](JwpDocumentExt::create_credential_jpt_pqc), signing the presentation
+  /// with the PQC method of this holder document identified by `fragment`.
+  ///
+  /// Predicates and pseudonyms set in the `options` require BBS+ and are not supported.
+  #[cfg(feature = "jpt-pq")]
+  async fn create_presentation_jpt_pqc<K, I>(
+    &self,
+    presentation: &mut SelectiveDisclosurePresentation,
+    storage: &Storage<K, I>,
+    fragment: &str,
+    options: &JwpPresentationOptions,
+  ) -> StorageResult<Jpt>
+  where
+    K: JwkStoragePQ,
+    I: KeyIdStorage;
 }
 
 // ====================================================================================================================
@@ -180,12 +271,49 @@ impl JwpDocumentExt for CoreDocument {
       |p| p.to_bytes().map_err(|_| Error::JwpBuildingError),
     )?;
 
-    let signature = <K as JwkStorageBbsPlusExt>::sign_bbs(storage.key_storage(), &key_id, &data, &header, jwk)
+    #[cfg(feature = "jpt-predicates-experimental")]
+    let proof = if let Some(ref holder_commitment) = options.holder_commitment {
+      // The commitment to the holder secret is signed together with the claims, encoded as for predicate claims.
+      let input = JptSigningInput::new(&jwp_builder, &options.predicate_claims)
+        .map_err(Error::JptPredicateError)?
+        .holder_commitment(holder_commitment)
+        .map_err(Error::JptHolderBindingError)?;
+      let signature = <K as JwkStorageBbsPlusExt>::sign_bbs_blind(
+        storage.key_storage(),
+        &key_id,
+        input.bbs_messages(),
+        &input.bbs_holder_commitment().expect("holder commitment is set"),
+        input.bbs_header(),
+        jwk,
+      )
+      .await
+      .map_err(Error::KeyStorageError)?;
+      input.into_proof(signature)
+    } else if options.predicate_claims.is_empty() {
+      <K as JwkStorageBbsPlusExt>::sign_bbs(storage.key_storage(), &key_id, &data, &header, jwk)
+        .await
+        .map_err(Error::KeyStorageError)?
+    } else {
+      // Claims that predicates can be proven over are signed as integers rather than as their JSON encoding.
+      let input = JptSigningInput::new(&jwp_builder, &options.predicate_claims).map_err(Error::JptPredicateError)?;
+      let signature = <K as JwkStorageBbsPlusExt>::sign_bbs_scalars(
+        storage.key_storage(),
+        &key_id,
+        input.bbs_messages(),
+        input.bbs_header(),
+        jwk,
+      )
+      .await
+      .map_err(Error::KeyStorageError)?;
+      input.into_proof(signature)
+    };
+    #[cfg(not(feature = "jpt-predicates-experimental"))]
+    let proof = <K as JwkStorageBbsPlusExt>::sign_bbs(storage.key_storage(), &key_id, &data, &header, jwk)
       .await
       .map_err(Error::KeyStorageError)?;
 
     jwp_builder
-      .build_with_proof(signature)
+      .build_with_proof(proof)
       .map_err(|_| Error::JwpBuildingError)?
       .encode(SerializationType::COMPACT)
       .map_err(|err| Error::EncodingError(Box::new(err)))
@@ -197,30 +325,17 @@ impl JwpDocumentExt for CoreDocument {
     method_id: &str,
     options: &JwpPresentationOptions,
   ) -> StorageResult<String> {
-    // Obtain the method corresponding to the given fragment.
-    let method: &VerificationMethod = self.resolve_method(method_id, None).ok_or(Error::MethodNotFound)?;
-    let MethodData::PublicKeyJwk(ref jwk) = method.data() else {
-      return Err(Error::NotPublicKeyJwk);
-    };
-
-    // Extract JwsAlgorithm.
-    let alg: ProofAlgorithm = jwk
-      .alg()
-      .unwrap_or("")
-      .parse()
-      .map_err(|_| Error::InvalidJwpAlgorithm)?;
-
-    let public_key: Jwk = jwk.try_into().map_err(|_| Error::NotPublicKeyJwk)?;
-
-    let mut presentation_header = PresentationProtectedHeader::new(alg.into());
-    presentation_header.set_nonce(options.nonce.clone());
-    presentation_header.set_aud(options.audience.as_ref().map(|u| u.to_string()));
-
-    presentation.set_presentation_header(presentation_header);
-
-    let jwp_builder = presentation.builder();
-
-    let presented_jwp = jwp_builder.build(&public_key).map_err(|_| Error::JwpBuildingError)?;
+    let public_key: Jwk = set_presentation_header(self, presentation, method_id, options)?;
+
+    #[cfg(feature = "jpt-predicates-experimental")]
+    let presented_jwp = presentation
+      .build(&public_key, &options.predicates)
+      .map_err(presentation_error)?;
+    #[cfg(not(feature = "jpt-predicates-experimental"))]
+    let presented_jwp = presentation
+      .builder()
+      .build(&public_key)
+      .map_err(|_| Error::JwpBuildingError)?;
 
     Ok(
       presented_jwp
@@ -263,6 +378,246 @@ impl JwpDocumentExt for CoreDocument {
       .await
       .map(Jpt::new)
   }
+
+  #[cfg(feature = "jpt-predicates-experimental")]
+  async fn create_jpt_holder_commitment<K, I>(
+    &self,
+    storage: &Storage<K, I>,
+    fragment: &str,
+    nonce: &str,
+  ) -> StorageResult<JptHolderCommitment>
+  where
+    K: JwkStorageBbsPlusExt,
+    I: KeyIdStorage,
+  {
+    // Obtain the method corresponding to the given fragment.
+    let method: &VerificationMethod = self.resolve_method(fragment, None).ok_or(Error::MethodNotFound)?;
+    let MethodData::PublicKeyJwk(ref jwk) = method.data() else {
+      return Err(Error::NotPublicKeyJwk);
+    };
+    let alg: ProofAlgorithm = jwk
+      .alg()
+      .unwrap_or("")
+      .parse()
+      .map_err(|_| Error::InvalidJwpAlgorithm)?;
+
+    let request: JptHolderProofRequest =
+      JptHolderCommitment::request(alg, nonce).map_err(Error::JptHolderBindingError)?;
+    let proof: Vec<u8> = prove_holder_secret(storage, method, &request).await?;
+
+    JptHolderCommitment::new(alg, nonce, &proof).map_err(Error::JptHolderBindingError)
+  }
+
+  #[cfg(feature = "jpt-predicates-experimental")]
+  async fn create_holder_bound_presentation_jpt<K, I>(
+    &self,
+    presentation: &mut SelectiveDisclosurePresentation,
+    method_id: &str,
+    storage: &Storage<K, I>,
+    holder_method: &VerificationMethod,
+    options: &JwpPresentationOptions,
+  ) -> StorageResult<Jpt>
+  where
+    K: JwkStorageBbsPlusExt,
+    I: KeyIdStorage,
+  {
+    let public_key: Jwk = set_presentation_header(self, presentation, method_id, options)?;
+
+    let holder_bound: JptHolderBoundPresentation<'_> = presentation
+      .holder_bound(&public_key, &options.predicates, options.verifier_id.as_deref())
+      .map_err(presentation_error)?;
+    let proof: Vec<u8> = prove_holder_secret(storage, holder_method, holder_bound.request()).await?;
+    let presented_jwp: JwpPresented = holder_bound.build(&proof).map_err(presentation_error)?;
+
+    presented_jwp
+      .encode(SerializationType::COMPACT)
+      .map(Jpt::new)
+      .map_err(|e| Error::EncodingError(Box::new(e)))
+  }
+
+  #[cfg(feature = "jpt-pq")]
+  async fn create_credential_jpt_pqc<K, I, T>(
+    &self,
+    credential: &Credential<T>,
+    storage: &Storage<K, I>,
+    fragment: &str,
+    holder_key: &PqcJwk,
+    options: &JwpCredentialOptions,
+    custom_claims: Option<Object>,
+  ) -> StorageResult<Jpt>
+  where
+    K: JwkStoragePQ,
+    I: KeyIdStorage,
+    T: ToOwned<Owned = T> + Serialize + DeserializeOwned + Sync,
+  {
+    #[cfg(feature = "jpt-predicates-experimental")]
+    if !options.predicate_claims.is_empty() || options.holder_commitment.is_some() {
+      return Err(Error::JptPqcError(identity_credential::Error::JwpPqcError(
+        "predicate claims and holder secrets require BBS+".to_owned(),
+      )));
+    }
+
+    let jpt_claims = credential
+      .serialize_jpt(custom_claims)
+      .map_err(Error::ClaimsSerializationError)?;
+
+    // Obtain the method corresponding to the given fragment.
+    let method: &VerificationMethod = self.resolve_method(fragment, None).ok_or(Error::MethodNotFound)?;
+    let MethodData::PublicKeyJwk(ref jwk) = method.data() else {
+      return Err(Error::NotPublicKeyJwk);
+    };
+    jwk
+      .alg()
+      .unwrap_or("")
+      .parse::<JwsAlgorithm>()
+      .map_err(|_| Error::InvalidJwsAlgorithm)?;
+
+    let kid = if let Some(ref kid) = options.kid {
+      kid.clone()
+    } else {
+      method.id().to_string()
+    };
+
+    let mut issuer_header = pqc_issuer_protected_header();
+    issuer_header.set_kid(Some(kid));
+
+    let jwp_builder = JwpIssuedBuilder::new(issuer_header, jpt_claims);
+    let input = JptPqcSigningInput::new(&jwp_builder, holder_key).map_err(Error::JptPqcError)?;
+    let signature: Vec<u8> = pq_sign(storage, method, input.signing_input()).await?;
+
+    jwp_builder
+      .build_with_proof(input.into_proof(signature))
+      .map_err(|_| Error::JwpBuildingError)?
+      .encode(SerializationType::COMPACT)
+      .map(Jpt::new)
+      .map_err(|err| Error::EncodingError(Box::new(err)))
+  }
+
+  #[cfg(feature = "jpt-pq")]
+  async fn create_presentation_jpt_pqc<K, I>(
+    &self,
+    presentation: &mut SelectiveDisclosurePresentation,
+    storage: &Storage<K, I>,
+    fragment: &str,
+    options: &JwpPresentationOptions,
+  ) -> StorageResult<Jpt>
+  where
+    K: JwkStoragePQ,
+    I: KeyIdStorage,
+  {
+    #[cfg(feature = "jpt-predicates-experimental")]
+    if !options.predicates.is_empty() || options.verifier_id.is_some() {
+      return Err(Error::JptPqcError(identity_credential::Error::JwpPqcError(
+        "predicates and pseudonyms require BBS+".to_owned(),
+      )));
+    }
+
+    // Obtain the holder's method corresponding to the given fragment.
+    let method: &VerificationMethod = self.resolve_method(fragment, None).ok_or(Error::MethodNotFound)?;
+
+    let mut presentation_header = PresentationProtectedHeader::new(JPT_PQC_ALGORITHM.into());
+    presentation_header.set_nonce(options.nonce.clone());
+    presentation_header.set_aud(options.audience.as_ref().map(|u| u.to_string()));
+    presentation.set_presentation_header(presentation_header);
+
+    let signing_input: Vec<u8> = presentation.pqc_signing_input().map_err(presentation_error)?;
+    let signature: Vec<u8> = pq_sign(storage, method, &signing_input).await?;
+
+    presentation
+      .build_pqc(&signature)
+      .map_err(presentation_error)?
+      .encode(SerializationType::COMPACT)
+      .map(Jpt::new)
+      .map_err(|e| Error::EncodingError(Box::new(e)))
+  }
+}
+
+/// Sets the Presentation Protected Header of `presentation`, returning the public key of the issuer's method.
+fn set_presentation_header(
+  issuer: &CoreDocument,
+  presentation: &mut SelectiveDisclosurePresentation,
+  method_id: &str,
+  options: &JwpPresentationOptions,
+) -> StorageResult<Jwk> {
+  // Obtain the method corresponding to the given fragment.
+  let method: &VerificationMethod = issuer.resolve_method(method_id, None).ok_or(Error::MethodNotFound)?;
+  let MethodData::PublicKeyJwk(ref jwk) = method.data() else {
+    return Err(Error::NotPublicKeyJwk);
+  };
+
+  // Extract JwsAlgorithm.
+  let alg: ProofAlgorithm = jwk
+    .alg()
+    .unwrap_or("")
+    .parse()
+    .map_err(|_| Error::InvalidJwpAlgorithm)?;
+
+  let public_key: Jwk = jwk.try_into().map_err(|_| Error::NotPublicKeyJwk)?;
+
+  let mut presentation_header = PresentationProtectedHeader::new(alg.into());
+  presentation_header.set_nonce(options.nonce.clone());
+  presentation_header.set_aud(options.audience.as_ref().map(|u| u.to_string()));
+
+  presentation.set_presentation_header(presentation_header);
+
+  Ok(public_key)
+}
+
+#[cfg(any(feature = "jpt-predicates-experimental", feature = "jpt-pq"))]
+fn presentation_error(err: identity_credential::Error) -> Error {
+  match err {
+    #[cfg(feature = "jpt-predicates-experimental")]
+    identity_credential::Error::JptPredicateError(_) => Error::JptPredicateError(err),
+    #[cfg(feature = "jpt-predicates-experimental")]
+    identity_credential::Error::JptHolderBindingError(_) => Error::JptHolderBindingError(err),
+    #[cfg(feature = "jpt-pq")]
+    identity_credential::Error::JwpPqcError(_) => Error::JptPqcError(err),
+    _ => Error::JwpBuildingError,
+  }
+}
+
+/// Answers `request` with the key storage holding the holder secret of `method`.
+#[cfg(feature = "jpt-predicates-experimental")]
+async fn prove_holder_secret<K, I>(
+  storage: &Storage<K, I>,
+  method: &VerificationMethod,
+  request: &JptHolderProofRequest,
+) -> StorageResult<Vec<u8>>
+where
+  K: JwkStorageBbsPlusExt,
+  I: KeyIdStorage,
+{
+  let MethodData::PublicKeyJwk(ref jwk) = method.data() else {
+    return Err(Error::NotPublicKeyJwk);
+  };
+  let method_digest: MethodDigest = MethodDigest::new(method).map_err(Error::MethodDigestConstructionError)?;
+  let key_id = <I as KeyIdStorage>::get_key_id(storage.key_id_storage(), &method_digest)
+    .await
+    .map_err(Error::KeyIdStorageError)?;
+
+  <K as JwkStorageBbsPlusExt>::prove_bbs_holder_secret(storage.key_storage(), &key_id, jwk, request)
+    .await
+    .map_err(Error::KeyStorageError)
+}
+
+/// Signs `data` with the PQC key stored for `method`.
+#[cfg(feature = "jpt-pq")]
+async fn pq_sign<K, I>(storage: &Storage<K, I>, method: &VerificationMethod, data: &[u8]) -> StorageResult<Vec<u8>>
+where
+  K: JwkStoragePQ,
+  I: KeyIdStorage,
+{
+  let MethodData::PublicKeyJwk(ref jwk) = method.data() else {
+    return Err(Error::NotPublicKeyJwk);
+  };
+  let method_digest: MethodDigest = MethodDigest::new(method).map_err(Error::MethodDigestConstructionError)?;
+  let key_id = <I as KeyIdStorage>::get_key_id(storage.key_id_storage(), &method_digest)
+    .await
+    .map_err(Error::KeyIdStorageError)?;
+
+  <K as JwkStoragePQ>::pq_sign(storage.key_storage(), &key_id, data, jwk)
+    .await
+    .map_err(Error::KeyStorageError)
 }
 
 // ====================================================================================================================
@@ -357,5 +712,80 @@ mod iota_document {
         .create_presentation_jpt(presentation, method_id, options)
         .await
     }
+
+    #[cfg(feature = "jpt-predicates-experimental")]
+    async fn create_jpt_holder_commitment<K, I>(
+      &self,
+      storage: &Storage<K, I>,
+      fragment: &str,
+      nonce: &str,
+    ) -> StorageResult<JptHolderCommitment>
+    where
+      K: JwkStorageBbsPlusExt,
+      I: KeyIdStorage,
+    {
+      self
+        .core_document()
+        .create_jpt_holder_commitment(storage, fragment, nonce)
+        .await
+    }
+
+    #[cfg(feature = "jpt-predicates-experimental")]
+    async fn create_holder_bound_presentation_jpt<K, I>(
+      &self,
+      presentation: &mut SelectiveDisclosurePresentation,
+      method_id: &str,
+      storage: &Storage<K, I>,
+      holder_method: &VerificationMethod,
+      options: &JwpPresentationOptions,
+    ) -> StorageResult<Jpt>
+    where
+      K: JwkStorageBbsPlusExt,
+      I: KeyIdStorage,
+    {
+      self
+        .core_document()
+        .create_holder_bound_presentation_jpt(presentation, method_id, storage, holder_method, options)
+        .await
+    }
+
+    #[cfg(feature = "jpt-pq")]
+    async fn create_credential_jpt_pqc<K, I, T>(
+      &self,
+      credential: &Credential<T>,
+      storage: &Storage<K, I>,
+      fragment: &str,
+      holder_key: &PqcJwk,
+      options: &JwpCredentialOptions,
+      custom_claims: Option<Object>,
+    ) -> StorageResult<Jpt>
+    where
+      K: JwkStoragePQ,
+      I: KeyIdStorage,
+      T: ToOwned<Owned = T> + Serialize + DeserializeOwned + Sync,
+    {
+      self
+        .core_document()
+        .create_credential_jpt_pqc(credential, storage, fragment, holder_key, options, custom_claims)
+        .await
+    }
+
+    #[cfg(feature = "jpt-pq")]
+    async fn create_presentation_jpt_pqc<K, I>(
+      &self,
+      presentation: &mut SelectiveDisclosurePresentation,
+      storage: &Storage<K, I>,
+      fragment: &str,
+      options: &JwpPresentationOptions,
+    ) -> StorageResult<Jpt>
+    where
+      K: JwkStoragePQ,
+      I: KeyIdStorage,
+    {
+      self
+        .core_document()
+        .create_presentation_jpt_pqc(presentation, storage, fragment, options)
+        .await
+    }
   }
 }
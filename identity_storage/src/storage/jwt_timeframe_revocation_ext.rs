@@ -0,0 +1,252 @@
+// Copyright 2020-2024 IOTA Stiftung, Fondazione Links
+// SPDX-License-Identifier: Apache-2.0
+
+use super::JwkStorageDocumentError as Error;
+use crate::JwkDocumentExt;
+#[cfg(feature = "hybrid")]
+use crate::JwkDocumentExtHybrid;
+use crate::JwkStorage;
+#[cfg(feature = "pqc")]
+use crate::JwkStoragePQ;
+#[cfg(feature = "pqc")]
+use crate::JwsDocumentExtPQC;
+use crate::JwsSignatureOptions;
+use crate::KeyIdStorage;
+use crate::Storage;
+use crate::StorageResult;
+use async_trait::async_trait;
+use identity_core::common::Duration;
+use identity_core::common::Timestamp;
+use identity_core::common::Url;
+use identity_core::convert::ToJson;
+use identity_credential::credential::Jws;
+use identity_credential::credential::Jwt;
+use identity_credential::revocation::TimeframeAttestationClaims;
+use identity_did::DID;
+use identity_document::document::CoreDocument;
+
+/// CoreDocument and IotaDocument extension to refresh the `RevocationTimeframe2024` validity timeframe of JWT
+/// credentials.
+///
+/// As the signature of a JWT credential cannot be updated, the issuer signs a compact validity timeframe attestation
+/// bound to the `id` of the credential instead, which holders present together with the credential. Verifiers check it
+/// with [`JwtCredentialValidatorUtils::check_timeframe_attestation`](identity_credential::validator::JwtCredentialValidatorUtils::check_timeframe_attestation).
+#[cfg_attr(not(feature = "send-sync-storage"), async_trait(?Send))]
+#[cfg_attr(feature = "send-sync-storage", async_trait)]
+pub trait JwtTimeframeRevocationExtension {
+  /// Attest that the credential identified by `credential_id` is valid for `duration` starting at `start_validity`, or
+  /// now if unset, signing the attestation with the method identified by `fragment`.
+  ///
+  /// The `typ` of the attestation is [`TimeframeAttestationClaims::TYP`] unless set in the `options`.
+  async fn create_timeframe_attestation<K, I>(
+    &self,
+    storage: &Storage<K, I>,
+    fragment: &str,
+    credential_id: &Url,
+    start_validity: Option<Timestamp>,
+    duration: Duration,
+    options: &JwsSignatureOptions,
+  ) -> StorageResult<Jwt>
+  where
+    K: JwkStorage,
+    I: KeyIdStorage;
+
+  /// Like [`create_timeframe_attestation`](JwtTimeframeRevocationExtension::create_timeframe_attestation), signing the
+  /// attestation with the PQC method identified by `fragment`.
+  #[cfg(feature = "pqc")]
+  async fn create_timeframe_attestation_pqc<K, I>(
+    &self,
+    storage: &Storage<K, I>,
+    fragment: &str,
+    credential_id: &Url,
+    start_validity: Option<Timestamp>,
+    duration: Duration,
+    options: &JwsSignatureOptions,
+  ) -> StorageResult<Jwt>
+  where
+    K: JwkStoragePQ,
+    I: KeyIdStorage;
+
+  /// Like [`create_timeframe_attestation`](JwtTimeframeRevocationExtension::create_timeframe_attestation), signing the
+  /// attestation with the PQ/T hybrid method identified by `fragment`.
+  #[cfg(feature = "hybrid")]
+  async fn create_timeframe_attestation_hybrid<K, I>(
+    &self,
+    storage: &Storage<K, I>,
+    fragment: &str,
+    credential_id: &Url,
+    start_validity: Option<Timestamp>,
+    duration: Duration,
+    options: &JwsSignatureOptions,
+  ) -> StorageResult<Jwt>
+  where
+    K: JwkStorage + JwkStoragePQ,
+    I: KeyIdStorage;
+}
+
+/// Returns the payload of an attestation by `issuer` and the options to sign it with.
+fn attestation_payload(
+  issuer: &CoreDocument,
+  credential_id: &Url,
+  start_validity: Option<Timestamp>,
+  duration: Duration,
+  options: &JwsSignatureOptions,
+) -> StorageResult<(Vec<u8>, JwsSignatureOptions)> {
+  let issuer_url: Url = Url::parse(issuer.id().as_str()).map_err(|err| Error::EncodingError(Box::new(err)))?;
+  let claims: TimeframeAttestationClaims =
+    TimeframeAttestationClaims::new(issuer_url, credential_id.clone(), start_validity, duration)
+      .map_err(Error::ClaimsSerializationError)?;
+  let payload: Vec<u8> = claims
+    .to_json_vec()
+    .map_err(|err| Error::EncodingError(Box::new(err)))?;
+
+  let mut options: JwsSignatureOptions = options.clone();
+  if options.typ.is_none() {
+    options.typ = Some(TimeframeAttestationClaims::TYP.to_owned());
+  }
+  Ok((payload, options))
+}
+
+fn into_jwt(jws: Jws) -> Jwt {
+  Jwt::new(jws.into())
+}
+
+// ====================================================================================================================
+// CoreDocument
+// ====================================================================================================================
+
+#[cfg_attr(not(feature = "send-sync-storage"), async_trait(?Send))]
+#[cfg_attr(feature = "send-sync-storage", async_trait)]
+impl JwtTimeframeRevocationExtension for CoreDocument {
+  async fn create_timeframe_attestation<K, I>(
+    &self,
+    storage: &Storage<K, I>,
+    fragment: &str,
+    credential_id: &Url,
+    start_validity: Option<Timestamp>,
+    duration: Duration,
+    options: &JwsSignatureOptions,
+  ) -> StorageResult<Jwt>
+  where
+    K: JwkStorage,
+    I: KeyIdStorage,
+  {
+    let (payload, options) = attestation_payload(self, credential_id, start_validity, duration, options)?;
+    JwkDocumentExt::create_jws(self, storage, fragment, &payload, &options)
+      .await
+      .map(into_jwt)
+  }
+
+  #[cfg(feature = "pqc")]
+  async fn create_timeframe_attestation_pqc<K, I>(
+    &self,
+    storage: &Storage<K, I>,
+    fragment: &str,
+    credential_id: &Url,
+    start_validity: Option<Timestamp>,
+    duration: Duration,
+    options: &JwsSignatureOptions,
+  ) -> StorageResult<Jwt>
+  where
+    K: JwkStoragePQ,
+    I: KeyIdStorage,
+  {
+    let (payload, options) = attestation_payload(self, credential_id, start_validity, duration, options)?;
+    self
+      .create_jws_pqc(storage, fragment, &payload, &options)
+      .await
+      .map(into_jwt)
+  }
+
+  #[cfg(feature = "hybrid")]
+  async fn create_timeframe_attestation_hybrid<K, I>(
+    &self,
+    storage: &Storage<K, I>,
+    fragment: &str,
+    credential_id: &Url,
+    start_validity: Option<Timestamp>,
+    duration: Duration,
+    options: &JwsSignatureOptions,
+  ) -> StorageResult<Jwt>
+  where
+    K: JwkStorage + JwkStoragePQ,
+    I: KeyIdStorage,
+  {
+    let (payload, options) = attestation_payload(self, credential_id, start_validity, duration, options)?;
+    JwkDocumentExtHybrid::create_jws(self, storage, fragment, &payload, &options)
+      .await
+      .map(into_jwt)
+  }
+}
+
+// ====================================================================================================================
+// IotaDocument
+// ====================================================================================================================
+#[cfg(feature = "iota-document")]
+mod iota_document {
+  use super::*;
+  use identity_iota_core::IotaDocument;
+
+  #[cfg_attr(not(feature = "send-sync-storage"), async_trait(?Send))]
+  #[cfg_attr(feature = "send-sync-storage", async_trait)]
+  impl JwtTimeframeRevocationExtension for IotaDocument {
+    async fn create_timeframe_attestation<K, I>(
+      &self,
+      storage: &Storage<K, I>,
+      fragment: &str,
+      credential_id: &Url,
+      start_validity: Option<Timestamp>,
+      duration: Duration,
+      options: &JwsSignatureOptions,
+    ) -> StorageResult<Jwt>
+    where
+      K: JwkStorage,
+      I: KeyIdStorage,
+    {
+      self
+        .core_document()
+        .create_timeframe_attestation(storage, fragment, credential_id, start_validity, duration, options)
+        .await
+    }
+
+    #[cfg(feature = "pqc")]
+    async fn create_timeframe_attestation_pqc<K, I>(
+      &self,
+      storage: &Storage<K, I>,
+      fragment: &str,
+      credential_id: &Url,
+      start_validity: Option<Timestamp>,
+      duration: Duration,
+      options: &JwsSignatureOptions,
+    ) -> StorageResult<Jwt>
+    where
+      K: JwkStoragePQ,
+      I: KeyIdStorage,
+    {
+      self
+        .core_document()
+        .create_timeframe_attestation_pqc(storage, fragment, credential_id, start_validity, duration, options)
+        .await
+    }
+
+    #[cfg(feature = "hybrid")]
+    async fn create_timeframe_attestation_hybrid<K, I>(
+      &self,
+      storage: &Storage<K, I>,
+      fragment: &str,
+      credential_id: &Url,
+      start_validity: Option<Timestamp>,
+      duration: Duration,
+      options: &JwsSignatureOptions,
+    ) -> StorageResult<Jwt>
+    where
+      K: JwkStorage + JwkStoragePQ,
+      I: KeyIdStorage,
+    {
+      self
+        .core_document()
+        .create_timeframe_attestation_hybrid(storage, fragment, credential_id, start_validity, duration, options)
+        .await
+    }
+  }
+}
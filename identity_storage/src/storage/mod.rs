@@ -3,11 +3,16 @@
 
 //! This module provides a type wrapping a key and key id storage.
 
+#[cfg(feature = "cose")]
+mod cose_document_ext;
+#[cfg(feature = "data-integrity")]
+mod data_integrity_document_ext;
 mod error;
 #[macro_use]
 mod jwk_document_ext;
 #[cfg(feature = "jpt-bbs-plus")]
 mod jwp_document_ext;
+mod jwt_timeframe_revocation_ext;
 mod signature_options;
 #[cfg(feature = "jpt-bbs-plus")]
 mod timeframe_revocation_ext;
@@ -17,15 +22,22 @@ mod hybrid_jws_document_ext;
 mod pqc_jws_document_ext;
 
 mod did_jwk_document_ext;
+mod did_key_document_ext;
+mod did_peer_document_ext;
 
 #[cfg(all(test, feature = "memstore"))]
 pub(crate) mod tests;
 
+#[cfg(feature = "cose")]
+pub use cose_document_ext::*;
+#[cfg(feature = "data-integrity")]
+pub use data_integrity_document_ext::*;
 pub use error::*;
 
 pub use jwk_document_ext::*;
 #[cfg(feature = "jpt-bbs-plus")]
 pub use jwp_document_ext::*;
+pub use jwt_timeframe_revocation_ext::*;
 pub use signature_options::*;
 #[cfg(feature = "jpt-bbs-plus")]
 pub use timeframe_revocation_ext::*;
@@ -35,6 +47,8 @@ pub use hybrid_jws_document_ext::*;
 pub use pqc_jws_document_ext::*;
 
 pub use did_jwk_document_ext::*;
+pub use did_key_document_ext::*;
+pub use did_peer_document_ext::*;
 
 /// A type wrapping a key and key id storage, typically used with [`JwkStorage`](crate::key_storage::JwkStorage) and
 /// [`KeyIdStorage`](crate::key_id_storage::KeyIdStorage) that should always be used together when calling methods from
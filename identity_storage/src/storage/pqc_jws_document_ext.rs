@@ -11,8 +11,10 @@ use crate::StorageResult;
 use async_trait::async_trait;
 use identity_core::common::Object;
 use identity_credential::credential::Credential;
+use identity_credential::credential::CredentialV2;
 use identity_credential::credential::Jws;
 use identity_credential::credential::Jwt;
+use identity_credential::credential::VC_JWT_MEDIA_TYPE;
 use identity_credential::presentation::JwtPresentationOptions;
 use identity_credential::presentation::Presentation;
 use identity_did::DIDUrl;
@@ -79,6 +81,27 @@ pub trait JwsDocumentExtPQC {
     I: KeyIdStorage,
     T: ToOwned<Owned = T> + Serialize + DeserializeOwned + Sync;
 
+  /// Produces a JWT using PQC algorithms where the payload is the given `credential`
+  /// in accordance with [VC-JOSE-COSE](https://www.w3.org/TR/vc-jose-cose/#securing-vcs-with-jose).
+  ///
+  /// Unless the `typ` is explicitly set in the options, the `typ` in the protected header is
+  /// [`VC_JWT_MEDIA_TYPE`]. The remaining header parameters are set as in
+  /// [`JwsDocumentExtPQC::create_credential_jwt_pqc`].
+  ///
+  /// The `custom_claims` can be used to set additional claims on the resulting JWT.
+  async fn create_credential_jwt_v2_pqc<K, I, T>(
+    &self,
+    credential: &CredentialV2<T>,
+    storage: &Storage<K, I>,
+    fragment: &str,
+    options: &JwsSignatureOptions,
+    custom_claims: Option<Object>,
+  ) -> StorageResult<Jwt>
+  where
+    K: JwkStoragePQ,
+    I: KeyIdStorage,
+    T: ToOwned<Owned = T> + Serialize + DeserializeOwned + Sync;
+
   /// Produces a JWT using PQC algorithms where the payload is produced from the given `presentation`
   /// in accordance with [VC Data Model v1.1](https://www.w3.org/TR/vc-data-model/#json-web-token).
   ///
@@ -264,6 +287,45 @@ impl JwsDocumentExtPQC for CoreDocument {
       .map(|jws| Jwt::new(jws.into()))
   }
 
+  async fn create_credential_jwt_v2_pqc<K, I, T>(
+    &self,
+    credential: &CredentialV2<T>,
+    storage: &Storage<K, I>,
+    fragment: &str,
+    options: &JwsSignatureOptions,
+    custom_claims: Option<Object>,
+  ) -> StorageResult<Jwt>
+  where
+    K: JwkStoragePQ,
+    I: KeyIdStorage,
+    T: ToOwned<Owned = T> + Serialize + DeserializeOwned + Sync,
+  {
+    if options.detached_payload {
+      return Err(Error::EncodingError(Box::<dyn std::error::Error + Send + Sync>::from(
+        "cannot use detached payload for credential signing",
+      )));
+    }
+
+    if !options.b64.unwrap_or(true) {
+      // JWTs should not have `b64` set per https://datatracker.ietf.org/doc/html/rfc7797#section-7.
+      return Err(Error::EncodingError(Box::<dyn std::error::Error + Send + Sync>::from(
+        "cannot use `b64 = false` with JWTs",
+      )));
+    }
+
+    let payload = credential
+      .serialize_jwt(custom_claims)
+      .map_err(Error::ClaimsSerializationError)?;
+    let options: JwsSignatureOptions = match options.typ {
+      Some(_) => options.clone(),
+      None => options.clone().typ(VC_JWT_MEDIA_TYPE),
+    };
+    self
+      .create_jws_pqc(storage, fragment, payload.as_bytes(), &options)
+      .await
+      .map(|jws| Jwt::new(jws.into()))
+  }
+
   async fn create_presentation_jwt_pqc<K, I, CRED, T>(
     &self,
     presentation: &Presentation<CRED, T>,
@@ -371,6 +433,25 @@ mod iota_document {
         .await
     }
 
+    async fn create_credential_jwt_v2_pqc<K, I, T>(
+      &self,
+      credential: &CredentialV2<T>,
+      storage: &Storage<K, I>,
+      fragment: &str,
+      options: &JwsSignatureOptions,
+      custom_claims: Option<Object>,
+    ) -> StorageResult<Jwt>
+    where
+      K: JwkStoragePQ,
+      I: KeyIdStorage,
+      T: ToOwned<Owned = T> + Serialize + DeserializeOwned + Sync,
+    {
+      self
+        .core_document()
+        .create_credential_jwt_v2_pqc(credential, storage, fragment, options, custom_claims)
+        .await
+    }
+
     async fn create_presentation_jwt_pqc<K, I, CRED, T>(
       &self,
       presentation: &Presentation<CRED, T>,
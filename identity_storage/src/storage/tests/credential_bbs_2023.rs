@@ -0,0 +1,208 @@
+// Copyright 2020-2024 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+use identity_core::common::Object;
+use identity_credential::credential::Credential;
+use identity_credential::credential::Proof;
+use identity_credential::credential::Subject;
+use identity_credential::data_integrity::derive_bbs_2023_credential;
+use identity_credential::data_integrity::Cryptosuite;
+use identity_credential::data_integrity::DataIntegrityProof;
+use identity_credential::validator::DataIntegrityValidator;
+use identity_credential::validator::DataIntegrityVerificationOptions;
+use identity_credential::validator::FailFast;
+use identity_credential::validator::JwtCredentialValidationOptions;
+use identity_credential::validator::JwtValidationError;
+use identity_document::document::CoreDocument;
+use identity_eddsa_verifier::EdDSAJwsVerifier;
+use identity_verification::MethodScope;
+use jsonprooftoken::jpa::algs::ProofAlgorithm;
+use serde_json::json;
+
+use crate::key_storage::JwkMemStore;
+use crate::storage::tests::credential_data_integrity::context_cache;
+use crate::storage::tests::test_utils::generate_credential;
+use crate::storage::tests::test_utils::setup_coredocument;
+use crate::storage::tests::test_utils::setup_iotadocument;
+use crate::storage::tests::test_utils::CredentialSetup;
+use crate::storage::tests::test_utils::Setup;
+use crate::DataIntegrityDocumentExt;
+use crate::DataIntegrityProofOptions;
+use crate::JwkDocumentExt;
+use crate::JwkStorageDocumentError;
+use crate::JwpDocumentExt;
+
+fn pointers(pointers: &[&str]) -> Vec<String> {
+  pointers.iter().map(ToString::to_string).collect()
+}
+
+async fn credential_bbs_2023_impl<T>(mut setup: Setup<T, T>)
+where
+  T: JwkDocumentExt + JwpDocumentExt + DataIntegrityDocumentExt + AsRef<CoreDocument>,
+{
+  let fragment: String = setup
+    .issuer_doc
+    .generate_method_jwp(
+      &setup.issuer_storage,
+      JwkMemStore::BLS12381G2_KEY_TYPE,
+      ProofAlgorithm::BLS12381_SHA256,
+      None,
+      MethodScope::assertion_method(),
+    )
+    .await
+    .unwrap();
+  let CredentialSetup {
+    mut credential,
+    issuance_date,
+    expiration_date,
+  } = generate_credential(&setup.issuer_doc, &[&setup.subject_doc], None, None);
+
+  let proof: Proof = setup
+    .issuer_doc
+    .create_credential_bbs_2023_proof(
+      &credential,
+      &setup.issuer_storage,
+      &fragment,
+      &DataIntegrityProofOptions::default()
+        .mandatory_pointers(pointers(&["/issuer", "/issuanceDate", "/credentialSubject/id"]))
+        .context_cache(context_cache()),
+    )
+    .await
+    .unwrap();
+  let base_proof: DataIntegrityProof = DataIntegrityProof::try_from(&proof).unwrap();
+  assert_eq!(base_proof.cryptosuite, Cryptosuite::Bbs2023);
+  assert!(base_proof.created.is_none());
+  credential.set_proof(Some(proof));
+
+  // EdDSA methods cannot create bbs-2023 proofs.
+  assert!(matches!(
+    setup
+      .issuer_doc
+      .create_credential_bbs_2023_proof(
+        &credential,
+        &setup.issuer_storage,
+        &setup.issuer_method_fragment,
+        &DataIntegrityProofOptions::default().context_cache(context_cache()),
+      )
+      .await
+      .unwrap_err(),
+    JwkStorageDocumentError::InvalidJwpAlgorithm
+  ));
+
+  let validator = DataIntegrityValidator::with_signature_verifier(EdDSAJwsVerifier::default(), context_cache());
+  let options: JwtCredentialValidationOptions = JwtCredentialValidationOptions::default()
+    .latest_issuance_date(issuance_date)
+    .earliest_expiry_date(expiration_date);
+
+  // Base proofs are only meant for the holder.
+  assert!(validator
+    .validate::<_, Object>(&credential, &setup.issuer_doc, &options, FailFast::FirstError)
+    .is_err());
+
+  // Only the mandatory statements and the name of the degree are disclosed.
+  let derived: Credential = derive_bbs_2023_credential(
+    &credential,
+    &pointers(&["/credentialSubject/degree/name"]),
+    b"challenge",
+    &context_cache(),
+  )
+  .unwrap();
+  let subject: &Subject = derived.credential_subject.first().unwrap();
+  assert_eq!(subject.id, credential.credential_subject.first().unwrap().id);
+  assert_eq!(
+    subject.properties["degree"],
+    json!({"type": "BachelorDegree", "name": "Bachelor of Science and Arts"})
+  );
+  assert!(!subject.properties.contains_key("name"));
+  assert!(!subject.properties.contains_key("GPA"));
+  assert!(derived.expiration_date.is_none());
+  assert_eq!(derived.issuer, credential.issuer);
+
+  let proof: DataIntegrityProof = validator
+    .validate(&derived, &setup.issuer_doc, &options, FailFast::FirstError)
+    .unwrap();
+  assert_eq!(proof.cryptosuite, Cryptosuite::Bbs2023);
+
+  // The derived proof is bound to the presentation header.
+  let trusted_issuers: &[T] = std::slice::from_ref(&setup.issuer_doc);
+  assert!(validator
+    .verify_credential_proof(
+      &derived,
+      trusted_issuers,
+      &DataIntegrityVerificationOptions::default().presentation_header(b"challenge".to_vec())
+    )
+    .is_ok());
+  assert!(matches!(
+    validator
+      .verify_credential_proof(
+        &derived,
+        trusted_issuers,
+        &DataIntegrityVerificationOptions::default().presentation_header(b"other".to_vec())
+      )
+      .unwrap_err(),
+    JwtValidationError::DataIntegrityProofError(_)
+  ));
+
+  // Disclosed statements cannot be modified and undisclosed statements cannot be added.
+  let mut tampered: Credential = derived.clone();
+  tampered
+    .credential_subject
+    .get_mut(0)
+    .unwrap()
+    .properties
+    .get_mut("degree")
+    .unwrap()["name"] = json!("Doctor of Philosophy");
+  assert!(validator
+    .verify_credential_proof(&tampered, trusted_issuers, &DataIntegrityVerificationOptions::default())
+    .is_err());
+  let mut tampered: Credential = derived.clone();
+  tampered
+    .credential_subject
+    .get_mut(0)
+    .unwrap()
+    .properties
+    .insert("GPA".to_owned(), json!("4.0"));
+  assert!(validator
+    .verify_credential_proof(&tampered, trusted_issuers, &DataIntegrityVerificationOptions::default())
+    .is_err());
+
+  // Only the mandatory statements are disclosed.
+  let minimal: Credential = derive_bbs_2023_credential(&credential, &[], &[], &context_cache()).unwrap();
+  assert!(!minimal
+    .credential_subject
+    .first()
+    .unwrap()
+    .properties
+    .contains_key("degree"));
+  assert!(validator
+    .validate(&minimal, &setup.issuer_doc, &options, FailFast::FirstError)
+    .is_ok());
+
+  // Everything is disclosed.
+  let full: Credential = derive_bbs_2023_credential(
+    &credential,
+    &pointers(&["/credentialSubject", "/expirationDate", "/type"]),
+    &[],
+    &context_cache(),
+  )
+  .unwrap();
+  assert_eq!(full.credential_subject, credential.credential_subject);
+  assert!(validator
+    .validate(&full, &setup.issuer_doc, &options, FailFast::FirstError)
+    .is_ok());
+
+  // Pointers must match the credential.
+  assert!(derive_bbs_2023_credential(
+    &credential,
+    &pointers(&["/credentialSubject/age"]),
+    &[],
+    &context_cache()
+  )
+  .is_err());
+}
+
+#[tokio::test]
+async fn credential_bbs_2023() {
+  credential_bbs_2023_impl(setup_coredocument(None, None).await).await;
+  credential_bbs_2023_impl(setup_iotadocument(None, None).await).await;
+}
@@ -0,0 +1,245 @@
+// Copyright 2020-2024 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+use identity_core::common::Duration;
+use identity_core::common::Object;
+use identity_core::common::Url;
+use identity_core::convert::ToJson;
+use identity_credential::credential::Cose;
+use identity_credential::credential::Credential;
+use identity_credential::credential::VC_CONTENT_TYPE;
+use identity_credential::credential::VC_COSE_MEDIA_TYPE;
+use identity_credential::credential::VP_COSE_MEDIA_TYPE;
+use identity_credential::presentation::Presentation;
+use identity_credential::presentation::PresentationBuilder;
+use identity_credential::validator::CoseCredentialValidator;
+use identity_credential::validator::CosePresentationValidator;
+use identity_credential::validator::DecodedCoseCredential;
+use identity_credential::validator::DecodedCosePresentation;
+use identity_credential::validator::FailFast;
+use identity_credential::validator::JwtCredentialValidationOptions;
+use identity_credential::validator::JwtValidationError;
+use identity_did::DID;
+use identity_document::document::CoreDocument;
+use identity_document::verifiable::JwsVerificationOptions;
+use identity_eddsa_verifier::EdDSAJwsVerifier;
+use identity_verification::jose::cose::CoseAlgorithm;
+use identity_verification::jose::cose::CoseSign1;
+use once_cell::sync::Lazy;
+
+use crate::storage::tests::test_utils::generate_credential;
+use crate::storage::tests::test_utils::setup_coredocument;
+use crate::storage::tests::test_utils::setup_iotadocument;
+use crate::storage::tests::test_utils::CredentialSetup;
+use crate::storage::tests::test_utils::Setup;
+use crate::CoseDocumentExt;
+use crate::CoseSignatureOptions;
+use crate::JwkDocumentExt;
+
+static COSE_CREDENTIAL_VALIDATOR_ED25519: Lazy<CoseCredentialValidator<EdDSAJwsVerifier>> =
+  Lazy::new(|| CoseCredentialValidator::with_signature_verifier(EdDSAJwsVerifier::default()));
+
+static COSE_PRESENTATION_VALIDATOR_ED25519: Lazy<CosePresentationValidator<EdDSAJwsVerifier>> =
+  Lazy::new(|| CosePresentationValidator::with_signature_verifier(EdDSAJwsVerifier::default()));
+
+async fn credential_cose_impl<T>(setup: Setup<T, T>)
+where
+  T: JwkDocumentExt + CoseDocumentExt + AsRef<CoreDocument>,
+{
+  let CredentialSetup {
+    credential,
+    issuance_date,
+    expiration_date,
+  } = generate_credential(&setup.issuer_doc, &[&setup.subject_doc], None, None);
+
+  let cose: Cose = setup
+    .issuer_doc
+    .create_credential_cose(
+      &credential,
+      &setup.issuer_storage,
+      &setup.issuer_method_fragment,
+      &CoseSignatureOptions::default(),
+    )
+    .await
+    .unwrap();
+
+  let message: CoseSign1 = CoseSign1::from_slice(cose.as_bytes()).unwrap();
+  assert_eq!(message.alg(), Some(CoseAlgorithm::EdDSA));
+  assert_eq!(message.protected_header().typ(), Some(VC_COSE_MEDIA_TYPE));
+  assert_eq!(message.protected_header().content_type(), Some(VC_CONTENT_TYPE));
+  let method_id: String = setup
+    .issuer_doc
+    .as_ref()
+    .resolve_method(setup.issuer_method_fragment.as_str(), None)
+    .unwrap()
+    .id()
+    .to_string();
+  assert_eq!(message.kid(), Some(method_id.as_bytes()));
+
+  let options: JwtCredentialValidationOptions = JwtCredentialValidationOptions::default()
+    .latest_issuance_date(issuance_date)
+    .earliest_expiry_date(expiration_date);
+  let decoded: DecodedCoseCredential = COSE_CREDENTIAL_VALIDATOR_ED25519
+    .validate(&cose, &setup.issuer_doc, &options, FailFast::FirstError)
+    .unwrap();
+  assert_eq!(decoded.credential, credential);
+
+  // The credential has expired.
+  let options: JwtCredentialValidationOptions = JwtCredentialValidationOptions::default()
+    .latest_issuance_date(issuance_date)
+    .earliest_expiry_date(expiration_date.checked_add(Duration::seconds(1)).unwrap());
+  let validation_errors = COSE_CREDENTIAL_VALIDATOR_ED25519
+    .validate::<_, Object>(&cose, &setup.issuer_doc, &options, FailFast::AllErrors)
+    .unwrap_err()
+    .validation_errors;
+  assert!(matches!(
+    validation_errors.as_slice(),
+    [JwtValidationError::ExpirationDate]
+  ));
+
+  // The subject is not a trusted issuer.
+  let validation_errors = COSE_CREDENTIAL_VALIDATOR_ED25519
+    .validate::<_, Object>(&cose, &setup.subject_doc, &options, FailFast::FirstError)
+    .unwrap_err()
+    .validation_errors;
+  assert!(matches!(
+    validation_errors.as_slice(),
+    [JwtValidationError::DocumentMismatch { .. }]
+  ));
+
+  // Tampering with the message invalidates the signature.
+  let mut tampered: Vec<u8> = cose.clone().into();
+  let last: usize = tampered.len() - 1;
+  tampered[last] ^= 1;
+  let validation_errors = COSE_CREDENTIAL_VALIDATOR_ED25519
+    .validate::<_, Object>(
+      &Cose::new(tampered),
+      &setup.issuer_doc,
+      &JwtCredentialValidationOptions::default(),
+      FailFast::FirstError,
+    )
+    .unwrap_err()
+    .validation_errors;
+  assert!(matches!(
+    validation_errors.as_slice(),
+    [JwtValidationError::Signature { .. }]
+  ));
+
+  // A detached payload must be supplied to the validator and is bound by the signature.
+  let detached: Cose = setup
+    .issuer_doc
+    .create_credential_cose(
+      &credential,
+      &setup.issuer_storage,
+      &setup.issuer_method_fragment,
+      &CoseSignatureOptions::new().detached_payload(true),
+    )
+    .await
+    .unwrap();
+  let payload: Vec<u8> = credential.to_json_vec().unwrap();
+  let options: JwtCredentialValidationOptions = JwtCredentialValidationOptions::default()
+    .latest_issuance_date(issuance_date)
+    .earliest_expiry_date(expiration_date);
+  let decoded: DecodedCoseCredential = COSE_CREDENTIAL_VALIDATOR_ED25519
+    .validate_detached(&detached, &payload, &setup.issuer_doc, &options, FailFast::FirstError)
+    .unwrap();
+  assert_eq!(decoded.credential, credential);
+  let validation_errors = COSE_CREDENTIAL_VALIDATOR_ED25519
+    .validate::<_, Object>(&detached, &setup.issuer_doc, &options, FailFast::FirstError)
+    .unwrap_err()
+    .validation_errors;
+  assert!(matches!(
+    validation_errors.as_slice(),
+    [JwtValidationError::CoseDecodingError(_)]
+  ));
+  let validation_errors = COSE_CREDENTIAL_VALIDATOR_ED25519
+    .validate_detached::<_, Object>(&cose, &payload, &setup.issuer_doc, &options, FailFast::FirstError)
+    .unwrap_err()
+    .validation_errors;
+  assert!(matches!(
+    validation_errors.as_slice(),
+    [JwtValidationError::CoseDecodingError(_)]
+  ));
+  let mut other_credential: Credential = credential.clone();
+  other_credential.id = Some(Url::parse("https://example.com/credentials/other").unwrap());
+  let validation_errors = COSE_CREDENTIAL_VALIDATOR_ED25519
+    .validate_detached::<_, Object>(
+      &detached,
+      &other_credential.to_json_vec().unwrap(),
+      &setup.issuer_doc,
+      &options,
+      FailFast::FirstError,
+    )
+    .unwrap_err()
+    .validation_errors;
+  assert!(matches!(
+    validation_errors.as_slice(),
+    [JwtValidationError::Signature { .. }]
+  ));
+
+  // A presentation must not be accepted as a credential.
+  let presentation: Presentation<Credential> = PresentationBuilder::new(
+    Url::parse(setup.subject_doc.as_ref().id().as_str()).unwrap(),
+    Object::new(),
+  )
+  .credential(credential.clone())
+  .build()
+  .unwrap();
+  let presentation_cose: Cose = setup
+    .subject_doc
+    .create_presentation_cose(
+      &presentation,
+      &setup.subject_storage,
+      &setup.subject_method_fragment,
+      &CoseSignatureOptions::default(),
+    )
+    .await
+    .unwrap();
+  assert_eq!(
+    CoseSign1::from_slice(presentation_cose.as_bytes())
+      .unwrap()
+      .protected_header()
+      .typ(),
+    Some(VP_COSE_MEDIA_TYPE)
+  );
+  assert!(matches!(
+    COSE_CREDENTIAL_VALIDATOR_ED25519
+      .verify_signature::<_, Object>(
+        &presentation_cose,
+        std::slice::from_ref(setup.subject_doc.as_ref()),
+        &JwsVerificationOptions::default()
+      )
+      .unwrap_err(),
+    JwtValidationError::CoseDecodingError(_)
+  ));
+
+  let decoded: DecodedCosePresentation<Credential> = COSE_PRESENTATION_VALIDATOR_ED25519
+    .validate(
+      &presentation_cose,
+      &setup.subject_doc,
+      &JwsVerificationOptions::default(),
+    )
+    .unwrap();
+  assert_eq!(decoded.presentation, presentation);
+  assert_eq!(decoded.presentation.verifiable_credential[0], credential);
+
+  // The issuer is not the holder of the presentation.
+  let validation_errors = COSE_PRESENTATION_VALIDATOR_ED25519
+    .validate::<_, Credential, Object>(
+      &presentation_cose,
+      &setup.issuer_doc,
+      &JwsVerificationOptions::default(),
+    )
+    .unwrap_err()
+    .presentation_validation_errors;
+  assert!(matches!(
+    validation_errors.as_slice(),
+    [JwtValidationError::DocumentMismatch { .. }]
+  ));
+}
+
+#[tokio::test]
+async fn credential_cose() {
+  credential_cose_impl(setup_coredocument(None, None).await).await;
+  credential_cose_impl(setup_iotadocument(None, None).await).await;
+}
@@ -0,0 +1,209 @@
+// Copyright 2020-2024 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+use identity_core::common::Object;
+use identity_core::common::Timestamp;
+use identity_core::common::Url;
+use identity_credential::credential::Credential;
+use identity_credential::credential::Proof;
+use identity_credential::data_integrity::ContextCache;
+use identity_credential::data_integrity::Cryptosuite;
+use identity_credential::data_integrity::DataIntegrityProof;
+use identity_credential::data_integrity::ProofPurpose;
+use identity_credential::presentation::Presentation;
+use identity_credential::presentation::PresentationBuilder;
+use identity_credential::validator::DataIntegrityValidator;
+use identity_credential::validator::DataIntegrityVerificationOptions;
+use identity_credential::validator::FailFast;
+use identity_credential::validator::JwtCredentialValidationOptions;
+use identity_credential::validator::JwtValidationError;
+use identity_did::DID;
+use identity_document::document::CoreDocument;
+use identity_eddsa_verifier::EdDSAJwsVerifier;
+use serde_json::json;
+
+use crate::storage::tests::test_utils::generate_credential;
+use crate::storage::tests::test_utils::setup_coredocument;
+use crate::storage::tests::test_utils::setup_iotadocument;
+use crate::storage::tests::test_utils::CredentialSetup;
+use crate::storage::tests::test_utils::Setup;
+use crate::DataIntegrityDocumentExt;
+use crate::DataIntegrityProofOptions;
+use crate::JwkDocumentExt;
+
+/// A stand-in for the credentials context, mapping all terms into a test vocabulary.
+pub(super) fn context_cache() -> ContextCache {
+  ContextCache::new().with_context(
+    "https://www.w3.org/2018/credentials/v1",
+    json!({
+      "@context": {
+        "@version": 1.1,
+        "@protected": true,
+        "@vocab": "https://example.org/vocab#",
+        "id": "@id",
+        "type": "@type",
+        "issuer": {"@id": "https://example.org/vocab#issuer", "@type": "@id"},
+        "issuanceDate": {
+          "@id": "https://example.org/vocab#issuanceDate",
+          "@type": "http://www.w3.org/2001/XMLSchema#dateTime"
+        },
+        "created": {
+          "@id": "http://purl.org/dc/terms/created",
+          "@type": "http://www.w3.org/2001/XMLSchema#dateTime"
+        },
+        "verificationMethod": {"@id": "https://w3id.org/security#verificationMethod", "@type": "@id"},
+        "verifiableCredential": {"@id": "https://example.org/vocab#verifiableCredential", "@container": "@graph"}
+      }
+    }),
+  )
+}
+
+async fn credential_data_integrity_impl<T>(setup: Setup<T, T>)
+where
+  T: JwkDocumentExt + DataIntegrityDocumentExt + AsRef<CoreDocument>,
+{
+  let CredentialSetup {
+    mut credential,
+    issuance_date,
+    expiration_date,
+  } = generate_credential(&setup.issuer_doc, &[&setup.subject_doc], None, None);
+  let options: JwtCredentialValidationOptions = JwtCredentialValidationOptions::default()
+    .latest_issuance_date(issuance_date)
+    .earliest_expiry_date(expiration_date);
+  let validator = DataIntegrityValidator::with_signature_verifier(EdDSAJwsVerifier::default(), context_cache());
+
+  for cryptosuite in [Cryptosuite::EddsaJcs2022, Cryptosuite::EddsaRdfc2022] {
+    let proof: Proof = setup
+      .issuer_doc
+      .create_credential_data_integrity_proof(
+        &credential,
+        &setup.issuer_storage,
+        &setup.issuer_method_fragment,
+        &DataIntegrityProofOptions::default()
+          .cryptosuite(cryptosuite)
+          .context_cache(context_cache()),
+      )
+      .await
+      .unwrap();
+    credential.set_proof(Some(proof));
+
+    let proof: DataIntegrityProof = validator
+      .validate(&credential, &setup.issuer_doc, &options, FailFast::FirstError)
+      .unwrap();
+    assert_eq!(proof.cryptosuite, cryptosuite);
+    assert_eq!(proof.proof_purpose, ProofPurpose::AssertionMethod);
+    assert_eq!(proof.context.is_some(), cryptosuite == Cryptosuite::EddsaJcs2022);
+
+    // Contexts are never fetched.
+    let uncached_validator =
+      DataIntegrityValidator::with_signature_verifier(EdDSAJwsVerifier::default(), ContextCache::new());
+    let result =
+      uncached_validator.validate::<_, Object>(&credential, &setup.issuer_doc, &options, FailFast::FirstError);
+    assert_eq!(result.is_ok(), cryptosuite == Cryptosuite::EddsaJcs2022);
+
+    // Tampering with the credential invalidates the proof.
+    let mut tampered: Credential = credential.clone();
+    tampered.id = Some(Url::parse("https://example.edu/credentials/1337").unwrap());
+    let validation_errors = validator
+      .validate::<_, Object>(&tampered, &setup.issuer_doc, &options, FailFast::FirstError)
+      .unwrap_err()
+      .validation_errors;
+    assert!(matches!(
+      validation_errors.as_slice(),
+      [JwtValidationError::Signature { .. }]
+    ));
+  }
+
+  // The subject is not a trusted issuer.
+  let validation_errors = validator
+    .validate::<_, Object>(&credential, &setup.subject_doc, &options, FailFast::FirstError)
+    .unwrap_err()
+    .validation_errors;
+  assert!(matches!(
+    validation_errors.as_slice(),
+    [JwtValidationError::DocumentMismatch { .. }]
+  ));
+
+  // The proof has expired.
+  let expired: Proof = setup
+    .issuer_doc
+    .create_credential_data_integrity_proof(
+      &credential,
+      &setup.issuer_storage,
+      &setup.issuer_method_fragment,
+      &DataIntegrityProofOptions::default().expires(Timestamp::parse("2020-01-01T00:00:00Z").unwrap()),
+    )
+    .await
+    .unwrap();
+  let mut expired_credential: Credential = credential.clone();
+  expired_credential.set_proof(Some(expired));
+  assert!(matches!(
+    validator
+      .verify_credential_proof(
+        &expired_credential,
+        std::slice::from_ref(&setup.issuer_doc),
+        &DataIntegrityVerificationOptions::default()
+      )
+      .unwrap_err(),
+    JwtValidationError::DataIntegrityProofError(_)
+  ));
+
+  // Presentations are bound to a challenge and domain.
+  let mut presentation: Presentation<Credential> = PresentationBuilder::new(
+    Url::parse(setup.subject_doc.as_ref().id().as_str()).unwrap(),
+    Object::new(),
+  )
+  .credential(credential.clone())
+  .build()
+  .unwrap();
+  let proof: Proof = setup
+    .subject_doc
+    .create_presentation_data_integrity_proof(
+      &presentation,
+      &setup.subject_storage,
+      &setup.subject_method_fragment,
+      &DataIntegrityProofOptions::default()
+        .challenge("475a7984-1bb5-4c4c-a56f-822bccd46440")
+        .domain("https://verifier.example"),
+    )
+    .await
+    .unwrap();
+  presentation.set_proof(Some(proof));
+
+  let verification_options = DataIntegrityVerificationOptions::default()
+    .challenge("475a7984-1bb5-4c4c-a56f-822bccd46440")
+    .domain("https://verifier.example");
+  let proof: DataIntegrityProof = validator
+    .validate_presentation(&presentation, &setup.subject_doc, &verification_options)
+    .unwrap();
+  assert_eq!(proof.proof_purpose, ProofPurpose::Authentication);
+
+  let validation_errors = validator
+    .validate_presentation(
+      &presentation,
+      &setup.subject_doc,
+      &verification_options.clone().challenge("other"),
+    )
+    .unwrap_err()
+    .presentation_validation_errors;
+  assert!(matches!(
+    validation_errors.as_slice(),
+    [JwtValidationError::DataIntegrityProofError(_)]
+  ));
+
+  // A credential proof must not be accepted as a presentation proof.
+  presentation.set_proof(credential.proof.clone());
+  assert!(validator
+    .validate_presentation(
+      &presentation,
+      &setup.issuer_doc,
+      &DataIntegrityVerificationOptions::default()
+    )
+    .is_err());
+}
+
+#[tokio::test]
+async fn credential_data_integrity() {
+  credential_data_integrity_impl(setup_coredocument(None, None).await).await;
+  credential_data_integrity_impl(setup_iotadocument(None, None).await).await;
+}
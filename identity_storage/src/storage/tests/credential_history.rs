@@ -0,0 +1,110 @@
+// Copyright 2020-2024 IOTA Stiftung, Fondazione Links
+// SPDX-License-Identifier: Apache-2.0
+
+//! Validation of credentials against the issuer document as it was at issuance time, after the issuer
+//! rotated its keys.
+
+use identity_core::common::Duration;
+use identity_core::common::Object;
+use identity_core::common::Timestamp;
+use identity_credential::credential::Jwt;
+use identity_credential::validator::FailFast;
+use identity_credential::validator::JwtCredentialValidationOptions;
+use identity_credential::validator::JwtCredentialValidator;
+use identity_credential::validator::JwtCredentialValidatorUtils;
+use identity_eddsa_verifier::EdDSAJwsVerifier;
+use identity_iota_core::block::address::Address;
+use identity_iota_core::block::address::Ed25519Address;
+use identity_iota_core::block::output::AliasId;
+use identity_iota_core::block::output::AliasOutput;
+use identity_iota_core::IotaClientExt;
+use identity_iota_core::IotaDID;
+use identity_iota_core::IotaDocument;
+use identity_iota_core::IotaIdentityClientExt;
+use identity_iota_core::MockLedger;
+use identity_iota_core::NetworkName;
+use identity_resolver::Resolver;
+use identity_verification::jws::JwsAlgorithm;
+use identity_verification::MethodScope;
+use iota_sdk::client::secret::SecretManager;
+
+use crate::key_id_storage::KeyIdMemstore;
+use crate::key_storage::JwkMemStore;
+use crate::storage::tests::test_utils;
+use crate::storage::tests::test_utils::CredentialSetup;
+use crate::storage::tests::test_utils::MemStorage;
+use crate::storage::JwkDocumentExt;
+use crate::storage::JwsSignatureOptions;
+
+#[tokio::test]
+async fn validate_against_issuer_document_at_issuance() {
+  let network: NetworkName = NetworkName::try_from("tst").unwrap();
+  let address: Address = Address::Ed25519(Ed25519Address::new([1; Ed25519Address::LENGTH]));
+  let ledger: MockLedger = MockLedger::new(&network);
+  let storage: MemStorage = MemStorage::new(JwkMemStore::new(), KeyIdMemstore::new());
+
+  let mut issuer: IotaDocument = IotaDocument::new(&network);
+  let fragment: String = issuer
+    .generate_method(
+      &storage,
+      JwkMemStore::ED25519_KEY_TYPE,
+      JwsAlgorithm::EdDSA,
+      None,
+      MethodScope::assertion_method(),
+    )
+    .await
+    .unwrap();
+  let output: AliasOutput = ledger.new_did_output(address, issuer, None).await.unwrap();
+  let issuer: IotaDocument = ledger
+    .publish_did_output(&SecretManager::Placeholder, output)
+    .await
+    .unwrap();
+  let issuer_did: IotaDID = issuer.id().clone();
+
+  // Issue the credential once the issuer document is on the ledger.
+  let issued_at: Timestamp = ledger.output_milestone(&AliasId::from(&issuer_did)).unwrap().timestamp;
+  let expires_at: Timestamp = issued_at.checked_add(Duration::days(365)).unwrap();
+  let CredentialSetup { credential, .. } =
+    test_utils::generate_credential(&issuer, &[&issuer], Some(issued_at), Some(expires_at));
+  let jwt: Jwt = issuer
+    .create_credential_jwt(&credential, &storage, &fragment, &JwsSignatureOptions::default(), None)
+    .await
+    .unwrap();
+
+  // Rotate the signing key.
+  let mut rotated: IotaDocument = issuer.clone();
+  rotated.remove_method(&issuer_did.to_url().join(format!("#{fragment}")).unwrap());
+  rotated
+    .generate_method(
+      &storage,
+      JwkMemStore::ED25519_KEY_TYPE,
+      JwsAlgorithm::EdDSA,
+      None,
+      MethodScope::assertion_method(),
+    )
+    .await
+    .unwrap();
+  let output: AliasOutput = ledger.update_did_output(rotated).await.unwrap();
+  ledger
+    .publish_did_output(&SecretManager::Placeholder, output)
+    .await
+    .unwrap();
+
+  let mut resolver: Resolver<IotaDocument> = Resolver::new();
+  resolver.attach_iota_handler(ledger);
+
+  let validator = JwtCredentialValidator::with_signature_verifier(EdDSAJwsVerifier::default());
+  let options = JwtCredentialValidationOptions::default();
+  let issuer_did: IotaDID = JwtCredentialValidatorUtils::extract_issuer_from_jwt(&jwt).unwrap();
+
+  let current: IotaDocument = resolver.resolve(&issuer_did).await.unwrap();
+  assert!(validator
+    .validate::<_, Object>(&jwt, &current, &options, FailFast::FirstError)
+    .is_err());
+
+  let historical: IotaDocument = resolver.resolve_at(&issuer_did, issued_at).await.unwrap();
+  assert_eq!(historical, issuer);
+  validator
+    .validate::<_, Object>(&jwt, &historical, &options, FailFast::FirstError)
+    .unwrap();
+}
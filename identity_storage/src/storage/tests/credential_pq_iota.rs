@@ -0,0 +1,194 @@
+// Copyright 2020-2024 IOTA Stiftung, Fondazione Links
+// SPDX-License-Identifier: Apache-2.0
+
+//! Validation of credentials issued by `did:iota` identities with post-quantum and hybrid keys,
+//! where the issuer documents are published to and resolved from a [`MockLedger`].
+
+use identity_core::common::Object;
+use identity_core::common::Timestamp;
+use identity_credential::credential::Jws;
+use identity_credential::credential::Jwt;
+use identity_credential::validator::FailFast;
+use identity_credential::validator::JwtCredentialValidationOptions;
+use identity_credential::validator::JwtCredentialValidator;
+use identity_credential::validator::JwtCredentialValidatorHybrid;
+use identity_document::verifiable::JwsVerificationOptions;
+use identity_eddsa_verifier::EdDSAJwsVerifier;
+use identity_iota_core::block::address::Address;
+use identity_iota_core::block::address::Ed25519Address;
+use identity_iota_core::block::output::AliasOutput;
+use identity_iota_core::IotaClientExt;
+use identity_iota_core::IotaDocument;
+use identity_iota_core::IotaIdentityClientExt;
+use identity_iota_core::MockLedger;
+use identity_iota_core::NetworkName;
+use identity_pqc_verifier::PQCJwsVerifier;
+use identity_verification::jwk::CompositeAlgId;
+use identity_verification::jws::JwsAlgorithm;
+use identity_verification::MethodScope;
+use iota_sdk::client::secret::SecretManager;
+
+use crate::key_id_storage::KeyIdMemstore;
+use crate::key_storage::JwkMemStore;
+use crate::key_storage::KeyType;
+use crate::storage::tests::test_utils;
+use crate::storage::tests::test_utils::CredentialSetup;
+use crate::storage::tests::test_utils::MemStorage;
+use crate::storage::JwkDocumentExtHybrid;
+use crate::storage::JwsDocumentExtPQC;
+use crate::storage::JwsSignatureOptions;
+
+fn network() -> NetworkName {
+  NetworkName::try_from("tst").unwrap()
+}
+
+fn address() -> Address {
+  Address::Ed25519(Ed25519Address::new([1; Ed25519Address::LENGTH]))
+}
+
+/// Publishes `document` in a new Alias Output, returning the document with its DID.
+async fn publish_new(ledger: &MockLedger, document: IotaDocument) -> IotaDocument {
+  let output: AliasOutput = ledger.new_did_output(address(), document, None).await.unwrap();
+  ledger
+    .publish_did_output(&SecretManager::Placeholder, output)
+    .await
+    .unwrap()
+}
+
+/// Publishes `document` in the existing Alias Output of its DID.
+async fn publish_update(ledger: &MockLedger, document: IotaDocument) -> IotaDocument {
+  let output: AliasOutput = ledger.update_did_output(document).await.unwrap();
+  ledger
+    .publish_did_output(&SecretManager::Placeholder, output)
+    .await
+    .unwrap()
+}
+
+fn validation_options() -> JwtCredentialValidationOptions {
+  JwtCredentialValidationOptions::default().earliest_expiry_date(Timestamp::parse("2020-06-01T00:00:00Z").unwrap())
+}
+
+/// Issues a credential with a new post-quantum method of type `key_type` and validates it against the
+/// resolved issuer document.
+async fn pq_credential_roundtrip(key_type: KeyType, alg: JwsAlgorithm) {
+  let ledger: MockLedger = MockLedger::new(&network());
+  let storage: MemStorage = MemStorage::new(JwkMemStore::new(), KeyIdMemstore::new());
+
+  let mut issuer: IotaDocument = IotaDocument::new(&network());
+  let fragment: String = issuer
+    .generate_method_pqc(&storage, key_type, alg, None, MethodScope::assertion_method())
+    .await
+    .unwrap();
+  let issuer: IotaDocument = publish_new(&ledger, issuer).await;
+
+  let CredentialSetup { credential, .. } = test_utils::generate_credential(&issuer, &[&issuer], None, None);
+  let jwt: Jwt = issuer
+    .create_credential_jwt_pqc(&credential, &storage, &fragment, &JwsSignatureOptions::default(), None)
+    .await
+    .unwrap();
+
+  let resolved: IotaDocument = ledger.resolve_did(issuer.id()).await.unwrap();
+  assert_eq!(resolved, issuer);
+
+  let validator = JwtCredentialValidator::with_signature_verifier(PQCJwsVerifier::default());
+  validator
+    .validate::<_, Object>(&jwt, &resolved, &validation_options(), FailFast::FirstError)
+    .unwrap();
+
+  // Removing the method from the published document invalidates the credential.
+  let mut updated: IotaDocument = resolved.clone();
+  updated.remove_method(&issuer.id().to_url().join(format!("#{fragment}")).unwrap());
+  publish_update(&ledger, updated).await;
+  let resolved: IotaDocument = ledger.resolve_did(issuer.id()).await.unwrap();
+  assert!(validator
+    .validate::<_, Object>(&jwt, &resolved, &validation_options(), FailFast::FirstError)
+    .is_err());
+}
+
+#[tokio::test]
+async fn ml_dsa_credential_iota_issuer() {
+  pq_credential_roundtrip(JwkMemStore::ML_DSA_KEY_TYPE, JwsAlgorithm::ML_DSA_65).await;
+}
+
+#[tokio::test]
+async fn slh_dsa_credential_iota_issuer() {
+  pq_credential_roundtrip(JwkMemStore::SLH_DSA_KEY_TYPE, JwsAlgorithm::SLH_DSA_SHA2_128s).await;
+}
+
+#[tokio::test]
+async fn falcon_credential_iota_issuer() {
+  pq_credential_roundtrip(JwkMemStore::FALCON_KEY_TYPE, JwsAlgorithm::FALCON512).await;
+}
+
+#[tokio::test]
+async fn composite_credential_iota_issuer() {
+  let ledger: MockLedger = MockLedger::new(&network());
+  let storage: MemStorage = MemStorage::new(JwkMemStore::new(), KeyIdMemstore::new());
+
+  let mut issuer: IotaDocument = IotaDocument::new(&network());
+  let fragment: String = issuer
+    .generate_method_hybrid(
+      &storage,
+      CompositeAlgId::IdMldsa65Ed25519Sha512,
+      None,
+      MethodScope::assertion_method(),
+    )
+    .await
+    .unwrap();
+  let issuer: IotaDocument = publish_new(&ledger, issuer).await;
+  let resolved: IotaDocument = ledger.resolve_did(issuer.id()).await.unwrap();
+  let composite = resolved
+    .expand_composite_method(fragment.as_str(), Some(MethodScope::assertion_method()))
+    .unwrap();
+  assert_eq!(composite.alg_id(), CompositeAlgId::IdMldsa65Ed25519Sha512);
+
+  // Plain hybrid JWS.
+  let jws: Jws = issuer
+    .create_jws(&storage, &fragment, b"payload", &JwsSignatureOptions::default())
+    .await
+    .unwrap();
+  let decoded = resolved
+    .verify_jws_hybrid(
+      &jws,
+      None,
+      &EdDSAJwsVerifier::default(),
+      &PQCJwsVerifier::default(),
+      &JwsVerificationOptions::default(),
+    )
+    .unwrap();
+  assert_eq!(decoded.claims.as_ref(), b"payload");
+
+  // Hybrid credential.
+  let CredentialSetup { credential, .. } = test_utils::generate_credential(&issuer, &[&issuer], None, None);
+  let jwt: Jwt = issuer
+    .create_credential_jwt_hybrid(&credential, &storage, &fragment, &JwsSignatureOptions::default(), None)
+    .await
+    .unwrap();
+  let validator =
+    JwtCredentialValidatorHybrid::with_signature_verifiers(EdDSAJwsVerifier::default(), PQCJwsVerifier::default());
+  validator
+    .validate::<_, Object>(&jwt, &resolved, &validation_options(), FailFast::FirstError)
+    .unwrap();
+
+  // A hybrid signature does not verify against a document of another issuer.
+  let mut other: IotaDocument = IotaDocument::new(&network());
+  other
+    .generate_method_hybrid(
+      &storage,
+      CompositeAlgId::IdMldsa65Ed25519Sha512,
+      Some(&fragment),
+      MethodScope::assertion_method(),
+    )
+    .await
+    .unwrap();
+  let other: IotaDocument = publish_new(&ledger, other).await;
+  assert!(other
+    .verify_jws_hybrid(
+      &jws,
+      None,
+      &EdDSAJwsVerifier::default(),
+      &PQCJwsVerifier::default(),
+      &JwsVerificationOptions::default().method_id(other.id().to_url().join(format!("#{fragment}")).unwrap()),
+    )
+    .is_err());
+}
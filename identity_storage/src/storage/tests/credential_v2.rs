@@ -0,0 +1,292 @@
+// Copyright 2020-2024 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+use identity_core::common::Duration;
+use identity_core::common::Object;
+use identity_core::common::Timestamp;
+use identity_core::common::Url;
+use identity_credential::credential::CredentialBuilder;
+use identity_credential::credential::CredentialV2;
+use identity_credential::credential::EnvelopedCredential;
+use identity_credential::credential::Jwt;
+use identity_credential::credential::LanguageValue;
+use identity_credential::credential::Subject;
+use identity_credential::credential::VcDataModelVersion;
+use identity_credential::presentation::JwtPresentationOptions;
+use identity_credential::presentation::Presentation;
+use identity_credential::presentation::PresentationBuilder;
+use identity_credential::validator::DecodedJwtCredentialV2;
+use identity_credential::validator::DecodedJwtPresentation;
+use identity_credential::validator::FailFast;
+use identity_credential::validator::JwtCredentialValidationOptions;
+use identity_credential::validator::JwtCredentialValidator;
+use identity_credential::validator::JwtCredentialValidatorUtils;
+use identity_credential::validator::JwtPresentationValidationOptions;
+use identity_credential::validator::JwtPresentationValidator;
+use identity_credential::validator::JwtValidationError;
+use identity_credential::validator::VersionedDecodedJwtCredential;
+use identity_did::DID;
+use identity_document::document::CoreDocument;
+use identity_eddsa_verifier::EdDSAJwsVerifier;
+use identity_verification::jws::Decoder;
+use once_cell::sync::Lazy;
+
+use crate::storage::tests::test_utils::setup_coredocument;
+use crate::storage::tests::test_utils::setup_iotadocument;
+use crate::storage::tests::test_utils::Setup;
+use crate::JwkDocumentExt;
+use crate::JwsSignatureOptions;
+
+static JWT_CREDENTIAL_VALIDATOR_ED25519: Lazy<JwtCredentialValidator<EdDSAJwsVerifier>> =
+  Lazy::new(|| JwtCredentialValidator::with_signature_verifier(EdDSAJwsVerifier::default()));
+
+fn valid_from() -> Timestamp {
+  Timestamp::parse("2020-01-01T00:00:00Z").unwrap()
+}
+
+fn valid_until() -> Timestamp {
+  Timestamp::parse("2024-01-01T00:00:00Z").unwrap()
+}
+
+fn generate_credential_v2<T: AsRef<CoreDocument>, U: AsRef<CoreDocument>>(issuer: &T, subject: &U) -> CredentialV2 {
+  let subject: Subject = Subject::with_id(Url::parse(subject.as_ref().id().as_str()).unwrap());
+  CredentialBuilder::default()
+    .id(Url::parse("https://example.edu/credentials/3732").unwrap())
+    .issuer(Url::parse(issuer.as_ref().id().as_str()).unwrap())
+    .type_("UniversityDegreeCredential")
+    .name(LanguageValue::new("Example University Degree", "en"))
+    .subject(subject)
+    .valid_from(valid_from())
+    .valid_until(valid_until())
+    .build_v2()
+    .unwrap()
+}
+
+fn header_typ(jwt: &Jwt) -> Option<String> {
+  Decoder::new()
+    .decode_compact_serialization(jwt.as_str().as_bytes(), None)
+    .unwrap()
+    .protected_header()
+    .and_then(|header| header.typ())
+    .map(ToOwned::to_owned)
+}
+
+async fn credential_jwt_v2_impl<T>(setup: Setup<T, T>)
+where
+  T: JwkDocumentExt + AsRef<CoreDocument>,
+{
+  let credential: CredentialV2 = generate_credential_v2(&setup.issuer_doc, &setup.subject_doc);
+  let jwt: Jwt = setup
+    .issuer_doc
+    .create_credential_jwt_v2(
+      &credential,
+      &setup.issuer_storage,
+      &setup.issuer_method_fragment,
+      &JwsSignatureOptions::default(),
+      None,
+    )
+    .await
+    .unwrap();
+
+  assert_eq!(header_typ(&jwt).as_deref(), Some("vc+jwt"));
+  assert_eq!(
+    JwtCredentialValidatorUtils::data_model_version(&jwt).unwrap(),
+    VcDataModelVersion::V2_0
+  );
+
+  let options: JwtCredentialValidationOptions = JwtCredentialValidationOptions::default()
+    .latest_issuance_date(valid_from())
+    .earliest_expiry_date(valid_until());
+  let decoded: VersionedDecodedJwtCredential = JWT_CREDENTIAL_VALIDATOR_ED25519
+    .validate_any_version(&jwt, &setup.issuer_doc, &options, FailFast::FirstError)
+    .unwrap();
+  assert_eq!(decoded.version(), VcDataModelVersion::V2_0);
+  let decoded: DecodedJwtCredentialV2 = decoded.into_v2_0().unwrap();
+  assert_eq!(decoded.credential, credential);
+
+  // `validFrom` lies in the future.
+  let options: JwtCredentialValidationOptions = JwtCredentialValidationOptions::default()
+    .latest_issuance_date(valid_from().checked_sub(Duration::seconds(1)).unwrap())
+    .earliest_expiry_date(valid_until());
+  let validation_errors = JWT_CREDENTIAL_VALIDATOR_ED25519
+    .validate_any_version::<_, Object>(&jwt, &setup.issuer_doc, &options, FailFast::AllErrors)
+    .unwrap_err()
+    .validation_errors;
+  assert!(matches!(
+    validation_errors.as_slice(),
+    [JwtValidationError::IssuanceDate]
+  ));
+
+  // `validUntil` lies in the past.
+  let options: JwtCredentialValidationOptions = JwtCredentialValidationOptions::default()
+    .latest_issuance_date(valid_from())
+    .earliest_expiry_date(valid_until().checked_add(Duration::seconds(1)).unwrap());
+  let validation_errors = JWT_CREDENTIAL_VALIDATOR_ED25519
+    .validate_any_version::<_, Object>(&jwt, &setup.issuer_doc, &options, FailFast::AllErrors)
+    .unwrap_err()
+    .validation_errors;
+  assert!(matches!(
+    validation_errors.as_slice(),
+    [JwtValidationError::ExpirationDate]
+  ));
+}
+
+#[tokio::test]
+async fn credential_jwt_v2() {
+  credential_jwt_v2_impl(setup_coredocument(None, None).await).await;
+  credential_jwt_v2_impl(setup_iotadocument(None, None).await).await;
+}
+
+async fn presentation_jwt_v2_impl<T>(setup: Setup<T, T>)
+where
+  T: JwkDocumentExt + AsRef<CoreDocument>,
+{
+  let credential: CredentialV2 = generate_credential_v2(&setup.issuer_doc, &setup.subject_doc);
+  let credential_jwt: Jwt = setup
+    .issuer_doc
+    .create_credential_jwt_v2(
+      &credential,
+      &setup.issuer_storage,
+      &setup.issuer_method_fragment,
+      &JwsSignatureOptions::default(),
+      None,
+    )
+    .await
+    .unwrap();
+
+  let presentation: Presentation<EnvelopedCredential> = PresentationBuilder::new(
+    Url::parse(setup.subject_doc.as_ref().id().as_str()).unwrap(),
+    Object::new(),
+  )
+  .credential(EnvelopedCredential::from_jwt(&credential_jwt).unwrap())
+  .build_v2()
+  .unwrap();
+  assert_eq!(presentation.data_model_version(), Some(VcDataModelVersion::V2_0));
+
+  let expiration_date: Timestamp = Timestamp::now_utc().checked_add(Duration::hours(1)).unwrap();
+  let presentation_jwt: Jwt = setup
+    .subject_doc
+    .create_presentation_jwt(
+      &presentation,
+      &setup.subject_storage,
+      &setup.subject_method_fragment,
+      &JwsSignatureOptions::default(),
+      &JwtPresentationOptions::default().expiration_date(expiration_date),
+    )
+    .await
+    .unwrap();
+  assert_eq!(header_typ(&presentation_jwt).as_deref(), Some("vp+jwt"));
+
+  let decoded: DecodedJwtPresentation<EnvelopedCredential> =
+    JwtPresentationValidator::with_signature_verifier(EdDSAJwsVerifier::default())
+      .validate(
+        &presentation_jwt,
+        &setup.subject_doc,
+        &JwtPresentationValidationOptions::default(),
+      )
+      .unwrap();
+  assert_eq!(decoded.presentation, presentation);
+  assert_eq!(decoded.expiration_date, Some(expiration_date));
+
+  let enveloped_jwt: Jwt = decoded.presentation.verifiable_credential[0].to_jwt().unwrap();
+  let decoded_credential: DecodedJwtCredentialV2 = JWT_CREDENTIAL_VALIDATOR_ED25519
+    .validate_any_version(
+      &enveloped_jwt,
+      &setup.issuer_doc,
+      &JwtCredentialValidationOptions::default()
+        .latest_issuance_date(valid_from())
+        .earliest_expiry_date(valid_until()),
+      FailFast::FirstError,
+    )
+    .unwrap()
+    .into_v2_0()
+    .unwrap();
+  assert_eq!(decoded_credential.credential, credential);
+}
+
+#[tokio::test]
+async fn presentation_jwt_v2() {
+  presentation_jwt_v2_impl(setup_coredocument(None, None).await).await;
+  presentation_jwt_v2_impl(setup_iotadocument(None, None).await).await;
+}
+
+#[cfg(feature = "hybrid-liboqs")]
+#[tokio::test]
+async fn credential_jwt_v2_pq_and_hybrid() {
+  use identity_credential::validator::JwtCredentialValidatorHybrid;
+  use identity_pqc_verifier::PQCJwsVerifier;
+  use identity_verification::jwk::CompositeAlgId;
+  use identity_verification::jws::JwsAlgorithm;
+  use identity_verification::MethodScope;
+
+  use crate::key_storage::JwkMemStore;
+  use crate::storage::JwkDocumentExtHybrid;
+  use crate::storage::JwsDocumentExtPQC;
+
+  let Setup {
+    mut issuer_doc,
+    subject_doc,
+    issuer_storage,
+    ..
+  } = setup_coredocument(None, None).await;
+  let options: JwtCredentialValidationOptions = JwtCredentialValidationOptions::default()
+    .latest_issuance_date(valid_from())
+    .earliest_expiry_date(valid_until());
+  let credential: CredentialV2 = generate_credential_v2(&issuer_doc, &subject_doc);
+
+  let pq_fragment: String = issuer_doc
+    .generate_method_pqc(
+      &issuer_storage,
+      JwkMemStore::ML_DSA_KEY_TYPE,
+      JwsAlgorithm::ML_DSA_44,
+      None,
+      MethodScope::assertion_method(),
+    )
+    .await
+    .unwrap();
+  let jwt: Jwt = issuer_doc
+    .create_credential_jwt_v2_pqc(
+      &credential,
+      &issuer_storage,
+      &pq_fragment,
+      &JwsSignatureOptions::default(),
+      None,
+    )
+    .await
+    .unwrap();
+  assert_eq!(header_typ(&jwt).as_deref(), Some("vc+jwt"));
+  let decoded: DecodedJwtCredentialV2 = JwtCredentialValidator::with_signature_verifier(PQCJwsVerifier::default())
+    .validate_any_version(&jwt, &issuer_doc, &options, FailFast::FirstError)
+    .unwrap()
+    .into_v2_0()
+    .unwrap();
+  assert_eq!(decoded.credential, credential);
+
+  let hybrid_fragment: String = issuer_doc
+    .generate_method_hybrid(
+      &issuer_storage,
+      CompositeAlgId::IdMldsa44Ed25519Sha512,
+      None,
+      MethodScope::assertion_method(),
+    )
+    .await
+    .unwrap();
+  let jwt: Jwt = issuer_doc
+    .create_credential_jwt_v2_hybrid(
+      &credential,
+      &issuer_storage,
+      &hybrid_fragment,
+      &JwsSignatureOptions::default(),
+      None,
+    )
+    .await
+    .unwrap();
+  assert_eq!(header_typ(&jwt).as_deref(), Some("vc+jwt"));
+  let decoded: DecodedJwtCredentialV2 =
+    JwtCredentialValidatorHybrid::with_signature_verifiers(EdDSAJwsVerifier::default(), PQCJwsVerifier::default())
+      .validate_any_version(&jwt, &issuer_doc, &options, FailFast::FirstError)
+      .unwrap()
+      .into_v2_0()
+      .unwrap();
+  assert_eq!(decoded.credential, credential);
+}
@@ -0,0 +1,75 @@
+// Copyright 2020-2024 IOTA Stiftung, Fondazione Links
+// SPDX-License-Identifier: Apache-2.0
+
+use identity_credential::credential::Jws;
+use identity_did::DIDKey;
+use identity_did::DIDKeyType;
+use identity_document::document::CoreDocument;
+use identity_document::verifiable::JwsVerificationOptions;
+use identity_eddsa_verifier::EdDSAJwsVerifier;
+use identity_verification::jose::jws::JwsAlgorithm;
+
+use crate::key_id_storage::KeyIdMemstore;
+use crate::key_storage::JwkMemStore;
+use crate::storage::DidKeyDocumentExt;
+use crate::storage::JwkDocumentExt;
+use crate::storage::JwsSignatureOptions;
+use crate::Storage;
+
+type MemStorage = Storage<JwkMemStore, KeyIdMemstore>;
+
+#[tokio::test]
+async fn new_did_key() {
+  let storage: MemStorage = Storage::new(JwkMemStore::new(), KeyIdMemstore::new());
+  let (document, fragment) = CoreDocument::new_did_key(&storage, JwkMemStore::ED25519_KEY_TYPE, JwsAlgorithm::EdDSA)
+    .await
+    .unwrap();
+
+  let did: DIDKey = DIDKey::try_from(document.id().clone()).unwrap();
+  assert_eq!(did.key_type(), DIDKeyType::Ed25519);
+  assert_eq!(CoreDocument::expand_did_key(did).unwrap(), document);
+
+  let jws: Jws = document
+    .create_jws(&storage, &fragment, b"test", &JwsSignatureOptions::new())
+    .await
+    .unwrap();
+  assert!(document
+    .verify_jws(
+      jws.as_str(),
+      None,
+      &EdDSAJwsVerifier::default(),
+      &JwsVerificationOptions::new()
+    )
+    .is_ok());
+}
+
+#[cfg(feature = "pqc-liboqs")]
+#[tokio::test]
+async fn new_did_key_pqc() {
+  use identity_pqc_verifier::PQCJwsVerifier;
+
+  use crate::storage::JwsDocumentExtPQC;
+
+  let storage: MemStorage = Storage::new(JwkMemStore::new(), KeyIdMemstore::new());
+  let (document, fragment) =
+    CoreDocument::new_did_key_pqc(&storage, JwkMemStore::ML_DSA_KEY_TYPE, JwsAlgorithm::ML_DSA_44)
+      .await
+      .unwrap();
+  assert_eq!(
+    DIDKey::try_from(document.id().clone()).unwrap().key_type(),
+    DIDKeyType::MlDsa44
+  );
+
+  let jws: Jws = document
+    .create_jws_pqc(&storage, &fragment, b"test", &JwsSignatureOptions::new())
+    .await
+    .unwrap();
+  assert!(document
+    .verify_jws(
+      jws.as_str(),
+      None,
+      &PQCJwsVerifier::default(),
+      &JwsVerificationOptions::new()
+    )
+    .is_ok());
+}
@@ -0,0 +1,218 @@
+// Copyright 2020-2024 IOTA Stiftung, Fondazione Links
+// SPDX-License-Identifier: Apache-2.0
+
+use identity_core::common::Object;
+use identity_core::convert::FromJson;
+use identity_credential::credential::Jws;
+use identity_did::DIDPeer;
+use identity_did::PeerNumalgo;
+use identity_did::PeerPurpose;
+use identity_document::document::CoreDocument;
+use identity_document::verifiable::JwsVerificationOptions;
+use identity_eddsa_verifier::EdDSAJwsVerifier;
+use identity_verification::jose::jws::JwsAlgorithm;
+
+use crate::key_id_storage::KeyIdMemstore;
+use crate::key_storage::JwkMemStore;
+use crate::storage::DidPeerDocumentExt;
+use crate::storage::JwkDocumentExt;
+use crate::storage::JwsSignatureOptions;
+use crate::Storage;
+
+type MemStorage = Storage<JwkMemStore, KeyIdMemstore>;
+
+const SIGNING_PURPOSES: [PeerPurpose; 4] = [
+  PeerPurpose::Assertion,
+  PeerPurpose::Verification,
+  PeerPurpose::CapabilityInvocation,
+  PeerPurpose::CapabilityDelegation,
+];
+
+fn service() -> Object {
+  Object::from_json_value(serde_json::json!({
+    "type": "DIDCommMessaging",
+    "serviceEndpoint": "https://example.com/endpoint"
+  }))
+  .unwrap()
+}
+
+async fn assert_signs(storage: &MemStorage, document: &CoreDocument, fragment: &str) {
+  let jws: Jws = document
+    .create_jws(storage, fragment, b"test", &JwsSignatureOptions::new())
+    .await
+    .unwrap();
+  assert!(document
+    .verify_jws(
+      jws.as_str(),
+      None,
+      &EdDSAJwsVerifier::default(),
+      &JwsVerificationOptions::new()
+    )
+    .is_ok());
+}
+
+#[tokio::test]
+async fn new_did_peer() {
+  let storage: MemStorage = Storage::new(JwkMemStore::new(), KeyIdMemstore::new());
+
+  for numalgo in [
+    PeerNumalgo::InceptionKey,
+    PeerNumalgo::MultipleInceptionKeys,
+    PeerNumalgo::ShortFormAndLongForm,
+  ] {
+    let services: Vec<Object> = match numalgo {
+      PeerNumalgo::InceptionKey => Vec::new(),
+      _ => vec![service()],
+    };
+    let (document, fragment) = CoreDocument::new_did_peer(
+      &storage,
+      numalgo,
+      JwkMemStore::ED25519_KEY_TYPE,
+      JwsAlgorithm::EdDSA,
+      &SIGNING_PURPOSES,
+      &services,
+    )
+    .await
+    .unwrap();
+
+    let did: DIDPeer = DIDPeer::try_from(document.id().clone()).unwrap();
+    assert_eq!(did.numalgo().unwrap(), numalgo);
+    assert_eq!(CoreDocument::expand_did_peer(did).unwrap(), document);
+    assert_eq!(document.service().len(), services.len());
+    assert_signs(&storage, &document, &fragment).await;
+  }
+}
+
+#[tokio::test]
+async fn new_did_peer_numalgo2_methods() {
+  let storage: MemStorage = Storage::new(JwkMemStore::new(), KeyIdMemstore::new());
+  let (document, fragment) = CoreDocument::new_did_peer(
+    &storage,
+    PeerNumalgo::MultipleInceptionKeys,
+    JwkMemStore::ED25519_KEY_TYPE,
+    JwsAlgorithm::EdDSA,
+    &[PeerPurpose::Assertion, PeerPurpose::Verification],
+    &[],
+  )
+  .await
+  .unwrap();
+  assert_eq!(fragment, "key-1");
+  assert_eq!(document.methods(None).len(), 2);
+  assert_eq!(document.assertion_method().len(), 1);
+  assert_eq!(document.authentication().len(), 1);
+  assert!(document.capability_invocation().is_empty());
+  assert!(document.capability_delegation().is_empty());
+
+  // The key is encoded once per requested purpose, each associated with the same stored key.
+  for fragment in ["key-1", "key-2"] {
+    assert_signs(&storage, &document, fragment).await;
+  }
+}
+
+#[tokio::test]
+async fn new_did_peer_rejects_invalid_purposes() {
+  let storage: MemStorage = Storage::new(JwkMemStore::new(), KeyIdMemstore::new());
+  for (numalgo, purposes) in [
+    (PeerNumalgo::MultipleInceptionKeys, &[][..]),
+    (PeerNumalgo::MultipleInceptionKeys, &[PeerPurpose::Encryption][..]),
+    (
+      PeerNumalgo::MultipleInceptionKeys,
+      &[PeerPurpose::Verification, PeerPurpose::Verification][..],
+    ),
+    (PeerNumalgo::ShortFormAndLongForm, &[PeerPurpose::Encryption][..]),
+    (PeerNumalgo::InceptionKey, &[PeerPurpose::Verification][..]),
+  ] {
+    assert!(CoreDocument::new_did_peer(
+      &storage,
+      numalgo,
+      JwkMemStore::ED25519_KEY_TYPE,
+      JwsAlgorithm::EdDSA,
+      purposes,
+      &[],
+    )
+    .await
+    .is_err());
+  }
+  assert_eq!(storage.key_storage().count().await, 0);
+}
+
+#[tokio::test]
+async fn new_did_peer_numalgo0_rejects_services() {
+  let storage: MemStorage = Storage::new(JwkMemStore::new(), KeyIdMemstore::new());
+  assert!(CoreDocument::new_did_peer(
+    &storage,
+    PeerNumalgo::InceptionKey,
+    JwkMemStore::ED25519_KEY_TYPE,
+    JwsAlgorithm::EdDSA,
+    &SIGNING_PURPOSES,
+    &[service()],
+  )
+  .await
+  .is_err());
+  assert_eq!(storage.key_storage().count().await, 0);
+}
+
+#[cfg(feature = "pqc-liboqs")]
+#[tokio::test]
+async fn new_did_peer_pqc() {
+  use identity_pqc_verifier::PQCJwsVerifier;
+
+  use crate::storage::JwsDocumentExtPQC;
+
+  let storage: MemStorage = Storage::new(JwkMemStore::new(), KeyIdMemstore::new());
+  let (document, fragment) = CoreDocument::new_did_peer_pqc(
+    &storage,
+    PeerNumalgo::MultipleInceptionKeys,
+    JwkMemStore::ML_DSA_KEY_TYPE,
+    JwsAlgorithm::ML_DSA_44,
+    &[PeerPurpose::Verification],
+    &[service()],
+  )
+  .await
+  .unwrap();
+
+  let jws: Jws = document
+    .create_jws_pqc(&storage, &fragment, b"test", &JwsSignatureOptions::new())
+    .await
+    .unwrap();
+  assert!(document
+    .verify_jws(
+      jws.as_str(),
+      None,
+      &PQCJwsVerifier::default(),
+      &JwsVerificationOptions::new()
+    )
+    .is_ok());
+}
+
+#[cfg(feature = "hybrid-liboqs")]
+#[tokio::test]
+async fn new_did_peer_composite() {
+  use identity_pqc_verifier::PQCJwsVerifier;
+  use identity_verification::jwk::CompositeAlgId;
+
+  use crate::storage::JwkDocumentExtHybrid;
+
+  let storage: MemStorage = Storage::new(JwkMemStore::new(), KeyIdMemstore::new());
+  let (document, fragment) =
+    CoreDocument::new_did_peer_composite(&storage, CompositeAlgId::IdMldsa44Ed25519Sha512, &[service()])
+      .await
+      .unwrap();
+  assert_eq!(
+    DIDPeer::try_from(document.id().clone()).unwrap().numalgo().unwrap(),
+    PeerNumalgo::ShortFormAndLongForm
+  );
+
+  let jws: Jws = JwkDocumentExtHybrid::create_jws(&document, &storage, &fragment, b"test", &JwsSignatureOptions::new())
+    .await
+    .unwrap();
+  assert!(document
+    .verify_jws_hybrid(
+      jws.as_str(),
+      None,
+      &EdDSAJwsVerifier::default(),
+      &PQCJwsVerifier::default(),
+      &JwsVerificationOptions::new()
+    )
+    .is_ok());
+}
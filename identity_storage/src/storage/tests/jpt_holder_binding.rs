@@ -0,0 +1,325 @@
+// Copyright 2020-2024 IOTA Stiftung, Fondazione Links
+// SPDX-License-Identifier: Apache-2.0
+
+use identity_core::common::Object;
+use identity_core::common::Timestamp;
+use identity_core::common::Url;
+use identity_core::convert::FromJson;
+use identity_credential::credential::Credential;
+use identity_credential::credential::CredentialBuilder;
+use identity_credential::credential::Jpt;
+use identity_credential::credential::JptHolderCommitment;
+use identity_credential::credential::JptPredicate;
+use identity_credential::credential::JptPseudonym;
+use identity_credential::credential::JwpCredentialOptions;
+use identity_credential::credential::Subject;
+use identity_credential::presentation::JwpPresentationOptions;
+use identity_credential::presentation::SelectiveDisclosurePresentation;
+use identity_credential::validator::DecodedJptCredential;
+use identity_credential::validator::DecodedJptPresentation;
+use identity_credential::validator::FailFast;
+use identity_credential::validator::JptCredentialValidationOptions;
+use identity_credential::validator::JptCredentialValidator;
+use identity_credential::validator::JptPresentationValidationOptions;
+use identity_credential::validator::JptPresentationValidator;
+use identity_credential::validator::JwtValidationError;
+use identity_did::DID;
+use identity_document::document::CoreDocument;
+use identity_verification::MethodScope;
+use identity_verification::VerificationMethod;
+use jsonprooftoken::jpa::algs::ProofAlgorithm;
+use serde_json::json;
+
+use crate::key_storage::JwkMemStore;
+use crate::storage::tests::test_utils::setup_coredocument;
+use crate::storage::tests::test_utils::MemStorage;
+use crate::JwkStorageDocumentError;
+use crate::JwpDocumentExt;
+
+const AGE: &str = "vc.credentialSubject.age";
+const ISSUER_NONCE: &str = "0f2c3b7e-7a4d-4b8e-9a55-2f5f3c1d6e21";
+const CHALLENGE: &str = "475a7984-1bb5-4c4c-a56f-822bccd46440";
+const VERIFIER: &str = "https://verifier.example.com";
+
+struct Holder {
+  doc: CoreDocument,
+  storage: MemStorage,
+  fragment: String,
+}
+
+impl Holder {
+  fn method(&self) -> &VerificationMethod {
+    self.doc.resolve_method(&self.fragment, None).unwrap()
+  }
+}
+
+/// Issues a credential bound to the secret of a new holder.
+async fn issue(alg: ProofAlgorithm) -> (CoreDocument, Holder, Jpt) {
+  let mut setup = setup_coredocument(None, None).await;
+  let issuer_fragment: String = setup
+    .issuer_doc
+    .generate_method_jwp(
+      &setup.issuer_storage,
+      JwkMemStore::BLS12381G2_KEY_TYPE,
+      alg,
+      None,
+      MethodScope::assertion_method(),
+    )
+    .await
+    .unwrap();
+  let holder_fragment: String = setup
+    .subject_doc
+    .generate_method_jwp(
+      &setup.subject_storage,
+      JwkMemStore::BLS12381G2_KEY_TYPE,
+      alg,
+      None,
+      MethodScope::authentication(),
+    )
+    .await
+    .unwrap();
+  let holder: Holder = Holder {
+    doc: setup.subject_doc,
+    storage: setup.subject_storage,
+    fragment: holder_fragment,
+  };
+
+  let commitment: JptHolderCommitment = holder
+    .doc
+    .create_jpt_holder_commitment(&holder.storage, &holder.fragment, ISSUER_NONCE)
+    .await
+    .unwrap();
+  assert_eq!(commitment.nonce(), ISSUER_NONCE);
+
+  let jpt: Jpt = issue_with(&setup.issuer_doc, &setup.issuer_storage, &issuer_fragment, commitment).await;
+  (setup.issuer_doc, holder, jpt)
+}
+
+async fn issue_with(
+  issuer_doc: &CoreDocument,
+  issuer_storage: &MemStorage,
+  fragment: &str,
+  commitment: JptHolderCommitment,
+) -> Jpt {
+  let subject: Subject = Subject::from_json_value(json!({
+    "id": "did:example:holder",
+    "name": "Alice",
+    "age": 30,
+  }))
+  .unwrap();
+  let credential: Credential = CredentialBuilder::default()
+    .id(Url::parse("https://example.edu/credentials/3732").unwrap())
+    .issuer(Url::parse(issuer_doc.id().as_str()).unwrap())
+    .type_("AgeCredential")
+    .subject(subject)
+    .issuance_date(Timestamp::parse("2020-01-01T00:00:00Z").unwrap())
+    .build()
+    .unwrap();
+
+  issuer_doc
+    .create_credential_jpt(
+      &credential,
+      issuer_storage,
+      fragment,
+      &JwpCredentialOptions::default()
+        .predicate_claim(AGE)
+        .holder_commitment(commitment),
+      None,
+    )
+    .await
+    .unwrap()
+}
+
+fn decode(issuer_doc: &CoreDocument, credential_jpt: &Jpt) -> (DecodedJptCredential, String) {
+  let decoded: DecodedJptCredential = JptCredentialValidator::validate::<_, Object>(
+    credential_jpt,
+    issuer_doc,
+    &JptCredentialValidationOptions::default(),
+    FailFast::FirstError,
+  )
+  .unwrap();
+  let method_id: String = decoded
+    .decoded_jwp
+    .get_issuer_protected_header()
+    .kid()
+    .unwrap()
+    .to_owned();
+  (decoded, method_id)
+}
+
+async fn present(
+  issuer_doc: &CoreDocument,
+  holder: &Holder,
+  credential_jpt: &Jpt,
+  options: &JwpPresentationOptions,
+) -> Result<Jpt, JwkStorageDocumentError> {
+  let (decoded, method_id) = decode(issuer_doc, credential_jpt);
+  let mut presentation: SelectiveDisclosurePresentation = SelectiveDisclosurePresentation::new(&decoded.decoded_jwp);
+  presentation.conceal_in_subject("name").unwrap();
+
+  issuer_doc
+    .create_holder_bound_presentation_jpt(&mut presentation, &method_id, &holder.storage, holder.method(), options)
+    .await
+}
+
+fn validate(
+  issuer_doc: &CoreDocument,
+  presentation_jpt: &Jpt,
+  options: JptPresentationValidationOptions,
+) -> Result<DecodedJptPresentation, JwtValidationError> {
+  JptPresentationValidator::validate::<_, Object>(
+    presentation_jpt,
+    issuer_doc,
+    &options.nonce(CHALLENGE),
+    FailFast::FirstError,
+  )
+  .map_err(|mut err| err.validation_errors.remove(0))
+}
+
+fn adult() -> JptPredicate {
+  JptPredicate::greater_than_or_equal(AGE, 18)
+}
+
+#[tokio::test]
+async fn holder_bound_credentials_are_presented_with_pseudonyms() {
+  for alg in [ProofAlgorithm::BLS12381_SHA256, ProofAlgorithm::BLS12381_SHAKE256] {
+    let (issuer_doc, holder, credential_jpt) = issue(alg).await;
+    let options: JwpPresentationOptions = JwpPresentationOptions::default()
+      .nonce(CHALLENGE)
+      .predicate(adult())
+      .verifier_id(VERIFIER);
+    let presentation_jpt: Jpt = present(&issuer_doc, &holder, &credential_jpt, &options).await.unwrap();
+
+    let decoded: DecodedJptPresentation = validate(
+      &issuer_doc,
+      &presentation_jpt,
+      JptPresentationValidationOptions::default()
+        .predicate(adult())
+        .verifier_id(VERIFIER),
+    )
+    .unwrap();
+    assert!(decoded.holder_bound);
+    assert_eq!(decoded.predicates, vec![adult()]);
+    assert_eq!(decoded.pseudonym.as_ref().unwrap().verifier_id(), VERIFIER);
+  }
+}
+
+#[tokio::test]
+async fn pseudonyms_are_stable_per_verifier() {
+  let (issuer_doc, holder, credential_jpt) = issue(ProofAlgorithm::BLS12381_SHA256).await;
+  let pseudonym = |verifier_id: &'static str| {
+    let (issuer_doc, holder, credential_jpt) = (&issuer_doc, &holder, &credential_jpt);
+    async move {
+      let options: JwpPresentationOptions = JwpPresentationOptions::default()
+        .nonce(CHALLENGE)
+        .verifier_id(verifier_id);
+      let presentation_jpt: Jpt = present(issuer_doc, holder, credential_jpt, &options).await.unwrap();
+      let decoded: DecodedJptPresentation = validate(
+        issuer_doc,
+        &presentation_jpt,
+        JptPresentationValidationOptions::default().verifier_id(verifier_id),
+      )
+      .unwrap();
+      decoded.pseudonym.unwrap()
+    }
+  };
+
+  let first: JptPseudonym = pseudonym(VERIFIER).await;
+  assert_eq!(first, pseudonym(VERIFIER).await);
+  assert_ne!(first.value(), pseudonym("https://other.example.com").await.value());
+}
+
+#[tokio::test]
+async fn holder_bound_credentials_require_the_holder_secret() {
+  let (issuer_doc, holder, credential_jpt) = issue(ProofAlgorithm::BLS12381_SHA256).await;
+  let (decoded, method_id) = decode(&issuer_doc, &credential_jpt);
+
+  // The credential cannot be presented without proving knowledge of the holder secret.
+  let mut presentation: SelectiveDisclosurePresentation = SelectiveDisclosurePresentation::new(&decoded.decoded_jwp);
+  let result = issuer_doc
+    .create_presentation_jpt(
+      &mut presentation,
+      &method_id,
+      &JwpPresentationOptions::default().nonce(CHALLENGE),
+    )
+    .await;
+  assert!(matches!(result, Err(JwkStorageDocumentError::JptHolderBindingError(_))));
+
+  // Another secret held by the holder does not open the commitment.
+  let mut other_holder: Holder = holder;
+  other_holder.fragment = other_holder
+    .doc
+    .generate_method_jwp(
+      &other_holder.storage,
+      JwkMemStore::BLS12381G2_KEY_TYPE,
+      ProofAlgorithm::BLS12381_SHA256,
+      None,
+      MethodScope::authentication(),
+    )
+    .await
+    .unwrap();
+  let presentation_jpt: Jpt = present(
+    &issuer_doc,
+    &other_holder,
+    &credential_jpt,
+    &JwpPresentationOptions::default().nonce(CHALLENGE),
+  )
+  .await
+  .unwrap();
+  assert!(matches!(
+    validate(
+      &issuer_doc,
+      &presentation_jpt,
+      JptPresentationValidationOptions::default()
+    ),
+    Err(JwtValidationError::JwpProofVerificationError(_))
+  ));
+}
+
+#[tokio::test]
+async fn verifiers_can_require_holder_binding_and_pseudonyms() {
+  let (issuer_doc, holder, credential_jpt) = issue(ProofAlgorithm::BLS12381_SHA256).await;
+  let presentation_jpt: Jpt = present(
+    &issuer_doc,
+    &holder,
+    &credential_jpt,
+    &JwpPresentationOptions::default().nonce(CHALLENGE),
+  )
+  .await
+  .unwrap();
+
+  let decoded: DecodedJptPresentation = validate(
+    &issuer_doc,
+    &presentation_jpt,
+    JptPresentationValidationOptions::default().holder_binding(true),
+  )
+  .unwrap();
+  assert!(decoded.pseudonym.is_none());
+  assert!(matches!(
+    validate(
+      &issuer_doc,
+      &presentation_jpt,
+      JptPresentationValidationOptions::default().verifier_id(VERIFIER)
+    ),
+    Err(JwtValidationError::MissingPseudonym(_))
+  ));
+
+  let presentation_jpt: Jpt = present(
+    &issuer_doc,
+    &holder,
+    &credential_jpt,
+    &JwpPresentationOptions::default()
+      .nonce(CHALLENGE)
+      .verifier_id("https://other.example.com"),
+  )
+  .await
+  .unwrap();
+  assert!(matches!(
+    validate(
+      &issuer_doc,
+      &presentation_jpt,
+      JptPresentationValidationOptions::default().verifier_id(VERIFIER)
+    ),
+    Err(JwtValidationError::MissingPseudonym(_))
+  ));
+}
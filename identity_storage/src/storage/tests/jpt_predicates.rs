@@ -0,0 +1,242 @@
+// Copyright 2020-2024 IOTA Stiftung, Fondazione Links
+// SPDX-License-Identifier: Apache-2.0
+
+use identity_core::common::Object;
+use identity_core::common::Timestamp;
+use identity_core::common::Url;
+use identity_core::convert::FromJson;
+use identity_credential::credential::Credential;
+use identity_credential::credential::CredentialBuilder;
+use identity_credential::credential::Jpt;
+use identity_credential::credential::JptPredicate;
+use identity_credential::credential::JwpCredentialOptions;
+use identity_credential::credential::Subject;
+use identity_credential::presentation::JwpPresentationOptions;
+use identity_credential::presentation::SelectiveDisclosurePresentation;
+use identity_credential::validator::DecodedJptCredential;
+use identity_credential::validator::DecodedJptPresentation;
+use identity_credential::validator::FailFast;
+use identity_credential::validator::JptCredentialValidationOptions;
+use identity_credential::validator::JptCredentialValidator;
+use identity_credential::validator::JptPresentationValidationOptions;
+use identity_credential::validator::JptPresentationValidator;
+use identity_credential::validator::JwtValidationError;
+use identity_did::DID;
+use identity_document::document::CoreDocument;
+use identity_verification::jose::jwu;
+use identity_verification::MethodScope;
+use jsonprooftoken::jpa::algs::ProofAlgorithm;
+use serde_json::json;
+use serde_json::Value;
+
+use crate::key_storage::JwkMemStore;
+use crate::storage::tests::test_utils::setup_coredocument;
+use crate::JwkStorageDocumentError;
+use crate::JwpDocumentExt;
+
+const AGE: &str = "vc.credentialSubject.age";
+const BIRTH_DATE: &str = "vc.credentialSubject.birthDate";
+const CHALLENGE: &str = "475a7984-1bb5-4c4c-a56f-822bccd46440";
+
+async fn issue(alg: ProofAlgorithm) -> (CoreDocument, Jpt) {
+  let mut setup = setup_coredocument(None, None).await;
+  let fragment: String = setup
+    .issuer_doc
+    .generate_method_jwp(
+      &setup.issuer_storage,
+      JwkMemStore::BLS12381G2_KEY_TYPE,
+      alg,
+      None,
+      MethodScope::assertion_method(),
+    )
+    .await
+    .unwrap();
+
+  let subject: Subject = Subject::from_json_value(json!({
+    "id": setup.subject_doc.id().as_str(),
+    "name": "Alice",
+    "age": 30,
+    "birthDate": "1994-06-15",
+  }))
+  .unwrap();
+  let credential: Credential = CredentialBuilder::default()
+    .id(Url::parse("https://example.edu/credentials/3732").unwrap())
+    .issuer(Url::parse(setup.issuer_doc.id().as_str()).unwrap())
+    .type_("AgeCredential")
+    .subject(subject)
+    .issuance_date(Timestamp::parse("2020-01-01T00:00:00Z").unwrap())
+    .build()
+    .unwrap();
+
+  let jpt: Jpt = setup
+    .issuer_doc
+    .create_credential_jpt(
+      &credential,
+      &setup.issuer_storage,
+      &fragment,
+      &JwpCredentialOptions::default()
+        .predicate_claim(AGE)
+        .predicate_claim(BIRTH_DATE),
+      None,
+    )
+    .await
+    .unwrap();
+
+  (setup.issuer_doc, jpt)
+}
+
+async fn present(
+  issuer_doc: &CoreDocument,
+  credential_jpt: &Jpt,
+  predicates: &[JptPredicate],
+) -> Result<Jpt, JwkStorageDocumentError> {
+  let decoded: DecodedJptCredential = JptCredentialValidator::validate::<_, Object>(
+    credential_jpt,
+    issuer_doc,
+    &JptCredentialValidationOptions::default(),
+    FailFast::FirstError,
+  )
+  .unwrap();
+  let method_id: String = decoded
+    .decoded_jwp
+    .get_issuer_protected_header()
+    .kid()
+    .unwrap()
+    .to_owned();
+
+  let mut presentation: SelectiveDisclosurePresentation = SelectiveDisclosurePresentation::new(&decoded.decoded_jwp);
+  presentation.conceal_in_subject("name").unwrap();
+  let options: JwpPresentationOptions = predicates.iter().cloned().fold(
+    JwpPresentationOptions::default().nonce(CHALLENGE),
+    |options, predicate| options.predicate(predicate),
+  );
+
+  issuer_doc
+    .create_presentation_jpt(&mut presentation, &method_id, &options)
+    .await
+}
+
+fn validate(
+  issuer_doc: &CoreDocument,
+  presentation_jpt: &Jpt,
+  required: &[JptPredicate],
+) -> Result<DecodedJptPresentation, JwtValidationError> {
+  let options: JptPresentationValidationOptions = required.iter().cloned().fold(
+    JptPresentationValidationOptions::default().nonce(CHALLENGE),
+    |options, predicate| options.predicate(predicate),
+  );
+  JptPresentationValidator::validate::<_, Object>(presentation_jpt, issuer_doc, &options, FailFast::FirstError)
+    .map_err(|mut err| err.validation_errors.remove(0))
+}
+
+fn adult() -> JptPredicate {
+  JptPredicate::greater_than_or_equal(AGE, 18)
+}
+
+fn born_before_2000() -> JptPredicate {
+  JptPredicate::less_than(BIRTH_DATE, Timestamp::parse("2000-01-01T00:00:00Z").unwrap())
+}
+
+#[tokio::test]
+async fn predicates_are_proven_without_disclosing_claims() {
+  for alg in [ProofAlgorithm::BLS12381_SHA256, ProofAlgorithm::BLS12381_SHAKE256] {
+    let (issuer_doc, credential_jpt) = issue(alg).await;
+    let presentation_jpt: Jpt = present(&issuer_doc, &credential_jpt, &[adult(), born_before_2000()])
+      .await
+      .unwrap();
+
+    let decoded: DecodedJptPresentation =
+      validate(&issuer_doc, &presentation_jpt, &[adult(), born_before_2000()]).unwrap();
+    assert_eq!(decoded.predicates, vec![adult(), born_before_2000()]);
+    let subject: &Subject = decoded.credential.credential_subject.first().unwrap();
+    // Concealed claims are decoded as `null`.
+    for claim in ["age", "birthDate", "name"] {
+      assert!(subject.properties.get(claim).map_or(true, Value::is_null));
+    }
+  }
+}
+
+#[tokio::test]
+async fn credentials_with_predicate_claims_can_be_presented_without_predicates() {
+  let (issuer_doc, credential_jpt) = issue(ProofAlgorithm::BLS12381_SHA256).await;
+  let presentation_jpt: Jpt = present(&issuer_doc, &credential_jpt, &[]).await.unwrap();
+
+  let decoded: DecodedJptPresentation = validate(&issuer_doc, &presentation_jpt, &[]).unwrap();
+  assert!(decoded.predicates.is_empty());
+  let subject: &Subject = decoded.credential.credential_subject.first().unwrap();
+  assert_eq!(subject.properties.get("age"), Some(&json!(30)));
+}
+
+#[tokio::test]
+async fn unsatisfied_predicates_cannot_be_proven() {
+  let (issuer_doc, credential_jpt) = issue(ProofAlgorithm::BLS12381_SHA256).await;
+  let result = present(&issuer_doc, &credential_jpt, &[JptPredicate::greater_than(AGE, 30)]).await;
+  assert!(matches!(result, Err(JwkStorageDocumentError::JptPredicateError(_))));
+
+  // Claims that were not declared as predicate claims by the issuer are not supported.
+  let result = present(
+    &issuer_doc,
+    &credential_jpt,
+    &[JptPredicate::greater_than("vc.credentialSubject.name", 0)],
+  )
+  .await;
+  assert!(matches!(result, Err(JwkStorageDocumentError::JptPredicateError(_))));
+}
+
+#[tokio::test]
+async fn required_predicates_must_be_proven() {
+  let (issuer_doc, credential_jpt) = issue(ProofAlgorithm::BLS12381_SHA256).await;
+  let presentation_jpt: Jpt = present(&issuer_doc, &credential_jpt, &[adult()]).await.unwrap();
+
+  assert!(matches!(
+    validate(&issuer_doc, &presentation_jpt, &[adult(), born_before_2000()]),
+    Err(JwtValidationError::UnprovenPredicate(_))
+  ));
+  assert!(matches!(
+    validate(
+      &issuer_doc,
+      &presentation_jpt,
+      &[JptPredicate::greater_than_or_equal(AGE, 21)]
+    ),
+    Err(JwtValidationError::UnprovenPredicate(_))
+  ));
+}
+
+#[tokio::test]
+async fn unbound_presentations_do_not_satisfy_holder_binding() {
+  let (issuer_doc, credential_jpt) = issue(ProofAlgorithm::BLS12381_SHA256).await;
+  let presentation_jpt: Jpt = present(&issuer_doc, &credential_jpt, &[adult()]).await.unwrap();
+
+  let decoded: DecodedJptPresentation = validate(&issuer_doc, &presentation_jpt, &[]).unwrap();
+  assert!(!decoded.holder_bound);
+  let result = JptPresentationValidator::validate::<_, Object>(
+    &presentation_jpt,
+    &issuer_doc,
+    &JptPresentationValidationOptions::default()
+      .nonce(CHALLENGE)
+      .holder_binding(true),
+    FailFast::FirstError,
+  );
+  assert!(matches!(
+    result.unwrap_err().validation_errors[0],
+    JwtValidationError::MissingHolderBinding
+  ));
+}
+
+#[tokio::test]
+async fn tampered_predicate_proofs_are_rejected() {
+  let (issuer_doc, credential_jpt) = issue(ProofAlgorithm::BLS12381_SHA256).await;
+  let presentation_jpt: Jpt = present(&issuer_doc, &credential_jpt, &[adult()]).await.unwrap();
+
+  let (rest, proof) = presentation_jpt.as_str().rsplit_once('.').unwrap();
+  let mut proof: Vec<u8> = jwu::decode_b64(proof).unwrap();
+  // Flip a bit of the last scalar of the range proof.
+  let last: usize = proof.len() - 1;
+  proof[last] ^= 1;
+  let tampered: Jpt = Jpt::new(format!("{rest}.{}", jwu::encode_b64(&proof)));
+
+  assert!(matches!(
+    validate(&issuer_doc, &tampered, &[adult()]),
+    Err(JwtValidationError::JwpProofVerificationError(_))
+  ));
+}
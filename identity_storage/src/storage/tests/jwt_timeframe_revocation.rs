@@ -0,0 +1,194 @@
+// Copyright 2020-2024 IOTA Stiftung, Fondazione Links
+// SPDX-License-Identifier: Apache-2.0
+
+use identity_core::common::Duration;
+use identity_core::common::Timestamp;
+use identity_core::common::Url;
+use identity_credential::credential::Credential;
+use identity_credential::credential::Jwt;
+use identity_credential::revocation::RevocationBitmap;
+use identity_credential::revocation::RevocationDocumentExt;
+use identity_credential::revocation::RevocationTimeframeStatus;
+use identity_credential::revocation::TimeframeAttestationClaims;
+use identity_credential::validator::JwtCredentialValidatorUtils;
+use identity_credential::validator::JwtValidationError;
+use identity_credential::validator::StatusCheck;
+use identity_did::DIDUrl;
+use identity_did::DID;
+use identity_document::document::CoreDocument;
+use identity_eddsa_verifier::EdDSAJwsVerifier;
+
+use crate::storage::tests::test_utils;
+use crate::storage::tests::test_utils::CredentialSetup;
+use crate::storage::tests::test_utils::Setup;
+use crate::JwsSignatureOptions;
+use crate::JwtTimeframeRevocationExtension;
+
+const INDEX: u32 = 5;
+
+fn start() -> Timestamp {
+  Timestamp::parse("2024-03-19T13:57:50Z").unwrap()
+}
+
+fn after(timestamp: Timestamp, duration: Duration) -> Option<Timestamp> {
+  timestamp.checked_add(duration)
+}
+
+/// Returns a credential whose `RevocationTimeframe2024` status is valid for a minute from [`start`].
+fn credential_with_timeframe(setup: &Setup<CoreDocument, CoreDocument>) -> (Credential, DIDUrl) {
+  let CredentialSetup { mut credential, .. } =
+    test_utils::generate_credential(&setup.issuer_doc, &[&setup.subject_doc], None, None);
+  let service_url: DIDUrl = setup.issuer_doc.id().to_url().join("#revocation-service").unwrap();
+  let status: RevocationTimeframeStatus = RevocationTimeframeStatus::new(
+    Some(start()),
+    Duration::minutes(1),
+    Url::parse(service_url.to_string()).unwrap(),
+    INDEX,
+  )
+  .unwrap();
+  credential.credential_status = Some(status.into());
+  (credential, service_url)
+}
+
+async fn attest(setup: &Setup<CoreDocument, CoreDocument>, credential_id: &Url, start: Timestamp) -> Jwt {
+  setup
+    .issuer_doc
+    .create_timeframe_attestation(
+      &setup.issuer_storage,
+      &setup.issuer_method_fragment,
+      credential_id,
+      Some(start),
+      Duration::minutes(1),
+      &JwsSignatureOptions::default(),
+    )
+    .await
+    .unwrap()
+}
+
+#[tokio::test]
+async fn credential_timeframes_are_checked() {
+  let setup = test_utils::setup_coredocument(None, None).await;
+  let (credential, _) = credential_with_timeframe(&setup);
+
+  let check = |at: Option<Timestamp>, status_check: StatusCheck| {
+    JwtCredentialValidatorUtils::check_timeframes_with_validity_timeframe_2024(&credential, at, status_check)
+  };
+  assert!(check(after(start(), Duration::seconds(30)), StatusCheck::Strict).is_ok());
+  assert!(matches!(
+    check(after(start(), Duration::minutes(2)), StatusCheck::Strict),
+    Err(JwtValidationError::OutsideTimeframe)
+  ));
+  assert!(check(after(start(), Duration::minutes(2)), StatusCheck::SkipAll).is_ok());
+}
+
+#[tokio::test]
+async fn timeframe_attestations_refresh_the_validity_timeframe() {
+  let setup = test_utils::setup_coredocument(None, None).await;
+  let (credential, _) = credential_with_timeframe(&setup);
+  let credential_id: Url = credential.id.clone().unwrap();
+
+  let refreshed: Timestamp = after(start(), Duration::hours(1)).unwrap();
+  let attestation: Jwt = attest(&setup, &credential_id, refreshed).await;
+  let check = |attestation: &Jwt, at: Option<Timestamp>| {
+    JwtCredentialValidatorUtils::check_timeframe_attestation(
+      &credential,
+      attestation,
+      &setup.issuer_doc,
+      &EdDSAJwsVerifier::default(),
+      at,
+    )
+  };
+
+  let claims: TimeframeAttestationClaims = check(&attestation, after(refreshed, Duration::seconds(30))).unwrap();
+  assert_eq!(claims.credential_id(), &credential_id);
+  assert_eq!(claims.start_validity_timeframe(), refreshed);
+
+  // The attestation supersedes the timeframe set in the credential.
+  assert!(matches!(
+    check(&attestation, after(start(), Duration::seconds(30))),
+    Err(JwtValidationError::OutsideTimeframe)
+  ));
+
+  // Attestations are bound to the credential.
+  let other: Jwt = attest(
+    &setup,
+    &Url::parse("https://example.edu/credentials/other").unwrap(),
+    refreshed,
+  )
+  .await;
+  assert!(matches!(
+    check(&other, after(refreshed, Duration::seconds(30))),
+    Err(JwtValidationError::InvalidStatus(_))
+  ));
+}
+
+#[tokio::test]
+async fn timeframe_attestations_must_be_signed_by_the_issuer() {
+  let setup = test_utils::setup_coredocument(None, None).await;
+  let (credential, _) = credential_with_timeframe(&setup);
+
+  let attestation: Jwt = setup
+    .subject_doc
+    .create_timeframe_attestation(
+      &setup.subject_storage,
+      &setup.subject_method_fragment,
+      credential.id.as_ref().unwrap(),
+      Some(start()),
+      Duration::minutes(1),
+      &JwsSignatureOptions::default(),
+    )
+    .await
+    .unwrap();
+
+  // The attestation is not signed by a method of the issuer.
+  assert!(matches!(
+    JwtCredentialValidatorUtils::check_timeframe_attestation(
+      &credential,
+      &attestation,
+      &setup.issuer_doc,
+      &EdDSAJwsVerifier::default(),
+      after(start(), Duration::seconds(30)),
+    ),
+    Err(JwtValidationError::MethodDataLookupError { .. })
+  ));
+
+  // The attestation is not made by the issuer of the credential.
+  assert!(matches!(
+    JwtCredentialValidatorUtils::check_timeframe_attestation(
+      &credential,
+      &attestation,
+      &setup.subject_doc,
+      &EdDSAJwsVerifier::default(),
+      after(start(), Duration::seconds(30)),
+    ),
+    Err(JwtValidationError::DocumentMismatch { .. })
+  ));
+}
+
+#[tokio::test]
+async fn timeframe_credentials_can_be_revoked() {
+  let mut setup = test_utils::setup_coredocument(None, None).await;
+  let (credential, service_url) = credential_with_timeframe(&setup);
+
+  let check = |issuer_doc: &CoreDocument| {
+    JwtCredentialValidatorUtils::check_revocation_with_validity_timeframe_2024(
+      &credential,
+      issuer_doc,
+      StatusCheck::Strict,
+    )
+  };
+  assert!(matches!(
+    check(&setup.issuer_doc),
+    Err(JwtValidationError::ServiceLookupError { .. })
+  ));
+
+  let bitmap: RevocationBitmap = RevocationBitmap::new();
+  setup
+    .issuer_doc
+    .insert_service(bitmap.to_service(service_url.clone()).unwrap())
+    .unwrap();
+  assert!(check(&setup.issuer_doc).is_ok());
+
+  setup.issuer_doc.revoke_credentials(&service_url, &[INDEX]).unwrap();
+  assert!(matches!(check(&setup.issuer_doc), Err(JwtValidationError::Revoked)));
+}
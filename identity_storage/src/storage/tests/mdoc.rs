@@ -0,0 +1,171 @@
+// Copyright 2020-2024 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+use identity_core::common::Duration;
+use identity_core::common::Timestamp;
+use identity_credential::ciborium::Value;
+use identity_credential::credential::Cose;
+use identity_credential::mdoc::IssuerSigned;
+use identity_credential::mdoc::MdocBuilder;
+use identity_credential::mdoc::MdocDocument;
+use identity_credential::mdoc::UnsignedMdoc;
+use identity_credential::validator::DecodedMdoc;
+use identity_credential::validator::JwtValidationError;
+use identity_credential::validator::MdocValidationError;
+use identity_credential::validator::MdocValidationOptions;
+use identity_credential::validator::MdocValidator;
+use identity_document::document::CoreDocument;
+use identity_eddsa_verifier::EdDSAJwsVerifier;
+use identity_verification::jose::cose::CoseKey;
+use identity_verification::jwk::Jwk;
+
+use crate::storage::tests::test_utils::setup_coredocument;
+use crate::storage::tests::test_utils::setup_iotadocument;
+use crate::storage::tests::test_utils::Setup;
+use crate::CoseDocumentExt;
+use crate::CoseSignatureOptions;
+use crate::JwkDocumentExt;
+
+const DOC_TYPE: &str = "org.iso.18013.5.1.mDL";
+const NAME_SPACE: &str = "org.iso.18013.5.1";
+
+fn method_jwk(document: &CoreDocument, fragment: &str) -> Jwk {
+  document
+    .resolve_method(fragment, None)
+    .unwrap()
+    .data()
+    .public_key_jwk()
+    .unwrap()
+    .clone()
+}
+
+async fn mdoc_impl<T>(setup: Setup<T, T>)
+where
+  T: JwkDocumentExt + CoseDocumentExt + AsRef<CoreDocument>,
+{
+  let device_key: CoseKey =
+    CoseKey::try_from(&method_jwk(setup.subject_doc.as_ref(), &setup.subject_method_fragment)).unwrap();
+  let valid_until: Timestamp = Timestamp::now_utc().checked_add(Duration::days(365)).unwrap();
+  let unsigned: UnsignedMdoc = MdocBuilder::new(DOC_TYPE)
+    .element(NAME_SPACE, "family_name", Value::Text("Doe".to_owned()))
+    .element(NAME_SPACE, "given_name", Value::Text("Jane".to_owned()))
+    .element(NAME_SPACE, "age_over_18", Value::Bool(true))
+    .device_key(device_key)
+    .valid_until(valid_until)
+    .build()
+    .unwrap();
+
+  // The issuer signs the mobile security object.
+  let issuer_auth: Cose = setup
+    .issuer_doc
+    .create_cose(
+      &setup.issuer_storage,
+      &setup.issuer_method_fragment,
+      unsigned.signing_payload(),
+      &CoseSignatureOptions::default(),
+    )
+    .await
+    .unwrap();
+  let issuer_signed: IssuerSigned = unsigned.into_issuer_signed(issuer_auth).unwrap();
+  let issuer_signed: IssuerSigned = IssuerSigned::from_slice(&issuer_signed.to_vec().unwrap()).unwrap();
+
+  // The holder only discloses a single data element and signs the session transcript.
+  let session_transcript: Value = Value::Text("session-transcript".to_owned());
+  let disclosed: IssuerSigned = issuer_signed.disclose([(NAME_SPACE, "age_over_18")]).unwrap();
+  let document: MdocDocument = MdocDocument::new(DOC_TYPE, disclosed);
+  let device_signature: Cose = setup
+    .subject_doc
+    .create_cose(
+      &setup.subject_storage,
+      &setup.subject_method_fragment,
+      &document.device_authentication_bytes(&session_transcript).unwrap(),
+      &CoseSignatureOptions::new().detached_payload(true),
+    )
+    .await
+    .unwrap();
+  let document: MdocDocument = document.with_device_signature(device_signature);
+  let document: MdocDocument = MdocDocument::from_slice(&document.to_vec().unwrap()).unwrap();
+
+  let validator = MdocValidator::with_signature_verifier(EdDSAJwsVerifier::default());
+  let options = MdocValidationOptions::default();
+  let decoded: DecodedMdoc = validator
+    .validate_document(&document, &setup.issuer_doc, &session_transcript, &options)
+    .unwrap();
+  assert_eq!(decoded.mso.doc_type, DOC_TYPE);
+  assert_eq!(decoded.name_spaces.len(), 1);
+  assert_eq!(decoded.name_spaces[NAME_SPACE].len(), 1);
+  assert_eq!(decoded.name_spaces[NAME_SPACE]["age_over_18"], Value::Bool(true));
+
+  // The device signature is bound to the session transcript.
+  assert!(matches!(
+    validator
+      .validate_document(
+        &document,
+        &setup.issuer_doc,
+        &Value::Text("other-session".to_owned()),
+        &options
+      )
+      .unwrap_err(),
+    MdocValidationError::DeviceSignature(_)
+  ));
+
+  // The subject is not a trusted issuer.
+  assert!(matches!(
+    validator
+      .validate_document(&document, &setup.subject_doc, &session_transcript, &options)
+      .unwrap_err(),
+    MdocValidationError::IssuerSignature(JwtValidationError::DocumentMismatch { .. })
+  ));
+
+  // The mdoc expires earlier than required.
+  let expiry_options =
+    MdocValidationOptions::default().earliest_expiry_date(valid_until.checked_add(Duration::days(1)).unwrap());
+  assert!(matches!(
+    validator
+      .validate_issuer_signed(&issuer_signed, &setup.issuer_doc, &expiry_options)
+      .unwrap_err(),
+    MdocValidationError::ExpirationDate
+  ));
+
+  // Presenting without a device signature is rejected.
+  assert!(matches!(
+    validator
+      .validate_document(
+        &MdocDocument::new(DOC_TYPE, issuer_signed.clone()),
+        &setup.issuer_doc,
+        &session_transcript,
+        &options
+      )
+      .unwrap_err(),
+    MdocValidationError::MissingDeviceSignature
+  ));
+
+  // Swapping a disclosed value invalidates its digest.
+  let tampered: Vec<u8> = issuer_signed
+    .to_vec()
+    .unwrap()
+    .windows(4)
+    .position(|window| window == b"Jane")
+    .map(|index| {
+      let mut bytes: Vec<u8> = issuer_signed.to_vec().unwrap();
+      bytes[index..index + 4].copy_from_slice(b"John");
+      bytes
+    })
+    .unwrap();
+  assert!(matches!(
+    validator
+      .validate_issuer_signed(
+        &IssuerSigned::from_slice(&tampered).unwrap(),
+        &setup.issuer_doc,
+        &options
+      )
+      .unwrap_err(),
+    MdocValidationError::DigestMismatch { .. }
+  ));
+}
+
+#[tokio::test]
+async fn mdoc() {
+  mdoc_impl(setup_coredocument(None, None).await).await;
+  mdoc_impl(setup_iotadocument(None, None).await).await;
+}
@@ -2,8 +2,27 @@
 // SPDX-License-Identifier: Apache-2.0
 
 mod api;
+#[cfg(feature = "bbs-2023")]
+mod credential_bbs_2023;
+#[cfg(feature = "cose")]
+mod credential_cose;
+#[cfg(feature = "data-integrity")]
+mod credential_data_integrity;
+mod credential_history;
 mod credential_jws;
+#[cfg(feature = "hybrid-liboqs")]
+mod credential_pq_iota;
+mod credential_v2;
 mod credential_validation;
+mod did_key;
+mod did_peer;
+#[cfg(feature = "jpt-predicates-experimental")]
+mod jpt_holder_binding;
+#[cfg(feature = "jpt-predicates-experimental")]
+mod jpt_predicates;
+mod jwt_timeframe_revocation;
 mod kb_jwt;
+#[cfg(feature = "mdoc")]
+mod mdoc;
 mod presentation_validation;
 pub(crate) mod test_utils;
@@ -27,7 +27,7 @@ use crate::key_storage::JwkMemStore;
 use crate::JwkDocumentExt;
 use crate::Storage;
 
-type MemStorage = Storage<JwkMemStore, KeyIdMemstore>;
+pub(crate) type MemStorage = Storage<JwkMemStore, KeyIdMemstore>;
 
 const ISSUER_DOCUMENT_JSON: &str = r#"
 {
@@ -136,7 +136,12 @@ impl TimeframeRevocationExtension for CoreDocument {
       .map_err(|_| Error::ProofUpdateError("'endValidityTimeframe' value NOT found".to_owned()))?
       .map_err(|_| Error::ProofUpdateError("'endValidityTimeframe' value NOT a JSON String".to_owned()))?;
 
-    let proof: [u8; BBSplusSignature::BYTES] = proof
+    // The signature may be followed by the description of the claims that predicates can be proven over.
+    if proof.len() < BBSplusSignature::BYTES {
+      return Err(Error::ProofUpdateError("Invalid bytes length of JWP proof".to_owned()));
+    }
+    let (signature, integer_claims) = proof.split_at(BBSplusSignature::BYTES);
+    let signature: [u8; BBSplusSignature::BYTES] = signature
       .try_into()
       .map_err(|_| Error::ProofUpdateError("Invalid bytes length of JWP proof".to_owned()))?;
 
@@ -150,10 +155,11 @@ impl TimeframeRevocationExtension for CoreDocument {
       number_of_signed_messages: payloads.0.len(),
     };
 
-    let new_proof =
-      <K as JwkStorageBbsPlusExt>::update_signature(storage.key_storage(), &key_id, jwk, &proof, proof_update_ctx)
+    let mut new_proof =
+      <K as JwkStorageBbsPlusExt>::update_signature(storage.key_storage(), &key_id, jwk, &signature, proof_update_ctx)
         .await
         .map_err(Error::KeyStorageError)?;
+    new_proof.extend_from_slice(integer_claims);
 
     credential_jwp.set_proof(&new_proof);
     credential_jwp.set_payloads(payloads);
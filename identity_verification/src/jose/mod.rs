@@ -22,6 +22,13 @@ pub mod jwu {
   pub use identity_jose::jwu::*;
 }
 
+#[cfg(feature = "cose")]
+pub mod cose {
+  //! Reexport of [identity_jose::cose].
+
+  pub use identity_jose::cose::*;
+}
+
 pub mod error {
   //! Reexport of [identity_jose::error].
 
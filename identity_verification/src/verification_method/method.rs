@@ -7,6 +7,7 @@ use std::borrow::Cow;
 
 use identity_did::DIDCompositeJwk;
 use identity_did::DIDJwk;
+use identity_did::DIDKey;
 use identity_jose::jwk::CompositeJwk;
 use identity_jose::jwk::Jwk;
 use serde::de;
@@ -300,10 +301,21 @@ impl TryFrom<DIDJwk> for VerificationMethod {
   }
 }
 
+impl TryFrom<DIDKey> for VerificationMethod {
+  type Error = Error;
+  fn try_from(did: DIDKey) -> Result<Self, Self::Error> {
+    let jwk = did.jwk();
+    let fragment = did.method_id().to_owned();
+    Self::new_from_jwk(did, jwk, Some(&fragment))
+  }
+}
+
 impl TryFrom<DIDCompositeJwk> for VerificationMethod {
   type Error = Error;
   fn try_from(did: DIDCompositeJwk) -> Result<Self, Self::Error> {
-    let jwk = did.composite_jwk();
+    let jwk = did
+      .try_composite_jwk()
+      .map_err(|_| Error::InvalidMethod("a short form did:compositejwk does not encode its key"))?;
     Self::new_from_compositejwk(did, jwk, Some("0"))
   }
 }
@@ -337,7 +349,7 @@ impl From<_VerificationMethod> for VerificationMethod {
       MethodData::PublicKeyBase58(_) => "publicKeyBase58",
       MethodData::PublicKeyJwk(_) => "publicKeyJwk",
       MethodData::PublicKeyMultibase(_) => "publicKeyMultibase",
-      MethodData::CompositeJwk(_) => "compositePublicKey",
+      MethodData::CompositeJwk(_) => "compositeJwk",
       MethodData::Custom(CustomMethodData { name, .. }) => name.as_str(),
     };
     properties.remove(key);
@@ -0,0 +1,254 @@
+// Copyright 2020-2024 IOTA Stiftung, Fondazione Links
+// SPDX-License-Identifier: Apache-2.0
+
+use std::fmt::Display;
+use std::fmt::Formatter;
+use std::str::FromStr;
+
+use identity_core::common::Url;
+use identity_did::CoreDID;
+use identity_did::Error as DIDError;
+use identity_did::DID;
+
+use crate::Error;
+use crate::Result;
+
+/// The placeholder for the SCID in the first log entry before the SCID is computed.
+pub const SCID_PLACEHOLDER: &str = "{SCID}";
+
+/// The percent-encoded [`SCID_PLACEHOLDER`], which is allowed in the method-specific id of a DID.
+const ENCODED_SCID_PLACEHOLDER: &str = "%7BSCID%7D";
+
+/// The name of the log file.
+const LOG_FILE: &str = "did.jsonl";
+
+/// A DID conforming to the [`did:webvh`](https://identity.foundation/didwebvh/v1.0/) method.
+///
+/// The method-specific id consists of the SCID of the DID followed by the location of its log, which is encoded as in
+/// `did:web`: `did:webvh:<scid>:<host>[%3A<port>][:<path>]*`.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, serde::Deserialize, serde::Serialize)]
+#[repr(transparent)]
+#[serde(into = "CoreDID", try_from = "CoreDID")]
+pub struct WebVhDID(CoreDID);
+
+impl WebVhDID {
+  /// [`WebVhDID`]'s method.
+  pub const METHOD: &'static str = "webvh";
+
+  /// Creates the DID with the given `scid`, whose log is published at `location`, e.g. `example.com%3A8080:dids:alice`.
+  ///
+  /// # Errors
+  ///
+  /// Fails if `scid` or `location` is malformed.
+  pub fn new(scid: &str, location: &str) -> Result<Self> {
+    if scid.is_empty() || !scid.bytes().all(|byte| byte.is_ascii_alphanumeric()) {
+      return Err(Error::InvalidDID(format!("invalid SCID \"{scid}\"")));
+    }
+    Self::parse(format!("did:{}:{scid}:{location}", Self::METHOD))
+  }
+
+  /// Returns the DID whose log is published at `location` with the SCID replaced by the [`SCID_PLACEHOLDER`].
+  ///
+  /// The document of the first log entry must be created with this DID, which is replaced with the actual DID once the
+  /// SCID is computed.
+  ///
+  /// # Errors
+  ///
+  /// Fails if `location` is malformed.
+  pub fn placeholder(location: &str) -> Result<CoreDID> {
+    let did: CoreDID = CoreDID::parse(format!("did:{}:{ENCODED_SCID_PLACEHOLDER}:{location}", Self::METHOD))
+      .map_err(|err| Error::InvalidDID(err.to_string()))?;
+    log_url(location)?;
+    Ok(did)
+  }
+
+  /// Parses a [`WebVhDID`] from a string.
+  ///
+  /// # Errors
+  ///
+  /// Fails if `input` is not a valid `did:webvh` DID.
+  pub fn parse(input: impl AsRef<str>) -> Result<Self> {
+    CoreDID::parse(input.as_ref())
+      .map_err(|err| Error::InvalidDID(err.to_string()))
+      .and_then(Self::try_from_core)
+  }
+
+  /// Converts a [`CoreDID`] to a [`WebVhDID`].
+  ///
+  /// # Errors
+  ///
+  /// Fails if `did` is not a valid `did:webvh` DID.
+  pub fn try_from_core(did: CoreDID) -> Result<Self> {
+    if did.method() != Self::METHOD {
+      return Err(Error::InvalidDID(format!("expected method \"{}\"", Self::METHOD)));
+    }
+    let (scid, location) = did
+      .method_id()
+      .split_once(':')
+      .ok_or_else(|| Error::InvalidDID("missing location".to_owned()))?;
+    if scid.is_empty() || !scid.bytes().all(|byte| byte.is_ascii_alphanumeric()) {
+      return Err(Error::InvalidDID(format!("invalid SCID \"{scid}\"")));
+    }
+    log_url(location)?;
+
+    Ok(Self(did))
+  }
+
+  /// Returns the self-certifying identifier of the DID.
+  pub fn scid(&self) -> &str {
+    self.split().0
+  }
+
+  /// Returns the location of the log, i.e. the part of the method-specific id following the SCID.
+  pub fn location(&self) -> &str {
+    self.split().1
+  }
+
+  /// Returns the HTTPS URL of the `did.jsonl` log of the DID.
+  ///
+  /// Without a path the log is located at `/.well-known/did.jsonl`, otherwise the path segments are followed by
+  /// `/did.jsonl`.
+  pub fn log_url(&self) -> Url {
+    log_url(self.location()).expect("the location of a did:webvh DID is valid")
+  }
+
+  /// Returns the `did:web` DID located at the same location, whose document may be published in parallel.
+  pub fn to_web_did(&self) -> CoreDID {
+    CoreDID::parse(format!("did:web:{}", self.location())).expect("the location of a did:webvh DID is valid")
+  }
+
+  fn split(&self) -> (&str, &str) {
+    self
+      .method_id()
+      .split_once(':')
+      .expect("the method id of a did:webvh DID contains a location")
+  }
+}
+
+/// Returns `true` if `did` was created by [`WebVhDID::placeholder`].
+pub(crate) fn is_placeholder(did: &CoreDID) -> bool {
+  did.method() == WebVhDID::METHOD
+    && did
+      .method_id()
+      .strip_prefix(ENCODED_SCID_PLACEHOLDER)
+      .map_or(false, |location| location.starts_with(':'))
+}
+
+/// Replaces the placeholder DID in the JSON text of a log entry with the [`SCID_PLACEHOLDER`].
+pub(crate) fn insert_scid_placeholder(json: &str) -> String {
+  json.replace(ENCODED_SCID_PLACEHOLDER, SCID_PLACEHOLDER)
+}
+
+/// Computes the HTTPS URL of the log published at `location`.
+fn log_url(location: &str) -> Result<Url> {
+  let invalid = || Error::InvalidDID(format!("invalid location \"{location}\""));
+
+  let mut segments = location.split(':');
+  let authority: String = segments
+    .next()
+    .filter(|authority| !authority.is_empty())
+    .ok_or_else(invalid)?
+    .replace("%3A", ":")
+    .replace("%3a", ":");
+  let path: Vec<&str> = segments.collect();
+  if path
+    .iter()
+    .any(|segment| segment.is_empty() || *segment == "." || *segment == "..")
+  {
+    return Err(invalid());
+  }
+
+  let mut url: Url = Url::parse(format!("https://{authority}/")).map_err(|_| invalid())?;
+  if url.domain().is_none() || url.path() != "/" || !url.username().is_empty() {
+    return Err(invalid());
+  }
+  if path.is_empty() {
+    url.set_path(&format!("/.well-known/{LOG_FILE}"));
+  } else {
+    url.set_path(&format!("/{}/{LOG_FILE}", path.join("/")));
+  }
+  Ok(url)
+}
+
+impl AsRef<CoreDID> for WebVhDID {
+  fn as_ref(&self) -> &CoreDID {
+    &self.0
+  }
+}
+
+impl From<WebVhDID> for CoreDID {
+  fn from(value: WebVhDID) -> Self {
+    value.0
+  }
+}
+
+impl TryFrom<CoreDID> for WebVhDID {
+  type Error = DIDError;
+  fn try_from(value: CoreDID) -> std::result::Result<Self, Self::Error> {
+    Self::try_from_core(value).map_err(|_| DIDError::InvalidMethodId)
+  }
+}
+
+impl<'a> TryFrom<&'a str> for WebVhDID {
+  type Error = DIDError;
+  fn try_from(value: &'a str) -> std::result::Result<Self, Self::Error> {
+    value.parse()
+  }
+}
+
+impl FromStr for WebVhDID {
+  type Err = DIDError;
+  fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+    s.parse::<CoreDID>().and_then(TryFrom::try_from)
+  }
+}
+
+impl From<WebVhDID> for String {
+  fn from(value: WebVhDID) -> Self {
+    value.to_string()
+  }
+}
+
+impl Display for WebVhDID {
+  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    write!(f, "{}", self.0)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_log_url() {
+    let did = WebVhDID::parse("did:webvh:QmScid:example.com").unwrap();
+    assert_eq!(did.scid(), "QmScid");
+    assert_eq!(did.location(), "example.com");
+    assert_eq!(did.log_url().as_str(), "https://example.com/.well-known/did.jsonl");
+    assert_eq!(did.to_web_did().as_str(), "did:web:example.com");
+
+    let did = WebVhDID::parse("did:webvh:QmScid:example.com%3A8080:dids:alice").unwrap();
+    assert_eq!(did.log_url().as_str(), "https://example.com:8080/dids/alice/did.jsonl");
+
+    for invalid in [
+      "did:webvh:example.com",
+      "did:webvh::example.com",
+      "did:webvh:Qm-Scid:example.com",
+      "did:webvh:QmScid:example.com::alice",
+      "did:webvh:QmScid:127.0.0.1",
+      "did:web:QmScid:example.com",
+    ] {
+      assert!(WebVhDID::parse(invalid).is_err(), "{invalid}");
+    }
+  }
+
+  #[test]
+  fn test_placeholder() {
+    let placeholder = WebVhDID::placeholder("example.com:alice").unwrap();
+    assert_eq!(
+      insert_scid_placeholder(placeholder.as_str()),
+      "did:webvh:{SCID}:example.com:alice"
+    );
+    assert!(WebVhDID::try_from_core(placeholder).is_err());
+  }
+}
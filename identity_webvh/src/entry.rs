@@ -0,0 +1,237 @@
+// Copyright 2020-2024 IOTA Stiftung, Fondazione Links
+// SPDX-License-Identifier: Apache-2.0
+
+use crypto::hashes::sha::Sha256;
+use crypto::hashes::Digest;
+use identity_core::common::Timestamp;
+use identity_core::convert::BaseEncoding;
+use identity_core::convert::ToJson;
+use identity_document::document::CoreDocument;
+use serde_json::Value;
+
+use crate::did::insert_scid_placeholder;
+use crate::update_key::multihash_sha256;
+use crate::Error;
+use crate::LogParameters;
+use crate::Result;
+use crate::UpdateKey;
+use crate::SCID_PLACEHOLDER;
+
+/// The type of the proofs of log entries.
+const PROOF_TYPE: &str = "DataIntegrityProof";
+/// The purpose of the proofs of log entries.
+const PROOF_PURPOSE: &str = "assertionMethod";
+
+/// An entry of a `did:webvh` log, recording a version of the DID document.
+#[derive(Clone, Debug, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct LogEntry {
+  pub(crate) version_id: String,
+  pub(crate) version_time: Timestamp,
+  pub(crate) parameters: LogParameters,
+  pub(crate) state: CoreDocument,
+  #[serde(default, skip_serializing_if = "Vec::is_empty")]
+  pub(crate) proof: Vec<LogProof>,
+}
+
+impl LogEntry {
+  /// Returns the version id of the entry, consisting of the version number and the entry hash, e.g. `1-Qm...`.
+  pub fn version_id(&self) -> &str {
+    &self.version_id
+  }
+
+  /// Returns the version number of the entry, starting at `1`.
+  ///
+  /// Returns `None` if the version id is malformed.
+  pub fn version_number(&self) -> Option<usize> {
+    self.split_version_id().map(|(number, _)| number)
+  }
+
+  /// Returns the hash part of the version id.
+  ///
+  /// Returns `None` if the version id is malformed.
+  pub fn entry_hash(&self) -> Option<&str> {
+    self.split_version_id().map(|(_, hash)| hash)
+  }
+
+  /// Returns the time at which the version was created.
+  pub fn version_time(&self) -> Timestamp {
+    self.version_time
+  }
+
+  /// Returns the parameters of the entry.
+  pub fn parameters(&self) -> &LogParameters {
+    &self.parameters
+  }
+
+  /// Returns the DID document of this version.
+  pub fn state(&self) -> &CoreDocument {
+    &self.state
+  }
+
+  /// Returns the proofs of the entry.
+  pub fn proof(&self) -> &[LogProof] {
+    &self.proof
+  }
+
+  fn split_version_id(&self) -> Option<(usize, &str)> {
+    let (number, hash) = self.version_id.split_once('-')?;
+    if number.starts_with('0') || hash.is_empty() {
+      return None;
+    }
+    number.parse().ok().map(|number| (number, hash))
+  }
+
+  /// Returns the JSON representation of the entry without its proofs.
+  pub(crate) fn to_unsigned_value(&self) -> Result<Value> {
+    let mut value: Value = self.to_json_value().map_err(Error::Encoding)?;
+    if let Value::Object(object) = &mut value {
+      object.remove("proof");
+    }
+    Ok(value)
+  }
+
+  /// Computes the hash of the entry chained to the version with the id `previous_version_id`, i.e. the SCID for the
+  /// first entry.
+  pub(crate) fn compute_entry_hash(&self, previous_version_id: &str) -> Result<String> {
+    let mut value: Value = self.to_unsigned_value()?;
+    value["versionId"] = Value::String(previous_version_id.to_owned());
+    jcs_multihash(&value)
+  }
+
+  /// Computes the SCID of the first entry of a log.
+  ///
+  /// The SCID is the hash of the entry with every occurrence of the SCID replaced by the [`SCID_PLACEHOLDER`], which is
+  /// also used as the version id.
+  pub(crate) fn compute_scid(&self, scid: &str) -> Result<String> {
+    let mut value: Value = self.to_unsigned_value()?;
+    value["versionId"] = Value::String(scid.to_owned());
+    let json: String = value
+      .to_json()
+      .map_err(Error::Encoding)?
+      .replace(scid, SCID_PLACEHOLDER);
+    compute_scid(&json)
+  }
+
+  /// Returns the data signed by a proof with the given configuration: the SHA-256 digest of the canonical proof
+  /// configuration followed by the SHA-256 digest of the canonical entry without proofs.
+  pub(crate) fn hash_data(&self, proof: &LogProof) -> Result<Vec<u8>> {
+    let mut config: Value = proof.to_json_value().map_err(Error::Encoding)?;
+    if let Value::Object(object) = &mut config {
+      object.remove("proofValue");
+    }
+    let config_hash = Sha256::digest(config.to_jcs().map_err(Error::Encoding)?);
+    let entry_hash = Sha256::digest(self.to_unsigned_value()?.to_jcs().map_err(Error::Encoding)?);
+    Ok([config_hash.as_slice(), entry_hash.as_slice()].concat())
+  }
+}
+
+/// A Data Integrity proof of a [`LogEntry`].
+#[derive(Clone, Debug, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LogProof {
+  #[serde(rename = "type")]
+  type_: String,
+  cryptosuite: String,
+  verification_method: String,
+  created: Timestamp,
+  proof_purpose: String,
+  #[serde(default, skip_serializing_if = "String::is_empty")]
+  proof_value: String,
+}
+
+impl LogProof {
+  /// Creates the configuration of a proof by `update_key`, without a proof value.
+  pub(crate) fn new(update_key: &UpdateKey, created: Timestamp) -> Self {
+    Self {
+      type_: PROOF_TYPE.to_owned(),
+      cryptosuite: update_key.key_type().cryptosuite().to_owned(),
+      verification_method: update_key.verification_method(),
+      created,
+      proof_purpose: PROOF_PURPOSE.to_owned(),
+      proof_value: String::new(),
+    }
+  }
+
+  /// Returns the name of the cryptosuite.
+  pub fn cryptosuite(&self) -> &str {
+    &self.cryptosuite
+  }
+
+  /// Returns the `did:key` verification method of the update key that created the proof.
+  pub fn verification_method(&self) -> &str {
+    &self.verification_method
+  }
+
+  /// Returns the creation time of the proof.
+  pub fn created(&self) -> Timestamp {
+    self.created
+  }
+
+  /// Returns the update key identified by the verification method of the proof.
+  ///
+  /// # Errors
+  ///
+  /// Fails if the verification method is not of the form `did:key:<multikey>#<multikey>`, if the key is not a
+  /// supported update key or if the cryptosuite does not match the key.
+  pub fn update_key(&self) -> Result<UpdateKey> {
+    let invalid = |message: String| Error::InvalidUpdateKey(message);
+    if self.type_ != PROOF_TYPE || self.proof_purpose != PROOF_PURPOSE {
+      return Err(invalid(format!("expected a {PROOF_TYPE} with purpose {PROOF_PURPOSE}")));
+    }
+    let (key, fragment) = self
+      .verification_method
+      .strip_prefix("did:key:")
+      .and_then(|method| method.split_once('#'))
+      .ok_or_else(|| {
+        invalid(format!(
+          "unsupported verification method \"{}\"",
+          self.verification_method
+        ))
+      })?;
+    if key != fragment {
+      return Err(invalid(format!(
+        "unsupported verification method \"{}\"",
+        self.verification_method
+      )));
+    }
+    let update_key: UpdateKey = UpdateKey::parse(key)?;
+    if update_key.key_type().cryptosuite() != self.cryptosuite {
+      return Err(invalid(format!(
+        "cryptosuite {} does not match the key type {}",
+        self.cryptosuite,
+        update_key.key_type()
+      )));
+    }
+    Ok(update_key)
+  }
+
+  /// Returns the decoded signature.
+  pub(crate) fn signature(&self) -> Result<Vec<u8>> {
+    if !self.proof_value.starts_with('z') {
+      return Err(Error::InvalidUpdateKey(
+        "the proof value is not base58btc encoded".to_owned(),
+      ));
+    }
+    BaseEncoding::decode_multibase(&self.proof_value).map_err(Error::Encoding)
+  }
+
+  pub(crate) fn set_signature(&mut self, signature: &[u8]) {
+    self.proof_value = BaseEncoding::encode_multibase(signature, None);
+  }
+}
+
+/// Computes the SCID from the JSON text of the first log entry, in which every occurrence of the SCID was replaced by
+/// the [`SCID_PLACEHOLDER`].
+pub(crate) fn compute_scid(json: &str) -> Result<String> {
+  let value: Value = serde_json::from_str(&insert_scid_placeholder(json))
+    .map_err(|err| Error::Encoding(identity_core::Error::DecodeJSON(err)))?;
+  jcs_multihash(&value)
+}
+
+fn jcs_multihash(value: &Value) -> Result<String> {
+  value
+    .to_jcs()
+    .map(|jcs| multihash_sha256(&jcs))
+    .map_err(Error::Encoding)
+}
@@ -0,0 +1,54 @@
+// Copyright 2020-2024 IOTA Stiftung, Fondazione Links
+// SPDX-License-Identifier: Apache-2.0
+
+/// Alias for a `Result` with the error type [`Error`].
+pub type Result<T, E = Error> = core::result::Result<T, E>;
+
+/// Errors that can occur when creating, updating or verifying `did:webvh` logs.
+#[derive(Debug, thiserror::Error, strum::IntoStaticStr)]
+#[non_exhaustive]
+pub enum Error {
+  /// Caused by a DID that does not conform to the `did:webvh` method.
+  #[error("invalid did:webvh DID: {0}")]
+  InvalidDID(String),
+  /// Caused by an update key that is not a supported multikey.
+  #[error("invalid update key: {0}")]
+  InvalidUpdateKey(String),
+  /// Caused by a log entry that cannot be parsed or violates the `did:webvh` specification.
+  #[error("invalid log entry {index}: {message}")]
+  InvalidEntry {
+    /// The position of the entry in the log, starting at `1`.
+    index: usize,
+    /// A description of the violation.
+    message: String,
+  },
+  /// Caused by a log whose SCID does not match its first entry or the DID.
+  #[error("the SCID of the log does not match: {0}")]
+  ScidMismatch(String),
+  /// Caused by an entry whose `versionId` does not match the hash of the entry.
+  #[error("the hash of log entry {0} does not match its versionId")]
+  EntryHashMismatch(usize),
+  /// Caused by an entry that is not signed by an authorized update key.
+  #[error("log entry {index} is not authorized: {message}")]
+  Unauthorized {
+    /// The position of the entry in the log, starting at `1`.
+    index: usize,
+    /// A description of the violation.
+    message: String,
+  },
+  /// Caused by new update keys that were not committed to by the `nextKeyHashes` of the previous entry.
+  #[error("log entry {0} violates pre-rotation: an update key was not committed to by nextKeyHashes")]
+  PreRotationViolation(usize),
+  /// Caused by an invalid proof signature.
+  #[error("the proof of log entry {0} could not be verified")]
+  ProofVerification(usize, #[source] identity_verification::jws::SignatureVerificationError),
+  /// Caused by an attempt to update a deactivated DID.
+  #[error("the DID has been deactivated")]
+  Deactivated,
+  /// Caused by a failure to sign a log entry.
+  #[error("signing the log entry failed")]
+  Signing(#[source] Box<dyn std::error::Error + Send + Sync + 'static>),
+  /// Caused by a failure to serialize or canonicalize a log entry.
+  #[error("encoding failed")]
+  Encoding(#[source] identity_core::Error),
+}
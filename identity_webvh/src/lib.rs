@@ -0,0 +1,33 @@
+// Copyright 2020-2024 IOTA Stiftung, Fondazione Links
+// SPDX-License-Identifier: Apache-2.0
+
+#![forbid(unsafe_code)]
+#![doc = include_str!("./../README.md")]
+#![warn(
+  rust_2018_idioms,
+  unreachable_pub,
+  missing_docs,
+  rustdoc::missing_crate_level_docs,
+  rustdoc::broken_intra_doc_links,
+  rustdoc::private_intra_doc_links,
+  rustdoc::private_doc_tests,
+  clippy::missing_safety_doc
+)]
+
+mod did;
+mod entry;
+mod error;
+mod log;
+mod parameters;
+mod signer;
+#[cfg(test)]
+mod tests;
+mod update_key;
+
+pub use self::did::*;
+pub use self::entry::*;
+pub use self::error::*;
+pub use self::log::*;
+pub use self::parameters::*;
+pub use self::signer::*;
+pub use self::update_key::*;
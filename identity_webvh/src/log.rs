@@ -0,0 +1,478 @@
+// Copyright 2020-2024 IOTA Stiftung, Fondazione Links
+// SPDX-License-Identifier: Apache-2.0
+
+use identity_core::common::Timestamp;
+use identity_core::convert::FromJson;
+use identity_core::convert::ToJson;
+use identity_document::document::CoreDocument;
+use identity_verification::jws::JwsVerifier;
+
+use crate::did::insert_scid_placeholder;
+use crate::did::is_placeholder;
+use crate::entry::compute_scid;
+use crate::Error;
+use crate::LogEntry;
+use crate::LogParameters;
+use crate::LogProof;
+use crate::LogSigner;
+use crate::Result;
+use crate::UpdateKey;
+use crate::WebVhDID;
+use crate::SCID_PLACEHOLDER;
+use crate::WEBVH_METHOD_VERSION;
+
+/// A verified version of a `did:webvh` DID document.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LogVersion {
+  /// The id of the version, e.g. `1-Qm...`.
+  pub version_id: String,
+  /// The time at which the version was created.
+  pub version_time: Timestamp,
+  /// The DID document.
+  pub document: CoreDocument,
+  /// Whether the DID was deactivated by this version.
+  pub deactivated: bool,
+}
+
+/// The `did.jsonl` log of a `did:webvh` DID, recording every version of its DID document.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct DidLog {
+  entries: Vec<LogEntry>,
+}
+
+impl DidLog {
+  /// Parses a log from its JSON Lines representation.
+  ///
+  /// The log is not verified, see [`DidLog::verify`].
+  ///
+  /// # Errors
+  ///
+  /// Fails if a line is not a valid log entry.
+  pub fn from_jsonl(jsonl: &str) -> Result<Self> {
+    let entries: Vec<LogEntry> = jsonl
+      .lines()
+      .filter(|line| !line.trim().is_empty())
+      .enumerate()
+      .map(|(index, line)| {
+        LogEntry::from_json(line).map_err(|err| Error::InvalidEntry {
+          index: index + 1,
+          message: err.to_string(),
+        })
+      })
+      .collect::<Result<_>>()?;
+
+    Ok(Self { entries })
+  }
+
+  /// Returns the JSON Lines representation of the log, which is published as `did.jsonl`.
+  pub fn to_jsonl(&self) -> Result<String> {
+    self.entries.iter().try_fold(String::new(), |mut jsonl, entry| {
+      jsonl.push_str(&entry.to_json().map_err(Error::Encoding)?);
+      jsonl.push('\n');
+      Ok(jsonl)
+    })
+  }
+
+  /// Returns the entries of the log, from oldest to newest.
+  pub fn entries(&self) -> &[LogEntry] {
+    &self.entries
+  }
+
+  /// Returns the latest entry of the log.
+  pub fn latest(&self) -> Option<&LogEntry> {
+    self.entries.last()
+  }
+
+  /// Creates the log of a new DID.
+  ///
+  /// The id of `document` must be a DID created by [`WebVhDID::placeholder`], every occurrence of which is replaced by
+  /// the actual DID once the SCID is computed. `parameters` must define the update keys, which must include the key of
+  /// `signer`. The `version_time` is typically [`Timestamp::now_utc`].
+  ///
+  /// # Errors
+  ///
+  /// Fails if the document or parameters are invalid or if signing fails.
+  pub async fn create<S>(
+    document: CoreDocument,
+    parameters: LogParameters,
+    version_time: Timestamp,
+    signer: &S,
+  ) -> Result<Self>
+  where
+    S: LogSigner + ?Sized,
+  {
+    if !is_placeholder(document.id()) {
+      return Err(Error::InvalidDID(format!(
+        "expected a placeholder DID created by WebVhDID::placeholder, found {}",
+        document.id()
+      )));
+    }
+
+    let preliminary: LogEntry = LogEntry {
+      version_id: SCID_PLACEHOLDER.to_owned(),
+      version_time,
+      parameters: LogParameters {
+        method: Some(WEBVH_METHOD_VERSION.to_owned()),
+        scid: Some(SCID_PLACEHOLDER.to_owned()),
+        ..parameters
+      },
+      state: document,
+      proof: Vec::new(),
+    };
+    let json: String = insert_scid_placeholder(&preliminary.to_json().map_err(Error::Encoding)?);
+    let scid: String = compute_scid(&json)?;
+    let mut entry: LogEntry =
+      LogEntry::from_json(&json.replace(SCID_PLACEHOLDER, &scid)).map_err(|err| Error::InvalidEntry {
+        index: 1,
+        message: err.to_string(),
+      })?;
+    entry.version_id = format!("1-{}", entry.compute_entry_hash(&scid)?);
+
+    sign_entry(&mut entry, signer).await?;
+    validate_entry(None, &entry)?;
+
+    Ok(Self { entries: vec![entry] })
+  }
+
+  /// Appends a new version of the DID document.
+  ///
+  /// `parameters` only contains the parameters that change. While pre-rotation is active it must define new update
+  /// keys, including the key of `signer`, committed to by the previous entry. Otherwise `signer` must hold one of the
+  /// current update keys.
+  ///
+  /// # Errors
+  ///
+  /// Fails if the log is invalid, if the DID is deactivated, if `signer` is not authorized or if signing fails.
+  pub async fn update<S>(
+    &mut self,
+    document: CoreDocument,
+    parameters: LogParameters,
+    version_time: Timestamp,
+    signer: &S,
+  ) -> Result<&LogEntry>
+  where
+    S: LogSigner + ?Sized,
+  {
+    let state: LogState = self.state()?;
+    if state.deactivated {
+      return Err(Error::Deactivated);
+    }
+
+    let mut entry: LogEntry = LogEntry {
+      version_id: String::new(),
+      version_time,
+      parameters,
+      state: document,
+      proof: Vec::new(),
+    };
+    entry.version_id = format!(
+      "{}-{}",
+      state.version_number + 1,
+      entry.compute_entry_hash(&state.version_id)?
+    );
+
+    sign_entry(&mut entry, signer).await?;
+    validate_entry(Some(&state), &entry)?;
+    self.entries.push(entry);
+
+    Ok(self.entries.last().expect("the entry was just added"))
+  }
+
+  /// Deactivates the DID, retaining the latest DID document.
+  ///
+  /// The update keys are removed so that the log cannot be updated anymore. While pre-rotation is active, the key of
+  /// `signer` must be committed to by the previous entry and pre-rotation is deactivated instead.
+  ///
+  /// # Errors
+  ///
+  /// Fails under the same conditions as [`DidLog::update`].
+  pub async fn deactivate<S>(&mut self, version_time: Timestamp, signer: &S) -> Result<&LogEntry>
+  where
+    S: LogSigner + ?Sized,
+  {
+    let state: LogState = self.state()?;
+    let document: CoreDocument = self.entries[state.version_number - 1].state.clone();
+    let parameters: LogParameters = if state.pre_rotation() {
+      LogParameters::new()
+        .with_update_keys([signer.update_key().clone()])
+        .with_next_key_hashes([])
+    } else {
+      LogParameters::new().with_update_keys([])
+    };
+
+    self
+      .update(
+        document,
+        LogParameters {
+          deactivated: Some(true),
+          ..parameters
+        },
+        version_time,
+        signer,
+      )
+      .await
+  }
+
+  /// Verifies the log of `did` and returns every version of its DID document, from oldest to newest.
+  ///
+  /// Ed25519 signatures are verified with `traditional_verifier` and ML-DSA signatures with `pq_verifier`, composite
+  /// signatures with both.
+  ///
+  /// # Errors
+  ///
+  /// Fails if any entry is invalid, is not chained to the previous one or is not signed by an authorized update key,
+  /// or if the log does not belong to `did`.
+  pub fn verify<TRV, PQV>(
+    &self,
+    did: &WebVhDID,
+    traditional_verifier: &TRV,
+    pq_verifier: &PQV,
+  ) -> Result<Vec<LogVersion>>
+  where
+    TRV: JwsVerifier,
+    PQV: JwsVerifier,
+  {
+    let mut state: Option<LogState> = None;
+    let mut versions: Vec<LogVersion> = Vec::with_capacity(self.entries.len());
+    for entry in &self.entries {
+      let next: LogState = validate_entry(state.as_ref(), entry)?;
+      verify_proofs(next.version_number, entry, traditional_verifier, pq_verifier)?;
+      versions.push(LogVersion {
+        version_id: entry.version_id.clone(),
+        version_time: entry.version_time,
+        document: entry.state.clone(),
+        deactivated: next.deactivated,
+      });
+      state = Some(next);
+    }
+
+    let state: LogState = state.ok_or_else(empty_log)?;
+    if state.scid != did.scid() {
+      return Err(Error::ScidMismatch(format!(
+        "the log has the SCID {}, but the DID {did} was requested",
+        state.scid
+      )));
+    }
+    if &state.did != did {
+      return Err(Error::InvalidDID(format!(
+        "the log belongs to {}, but {did} was requested",
+        state.did
+      )));
+    }
+
+    Ok(versions)
+  }
+
+  /// Validates every entry except for the proof signatures and returns the resulting state.
+  fn state(&self) -> Result<LogState> {
+    let mut state: Option<LogState> = None;
+    for entry in &self.entries {
+      state = Some(validate_entry(state.as_ref(), entry)?);
+    }
+    state.ok_or_else(empty_log)
+  }
+}
+
+/// The state of a log after an entry.
+struct LogState {
+  scid: String,
+  did: WebVhDID,
+  version_id: String,
+  version_number: usize,
+  version_time: Timestamp,
+  update_keys: Vec<UpdateKey>,
+  next_key_hashes: Vec<String>,
+  portable: bool,
+  deactivated: bool,
+}
+
+impl LogState {
+  fn pre_rotation(&self) -> bool {
+    !self.next_key_hashes.is_empty()
+  }
+}
+
+fn empty_log() -> Error {
+  Error::InvalidEntry {
+    index: 1,
+    message: "the log is empty".to_owned(),
+  }
+}
+
+async fn sign_entry<S>(entry: &mut LogEntry, signer: &S) -> Result<()>
+where
+  S: LogSigner + ?Sized,
+{
+  let mut proof: LogProof = LogProof::new(signer.update_key(), entry.version_time);
+  let data: Vec<u8> = entry.hash_data(&proof)?;
+  proof.set_signature(&signer.sign(&data).await?);
+  entry.proof.push(proof);
+  Ok(())
+}
+
+/// Validates `entry` following the state `previous`, except for the proof signatures.
+fn validate_entry(previous: Option<&LogState>, entry: &LogEntry) -> Result<LogState> {
+  let index: usize = previous.map_or(1, |state| state.version_number + 1);
+  let invalid = |message: &str| Error::InvalidEntry {
+    index,
+    message: message.to_owned(),
+  };
+  let parameters: &LogParameters = &entry.parameters;
+
+  if previous.map_or(false, |state| state.deactivated) {
+    return Err(Error::Deactivated);
+  }
+  if parameters.has_witness() {
+    return Err(invalid("witnesses are not supported"));
+  }
+  if parameters
+    .method
+    .as_deref()
+    .map_or(false, |method| method != WEBVH_METHOD_VERSION)
+  {
+    return Err(invalid("unsupported method version"));
+  }
+
+  let (Some(version_number), Some(entry_hash)) = (entry.version_number(), entry.entry_hash()) else {
+    return Err(invalid("malformed versionId"));
+  };
+  if version_number != index {
+    return Err(invalid("unexpected version number"));
+  }
+  if entry.version_time > Timestamp::now_utc() {
+    return Err(invalid("versionTime is in the future"));
+  }
+  if previous.map_or(false, |state| entry.version_time <= state.version_time) {
+    return Err(invalid("versionTime is not later than the previous versionTime"));
+  }
+
+  let scid: &str = match previous {
+    None => {
+      if parameters.method.is_none() {
+        return Err(invalid("the first entry must define the method"));
+      }
+      let scid: &str = parameters
+        .scid
+        .as_deref()
+        .ok_or_else(|| invalid("the first entry must define the scid"))?;
+      let computed: String = entry.compute_scid(scid)?;
+      if computed != scid {
+        return Err(Error::ScidMismatch(format!(
+          "the first entry has the SCID {computed}, but declares {scid}"
+        )));
+      }
+      scid
+    }
+    Some(state) => {
+      if parameters.scid.as_deref().map_or(false, |scid| scid != state.scid) {
+        return Err(invalid("the scid cannot change"));
+      }
+      &state.scid
+    }
+  };
+
+  let did: WebVhDID = WebVhDID::try_from_core(entry.state.id().clone()).map_err(|err| Error::InvalidEntry {
+    index,
+    message: err.to_string(),
+  })?;
+  if did.scid() != scid {
+    return Err(Error::ScidMismatch(format!(
+      "the DID document of entry {index} has the id {did}"
+    )));
+  }
+  if let Some(state) = previous {
+    if did != state.did && !state.portable {
+      return Err(invalid("the DID cannot change as it is not portable"));
+    }
+    if parameters.portable == Some(true) && !state.portable {
+      return Err(invalid("portability can only be enabled in the first entry"));
+    }
+  }
+
+  let previous_version_id: &str = previous.map_or(scid, |state| &state.version_id);
+  if entry.compute_entry_hash(previous_version_id)? != entry_hash {
+    return Err(Error::EntryHashMismatch(index));
+  }
+
+  let authorized_keys: &[UpdateKey] = match previous {
+    None => parameters
+      .update_keys
+      .as_deref()
+      .filter(|keys| !keys.is_empty())
+      .ok_or_else(|| invalid("the first entry must define updateKeys"))?,
+    Some(state) if state.pre_rotation() => {
+      let update_keys: &[UpdateKey] = parameters
+        .update_keys
+        .as_deref()
+        .ok_or(Error::PreRotationViolation(index))?;
+      if update_keys
+        .iter()
+        .any(|update_key| !state.next_key_hashes.contains(&update_key.hash()))
+      {
+        return Err(Error::PreRotationViolation(index));
+      }
+      update_keys
+    }
+    Some(state) => &state.update_keys,
+  };
+  if entry.proof.is_empty() {
+    return Err(Error::Unauthorized {
+      index,
+      message: "the entry has no proof".to_owned(),
+    });
+  }
+  for proof in &entry.proof {
+    let update_key: UpdateKey = proof.update_key().map_err(|err| Error::Unauthorized {
+      index,
+      message: err.to_string(),
+    })?;
+    if !authorized_keys.contains(&update_key) {
+      return Err(Error::Unauthorized {
+        index,
+        message: format!("{update_key} is not an authorized update key"),
+      });
+    }
+  }
+
+  Ok(LogState {
+    scid: scid.to_owned(),
+    did,
+    version_id: entry.version_id.clone(),
+    version_number,
+    version_time: entry.version_time,
+    update_keys: parameters
+      .update_keys
+      .clone()
+      .or_else(|| previous.map(|state| state.update_keys.clone()))
+      .unwrap_or_default(),
+    next_key_hashes: parameters
+      .next_key_hashes
+      .clone()
+      .or_else(|| previous.map(|state| state.next_key_hashes.clone()))
+      .unwrap_or_default(),
+    portable: parameters
+      .portable
+      .or_else(|| previous.map(|state| state.portable))
+      .unwrap_or_default(),
+    deactivated: parameters.deactivated.unwrap_or_default(),
+  })
+}
+
+/// Verifies the signatures of the proofs of `entry`, whose update keys were validated by [`validate_entry`].
+fn verify_proofs<TRV, PQV>(index: usize, entry: &LogEntry, traditional_verifier: &TRV, pq_verifier: &PQV) -> Result<()>
+where
+  TRV: JwsVerifier,
+  PQV: JwsVerifier,
+{
+  for proof in &entry.proof {
+    let update_key: UpdateKey = proof.update_key()?;
+    let signature: Vec<u8> = proof.signature().map_err(|err| Error::Unauthorized {
+      index,
+      message: err.to_string(),
+    })?;
+    update_key
+      .verify(&entry.hash_data(proof)?, &signature, traditional_verifier, pq_verifier)
+      .map_err(|err| Error::ProofVerification(index, err))?;
+  }
+  Ok(())
+}
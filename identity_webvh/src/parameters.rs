@@ -0,0 +1,120 @@
+// Copyright 2020-2024 IOTA Stiftung, Fondazione Links
+// SPDX-License-Identifier: Apache-2.0
+
+use serde_json::Value;
+
+use crate::UpdateKey;
+
+/// The version of the `did:webvh` specification implemented by this crate.
+pub const WEBVH_METHOD_VERSION: &str = "did:webvh:1.0";
+
+/// The parameters of a `did:webvh` log entry.
+///
+/// The first entry of a log defines the initial parameters, subsequent entries only contain the parameters that
+/// change. Witnesses are not supported: a log configuring a witness is rejected.
+#[derive(Clone, Debug, Default, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct LogParameters {
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub(crate) method: Option<String>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub(crate) scid: Option<String>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub(crate) update_keys: Option<Vec<UpdateKey>>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub(crate) next_key_hashes: Option<Vec<String>>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub(crate) portable: Option<bool>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub(crate) deactivated: Option<bool>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub(crate) ttl: Option<u64>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub(crate) witness: Option<Value>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub(crate) watchers: Option<Vec<String>>,
+}
+
+impl LogParameters {
+  /// Creates parameters that change nothing.
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  /// Sets the keys authorized to sign subsequent log entries.
+  ///
+  /// While pre-rotation is active, every new update key must have been committed to by
+  /// [`LogParameters::with_next_key_hashes`] in the previous entry.
+  pub fn with_update_keys(mut self, update_keys: impl IntoIterator<Item = UpdateKey>) -> Self {
+    self.update_keys = Some(update_keys.into_iter().collect());
+    self
+  }
+
+  /// Commits to the update keys of the next entry, see [`UpdateKey::hash`].
+  ///
+  /// An empty list deactivates pre-rotation.
+  pub fn with_next_key_hashes(mut self, next_key_hashes: impl IntoIterator<Item = String>) -> Self {
+    self.next_key_hashes = Some(next_key_hashes.into_iter().collect());
+    self
+  }
+
+  /// Sets whether the DID may be moved to a different location while retaining its SCID.
+  ///
+  /// Portability can only be enabled in the first log entry.
+  pub fn with_portable(mut self, portable: bool) -> Self {
+    self.portable = Some(portable);
+    self
+  }
+
+  /// Sets the number of seconds for which resolvers may cache the log.
+  pub fn with_ttl(mut self, ttl: u64) -> Self {
+    self.ttl = Some(ttl);
+    self
+  }
+
+  /// Returns the version of the specification, set in the first entry.
+  pub fn method(&self) -> Option<&str> {
+    self.method.as_deref()
+  }
+
+  /// Returns the SCID of the DID, set in the first entry.
+  pub fn scid(&self) -> Option<&str> {
+    self.scid.as_deref()
+  }
+
+  /// Returns the new update keys, if they change.
+  pub fn update_keys(&self) -> Option<&[UpdateKey]> {
+    self.update_keys.as_deref()
+  }
+
+  /// Returns the new pre-rotation commitments, if they change.
+  pub fn next_key_hashes(&self) -> Option<&[String]> {
+    self.next_key_hashes.as_deref()
+  }
+
+  /// Returns the new portability, if it changes.
+  pub fn portable(&self) -> Option<bool> {
+    self.portable
+  }
+
+  /// Returns whether the DID is deactivated by the entry.
+  pub fn deactivated(&self) -> Option<bool> {
+    self.deactivated
+  }
+
+  /// Returns the new cache lifetime in seconds, if it changes.
+  pub fn ttl(&self) -> Option<u64> {
+    self.ttl
+  }
+
+  /// Returns `true` if a witness is configured.
+  ///
+  /// An empty witness configuration, as emitted by implementations that disable witnessing explicitly, is ignored.
+  pub(crate) fn has_witness(&self) -> bool {
+    match &self.witness {
+      None | Some(Value::Null) => false,
+      Some(Value::Object(witness)) => !witness.is_empty(),
+      Some(_) => true,
+    }
+  }
+}
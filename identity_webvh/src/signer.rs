@@ -0,0 +1,162 @@
+// Copyright 2020-2024 IOTA Stiftung, Fondazione Links
+// SPDX-License-Identifier: Apache-2.0
+
+use async_trait::async_trait;
+use identity_storage::JwkGenOutput;
+use identity_storage::JwkStorage;
+use identity_storage::JwkStoragePQ;
+use identity_storage::KeyId;
+use identity_storage::KeyIdStorage;
+use identity_storage::KeyType;
+use identity_storage::Storage;
+use identity_verification::jwk::CompositeJwk;
+use identity_verification::jwk::Jwk;
+use identity_verification::jws::JwsAlgorithm;
+
+use crate::update_key::composite_signing_input;
+use crate::Error;
+use crate::Result;
+use crate::UpdateKey;
+use crate::UpdateKeyType;
+
+/// Separates the key ids of the components of a composite key, as in the hybrid document extension of
+/// `identity_storage`.
+const COMPOSITE_KEY_ID_SEPARATOR: char = '~';
+
+/// Signs `did:webvh` log entries with an [`UpdateKey`].
+#[cfg_attr(not(feature = "send-sync-storage"), async_trait(?Send))]
+#[cfg_attr(feature = "send-sync-storage", async_trait)]
+pub trait LogSigner {
+  /// Returns the update key whose private key creates the signatures.
+  fn update_key(&self) -> &UpdateKey;
+
+  /// Signs `data` as required by the cryptosuite of the [`UpdateKeyType`] of the key.
+  async fn sign(&self, data: &[u8]) -> Result<Vec<u8>>;
+}
+
+/// A [`LogSigner`] backed by a [`Storage`].
+///
+/// Composite keys are stored as two keys whose key ids are joined by `~`.
+pub struct StorageLogSigner<'s, K, I> {
+  storage: &'s Storage<K, I>,
+  key_id: KeyId,
+  update_key: UpdateKey,
+}
+
+impl<'s, K, I> StorageLogSigner<'s, K, I>
+where
+  K: JwkStorage + JwkStoragePQ,
+{
+  /// Creates a signer for the key with the given id, whose public key is `update_key`.
+  pub fn new(storage: &'s Storage<K, I>, key_id: KeyId, update_key: UpdateKey) -> Self {
+    Self {
+      storage,
+      key_id,
+      update_key,
+    }
+  }
+
+  /// Generates a new key of the given type in `storage`.
+  ///
+  /// # Errors
+  ///
+  /// Fails if the key storage does not support the key type.
+  pub async fn generate(storage: &'s Storage<K, I>, key_type: UpdateKeyType) -> Result<Self> {
+    let ed25519 = || KeyType::from_static_str("Ed25519");
+    let ml_dsa = || KeyType::from_static_str("ML-DSA");
+
+    let (key_id, update_key) = match (key_type.composite_alg(), key_type.pq_alg()) {
+      (None, None) => {
+        let JwkGenOutput { key_id, jwk, .. } = storage
+          .key_storage()
+          .generate(ed25519(), JwsAlgorithm::EdDSA)
+          .await
+          .map_err(|err| Error::Signing(err.into()))?;
+        (key_id, UpdateKey::from_jwk(&jwk)?)
+      }
+      (None, Some(alg)) => {
+        let JwkGenOutput { key_id, jwk, .. } = storage
+          .key_storage()
+          .generate_pq_key(ml_dsa(), alg)
+          .await
+          .map_err(|err| Error::Signing(err.into()))?;
+        (key_id, UpdateKey::from_jwk(&jwk)?)
+      }
+      (Some(alg_id), Some(alg)) => {
+        let traditional: JwkGenOutput = storage
+          .key_storage()
+          .generate(ed25519(), JwsAlgorithm::EdDSA)
+          .await
+          .map_err(|err| Error::Signing(err.into()))?;
+        let pq: JwkGenOutput = storage
+          .key_storage()
+          .generate_pq_key(ml_dsa(), alg)
+          .await
+          .map_err(|err| Error::Signing(err.into()))?;
+        let key_id: KeyId = KeyId::new(format!(
+          "{}{COMPOSITE_KEY_ID_SEPARATOR}{}",
+          traditional.key_id.as_str(),
+          pq.key_id.as_str()
+        ));
+        let composite: CompositeJwk = CompositeJwk::new(alg_id, traditional.jwk, pq.jwk);
+        (key_id, UpdateKey::from_composite_jwk(&composite)?)
+      }
+      (Some(_), None) => unreachable!("composite keys contain an ML-DSA key"),
+    };
+
+    Ok(Self::new(storage, key_id, update_key))
+  }
+
+  /// Returns the id of the key in the key storage.
+  pub fn key_id(&self) -> &KeyId {
+    &self.key_id
+  }
+}
+
+#[cfg_attr(not(feature = "send-sync-storage"), async_trait(?Send))]
+#[cfg_attr(feature = "send-sync-storage", async_trait)]
+impl<'s, K, I> LogSigner for StorageLogSigner<'s, K, I>
+where
+  K: JwkStorage + JwkStoragePQ,
+  I: KeyIdStorage,
+{
+  fn update_key(&self) -> &UpdateKey {
+    &self.update_key
+  }
+
+  async fn sign(&self, data: &[u8]) -> Result<Vec<u8>> {
+    let key_storage: &K = self.storage.key_storage();
+    let key_type: UpdateKeyType = self.update_key.key_type();
+    let signature: Vec<u8> = match (key_type.composite_alg(), key_type.pq_alg()) {
+      (None, None) => {
+        let jwk: Jwk = self.update_key.to_jwk()?;
+        key_storage.sign(&self.key_id, data, &jwk).await
+      }
+      (None, Some(_)) => {
+        let jwk: Jwk = self.update_key.to_jwk()?;
+        key_storage.pq_sign(&self.key_id, data, &jwk).await
+      }
+      (Some(_), _) => {
+        let composite: CompositeJwk = self.update_key.to_composite_jwk()?;
+        let (traditional_key_id, pq_key_id) = self
+          .key_id
+          .as_str()
+          .split_once(COMPOSITE_KEY_ID_SEPARATOR)
+          .map(|(traditional, pq)| (KeyId::new(traditional), KeyId::new(pq)))
+          .ok_or_else(|| Error::Signing(format!("\"{}\" is not a composite key id", self.key_id).into()))?;
+        let signing_input: Vec<u8> = composite_signing_input(composite.alg_id(), data);
+        let traditional_signature: Vec<u8> = key_storage
+          .sign(&traditional_key_id, &signing_input, composite.traditional_public_key())
+          .await
+          .map_err(|err| Error::Signing(err.into()))?;
+        key_storage
+          .pq_sign(&pq_key_id, &signing_input, composite.pq_public_key())
+          .await
+          .map(|pq_signature| [traditional_signature, pq_signature].concat())
+      }
+    }
+    .map_err(|err| Error::Signing(err.into()))?;
+
+    Ok(signature)
+  }
+}
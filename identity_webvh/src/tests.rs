@@ -0,0 +1,288 @@
+// Copyright 2020-2024 IOTA Stiftung, Fondazione Links
+// SPDX-License-Identifier: Apache-2.0
+
+use identity_core::common::Object;
+use identity_core::common::Timestamp;
+use identity_core::common::Url;
+use identity_core::convert::ToJson;
+use identity_did::CoreDID;
+use identity_document::document::CoreDocument;
+use identity_eddsa_verifier::EdDSAJwsVerifier;
+use identity_pqc_verifier::PQCJwsVerifier;
+use identity_storage::JwkMemStore;
+use identity_storage::KeyIdMemstore;
+use identity_storage::Storage;
+use identity_verification::MethodScope;
+use identity_verification::VerificationMethod;
+
+use crate::*;
+
+type MemStorage = Storage<JwkMemStore, KeyIdMemstore>;
+
+const LOCATION: &str = "example.com%3A8443:dids:alice";
+
+fn storage() -> MemStorage {
+  Storage::new(JwkMemStore::new(), KeyIdMemstore::new())
+}
+
+fn timestamp(seconds: i64) -> Timestamp {
+  Timestamp::from_unix(1_700_000_000 + seconds).unwrap()
+}
+
+fn verify(log: &DidLog) -> Result<Vec<LogVersion>> {
+  let did: WebVhDID = WebVhDID::try_from_core(log.latest().unwrap().state().id().clone()).unwrap();
+  log.verify(&did, &EdDSAJwsVerifier::default(), &PQCJwsVerifier::default())
+}
+
+/// Creates a document for the placeholder DID with the Ed25519 `assertion_key` as an assertion method.
+fn initial_document(assertion_key: &UpdateKey) -> CoreDocument {
+  let did: CoreDID = WebVhDID::placeholder(LOCATION).unwrap();
+  let mut document: CoreDocument = CoreDocument::builder(Object::new()).id(did.clone()).build().unwrap();
+  let method: VerificationMethod =
+    VerificationMethod::new_from_jwk(did, assertion_key.to_jwk().unwrap(), Some("key-1")).unwrap();
+  document.insert_method(method, MethodScope::assertion_method()).unwrap();
+  document
+}
+
+fn with_alias(document: &CoreDocument, alias: &str) -> CoreDocument {
+  let mut document: CoreDocument = document.clone();
+  document.also_known_as_mut().append(Url::parse(alias).unwrap());
+  document
+}
+
+#[tokio::test]
+async fn create_update_and_verify() {
+  for key_type in [
+    UpdateKeyType::Ed25519,
+    UpdateKeyType::MlDsa44,
+    UpdateKeyType::MlDsa65Ed25519,
+  ] {
+    let storage: MemStorage = storage();
+    let signer = StorageLogSigner::generate(&storage, key_type).await.unwrap();
+    let assertion_key = StorageLogSigner::generate(&storage, UpdateKeyType::Ed25519)
+      .await
+      .unwrap();
+
+    let mut log: DidLog = DidLog::create(
+      initial_document(assertion_key.update_key()),
+      LogParameters::new().with_update_keys([signer.update_key().clone()]),
+      timestamp(0),
+      &signer,
+    )
+    .await
+    .unwrap();
+
+    let first: CoreDocument = log.latest().unwrap().state().clone();
+    let did: WebVhDID = WebVhDID::try_from_core(first.id().clone()).unwrap();
+    assert_eq!(did.location(), LOCATION);
+    assert_eq!(log.latest().unwrap().parameters().scid(), Some(did.scid()));
+    assert!(!first.methods(None).is_empty());
+    assert!(first.methods(None).iter().all(|method| method.id().did() == first.id()));
+
+    let second: CoreDocument = with_alias(&first, "https://alice.example");
+    log
+      .update(second.clone(), LogParameters::new(), timestamp(10), &signer)
+      .await
+      .unwrap();
+
+    let log: DidLog = DidLog::from_jsonl(&log.to_jsonl().unwrap()).unwrap();
+    let versions: Vec<LogVersion> = verify(&log).unwrap();
+    assert_eq!(versions.len(), 2);
+    assert_eq!(versions[0].document, first);
+    assert_eq!(versions[1].document, second);
+    assert_eq!(versions[1].version_time, timestamp(10));
+    assert!(versions[1].version_id.starts_with("2-"));
+    assert_eq!(log.entries()[1].proof()[0].cryptosuite(), key_type.cryptosuite());
+  }
+}
+
+#[tokio::test]
+async fn key_rotation_and_authorization() {
+  let storage: MemStorage = storage();
+  let first_key = StorageLogSigner::generate(&storage, UpdateKeyType::Ed25519)
+    .await
+    .unwrap();
+  let second_key = StorageLogSigner::generate(&storage, UpdateKeyType::MlDsa44Ed25519)
+    .await
+    .unwrap();
+
+  let mut log: DidLog = DidLog::create(
+    initial_document(first_key.update_key()),
+    LogParameters::new().with_update_keys([first_key.update_key().clone()]),
+    timestamp(0),
+    &first_key,
+  )
+  .await
+  .unwrap();
+  let document: CoreDocument = log.latest().unwrap().state().clone();
+
+  // A key that is not an update key cannot sign.
+  assert!(matches!(
+    log
+      .update(document.clone(), LogParameters::new(), timestamp(10), &second_key)
+      .await
+      .unwrap_err(),
+    Error::Unauthorized { index: 2, .. }
+  ));
+
+  // Rotating to a new key requires a signature of the current key.
+  log
+    .update(
+      document.clone(),
+      LogParameters::new().with_update_keys([second_key.update_key().clone()]),
+      timestamp(10),
+      &first_key,
+    )
+    .await
+    .unwrap();
+  assert!(matches!(
+    log
+      .update(document.clone(), LogParameters::new(), timestamp(20), &first_key)
+      .await
+      .unwrap_err(),
+    Error::Unauthorized { index: 3, .. }
+  ));
+  log
+    .update(
+      with_alias(&document, "https://alice.example"),
+      LogParameters::new(),
+      timestamp(20),
+      &second_key,
+    )
+    .await
+    .unwrap();
+
+  assert_eq!(verify(&log).unwrap().len(), 3);
+}
+
+#[tokio::test]
+async fn pre_rotation() {
+  let storage: MemStorage = storage();
+  let first_key = StorageLogSigner::generate(&storage, UpdateKeyType::Ed25519)
+    .await
+    .unwrap();
+  let committed_key = StorageLogSigner::generate(&storage, UpdateKeyType::MlDsa65)
+    .await
+    .unwrap();
+  let uncommitted_key = StorageLogSigner::generate(&storage, UpdateKeyType::Ed25519)
+    .await
+    .unwrap();
+
+  let mut log: DidLog = DidLog::create(
+    initial_document(first_key.update_key()),
+    LogParameters::new()
+      .with_update_keys([first_key.update_key().clone()])
+      .with_next_key_hashes([committed_key.update_key().hash()]),
+    timestamp(0),
+    &first_key,
+  )
+  .await
+  .unwrap();
+  let document: CoreDocument = log.latest().unwrap().state().clone();
+
+  // While pre-rotation is active, every entry must rotate to committed keys.
+  assert!(matches!(
+    log
+      .update(document.clone(), LogParameters::new(), timestamp(10), &first_key)
+      .await
+      .unwrap_err(),
+    Error::PreRotationViolation(2)
+  ));
+  assert!(matches!(
+    log
+      .update(
+        document.clone(),
+        LogParameters::new().with_update_keys([uncommitted_key.update_key().clone()]),
+        timestamp(10),
+        &uncommitted_key,
+      )
+      .await
+      .unwrap_err(),
+    Error::PreRotationViolation(2)
+  ));
+
+  log
+    .update(
+      document.clone(),
+      LogParameters::new()
+        .with_update_keys([committed_key.update_key().clone()])
+        .with_next_key_hashes([uncommitted_key.update_key().hash()]),
+      timestamp(10),
+      &committed_key,
+    )
+    .await
+    .unwrap();
+  log.deactivate(timestamp(20), &uncommitted_key).await.unwrap();
+
+  let versions: Vec<LogVersion> = verify(&log).unwrap();
+  assert!(versions.last().unwrap().deactivated);
+  assert!(matches!(
+    log
+      .update(document, LogParameters::new(), timestamp(30), &uncommitted_key)
+      .await
+      .unwrap_err(),
+    Error::Deactivated
+  ));
+}
+
+#[tokio::test]
+async fn tampering_is_detected() {
+  let storage: MemStorage = storage();
+  let signer = StorageLogSigner::generate(&storage, UpdateKeyType::Ed25519)
+    .await
+    .unwrap();
+  let mut log: DidLog = DidLog::create(
+    initial_document(signer.update_key()),
+    LogParameters::new().with_update_keys([signer.update_key().clone()]),
+    timestamp(0),
+    &signer,
+  )
+  .await
+  .unwrap();
+  let document: CoreDocument = log.latest().unwrap().state().clone();
+  log
+    .update(
+      with_alias(&document, "https://alice.example"),
+      LogParameters::new(),
+      timestamp(10),
+      &signer,
+    )
+    .await
+    .unwrap();
+  let jsonl: String = log.to_jsonl().unwrap();
+
+  let altered: DidLog = DidLog::from_jsonl(&jsonl.replace("https://alice.example", "https://mallory.example")).unwrap();
+  assert!(matches!(verify(&altered).unwrap_err(), Error::EntryHashMismatch(2)));
+
+  let lines: Vec<&str> = jsonl.lines().collect();
+  let reordered: DidLog = DidLog::from_jsonl(&format!("{}\n{}\n", lines[1], lines[0])).unwrap();
+  assert!(verify(&reordered).is_err());
+
+  let truncated: DidLog = DidLog::from_jsonl(lines[1]).unwrap();
+  assert!(verify(&truncated).is_err());
+
+  // Re-signing an altered entry with an unauthorized key does not help.
+  let mut entry: LogEntry = log.entries()[1].clone();
+  entry.proof[0] = log.entries()[0].proof()[0].clone();
+  let forged: DidLog = DidLog::from_jsonl(&format!("{}\n{}", lines[0], entry.to_json().unwrap())).unwrap();
+  assert!(matches!(verify(&forged).unwrap_err(), Error::ProofVerification(2, _)));
+
+  let other: WebVhDID = WebVhDID::new("QmOther", LOCATION).unwrap();
+  assert!(matches!(
+    log
+      .verify(&other, &EdDSAJwsVerifier::default(), &PQCJwsVerifier::default())
+      .unwrap_err(),
+    Error::ScidMismatch(_)
+  ));
+  assert!(DidLog::create(
+    CoreDocument::builder(Object::new())
+      .id(CoreDID::parse("did:example:alice").unwrap())
+      .build()
+      .unwrap(),
+    LogParameters::new().with_update_keys([signer.update_key().clone()]),
+    timestamp(0),
+    &signer,
+  )
+  .await
+  .is_err());
+}
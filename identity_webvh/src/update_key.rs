@@ -0,0 +1,452 @@
+// Copyright 2020-2024 IOTA Stiftung, Fondazione Links
+// SPDX-License-Identifier: Apache-2.0
+
+use std::fmt::Display;
+use std::fmt::Formatter;
+use std::str::FromStr;
+
+use crypto::hashes::sha::Sha256;
+use crypto::hashes::sha::Sha512;
+use crypto::hashes::Digest;
+use identity_core::convert::BaseEncoding;
+use identity_did::multicodec::decode_varint;
+use identity_did::multicodec::encode_varint;
+use identity_verification::jwk::CompositeAlgId;
+use identity_verification::jwk::CompositeJwk;
+use identity_verification::jwk::EdCurve;
+use identity_verification::jwk::Jwk;
+use identity_verification::jwk::JwkParams;
+use identity_verification::jwk::JwkParamsOkp;
+use identity_verification::jwk::JwkType;
+use identity_verification::jws::JwsAlgorithm;
+use identity_verification::jws::JwsVerifier;
+use identity_verification::jws::SignatureVerificationError;
+use identity_verification::jws::SignatureVerificationErrorKind;
+use identity_verification::jws::VerificationInput;
+use identity_verification::jwu;
+
+use crate::Error;
+use crate::Result;
+
+/// The length of an Ed25519 public key.
+const ED25519_PUBLIC_KEY_LENGTH: usize = 32;
+/// The length of an Ed25519 signature.
+const ED25519_SIGNATURE_LENGTH: usize = 64;
+/// The multihash code of SHA2-256 followed by the digest length.
+const SHA256_MULTIHASH_PREFIX: [u8; 2] = [0x12, 0x20];
+
+/// The type of an [`UpdateKey`].
+///
+/// Only the Ed25519 multicodec is registered. The ML-DSA codes are provisional and the composite codes are taken from
+/// the private-use range, so logs signed with these keys are not interoperable with other `did:webvh` implementations.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum UpdateKeyType {
+  /// Ed25519, signing with the `eddsa-jcs-2022` cryptosuite.
+  Ed25519,
+  /// ML-DSA-44, signing with the `mldsa44-jcs-2024` cryptosuite.
+  MlDsa44,
+  /// ML-DSA-65, signing with the `mldsa65-jcs-2024` cryptosuite.
+  MlDsa65,
+  /// ML-DSA-87, signing with the `mldsa87-jcs-2024` cryptosuite.
+  MlDsa87,
+  /// `id-MLDSA44-Ed25519-SHA512`, signing with the `mldsa44-ed25519-jcs-2024` cryptosuite.
+  MlDsa44Ed25519,
+  /// `id-MLDSA65-Ed25519-SHA512`, signing with the `mldsa65-ed25519-jcs-2024` cryptosuite.
+  MlDsa65Ed25519,
+}
+
+impl UpdateKeyType {
+  const ALL: [Self; 6] = [
+    Self::Ed25519,
+    Self::MlDsa44,
+    Self::MlDsa65,
+    Self::MlDsa87,
+    Self::MlDsa44Ed25519,
+    Self::MlDsa65Ed25519,
+  ];
+
+  /// Returns the multicodec code of the public key type.
+  pub const fn multicodec(self) -> u64 {
+    match self {
+      Self::Ed25519 => 0xed,
+      Self::MlDsa44 => 0x1210,
+      Self::MlDsa65 => 0x1211,
+      Self::MlDsa87 => 0x1212,
+      Self::MlDsa44Ed25519 => 0x30_0001,
+      Self::MlDsa65Ed25519 => 0x30_0002,
+    }
+  }
+
+  /// Returns the name of the Data Integrity cryptosuite used to sign with keys of this type.
+  pub const fn cryptosuite(self) -> &'static str {
+    match self {
+      Self::Ed25519 => "eddsa-jcs-2022",
+      Self::MlDsa44 => "mldsa44-jcs-2024",
+      Self::MlDsa65 => "mldsa65-jcs-2024",
+      Self::MlDsa87 => "mldsa87-jcs-2024",
+      Self::MlDsa44Ed25519 => "mldsa44-ed25519-jcs-2024",
+      Self::MlDsa65Ed25519 => "mldsa65-ed25519-jcs-2024",
+    }
+  }
+
+  /// Returns the length of the encoded public key.
+  ///
+  /// Composite public keys are encoded as the Ed25519 public key followed by the ML-DSA public key.
+  pub const fn public_key_length(self) -> usize {
+    match self {
+      Self::Ed25519 => ED25519_PUBLIC_KEY_LENGTH,
+      Self::MlDsa44 => 1312,
+      Self::MlDsa65 => 1952,
+      Self::MlDsa87 => 2592,
+      Self::MlDsa44Ed25519 => ED25519_PUBLIC_KEY_LENGTH + 1312,
+      Self::MlDsa65Ed25519 => ED25519_PUBLIC_KEY_LENGTH + 1952,
+    }
+  }
+
+  /// Returns the ML-DSA algorithm of the key, if any.
+  pub(crate) const fn pq_alg(self) -> Option<JwsAlgorithm> {
+    match self {
+      Self::Ed25519 => None,
+      Self::MlDsa44 | Self::MlDsa44Ed25519 => Some(JwsAlgorithm::ML_DSA_44),
+      Self::MlDsa65 | Self::MlDsa65Ed25519 => Some(JwsAlgorithm::ML_DSA_65),
+      Self::MlDsa87 => Some(JwsAlgorithm::ML_DSA_87),
+    }
+  }
+
+  /// Returns the composite algorithm of the key, if any.
+  pub(crate) const fn composite_alg(self) -> Option<CompositeAlgId> {
+    match self {
+      Self::MlDsa44Ed25519 => Some(CompositeAlgId::IdMldsa44Ed25519Sha512),
+      Self::MlDsa65Ed25519 => Some(CompositeAlgId::IdMldsa65Ed25519Sha512),
+      _ => None,
+    }
+  }
+
+  fn from_multicodec(code: u64) -> Option<Self> {
+    Self::ALL.into_iter().find(|key_type| key_type.multicodec() == code)
+  }
+}
+
+impl Display for UpdateKeyType {
+  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    f.write_str(self.cryptosuite())
+  }
+}
+
+/// A key authorized to update a `did:webvh` log, encoded as a
+/// [Multikey](https://www.w3.org/TR/controller-document/#multikey) public key.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, serde::Deserialize, serde::Serialize)]
+#[serde(into = "String", try_from = "String")]
+pub struct UpdateKey {
+  multikey: String,
+  key_type: UpdateKeyType,
+  public_key: Vec<u8>,
+}
+
+impl UpdateKey {
+  /// Parses an update key from its multibase encoded multikey representation.
+  ///
+  /// # Errors
+  ///
+  /// Fails if `multikey` is not a base58btc encoded multikey of a supported [`UpdateKeyType`].
+  pub fn parse(multikey: impl Into<String>) -> Result<Self> {
+    let multikey: String = multikey.into();
+    if !multikey.starts_with('z') {
+      return Err(Error::InvalidUpdateKey(format!(
+        "expected a base58btc multibase string, found \"{multikey}\""
+      )));
+    }
+    let bytes: Vec<u8> =
+      BaseEncoding::decode_multibase(&multikey).map_err(|err| Error::InvalidUpdateKey(err.to_string()))?;
+    let (code, public_key) =
+      decode_varint(&bytes).ok_or_else(|| Error::InvalidUpdateKey("malformed multicodec prefix".to_owned()))?;
+    let key_type: UpdateKeyType = UpdateKeyType::from_multicodec(code)
+      .ok_or_else(|| Error::InvalidUpdateKey(format!("unsupported multicodec 0x{code:x}")))?;
+    if public_key.len() != key_type.public_key_length() {
+      return Err(Error::InvalidUpdateKey(format!(
+        "expected a {key_type} public key of {} bytes, found {} bytes",
+        key_type.public_key_length(),
+        public_key.len()
+      )));
+    }
+
+    Ok(Self {
+      multikey,
+      key_type,
+      public_key: public_key.to_vec(),
+    })
+  }
+
+  /// Creates an update key from a public Ed25519 or ML-DSA [`Jwk`].
+  ///
+  /// # Errors
+  ///
+  /// Fails if `jwk` is not a public key of a supported type.
+  pub fn from_jwk(jwk: &Jwk) -> Result<Self> {
+    let (key_type, public_key) = match jwk.params() {
+      JwkParams::Okp(params) if jwk.is_public() => {
+        params
+          .try_ed_curve()
+          .ok()
+          .filter(|curve| *curve == EdCurve::Ed25519)
+          .ok_or_else(|| Error::InvalidUpdateKey(format!("unsupported curve \"{}\"", params.crv)))?;
+        (UpdateKeyType::Ed25519, decode_b64(&params.x)?)
+      }
+      JwkParams::MLDSA(params) if jwk.is_public() => {
+        let key_type: UpdateKeyType = match jwk.alg().and_then(|alg| JwsAlgorithm::from_str(alg).ok()) {
+          Some(JwsAlgorithm::ML_DSA_44) => UpdateKeyType::MlDsa44,
+          Some(JwsAlgorithm::ML_DSA_65) => UpdateKeyType::MlDsa65,
+          Some(JwsAlgorithm::ML_DSA_87) => UpdateKeyType::MlDsa87,
+          _ => return Err(Error::InvalidUpdateKey("ML-DSA key without a supported alg".to_owned())),
+        };
+        (key_type, decode_b64(&params.public)?)
+      }
+      _ => {
+        return Err(Error::InvalidUpdateKey(
+          "expected a public Ed25519 or ML-DSA key".to_owned(),
+        ))
+      }
+    };
+
+    Self::from_public_key(key_type, public_key)
+  }
+
+  /// Creates an update key from a [`CompositeJwk`].
+  ///
+  /// # Errors
+  ///
+  /// Fails if the component keys do not match the algorithm of the composite key.
+  pub fn from_composite_jwk(jwk: &CompositeJwk) -> Result<Self> {
+    let key_type: UpdateKeyType = match jwk.alg_id() {
+      CompositeAlgId::IdMldsa44Ed25519Sha512 => UpdateKeyType::MlDsa44Ed25519,
+      CompositeAlgId::IdMldsa65Ed25519Sha512 => UpdateKeyType::MlDsa65Ed25519,
+    };
+    let traditional: Self = Self::from_jwk(jwk.traditional_public_key())?;
+    let pq: Self = Self::from_jwk(jwk.pq_public_key())?;
+    if traditional.key_type != UpdateKeyType::Ed25519 || pq.key_type.pq_alg() != key_type.pq_alg() {
+      return Err(Error::InvalidUpdateKey(format!(
+        "the component keys do not match {}",
+        jwk.alg_id().name()
+      )));
+    }
+
+    Self::from_public_key(key_type, [traditional.public_key, pq.public_key].concat())
+  }
+
+  fn from_public_key(key_type: UpdateKeyType, public_key: Vec<u8>) -> Result<Self> {
+    let mut bytes: Vec<u8> = encode_varint(key_type.multicodec());
+    bytes.extend_from_slice(&public_key);
+    Self::parse(BaseEncoding::encode_multibase(&bytes, None))
+  }
+
+  /// Returns the multibase encoded multikey representation of the key.
+  pub fn as_str(&self) -> &str {
+    &self.multikey
+  }
+
+  /// Returns the type of the key.
+  pub fn key_type(&self) -> UpdateKeyType {
+    self.key_type
+  }
+
+  /// Returns the hash of the key committed to in the `nextKeyHashes` parameter for pre-rotation.
+  ///
+  /// The hash is the base58btc encoded SHA2-256 multihash of the multikey representation of the key.
+  pub fn hash(&self) -> String {
+    multihash_sha256(self.multikey.as_bytes())
+  }
+
+  /// Returns the `did:key` verification method of the key, which identifies the signer in the proof of a log entry.
+  pub fn verification_method(&self) -> String {
+    format!("did:key:{key}#{key}", key = self.multikey)
+  }
+
+  /// Returns the key as a [`Jwk`].
+  ///
+  /// # Errors
+  ///
+  /// Fails for composite keys, see [`UpdateKey::to_composite_jwk`].
+  pub fn to_jwk(&self) -> Result<Jwk> {
+    match self.key_type {
+      UpdateKeyType::Ed25519 => Ok(ed25519_jwk(&self.public_key)),
+      key_type => match key_type.pq_alg().filter(|_| key_type.composite_alg().is_none()) {
+        Some(alg) => Ok(ml_dsa_jwk(&self.public_key, alg)),
+        None => Err(Error::InvalidUpdateKey(format!("{key_type} is a composite key"))),
+      },
+    }
+  }
+
+  /// Returns the key as a [`CompositeJwk`].
+  ///
+  /// # Errors
+  ///
+  /// Fails for keys that are not composite keys, see [`UpdateKey::to_jwk`].
+  pub fn to_composite_jwk(&self) -> Result<CompositeJwk> {
+    let (Some(alg_id), Some(pq_alg)) = (self.key_type.composite_alg(), self.key_type.pq_alg()) else {
+      return Err(Error::InvalidUpdateKey(format!(
+        "{} is not a composite key",
+        self.key_type
+      )));
+    };
+    let (traditional, pq) = self.public_key.split_at(ED25519_PUBLIC_KEY_LENGTH);
+    Ok(CompositeJwk::new(
+      alg_id,
+      ed25519_jwk(traditional),
+      ml_dsa_jwk(pq, pq_alg),
+    ))
+  }
+
+  /// Verifies that `signature` is a signature of `data` by this key.
+  ///
+  /// Ed25519 signatures are verified with `traditional_verifier` and ML-DSA signatures with `pq_verifier`. Composite
+  /// signatures consist of the Ed25519 signature followed by the ML-DSA signature, both computed over the DER encoded
+  /// OID of the composite algorithm followed by the SHA-512 digest of `data`.
+  pub fn verify<TRV, PQV>(
+    &self,
+    data: &[u8],
+    signature: &[u8],
+    traditional_verifier: &TRV,
+    pq_verifier: &PQV,
+  ) -> std::result::Result<(), SignatureVerificationError>
+  where
+    TRV: JwsVerifier,
+    PQV: JwsVerifier,
+  {
+    let input = |alg: JwsAlgorithm, signing_input: &[u8], signature: &[u8]| VerificationInput {
+      alg,
+      signing_input: signing_input.into(),
+      decoded_signature: signature.into(),
+    };
+
+    match (self.key_type.composite_alg(), self.key_type.pq_alg()) {
+      (None, None) => traditional_verifier.verify(
+        input(JwsAlgorithm::EdDSA, data, signature),
+        &ed25519_jwk(&self.public_key),
+      ),
+      (None, Some(alg)) => pq_verifier.verify(input(alg, data, signature), &ml_dsa_jwk(&self.public_key, alg)),
+      (Some(alg_id), Some(pq_alg)) => {
+        if signature.len() <= ED25519_SIGNATURE_LENGTH {
+          return Err(
+            SignatureVerificationError::new(SignatureVerificationErrorKind::InvalidSignature)
+              .with_custom_message("composite signature too short"),
+          );
+        }
+        let signing_input: Vec<u8> = composite_signing_input(alg_id, data);
+        let (traditional_signature, pq_signature) = signature.split_at(ED25519_SIGNATURE_LENGTH);
+        let (traditional_key, pq_key) = self.public_key.split_at(ED25519_PUBLIC_KEY_LENGTH);
+        traditional_verifier.verify(
+          input(JwsAlgorithm::EdDSA, &signing_input, traditional_signature),
+          &ed25519_jwk(traditional_key),
+        )?;
+        pq_verifier.verify(input(pq_alg, &signing_input, pq_signature), &ml_dsa_jwk(pq_key, pq_alg))
+      }
+      (Some(_), None) => unreachable!("composite keys contain an ML-DSA key"),
+    }
+  }
+}
+
+/// Returns the message signed by both components of a composite key, i.e. the DER encoded OID of the composite
+/// algorithm followed by the SHA-512 digest of `data`.
+pub(crate) fn composite_signing_input(alg_id: CompositeAlgId, data: &[u8]) -> Vec<u8> {
+  let last_arc: u8 = match alg_id {
+    CompositeAlgId::IdMldsa44Ed25519Sha512 => 0x03,
+    CompositeAlgId::IdMldsa65Ed25519Sha512 => 0x0A,
+  };
+  let mut input: Vec<u8> = vec![
+    0x06, 0x0B, 0x60, 0x86, 0x48, 0x01, 0x86, 0xFA, 0x6B, 0x50, 0x08, 0x01, last_arc,
+  ];
+  input.extend_from_slice(&Sha512::digest(data));
+  input
+}
+
+/// Returns the base58btc encoded SHA2-256 multihash of `data`, without a multibase prefix.
+pub(crate) fn multihash_sha256(data: &[u8]) -> String {
+  let mut multihash: Vec<u8> = SHA256_MULTIHASH_PREFIX.to_vec();
+  multihash.extend_from_slice(&Sha256::digest(data));
+  BaseEncoding::encode_base58(&multihash)
+}
+
+fn ed25519_jwk(public_key: &[u8]) -> Jwk {
+  let mut params: JwkParamsOkp = JwkParamsOkp::new();
+  params.crv = EdCurve::Ed25519.name().to_owned();
+  params.x = jwu::encode_b64(public_key);
+  let mut jwk: Jwk = Jwk::from_params(params);
+  jwk.set_alg(JwsAlgorithm::EdDSA.name());
+  jwk
+}
+
+fn ml_dsa_jwk(public_key: &[u8], alg: JwsAlgorithm) -> Jwk {
+  let mut params: JwkParams = JwkParams::new(JwkType::MLDSA);
+  if let JwkParams::MLDSA(params) = &mut params {
+    params.public = jwu::encode_b64(public_key);
+  }
+  let mut jwk: Jwk = Jwk::from_params(params);
+  jwk.set_alg(alg.name());
+  jwk
+}
+
+fn decode_b64(data: &str) -> Result<Vec<u8>> {
+  jwu::decode_b64(data).map_err(|err| Error::InvalidUpdateKey(err.to_string()))
+}
+
+impl Display for UpdateKey {
+  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    f.write_str(&self.multikey)
+  }
+}
+
+impl FromStr for UpdateKey {
+  type Err = Error;
+  fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+    Self::parse(s)
+  }
+}
+
+impl TryFrom<String> for UpdateKey {
+  type Error = Error;
+  fn try_from(value: String) -> std::result::Result<Self, Self::Error> {
+    Self::parse(value)
+  }
+}
+
+impl From<UpdateKey> for String {
+  fn from(value: UpdateKey) -> Self {
+    value.multikey
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_ed25519_multikey() {
+    // Multikey taken from the did:webvh specification examples.
+    let multikey: &str = "z6MkhbNRN2Q9BaY9TvTc2K3izkhfVwgHiXL7VWZnTqxEvc3R";
+    let key: UpdateKey = UpdateKey::parse(multikey).unwrap();
+    assert_eq!(key.key_type(), UpdateKeyType::Ed25519);
+    assert_eq!(UpdateKey::from_jwk(&key.to_jwk().unwrap()).unwrap(), key);
+    assert_eq!(key.verification_method(), format!("did:key:{multikey}#{multikey}"));
+    assert!(key.to_composite_jwk().is_err());
+
+    assert!(UpdateKey::parse(&multikey[..multikey.len() - 1]).is_err());
+    assert!(UpdateKey::parse(multikey.replacen('z', "u", 1)).is_err());
+  }
+
+  #[test]
+  fn test_composite_multikey() {
+    let traditional: Jwk = ed25519_jwk(&[1; ED25519_PUBLIC_KEY_LENGTH]);
+    let pq: Jwk = ml_dsa_jwk(&[2; 1312], JwsAlgorithm::ML_DSA_44);
+    let composite: CompositeJwk = CompositeJwk::new(CompositeAlgId::IdMldsa44Ed25519Sha512, traditional, pq.clone());
+
+    let key: UpdateKey = UpdateKey::from_composite_jwk(&composite).unwrap();
+    assert_eq!(key.key_type(), UpdateKeyType::MlDsa44Ed25519);
+    assert_eq!(key.to_composite_jwk().unwrap(), composite);
+    assert_eq!(UpdateKey::parse(key.as_str()).unwrap(), key);
+    assert!(key.to_jwk().is_err());
+
+    let mismatched: CompositeJwk = CompositeJwk::new(CompositeAlgId::IdMldsa65Ed25519Sha512, pq.clone(), pq);
+    assert!(UpdateKey::from_composite_jwk(&mismatched).is_err());
+  }
+}